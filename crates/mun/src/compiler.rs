@@ -0,0 +1,23 @@
+//! Compilation of Mun source code into hot reloadable assemblies.
+//!
+//! This module is part of the stable API of the `mun` crate. Prefer it over depending on the
+//! `mun_compiler` crate directly, whose API may change between releases.
+
+pub use mun_compiler::{
+    check_manifest, compile_manifest, compile_manifest_assemblies, iter_source_files,
+    timings::Timings, CompilerOptions, Config, DisplayColor, Driver, EmitKind, FileId, JitAssembly,
+    JitCode, MessageFormat, OptimizationLevel, OutputKind, PathOrInline, RebuildReason,
+    RelativePath, RelativePathBuf, Severity, Snippet, Target,
+};
+pub use mun_project::Package;
+
+/// Diagnostics in a machine-readable format, see [`Driver::json_diagnostics`].
+pub mod diagnostics_json {
+    pub use mun_compiler::diagnostics_json::{JsonDiagnostic, JsonEdit, JsonSpan, JsonSuggestion};
+}
+
+/// Optional checks that warn about code that compiles but is likely to be a mistake, see
+/// [`Config::lint_levels`].
+pub mod lint {
+    pub use mun_compiler::lint::{find_lint, Lint, LintLevel, LintLevels, LINTS};
+}
@@ -1,4 +1,12 @@
+//! The `mun` crate provides the command-line interface of Mun as well as a stable API for
+//! embedding Mun in a host application. Embedders should only depend on this crate: the
+//! [`compiler`] and [`runtime`] modules re-export the supported parts of the internal crates.
+
+pub mod compiler;
 mod ops;
+pub mod runtime;
+
+pub use mun_runtime::{invoke_fn, static_assembly, value_struct};
 
 use std::env;
 use std::ffi::OsString;
@@ -6,7 +14,9 @@ use std::ffi::OsString;
 use clap::{App, AppSettings, Arg, SubCommand};
 use mun_project::MANIFEST_FILENAME;
 
-use ops::{build, init, language_server, new, start};
+use ops::{
+    bench, bindings, build, check, explain, fmt, init, language_server, new, repl, start, test,
+};
 
 #[derive(Copy, Debug, Clone, PartialEq, Eq)]
 pub enum ExitStatus {
@@ -53,7 +63,8 @@ where
                         .short("O")
                         .long("opt-level")
                         .takes_value(true)
-                        .help("optimize with possible levels 0-3"),
+                        .possible_values(&["0", "1", "2", "3"])
+                        .help("optimize with possible levels 0-3 (defaults to 2)"),
                 )
                 .arg(
                     Arg::with_name("target")
@@ -68,8 +79,338 @@ where
                         .possible_values(&["enable", "auto", "disable"])
                         .help("color text in terminal"),
                 )
+                .arg(
+                    Arg::with_name("message-format")
+                        .long("message-format")
+                        .takes_value(true)
+                        .possible_values(&["human", "json"])
+                        .help("the format of diagnostics; `json` emits a JSON object per line"),
+                )
+                .arg(
+                    Arg::with_name("jobs")
+                        .short("j")
+                        .long("jobs")
+                        .takes_value(true)
+                        .value_name("N")
+                        .help("the number of threads to compile on (defaults to the number of logical CPUs)"),
+                )
+                .arg(
+                    Arg::with_name("timings")
+                        .long("timings")
+                        .help("report the time spent in every phase of the compilation per file"),
+                )
+                .arg(
+                    Arg::with_name("overflow-checks")
+                        .long("overflow-checks")
+                        .takes_value(true)
+                        .possible_values(&["enable", "disable"])
+                        .help("check integer arithmetic for overflow (defaults to enabled for opt-level 0)"),
+                )
+                .arg(
+                    Arg::with_name("debug-info")
+                        .short("g")
+                        .long("debug-info")
+                        .help("emit debug information for native debuggers and profilers"),
+                )
+                .arg(
+                    Arg::with_name("allow")
+                        .short("A")
+                        .long("allow")
+                        .takes_value(true)
+                        .value_name("LINT")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("set the level of a lint to allow"),
+                )
+                .arg(
+                    Arg::with_name("warn")
+                        .short("W")
+                        .long("warn")
+                        .takes_value(true)
+                        .value_name("LINT")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("set the level of a lint to warn"),
+                )
+                .arg(
+                    Arg::with_name("deny")
+                        .short("D")
+                        .long("deny")
+                        .takes_value(true)
+                        .value_name("LINT")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("set the level of a lint to deny"),
+                )
+                .arg(
+                    Arg::with_name("emit")
+                        .long("emit")
+                        .takes_value(true)
+                        .value_name("KIND")
+                        .multiple(true)
+                        .use_delimiter(true)
+                        .possible_values(&["hir", "llvm-ir", "llvm-bc", "asm", "obj"])
+                        .help("write additional output next to every assembly"),
+                )
+                .arg(
+                    Arg::with_name("output-kind")
+                        .long("output-kind")
+                        .takes_value(true)
+                        .value_name("KIND")
+                        .possible_values(&["shared", "static"])
+                        .help("build shared libraries that can be hot reloaded (default) or object files and C headers that are statically linked into an executable"),
+                )
+                .arg(
+                    Arg::with_name("apply-fixes")
+                        .long("apply-fixes")
+                        .conflicts_with("watch")
+                        .help("apply the fixes suggested by diagnostics to the source files"),
+                )
+                .arg(
+                    Arg::with_name("single-assembly")
+                        .long("single-assembly")
+                        .help("compile all source files of the package into a single assembly that is named after the package"),
+                )
+                .arg(
+                    Arg::with_name("name")
+                        .long("name")
+                        .takes_value(true)
+                        .value_name("NAME")
+                        .help("compile all source files of the package into a single assembly named NAME"),
+                )
+                .arg(
+                    Arg::with_name("out-dir")
+                        .long("out-dir")
+                        .takes_value(true)
+                        .value_name("DIR")
+                        .help("the directory to write the assemblies to, instead of the `target-dir` of the manifest"),
+                )
                 .about("Compiles a local Mun file into a module"),
         )
+        .subcommand(
+            SubCommand::with_name("check")
+                .arg(
+                    Arg::with_name("manifest-path")
+                        .long("manifest-path")
+                        .takes_value(true)
+                        .help(&format!("Path to {}", MANIFEST_FILENAME))
+                )
+                .arg(
+                    Arg::with_name("color")
+                        .long("color")
+                        .takes_value(true)
+                        .possible_values(&["enable", "auto", "disable"])
+                        .help("color text in terminal"),
+                )
+                .arg(
+                    Arg::with_name("message-format")
+                        .long("message-format")
+                        .takes_value(true)
+                        .possible_values(&["human", "json"])
+                        .help("the format of diagnostics; `json` emits a JSON object per line"),
+                )
+                .arg(
+                    Arg::with_name("jobs")
+                        .short("j")
+                        .long("jobs")
+                        .takes_value(true)
+                        .value_name("N")
+                        .help("the number of threads to compile on (defaults to the number of logical CPUs)"),
+                )
+                .arg(
+                    Arg::with_name("timings")
+                        .long("timings")
+                        .help("report the time spent in every phase of the compilation per file"),
+                )
+                .arg(
+                    Arg::with_name("allow")
+                        .short("A")
+                        .long("allow")
+                        .takes_value(true)
+                        .value_name("LINT")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("set the level of a lint to allow"),
+                )
+                .arg(
+                    Arg::with_name("warn")
+                        .short("W")
+                        .long("warn")
+                        .takes_value(true)
+                        .value_name("LINT")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("set the level of a lint to warn"),
+                )
+                .arg(
+                    Arg::with_name("deny")
+                        .short("D")
+                        .long("deny")
+                        .takes_value(true)
+                        .value_name("LINT")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("set the level of a lint to deny"),
+                )
+                .about("Checks a local Mun package for errors without generating code"),
+        )
+        .subcommand(
+            SubCommand::with_name("test")
+                .arg(
+                    Arg::with_name("TESTNAME")
+                        .help("only run the tests of which the name contains TESTNAME")
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("manifest-path")
+                        .long("manifest-path")
+                        .takes_value(true)
+                        .help(&format!("Path to {}", MANIFEST_FILENAME))
+                )
+                .arg(
+                    Arg::with_name("color")
+                        .long("color")
+                        .takes_value(true)
+                        .possible_values(&["enable", "auto", "disable"])
+                        .help("color text in terminal"),
+                )
+                .arg(
+                    Arg::with_name("message-format")
+                        .long("message-format")
+                        .takes_value(true)
+                        .possible_values(&["human", "json"])
+                        .help("the format of diagnostics; `json` emits a JSON object per line"),
+                )
+                .arg(
+                    Arg::with_name("jobs")
+                        .short("j")
+                        .long("jobs")
+                        .takes_value(true)
+                        .value_name("N")
+                        .help("the number of threads to compile on (defaults to the number of logical CPUs)"),
+                )
+                .arg(
+                    Arg::with_name("allow")
+                        .short("A")
+                        .long("allow")
+                        .takes_value(true)
+                        .value_name("LINT")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("set the level of a lint to allow"),
+                )
+                .arg(
+                    Arg::with_name("warn")
+                        .short("W")
+                        .long("warn")
+                        .takes_value(true)
+                        .value_name("LINT")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("set the level of a lint to warn"),
+                )
+                .arg(
+                    Arg::with_name("deny")
+                        .short("D")
+                        .long("deny")
+                        .takes_value(true)
+                        .value_name("LINT")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("set the level of a lint to deny"),
+                )
+                .about("Builds a local Mun package and runs its test functions"),
+        )
+        .subcommand(
+            SubCommand::with_name("bench")
+                .arg(
+                    Arg::with_name("BENCHNAME")
+                        .help("only run the benchmarks of which the name contains BENCHNAME")
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("manifest-path")
+                        .long("manifest-path")
+                        .takes_value(true)
+                        .help(&format!("Path to {}", MANIFEST_FILENAME))
+                )
+                .arg(
+                    Arg::with_name("opt-level")
+                        .short("O")
+                        .long("opt-level")
+                        .takes_value(true)
+                        .possible_values(&["0", "1", "2", "3"])
+                        .help("optimize with possible levels 0-3 (defaults to 2)"),
+                )
+                .arg(
+                    Arg::with_name("color")
+                        .long("color")
+                        .takes_value(true)
+                        .possible_values(&["enable", "auto", "disable"])
+                        .help("color text in terminal"),
+                )
+                .arg(
+                    Arg::with_name("message-format")
+                        .long("message-format")
+                        .takes_value(true)
+                        .possible_values(&["human", "json"])
+                        .help("the format of diagnostics; `json` emits a JSON object per line"),
+                )
+                .arg(
+                    Arg::with_name("jobs")
+                        .short("j")
+                        .long("jobs")
+                        .takes_value(true)
+                        .value_name("N")
+                        .help("the number of threads to compile on (defaults to the number of logical CPUs)"),
+                )
+                .arg(
+                    Arg::with_name("allow")
+                        .short("A")
+                        .long("allow")
+                        .takes_value(true)
+                        .value_name("LINT")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("set the level of a lint to allow"),
+                )
+                .arg(
+                    Arg::with_name("warn")
+                        .short("W")
+                        .long("warn")
+                        .takes_value(true)
+                        .value_name("LINT")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("set the level of a lint to warn"),
+                )
+                .arg(
+                    Arg::with_name("deny")
+                        .short("D")
+                        .long("deny")
+                        .takes_value(true)
+                        .value_name("LINT")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("set the level of a lint to deny"),
+                )
+                .about("Builds a local Mun package and measures its benchmark functions"),
+        )
+        .subcommand(
+            SubCommand::with_name("fmt")
+                .arg(
+                    Arg::with_name("manifest-path")
+                        .long("manifest-path")
+                        .takes_value(true)
+                        .help(&format!("Path to {}", MANIFEST_FILENAME))
+                )
+                .arg(
+                    Arg::with_name("check")
+                        .long("check")
+                        .help("report the files that are not formatted instead of formatting them"),
+                )
+                .about("Formats the source files of a local Mun package"),
+        )
         .subcommand(
             SubCommand::with_name("start")
                 .arg(
@@ -91,12 +432,77 @@ where
                         .help("how much to delay received filesystem events (in ms). This allows bundling of identical events, e.g. when several writes to the same file are detected. A high delay will make hot reloading less responsive. (defaults to 10 ms)"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("repl")
+                .arg(
+                    Arg::with_name("opt-level")
+                        .short("O")
+                        .long("opt-level")
+                        .takes_value(true)
+                        .possible_values(&["0", "1", "2", "3"])
+                        .help("optimize with possible levels 0-3 (defaults to 2)"),
+                )
+                .arg(
+                    Arg::with_name("color")
+                        .long("color")
+                        .takes_value(true)
+                        .possible_values(&["enable", "auto", "disable"])
+                        .help("color text in terminal"),
+                )
+                .about("Starts an interactive session that evaluates Mun expressions and definitions"),
+        )
+        .subcommand(
+            SubCommand::with_name("bindings")
+                .arg(
+                    Arg::with_name("LIBRARY")
+                        .help("the compiled assembly to generate bindings for")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short("o")
+                        .long("output")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help("the path of the generated bindings (defaults to the path of the assembly with the `hpp` or `cs` extension)"),
+                )
+                .arg(
+                    Arg::with_name("language")
+                        .long("language")
+                        .takes_value(true)
+                        .possible_values(&["cpp", "csharp"])
+                        .default_value("cpp")
+                        .help("the language of the generated bindings"),
+                )
+                .about("Generates C++ or C# bindings for the structs and functions of a compiled assembly"),
+        )
         .subcommand(
             SubCommand::with_name("new")
                 .arg(Arg::with_name("path").help("the path to create a new project").required(true).index(1))
+                .arg(
+                    Arg::with_name("host")
+                        .long("host")
+                        .help("also create a Rust application that runs the package"),
+                )
         )
         .subcommand(
             SubCommand::with_name("init")
+                .arg(
+                    Arg::with_name("host")
+                        .long("host")
+                        .help("also create a Rust application that runs the package"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("explain")
+                .arg(
+                    Arg::with_name("CODE")
+                        .help("the error code, e.g. E0002, or the name of a lint")
+                        .required(true)
+                        .index(1),
+                )
+                .about("Prints a detailed explanation of an error code or lint"),
         )
         .subcommand(
             SubCommand::with_name("language-server")
@@ -105,11 +511,18 @@ where
 
     match matches {
         Ok(matches) => match matches.subcommand() {
+            ("bindings", Some(matches)) => bindings(matches),
             ("build", Some(matches)) => build(matches),
+            ("check", Some(matches)) => check(matches),
+            ("test", Some(matches)) => test(matches),
+            ("bench", Some(matches)) => bench(matches),
+            ("fmt", Some(matches)) => fmt(matches),
+            ("explain", Some(matches)) => explain(matches),
             ("language-server", Some(matches)) => language_server(matches),
             ("start", Some(matches)) => start(matches).map(|_| ExitStatus::Success),
+            ("repl", Some(matches)) => repl(matches),
             ("new", Some(matches)) => new(matches),
-            ("init", Some(_)) => init(),
+            ("init", Some(matches)) => init(matches),
             _ => unreachable!(),
         },
         Err(e) => {
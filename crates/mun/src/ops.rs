@@ -1,11 +1,25 @@
+mod bench;
+mod bindings;
 mod build;
+mod check;
+mod explain;
+mod fmt;
 pub mod init;
 mod language_server;
 mod new;
+mod repl;
 mod start;
+mod test;
 
+pub use bench::bench;
+pub use bindings::bindings;
 pub use build::build;
+pub use check::check;
+pub use explain::explain;
+pub use fmt::fmt;
 pub use init::init;
 pub use language_server::language_server;
 pub use new::new;
+pub use repl::repl;
 pub use start::start;
+pub use test::test;
@@ -0,0 +1,168 @@
+use clap::ArgMatches;
+use mun_compiler::Target;
+use mun_project::Workspace;
+use mun_runtime::{InvokeError, Runtime};
+use std::time::Duration;
+
+use crate::ops::build::{compiler_options, locate_manifest};
+use crate::ops::test::load_runtime;
+use crate::ExitStatus;
+
+/// The minimum duration of a single sample. The number of iterations per sample is increased
+/// until a sample takes at least this long, which also warms up the caches.
+const MIN_SAMPLE_DURATION: Duration = Duration::from_millis(10);
+
+/// The number of samples that are measured per benchmark.
+const NUM_SAMPLES: usize = 50;
+
+/// This method is invoked when the executable is run with the `bench` argument indicating that a
+/// user requested us to build a project and measure the functions that are marked with a
+/// `#[bench]` attribute. Every benchmark is invoked repeatedly and the statistics of the time per
+/// iteration are reported.
+pub fn bench(matches: &ArgMatches) -> Result<ExitStatus, anyhow::Error> {
+    log::trace!("starting bench");
+
+    let manifest_path = locate_manifest(matches)?;
+    log::info!("located manifest at: {}", manifest_path.display());
+
+    let workspace = Workspace::from_file(&manifest_path)?;
+    let mut options = compiler_options(matches, workspace.build_settings())?;
+    // Benchmarks are run on this machine, regardless of the target that the package is built for
+    options.target = Target::host_target()?;
+
+    let assemblies = match mun_compiler::compile_manifest_assemblies(&manifest_path, options)? {
+        Some(assemblies) => assemblies,
+        None => return Ok(ExitStatus::Error),
+    };
+    let runtime = match load_runtime(assemblies)? {
+        Some(runtime) => runtime,
+        None => {
+            println!("running 0 benches");
+            return Ok(ExitStatus::Success);
+        }
+    };
+
+    let filter = matches.value_of("BENCHNAME");
+    let mut benches: Vec<_> = runtime
+        .benches()
+        .map(|bench| bench.prototype.name().to_owned())
+        .filter(|name| filter.map_or(true, |filter| name.contains(filter)))
+        .collect();
+    benches.sort();
+
+    println!("running {} benches", benches.len());
+    let mut failures = Vec::new();
+    for name in benches.iter() {
+        match measure(&runtime, name) {
+            Ok(summary) => println!(
+                "bench {} ... {:.2} ns/iter (+/- {:.2}), mean {:.2}, min {:.2}, max {:.2}",
+                name, summary.median, summary.std_dev, summary.mean, summary.min, summary.max
+            ),
+            Err(error) => {
+                println!("bench {} ... FAILED", name);
+                failures.push((name, error));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        println!("\nfailures:");
+        for (name, error) in failures.iter() {
+            match error.panic() {
+                Some(panic) => println!("    {} panicked: {}", name, panic),
+                None => println!("    {}: {}", name, error),
+            }
+        }
+    }
+
+    println!(
+        "\nbench result: {}. {} measured; {} failed",
+        if failures.is_empty() { "ok" } else { "FAILED" },
+        benches.len() - failures.len(),
+        failures.len()
+    );
+
+    Ok(if failures.is_empty() {
+        ExitStatus::Success
+    } else {
+        ExitStatus::Error
+    })
+}
+
+/// Measures the benchmark `name` and returns the statistics of the nanoseconds per iteration of
+/// its samples.
+fn measure(runtime: &Runtime, name: &str) -> Result<Summary, InvokeError> {
+    // Double the number of iterations until a sample is long enough to be measured accurately
+    let mut iterations = 1u64;
+    while runtime.run_bench(name, iterations)? < MIN_SAMPLE_DURATION && iterations < 1 << 32 {
+        iterations *= 2;
+    }
+
+    let samples = (0..NUM_SAMPLES)
+        .map(|_| {
+            runtime
+                .run_bench(name, iterations)
+                .map(|elapsed| elapsed.as_nanos() as f64 / iterations as f64)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Summary::new(&samples))
+}
+
+/// The statistics of a set of samples.
+#[derive(Clone, Debug, PartialEq)]
+struct Summary {
+    median: f64,
+    mean: f64,
+    std_dev: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Summary {
+    /// Computes the statistics of `samples`, which must not be empty.
+    fn new(samples: &[f64]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let len = sorted.len();
+        let median = if len % 2 == 0 {
+            (sorted[len / 2 - 1] + sorted[len / 2]) / 2.0
+        } else {
+            sorted[len / 2]
+        };
+        let mean = sorted.iter().sum::<f64>() / len as f64;
+        let variance = sorted
+            .iter()
+            .map(|sample| (sample - mean) * (sample - mean))
+            .sum::<f64>()
+            / len as f64;
+
+        Summary {
+            median,
+            mean,
+            std_dev: variance.sqrt(),
+            min: sorted[0],
+            max: sorted[len - 1],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Summary;
+
+    #[test]
+    fn summary() {
+        assert_eq!(
+            Summary::new(&[4.0, 2.0, 8.0, 6.0]),
+            Summary {
+                median: 5.0,
+                mean: 5.0,
+                std_dev: 5.0f64.sqrt(),
+                min: 2.0,
+                max: 8.0,
+            }
+        );
+        assert_eq!(Summary::new(&[3.0, 1.0, 2.0]).median, 2.0);
+    }
+}
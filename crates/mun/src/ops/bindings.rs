@@ -0,0 +1,47 @@
+mod cpp;
+mod csharp;
+
+use std::path::{Path, PathBuf};
+
+use clap::ArgMatches;
+use mun_abi::{StructMemoryKind, TypeInfo};
+use mun_runtime::Assembly;
+
+use crate::ExitStatus;
+
+/// Generates bindings for the structs and functions of an assembly in the requested language: a
+/// C++ header or a C# source file.
+pub fn bindings(matches: &ArgMatches) -> Result<ExitStatus, anyhow::Error> {
+    let library_path = Path::new(matches.value_of("LIBRARY").unwrap()); // Safe because its a required arg
+    let is_csharp = matches.value_of("language") == Some("csharp");
+    let output_path = matches.value_of("output").map_or_else(
+        || library_path.with_extension(if is_csharp { "cs" } else { "hpp" }),
+        PathBuf::from,
+    );
+
+    let assembly = Assembly::inspect(library_path)?;
+    let name = library_path
+        .file_stem()
+        .map_or_else(|| "assembly".into(), |stem| stem.to_string_lossy());
+    let bindings = if is_csharp {
+        csharp::generate_source(&assembly, &name)
+    } else {
+        cpp::generate_header(&assembly, &name)
+    };
+    std::fs::write(&output_path, bindings)?;
+    Ok(ExitStatus::Success)
+}
+
+/// Returns the names and types of the fields of the struct `ty`.
+fn fields_of(ty: &TypeInfo) -> impl Iterator<Item = (&str, &TypeInfo)> {
+    let struct_info = ty.as_struct().unwrap();
+    struct_info
+        .field_names()
+        .zip(struct_info.field_types().iter().copied())
+}
+
+/// Returns whether `ty` is a struct that is allocated by the garbage collector.
+fn is_gc_struct(ty: &TypeInfo) -> bool {
+    ty.as_struct()
+        .map_or(false, |s| s.memory_kind == StructMemoryKind::GC)
+}
@@ -0,0 +1,540 @@
+//! Generates C++ headers that wrap the C API of the runtime.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use mun_abi::TypeInfo;
+use mun_runtime::Assembly;
+
+use super::{fields_of, is_gc_struct};
+
+/// Names that cannot be used as identifiers in C++, separated by whitespace.
+const CPP_KEYWORDS: &str = "\
+    alignas alignof and asm auto bool case catch char class const constexpr continue decltype \
+    default delete do double else enum explicit export extern false float for friend goto if \
+    inline int long mutable namespace new noexcept not nullptr operator or private protected \
+    public register return short signed sizeof static struct switch template this throw true \
+    try typedef typename union unsigned using virtual void volatile while";
+
+/// Generates a C++ header for the `assembly` named `name`. The header wraps the C API of the
+/// runtime: every gc struct is exposed as a class that roots the struct and accesses its fields by
+/// name, every value struct as a struct with the same layout, and every public function as a
+/// function that invokes it through the runtime. Structs and functions that use types without a
+/// C++ equivalent are omitted.
+pub(super) fn generate_header(assembly: &Assembly, name: &str) -> String {
+    let namespace = identifier(name);
+    let mut header = String::new();
+    writeln!(
+        header,
+        "// Generated by `mun bindings` from `{}`. Do not edit.",
+        assembly.library_path().display()
+    )
+    .unwrap();
+    writeln!(header).unwrap();
+    writeln!(
+        header,
+        "#ifndef MUN_BINDINGS_{}_HPP_",
+        namespace.to_ascii_uppercase()
+    )
+    .unwrap();
+    writeln!(
+        header,
+        "#define MUN_BINDINGS_{}_HPP_",
+        namespace.to_ascii_uppercase()
+    )
+    .unwrap();
+    header.push_str(
+        r#"
+#include <cstdint>
+#include <stdexcept>
+#include <string>
+#include <utility>
+
+#include "mun/runtime_capi.h"
+
+"#,
+    );
+    writeln!(header, "namespace mun {{").unwrap();
+    writeln!(header, "namespace {} {{", namespace).unwrap();
+    header.push_str(DETAIL);
+
+    let structs: Vec<&TypeInfo> = assembly
+        .types()
+        .iter()
+        .copied()
+        .filter(|ty| ty.as_struct().is_some())
+        .collect();
+    let gc_structs: Vec<&TypeInfo> = structs
+        .iter()
+        .copied()
+        .filter(|ty| is_gc_struct(ty))
+        .collect();
+
+    // Classes are declared before any of them is defined, as they can refer to each other
+    for ty in gc_structs.iter() {
+        writeln!(header, "class {};", identifier(ty.name())).unwrap();
+    }
+    if !gc_structs.is_empty() {
+        writeln!(header).unwrap();
+    }
+
+    let mut value_structs = HashMap::new();
+    for ty in structs.iter() {
+        write_value_struct(&mut header, ty, &mut value_structs);
+    }
+    for ty in gc_structs.iter() {
+        write_class(&mut header, ty);
+    }
+    for ty in gc_structs.iter() {
+        write_class_members(&mut header, ty);
+    }
+    for function in assembly.functions() {
+        write_function(&mut header, function);
+    }
+
+    writeln!(header, "}}  // namespace {}", namespace).unwrap();
+    writeln!(header, "}}  // namespace mun").unwrap();
+    writeln!(header).unwrap();
+    writeln!(
+        header,
+        "#endif  // MUN_BINDINGS_{}_HPP_",
+        namespace.to_ascii_uppercase()
+    )
+    .unwrap();
+    header
+}
+
+/// Helper functions that are shared by all bindings of an assembly.
+const DETAIL: &str = r#"
+namespace detail {
+/// Throws the error of a failed call to the runtime as an exception.
+inline void check(MunErrorHandle error) {
+    if (error._0 != 0) {
+        const char *message = mun_error_message(error);
+        std::runtime_error exception(message);
+        mun_destroy_string(message);
+        mun_error_destroy(error);
+        throw exception;
+    }
+}
+
+/// Returns a pointer to the function `name`, which changes when the assembly is hot reloaded.
+inline void *fn_ptr(MunRuntimeHandle runtime, const char *name) {
+    bool has_fn_info = false;
+    MunFunctionDefinition fn_definition;
+    check(mun_runtime_get_function_definition(runtime, name, &has_fn_info, &fn_definition));
+    if (!has_fn_info) {
+        throw std::runtime_error(std::string("failed to obtain function '") + name + "'");
+    }
+    return const_cast<void *>(fn_definition.fn_ptr);
+}
+
+/// Returns the type information of the type `name`.
+inline MunUnsafeTypeInfo type_info(MunRuntimeHandle runtime, const char *name) {
+    bool has_type_info = false;
+    MunUnsafeTypeInfo type_info;
+    check(mun_runtime_find_type_info(runtime, name, &has_type_info, &type_info));
+    if (!has_type_info) {
+        throw std::runtime_error(std::string("failed to obtain type '") + name + "'");
+    }
+    return type_info;
+}
+}  // namespace detail
+
+"#;
+
+/// Writes the definition of the value struct `ty`, after the value structs that it contains.
+/// Returns `false` if the struct is omitted, because it contains a type without a C++ equivalent.
+/// Nothing is written for gc structs. `value_structs` contains the value structs that were
+/// already visited.
+fn write_value_struct<'a>(
+    header: &mut String,
+    ty: &'a TypeInfo,
+    value_structs: &mut HashMap<&'a str, bool>,
+) -> bool {
+    if is_gc_struct(ty) {
+        return true;
+    }
+    if let Some(is_written) = value_structs.get(ty.name()) {
+        return *is_written;
+    }
+    value_structs.insert(ty.name(), false);
+
+    let name = identifier(ty.name());
+    let mut fields = Vec::new();
+    for (field_name, field_ty) in fields_of(ty) {
+        // A value struct contains the handles of the gc structs that it refers to
+        let cpp_ty = if is_gc_struct(field_ty) {
+            Some("MunGcPtr".to_string())
+        } else if field_ty.as_struct().is_some() {
+            if write_value_struct(header, field_ty, value_structs) {
+                Some(identifier(field_ty.name()))
+            } else {
+                None
+            }
+        } else {
+            cpp_type(field_ty)
+        };
+        match cpp_ty {
+            Some(cpp_ty) => fields.push((cpp_ty, identifier(field_name))),
+            None => {
+                writeln!(
+                    header,
+                    "// `{}` is omitted, as it contains a type without a C++ equivalent.\n",
+                    ty.name()
+                )
+                .unwrap();
+                return false;
+            }
+        }
+    }
+
+    writeln!(header, "/// The value struct `{}`.", ty.name()).unwrap();
+    writeln!(header, "struct {} {{", name).unwrap();
+    for (cpp_ty, field_name) in fields {
+        writeln!(header, "    {} {};", cpp_ty, field_name).unwrap();
+    }
+    writeln!(header, "}};").unwrap();
+    writeln!(
+        header,
+        "static_assert(sizeof({}) == {}, \"the layout of `{}` does not match the assembly\");",
+        name,
+        ty.size_in_bytes(),
+        name
+    )
+    .unwrap();
+    writeln!(header).unwrap();
+    value_structs.insert(ty.name(), true);
+    true
+}
+
+/// Writes the definition of the class that wraps the gc struct `ty`.
+fn write_class(header: &mut String, ty: &TypeInfo) {
+    let name = identifier(ty.name());
+    writeln!(
+        header,
+        "/// A rooted reference to a `{}` struct, which keeps it alive.",
+        ty.name()
+    )
+    .unwrap();
+    writeln!(header, "class {} {{", name).unwrap();
+    writeln!(header, "public:").unwrap();
+    writeln!(header, "    /// Roots the struct `handle`.").unwrap();
+    writeln!(
+        header,
+        "    {}(MunRuntimeHandle runtime, MunGcPtr handle);",
+        name
+    )
+    .unwrap();
+    writeln!(header, "    {}(const {} &other);", name, name).unwrap();
+    writeln!(header, "    {} &operator=({} other);", name, name).unwrap();
+    writeln!(header, "    ~{}();", name).unwrap();
+    writeln!(header).unwrap();
+    writeln!(
+        header,
+        "    /// Allocates a new struct, of which all fields are zero-initialized."
+    )
+    .unwrap();
+    writeln!(
+        header,
+        "    static {} create(MunRuntimeHandle runtime);",
+        name
+    )
+    .unwrap();
+    writeln!(header).unwrap();
+    writeln!(header, "    /// Returns the handle of the struct.").unwrap();
+    writeln!(header, "    MunGcPtr handle() const;").unwrap();
+    for (field_name, field_ty) in fields_of(ty) {
+        writeln!(header).unwrap();
+        match cpp_field_type(field_ty) {
+            Some(cpp_ty) => {
+                let field = identifier(field_name);
+                writeln!(header, "    {} {}() const;", cpp_ty, field).unwrap();
+                writeln!(
+                    header,
+                    "    void set_{}({});",
+                    field_name,
+                    cpp_parameter(field_ty, &cpp_ty, "value")
+                )
+                .unwrap();
+            }
+            None => writeln!(
+                header,
+                "    // `{}` is omitted, as its type `{}` has no C++ equivalent.",
+                field_name,
+                field_ty.name()
+            )
+            .unwrap(),
+        }
+    }
+    writeln!(header).unwrap();
+    writeln!(header, "private:").unwrap();
+    writeln!(header, "    MunRuntimeHandle runtime_;").unwrap();
+    writeln!(header, "    MunGcPtr handle_;").unwrap();
+    writeln!(header, "}};").unwrap();
+    writeln!(header).unwrap();
+}
+
+/// Writes the definitions of the member functions of the class that wraps the gc struct `ty`.
+fn write_class_members(header: &mut String, ty: &TypeInfo) {
+    let name = identifier(ty.name());
+    write!(
+        header,
+        r#"inline {name}::{name}(MunRuntimeHandle runtime, MunGcPtr handle)
+    : runtime_(runtime), handle_(handle) {{
+    detail::check(mun_gc_root(runtime_, handle_));
+}}
+
+inline {name}::{name}(const {name} &other) : {name}(other.runtime_, other.handle_) {{}}
+
+inline {name} &{name}::operator=({name} other) {{
+    std::swap(runtime_, other.runtime_);
+    std::swap(handle_, other.handle_);
+    return *this;
+}}
+
+inline {name}::~{name}() {{
+    // Errors cannot be reported from a destructor
+    mun_error_destroy(mun_gc_unroot(runtime_, handle_));
+}}
+
+inline {name} {name}::create(MunRuntimeHandle runtime) {{
+    MunGcPtr handle;
+    detail::check(mun_gc_alloc(runtime, detail::type_info(runtime, "{ty_name}"), &handle));
+    return {name}(runtime, handle);
+}}
+
+inline MunGcPtr {name}::handle() const {{ return handle_; }}
+"#,
+        name = name,
+        ty_name = ty.name()
+    )
+    .unwrap();
+
+    for (field_name, field_ty) in fields_of(ty) {
+        let cpp_ty = match cpp_field_type(field_ty) {
+            Some(cpp_ty) => cpp_ty,
+            None => continue,
+        };
+        let field = identifier(field_name);
+        let guid = guid_literal(field_ty);
+        writeln!(header).unwrap();
+        if is_gc_struct(field_ty) {
+            write!(
+                header,
+                r#"inline {cpp_ty} {name}::{field}() const {{
+    MunGcPtr value;
+    detail::check(mun_struct_get_field(runtime_, handle_, "{field_name}", {guid}, &value));
+    return {cpp_ty}(runtime_, value);
+}}
+
+inline void {name}::set_{field_name}(const {cpp_ty} &value) {{
+    MunGcPtr handle = value.handle();
+    detail::check(mun_struct_set_field(runtime_, handle_, "{field_name}", {guid}, &handle));
+}}
+"#,
+                cpp_ty = cpp_ty,
+                name = name,
+                field = field,
+                field_name = field_name,
+                guid = guid
+            )
+            .unwrap();
+        } else {
+            write!(
+                header,
+                r#"inline {cpp_ty} {name}::{field}() const {{
+    {cpp_ty} value;
+    detail::check(mun_struct_get_field(runtime_, handle_, "{field_name}", {guid}, &value));
+    return value;
+}}
+
+inline void {name}::set_{field_name}({param}) {{
+    detail::check(mun_struct_set_field(runtime_, handle_, "{field_name}", {guid}, &value));
+}}
+"#,
+                cpp_ty = cpp_ty,
+                param = cpp_parameter(field_ty, &cpp_ty, "value"),
+                name = name,
+                field = field,
+                field_name = field_name,
+                guid = guid
+            )
+            .unwrap();
+        }
+    }
+    writeln!(header).unwrap();
+}
+
+/// Writes a function that invokes the public function `function` through the runtime.
+fn write_function(header: &mut String, function: &mun_abi::FunctionDefinition) {
+    let fn_name = function.prototype.name();
+    let signature = &function.prototype.signature;
+    let arg_types: Option<Vec<String>> = signature
+        .arg_types()
+        .iter()
+        .map(|ty| cpp_field_type(ty))
+        .collect();
+    let return_type = match signature.return_type() {
+        Some(ty) => cpp_field_type(ty),
+        None => Some("void".to_string()),
+    };
+    let (arg_types, return_type) = match (arg_types, return_type) {
+        (Some(arg_types), Some(return_type)) => (arg_types, return_type),
+        _ => {
+            writeln!(
+                header,
+                "// `{}` is omitted, as its signature contains a type without a C++ equivalent.\n",
+                fn_name
+            )
+            .unwrap();
+            return;
+        }
+    };
+
+    // Gc structs are passed to and returned from Mun functions as handles
+    let raw_type = |ty: &TypeInfo, cpp_ty: &str| {
+        if is_gc_struct(ty) {
+            "MunGcPtr".to_string()
+        } else {
+            cpp_ty.to_string()
+        }
+    };
+    let raw_return_type = signature
+        .return_type()
+        .map_or_else(|| "void".to_string(), |ty| raw_type(ty, &return_type));
+    let raw_arg_types: Vec<String> = signature
+        .arg_types()
+        .iter()
+        .zip(arg_types.iter())
+        .map(|(ty, cpp_ty)| raw_type(ty, cpp_ty))
+        .collect();
+    let params: Vec<String> = signature
+        .arg_types()
+        .iter()
+        .zip(arg_types.iter())
+        .enumerate()
+        .map(|(idx, (ty, cpp_ty))| {
+            format!(", {}", cpp_parameter(ty, cpp_ty, &format!("arg{}", idx)))
+        })
+        .collect();
+    let args: Vec<String> = signature
+        .arg_types()
+        .iter()
+        .enumerate()
+        .map(|(idx, ty)| {
+            if is_gc_struct(ty) {
+                format!("arg{}.handle()", idx)
+            } else {
+                format!("arg{}", idx)
+            }
+        })
+        .collect();
+
+    match function.docs() {
+        Some(docs) => {
+            for line in docs.lines() {
+                let line = line.trim();
+                if line.is_empty() {
+                    writeln!(header, "///").unwrap();
+                } else {
+                    writeln!(header, "/// {}", line).unwrap();
+                }
+            }
+        }
+        None => writeln!(header, "/// Invokes the Mun function `{}`.", fn_name).unwrap(),
+    }
+    writeln!(
+        header,
+        "inline {} {}(MunRuntimeHandle runtime{}) {{",
+        return_type,
+        identifier(fn_name),
+        params.concat()
+    )
+    .unwrap();
+    writeln!(
+        header,
+        "    auto fn = reinterpret_cast<{} (*)({})>(detail::fn_ptr(runtime, \"{}\"));",
+        raw_return_type,
+        raw_arg_types.join(", "),
+        fn_name
+    )
+    .unwrap();
+    let call = format!("fn({})", args.join(", "));
+    match signature.return_type() {
+        None => writeln!(header, "    {};", call).unwrap(),
+        Some(ty) if is_gc_struct(ty) => {
+            writeln!(header, "    return {}(runtime, {});", return_type, call).unwrap()
+        }
+        Some(_) => writeln!(header, "    return {};", call).unwrap(),
+    }
+    writeln!(header, "}}").unwrap();
+    writeln!(header).unwrap();
+}
+
+/// Returns the C++ type of a field, argument or return value of type `ty`, if it exists.
+fn cpp_field_type(ty: &TypeInfo) -> Option<String> {
+    if ty.as_struct().is_some() {
+        Some(identifier(ty.name()))
+    } else {
+        cpp_type(ty)
+    }
+}
+
+/// Returns the declaration of the parameter `name` for a value of type `ty`, of which the C++ type
+/// is `cpp_ty`. Structs are passed by reference.
+fn cpp_parameter(ty: &TypeInfo, cpp_ty: &str, name: &str) -> String {
+    if ty.as_struct().is_some() {
+        format!("const {} &{}", cpp_ty, name)
+    } else {
+        format!("{} {}", cpp_ty, name)
+    }
+}
+
+/// Returns the C++ type of the fundamental type `ty`, if it exists.
+fn cpp_type(ty: &TypeInfo) -> Option<String> {
+    let cpp_ty = match ty.name() {
+        "core::bool" => "bool",
+        "core::char" => "char32_t",
+        "core::i8" => "int8_t",
+        "core::i16" => "int16_t",
+        "core::i32" => "int32_t",
+        "core::i64" => "int64_t",
+        "core::isize" => "intptr_t",
+        "core::u8" => "uint8_t",
+        "core::u16" => "uint16_t",
+        "core::u32" => "uint32_t",
+        "core::u64" => "uint64_t",
+        "core::usize" => "uintptr_t",
+        "core::f32" => "float",
+        "core::f64" => "double",
+        _ => return None,
+    };
+    Some(cpp_ty.to_string())
+}
+
+/// Returns a C++ literal of the `Guid` of `ty`.
+fn guid_literal(ty: &TypeInfo) -> String {
+    let bytes: Vec<String> = ty.guid.b.iter().map(|b| format!("{:#04x}", b)).collect();
+    format!("MunGuid{{{{{}}}}}", bytes.join(", "))
+}
+
+/// Converts `name` into a valid C++ identifier.
+fn identifier(name: &str) -> String {
+    let mut identifier: String = name
+        .replace("::", "_")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if identifier
+        .chars()
+        .next()
+        .map_or(true, |c| c.is_ascii_digit())
+        || CPP_KEYWORDS
+            .split_whitespace()
+            .any(|keyword| keyword == identifier)
+    {
+        identifier.insert(0, '_');
+    }
+    identifier
+}
@@ -0,0 +1,668 @@
+//! Generates C# sources that wrap the C API of the runtime through P/Invoke, e.g. for Unity.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use mun_abi::TypeInfo;
+use mun_runtime::Assembly;
+
+use super::{fields_of, is_gc_struct};
+
+/// Names of the members of the generated classes, which cannot be used for fields and functions.
+const RESERVED_MEMBERS: &[&str] = &["Create", "Dispose", "Handle"];
+
+/// Generates a C# source file for the `assembly` named `name`. The source declares the C API of
+/// the runtime through P/Invoke and wraps it: every gc struct is exposed as a disposable class that
+/// roots the struct and accesses its fields as properties, every value struct as a struct with the
+/// same layout, and every public function as a static method of the `Functions` class that
+/// invokes it through the runtime. Structs and functions that use types without a C# equivalent
+/// are omitted.
+pub(super) fn generate_source(assembly: &Assembly, name: &str) -> String {
+    let mut source = String::new();
+    writeln!(
+        source,
+        "// Generated by `mun bindings` from `{}`. Do not edit.",
+        assembly.library_path().display()
+    )
+    .unwrap();
+    source.push_str(
+        r#"
+using System;
+using System.Runtime.InteropServices;
+
+"#,
+    );
+    writeln!(source, "namespace Mun.{}", identifier(name)).unwrap();
+    writeln!(source, "{{").unwrap();
+    source.push_str(NATIVE);
+
+    let structs: Vec<&TypeInfo> = assembly
+        .types()
+        .iter()
+        .copied()
+        .filter(|ty| ty.as_struct().is_some())
+        .collect();
+
+    let mut value_structs = HashMap::new();
+    for ty in structs.iter() {
+        write_value_struct(&mut source, ty, &mut value_structs);
+    }
+    for ty in structs.iter().filter(|ty| is_gc_struct(ty)) {
+        write_class(&mut source, ty, &value_structs);
+    }
+
+    writeln!(
+        source,
+        "    /// <summary>The public functions of the assembly.</summary>"
+    )
+    .unwrap();
+    writeln!(source, "    public static class Functions").unwrap();
+    writeln!(source, "    {{").unwrap();
+    let functions: Vec<String> = assembly
+        .functions()
+        .iter()
+        .map(|function| function_source(function, &value_structs))
+        .collect();
+    source.push_str(&functions.join("\n"));
+    writeln!(source, "    }}").unwrap();
+    writeln!(source, "}}").unwrap();
+    source
+}
+
+/// The P/Invoke declarations of the C API of the runtime, and helper functions that are shared by
+/// all bindings of an assembly.
+const NATIVE: &str = r#"    /// <summary>
+    /// The declarations of the C API of the Mun runtime, which is loaded from the `mun_runtime`
+    /// library.
+    /// </summary>
+    internal static class Native
+    {
+        private const string Library = "mun_runtime";
+
+        /// <summary>The `Guid` of a Mun type, of which the bytes are stored in little-endian order.</summary>
+        [StructLayout(LayoutKind.Sequential)]
+        public struct MunGuid
+        {
+            public ulong Low;
+            public ulong High;
+
+            public MunGuid(ulong low, ulong high)
+            {
+                Low = low;
+                High = high;
+            }
+        }
+
+        [StructLayout(LayoutKind.Sequential)]
+        public struct FunctionDefinition
+        {
+            public IntPtr Name;
+            public IntPtr ArgTypes;
+            public IntPtr ReturnType;
+            public ushort NumArgTypes;
+            public IntPtr FnPtr;
+            public IntPtr Docs;
+        }
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern IntPtr mun_error_message(UIntPtr error);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern void mun_error_destroy(UIntPtr error);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern void mun_destroy_string(IntPtr str);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern UIntPtr mun_runtime_get_function_definition(
+            IntPtr runtime,
+            [MarshalAs(UnmanagedType.LPStr)] string fnName,
+            [MarshalAs(UnmanagedType.U1)] out bool hasFnInfo,
+            out FunctionDefinition fnDefinition);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern UIntPtr mun_runtime_find_type_info(
+            IntPtr runtime,
+            [MarshalAs(UnmanagedType.LPStr)] string typeName,
+            [MarshalAs(UnmanagedType.U1)] out bool hasTypeInfo,
+            out IntPtr typeInfo);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern UIntPtr mun_gc_alloc(IntPtr runtime, IntPtr typeInfo, out IntPtr obj);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern UIntPtr mun_gc_root(IntPtr runtime, IntPtr obj);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern UIntPtr mun_gc_unroot(IntPtr runtime, IntPtr obj);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern UIntPtr mun_struct_get_field(
+            IntPtr runtime,
+            IntPtr obj,
+            [MarshalAs(UnmanagedType.LPStr)] string fieldName,
+            MunGuid fieldType,
+            IntPtr value);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern UIntPtr mun_struct_set_field(
+            IntPtr runtime,
+            IntPtr obj,
+            [MarshalAs(UnmanagedType.LPStr)] string fieldName,
+            MunGuid fieldType,
+            IntPtr value);
+
+        /// <summary>Throws the error of a failed call to the runtime as an exception.</summary>
+        public static void Check(UIntPtr error)
+        {
+            if (error != UIntPtr.Zero)
+            {
+                IntPtr message = mun_error_message(error);
+                string text = Marshal.PtrToStringAnsi(message);
+                mun_destroy_string(message);
+                mun_error_destroy(error);
+                throw new InvalidOperationException(text);
+            }
+        }
+
+        /// <summary>
+        /// Returns a pointer to the function <paramref name="name"/>, which changes when the
+        /// assembly is hot reloaded.
+        /// </summary>
+        public static IntPtr FnPtr(IntPtr runtime, string name)
+        {
+            bool hasFnInfo;
+            FunctionDefinition fnDefinition;
+            Check(mun_runtime_get_function_definition(runtime, name, out hasFnInfo, out fnDefinition));
+            if (!hasFnInfo)
+            {
+                throw new InvalidOperationException("failed to obtain function '" + name + "'");
+            }
+            return fnDefinition.FnPtr;
+        }
+
+        /// <summary>Returns the type information of the type <paramref name="name"/>.</summary>
+        public static IntPtr TypeInfo(IntPtr runtime, string name)
+        {
+            bool hasTypeInfo;
+            IntPtr typeInfo;
+            Check(mun_runtime_find_type_info(runtime, name, out hasTypeInfo, out typeInfo));
+            if (!hasTypeInfo)
+            {
+                throw new InvalidOperationException("failed to obtain type '" + name + "'");
+            }
+            return typeInfo;
+        }
+
+        /// <summary>Returns the value of the field <paramref name="name"/> of the struct <paramref name="obj"/>.</summary>
+        public static T GetField<T>(IntPtr runtime, IntPtr obj, string name, MunGuid type) where T : struct
+        {
+            IntPtr value = Marshal.AllocHGlobal(Marshal.SizeOf(typeof(T)));
+            try
+            {
+                Check(mun_struct_get_field(runtime, obj, name, type, value));
+                return (T)Marshal.PtrToStructure(value, typeof(T));
+            }
+            finally
+            {
+                Marshal.FreeHGlobal(value);
+            }
+        }
+
+        /// <summary>Sets the value of the field <paramref name="name"/> of the struct <paramref name="obj"/>.</summary>
+        public static void SetField<T>(IntPtr runtime, IntPtr obj, string name, MunGuid type, T value) where T : struct
+        {
+            IntPtr ptr = Marshal.AllocHGlobal(Marshal.SizeOf(typeof(T)));
+            try
+            {
+                Marshal.StructureToPtr(value, ptr, false);
+                Check(mun_struct_set_field(runtime, obj, name, type, ptr));
+            }
+            finally
+            {
+                Marshal.FreeHGlobal(ptr);
+            }
+        }
+    }
+
+"#;
+
+/// Writes the definition of the value struct `ty`, after the value structs that it contains.
+/// Returns `false` if the struct is omitted, because it contains a type without a C# equivalent.
+/// Nothing is written for gc structs. `value_structs` contains the value structs that were
+/// already visited.
+fn write_value_struct<'a>(
+    source: &mut String,
+    ty: &'a TypeInfo,
+    value_structs: &mut HashMap<&'a str, bool>,
+) -> bool {
+    if is_gc_struct(ty) {
+        return true;
+    }
+    if let Some(is_written) = value_structs.get(ty.name()) {
+        return *is_written;
+    }
+    value_structs.insert(ty.name(), false);
+
+    let name = identifier(ty.name());
+    let mut fields = Vec::new();
+    for (field_name, field_ty) in fields_of(ty) {
+        // A value struct contains the handles of the gc structs that it refers to
+        let cs_ty = if is_gc_struct(field_ty) {
+            Some("IntPtr".to_string())
+        } else if field_ty.as_struct().is_some() {
+            if write_value_struct(source, field_ty, value_structs) {
+                Some(identifier(field_ty.name()))
+            } else {
+                None
+            }
+        } else {
+            cs_type(field_ty)
+        };
+        match cs_ty {
+            Some(cs_ty) => fields.push((cs_ty, member_identifier(field_name, &name))),
+            None => {
+                writeln!(
+                    source,
+                    "    // `{}` is omitted, as it contains a type without a C# equivalent.\n",
+                    ty.name()
+                )
+                .unwrap();
+                return false;
+            }
+        }
+    }
+
+    writeln!(
+        source,
+        "    /// <summary>The value struct `{}`.</summary>",
+        ty.name()
+    )
+    .unwrap();
+    writeln!(source, "    [StructLayout(LayoutKind.Sequential)]").unwrap();
+    writeln!(source, "    public struct {}", name).unwrap();
+    writeln!(source, "    {{").unwrap();
+    for (cs_ty, field_name) in fields {
+        if cs_ty == "bool" {
+            writeln!(source, "        [MarshalAs(UnmanagedType.U1)]").unwrap();
+        }
+        writeln!(source, "        public {} {};", cs_ty, field_name).unwrap();
+    }
+    writeln!(source, "    }}").unwrap();
+    writeln!(source).unwrap();
+    value_structs.insert(ty.name(), true);
+    true
+}
+
+/// Writes the definition of the class that wraps the gc struct `ty`.
+fn write_class(source: &mut String, ty: &TypeInfo, value_structs: &HashMap<&str, bool>) {
+    let name = identifier(ty.name());
+    write!(
+        source,
+        r#"    /// <summary>
+    /// A rooted reference to a `{ty_name}` struct, which keeps it alive until it is disposed.
+    /// </summary>
+    public sealed class {name} : IDisposable
+    {{
+        private readonly IntPtr runtime;
+        private IntPtr handle;
+
+        /// <summary>Roots the struct <paramref name="handle"/>.</summary>
+        public {name}(IntPtr runtime, IntPtr handle)
+        {{
+            Native.Check(Native.mun_gc_root(runtime, handle));
+            this.runtime = runtime;
+            this.handle = handle;
+        }}
+
+        /// <summary>Allocates a new struct, of which all fields are zero-initialized.</summary>
+        public static {name} Create(IntPtr runtime)
+        {{
+            IntPtr handle;
+            Native.Check(Native.mun_gc_alloc(runtime, Native.TypeInfo(runtime, "{ty_name}"), out handle));
+            return new {name}(runtime, handle);
+        }}
+
+        /// <summary>The handle of the struct.</summary>
+        public IntPtr Handle
+        {{
+            get {{ return handle; }}
+        }}
+"#,
+        name = name,
+        ty_name = ty.name()
+    )
+    .unwrap();
+
+    for (field_name, field_ty) in fields_of(ty) {
+        writeln!(source).unwrap();
+        let cs_ty = match cs_field_type(field_ty, value_structs) {
+            Some(cs_ty) => cs_ty,
+            None => {
+                writeln!(
+                    source,
+                    "        // `{}` is omitted, as its type `{}` has no C# equivalent.",
+                    field_name,
+                    field_ty.name()
+                )
+                .unwrap();
+                continue;
+            }
+        };
+
+        let guid = guid_literal(field_ty);
+        let (getter, setter) = if is_gc_struct(field_ty) {
+            (
+                format!(
+                    "new {}(runtime, Native.GetField<IntPtr>(runtime, handle, \"{}\", {}))",
+                    cs_ty, field_name, guid
+                ),
+                format!(
+                    "Native.SetField(runtime, handle, \"{}\", {}, value.Handle)",
+                    field_name, guid
+                ),
+            )
+        } else if cs_ty == "bool" {
+            // A `bool` is marshaled as a 4-byte integer, unless it is marshaled as a struct field
+            (
+                format!(
+                    "Native.GetField<byte>(runtime, handle, \"{}\", {}) != 0",
+                    field_name, guid
+                ),
+                format!(
+                    "Native.SetField(runtime, handle, \"{}\", {}, (byte)(value ? 1 : 0))",
+                    field_name, guid
+                ),
+            )
+        } else {
+            (
+                format!(
+                    "Native.GetField<{}>(runtime, handle, \"{}\", {})",
+                    cs_ty, field_name, guid
+                ),
+                format!(
+                    "Native.SetField(runtime, handle, \"{}\", {}, value)",
+                    field_name, guid
+                ),
+            )
+        };
+        if is_gc_struct(field_ty) {
+            writeln!(
+                source,
+                "        /// <summary>The field `{}`. The returned reference has to be disposed.</summary>",
+                field_name
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                source,
+                "        /// <summary>The field `{}`.</summary>",
+                field_name
+            )
+            .unwrap();
+        }
+        write!(
+            source,
+            r#"        public {cs_ty} {property}
+        {{
+            get {{ return {getter}; }}
+            set {{ {setter}; }}
+        }}
+"#,
+            cs_ty = cs_ty,
+            property = member_identifier(field_name, &name),
+            getter = getter,
+            setter = setter
+        )
+        .unwrap();
+    }
+
+    write!(
+        source,
+        r#"
+        /// <summary>Unroots the struct, after which it can be garbage collected.</summary>
+        public void Dispose()
+        {{
+            if (handle != IntPtr.Zero)
+            {{
+                // Errors cannot be reported from `Dispose`
+                Native.mun_error_destroy(Native.mun_gc_unroot(runtime, handle));
+                handle = IntPtr.Zero;
+            }}
+        }}
+    }}
+
+"#
+    )
+    .unwrap();
+}
+
+/// Returns the source of a static method that invokes the public function `function` through the
+/// runtime, preceded by the delegate type of the function.
+fn function_source(
+    function: &mun_abi::FunctionDefinition,
+    value_structs: &HashMap<&str, bool>,
+) -> String {
+    let fn_name = function.prototype.name();
+    let signature = &function.prototype.signature;
+    let arg_types: Option<Vec<String>> = signature
+        .arg_types()
+        .iter()
+        .map(|ty| cs_field_type(ty, value_structs))
+        .collect();
+    let return_type = match signature.return_type() {
+        Some(ty) => cs_field_type(ty, value_structs),
+        None => Some("void".to_string()),
+    };
+    let (arg_types, return_type) = match (arg_types, return_type) {
+        (Some(arg_types), Some(return_type)) => (arg_types, return_type),
+        _ => {
+            return format!(
+                "        // `{}` is omitted, as its signature contains a type without a C# equivalent.\n",
+                fn_name
+            )
+        }
+    };
+
+    // Gc structs are passed to and returned from Mun functions as handles, and a `bool` has to be
+    // marshaled as a single byte
+    let raw_type = |ty: &TypeInfo, cs_ty: &str| {
+        if is_gc_struct(ty) {
+            "IntPtr".to_string()
+        } else if cs_ty == "bool" {
+            "[MarshalAs(UnmanagedType.U1)] bool".to_string()
+        } else {
+            cs_ty.to_string()
+        }
+    };
+    let raw_params: Vec<String> = signature
+        .arg_types()
+        .iter()
+        .zip(arg_types.iter())
+        .enumerate()
+        .map(|(idx, (ty, cs_ty))| format!("{} arg{}", raw_type(ty, cs_ty), idx))
+        .collect();
+    let params: Vec<String> = arg_types
+        .iter()
+        .enumerate()
+        .map(|(idx, cs_ty)| format!(", {} arg{}", cs_ty, idx))
+        .collect();
+    let args: Vec<String> = signature
+        .arg_types()
+        .iter()
+        .enumerate()
+        .map(|(idx, ty)| {
+            if is_gc_struct(ty) {
+                format!("arg{}.Handle", idx)
+            } else {
+                format!("arg{}", idx)
+            }
+        })
+        .collect();
+
+    let method = member_identifier(fn_name, "Functions");
+    let delegate = format!("{}Fn", method);
+    let mut source = String::new();
+    writeln!(
+        source,
+        "        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]"
+    )
+    .unwrap();
+    let raw_return_type = match signature.return_type() {
+        Some(ty) if is_gc_struct(ty) => "IntPtr",
+        Some(_) if return_type == "bool" => {
+            writeln!(source, "        [return: MarshalAs(UnmanagedType.U1)]").unwrap();
+            "bool"
+        }
+        _ => return_type.as_str(),
+    };
+    writeln!(
+        source,
+        "        private delegate {} {}({});",
+        raw_return_type,
+        delegate,
+        raw_params.join(", ")
+    )
+    .unwrap();
+    writeln!(source).unwrap();
+
+    writeln!(source, "        /// <summary>").unwrap();
+    match function.docs() {
+        Some(docs) => {
+            for line in docs.lines() {
+                let line = line.trim();
+                if line.is_empty() {
+                    writeln!(source, "        ///").unwrap();
+                } else {
+                    writeln!(source, "        /// {}", xml_escape(line)).unwrap();
+                }
+            }
+        }
+        None => writeln!(
+            source,
+            "        /// Invokes the Mun function `{}`.",
+            fn_name
+        )
+        .unwrap(),
+    }
+    writeln!(source, "        /// </summary>").unwrap();
+    writeln!(
+        source,
+        "        public static {} {}(IntPtr runtime{})",
+        return_type,
+        method,
+        params.concat()
+    )
+    .unwrap();
+    writeln!(source, "        {{").unwrap();
+    writeln!(
+        source,
+        "            var fn = ({})Marshal.GetDelegateForFunctionPointer(Native.FnPtr(runtime, \"{}\"), typeof({}));",
+        delegate, fn_name, delegate
+    )
+    .unwrap();
+    let call = format!("fn({})", args.join(", "));
+    match signature.return_type() {
+        None => writeln!(source, "            {};", call).unwrap(),
+        Some(ty) if is_gc_struct(ty) => writeln!(
+            source,
+            "            return new {}(runtime, {});",
+            return_type, call
+        )
+        .unwrap(),
+        Some(_) => writeln!(source, "            return {};", call).unwrap(),
+    }
+    writeln!(source, "        }}").unwrap();
+    source
+}
+
+/// Returns the C# type of a field, argument or return value of type `ty`, if it exists.
+/// `value_structs` contains the value structs that were written.
+fn cs_field_type(ty: &TypeInfo, value_structs: &HashMap<&str, bool>) -> Option<String> {
+    if is_gc_struct(ty) {
+        Some(identifier(ty.name()))
+    } else if ty.as_struct().is_some() {
+        if value_structs.get(ty.name()).copied().unwrap_or(false) {
+            Some(identifier(ty.name()))
+        } else {
+            None
+        }
+    } else {
+        cs_type(ty)
+    }
+}
+
+/// Returns the C# type of the fundamental type `ty`, if it exists.
+fn cs_type(ty: &TypeInfo) -> Option<String> {
+    let cs_ty = match ty.name() {
+        "core::bool" => "bool",
+        "core::i8" => "sbyte",
+        "core::i16" => "short",
+        "core::i32" => "int",
+        "core::i64" => "long",
+        "core::isize" => "IntPtr",
+        "core::u8" => "byte",
+        "core::u16" => "ushort",
+        "core::u32" => "uint",
+        "core::u64" => "ulong",
+        "core::usize" => "UIntPtr",
+        "core::f32" => "float",
+        "core::f64" => "double",
+        _ => return None,
+    };
+    Some(cs_ty.to_string())
+}
+
+/// Returns a C# expression that constructs the `Guid` of `ty`.
+fn guid_literal(ty: &TypeInfo) -> String {
+    let half = |bytes: &[u8]| {
+        bytes
+            .iter()
+            .rev()
+            .fold(0u64, |value, byte| (value << 8) | u64::from(*byte))
+    };
+    format!(
+        "new Native.MunGuid({:#018x}UL, {:#018x}UL)",
+        half(&ty.guid.b[..8]),
+        half(&ty.guid.b[8..])
+    )
+}
+
+/// Escapes the characters of `text` that have a special meaning in XML documentation comments.
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Converts `name` into a C# identifier in PascalCase, e.g. `new_foo` into `NewFoo`. Every segment
+/// of a path is capitalized.
+fn identifier(name: &str) -> String {
+    let mut identifier = String::new();
+    for part in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            identifier.push(first.to_ascii_uppercase());
+            identifier.extend(chars);
+        }
+    }
+    // The fields of tuple structs are named after their index
+    if identifier
+        .chars()
+        .next()
+        .map_or(true, |c| c.is_ascii_digit())
+    {
+        identifier.insert_str(0, "Item");
+    }
+    identifier
+}
+
+/// Converts `name` into the identifier of a member of the type `type_name`. C# does not allow
+/// members with the name of their type, nor with the names of the generated members.
+fn member_identifier(name: &str, type_name: &str) -> String {
+    let mut identifier = identifier(name);
+    if identifier == type_name || RESERVED_MEMBERS.contains(&identifier.as_str()) {
+        identifier.push('_');
+    }
+    identifier
+}
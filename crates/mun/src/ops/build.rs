@@ -3,8 +3,9 @@ use std::path::{Path, PathBuf};
 
 use anyhow::anyhow;
 use clap::ArgMatches;
-use mun_compiler::{Config, DisplayColor, Target};
-use mun_project::MANIFEST_FILENAME;
+use mun_compiler::lint::{self, LintLevel, LintLevels};
+use mun_compiler::{Config, DisplayColor, EmitKind, MessageFormat, OutputKind, Target};
+use mun_project::{BuildSettings, Workspace, MANIFEST_FILENAME};
 
 use crate::ExitStatus;
 
@@ -13,9 +14,23 @@ use crate::ExitStatus;
 pub fn build(matches: &ArgMatches) -> Result<ExitStatus, anyhow::Error> {
     log::trace!("starting build");
 
-    let options = compiler_options(matches)?;
+    let manifest_path = locate_manifest(matches)?;
+    log::info!("located build manifest at: {}", manifest_path.display());
+
+    let workspace = Workspace::from_file(&manifest_path)?;
+    let options = compiler_options(matches, workspace.build_settings())?;
+
+    if matches.is_present("watch") {
+        mun_compiler_daemon::compile_and_watch_manifest(&manifest_path, options)
+    } else {
+        mun_compiler::compile_manifest(&manifest_path, options)
+    }
+    .map(Into::into)
+}
 
-    // Locate the manifest
+/// Returns the path of the manifest that is specified with `--manifest-path`, or else of the
+/// manifest in the current directory or one of its parent directories.
+pub(super) fn locate_manifest(matches: &ArgMatches) -> Result<PathBuf, anyhow::Error> {
     let manifest_path = match matches.value_of("manifest-path") {
         None => {
             let current_dir =
@@ -31,15 +46,7 @@ pub fn build(matches: &ArgMatches) -> Result<ExitStatus, anyhow::Error> {
         Some(path) => std::fs::canonicalize(Path::new(path))
             .map_err(|_| anyhow::anyhow!("'{}' does not refer to a valid manifest path", path))?,
     };
-
-    log::info!("located build manifest at: {}", manifest_path.display());
-
-    if matches.is_present("watch") {
-        mun_compiler_daemon::compile_and_watch_manifest(&manifest_path, options)
-    } else {
-        mun_compiler::compile_manifest(&manifest_path, options)
-    }
-    .map(Into::into)
+    Ok(manifest_path)
 }
 
 /// Find a Mun manifest file in the specified directory or one of its parents.
@@ -55,8 +62,17 @@ fn find_manifest(directory: &Path) -> Option<PathBuf> {
     None
 }
 
-fn compiler_options(matches: &ArgMatches) -> Result<mun_compiler::Config, anyhow::Error> {
-    let optimization_lvl = match matches.value_of("opt-level") {
+/// Returns the compiler configuration that is specified by the command line arguments. The
+/// settings in the `build` section of the manifest are used for arguments that are not specified.
+pub(super) fn compiler_options(
+    matches: &ArgMatches,
+    build_settings: &BuildSettings,
+) -> Result<mun_compiler::Config, anyhow::Error> {
+    let opt_level = build_settings.opt_level.map(|level| level.to_string());
+    let optimization_lvl = match matches
+        .value_of("opt-level")
+        .or_else(|| opt_level.as_deref())
+    {
         Some("0") => mun_compiler::OptimizationLevel::None,
         Some("1") => mun_compiler::OptimizationLevel::Less,
         None | Some("2") => mun_compiler::OptimizationLevel::Default,
@@ -75,16 +91,85 @@ fn compiler_options(matches: &ArgMatches) -> Result<mun_compiler::Config, anyhow
         })
         .unwrap_or(DisplayColor::Auto);
 
+    let overflow_checks = matches
+        .value_of("overflow-checks")
+        .map(|value| value == "enable");
+
+    let assembly_name = matches.value_of("name");
+    if let Some(name) = assembly_name {
+        if name.is_empty() || name.contains(&['/', '\\'][..]) {
+            return Err(anyhow!("invalid assembly name `{}`", name));
+        }
+    }
+
+    let jobs = match matches.value_of("jobs") {
+        Some(jobs) => match jobs.parse() {
+            Ok(jobs) if jobs > 0 => Some(jobs),
+            _ => return Err(anyhow!("the number of jobs must be a positive integer")),
+        },
+        None => None,
+    };
+
     Ok(Config {
         target: matches
             .value_of("target")
+            .or_else(|| build_settings.target.as_deref())
             .map_or_else(Target::host_target, Target::search)?,
         optimization_lvl,
-        out_dir: None,
+        out_dir: matches.value_of("out-dir").map(PathBuf::from),
         display_color,
+        message_format: match matches.value_of("message-format") {
+            Some("json") => MessageFormat::Json,
+            _ => MessageFormat::Human,
+        },
+        overflow_checks,
+        debug_info: matches.is_present("debug-info"),
+        lint_levels: lint_levels(matches)?,
+        emit_hir: matches
+            .values_of("emit")
+            .map_or(false, |mut kinds| kinds.any(|kind| kind == "hir")),
+        emit: matches.values_of("emit").map_or_else(Vec::new, |kinds| {
+            kinds.filter_map(EmitKind::from_name).collect()
+        }),
+        output_kind: matches
+            .value_of("output-kind")
+            .and_then(OutputKind::from_name)
+            .unwrap_or_default(),
+        apply_fixes: matches.is_present("apply-fixes"),
+        single_assembly: matches.is_present("single-assembly") || assembly_name.is_some(),
+        assembly_name: assembly_name.map(ToOwned::to_owned),
+        jobs,
+        timings: matches.is_present("timings"),
     })
 }
 
+/// Returns the lint levels that are specified with `--allow`, `--warn` and `--deny`. If a lint is
+/// specified multiple times, the last occurrence wins.
+fn lint_levels(matches: &ArgMatches) -> Result<LintLevels, anyhow::Error> {
+    let mut specified = Vec::new();
+    for (arg, level) in &[
+        ("allow", LintLevel::Allow),
+        ("warn", LintLevel::Warn),
+        ("deny", LintLevel::Deny),
+    ] {
+        if let (Some(indices), Some(values)) = (matches.indices_of(arg), matches.values_of(arg)) {
+            specified.extend(
+                indices
+                    .zip(values)
+                    .map(|(index, name)| (index, name, *level)),
+            );
+        }
+    }
+    specified.sort_by_key(|(index, _, _)| *index);
+
+    let mut levels = LintLevels::default();
+    for (_, name, level) in specified {
+        let lint = lint::find_lint(name).ok_or_else(|| anyhow!("unknown lint: `{}`", name))?;
+        levels.set(lint, level);
+    }
+    Ok(levels)
+}
+
 #[cfg(test)]
 mod test {
     use super::find_manifest;
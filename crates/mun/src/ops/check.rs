@@ -0,0 +1,20 @@
+use clap::ArgMatches;
+use mun_project::Workspace;
+
+use crate::ops::build::{compiler_options, locate_manifest};
+use crate::ExitStatus;
+
+/// This method is invoked when the executable is run with the `check` argument indicating that a
+/// user requested us to check a project for errors without building it. Because no code is
+/// generated, this is considerably faster than a build.
+pub fn check(matches: &ArgMatches) -> Result<ExitStatus, anyhow::Error> {
+    log::trace!("starting check");
+
+    let manifest_path = locate_manifest(matches)?;
+    log::info!("located manifest at: {}", manifest_path.display());
+
+    let workspace = Workspace::from_file(&manifest_path)?;
+    let options = compiler_options(matches, workspace.build_settings())?;
+
+    mun_compiler::check_manifest(&manifest_path, options).map(Into::into)
+}
@@ -0,0 +1,15 @@
+use anyhow::anyhow;
+use clap::ArgMatches;
+use mun_compiler::error_codes;
+
+use crate::ExitStatus;
+
+/// This method is invoked when the executable is run with the `explain` argument. It prints the
+/// extended explanation of an error code, e.g. `E0002`, or the description of a lint.
+pub fn explain(matches: &ArgMatches) -> Result<ExitStatus, anyhow::Error> {
+    let code = matches.value_of("CODE").unwrap();
+    let explanation = error_codes::explain(code)
+        .ok_or_else(|| anyhow!("unknown error code or lint: `{}`", code))?;
+    print!("{}", explanation);
+    Ok(ExitStatus::Success)
+}
@@ -0,0 +1,69 @@
+use clap::ArgMatches;
+use mun_project::Workspace;
+
+use crate::ops::build::locate_manifest;
+use crate::ExitStatus;
+
+/// This method is invoked when the executable is run with the `fmt` argument indicating that a
+/// user requested us to format the source files of a project. Only the packages inside the
+/// directory of the workspace are formatted, not the packages that they depend on through a path
+/// outside of it. With `--check`, the files are not changed but reported if they are not
+/// formatted.
+pub fn fmt(matches: &ArgMatches) -> Result<ExitStatus, anyhow::Error> {
+    log::trace!("starting fmt");
+
+    let manifest_path = locate_manifest(matches)?;
+    log::info!("located manifest at: {}", manifest_path.display());
+
+    let workspace = Workspace::from_file(&manifest_path)?;
+    let check = matches.is_present("check");
+
+    let mut success = true;
+    for package in workspace.packages() {
+        if !package.root().starts_with(workspace.root()) {
+            continue;
+        }
+        let source_directory = package.source_directory().ok_or_else(|| {
+            anyhow::anyhow!(
+                "the source directory of package `{}` does not exist",
+                package.name()
+            )
+        })?;
+
+        for source_file_path in mun_compiler::iter_source_files(&source_directory) {
+            let text = std::fs::read_to_string(&source_file_path).map_err(|e| {
+                anyhow::anyhow!("could not read '{}': {}", source_file_path.display(), e)
+            })?;
+            let formatted = match mun_fmt::format(&text) {
+                Ok(formatted) => formatted,
+                Err(e) => {
+                    eprintln!(
+                        "error: could not format '{}': {}",
+                        source_file_path.display(),
+                        e
+                    );
+                    success = false;
+                    continue;
+                }
+            };
+            if formatted == text {
+                continue;
+            }
+
+            if check {
+                println!("'{}' is not formatted", source_file_path.display());
+                success = false;
+            } else {
+                std::fs::write(&source_file_path, formatted).map_err(|e| {
+                    anyhow::anyhow!("could not write '{}': {}", source_file_path.display(), e)
+                })?;
+            }
+        }
+    }
+
+    Ok(if success {
+        ExitStatus::Success
+    } else {
+        ExitStatus::Error
+    })
+}
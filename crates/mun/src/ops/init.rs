@@ -2,12 +2,14 @@ use std::fs;
 use std::path::Path;
 
 use anyhow::anyhow;
+use clap::ArgMatches;
+use mun_project::MANIFEST_FILENAME;
 
 use crate::ExitStatus;
 
 /// This method is invoked when the executable is run with the `init` argument indicating that a
 /// user requested us to create a new project in the current directory.
-pub fn init() -> Result<ExitStatus, anyhow::Error> {
+pub fn init(matches: &ArgMatches) -> Result<ExitStatus, anyhow::Error> {
     let create_in = std::env::current_dir().expect("could not determine current working directory");
     let project_name = create_in
         .file_name()
@@ -15,11 +17,21 @@ pub fn init() -> Result<ExitStatus, anyhow::Error> {
         .to_str()
         .expect("Project name must be valid UTF-8");
 
-    create_project(&create_in, project_name)
+    if create_in.join(MANIFEST_FILENAME).exists() {
+        eprint!("`mun init` cannot be run on existing Mun packages");
+        return Ok(ExitStatus::Error);
+    }
+    create_project(&create_in, project_name, matches.is_present("host"))
 }
 
-/// This is used by `init` and `new` arguments to create projects in different paths.
-pub fn create_project(create_in: &Path, project_name: &str) -> Result<ExitStatus, anyhow::Error> {
+/// This is used by `init` and `new` arguments to create projects in different paths. If
+/// `with_host` is `true`, a Rust application that runs the package is created in the `host`
+/// directory.
+pub fn create_project(
+    create_in: &Path,
+    project_name: &str,
+    with_host: bool,
+) -> Result<ExitStatus, anyhow::Error> {
     log::trace!("Creating new project");
     {
         let manifest_path = create_in.join("mun.toml");
@@ -51,10 +63,67 @@ version="0.1.0"
 "#,
         )?;
     }
+    {
+        // Existing ignore rules, e.g. of a repository that the package is created in, are kept
+        let gitignore_path = create_in.join(".gitignore");
+        if !gitignore_path.exists() {
+            let ignored = if with_host {
+                "/target\n/host/target\n"
+            } else {
+                "/target\n"
+            };
+            write(&gitignore_path, ignored)?;
+        }
+    }
+    if with_host {
+        create_host(create_in, project_name)?;
+    }
     println!("Created `{}` package", project_name);
     Ok(ExitStatus::Success)
 }
 
+/// Creates a Rust application in the `host` directory of the project that loads the assembly of
+/// the package and invokes its `main` function.
+fn create_host(create_in: &Path, project_name: &str) -> anyhow::Result<()> {
+    let host_path = create_in.join("host");
+    create_dir(&host_path)?;
+    write(
+        host_path.join("Cargo.toml"),
+        format!(
+            r#"[package]
+name = "{}-host"
+version = "0.1.0"
+edition = "2018"
+
+[dependencies]
+mun = "{}"
+"#,
+            project_name,
+            env!("CARGO_PKG_VERSION"),
+        ),
+    )?;
+
+    let src_path = host_path.join("src");
+    create_dir(&src_path)?;
+    write(
+        src_path.join("main.rs"),
+        r#"use mun::{invoke_fn, runtime::RuntimeBuilder};
+
+fn main() {
+    // Build the assembly with `mun build` in the directory of the package
+    let library_path = concat!(env!("CARGO_MANIFEST_DIR"), "/../target/main.munlib");
+    let runtime = RuntimeBuilder::new(library_path)
+        .spawn()
+        .expect("failed to load the assembly, run `mun build` first");
+
+    let runtime_ref = runtime.borrow();
+    let result: f64 = invoke_fn!(runtime_ref, "main").unwrap();
+    println!("main() returned {}", result);
+}
+"#,
+    )
+}
+
 /// Shortcut function for creating new directories.
 pub fn create_dir(path: impl AsRef<Path>) -> anyhow::Result<()> {
     fs::create_dir(&path)
@@ -6,13 +6,22 @@ use crate::ExitStatus;
 /// This method is invoked when the executable is run with the `new` argument indicating that a
 /// user requested us to create a new project in a new directory.
 pub fn new(matches: &ArgMatches) -> Result<ExitStatus, anyhow::Error> {
-    let project_name = matches.value_of("path").expect(
+    let path = matches.value_of("path").expect(
         "Path argument not found: This should be unreachable as clap requires this argument.",
     );
 
     let create_in = std::env::current_dir()
         .expect("could not determine current working directory")
-        .join(project_name);
+        .join(path);
+
+    // The package is named after the directory that it is created in
+    let project_name = match create_in.file_name().and_then(|name| name.to_str()) {
+        Some(project_name) => project_name,
+        None => {
+            eprint!("cannot derive a package name from the path `{}`", path);
+            return Ok(ExitStatus::Error);
+        }
+    };
 
     if create_in.exists() {
         eprint!(
@@ -23,5 +32,5 @@ pub fn new(matches: &ArgMatches) -> Result<ExitStatus, anyhow::Error> {
         return Ok(ExitStatus::Error);
     }
     create_dir(&create_in)?;
-    create_project(&create_in, project_name)
+    create_project(&create_in, project_name, matches.is_present("host"))
 }
@@ -0,0 +1,427 @@
+use std::any::Any;
+use std::io::{self, BufRead, Write};
+
+use anyhow::anyhow;
+use clap::ArgMatches;
+use mun_compiler::{Config, Driver, FileId, PathOrInline, RelativePathBuf};
+use mun_project::BuildSettings;
+use mun_runtime::{
+    invoke_fn, ReturnTypeReflection, Runtime, RuntimeBuilder, StaticAssembly, StructRef,
+};
+use mun_syntax::ast::{self, ModuleItemOwner, NameOwner};
+use mun_syntax::AstNode;
+
+use crate::ops::build::compiler_options;
+use crate::ExitStatus;
+
+/// The name of the function that is generated to evaluate the entered `let` statements and
+/// expression.
+const EVAL_FN_NAME: &str = "__repl_eval";
+
+const HELP: &str = "\
+Enter an expression to evaluate it, e.g. `1 + 2`, or a function or struct definition to use it in
+later inputs. A definition replaces an earlier definition with the same name. Variables that are
+declared with `let` are evaluated again for every input, so assigning to them has no lasting
+effect. An input continues on the next line until its brackets are balanced.
+
+Commands:
+    :help     show this message
+    :reset    forget all definitions and variables
+    :quit     exit the REPL
+";
+
+/// This method is invoked when the executable is run with the `repl` argument indicating that a
+/// user requested an interactive session. Every input is compiled together with the definitions
+/// of earlier inputs by the just-in-time compiler and hot reloaded into a runtime that persists
+/// for the whole session.
+pub fn repl(matches: &ArgMatches) -> Result<ExitStatus, anyhow::Error> {
+    log::trace!("starting repl");
+
+    let options = compiler_options(matches, &BuildSettings::default())?;
+    let session = Session::new(options)?;
+
+    let stdin = io::stdin();
+    run(session, &mut stdin.lock(), &mut io::stdout())?;
+    Ok(ExitStatus::Success)
+}
+
+/// Reads inputs from `input` and writes their results to `output`, until the input ends or the
+/// user quits.
+fn run(
+    mut session: Session,
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+) -> Result<(), anyhow::Error> {
+    writeln!(
+        output,
+        "Mun {}. Enter :help for help.",
+        env!("CARGO_PKG_VERSION")
+    )?;
+
+    let mut text = String::new();
+    loop {
+        write!(output, "{}", if text.is_empty() { "> " } else { ". " })?;
+        output.flush()?;
+        if input.read_line(&mut text)? == 0 {
+            // The input ended, e.g. because Ctrl-D was pressed
+            writeln!(output)?;
+            return Ok(());
+        }
+        if is_incomplete(&text) {
+            continue;
+        }
+
+        let entered = std::mem::take(&mut text);
+        match entered.trim() {
+            "" => {}
+            ":quit" | ":q" => return Ok(()),
+            ":help" | ":h" => write!(output, "{}", HELP)?,
+            ":reset" => session.reset(),
+            command if command.starts_with(':') => writeln!(
+                output,
+                "unknown command `{}`, enter :help for help",
+                command
+            )?,
+            trimmed => {
+                if let Err(e) = session.eval(trimmed, output) {
+                    writeln!(output, "error: {}", e)?;
+                }
+            }
+        }
+    }
+}
+
+/// Returns true if `text` opens more brackets than it closes, in which case the input continues
+/// on the next line. Brackets in comments and literals are counted as well.
+fn is_incomplete(text: &str) -> bool {
+    let depth = text.chars().fold(0i32, |depth, c| match c {
+        '{' | '(' | '[' => depth + 1,
+        '}' | ')' | ']' => depth - 1,
+        _ => depth,
+    });
+    depth > 0
+}
+
+/// A function, struct or impl definition that was entered.
+#[derive(Clone, Debug)]
+struct Item {
+    /// The name of the function or struct, or `None` for an impl
+    name: Option<String>,
+    text: String,
+}
+
+/// An input of the user that is not a command.
+enum Input {
+    Items(Vec<Item>),
+    /// A `let` statement, including the terminating semicolon
+    Binding(String),
+    Expr(String),
+}
+
+impl Input {
+    fn parse(text: &str) -> Input {
+        let parse = ast::SourceFile::parse(text);
+        let items: Vec<_> = parse.tree().items().collect();
+        if parse.errors().is_empty() && !items.is_empty() {
+            return Input::Items(
+                items
+                    .into_iter()
+                    .map(|item| Item {
+                        name: match item.kind() {
+                            ast::ModuleItemKind::FunctionDef(f) => f.name(),
+                            ast::ModuleItemKind::StructDef(s) => s.name(),
+                            ast::ModuleItemKind::ImplDef(_) => None,
+                        }
+                        .map(|name| name.text().to_string()),
+                        text: item.syntax().text().to_string(),
+                    })
+                    .collect(),
+            );
+        }
+
+        let text = text.trim_end_matches(';').trim_end();
+        if text.starts_with("let") && text[3..].starts_with(char::is_whitespace) {
+            Input::Binding(format!("{};", text))
+        } else {
+            Input::Expr(text.to_string())
+        }
+    }
+}
+
+/// The state of a REPL session: the definitions and `let` statements that were entered so far,
+/// and the runtime that the compiled code is loaded into.
+struct Session {
+    driver: Driver,
+    file_id: FileId,
+    items: Vec<Item>,
+    bindings: Vec<String>,
+    /// The runtime is created when the first input is compiled. It is declared before
+    /// `_initial_code`, which keeps the code of the first assembly alive, so it is dropped first.
+    runtime: Option<Runtime>,
+    _initial_code: Option<Box<dyn Any>>,
+}
+
+impl Session {
+    fn new(config: Config) -> Result<Self, anyhow::Error> {
+        let input = PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("repl.mun"),
+            contents: String::new(),
+        };
+        let (driver, file_id) = Driver::with_file(config, input)?;
+        Ok(Session {
+            driver,
+            file_id,
+            items: Vec::new(),
+            bindings: Vec::new(),
+            runtime: None,
+            _initial_code: None,
+        })
+    }
+
+    /// Forgets all definitions and `let` statements. The code of the runtime is replaced when the
+    /// next input is compiled.
+    fn reset(&mut self) {
+        self.items.clear();
+        self.bindings.clear();
+    }
+
+    /// Compiles `text`, which is a definition, a `let` statement or an expression, together with
+    /// the definitions and `let` statements of earlier inputs and evaluates it. The value of an
+    /// expression is written to `output`. If `text` does not compile, the errors are written to
+    /// `output` instead and the session is left unchanged.
+    fn eval(&mut self, text: &str, output: &mut dyn Write) -> Result<(), anyhow::Error> {
+        let mut items = self.items.clone();
+        let mut bindings = self.bindings.clone();
+        let (expr, should_invoke) = match Input::parse(text) {
+            Input::Items(new_items) => {
+                items.retain(|item| {
+                    item.name.is_none() || new_items.iter().all(|new| new.name != item.name)
+                });
+                items.extend(new_items);
+                (None, false)
+            }
+            // The statement is invoked once to report a panic of its initializer right away
+            Input::Binding(binding) => {
+                bindings.push(binding);
+                (None, true)
+            }
+            Input::Expr(expr) => (Some(expr), true),
+        };
+
+        // The type of the expression is inferred before the function that returns it is generated
+        let ret_type = match &expr {
+            Some(expr) => {
+                self.driver
+                    .set_file_text(self.file_id, source(&items, &bindings, Some(expr), None));
+                self.driver.tail_expr_type(self.file_id, EVAL_FN_NAME)
+            }
+            None => None,
+        };
+        self.driver.set_file_text(
+            self.file_id,
+            source(&items, &bindings, expr.as_deref(), ret_type.as_deref()),
+        );
+
+        // Warnings, e.g. about variables that are not used yet, are not reported
+        let mut diagnostics = Vec::new();
+        if self.driver.emit_diagnostics(&mut diagnostics)? {
+            output.write_all(&diagnostics)?;
+            return Ok(());
+        }
+
+        self.load()?;
+        if should_invoke {
+            let runtime = self
+                .runtime
+                .as_ref()
+                .expect("the runtime is created by `load`");
+            if let Some(value) = evaluate(runtime)? {
+                writeln!(output, "{}", value)?;
+            }
+        }
+        self.items = items;
+        self.bindings = bindings;
+        Ok(())
+    }
+
+    /// Compiles the source of the session into the memory of this process and loads it into the
+    /// runtime, which hot reloads the assembly of the previous input.
+    fn load(&mut self) -> Result<(), anyhow::Error> {
+        let jit = self.driver.jit_assembly(self.file_id)?;
+        let assembly = StaticAssembly {
+            get_info: jit.get_info,
+            get_version: jit.get_version,
+            set_allocator_handle: jit.set_allocator_handle,
+        };
+        match &mut self.runtime {
+            Some(runtime) => {
+                let library_path = runtime
+                    .assemblies()
+                    .next()
+                    .expect("the runtime is created with an assembly")
+                    .library_path()
+                    .to_path_buf();
+                runtime.load_in_process(&library_path, assembly, Box::new(jit.code))?;
+            }
+            None => {
+                self.runtime = Some(RuntimeBuilder::from_static(assembly).finish()?);
+                self._initial_code = Some(Box::new(jit.code));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the source of the session: the definitions, followed by a public function that
+/// executes the `let` statements and returns the value of the expression, if any.
+fn source(
+    items: &[Item],
+    bindings: &[String],
+    expr: Option<&str>,
+    ret_type: Option<&str>,
+) -> String {
+    let mut source = String::new();
+    for item in items {
+        source.push_str(&item.text);
+        source.push('\n');
+    }
+    source.push_str(&format!("pub fn {}()", EVAL_FN_NAME));
+    if let Some(ret_type) = ret_type {
+        source.push_str(&format!(" -> {}", ret_type));
+    }
+    source.push_str(" {\n");
+    for statement in bindings.iter().map(String::as_str).chain(expr) {
+        source.push_str(&format!("    {}\n", statement));
+    }
+    source.push_str("}\n");
+    source
+}
+
+/// Evaluates `$value` as the Rust type of the primitive Mun type with the GUID `$guid` and formats
+/// the result, or evaluates to `None` if the type is not primitive.
+macro_rules! format_primitive {
+    (@types $guid:expr, $value:expr; $($ty:ty),+) => {{
+        let guid = $guid;
+        $(
+            if *guid == <$ty as ReturnTypeReflection>::type_guid() {
+                let value: $ty = $value;
+                Some(format!("{:?}", value))
+            } else
+        )+
+        {
+            None
+        }
+    }};
+    ($guid:expr, $value:expr) => {
+        format_primitive!(@types $guid, $value;
+            bool, char, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64)
+    };
+}
+
+/// Invokes the function that evaluates the input and formats the value that it returns, or
+/// returns `None` if it returns no value.
+fn evaluate(runtime: &Runtime) -> Result<Option<String>, anyhow::Error> {
+    let fn_definition = runtime
+        .get_function_definition(EVAL_FN_NAME)
+        .ok_or_else(|| anyhow!("failed to obtain function '{}'", EVAL_FN_NAME))?;
+    let ret_type = match fn_definition.prototype.signature.return_type() {
+        Some(ret_type) => ret_type,
+        None => {
+            return invoke_fn!(runtime, EVAL_FN_NAME)
+                .map(|_: ()| None)
+                .map_err(|e| anyhow!("{}", e))
+        }
+    };
+
+    if ret_type.as_struct().is_some() {
+        let value: StructRef = invoke_fn!(runtime, EVAL_FN_NAME).map_err(|e| anyhow!("{}", e))?;
+        return format_struct(&value).map(Some);
+    }
+    format_primitive!(
+        &ret_type.guid,
+        invoke_fn!(runtime, EVAL_FN_NAME).map_err(|e| anyhow!("{}", e))?
+    )
+    .map(Some)
+    .ok_or_else(|| anyhow!("values of type `{}` cannot be printed", ret_type.name()))
+}
+
+/// Formats a struct and the values of its fields, e.g. `Foo { a: 1, b: Bar { c: true } }`.
+fn format_struct(value: &StructRef) -> Result<String, anyhow::Error> {
+    let mut fields = Vec::new();
+    for (index, (name, ty)) in value.fields().enumerate() {
+        let field = if ty.as_struct().is_some() {
+            let field: StructRef = value.get_by_index(index).map_err(|e| anyhow!("{}", e))?;
+            format_struct(&field)?
+        } else {
+            format_primitive!(
+                &ty.guid,
+                value.get_by_index(index).map_err(|e| anyhow!("{}", e))?
+            )
+            .unwrap_or_else(|| format!("<{}>", ty.name()))
+        };
+        fields.push(format!("{}: {}", name, field));
+    }
+
+    let name = value.type_info().name();
+    Ok(if fields.is_empty() {
+        name.to_string()
+    } else {
+        format!("{} {{ {} }}", name, fields.join(", "))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_incomplete, Session};
+    use mun_compiler::{Config, DisplayColor};
+
+    fn eval(session: &mut Session, text: &str) -> String {
+        let mut output = Vec::new();
+        session.eval(text, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn session() {
+        let config = Config {
+            display_color: DisplayColor::Disable,
+            ..Config::default()
+        };
+        let mut session = Session::new(config).unwrap();
+
+        assert_eq!(eval(&mut session, "struct Foo { a: i32, b: bool }"), "");
+        assert_eq!(
+            eval(&mut session, "fn add(a: i32, b: i32) -> i32 { a + b }"),
+            ""
+        );
+        assert_eq!(eval(&mut session, "let x = add(1, 2);"), "");
+        assert_eq!(eval(&mut session, "x * 2"), "6\n");
+        assert_eq!(eval(&mut session, "1.5 + 1.0"), "2.5\n");
+        assert_eq!(
+            eval(&mut session, "Foo { a: x, b: true }"),
+            "Foo { a: 3, b: true }\n"
+        );
+
+        // A definition replaces the earlier definition with the same name
+        assert_eq!(
+            eval(&mut session, "fn add(a: i32, b: i32) -> i32 { a - b }"),
+            ""
+        );
+        assert_eq!(eval(&mut session, "x"), "-1\n");
+
+        // An input that does not compile leaves the session unchanged
+        assert!(eval(&mut session, "let y = z;").contains("error"));
+        assert_eq!(eval(&mut session, "x + 1"), "0\n");
+
+        session.reset();
+        assert!(eval(&mut session, "x").contains("error"));
+    }
+
+    #[test]
+    fn incomplete_input() {
+        assert!(is_incomplete("fn foo() {\n"));
+        assert!(is_incomplete("add(1,\n"));
+        assert!(!is_incomplete("fn foo() {\n}\n"));
+        assert!(!is_incomplete("1 + 2\n"));
+    }
+}
@@ -0,0 +1,93 @@
+use clap::ArgMatches;
+use mun_compiler::Target;
+use mun_project::Workspace;
+use mun_runtime::{Runtime, RuntimeBuilder};
+use std::path::PathBuf;
+
+use crate::ops::build::{compiler_options, locate_manifest};
+use crate::ExitStatus;
+
+/// This method is invoked when the executable is run with the `test` argument indicating that a
+/// user requested us to build a project and run the functions that are marked with a `#[test]`
+/// attribute. A test fails if it panics, e.g. because an assertion failed.
+pub fn test(matches: &ArgMatches) -> Result<ExitStatus, anyhow::Error> {
+    log::trace!("starting test");
+
+    let manifest_path = locate_manifest(matches)?;
+    log::info!("located manifest at: {}", manifest_path.display());
+
+    let workspace = Workspace::from_file(&manifest_path)?;
+    let mut options = compiler_options(matches, workspace.build_settings())?;
+    // Tests are run on this machine, regardless of the target that the package is built for
+    options.target = Target::host_target()?;
+
+    let assemblies = match mun_compiler::compile_manifest_assemblies(&manifest_path, options)? {
+        Some(assemblies) => assemblies,
+        None => return Ok(ExitStatus::Error),
+    };
+
+    let runtime = match load_runtime(assemblies)? {
+        Some(runtime) => runtime,
+        None => {
+            println!("running 0 tests");
+            return Ok(ExitStatus::Success);
+        }
+    };
+
+    let filter = matches.value_of("TESTNAME");
+    let mut tests: Vec<_> = runtime
+        .tests()
+        .map(|test| test.prototype.name().to_owned())
+        .filter(|name| filter.map_or(true, |filter| name.contains(filter)))
+        .collect();
+    tests.sort();
+
+    println!("running {} tests", tests.len());
+    let mut failures = Vec::new();
+    for name in tests.iter() {
+        match runtime.run_test(name) {
+            Ok(()) => println!("test {} ... ok", name),
+            Err(error) => {
+                println!("test {} ... FAILED", name);
+                failures.push((name, error));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        println!("\nfailures:");
+        for (name, error) in failures.iter() {
+            match error.panic() {
+                Some(panic) => println!("    {} panicked: {}", name, panic),
+                None => println!("    {}: {}", name, error),
+            }
+        }
+    }
+
+    println!(
+        "\ntest result: {}. {} passed; {} failed",
+        if failures.is_empty() { "ok" } else { "FAILED" },
+        tests.len() - failures.len(),
+        failures.len()
+    );
+
+    Ok(if failures.is_empty() {
+        ExitStatus::Success
+    } else {
+        ExitStatus::Error
+    })
+}
+
+/// Loads the compiled `assemblies` into a single runtime, so the functions of one assembly can
+/// call the functions of the others. Returns `None` if there are no assemblies.
+pub(super) fn load_runtime(assemblies: Vec<PathBuf>) -> Result<Option<Runtime>, anyhow::Error> {
+    let mut assemblies = assemblies.into_iter();
+    let mut builder = match assemblies.next() {
+        Some(library_path) => RuntimeBuilder::new(library_path),
+        None => return Ok(None),
+    };
+    for library_path in assemblies {
+        builder = builder.add_library(library_path);
+    }
+    Ok(Some(builder.finish()?))
+}
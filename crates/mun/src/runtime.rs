@@ -0,0 +1,42 @@
+//! Loading, invoking, reflecting and hot reloading of Mun assemblies.
+//!
+//! This module is part of the stable API of the `mun` crate. Prefer it over depending on the
+//! `mun_runtime` crate directly, whose API may change between releases. Functions are invoked with
+//! the `invoke_fn!` macro and [`Runtime::update`] reloads modified assemblies.
+
+#[cfg(all(
+    any(target_arch = "x86_64", target_arch = "aarch64"),
+    not(target_os = "windows")
+))]
+pub use mun_runtime::Value;
+pub use mun_runtime::{
+    AllocationEvent, Allocator, ArgumentReflection, Assembly, AssemblyInfo, ClosureConflict,
+    DispatchTable, FieldInfo, FunctionDefinition, FunctionDefinitionStorage, FunctionPrototype,
+    GarbageCollector, GcStats, Guid, Handle, InterruptHandle, IntoClosureDefinition,
+    IntoFunctionDefinition, InvokeErr0, InvokeErr1, InvokeErr2, InvokeErr3, InvokeErr4, InvokeErr5,
+    InvokeErr6, InvokeError, Marshal, MunStruct, Panic, PinnedStruct, RawStruct,
+    ReturnTypeReflection, RootedStruct, Runtime, RuntimeBuilder, RuntimeOptions, StaticAssembly,
+    StructInfo, StructRef, SystemAllocator, TypeInfo, UnsafeTypeInfo, ValueStruct, WeakStructRef,
+    DEFAULT_MAX_STACK_SIZE,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use mun_runtime::{ReloadChanges, StructMigration};
+
+/// The types of the Mun ABI that the reflection API of the runtime refers to.
+pub mod abi {
+    pub use mun_abi::{
+        AssemblyInfo, DispatchTable, FieldInfo, FunctionDefinition, FunctionPrototype,
+        FunctionSignature, Guid, ModuleInfo, Privacy, StructInfo, StructMemoryKind, TypeGroup,
+        TypeInfo,
+    };
+}
+
+/// The interface of the garbage collector that manages the memory of Mun objects, see
+/// [`Runtime::gc`].
+pub mod gc {
+    pub use mun_memory::gc::{
+        Allocator, GcPtr, GcRuntime, HasIndirectionPtr, RawGcPtr, Stats, SystemAllocator,
+        TypeTrace, WeakGcPtr,
+    };
+    pub use mun_memory::TypeMemory;
+}
@@ -1,5 +1,4 @@
-use mun::run_with_args;
-use mun_runtime::{invoke_fn, RuntimeBuilder};
+use mun::{invoke_fn, run_with_args, runtime::RuntimeBuilder};
 use serial_test::serial;
 use std::env::set_current_dir;
 use std::ffi::OsString;
@@ -38,7 +37,754 @@ fn mun_new() {
     build_and_run(project.as_ref().join("mun_project_example"));
 }
 
+/// Creates a new project with a host application using `mun new --host` and then tests that the
+/// package works.
+#[test]
+#[serial] // This test must be run in serial as files may conflict.
+fn mun_new_with_host() {
+    let project = tempfile::Builder::new()
+        .prefix("mun_projects")
+        .tempdir()
+        .unwrap();
+
+    set_current_dir(&project).unwrap();
+
+    // The package is named after the last component of the path
+    std::fs::create_dir(project.as_ref().join("nested")).unwrap();
+    let args: Vec<OsString> = vec![
+        "mun".into(),
+        "new".into(),
+        "nested/mun_project_example".into(),
+        "--host".into(),
+    ];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    let package = project.as_ref().join("nested/mun_project_example");
+    let manifest = std::fs::read_to_string(package.join("mun.toml")).unwrap();
+    assert!(manifest.contains("name=\"mun_project_example\""));
+    let host_manifest = std::fs::read_to_string(package.join("host/Cargo.toml")).unwrap();
+    assert!(host_manifest.contains("name = \"mun_project_example-host\""));
+    assert!(package.join("host/src/main.rs").is_file());
+    assert_eq!(
+        std::fs::read_to_string(package.join(".gitignore")).unwrap(),
+        "/target\n/host/target\n"
+    );
+    build_and_run(package);
+}
+
+/// Tests that `mun init` does not overwrite an existing package.
+#[test]
+#[serial] // This test must be run in serial as files may conflict.
+fn mun_init_existing_package() {
+    let project = tempfile::Builder::new()
+        .prefix("mun_project_example")
+        .tempdir()
+        .unwrap();
+
+    set_current_dir(&project).unwrap();
+
+    let args = || -> Vec<OsString> { vec!["mun".into(), "init".into()] };
+    assert_eq!(run_with_args(args()).unwrap(), mun::ExitStatus::Success);
+    assert_eq!(run_with_args(args()).unwrap(), mun::ExitStatus::Error);
+    build_and_run(project);
+}
+
+/// Creates a new project and tests that `--emit` writes the requested artifacts next to the
+/// assembly.
+#[test]
+#[serial] // This test must be run in serial as files may conflict.
+fn mun_build_emit() {
+    let project = tempfile::Builder::new()
+        .prefix("mun_project_example")
+        .tempdir()
+        .unwrap();
+
+    set_current_dir(&project).unwrap();
+
+    let args: Vec<OsString> = vec!["mun".into(), "init".into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    let args: Vec<OsString> = vec![
+        "mun".into(),
+        "build".into(),
+        "--manifest-path".into(),
+        project.as_ref().join("mun.toml").into(),
+        "--emit".into(),
+        "llvm-ir,llvm-bc,asm,obj".into(),
+    ];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    for extension in &["munlib", "ll", "bc", "s", "o"] {
+        let path = project
+            .as_ref()
+            .join("target/main")
+            .with_extension(extension);
+        assert!(path.is_file(), "{} was not written", path.display());
+    }
+    let ir = std::fs::read_to_string(project.as_ref().join("target/main.ll")).unwrap();
+    assert!(ir.contains("define"));
+}
+
+/// Creates a new project and tests that `--opt-level` reaches the LLVM pass pipelines: without
+/// optimizations local variables remain on the stack, whereas `-O3` promotes them to registers.
+#[test]
+#[serial] // This test must be run in serial as files may conflict.
+fn mun_build_opt_level() {
+    let project = tempfile::Builder::new()
+        .prefix("mun_project_example")
+        .tempdir()
+        .unwrap();
+
+    set_current_dir(&project).unwrap();
+
+    let args: Vec<OsString> = vec!["mun".into(), "init".into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+    std::fs::write(
+        project.as_ref().join("src/main.mun"),
+        "pub fn main() -> i32 { let a = 5; a }\n",
+    )
+    .unwrap();
+
+    let build = |opt_level: &str| {
+        let args: Vec<OsString> = vec![
+            "mun".into(),
+            "build".into(),
+            "--manifest-path".into(),
+            project.as_ref().join("mun.toml").into(),
+            "--opt-level".into(),
+            opt_level.into(),
+            "--emit".into(),
+            "llvm-ir".into(),
+        ];
+        assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+        let target_dir = project.as_ref().join("target");
+        let ir = std::fs::read_to_string(target_dir.join("main.ll")).unwrap();
+        std::fs::remove_dir_all(target_dir).unwrap();
+        ir
+    };
+    assert!(build("0").contains("alloca"));
+    assert!(!build("3").contains("alloca"));
+}
+
+/// Creates a new project and tests that building it twice produces identical assemblies.
+#[test]
+#[serial] // This test must be run in serial as files may conflict.
+fn mun_build_reproducible() {
+    let project = tempfile::Builder::new()
+        .prefix("mun_project_example")
+        .tempdir()
+        .unwrap();
+
+    set_current_dir(&project).unwrap();
+
+    let args: Vec<OsString> = vec!["mun".into(), "init".into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+    std::fs::write(
+        project.as_ref().join("src/main.mun"),
+        r#"
+        pub struct Foo { a: f32, b: i32 }
+        struct(value) Bar(f64, bool);
+
+        fn private(a: i32) -> i32 { a * 2 }
+        pub fn foo(a: f32) -> Foo { Foo { a, b: private(3) } }
+        pub fn bar(a: f64) -> Bar { Bar(a, true) }
+        pub fn baz(foo: Foo, bar: Bar) -> f64 { bar.0 + foo.a as f64 }
+        pub fn main() -> f64 { baz(foo(1.0), bar(2.0)) }
+        "#,
+    )
+    .unwrap();
+
+    let build = || {
+        let args: Vec<OsString> = vec![
+            "mun".into(),
+            "build".into(),
+            "--manifest-path".into(),
+            project.as_ref().join("mun.toml").into(),
+        ];
+        assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+        let target_dir = project.as_ref().join("target");
+        let assembly = std::fs::read(target_dir.join("main.munlib")).unwrap();
+        std::fs::remove_dir_all(target_dir).unwrap();
+        assembly
+    };
+    assert!(
+        build() == build(),
+        "building twice produced different assemblies"
+    );
+}
+
+/// Creates a new project and tests that `--output-kind static` writes an object file and a C
+/// header instead of a munlib.
+#[test]
+#[serial] // This test must be run in serial as files may conflict.
+fn mun_build_static() {
+    let project = tempfile::Builder::new()
+        .prefix("mun_project_example")
+        .tempdir()
+        .unwrap();
+
+    set_current_dir(&project).unwrap();
+
+    let args: Vec<OsString> = vec!["mun".into(), "init".into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    let args: Vec<OsString> = vec![
+        "mun".into(),
+        "build".into(),
+        "--manifest-path".into(),
+        project.as_ref().join("mun.toml").into(),
+        "--output-kind".into(),
+        "static".into(),
+    ];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    assert!(project.as_ref().join("target/main.o").is_file());
+    assert!(!project.as_ref().join("target/main.munlib").exists());
+    let header = std::fs::read_to_string(project.as_ref().join("target/main.h")).unwrap();
+    assert!(header.contains("MunAssemblyInfo get_info(void);"));
+    assert!(header.contains("//   - main"));
+}
+
+/// Creates a new project and tests that `--target` compiles it for another target than the host.
+#[test]
+#[serial] // This test must be run in serial as files may conflict.
+fn mun_build_target() {
+    let project = tempfile::Builder::new()
+        .prefix("mun_project_example")
+        .tempdir()
+        .unwrap();
+
+    set_current_dir(&project).unwrap();
+
+    let args: Vec<OsString> = vec!["mun".into(), "init".into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    let build_args = |target: &str| -> Vec<OsString> {
+        vec![
+            "mun".into(),
+            "build".into(),
+            "--manifest-path".into(),
+            project.as_ref().join("mun.toml").into(),
+            "--output-kind".into(),
+            "static".into(),
+            "--target".into(),
+            target.into(),
+        ]
+    };
+    assert_eq!(
+        run_with_args(build_args("aarch64-linux-android")).unwrap(),
+        mun::ExitStatus::Success
+    );
+
+    // An ELF object file for the AArch64 architecture
+    let object = std::fs::read(project.as_ref().join("target/main.o")).unwrap();
+    assert_eq!(&object[..4], b"\x7fELF");
+    assert_eq!(u16::from_le_bytes([object[18], object[19]]), 183);
+
+    let error = run_with_args(build_args("x86_64-unknown-switch")).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("unknown target `x86_64-unknown-switch`"));
+}
+
+/// Creates a new project and tests that `mun bindings` generates a C++ header for its assembly.
+#[test]
+#[serial] // This test must be run in serial as files may conflict.
+fn mun_bindings() {
+    let project = tempfile::Builder::new()
+        .prefix("mun_project_example")
+        .tempdir()
+        .unwrap();
+
+    set_current_dir(&project).unwrap();
+
+    let args: Vec<OsString> = vec!["mun".into(), "init".into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+    std::fs::write(
+        project.as_ref().join("src/main.mun"),
+        r#"
+        pub struct Foo { a: f32, bar: Bar }
+        pub struct(value) Bar(f64, bool);
+
+        pub fn foo(a: f32) -> Foo { Foo { a, bar: Bar(2.0, true) } }
+        pub fn baz(foo: Foo, bar: Bar) -> f64 { bar.0 + foo.a as f64 }
+        "#,
+    )
+    .unwrap();
+
+    let args: Vec<OsString> = vec![
+        "mun".into(),
+        "build".into(),
+        "--manifest-path".into(),
+        project.as_ref().join("mun.toml").into(),
+    ];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    let args: Vec<OsString> = vec![
+        "mun".into(),
+        "bindings".into(),
+        project.as_ref().join("target/main.munlib").into(),
+    ];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    let header = std::fs::read_to_string(project.as_ref().join("target/main.hpp")).unwrap();
+    assert!(header.contains("namespace main {"));
+    assert!(header.contains("class Foo {"));
+    assert!(header.contains("    float a() const;"));
+    assert!(header.contains("    void set_bar(const Bar &value);"));
+    assert!(header.contains("struct Bar {\n    double _0;\n    bool _1;\n};"));
+    assert!(header.contains("inline Foo foo(MunRuntimeHandle runtime, float arg0) {"));
+    assert!(header.contains(
+        "inline double baz(MunRuntimeHandle runtime, const Foo &arg0, const Bar &arg1) {"
+    ));
+
+    let args: Vec<OsString> = vec![
+        "mun".into(),
+        "bindings".into(),
+        "--language".into(),
+        "csharp".into(),
+        project.as_ref().join("target/main.munlib").into(),
+    ];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    let source = std::fs::read_to_string(project.as_ref().join("target/main.cs")).unwrap();
+    assert!(source.contains("namespace Mun.Main"));
+    assert!(source.contains("    public sealed class Foo : IDisposable"));
+    assert!(source.contains("        public float A\n"));
+    assert!(source.contains("        public Bar Bar\n"));
+    assert!(source.contains(
+        "        public double Item0;\n        [MarshalAs(UnmanagedType.U1)]\n        public bool Item1;"
+    ));
+    assert!(source.contains("        public static Foo Foo(IntPtr runtime, float arg0)"));
+    assert!(source.contains("        private delegate double BazFn(IntPtr arg0, Bar arg1);"));
+}
+
+/// Creates a workspace of which a member depends on another package, and tests that building the
+/// workspace compiles both packages.
+#[test]
+#[serial] // This test must be run in serial as files may conflict.
+fn mun_build_workspace() {
+    let workspace = tempfile::Builder::new()
+        .prefix("mun_workspace")
+        .tempdir()
+        .unwrap();
+    let root = workspace.as_ref();
+
+    std::fs::write(root.join("mun.toml"), "[workspace]\nmembers = [\"game\"]\n").unwrap();
+    std::fs::create_dir_all(root.join("game/src")).unwrap();
+    std::fs::write(
+        root.join("game/mun.toml"),
+        r#"[package]
+name = "game"
+version = "0.1.0"
+
+[dependencies]
+physics = { path = "../physics" }
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("game/src/main.mun"),
+        "extern fn gravity() -> f64;\n\npub fn main() -> f64 { gravity() }\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("physics/scripts")).unwrap();
+    std::fs::write(
+        root.join("physics/mun.toml"),
+        r#"[package]
+name = "physics"
+version = "0.1.0"
+source-dir = "scripts"
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("physics/scripts/physics.mun"),
+        "pub fn gravity() -> f64 { 9.81 }\n",
+    )
+    .unwrap();
+
+    let args: Vec<OsString> = vec![
+        "mun".into(),
+        "build".into(),
+        "--manifest-path".into(),
+        root.join("mun.toml").into(),
+    ];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    let runtime = RuntimeBuilder::new(root.join("game/target/main.munlib"))
+        .add_library(root.join("physics/target/physics.munlib"))
+        .spawn()
+        .unwrap();
+    let runtime_ref = runtime.borrow();
+    let result: f64 = invoke_fn!(runtime_ref, "main").unwrap();
+    assert_eq!(result, 9.81);
+}
+
 /// Builds and runs an newly generated mun project
+/// Builds a package that depends on the compiled assembly of another package, and tests that the
+/// runtime loads the dependency together with the assembly that depends on it.
+#[test]
+#[serial] // This test must be run in serial as files may conflict.
+fn mun_build_assembly_dependency() {
+    let workspace = tempfile::Builder::new()
+        .prefix("mun_assembly_dependency")
+        .tempdir()
+        .unwrap();
+    let root = workspace.as_ref();
+
+    std::fs::create_dir_all(root.join("physics/src")).unwrap();
+    std::fs::write(
+        root.join("physics/mun.toml"),
+        "[package]\nname = \"physics\"\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("physics/src/physics.mun"),
+        "pub fn gravity() -> f64 { -9.81 }\n\npub fn scale(value: f64, factor: f64) -> f64 { value * factor }\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("game/src")).unwrap();
+    std::fs::write(
+        root.join("game/mun.toml"),
+        r#"[package]
+name = "game"
+version = "0.1.0"
+
+[dependencies]
+physics = { assembly = "../physics/target/physics.munlib" }
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("game/src/main.mun"),
+        "pub fn main() -> f64 { physics::scale(physics::gravity(), 2.0) }\n",
+    )
+    .unwrap();
+
+    for package in ["physics", "game"].iter() {
+        let args: Vec<OsString> = vec![
+            "mun".into(),
+            "build".into(),
+            "--manifest-path".into(),
+            root.join(package).join("mun.toml").into(),
+        ];
+        assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+    }
+    assert!(!root.join("game/target/physics.munlib").exists());
+
+    let runtime = RuntimeBuilder::new(root.join("game/target/main.munlib"))
+        .spawn()
+        .unwrap();
+    let runtime_ref = runtime.borrow();
+    let result: f64 = invoke_fn!(runtime_ref, "main").unwrap();
+    assert_eq!(result, -19.62);
+}
+
+/// Creates a package with multiple files that define functions with the same name, and tests that
+/// building it with `--single-assembly` produces a single assembly named after the package.
+#[test]
+#[serial] // This test must be run in serial as files may conflict.
+fn mun_build_single_assembly() {
+    let package = tempfile::Builder::new()
+        .prefix("mun_single_assembly")
+        .tempdir()
+        .unwrap();
+    let root = package.as_ref();
+
+    std::fs::write(
+        root.join("mun.toml"),
+        "[package]\nname = \"game\"\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("src/main.mun"),
+        "pub fn main() -> i32 { value() + physics::gravity() }\n\nfn value() -> i32 { 1 }\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("src/physics.mun"),
+        "pub fn gravity() -> i32 { value() }\n\nfn value() -> i32 { 9 }\n",
+    )
+    .unwrap();
+
+    let args: Vec<OsString> = vec![
+        "mun".into(),
+        "build".into(),
+        "--single-assembly".into(),
+        "--manifest-path".into(),
+        root.join("mun.toml").into(),
+    ];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+    assert!(!root.join("target/main.munlib").exists());
+
+    let runtime = RuntimeBuilder::new(root.join("target/game.munlib"))
+        .spawn()
+        .unwrap();
+    let runtime_ref = runtime.borrow();
+    let result: i32 = invoke_fn!(runtime_ref, "main").unwrap();
+    assert_eq!(result, 10);
+}
+
+/// Tests that the assemblies of a package are written to the `target-dir` of its manifest, or to
+/// the directory specified with `--out-dir` under the name specified with `--name`.
+#[test]
+#[serial] // This test must be run in serial as files may conflict.
+fn mun_build_out_dir() {
+    let package = tempfile::Builder::new()
+        .prefix("mun_out_dir")
+        .tempdir()
+        .unwrap();
+    let root = package.as_ref();
+
+    std::fs::write(
+        root.join("mun.toml"),
+        "[package]\nname = \"game\"\nversion = \"0.1.0\"\n\n[build]\ntarget-dir = \"assets\"\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/main.mun"), "pub fn main() -> i32 { 5 }\n").unwrap();
+
+    let build_args = |extra_args: &[&str]| -> Vec<OsString> {
+        let mut args: Vec<OsString> = vec![
+            "mun".into(),
+            "build".into(),
+            "--manifest-path".into(),
+            root.join("mun.toml").into(),
+        ];
+        args.extend(extra_args.iter().map(OsString::from));
+        args
+    };
+    assert_eq!(
+        run_with_args(build_args(&[])).unwrap(),
+        mun::ExitStatus::Success
+    );
+    assert!(root.join("assets/main.munlib").is_file());
+    assert!(!root.join("target").exists());
+
+    let out_dir = root.join("game/scripts");
+    let out_dir_arg = out_dir.to_str().unwrap();
+    assert_eq!(
+        run_with_args(build_args(&["--out-dir", out_dir_arg, "--name", "logic"])).unwrap(),
+        mun::ExitStatus::Success
+    );
+
+    let runtime = RuntimeBuilder::new(out_dir.join("logic.munlib"))
+        .spawn()
+        .unwrap();
+    let runtime_ref = runtime.borrow();
+    let result: i32 = invoke_fn!(runtime_ref, "main").unwrap();
+    assert_eq!(result, 5);
+
+    assert!(run_with_args(build_args(&["--name", "../logic"])).is_err());
+}
+
+/// Creates a new project and tests that `mun check` reports errors without writing assemblies.
+#[test]
+#[serial] // This test must be run in serial as files may conflict.
+fn mun_check() {
+    let project = tempfile::Builder::new()
+        .prefix("mun_project_example")
+        .tempdir()
+        .unwrap();
+
+    set_current_dir(&project).unwrap();
+
+    let args: Vec<OsString> = vec!["mun".into(), "init".into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    let check_args = || -> Vec<OsString> {
+        vec![
+            "mun".into(),
+            "check".into(),
+            "--manifest-path".into(),
+            project.as_ref().join("mun.toml").into(),
+        ]
+    };
+    assert_eq!(
+        run_with_args(check_args()).unwrap(),
+        mun::ExitStatus::Success
+    );
+    assert!(!project.as_ref().join("target/main.munlib").exists());
+
+    std::fs::write(
+        project.as_ref().join("src/main.mun"),
+        "pub fn main() -> f64 { true }",
+    )
+    .unwrap();
+    assert_eq!(run_with_args(check_args()).unwrap(), mun::ExitStatus::Error);
+
+    let mut json_args = check_args();
+    json_args.extend(vec!["--message-format".into(), "json".into()]);
+    assert_eq!(run_with_args(json_args).unwrap(), mun::ExitStatus::Error);
+}
+
+/// Creates a new project and tests that `mun test` runs its test functions.
+#[test]
+#[serial] // This test must be run in serial as files may conflict.
+fn mun_test() {
+    let project = tempfile::Builder::new()
+        .prefix("mun_project_example")
+        .tempdir()
+        .unwrap();
+
+    set_current_dir(&project).unwrap();
+
+    let args: Vec<OsString> = vec!["mun".into(), "init".into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    let test_args = || -> Vec<OsString> {
+        vec![
+            "mun".into(),
+            "test".into(),
+            "--manifest-path".into(),
+            project.as_ref().join("mun.toml").into(),
+        ]
+    };
+
+    std::fs::write(
+        project.as_ref().join("src/main.mun"),
+        r#"
+pub fn add(a: i32, b: i32) -> i32 { a + b }
+
+#[test]
+fn adds() { core::assert(add(1, 2) == 3); }
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        run_with_args(test_args()).unwrap(),
+        mun::ExitStatus::Success
+    );
+
+    std::fs::write(
+        project.as_ref().join("src/main.mun"),
+        r#"
+pub fn add(a: i32, b: i32) -> i32 { a + b }
+
+#[test]
+fn adds() { core::assert(add(1, 2) == 4, "wrong sum"); }
+"#,
+    )
+    .unwrap();
+    assert_eq!(run_with_args(test_args()).unwrap(), mun::ExitStatus::Error);
+}
+
+/// Creates a new project and tests that `mun bench` measures its benchmark functions.
+#[test]
+#[serial] // This test must be run in serial as files may conflict.
+fn mun_bench() {
+    let project = tempfile::Builder::new()
+        .prefix("mun_project_example")
+        .tempdir()
+        .unwrap();
+
+    set_current_dir(&project).unwrap();
+
+    let args: Vec<OsString> = vec!["mun".into(), "init".into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    let bench_args = || -> Vec<OsString> {
+        vec![
+            "mun".into(),
+            "bench".into(),
+            "--manifest-path".into(),
+            project.as_ref().join("mun.toml").into(),
+        ]
+    };
+
+    std::fs::write(
+        project.as_ref().join("src/main.mun"),
+        r#"
+pub fn add(a: i32, b: i32) -> i32 { a + b }
+
+#[bench]
+fn adds() { add(1, 2); }
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        run_with_args(bench_args()).unwrap(),
+        mun::ExitStatus::Success
+    );
+
+    std::fs::write(
+        project.as_ref().join("src/main.mun"),
+        r#"
+pub fn add(a: i32, b: i32) -> i32 { a + b }
+
+#[bench]
+fn adds() { core::assert(add(1, 2) == 4, "wrong sum"); }
+"#,
+    )
+    .unwrap();
+    assert_eq!(run_with_args(bench_args()).unwrap(), mun::ExitStatus::Error);
+}
+
+/// Creates a new project and tests that `mun fmt` formats its source files.
+#[test]
+#[serial] // This test must be run in serial as files may conflict.
+fn mun_fmt() {
+    let project = tempfile::Builder::new()
+        .prefix("mun_project_example")
+        .tempdir()
+        .unwrap();
+
+    set_current_dir(&project).unwrap();
+
+    let args: Vec<OsString> = vec!["mun".into(), "init".into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    let fmt_args = |check: bool| -> Vec<OsString> {
+        let mut args: Vec<OsString> = vec![
+            "mun".into(),
+            "fmt".into(),
+            "--manifest-path".into(),
+            project.as_ref().join("mun.toml").into(),
+        ];
+        if check {
+            args.push("--check".into());
+        }
+        args
+    };
+
+    let source_path = project.as_ref().join("src/main.mun");
+    std::fs::write(&source_path, "pub fn add(a:i32,b:i32)->i32{a+b}").unwrap();
+    assert_eq!(
+        run_with_args(fmt_args(true)).unwrap(),
+        mun::ExitStatus::Error
+    );
+    assert_eq!(
+        run_with_args(fmt_args(false)).unwrap(),
+        mun::ExitStatus::Success
+    );
+    assert_eq!(
+        std::fs::read_to_string(&source_path).unwrap(),
+        "pub fn add(a: i32, b: i32) -> i32 { a + b }\n"
+    );
+    assert_eq!(
+        run_with_args(fmt_args(true)).unwrap(),
+        mun::ExitStatus::Success
+    );
+}
+
+/// Tests that `mun explain` knows error codes and lints.
+#[test]
+fn mun_explain() {
+    let explain_args =
+        |code: &str| -> Vec<OsString> { vec!["mun".into(), "explain".into(), code.into()] };
+    assert_eq!(
+        run_with_args(explain_args("E0002")).unwrap(),
+        mun::ExitStatus::Success
+    );
+    assert_eq!(
+        run_with_args(explain_args("unused_variables")).unwrap(),
+        mun::ExitStatus::Success
+    );
+    assert!(run_with_args(explain_args("E9999")).is_err());
+}
+
 fn build_and_run(project: impl AsRef<Path>) {
     let args: Vec<OsString> = vec![
         "mun".into(),
@@ -0,0 +1,102 @@
+//! Guards the stable API of the `mun` crate: every item that embedders rely on, as well as every
+//! type that the signatures of those items mention, must be reachable through `mun::` alone. This
+//! file deliberately never names `mun_compiler`, `mun_runtime`, `mun_abi` or `mun_memory`, so an
+//! item that is missing from the facade fails to compile.
+
+#![allow(unused_imports, dead_code)]
+
+use mun::compiler::{
+    check_manifest, compile_manifest, compile_manifest_assemblies,
+    diagnostics_json::{JsonDiagnostic, JsonEdit, JsonSpan, JsonSuggestion},
+    iter_source_files,
+    lint::{find_lint, Lint, LintLevel, LintLevels, LINTS},
+    CompilerOptions, Config, DisplayColor, Driver, EmitKind, FileId, JitAssembly, JitCode,
+    MessageFormat, OptimizationLevel, OutputKind, Package, PathOrInline, RebuildReason,
+    RelativePath, RelativePathBuf, Severity, Snippet, Target, Timings,
+};
+#[cfg(all(
+    any(target_arch = "x86_64", target_arch = "aarch64"),
+    not(target_os = "windows")
+))]
+use mun::runtime::Value;
+use mun::runtime::{
+    abi::{
+        FunctionSignature, ModuleInfo, Privacy, StructMemoryKind, TypeGroup,
+        TypeInfo as AbiTypeInfo,
+    },
+    gc::{GcPtr, GcRuntime, HasIndirectionPtr, RawGcPtr, Stats, TypeMemory, TypeTrace, WeakGcPtr},
+    AllocationEvent, Allocator, ArgumentReflection, Assembly, AssemblyInfo, ClosureConflict,
+    DispatchTable, FieldInfo, FunctionDefinition, FunctionDefinitionStorage, FunctionPrototype,
+    GarbageCollector, GcStats, Guid, Handle, InterruptHandle, IntoClosureDefinition,
+    IntoFunctionDefinition, InvokeErr0, InvokeErr1, InvokeErr2, InvokeErr3, InvokeErr4, InvokeErr5,
+    InvokeErr6, InvokeError, Marshal, MunStruct, Panic, PinnedStruct, RawStruct,
+    ReturnTypeReflection, RootedStruct, Runtime, RuntimeBuilder, RuntimeOptions, StaticAssembly,
+    StructInfo, StructRef, SystemAllocator, TypeInfo, UnsafeTypeInfo, ValueStruct, WeakStructRef,
+    DEFAULT_MAX_STACK_SIZE,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use mun::runtime::{ReloadChanges, StructMigration};
+use mun::{invoke_fn, static_assembly, value_struct};
+
+/// Spells out the types that the signatures of the runtime refer to. It is never called; it only
+/// has to compile.
+fn runtime_signatures(runtime: &mut Runtime) {
+    let _: Option<&FunctionDefinition> = runtime.get_function_definition("add");
+    let _: Vec<&Assembly> = runtime.assemblies().collect();
+    let _: Vec<&TypeInfo> = runtime.types().collect();
+    let _: &dyn GcRuntime<UnsafeTypeInfo> = runtime.gc();
+    let _: GcStats = runtime.gc_stats();
+    let _: InterruptHandle = runtime.interrupt_handle();
+    runtime.on_after_reload(|_: &Assembly, _: &ReloadChanges| {});
+
+    let result: Result<i32, InvokeErr2<i32, i32>> = invoke_fn!(runtime, "add", 1i32, 2i32);
+    if let Err(error) = &result {
+        let _: &InvokeError = error.error();
+        let _: Option<&Panic> = error.panic();
+    }
+}
+
+/// Spells out the types that the signatures of the compiler refer to and how an assembly that is
+/// compiled in memory is handed to the runtime. It is never called; it only has to compile.
+fn compiler_signatures(driver: &mut Driver, file_id: FileId, runtime: &mut Runtime) {
+    let jit: JitAssembly = driver.jit_assembly(file_id).unwrap();
+    let code: JitCode = jit.code;
+    let assembly = StaticAssembly {
+        get_info: jit.get_info,
+        get_version: jit.get_version,
+        set_allocator_handle: jit.set_allocator_handle,
+    };
+    runtime
+        .load_in_process(
+            &driver.assembly_output_path(file_id),
+            assembly,
+            Box::new(code),
+        )
+        .unwrap();
+}
+
+/// Compiles, loads and invokes a Mun function without leaving the `mun` crate.
+#[test]
+fn compile_load_and_invoke() {
+    let out_dir = tempfile::TempDir::new().unwrap();
+    let config = Config {
+        out_dir: Some(out_dir.path().to_path_buf()),
+        display_color: DisplayColor::Disable,
+        ..Config::default()
+    };
+    let input = PathOrInline::Inline {
+        rel_path: RelativePathBuf::from("main.mun"),
+        contents: "pub fn add(a: i32, b: i32) -> i32 { a + b }".to_owned(),
+    };
+    let (mut driver, file_id) = Driver::with_file(config, input).unwrap();
+    let mut compiler_errors: Vec<u8> = Vec::new();
+    assert!(!driver.emit_diagnostics(&mut compiler_errors).unwrap());
+    driver.write_assembly(file_id, true).unwrap();
+
+    let runtime = RuntimeBuilder::new(driver.assembly_output_path(file_id))
+        .spawn()
+        .unwrap();
+    let runtime = runtime.borrow();
+    let result: i32 = invoke_fn!(runtime, "add", 3i32, 4i32).unwrap();
+    assert_eq!(result, 7);
+}
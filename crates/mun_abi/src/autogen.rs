@@ -230,12 +230,14 @@ pub struct FunctionDefinition {
     pub prototype: FunctionPrototype,
     #[doc = " Function pointer"]
     pub fn_ptr: *const ::std::os::raw::c_void,
+    #[doc = " Documentation of the function, or null if the function is not documented"]
+    pub docs: *const ::std::os::raw::c_char,
 }
 #[test]
 fn bindgen_test_layout_FunctionDefinition() {
     assert_eq!(
         ::std::mem::size_of::<FunctionDefinition>(),
-        40usize,
+        48usize,
         concat!("Size of: ", stringify!(FunctionDefinition))
     );
     assert_eq!(
@@ -263,6 +265,16 @@ fn bindgen_test_layout_FunctionDefinition() {
             stringify!(fn_ptr)
         )
     );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<FunctionDefinition>())).docs as *const _ as usize },
+        40usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(FunctionDefinition),
+            "::",
+            stringify!(docs)
+        )
+    );
 }
 #[doc = " Represents a struct declaration."]
 #[doc = ""]
@@ -280,12 +292,15 @@ pub struct StructInfo {
     pub num_fields: u16,
     #[doc = " Struct memory kind"]
     pub memory_kind: StructMemoryKind,
+    #[doc = " Struct fields' previous names, or null if none of the fields was renamed. The name of a"]
+    #[doc = " field that was not renamed is null."]
+    pub field_renamed_from: *const *const ::std::os::raw::c_char,
 }
 #[test]
 fn bindgen_test_layout_StructInfo() {
     assert_eq!(
         ::std::mem::size_of::<StructInfo>(),
-        32usize,
+        40usize,
         concat!("Size of: ", stringify!(StructInfo))
     );
     assert_eq!(
@@ -343,6 +358,16 @@ fn bindgen_test_layout_StructInfo() {
             stringify!(memory_kind)
         )
     );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<StructInfo>())).field_renamed_from as *const _ as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(StructInfo),
+            "::",
+            stringify!(field_renamed_from)
+        )
+    );
 }
 #[doc = " Represents a module declaration."]
 #[doc = ""]
@@ -496,12 +521,27 @@ pub struct AssemblyInfo {
     pub dependencies: *const *const ::std::os::raw::c_char,
     #[doc = " Number of dependencies"]
     pub num_dependencies: u32,
+    #[doc = " Version of the compiler that generated the assembly"]
+    pub compiler_version: *const ::std::os::raw::c_char,
+    #[doc = " Hexadecimal MD5 hash of the source code that the assembly was compiled from"]
+    pub source_hash: *const ::std::os::raw::c_char,
+    #[doc = " Hexadecimal MD5 hash of the source hash, the compiler version and the compiler options."]
+    #[doc = " Two assemblies with the same fingerprint contain the same code."]
+    pub fingerprint: *const ::std::os::raw::c_char,
+    #[doc = " Test functions, which are marked with a `#[test]` attribute"]
+    pub tests: *const FunctionDefinition,
+    #[doc = " Number of test functions"]
+    pub num_tests: u32,
+    #[doc = " Benchmark functions, which are marked with a `#[bench]` attribute"]
+    pub benches: *const FunctionDefinition,
+    #[doc = " Number of benchmark functions"]
+    pub num_benches: u32,
 }
 #[test]
 fn bindgen_test_layout_AssemblyInfo() {
     assert_eq!(
         ::std::mem::size_of::<AssemblyInfo>(),
-        80usize,
+        136usize,
         concat!("Size of: ", stringify!(AssemblyInfo))
     );
     assert_eq!(
@@ -549,4 +589,74 @@ fn bindgen_test_layout_AssemblyInfo() {
             stringify!(num_dependencies)
         )
     );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<AssemblyInfo>())).compiler_version as *const _ as usize },
+        80usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(AssemblyInfo),
+            "::",
+            stringify!(compiler_version)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<AssemblyInfo>())).source_hash as *const _ as usize },
+        88usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(AssemblyInfo),
+            "::",
+            stringify!(source_hash)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<AssemblyInfo>())).fingerprint as *const _ as usize },
+        96usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(AssemblyInfo),
+            "::",
+            stringify!(fingerprint)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<AssemblyInfo>())).tests as *const _ as usize },
+        104usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(AssemblyInfo),
+            "::",
+            stringify!(tests)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<AssemblyInfo>())).num_tests as *const _ as usize },
+        112usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(AssemblyInfo),
+            "::",
+            stringify!(num_tests)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<AssemblyInfo>())).benches as *const _ as usize },
+        120usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(AssemblyInfo),
+            "::",
+            stringify!(benches)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<AssemblyInfo>())).num_benches as *const _ as usize },
+        128usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(AssemblyInfo),
+            "::",
+            stringify!(num_benches)
+        )
+    );
 }
@@ -152,10 +152,48 @@ impl fmt::Display for FunctionPrototype {
 unsafe impl Send for FunctionPrototype {}
 unsafe impl Sync for FunctionPrototype {}
 
+impl FunctionDefinition {
+    /// Returns the function's documentation, if any.
+    pub fn docs(&self) -> Option<&str> {
+        if self.docs.is_null() {
+            None
+        } else {
+            Some(unsafe { str::from_utf8_unchecked(CStr::from_ptr(self.docs).to_bytes()) })
+        }
+    }
+}
+
 unsafe impl Send for FunctionDefinition {}
 unsafe impl Sync for FunctionDefinition {}
 
+/// A field of a struct.
+#[derive(Clone, Copy, Debug)]
+pub struct FieldInfo<'a> {
+    /// The name of the field
+    pub name: &'a str,
+    /// The type of the field
+    pub type_info: &'a TypeInfo,
+    /// The offset of the field in bytes, relative to the start of the struct
+    pub offset: u16,
+    /// The previous name of the field, if it was renamed
+    pub renamed_from: Option<&'a str>,
+}
+
 impl StructInfo {
+    /// Returns the struct's fields, in the order in which they are declared.
+    pub fn fields(&self) -> impl Iterator<Item = FieldInfo<'_>> {
+        self.field_names()
+            .zip(self.field_types().iter())
+            .zip(self.field_offsets().iter())
+            .zip(self.field_renamed_from())
+            .map(|(((name, type_info), offset), renamed_from)| FieldInfo {
+                name,
+                type_info,
+                offset: *offset,
+                renamed_from,
+            })
+    }
+
     /// Returns the struct's field names.
     pub fn field_names(&self) -> impl Iterator<Item = &str> {
         let field_names = if self.num_fields == 0 {
@@ -169,6 +207,25 @@ impl StructInfo {
             .map(|n| unsafe { str::from_utf8_unchecked(CStr::from_ptr(*n).to_bytes()) })
     }
 
+    /// Returns the previous names of the struct's fields, or `None` for fields that were not
+    /// renamed.
+    pub fn field_renamed_from(&self) -> impl Iterator<Item = Option<&str>> {
+        let renamed_from = if self.field_renamed_from.is_null() {
+            None
+        } else {
+            Some(unsafe {
+                slice::from_raw_parts(self.field_renamed_from, self.num_fields as usize)
+            })
+        };
+
+        (0..self.num_fields as usize).map(move |idx| {
+            renamed_from
+                .map(|renamed_from| renamed_from[idx])
+                .filter(|name| !name.is_null())
+                .map(|name| unsafe { str::from_utf8_unchecked(CStr::from_ptr(name).to_bytes()) })
+        })
+    }
+
     /// Returns the struct's field types.
     pub fn field_types(&self) -> &[&TypeInfo] {
         if self.num_fields == 0 {
@@ -363,6 +420,42 @@ impl AssemblyInfo {
             .iter()
             .map(|d| unsafe { str::from_utf8_unchecked(CStr::from_ptr(*d).to_bytes()) })
     }
+
+    /// Returns the version of the compiler that generated the assembly.
+    pub fn compiler_version(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(CStr::from_ptr(self.compiler_version).to_bytes()) }
+    }
+
+    /// Returns the hexadecimal MD5 hash of the source code that the assembly was compiled from.
+    pub fn source_hash(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(CStr::from_ptr(self.source_hash).to_bytes()) }
+    }
+
+    /// Returns the fingerprint of the assembly, which only changes if the source code, the compiler
+    /// version or the compiler options change. Unlike a timestamp it identifies the contents of
+    /// the assembly, so it can be used to invalidate caches and to reproduce bug reports.
+    pub fn fingerprint(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(CStr::from_ptr(self.fingerprint).to_bytes()) }
+    }
+
+    /// Returns the test functions of the assembly, which are marked with a `#[test]` attribute.
+    pub fn tests(&self) -> &[FunctionDefinition] {
+        if self.num_tests == 0 {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(self.tests, self.num_tests as usize) }
+        }
+    }
+
+    /// Returns the benchmark functions of the assembly, which are marked with a `#[bench]`
+    /// attribute.
+    pub fn benches(&self) -> &[FunctionDefinition] {
+        if self.num_benches == 0 {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(self.benches, self.num_benches as usize) }
+        }
+    }
 }
 
 unsafe impl Send for AssemblyInfo {}
@@ -410,6 +503,7 @@ mod tests {
 
     const FAKE_TYPE_NAME: &str = "type-name";
     const FAKE_FIELD_NAME: &str = "field-name";
+    const FAKE_RENAMED_FROM: &str = "renamed-from";
 
     #[test]
     fn test_type_info_name() {
@@ -482,6 +576,7 @@ mod tests {
     }
 
     const FAKE_FN_NAME: &str = "fn-name";
+    const FAKE_FN_DOCS: &str = "fn-docs";
 
     #[test]
     fn test_fn_prototype_name() {
@@ -544,6 +639,7 @@ mod tests {
             field_offsets: field_offsets.as_ptr(),
             num_fields: field_names.len() as u16,
             memory_kind,
+            field_renamed_from: ptr::null(),
         }
     }
 
@@ -577,6 +673,38 @@ mod tests {
         }
         assert_eq!(struct_info.field_types(), field_types);
         assert_eq!(struct_info.field_offsets(), field_offsets);
+
+        let fields: Vec<_> = struct_info.fields().collect();
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].name, FAKE_FIELD_NAME);
+        assert_eq!(fields[0].type_info, &type_info);
+        assert_eq!(fields[0].offset, 1);
+        assert_eq!(fields[0].renamed_from, None);
+    }
+
+    #[test]
+    fn test_struct_info_field_renamed_from() {
+        let field_name = CString::new(FAKE_FIELD_NAME).expect("Invalid fake field name.");
+        let renamed_from = CString::new(FAKE_RENAMED_FROM).expect("Invalid fake field name.");
+        let type_name = CString::new(FAKE_TYPE_NAME).expect("Invalid fake type name.");
+        let type_info = fake_type_info(&type_name, TypeGroup::FundamentalTypes, 1, 1);
+
+        let field_names = &[field_name.as_ptr(), field_name.as_ptr()];
+        let field_types = &[&type_info, &type_info];
+        let field_offsets = &[0, 1];
+        let mut struct_info =
+            fake_struct_info(field_names, field_types, field_offsets, Default::default());
+        assert_eq!(
+            struct_info.field_renamed_from().collect::<Vec<_>>(),
+            vec![None, None]
+        );
+
+        let field_renamed_from = &[ptr::null(), renamed_from.as_ptr()];
+        struct_info.field_renamed_from = field_renamed_from.as_ptr();
+        assert_eq!(
+            struct_info.field_renamed_from().collect::<Vec<_>>(),
+            vec![None, Some(FAKE_RENAMED_FROM)]
+        );
     }
 
     #[test]
@@ -640,9 +768,11 @@ mod tests {
         let fn_name = CString::new(FAKE_FN_NAME).expect("Invalid fake fn name.");
         let fn_prototype = fake_fn_prototype(&fn_name, &[], return_type);
 
+        let fn_docs = CString::new(FAKE_FN_DOCS).expect("Invalid fake fn docs.");
         let fn_info = FunctionDefinition {
             prototype: fn_prototype,
             fn_ptr: ptr::null(),
+            docs: fn_docs.as_ptr(),
         };
         let functions = &[fn_info];
 
@@ -658,6 +788,7 @@ mod tests {
         assert_eq!(result_functions.len(), functions.len());
         for (lhs, rhs) in result_functions.iter().zip(functions.iter()) {
             assert_eq!(lhs.fn_ptr, rhs.fn_ptr);
+            assert_eq!(lhs.docs(), Some(FAKE_FN_DOCS));
             assert_eq!(lhs.prototype.name(), rhs.prototype.name());
             assert_eq!(
                 lhs.prototype.signature.arg_types(),
@@ -893,16 +1024,27 @@ mod tests {
         symbols: ModuleInfo,
         dispatch_table: DispatchTable,
         dependencies: &[*const c_char],
+        metadata: &CString,
+        tests: &[FunctionDefinition],
+        benches: &[FunctionDefinition],
     ) -> AssemblyInfo {
         AssemblyInfo {
             symbols,
             dispatch_table,
             dependencies: dependencies.as_ptr(),
             num_dependencies: dependencies.len() as u32,
+            compiler_version: metadata.as_ptr(),
+            source_hash: metadata.as_ptr(),
+            fingerprint: metadata.as_ptr(),
+            tests: tests.as_ptr(),
+            num_tests: tests.len() as u32,
+            benches: benches.as_ptr(),
+            num_benches: benches.len() as u32,
         }
     }
 
     const FAKE_DEPENDENCY: &str = "path/to/dependency.dylib";
+    const FAKE_METADATA: &str = "0123456789abcdef";
 
     #[test]
     fn test_assembly_info_dependencies() {
@@ -913,11 +1055,52 @@ mod tests {
 
         let dependency = CString::new(FAKE_DEPENDENCY).expect("Invalid fake dependency.");
         let dependencies = &[dependency.as_ptr()];
-        let assembly = fake_assembly_info(module, dispatch_table, dependencies);
+        let metadata = CString::new(FAKE_METADATA).expect("Invalid fake metadata.");
+        let assembly =
+            fake_assembly_info(module, dispatch_table, dependencies, &metadata, &[], &[]);
 
         assert_eq!(assembly.dependencies().count(), dependencies.len());
         for (lhs, rhs) in assembly.dependencies().zip([FAKE_DEPENDENCY].iter()) {
             assert_eq!(lhs, *rhs)
         }
     }
+
+    #[test]
+    fn test_assembly_info_metadata() {
+        let module_path = CString::new(FAKE_MODULE_PATH).expect("Invalid fake module path.");
+        let module = fake_module_info(&module_path, &[], &[]);
+
+        let dispatch_table = fake_dispatch_table(&[], &mut []);
+
+        let metadata = CString::new(FAKE_METADATA).expect("Invalid fake metadata.");
+        let assembly = fake_assembly_info(module, dispatch_table, &[], &metadata, &[], &[]);
+
+        assert_eq!(assembly.compiler_version(), FAKE_METADATA);
+        assert_eq!(assembly.source_hash(), FAKE_METADATA);
+        assert_eq!(assembly.fingerprint(), FAKE_METADATA);
+        assert!(assembly.tests().is_empty());
+        assert!(assembly.benches().is_empty());
+    }
+
+    #[test]
+    fn test_assembly_info_tests_and_benches() {
+        let module_path = CString::new(FAKE_MODULE_PATH).expect("Invalid fake module path.");
+        let module = fake_module_info(&module_path, &[], &[]);
+
+        let dispatch_table = fake_dispatch_table(&[], &mut []);
+
+        let fn_name = CString::new(FAKE_FN_NAME).expect("Invalid fake fn name.");
+        let test = FunctionDefinition {
+            prototype: fake_fn_prototype(&fn_name, &[], None),
+            fn_ptr: ptr::null(),
+            docs: ptr::null(),
+        };
+        let tests = &[test];
+        let metadata = CString::new(FAKE_METADATA).expect("Invalid fake metadata.");
+        let assembly = fake_assembly_info(module, dispatch_table, &[], &metadata, tests, tests);
+
+        assert_eq!(assembly.tests().len(), tests.len());
+        assert_eq!(assembly.tests()[0].prototype.name(), FAKE_FN_NAME);
+        assert_eq!(assembly.benches().len(), tests.len());
+    }
 }
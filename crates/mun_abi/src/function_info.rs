@@ -1,12 +1,90 @@
 use crate::{
     FunctionDefinition, FunctionPrototype, FunctionSignature, HasStaticTypeInfo, TypeInfo,
 };
-use std::{ffi::CString, ptr};
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use std::{
+    any::{Any, TypeId},
+    collections::{hash_map::Entry, HashMap},
+    ffi::CString,
+    fmt, ptr,
+    sync::Arc,
+};
+
+type Closure = Arc<dyn Any + Send + Sync>;
+
+/// The closures that were converted into a `FunctionDefinition`, indexed by their type. As every
+/// closure has a unique type, the monomorphized trampoline of a closure uses its type to find it.
+static CLOSURES: Lazy<RwLock<HashMap<TypeId, Closure>>> = Lazy::new(Default::default);
+
+/// The error that is returned when a closure is converted into a `FunctionDefinition` while a
+/// closure of the same type is still registered, e.g. because the same closure expression was
+/// converted twice. The trampoline of a closure can only find one closure per type.
+#[derive(Debug)]
+pub struct ClosureConflict {
+    name: String,
+}
+
+impl fmt::Display for ClosureConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot bind `{}`: a closure of the same type is already bound to a function",
+            self.name
+        )
+    }
+}
+
+impl std::error::Error for ClosureConflict {}
+
+/// Registers `closure` so it can be invoked through its trampoline. The closure is unregistered as
+/// soon as the returned `ClosureRegistration` is dropped. Fails if a closure of the same type is
+/// already registered.
+fn register_closure<F: Any + Send + Sync>(
+    name: &str,
+    closure: F,
+) -> Result<ClosureRegistration, ClosureConflict> {
+    let type_id = TypeId::of::<F>();
+    match CLOSURES.write().entry(type_id) {
+        Entry::Occupied(_) => Err(ClosureConflict {
+            name: name.to_owned(),
+        }),
+        Entry::Vacant(entry) => {
+            entry.insert(Arc::new(closure));
+            Ok(ClosureRegistration { type_id })
+        }
+    }
+}
+
+/// Returns the registered closure of type `F`.
+///
+/// # Panics
+///
+/// Panics if no closure of type `F` is registered.
+fn registered_closure<F: Any + Send + Sync>() -> Closure {
+    CLOSURES
+        .read()
+        .get(&TypeId::of::<F>())
+        .cloned()
+        .expect("the closure was invoked after its function definition was dropped")
+}
+
+/// Unregisters a closure when dropped.
+struct ClosureRegistration {
+    type_id: TypeId,
+}
+
+impl Drop for ClosureRegistration {
+    fn drop(&mut self) {
+        CLOSURES.write().remove(&self.type_id);
+    }
+}
 
 /// Owned storage for C-style `FunctionDefinition`.
 pub struct FunctionDefinitionStorage {
     _name: CString,
     _type_infos: Vec<&'static TypeInfo>,
+    _closure: Option<ClosureRegistration>,
 }
 
 impl FunctionDefinitionStorage {
@@ -38,15 +116,33 @@ impl FunctionDefinitionStorage {
                 },
             },
             fn_ptr,
+            docs: ptr::null(),
         };
 
         let fn_storage = FunctionDefinitionStorage {
             _name: name,
             _type_infos: type_infos,
+            _closure: None,
         };
 
         (fn_info, fn_storage)
     }
+
+    /// Constructs a new `FunctionDefinition` for `closure`, which is invoked through
+    /// `trampoline`. The closure stays registered for as long as the returned
+    /// `FunctionDefinitionStorage` is alive.
+    fn new_closure<F: Any + Send + Sync>(
+        name: &str,
+        args: &[&'static TypeInfo],
+        ret: Option<&'static TypeInfo>,
+        trampoline: *const std::ffi::c_void,
+        closure: F,
+    ) -> Result<(FunctionDefinition, FunctionDefinitionStorage), ClosureConflict> {
+        let registration = register_closure(name, closure)?;
+        let (fn_info, mut fn_storage) = Self::new_function(name, args, ret, trampoline);
+        fn_storage._closure = Some(registration);
+        Ok((fn_info, fn_storage))
+    }
 }
 
 /// A value-to-`FunctionDefinition` conversion that consumes the input value.
@@ -63,7 +159,10 @@ macro_rules! into_function_info_impl {
             impl<$R: HasStaticTypeInfo, $($T: HasStaticTypeInfo,)*> IntoFunctionDefinition
             for extern "C" fn($($T),*) -> $R
             {
-                fn into<S: AsRef<str>>(self, name: S) -> (FunctionDefinition, FunctionDefinitionStorage) {
+                fn into<S: AsRef<str>>(
+                    self,
+                    name: S,
+                ) -> Result<(FunctionDefinition, FunctionDefinitionStorage), ClosureConflict> {
                     FunctionDefinitionStorage::new_function(
                         name.as_ref(),
                         &[$($T::type_info(),)*],
@@ -76,7 +175,42 @@ macro_rules! into_function_info_impl {
             impl<$($T: HasStaticTypeInfo,)*> IntoFunctionDefinition
             for extern "C" fn($($T),*)
             {
-                fn into<S: AsRef<str>>(self, name: S) -> (FunctionDefinition, FunctionDefinitionStorage) {
+                fn into<S: AsRef<str>>(
+                    self,
+                    name: S,
+                ) -> Result<(FunctionDefinition, FunctionDefinitionStorage), ClosureConflict> {
+                    FunctionDefinitionStorage::new_function(
+                        name.as_ref(),
+                        &[$($T::type_info(),)*],
+                        None,
+                        self as *const std::ffi::c_void,
+                    )
+                }
+            }
+
+            impl<$R: HasStaticTypeInfo, $($T: HasStaticTypeInfo,)*> IntoFunctionDefinition
+            for extern "C-unwind" fn($($T),*) -> $R
+            {
+                fn into<S: AsRef<str>>(
+                    self,
+                    name: S,
+                ) -> Result<(FunctionDefinition, FunctionDefinitionStorage), ClosureConflict> {
+                    FunctionDefinitionStorage::new_function(
+                        name.as_ref(),
+                        &[$($T::type_info(),)*],
+                        Some($R::type_info()),
+                        self as *const std::ffi::c_void,
+                    )
+                }
+            }
+
+            impl<$($T: HasStaticTypeInfo,)*> IntoFunctionDefinition
+            for extern "C-unwind" fn($($T),*)
+            {
+                fn into<S: AsRef<str>>(
+                    self,
+                    name: S,
+                ) -> Result<(FunctionDefinition, FunctionDefinitionStorage), ClosureConflict> {
                     FunctionDefinitionStorage::new_function(
                         name.as_ref(),
                         &[$($T::type_info(),)*],
@@ -102,3 +236,163 @@ into_function_info_impl! {
     extern "C" fn(A, B, C, D, E, F, G, H, I) -> R;
     extern "C" fn(A, B, C, D, E, F, G, H, I, J) -> R;
 }
+
+/// A closure-to-`FunctionDefinition` conversion that consumes the closure. The `Marker` type
+/// parameter distinguishes the signatures of closures and can always be inferred.
+///
+/// The resulting `FunctionDefinition` invokes a trampoline that calls the closure, which remains
+/// callable for as long as the `FunctionDefinitionStorage` is alive. As only one closure per
+/// closure type can be registered at a time, converting a second closure of the same type fails
+/// until the storage of the first one is dropped.
+pub trait IntoClosureDefinition<Marker> {
+    /// Performs the conversion.
+    fn into<S: AsRef<str>>(
+        self,
+        name: S,
+    ) -> Result<(FunctionDefinition, FunctionDefinitionStorage), ClosureConflict>;
+}
+
+macro_rules! into_closure_info_impl {
+    ($(
+        fn($($T:ident: $t:ident),*) -> $R:ident;
+    )+) => {
+        $(
+            impl<Func, $R: HasStaticTypeInfo, $($T: HasStaticTypeInfo,)*>
+                IntoClosureDefinition<fn($($T),*) -> $R> for Func
+            where
+                Func: Fn($($T),*) -> $R + Send + Sync + 'static,
+            {
+                fn into<S: AsRef<str>>(
+                    self,
+                    name: S,
+                ) -> Result<(FunctionDefinition, FunctionDefinitionStorage), ClosureConflict> {
+                    extern "C" fn trampoline<Func, $R, $($T,)*>($($t: $T),*) -> $R
+                    where
+                        Func: Fn($($T),*) -> $R + Send + Sync + 'static,
+                    {
+                        let closure = registered_closure::<Func>();
+                        (closure.downcast_ref::<Func>().unwrap())($($t),*)
+                    }
+
+                    FunctionDefinitionStorage::new_closure(
+                        name.as_ref(),
+                        &[$($T::type_info(),)*],
+                        Some($R::type_info()),
+                        trampoline::<Func, $R, $($T,)*> as *const std::ffi::c_void,
+                        self,
+                    )
+                }
+            }
+
+            impl<Func, $($T: HasStaticTypeInfo,)*> IntoClosureDefinition<fn($($T),*)> for Func
+            where
+                Func: Fn($($T),*) + Send + Sync + 'static,
+            {
+                fn into<S: AsRef<str>>(
+                    self,
+                    name: S,
+                ) -> Result<(FunctionDefinition, FunctionDefinitionStorage), ClosureConflict> {
+                    extern "C" fn trampoline<Func, $($T,)*>($($t: $T),*)
+                    where
+                        Func: Fn($($T),*) + Send + Sync + 'static,
+                    {
+                        let closure = registered_closure::<Func>();
+                        (closure.downcast_ref::<Func>().unwrap())($($t),*)
+                    }
+
+                    FunctionDefinitionStorage::new_closure(
+                        name.as_ref(),
+                        &[$($T::type_info(),)*],
+                        None,
+                        trampoline::<Func, $($T,)*> as *const std::ffi::c_void,
+                        self,
+                    )
+                }
+            }
+        )+
+    }
+}
+
+into_closure_info_impl! {
+    fn() -> R;
+    fn(A: a) -> R;
+    fn(A: a, B: b) -> R;
+    fn(A: a, B: b, C: c) -> R;
+    fn(A: a, B: b, C: c, D: d) -> R;
+    fn(A: a, B: b, C: c, D: d, E: e) -> R;
+    fn(A: a, B: b, C: c, D: d, E: e, F: f) -> R;
+    fn(A: a, B: b, C: c, D: d, E: e, F: f, G: g) -> R;
+    fn(A: a, B: b, C: c, D: d, E: e, F: f, G: g, H: h) -> R;
+    fn(A: a, B: b, C: c, D: d, E: e, F: f, G: g, H: h, I: i) -> R;
+    fn(A: a, B: b, C: c, D: d, E: e, F: f, G: g, H: h, I: i, J: j) -> R;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IntoClosureDefinition;
+    use crate::HasStaticTypeInfo;
+    use std::sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc,
+    };
+
+    #[test]
+    fn test_closure_definition() {
+        let offset = 5i64;
+        let (fn_def, _storage) =
+            IntoClosureDefinition::into(move |a: i64, b: i64| a + b + offset, "add").unwrap();
+
+        assert_eq!(fn_def.prototype.name(), "add");
+        assert_eq!(
+            fn_def.prototype.signature.arg_types(),
+            &[i64::type_info(), i64::type_info()]
+        );
+        assert_eq!(
+            fn_def.prototype.signature.return_type(),
+            Some(i64::type_info())
+        );
+
+        let add: extern "C" fn(i64, i64) -> i64 = unsafe { std::mem::transmute(fn_def.fn_ptr) };
+        assert_eq!(add(3, 4), 12);
+    }
+
+    #[test]
+    fn test_closure_definition_without_return_type() {
+        let sum = Arc::new(AtomicI64::new(0));
+        let (fn_def, _storage) = {
+            let sum = sum.clone();
+            IntoClosureDefinition::into(
+                move |value: i64| {
+                    sum.fetch_add(value, Ordering::SeqCst);
+                },
+                "log",
+            )
+            .unwrap()
+        };
+
+        assert_eq!(fn_def.prototype.signature.return_type(), None);
+
+        let log: extern "C" fn(i64) = unsafe { std::mem::transmute(fn_def.fn_ptr) };
+        log(3);
+        log(6);
+        assert_eq!(sum.load(Ordering::SeqCst), 9);
+    }
+
+    #[test]
+    fn test_closure_conflict() {
+        let offset_by = |offset: i64| move |value: i64| value + offset;
+
+        let (_fn_def, storage) = IntoClosureDefinition::into(offset_by(1), "first").unwrap();
+        let error = IntoClosureDefinition::into(offset_by(2), "second").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "cannot bind `second`: a closure of the same type is already bound to a function"
+        );
+
+        // The closure can be converted again once the first one was dropped
+        drop(storage);
+        let (fn_def, _storage) = IntoClosureDefinition::into(offset_by(3), "third").unwrap();
+        let third: extern "C" fn(i64) -> i64 = unsafe { std::mem::transmute(fn_def.fn_ptr) };
+        assert_eq!(third(1), 4);
+    }
+}
@@ -0,0 +1,95 @@
+//! The derivation of the GUIDs of types. A GUID only depends on the contents of a type, so a type
+//! has the same GUID in every assembly and for every version of the compiler that shares the same
+//! `ABI_VERSION`. The rules are:
+//!
+//! - The GUID of a fundamental type is the MD5 hash of its name, e.g. `core::i32`.
+//! - The GUID of a struct is the MD5 hash of `struct <name>{<field>: <guid>,...}`, where `<guid>`
+//!   is the hexadecimal GUID of the type of the field. The fields are listed in the order in which
+//!   they are declared.
+//! - A field that refers to a `gc` struct uses the GUID of `struct <name>` instead, because a `gc`
+//!   struct can refer to itself.
+//!
+//! The derivation is part of the ABI. Changing it requires a new `ABI_VERSION`, so the runtime
+//! never compares GUIDs that were derived differently.
+
+use crate::Guid;
+use std::fmt;
+
+impl Guid {
+    /// Returns the GUID of the fundamental type with the specified name.
+    pub fn from_name(name: &str) -> Guid {
+        Guid {
+            b: md5::compute(name.as_bytes()).0,
+        }
+    }
+
+    /// Returns the GUID of the struct with the specified name and fields, which are given as the
+    /// name of every field and the GUID of its type.
+    pub fn from_struct<'a>(name: &str, fields: impl IntoIterator<Item = (&'a str, Guid)>) -> Guid {
+        let fields: Vec<String> = fields
+            .into_iter()
+            .map(|(field_name, field_guid)| format!("{}: {}", field_name, field_guid))
+            .collect();
+        Guid::from_name(&format!("struct {}{{{}}}", name, fields.join(",")))
+    }
+
+    /// Returns the GUID that a field uses to refer to the `gc` struct with the specified name.
+    pub fn from_gc_struct_reference(name: &str) -> Guid {
+        Guid::from_name(&format!("struct {}", name))
+    }
+}
+
+impl fmt::Display for Guid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.b.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Guid;
+
+    // These GUIDs are persisted by hosts, so they must never change within an `ABI_VERSION`.
+
+    #[test]
+    fn fundamental_guid() {
+        assert_eq!(
+            Guid::from_name("core::i32").to_string(),
+            "17797a7419d63217d235954317885bfa"
+        );
+    }
+
+    #[test]
+    fn struct_guid() {
+        let f32_guid = Guid::from_name("core::f32");
+        assert_eq!(
+            Guid::from_struct("Vec2", vec![("x", f32_guid), ("y", f32_guid)]).to_string(),
+            "856053b1a1a5eb75dd4d410a32fea53c"
+        );
+    }
+
+    #[test]
+    fn recursive_struct_guid() {
+        let fields = vec![
+            ("value", Guid::from_name("core::i32")),
+            ("next", Guid::from_gc_struct_reference("Node")),
+        ];
+        assert_eq!(
+            Guid::from_struct("Node", fields).to_string(),
+            "bbce42072e74fc02900c020e85bd4d94"
+        );
+    }
+
+    #[test]
+    fn field_order_changes_guid() {
+        let i32_guid = Guid::from_name("core::i32");
+        let f32_guid = Guid::from_name("core::f32");
+        assert_ne!(
+            Guid::from_struct("Foo", vec![("a", i32_guid), ("b", f32_guid)]),
+            Guid::from_struct("Foo", vec![("b", f32_guid), ("a", i32_guid)])
+        );
+    }
+}
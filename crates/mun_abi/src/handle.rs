@@ -0,0 +1,6 @@
+/// An opaque value that is owned by the host, like the id of an entity or a pointer to a resource
+/// of a game engine. Mun code can store a handle and pass it back to the host, but it cannot
+/// interpret it; it can only compare handles for equality. Written as `Handle` in Mun.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Handle(pub usize);
@@ -8,11 +8,17 @@
 mod autogen;
 mod autogen_impl;
 mod function_info;
+mod guid;
+mod handle;
 mod static_type_map;
 mod type_info;
 
 pub use autogen::*;
-pub use function_info::{FunctionDefinitionStorage, IntoFunctionDefinition};
+pub use autogen_impl::FieldInfo;
+pub use function_info::{
+    ClosureConflict, FunctionDefinitionStorage, IntoClosureDefinition, IntoFunctionDefinition,
+};
+pub use handle::Handle;
 pub use type_info::HasStaticTypeInfo;
 
 /// The Mun ABI prelude
@@ -21,13 +27,14 @@ pub use type_info::HasStaticTypeInfo;
 pub mod prelude {
     pub use crate::autogen::*;
     pub use crate::{
-        HasStaticTypeInfo, IntoFunctionDefinition, Privacy, StructMemoryKind, TypeGroup,
+        HasStaticTypeInfo, IntoClosureDefinition, IntoFunctionDefinition, Privacy,
+        StructMemoryKind, TypeGroup,
     };
 }
 
 /// Defines the current abi version
 #[allow(clippy::zero_prefixed_literal)]
-pub const ABI_VERSION: u32 = 00_02_00;
+pub const ABI_VERSION: u32 = 00_03_00;
 /// Defines the name for the `get_info` function
 pub const GET_INFO_FN_NAME: &str = "get_info";
 /// Defines the name for the `get_version` function
@@ -1,4 +1,4 @@
-use crate::{static_type_map::StaticTypeMap, Guid, TypeGroup, TypeInfo};
+use crate::{static_type_map::StaticTypeMap, Guid, Handle, TypeGroup, TypeInfo};
 use once_cell::sync::OnceCell;
 use std::convert::TryInto;
 use std::ffi::{CStr, CString};
@@ -41,9 +41,7 @@ impl<T: HasStaticTypeInfoName + 'static> HasStaticTypeInfo for *const T {
         &map.call_once::<T, _>(|| {
             let name =
                 CString::new(format!("*const {}", T::type_name().to_str().unwrap())).unwrap();
-            let guid = Guid {
-                b: md5::compute(&name.as_bytes()).0,
-            };
+            let guid = Guid::from_name(name.to_str().unwrap());
             let name_ptr = name.as_ptr();
             (
                 name,
@@ -79,9 +77,7 @@ impl<T: HasStaticTypeInfoName + 'static> HasStaticTypeInfo for *mut T {
 
         &map.call_once::<T, _>(|| {
             let name = CString::new(format!("*mut {}", T::type_name().to_str().unwrap())).unwrap();
-            let guid = Guid {
-                b: md5::compute(&name.as_bytes()).0,
-            };
+            let guid = Guid::from_name(name.to_str().unwrap());
             let name_ptr = name.as_ptr();
             (
                 name,
@@ -116,7 +112,7 @@ macro_rules! impl_basic_type_info {
                             .get_or_init(|| CString::new(format!("core::{}", stringify!($ty))).unwrap());
 
                         TypeInfo {
-                            guid: Guid{ b: md5::compute(&type_info_name.as_bytes()).0 },
+                            guid: Guid::from_name(type_info_name.to_str().unwrap()),
                             name: type_info_name.as_ptr(),
                             group: TypeGroup::FundamentalTypes,
                             size_in_bits: (std::mem::size_of::<$ty>() * 8)
@@ -150,7 +146,9 @@ macro_rules! impl_has_type_info_name {
     }
 }
 
-impl_basic_type_info!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64, bool);
+impl_basic_type_info!(
+    i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64, bool, char, Handle
+);
 
 impl_has_type_info_name!(
     std::ffi::c_void => "core::void",
@@ -187,7 +185,7 @@ impl HasStaticTypeInfo for isize {
 
 #[cfg(test)]
 mod tests {
-    use super::HasStaticTypeInfoName;
+    use super::{HasStaticTypeInfo, HasStaticTypeInfoName};
 
     #[test]
     fn ptr_test() {
@@ -200,4 +198,12 @@ mod tests {
         let ty = <*const *const std::ffi::c_void>::type_name();
         assert_eq!(ty.to_str().unwrap(), "*const *const core::void");
     }
+
+    #[test]
+    fn handle_test() {
+        let type_info = crate::Handle::type_info();
+        assert_eq!(type_info.name(), "core::Handle");
+        assert_eq!(type_info.group, crate::TypeGroup::FundamentalTypes);
+        assert_eq!(type_info.size_in_bytes(), std::mem::size_of::<usize>());
+    }
 }
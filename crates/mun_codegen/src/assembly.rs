@@ -1,11 +1,15 @@
+use crate::code_gen::ObjectFile;
 use crate::{IrDatabase, ModuleBuilder};
-use std::path::Path;
+use inkwell::OptimizationLevel;
+use mun_target::spec;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tempfile::NamedTempFile;
+use tempfile::TempDir;
 
 #[derive(Debug)]
 pub struct Assembly {
-    file: NamedTempFile,
+    _dir: TempDir,
+    path: PathBuf,
 }
 
 impl PartialEq for Assembly {
@@ -21,7 +25,30 @@ impl Assembly {
 
     /// Returns the current location of the assembly
     pub fn path(&self) -> &Path {
-        self.file.path()
+        &self.path
+    }
+
+    /// Compiles the bitcode of the module of an assembly, see `IrDatabase::assembly_bitcode`, to
+    /// machine code for `target` and links it into a shared object named `file_name`. This does
+    /// not require a database, so multiple assemblies can be compiled in parallel.
+    pub fn from_bitcode(
+        target: &spec::Target,
+        optimization_lvl: OptimizationLevel,
+        file_name: &str,
+        bitcode: &[u8],
+    ) -> Result<Self, anyhow::Error> {
+        // The name of the shared object is embedded in it on some platforms. It is derived from
+        // the source file instead of being random, so that the same input always produces the
+        // same output.
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join(file_name);
+
+        let obj_file = tracing::info_span!("codegen", file = file_name)
+            .in_scope(|| ObjectFile::from_bitcode(target, optimization_lvl, bitcode))?;
+        tracing::info_span!("link", file = file_name)
+            .in_scope(|| obj_file.into_shared_object(&path))?;
+
+        Ok(Assembly { _dir: dir, path })
     }
 
     /// Copies the assembly to the specified location
@@ -30,17 +57,59 @@ impl Assembly {
     }
 }
 
-/// Create a new temporary file that contains the linked object
-pub fn assembly_query(db: &dyn IrDatabase, file_id: hir::FileId) -> Arc<Assembly> {
-    let file = NamedTempFile::new().expect("could not create temp file for shared object");
+/// Returns the files that are compiled into the same assembly as the specified file.
+pub(crate) fn assembly_files_query(
+    db: &dyn IrDatabase,
+    file_id: hir::FileId,
+) -> Arc<Vec<hir::FileId>> {
+    if !db.single_assembly() {
+        return Arc::new(vec![file_id]);
+    }
+    let dependencies = db.assembly_dependencies();
+    let mut files: Vec<_> = db
+        .source_root(db.file_source_root(file_id))
+        .files()
+        .filter(|file_id| !dependencies.contains_key(file_id))
+        .collect();
+    files.sort_by_key(|file_id| db.file_relative_path(*file_id));
+    Arc::new(files)
+}
 
-    let module_builder = ModuleBuilder::new(db, file_id).expect("could not create ModuleBuilder");
-    let obj_file = module_builder
-        .build()
-        .expect("unable to create object file");
-    obj_file
-        .into_shared_object(file.path())
-        .expect("could not link object file");
+/// Returns the file that identifies the assembly that the specified file is compiled into.
+pub(crate) fn assembly_group_query(db: &dyn IrDatabase, file_id: hir::FileId) -> hir::FileId {
+    db.assembly_files(file_id)[0]
+}
+
+/// Returns the name of the shared object of the assembly identified by the specified file.
+pub fn assembly_file_name(db: &dyn IrDatabase, file_id: hir::FileId) -> String {
+    let file_name = db
+        .file_relative_path(db.assembly_group(file_id))
+        .with_extension(Assembly::EXTENSION);
+    file_name.file_name().unwrap_or("assembly").to_owned()
+}
 
-    Arc::new(Assembly { file })
+/// Returns the bitcode of the module of the assembly that the specified file is compiled into,
+/// before it is optimized.
+pub(crate) fn assembly_bitcode_query(db: &dyn IrDatabase, file_id: hir::FileId) -> Arc<Vec<u8>> {
+    let file_id = db.assembly_group(file_id);
+    let relative_path = db.file_relative_path(file_id);
+    let bitcode = tracing::info_span!("ir", file = %relative_path).in_scope(|| {
+        ModuleBuilder::new(db, file_id)
+            .expect("could not create ModuleBuilder")
+            .build_bitcode()
+            .expect("unable to link module")
+    });
+    Arc::new(bitcode)
+}
+
+/// Create a new temporary file that contains the linked object
+pub fn assembly_query(db: &dyn IrDatabase, file_id: hir::FileId) -> Arc<Assembly> {
+    let assembly = Assembly::from_bitcode(
+        &db.target(),
+        db.optimization_lvl(),
+        &assembly_file_name(db, file_id),
+        &db.assembly_bitcode(file_id),
+    )
+    .expect("could not create assembly");
+    Arc::new(assembly)
 }
@@ -5,6 +5,9 @@ use crate::IrDatabase;
 use hir::FileId;
 use inkwell::targets::TargetData;
 use inkwell::{
+    context::Context,
+    execution_engine::ExecutionEngine,
+    memory_buffer::MemoryBuffer,
     module::Module,
     passes::{PassManager, PassManagerBuilder},
     targets::{CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetMachine},
@@ -14,13 +17,20 @@ use mun_target::spec;
 use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::io::{self, Write};
-use std::{path::Path, sync::Arc};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use tempfile::NamedTempFile;
 use thiserror::Error;
 
+mod header;
 mod linker;
 pub mod symbols;
 
+pub use header::generate_c_header;
+
 #[derive(Debug, Error)]
 enum CodeGenerationError {
     #[error("{0}")]
@@ -31,10 +41,18 @@ enum CodeGenerationError {
     UnknownTargetTriple(String),
     #[error("error creating target machine")]
     CouldNotCreateTargetMachine,
+    #[error("the data layout of target `{0}` is `{1}`, but LLVM uses `{2}`")]
+    InconsistentDataLayout(String, String, String),
     #[error("error creating object file")]
     CouldNotCreateObjectFile(io::Error),
     #[error("error generating machine code")]
     CodeGenerationError(String),
+    #[error("error writing {0} to '{1}': {2}")]
+    CouldNotEmit(EmitKind, PathBuf, String),
+    #[error("error creating JIT execution engine: {0}")]
+    CouldNotCreateExecutionEngine(String),
+    #[error("function `{0}` is missing from the JIT compiled assembly")]
+    MissingJitFunction(String),
 }
 
 impl From<LinkerError> for CodeGenerationError {
@@ -71,6 +89,25 @@ impl ObjectFile {
         })
     }
 
+    /// Constructs a new object file for `target` from the bitcode of a module that was written by
+    /// `ModuleBuilder::build_bitcode`, which is optimized first. The module is read into an LLVM
+    /// context of its own, so object files can be constructed on multiple threads at once.
+    pub fn from_bitcode(
+        target: &spec::Target,
+        optimization_lvl: OptimizationLevel,
+        bitcode: &[u8],
+    ) -> Result<Self, anyhow::Error> {
+        let context = Context::create();
+        let buffer = MemoryBuffer::create_from_memory_range_copy(bitcode, "assembly");
+        let module = Module::parse_bitcode_from_buffer(&buffer, &context)
+            .map_err(|e| CodeGenerationError::CodeGenerationError(e.to_string()))?;
+        tracing::info_span!("optimize").in_scope(|| optimize_module(&module, optimization_lvl));
+
+        let (_llvm_target, target_machine) = create_target_machine(target, optimization_lvl)?;
+        tracing::info_span!("emit")
+            .in_scope(|| ObjectFile::new(target, &target_machine, Arc::new(module)))
+    }
+
     /// Links the object file into a shared object.
     pub fn into_shared_object(self, output_path: &Path) -> Result<(), anyhow::Error> {
         // Construct a linker for the target
@@ -85,6 +122,125 @@ impl ObjectFile {
     }
 }
 
+/// An intermediate artifact of the code generation that can be written to disk next to an
+/// assembly, e.g. to inspect the code that is generated for a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EmitKind {
+    /// Textual LLVM IR
+    LlvmIr,
+    /// LLVM bitcode
+    LlvmBitcode,
+    /// Assembly code of the target
+    Assembly,
+    /// The object file that is linked into the assembly
+    Object,
+}
+
+impl EmitKind {
+    /// All kinds of artifacts that can be emitted.
+    pub const ALL: &'static [EmitKind] = &[
+        EmitKind::LlvmIr,
+        EmitKind::LlvmBitcode,
+        EmitKind::Assembly,
+        EmitKind::Object,
+    ];
+
+    /// Returns the name of the kind as used on the command line, e.g. `llvm-ir`.
+    pub fn name(self) -> &'static str {
+        match self {
+            EmitKind::LlvmIr => "llvm-ir",
+            EmitKind::LlvmBitcode => "llvm-bc",
+            EmitKind::Assembly => "asm",
+            EmitKind::Object => "obj",
+        }
+    }
+
+    /// Returns the kind with the specified command line name.
+    pub fn from_name(name: &str) -> Option<EmitKind> {
+        EmitKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.name() == name)
+    }
+
+    /// Returns the file extension of the artifact.
+    pub fn extension(self) -> &'static str {
+        match self {
+            EmitKind::LlvmIr => "ll",
+            EmitKind::LlvmBitcode => "bc",
+            EmitKind::Assembly => "s",
+            EmitKind::Object => "o",
+        }
+    }
+}
+
+impl fmt::Display for EmitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The kind of output that is produced for every source file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutputKind {
+    /// A shared library (munlib) that is loaded by the runtime and can be hot reloaded
+    SharedLibrary,
+    /// A relocatable object file and a C header that declares its entry points, which are
+    /// statically linked into an executable. Statically linked assemblies cannot be hot reloaded.
+    StaticLibrary,
+}
+
+impl Default for OutputKind {
+    fn default() -> Self {
+        OutputKind::SharedLibrary
+    }
+}
+
+impl OutputKind {
+    /// Returns the kind with the specified command line name, either `shared` or `static`.
+    pub fn from_name(name: &str) -> Option<OutputKind> {
+        match name {
+            "shared" => Some(OutputKind::SharedLibrary),
+            "static" => Some(OutputKind::StaticLibrary),
+            _ => None,
+        }
+    }
+}
+
+/// An assembly that is compiled into the memory of the current process by the LLVM just-in-time
+/// compiler, instead of being linked into a shared library on disk. The code of the assembly is
+/// freed once all handles to its `code` are dropped, so they must outlive any use of its functions.
+pub struct JitAssembly {
+    /// Returns the information of the assembly
+    pub get_info: extern "C" fn() -> abi::AssemblyInfo,
+    /// Returns the ABI version of the assembly
+    pub get_version: extern "C" fn() -> u32,
+    /// Sets the allocator that is used by the assembly
+    pub set_allocator_handle: extern "C" fn(*mut std::ffi::c_void),
+    /// Keeps the code of the assembly alive
+    pub code: JitCode,
+}
+
+/// A handle that keeps the code of a [`JitAssembly`] alive. Unlike the code itself, the handle can
+/// be sent to and dropped on any thread.
+#[derive(Clone)]
+pub struct JitCode(Arc<JitCodeInner>);
+
+/// The execution engine that contains the code of a [`JitAssembly`]. The fields are dropped in
+/// order of declaration, so the context outlives the engine and the module.
+struct JitCodeInner {
+    _execution_engine: ExecutionEngine,
+    _module: Module,
+    _context: Context,
+}
+
+// Safety: the engine and the module are read into an LLVM context of their own, which is not
+// shared with any other value, so the context is never accessed by two threads at once. They are
+// never accessed after the assembly has been compiled; they are only kept alive, and eventually
+// dropped together on whichever thread drops the last handle.
+unsafe impl Send for JitCodeInner {}
+unsafe impl Sync for JitCodeInner {}
+
 /// A struct that can be used to build an LLVM `Module`.
 pub struct ModuleBuilder<'a> {
     db: &'a dyn IrDatabase,
@@ -95,8 +251,9 @@ pub struct ModuleBuilder<'a> {
 }
 
 impl<'a> ModuleBuilder<'a> {
-    /// Constructs module for the given `hir::FileId` at the specified output file location.
+    /// Constructs module for the assembly that the given `hir::FileId` is compiled into.
     pub fn new(db: &'a dyn IrDatabase, file_id: FileId) -> Result<Self, anyhow::Error> {
+        let file_id = db.assembly_group(file_id);
         let target = db.target();
 
         // Construct a module for the assembly
@@ -105,26 +262,9 @@ impl<'a> ModuleBuilder<'a> {
                 .create_module(db.file_relative_path(file_id).as_str()),
         );
 
-        // Initialize the x86 target
-        Target::initialize_x86(&InitializationConfig::default());
-
-        // Retrieve the LLVM target using the specified target.
-        let llvm_target = Target::from_triple(&target.llvm_target)
-            .map_err(|e| CodeGenerationError::UnknownTargetTriple(e.to_string()))?;
+        let (llvm_target, target_machine) = create_target_machine(&target, db.optimization_lvl())?;
         assembly_module.set_target(&llvm_target);
 
-        // Construct target machine for machine code generation
-        let target_machine = llvm_target
-            .create_target_machine(
-                &target.llvm_target,
-                &target.options.cpu,
-                &target.options.features,
-                db.optimization_lvl(),
-                RelocMode::PIC,
-                CodeModel::Default,
-            )
-            .ok_or(CodeGenerationError::CouldNotCreateTargetMachine)?;
-
         Ok(Self {
             db,
             file_id,
@@ -136,6 +276,106 @@ impl<'a> ModuleBuilder<'a> {
 
     /// Constructs an object file.
     pub fn build(self) -> Result<ObjectFile, anyhow::Error> {
+        self.finalize_module()?;
+
+        // Debug print the IR
+        //println!("{}", assembly_module.print_to_string().to_string());
+
+        ObjectFile::new(
+            &self.db.target(),
+            &self.target_machine,
+            self.assembly_module,
+        )
+    }
+
+    /// Returns the bitcode of the module of the assembly, before it is optimized. Unlike the
+    /// module, which belongs to the LLVM context of the database, the bitcode can be sent to
+    /// another thread to construct an object file with `ObjectFile::from_bitcode`.
+    pub fn build_bitcode(self) -> Result<Vec<u8>, anyhow::Error> {
+        self.link_module()?;
+        Ok(self
+            .assembly_module
+            .write_bitcode_to_memory()
+            .as_slice()
+            .to_vec())
+    }
+
+    /// Compiles the assembly into the memory of the current process, which skips writing it to
+    /// disk and loading it as a shared library. The target of the database must be the host. The
+    /// module is read into an LLVM context of its own, so the code can be freed on any thread.
+    pub fn build_jit(self) -> Result<JitAssembly, anyhow::Error> {
+        self.finalize_module()?;
+
+        let bitcode = self.assembly_module.write_bitcode_to_memory();
+        let context = Context::create();
+        let module = Module::parse_bitcode_from_buffer(&bitcode, &context)
+            .map_err(|e| CodeGenerationError::CodeGenerationError(e.to_string()))?;
+
+        Target::initialize_native(&InitializationConfig::default())
+            .map_err(CodeGenerationError::CouldNotCreateExecutionEngine)?;
+        let execution_engine = module
+            .create_jit_execution_engine(self.db.optimization_lvl())
+            .map_err(|e| CodeGenerationError::CouldNotCreateExecutionEngine(e.to_string()))?;
+
+        let function_address = |name: &str| {
+            execution_engine
+                .get_function_address(name)
+                .map_err(|_| CodeGenerationError::MissingJitFunction(name.to_string()))
+        };
+        let get_info = function_address(abi::GET_INFO_FN_NAME)?;
+        let get_version = function_address(abi::GET_VERSION_FN_NAME)?;
+        let set_allocator_handle = function_address(abi::SET_ALLOCATOR_HANDLE_FN_NAME)?;
+
+        // Safety: the functions are generated by `symbols::gen_reflection_ir` with exactly these
+        // signatures and stay valid for as long as the execution engine is alive.
+        Ok(JitAssembly {
+            get_info: unsafe { std::mem::transmute(get_info) },
+            get_version: unsafe { std::mem::transmute(get_version) },
+            set_allocator_handle: unsafe { std::mem::transmute(set_allocator_handle) },
+            code: JitCode(Arc::new(JitCodeInner {
+                _execution_engine: execution_engine,
+                _module: module,
+                _context: context,
+            })),
+        })
+    }
+
+    /// Writes the optimized module of the assembly to `path` in the format of `kind`.
+    pub fn emit(self, kind: EmitKind, path: &Path) -> Result<(), anyhow::Error> {
+        self.finalize_module()?;
+
+        let module = &self.assembly_module;
+        let result = match kind {
+            EmitKind::LlvmIr => module.print_to_file(path).map_err(|e| e.to_string()),
+            EmitKind::LlvmBitcode => {
+                if module.write_bitcode_to_path(path) {
+                    Ok(())
+                } else {
+                    Err("could not write bitcode".to_string())
+                }
+            }
+            EmitKind::Assembly => self
+                .target_machine
+                .write_to_file(module, FileType::Assembly, path)
+                .map_err(|e| e.to_string()),
+            EmitKind::Object => self
+                .target_machine
+                .write_to_file(module, FileType::Object, path)
+                .map_err(|e| e.to_string()),
+        };
+        result.map_err(|e| CodeGenerationError::CouldNotEmit(kind, path.to_path_buf(), e).into())
+    }
+
+    /// Links the IR of the files into the assembly module, adds the reflection information and
+    /// optimizes the result.
+    fn finalize_module(&self) -> Result<(), anyhow::Error> {
+        self.link_module()?;
+        optimize_module(&self.assembly_module, self.db.optimization_lvl());
+        Ok(())
+    }
+
+    /// Links the IR of the files into the assembly module and adds the reflection information.
+    fn link_module(&self) -> Result<(), anyhow::Error> {
         let group_ir = self.db.group_ir(self.file_id);
         let file = self.db.file_ir(self.file_id);
 
@@ -166,33 +406,74 @@ impl<'a> ModuleBuilder<'a> {
         symbols::gen_reflection_ir(
             self.db,
             &value_context,
-            &file.api,
+            &file,
             &group_ir.dispatch_table,
             &group_ir.type_table,
         );
 
-        // Optimize the assembly module
-        optimize_module(&self.assembly_module, self.db.optimization_lvl());
+        Ok(())
+    }
+}
 
-        // Debug print the IR
-        //println!("{}", assembly_module.print_to_string().to_string());
+/// Constructs a target machine that generates machine code for `target`.
+fn create_target_machine(
+    target: &spec::Target,
+    optimization_lvl: OptimizationLevel,
+) -> Result<(Target, TargetMachine), anyhow::Error> {
+    // Initialize the supported targets
+    Target::initialize_x86(&InitializationConfig::default());
+    Target::initialize_aarch64(&InitializationConfig::default());
+    Target::initialize_webassembly(&InitializationConfig::default());
 
-        ObjectFile::new(
-            &self.db.target(),
-            &self.target_machine,
-            self.assembly_module,
+    // Retrieve the LLVM target using the specified target.
+    let llvm_target = Target::from_triple(&target.llvm_target)
+        .map_err(|e| CodeGenerationError::UnknownTargetTriple(e.to_string()))?;
+
+    // Construct target machine for machine code generation
+    let target_machine = llvm_target
+        .create_target_machine(
+            &target.llvm_target,
+            &target.options.cpu,
+            &target.options.features,
+            optimization_lvl,
+            RelocMode::PIC,
+            CodeModel::Default,
+        )
+        .ok_or(CodeGenerationError::CouldNotCreateTargetMachine)?;
+
+    // The ABI types are laid out according to the data layout of the target, which must therefore
+    // match the layout that LLVM generates machine code for.
+    let data_layout = target_machine.get_target_data().get_data_layout();
+    let data_layout = data_layout.as_str().to_string_lossy();
+    if data_layout != target.data_layout {
+        return Err(CodeGenerationError::InconsistentDataLayout(
+            target.llvm_target.clone(),
+            target.data_layout.clone(),
+            data_layout.into_owned(),
         )
+        .into());
     }
+
+    Ok((llvm_target, target_machine))
 }
 
 /// Optimizes the specified LLVM `Module` using the default passes for the given
 /// `OptimizationLevel`.
+///
+/// The module contains the IR of all files of the assembly, so when optimizations are enabled a
+/// link-time optimization step is run as well. This allows functions that are called directly
+/// (see `ir::function::is_called_directly`) to be inlined into callers that were generated from
+/// another file. The symbols of the module are not internalized because they are looked up by the
+/// runtime.
 fn optimize_module(module: &Module, optimization_lvl: OptimizationLevel) {
     let pass_builder = PassManagerBuilder::create();
     pass_builder.set_optimization_level(optimization_lvl);
 
     let module_pass_manager = PassManager::create(());
     pass_builder.populate_module_pass_manager(&module_pass_manager);
+    if optimization_lvl != OptimizationLevel::None {
+        pass_builder.populate_lto_pass_manager(&module_pass_manager, false, true);
+    }
     module_pass_manager.run_on(module);
 }
 
@@ -0,0 +1,72 @@
+use crate::IrDatabase;
+use hir::FileId;
+use std::fmt::Write;
+
+/// Generates a C header that declares the entry points of the assembly that is compiled from
+/// `file_id`. A host that statically links the object file of the assembly into its executable
+/// passes these entry points to the runtime, e.g. with the `static_assembly!` macro of the
+/// `mun_runtime` crate. The public functions of the assembly are not exported by name; they are
+/// invoked through the runtime.
+pub fn generate_c_header(db: &dyn IrDatabase, file_id: FileId) -> String {
+    let file_id = db.assembly_group(file_id);
+    let path = db.file_relative_path(file_id);
+    let include_guard: String = path
+        .with_extension("")
+        .as_str()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    let mut header = String::new();
+    writeln!(
+        header,
+        "// Generated by the Mun compiler from `{}`. Do not edit.",
+        path
+    )
+    .unwrap();
+    writeln!(header, "//").unwrap();
+    writeln!(
+        header,
+        "// The public functions of the assembly can be invoked through the runtime:"
+    )
+    .unwrap();
+    for function in db.file_ir(file_id).api.iter() {
+        writeln!(header, "//   - {}", function.name(db.upcast())).unwrap();
+    }
+    writeln!(header).unwrap();
+    writeln!(header, "#ifndef MUN_ASSEMBLY_{}_H_", include_guard).unwrap();
+    writeln!(header, "#define MUN_ASSEMBLY_{}_H_", include_guard).unwrap();
+    header.push_str(
+        r#"
+#include <stdint.h>
+
+#include "mun_abi.h"
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/// Returns the information of the assembly.
+MunAssemblyInfo get_info(void);
+
+/// Returns the version of the ABI that the assembly was compiled with.
+uint32_t get_version(void);
+
+/// Sets the handle of the allocator that is used by the assembly.
+void set_allocator_handle(void *handle);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif
+
+"#,
+    );
+    writeln!(header, "#endif  // MUN_ASSEMBLY_{}_H_", include_guard).unwrap();
+    header
+}
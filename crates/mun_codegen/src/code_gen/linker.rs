@@ -31,6 +31,7 @@ pub fn create_with_target(target: &spec::Target) -> Box<dyn Linker> {
         LinkerFlavor::Ld => Box::new(LdLinker::new(target)),
         LinkerFlavor::Ld64 => Box::new(Ld64Linker::new(target)),
         LinkerFlavor::Msvc => Box::new(MsvcLinker::new(target)),
+        LinkerFlavor::Wasm => Box::new(WasmLinker::new(target)),
     }
 }
 
@@ -90,8 +91,13 @@ struct Ld64Linker {
 
 impl Ld64Linker {
     fn new(target: &spec::Target) -> Self {
+        // `ld64` uses Apple's names for the architectures
+        let arch = match target.arch.as_str() {
+            "aarch64" => "arm64",
+            arch => arch,
+        };
         Ld64Linker {
-            args: vec![format!("-arch {}", &target.arch)],
+            args: vec![format!("-arch {}", arch)],
         }
     }
 }
@@ -173,6 +179,8 @@ impl Linker for MsvcLinker {
 
         self.args.push("/DLL".to_owned());
         self.args.push("/NOENTRY".to_owned());
+        // Do not embed a timestamp, to produce the same output for the same input
+        self.args.push("/Brepro".to_owned());
         self.args.push(format!("/EXPORT:{}", abi::GET_INFO_FN_NAME));
         self.args
             .push(format!("/EXPORT:{}", abi::GET_VERSION_FN_NAME));
@@ -189,3 +197,48 @@ impl Linker for MsvcLinker {
             .map_err(LinkerError::LinkError)
     }
 }
+
+struct WasmLinker {
+    args: Vec<String>,
+}
+
+impl WasmLinker {
+    fn new(_target: &spec::Target) -> Self {
+        WasmLinker {
+            args: Vec::default(),
+        }
+    }
+}
+
+impl Linker for WasmLinker {
+    fn add_object(&mut self, path: &Path) -> Result<(), LinkerError> {
+        let path_str = path
+            .to_str()
+            .ok_or_else(|| LinkerError::PathError(path.to_owned()))?
+            .to_owned();
+        self.args.push(path_str);
+        Ok(())
+    }
+
+    fn build_shared_object(&mut self, path: &Path) -> Result<(), LinkerError> {
+        let path_str = path
+            .to_str()
+            .ok_or_else(|| LinkerError::PathError(path.to_owned()))?;
+
+        // WebAssembly has no dynamic libraries. Instead, a relocatable module is created that is
+        // linked into the WebAssembly module of the host.
+        self.args.push("--relocatable".to_owned());
+
+        // Specify output path
+        self.args.push("-o".to_owned());
+        self.args.push(path_str.to_owned());
+
+        Ok(())
+    }
+
+    fn finalize(&mut self) -> Result<(), LinkerError> {
+        mun_lld::link(mun_lld::LldFlavor::Wasm, &self.args)
+            .ok()
+            .map_err(LinkerError::LinkError)
+    }
+}
@@ -1,6 +1,7 @@
 use crate::ir::types as ir;
 use crate::ir::{
     dispatch_table::{DispatchTable, DispatchableFunction},
+    file::FileIR,
     function,
     type_table::TypeTable,
 };
@@ -8,18 +9,18 @@ use crate::type_info::TypeInfo;
 use crate::value::{AsValue, CanInternalize, Global, IrValueContext, IterAsIrValue, Value};
 use crate::IrDatabase;
 use hir::Ty;
-use inkwell::{attributes::Attribute, module::Linkage, AddressSpace};
-use std::collections::HashSet;
+use inkwell::{attributes::Attribute, module::Linkage};
 use std::ffi::CString;
 
-/// Construct a `MunFunctionPrototype` struct for the specified HIR function.
+/// Construct a `MunFunctionPrototype` struct with the specified name for the specified HIR
+/// function.
 fn gen_prototype_from_function(
     db: &dyn IrDatabase,
     context: &IrValueContext,
     function: hir::Function,
+    name: String,
 ) -> ir::FunctionPrototype {
     let module = context.module;
-    let name = function.name(db.upcast()).to_string();
 
     // Internalize the name of the function prototype
     let name_str = CString::new(name.clone())
@@ -124,17 +125,20 @@ fn gen_signature_return_type_from_type_info(
         .unwrap_or_else(|| Value::null(context))
 }
 
-/// Construct a global that holds a reference to all functions. e.g.:
+/// Construct a global with the specified name that holds a reference to all functions, of which
+/// the prototypes are named by `prototype_name`. e.g.:
 /// MunFunctionDefinition[] definitions = { ... }
 fn get_function_definition_array<'a>(
     db: &dyn IrDatabase,
     context: &IrValueContext,
     functions: impl Iterator<Item = &'a hir::Function>,
+    global_name: &str,
+    prototype_name: impl Fn(hir::Function) -> String,
 ) -> Global<[ir::FunctionDefinition]> {
     let module = context.module;
     functions
         .map(|f| {
-            let name = f.name(db.upcast()).to_string();
+            let name = function::symbol_name(db, *f);
 
             // Get the function from the cloned module and modify the linkage of the function.
             let value = module
@@ -146,17 +150,29 @@ fn get_function_definition_array<'a>(
             value.set_linkage(Linkage::Private);
 
             // Generate the signature from the function
-            let prototype = gen_prototype_from_function(db, context, *f);
+            let prototype = gen_prototype_from_function(db, context, *f, prototype_name(*f));
+
+            // Embed the documentation of the function, if any, for use by tooling
+            let docs = f
+                .docs(db.upcast())
+                .and_then(|docs| CString::new(docs.as_str()).ok())
+                .map(|docs| {
+                    docs.intern(format!("fn_def::<{}>::docs", name), context)
+                        .as_value(context)
+                })
+                .unwrap_or_else(|| Value::null(context));
+
             ir::FunctionDefinition {
                 prototype,
                 fn_ptr: Value::<*const fn()>::with_cast(
                     value.as_global_value().as_pointer_value(),
                     context,
                 ),
+                docs,
             }
         })
         .as_value(context)
-        .into_const_private_global("fn.get_info.functions", context)
+        .into_const_private_global(global_name, context)
 }
 
 /// Generate the dispatch table information. e.g.:
@@ -194,20 +210,29 @@ fn gen_dispatch_table(
     }
 }
 
-/// Constructs IR that exposes the types and symbols in the specified module. A function called
+/// Constructs IR that exposes the types and symbols in the specified file. A function called
 /// `get_info` is constructed that returns a struct `MunAssemblyInfo`. See the `mun_abi` crate
 /// for the ABI that `get_info` exposes.
+///
+/// The path of the module is set to the package-relative path of the source file it was compiled
+/// from, which allows tools to map the symbols in an assembly back to their source file. An
+/// assembly that is compiled from multiple files uses the path of its first file.
 pub(super) fn gen_reflection_ir(
     db: &dyn IrDatabase,
     context: &IrValueContext,
-    api: &HashSet<hir::Function>,
+    file: &FileIR,
     dispatch_table: &DispatchTable,
     type_table: &TypeTable,
 ) {
     let module = context.module;
+    let file_id = file.file_id;
+    let (api, tests, benches) = (&file.api, &file.tests, &file.benches);
 
     let num_functions = api.len() as u32;
-    let functions = get_function_definition_array(db, context, api.iter());
+    let functions =
+        get_function_definition_array(db, context, api.iter(), "fn.get_info.functions", |f| {
+            f.export_name(db.upcast())
+        });
 
     // Get the TypeTable global
     let types = TypeTable::find_global(module)
@@ -216,8 +241,8 @@ pub(super) fn gen_reflection_ir(
 
     // Construct the module info struct
     let module_info = ir::ModuleInfo {
-        path: CString::new("")
-            .unwrap()
+        path: CString::new(db.file_relative_path(file_id).as_str())
+            .expect("source file paths cannot contain nul characters")
             .intern("module_info::path", context)
             .as_value(context),
         functions: functions.as_value(context),
@@ -229,26 +254,156 @@ pub(super) fn gen_reflection_ir(
     // Construct the dispatch table struct
     let dispatch_table = gen_dispatch_table(context, dispatch_table);
 
+    // Embed the metadata that identifies how the assembly was built
+    let metadata = gen_build_metadata(db, context, file_id);
+
+    // Construct the paths of the assemblies that the runtime loads before linking this one
+    let dependencies = gen_dependencies(db, context);
+
+    // Construct the test and benchmark functions. They are named after their module path, so
+    // functions with the same name in different files can be told apart.
+    let tests = Tests {
+        tests: get_function_definition_array(db, context, tests.iter(), "fn.get_info.tests", |f| {
+            function::qualified_name(db, f)
+        })
+        .as_value(context),
+        num_tests: tests.len() as u32,
+        benches: get_function_definition_array(
+            db,
+            context,
+            benches.iter(),
+            "fn.get_info.benches",
+            |f| function::qualified_name(db, f),
+        )
+        .as_value(context),
+        num_benches: benches.len() as u32,
+    };
+
     // Construct the actual `get_info` function
-    gen_get_info_fn(db, context, module_info, dispatch_table);
+    gen_get_info_fn(
+        db,
+        context,
+        module_info,
+        dispatch_table,
+        dependencies,
+        metadata,
+        tests,
+    );
     gen_set_allocator_handle_fn(db, context);
     gen_get_version_fn(db, context);
 }
 
+/// The test and benchmark functions of an assembly.
+struct Tests {
+    tests: Value<*const ir::FunctionDefinition>,
+    num_tests: u32,
+    benches: Value<*const ir::FunctionDefinition>,
+    num_benches: u32,
+}
+
+/// The paths of the assemblies that an assembly depends on.
+struct Dependencies {
+    paths: Value<*const *const u8>,
+    num_paths: u32,
+}
+
+/// Constructs the paths of the compiled assemblies that the source depends on.
+fn gen_dependencies(db: &dyn IrDatabase, context: &IrValueContext) -> Dependencies {
+    let dependencies = db.assembly_dependencies();
+    let paths = dependencies
+        .values()
+        .enumerate()
+        .map(|(idx, path)| {
+            CString::new(path.as_str())
+                .expect("assembly paths cannot contain nul characters")
+                .intern(format!("assembly_info::dependencies.{}", idx), context)
+                .as_value(context)
+        })
+        .into_const_private_pointer_or_null("assembly_info::dependencies", context);
+    Dependencies {
+        paths,
+        num_paths: dependencies.len() as u32,
+    }
+}
+
+/// Returns the hash of the source of the assembly identified by the specified file. The paths of
+/// the files are included if there are multiple, so moving code between files changes the hash.
+fn source_hash(db: &dyn IrDatabase, file_id: hir::FileId) -> String {
+    let files = db.assembly_files(file_id);
+    if files.len() == 1 {
+        return format!("{:x}", md5::compute(db.file_text(files[0]).as_bytes()));
+    }
+    let mut context = md5::Context::new();
+    for file_id in files.iter() {
+        context.consume(format!("{}\n", db.file_relative_path(*file_id)).as_bytes());
+        context.consume(db.file_text(*file_id).as_bytes());
+        context.consume(b"\n");
+    }
+    format!("{:x}", context.compute())
+}
+
+/// The metadata of an assembly that describes the compiler and the source it was built from.
+struct BuildMetadata {
+    compiler_version: Value<*const u8>,
+    source_hash: Value<*const u8>,
+    fingerprint: Value<*const u8>,
+}
+
+/// Constructs the build metadata of the assembly that is identified by the specified file. The
+/// fingerprint is a hash of everything that influences the generated code: the compiler version,
+/// the source, the target and the compiler options. It intentionally contains no timestamp, so
+/// building the same source twice results in the same fingerprint.
+fn gen_build_metadata(
+    db: &dyn IrDatabase,
+    context: &IrValueContext,
+    file_id: hir::FileId,
+) -> BuildMetadata {
+    let compiler_version = env!("CARGO_PKG_VERSION");
+    let source_hash = source_hash(db, file_id);
+    let fingerprint = format!(
+        "{:x}",
+        md5::compute(format!(
+            "{}\n{}\n{}\n{:?}\n{}\n{}",
+            compiler_version,
+            source_hash,
+            db.target().llvm_target,
+            db.optimization_lvl(),
+            db.overflow_checks(),
+            db.debug_info()
+        ))
+    );
+
+    let intern = |text: &str, name: &str| {
+        CString::new(text)
+            .expect("build metadata cannot contain nul characters")
+            .intern(name, context)
+            .as_value(context)
+    };
+    BuildMetadata {
+        compiler_version: intern(compiler_version, "assembly_info::compiler_version"),
+        source_hash: intern(&source_hash, "assembly_info::source_hash"),
+        fingerprint: intern(&fingerprint, "assembly_info::fingerprint"),
+    }
+}
+
 /// Construct the actual `get_info` function.
 fn gen_get_info_fn(
     db: &dyn IrDatabase,
     context: &IrValueContext,
     module_info: ir::ModuleInfo,
     dispatch_table: ir::DispatchTable,
+    dependencies: Dependencies,
+    metadata: BuildMetadata,
+    tests: Tests,
 ) {
     let target = db.target();
-    let str_type = context.context.i8_type().ptr_type(AddressSpace::Generic);
 
     // Construct the return type of the `get_info` method. Depending on the C ABI this is either the
-    // `MunAssemblyInfo` struct or void. On windows the return argument is passed back to the caller
-    // through a pointer to the return type as the first argument. e.g.:
-    // On Windows:
+    // `MunAssemblyInfo` struct or void. On windows, WebAssembly and AArch64 the return argument is
+    // passed back to the caller through a pointer to the return type as the first argument. On
+    // AArch64 this pointer is passed in the dedicated `x8` register, which LLVM only uses for an
+    // `sret` argument. e.g.:
+    // On Windows, WebAssembly and AArch64:
     // ```c
     // void get_info(MunModuleInfo* result) {...}
     // ```
@@ -256,7 +411,9 @@ fn gen_get_info_fn(
     // ```c
     // MunModuleInfo get_info() { ... }
     // ```
-    let get_symbols_type = if target.options.is_like_windows {
+    let returns_by_pointer =
+        target.options.is_like_windows || target.options.is_like_wasm || target.arch == "aarch64";
+    let get_symbols_type = if returns_by_pointer {
         Value::<fn(*mut ir::AssemblyInfo)>::get_ir_type(context.type_context)
     } else {
         Value::<fn() -> ir::AssemblyInfo>::get_ir_type(context.type_context)
@@ -267,7 +424,7 @@ fn gen_get_info_fn(
             .module
             .add_function("get_info", get_symbols_type, Some(Linkage::DLLExport));
 
-    if target.options.is_like_windows {
+    if returns_by_pointer {
         get_symbols_fn.add_attribute(
             inkwell::attributes::AttributeLoc::Param(0),
             context
@@ -282,7 +439,7 @@ fn gen_get_info_fn(
 
     // Get a pointer to the IR value that will hold the return value. Again this differs depending
     // on the C ABI.
-    let result_ptr = if target.options.is_like_windows {
+    let result_ptr = if returns_by_pointer {
         get_symbols_fn
             .get_nth_param(0)
             .unwrap()
@@ -300,21 +457,48 @@ fn gen_get_info_fn(
     let dependencies_addr = unsafe { builder.build_struct_gep(result_ptr, 2, "dependencies") };
     let num_dependencies_addr =
         unsafe { builder.build_struct_gep(result_ptr, 3, "num_dependencies") };
+    let compiler_version_addr =
+        unsafe { builder.build_struct_gep(result_ptr, 4, "compiler_version") };
+    let source_hash_addr = unsafe { builder.build_struct_gep(result_ptr, 5, "source_hash") };
+    let fingerprint_addr = unsafe { builder.build_struct_gep(result_ptr, 6, "fingerprint") };
+    let tests_addr = unsafe { builder.build_struct_gep(result_ptr, 7, "tests") };
+    let num_tests_addr = unsafe { builder.build_struct_gep(result_ptr, 8, "num_tests") };
+    let benches_addr = unsafe { builder.build_struct_gep(result_ptr, 9, "benches") };
+    let num_benches_addr = unsafe { builder.build_struct_gep(result_ptr, 10, "num_benches") };
 
     // Assign the struct values one by one.
     builder.build_store(symbols_addr, module_info.as_value(context).value);
     builder.build_store(dispatch_table_addr, dispatch_table.as_value(context).value);
+    builder.build_store(dependencies_addr, dependencies.paths.value);
+    builder.build_store(
+        num_dependencies_addr,
+        context
+            .context
+            .i32_type()
+            .const_int(dependencies.num_paths as u64, false),
+    );
+    builder.build_store(compiler_version_addr, metadata.compiler_version.value);
+    builder.build_store(source_hash_addr, metadata.source_hash.value);
+    builder.build_store(fingerprint_addr, metadata.fingerprint.value);
+    builder.build_store(tests_addr, tests.tests.value);
     builder.build_store(
-        dependencies_addr,
-        str_type.ptr_type(AddressSpace::Generic).const_null(),
+        num_tests_addr,
+        context
+            .context
+            .i32_type()
+            .const_int(tests.num_tests as u64, false),
     );
+    builder.build_store(benches_addr, tests.benches.value);
     builder.build_store(
-        num_dependencies_addr,
-        context.context.i32_type().const_int(0 as u64, false),
+        num_benches_addr,
+        context
+            .context
+            .i32_type()
+            .const_int(tests.num_benches as u64, false),
     );
 
     // Construct the return statement of the function.
-    if target.options.is_like_windows {
+    if returns_by_pointer {
         builder.build_return(None);
     } else {
         builder.build_return(Some(&builder.build_load(result_ptr, "")));
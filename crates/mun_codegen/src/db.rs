@@ -2,7 +2,7 @@
 
 use crate::{
     assembly::Assembly,
-    ir::{file::FileIR, file_group::FileGroupIR},
+    ir::{file::FileIR, file_group::FileGroupIR, function::FunctionIR},
     type_info::TypeInfo,
     CodeGenParams, Context,
 };
@@ -14,7 +14,7 @@ use inkwell::{
 };
 use parking_lot::RwLock;
 use std::any::TypeId;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 pub type StructMapping = RwLock<HashMap<(&'static str, TypeId), StructType>>;
@@ -24,6 +24,10 @@ pub type StructMapping = RwLock<HashMap<(&'static str, TypeId), StructType>>;
 #[salsa::query_group(IrDatabaseStorage)]
 pub trait IrDatabase: hir::HirDatabase + hir::Upcast<dyn hir::HirDatabase> {
     /// Get the LLVM context that should be used for all generation steps.
+    ///
+    /// All cached types and values are owned by this context, which cannot be shared between
+    /// threads. IR is therefore generated on a single thread; it is only optimized and compiled
+    /// to machine code in parallel, from its bitcode, see `Assembly::from_bitcode`.
     #[salsa::input]
     fn context(&self) -> Arc<Context>;
 
@@ -31,6 +35,37 @@ pub trait IrDatabase: hir::HirDatabase + hir::Upcast<dyn hir::HirDatabase> {
     #[salsa::input]
     fn optimization_lvl(&self) -> OptimizationLevel;
 
+    /// Gets whether integer arithmetic is checked for overflow. If it is, an overflow calls the
+    /// `panic` intrinsic, otherwise the result wraps around.
+    #[salsa::input]
+    fn overflow_checks(&self) -> bool;
+
+    /// Gets whether debug information is emitted, which maps the generated machine code back to
+    /// the source code for native debuggers and profilers.
+    #[salsa::input]
+    fn debug_info(&self) -> bool;
+
+    /// Gets whether all files of a source root are compiled into a single assembly. Otherwise,
+    /// every file is compiled into its own assembly.
+    #[salsa::input]
+    fn single_assembly(&self) -> bool;
+
+    /// Gets the files that declare the public items of the compiled assemblies that the source
+    /// depends on, mapped to the paths of the assemblies. These files are not compiled, calls to
+    /// their functions are linked by the runtime, which loads the assemblies as dependencies.
+    #[salsa::input]
+    fn assembly_dependencies(&self) -> Arc<BTreeMap<hir::FileId, String>>;
+
+    /// Returns the files that are compiled into the same assembly as the specified file, ordered
+    /// by their relative path.
+    #[salsa::invoke(crate::assembly::assembly_files_query)]
+    fn assembly_files(&self, file: hir::FileId) -> Arc<Vec<hir::FileId>>;
+
+    /// Returns the file that identifies the assembly that the specified file is compiled into,
+    /// which is the first of its `assembly_files`.
+    #[salsa::invoke(crate::assembly::assembly_group_query)]
+    fn assembly_group(&self, file: hir::FileId) -> hir::FileId;
+
     /// Returns the target machine's data layout for code generation.
     #[salsa::invoke(crate::code_gen::target_data_query)]
     fn target_data(&self) -> Arc<TargetData>;
@@ -50,21 +85,28 @@ pub trait IrDatabase: hir::HirDatabase + hir::Upcast<dyn hir::HirDatabase> {
     #[salsa::invoke(crate::ir::ty::struct_ty_query)]
     fn struct_ty(&self, s: hir::Struct) -> StructType;
 
-    /// Given a `hir::FileId` generate code that is shared among the group of files.
-    /// TODO: Currently, a group always consists of a single file. Need to add support for multiple
-    /// files using something like `FileGroupId`.
+    /// Given the `hir::FileId` that identifies an assembly, generate code that is shared among
+    /// its files.
     #[salsa::invoke(crate::ir::file_group::ir_query)]
     fn group_ir(&self, file: hir::FileId) -> Arc<FileGroupIR>;
 
-    /// Returns a fully linked shared object for the specified group of files.
-    /// TODO: Current, a group always consists of a single file. Need to add support for multiple.
+    /// Returns the bitcode of the module of the assembly identified by the specified file, which
+    /// can be compiled to machine code on another thread with `Assembly::from_bitcode`.
+    #[salsa::invoke(crate::assembly::assembly_bitcode_query)]
+    fn assembly_bitcode(&self, file: hir::FileId) -> Arc<Vec<u8>>;
+
+    /// Returns a fully linked shared object for the assembly identified by the specified file.
     #[salsa::invoke(crate::assembly::assembly_query)]
     fn assembly(&self, file: hir::FileId) -> Arc<Assembly>;
 
-    /// Given a `hir::FileId` generate code for the module.
+    /// Given the `hir::FileId` that identifies an assembly, generate code for all its files.
     #[salsa::invoke(crate::ir::file::ir_query)]
     fn file_ir(&self, file: hir::FileId) -> Arc<FileIR>;
 
+    /// Given a `hir::Function` generate code for the function and its wrapper.
+    #[salsa::invoke(crate::ir::function::ir_query)]
+    fn function_ir(&self, function: hir::Function) -> Arc<FunctionIR>;
+
     /// Given a type, return the runtime `TypeInfo` that can be used to reflect the type.
     #[salsa::invoke(crate::ir::ty::type_info_query)]
     fn type_info(&self, ty: hir::Ty) -> TypeInfo;
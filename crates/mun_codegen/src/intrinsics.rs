@@ -7,6 +7,7 @@ use std::ffi;
 
 #[macro_use]
 mod macros;
+pub(crate) mod math;
 
 /// Defines the properties of an intrinsic function that can be called from Mun. These functions
 /// are mostly used internally.
@@ -21,4 +22,29 @@ pub trait Intrinsic: Sync {
 intrinsics! {
     /// Allocates memory for the specified `type` in the allocator referred to by `alloc_handle`.
     pub fn new(type: *const TypeInfo, alloc_handle: *mut ffi::c_void) -> *const *mut ffi::c_void;
+
+    /// Releases the memory of the object referred to by `obj` in the allocator referred to by
+    /// `alloc_handle`. The object must no longer be referenced.
+    pub fn drop(obj: *const *mut ffi::c_void, alloc_handle: *mut ffi::c_void) -> ();
+
+    /// Notifies the allocator referred to by `alloc_handle` that references to other objects were
+    /// stored in the object referred to by `obj`, so an incremental collection traces them.
+    pub fn write_barrier(obj: *const *mut ffi::c_void, alloc_handle: *mut ffi::c_void) -> ();
+
+    /// Aborts the execution of the current function with the specified null-terminated `message`
+    /// and the one-based `line` and `column` in the source code at which it occurred.
+    pub fn panic(message: *const u8, line: u32, column: u32) -> ();
+
+    /// Returns the lowest address that the stack of the current thread may grow to before a
+    /// recursive function panics, or null if the stack is not limited.
+    pub fn stack_limit() -> *const u8;
+
+    /// Returns a pointer to the number of loop iterations that the current thread may execute
+    /// before `interrupt_check` has to be called. The counter is decremented by every iteration.
+    pub fn interrupt_counter() -> *mut i64;
+
+    /// Aborts the execution of the current function if the invocation exceeded its budget or was
+    /// interrupted by the host. Otherwise, the counter that is returned by `interrupt_counter` is
+    /// reset.
+    pub fn interrupt_check() -> ();
 }
@@ -0,0 +1,51 @@
+//! The math functions of the `core` module that are lowered to LLVM intrinsics, e.g. `core::sqrt`
+//! to `llvm.sqrt.f32`. LLVM compiles these to native instructions where the target supports them
+//! and otherwise to calls into the C math library, like `sinf` or `pow`.
+
+use crate::ir::try_convert_any_to_basic;
+use crate::{CodeGenParams, IrDatabase, Module};
+use hir::{FloatBitness, MathIntrinsic, Ty, TypeCtor};
+use inkwell::types::BasicType;
+use inkwell::values::FunctionValue;
+
+/// Returns the LLVM intrinsic that implements `intrinsic` for floating point values, or vectors of
+/// floating point values, of type `ty`. The intrinsic is declared in `module` if it was not
+/// declared before.
+pub(crate) fn float_intrinsic(
+    db: &dyn IrDatabase,
+    module: &Module,
+    intrinsic: MathIntrinsic,
+    ty: &Ty,
+) -> FunctionValue {
+    let base_name = match intrinsic {
+        MathIntrinsic::Sqrt => "llvm.sqrt",
+        MathIntrinsic::Sin => "llvm.sin",
+        MathIntrinsic::Cos => "llvm.cos",
+        MathIntrinsic::Pow => "llvm.pow",
+        MathIntrinsic::Abs => "llvm.fabs",
+        MathIntrinsic::Min => "llvm.minnum",
+        MathIntrinsic::Max => "llvm.maxnum",
+    };
+    let type_suffix = match ty.as_simple() {
+        Some(TypeCtor::Float(float_ty)) => match float_ty.bitness {
+            FloatBitness::X32 => "f32".to_owned(),
+            FloatBitness::X64 => "f64".to_owned(),
+        },
+        Some(TypeCtor::FloatVector(lanes)) => format!("v{}f32", lanes),
+        _ => unreachable!("{:?} is not a floating point type", ty),
+    };
+    let name = format!("{}.{}", base_name, type_suffix);
+    if let Some(function) = module.get_function(&name) {
+        return function;
+    }
+
+    let value_ty = try_convert_any_to_basic(db.type_ir(
+        ty.clone(),
+        CodeGenParams {
+            make_marshallable: false,
+        },
+    ))
+    .expect("expected a basic type");
+    let param_tys = vec![value_ty; intrinsic.arg_count()];
+    module.add_function(&name, value_ty.fn_type(&param_tys, false), None)
+}
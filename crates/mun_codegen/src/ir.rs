@@ -9,11 +9,14 @@ use inkwell::AddressSpace;
 pub mod adt;
 pub mod body;
 #[macro_use]
+pub(crate) mod debug_info;
 pub(crate) mod dispatch_table;
+pub(crate) mod escape_analysis;
 pub mod file;
 pub(crate) mod file_group;
 pub mod function;
 mod intrinsics;
+pub(crate) mod tail_calls;
 pub mod ty;
 pub(crate) mod type_table;
 pub mod types;
@@ -165,6 +168,7 @@ impl_fundamental_ir_types!(
     u128 => i128_type():IntType,
 
     bool => bool_type():IntType,
+    char => i32_type():IntType,
 
     f32 => f32_type():FloatType,
     f64 => f64_type():FloatType
@@ -1,31 +1,53 @@
 use crate::intrinsics;
 use crate::{
-    ir::{dispatch_table::DispatchTable, try_convert_any_to_basic, type_table::TypeTable},
-    CodeGenParams, IrDatabase,
+    ir::{
+        dispatch_table::DispatchTable, function::has_stack_check, try_convert_any_to_basic,
+        type_table::TypeTable,
+    },
+    CodeGenParams, IrDatabase, Module,
 };
 use hir::{
-    ArenaId, ArithOp, BinaryOp, Body, CmpOp, Expr, ExprId, HirDisplay, InferenceResult, Literal,
-    LogicOp, Name, Ordering, Pat, PatId, Path, Resolution, Resolver, Statement, TypeCtor, UnaryOp,
+    ArenaId, ArithOp, BinaryOp, Body, BuiltinMethod, CmpOp, Expr, ExprId, HirDisplay,
+    InferenceResult, IntTy, Literal, LogicOp, MathIntrinsic, Name, Ordering, Pat, PatId, Path,
+    Resolution, Resolver, SourceLocationField, Statement, TypeCtor, UnaryOp, VectorComponent,
 };
 use inkwell::{
+    attributes::AttributeLoc,
     builder::Builder,
-    values::{BasicValueEnum, CallSiteValue, FloatValue, FunctionValue, IntValue, StructValue},
-    AddressSpace, FloatPredicate, IntPredicate,
+    values::{
+        BasicValue, BasicValueEnum, CallSiteValue, FloatMathValue, FloatValue, FunctionValue,
+        IntValue, StructValue, VectorValue,
+    },
+    AddressSpace, AtomicOrdering, AtomicRMWBinOp, FloatPredicate, IntPredicate,
+};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
 };
-use std::{collections::HashMap, sync::Arc};
 
+use crate::ir::debug_info::FunctionDebugInfo;
+use crate::ir::escape_analysis::NonEscapingAllocations;
+use crate::ir::function::sret_attribute;
+use crate::ir::intrinsics::{gc_object_receiver, is_overflow_checked, needs_write_barrier};
+use crate::ir::tail_calls;
+use crate::ir::ty::{
+    any_ty_query, int_ty_query, optional_ty_query, returns_by_pointer, source_location_ty_query,
+};
 use crate::ir::types as ir;
 use crate::value::Global;
 use hir::ResolveBitness;
 use inkwell::basic_block::BasicBlock;
+use inkwell::types::BasicTypeEnum;
 use inkwell::values::{AggregateValueEnum, GlobalValue, PointerValue};
 
 struct LoopInfo {
+    label: Option<Name>,
     break_values: Vec<(
         inkwell::values::BasicValueEnum,
         inkwell::basic_block::BasicBlock,
     )>,
     exit_block: BasicBlock,
+    continue_block: BasicBlock,
 }
 
 #[derive(Clone)]
@@ -39,25 +61,43 @@ pub(crate) struct BodyIrGenerator<'a, 'b> {
     db: &'a dyn IrDatabase,
     body: Arc<Body>,
     infer: Arc<InferenceResult>,
+    const_eval: Arc<hir::ConstEvalResult>,
     builder: Builder,
+    module: &'a Module,
     fn_value: FunctionValue,
     pat_to_param: HashMap<PatId, inkwell::values::BasicValueEnum>,
     pat_to_local: HashMap<PatId, inkwell::values::PointerValue>,
     pat_to_name: HashMap<PatId, String>,
-    function_map: &'a HashMap<hir::Function, FunctionValue>,
+    function_map: &'a BTreeMap<hir::Function, FunctionValue>,
     dispatch_table: &'b DispatchTable,
     type_table: &'b TypeTable,
-    active_loop: Option<LoopInfo>,
+    /// The loops that enclose the expression that is currently being generated, the innermost
+    /// loop is last.
+    active_loops: Vec<LoopInfo>,
     hir_function: hir::Function,
     params: CodeGenParams,
     external_globals: ExternalGlobals,
+    /// The debug information of the function, if debug information is emitted
+    debug_info: Option<FunctionDebugInfo<'b>>,
+    /// The heap allocations that are dropped as soon as they are no longer used
+    non_escaping: NonEscapingAllocations,
+    /// The calls of the function to itself that are compiled to a jump to `tail_call_block`
+    tail_calls: HashSet<ExprId>,
+    /// The block that follows the initialization of the parameters, if there are tail calls
+    tail_call_block: Option<BasicBlock>,
+    /// The memory that holds the value of every parameter, or `None` for a wildcard parameter
+    param_ptrs: Vec<Option<PointerValue>>,
+    /// The memory of the caller that the return value is written to, if the function returns its
+    /// value through an `sret` pointer
+    ret_ptr: Option<PointerValue>,
 }
 
 impl<'a, 'b> BodyIrGenerator<'a, 'b> {
     pub fn new(
         db: &'a dyn IrDatabase,
+        module: &'a Module,
         function: (hir::Function, FunctionValue),
-        function_map: &'a HashMap<hir::Function, FunctionValue>,
+        function_map: &'a BTreeMap<hir::Function, FunctionValue>,
         dispatch_table: &'b DispatchTable,
         type_table: &'b TypeTable,
         params: CodeGenParams,
@@ -68,6 +108,9 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
         // Get the type information from the `hir::Function`
         let body = hir_function.body(db.upcast());
         let infer = hir_function.infer(db.upcast());
+        let const_eval = hir_function.const_eval(db.upcast());
+        let non_escaping = NonEscapingAllocations::new(db, &body, &infer);
+        let tail_calls = tail_calls::tail_self_calls(hir_function, &body, &infer);
 
         // Construct a builder for the IR function
         let context = db.context();
@@ -79,7 +122,9 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
             db,
             body,
             infer,
+            const_eval,
             builder,
+            module,
             fn_value: ir_function,
             pat_to_param: HashMap::default(),
             pat_to_local: HashMap::default(),
@@ -87,15 +132,40 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
             function_map,
             dispatch_table,
             type_table,
-            active_loop: None,
+            active_loops: Vec::new(),
             hir_function,
             params,
             external_globals,
+            debug_info: None,
+            non_escaping,
+            tail_calls,
+            tail_call_block: None,
+            param_ptrs: Vec::new(),
+            ret_ptr: None,
         }
     }
 
+    /// Attaches source locations and variable descriptions to the generated instructions.
+    pub fn set_debug_info(&mut self, debug_info: FunctionDebugInfo<'b>) {
+        self.debug_info = Some(debug_info);
+    }
+
     /// Generates IR for the body of the function.
     pub fn gen_fn_body(&mut self) {
+        // A value that is returned through an `sret` pointer precedes the other parameters
+        let fn_sig = self
+            .hir_function
+            .ty(self.db.upcast())
+            .callable_sig(self.db.upcast())
+            .unwrap();
+        if returns_by_pointer(self.db, fn_sig.ret()) {
+            self.ret_ptr = self
+                .fn_value
+                .get_first_param()
+                .map(|param| param.into_pointer_value());
+        }
+        let param_offset = if self.ret_ptr.is_some() { 1 } else { 0 };
+
         // Iterate over all parameters and their type and store them so we can reference them
         // later in code.
         for (i, (pat, _ty)) in self.body.params().iter().enumerate() {
@@ -104,15 +174,21 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
             match &body[*pat] {
                 Pat::Bind { name } => {
                     let name = name.to_string();
-                    let param = self.fn_value.get_nth_param(i as u32).unwrap();
+                    let param = self
+                        .fn_value
+                        .get_nth_param(i as u32 + param_offset)
+                        .unwrap();
                     let builder = self.new_alloca_builder();
                     let param_ptr = builder.build_alloca(param.get_type(), &name);
                     builder.build_store(param_ptr, param);
+                    self.declare_debug_variable(*pat, param_ptr, &name, Some(i as u32 + 1));
                     self.pat_to_local.insert(*pat, param_ptr);
                     self.pat_to_name.insert(*pat, name);
+                    self.param_ptrs.push(Some(param_ptr));
                 }
                 Pat::Wild => {
                     // Wildcard patterns cannot be referenced from code. So nothing to do.
+                    self.param_ptrs.push(None);
                 }
                 Pat::Path(_) => unreachable!(
                     "Path patterns are not supported as parameters, are we missing a diagnostic?"
@@ -123,6 +199,21 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
             }
         }
 
+        if has_stack_check(self.db, self.hir_function) {
+            self.gen_stack_check();
+        }
+
+        // Tail calls jump back to this block after replacing the values of the parameters
+        if !self.tail_calls.is_empty() {
+            let tail_call_block = self
+                .db
+                .context()
+                .append_basic_block(&self.fn_value, "tail_call");
+            self.builder.build_unconditional_branch(&tail_call_block);
+            self.builder.position_at_end(&tail_call_block);
+            self.tail_call_block = Some(tail_call_block);
+        }
+
         // Generate code for the body of the function
         let ret_value = self.gen_expr(self.body.body_expr());
 
@@ -137,13 +228,138 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
             .unwrap()
             .ret()
             .clone();
-        if !block_ret_type.is_never() {
+        if !block_ret_type.is_never() && !self.is_block_terminated() {
             if fn_ret_type.is_empty() {
                 self.builder.build_return(None);
             } else if let Some(value) = ret_value {
-                self.builder.build_return(Some(&value));
+                self.gen_return_value(value);
             }
         }
+
+        // If every branch of the body ends with a tail call, the block that would have returned
+        // the value of the body is never reached.
+        if self.tail_call_block.is_some() && !self.is_block_terminated() {
+            self.builder.build_unreachable();
+        }
+    }
+
+    /// Generates IR that panics if the frame of the function lies below the stack limit of the
+    /// runtime, before the function writes to its frame. This turns a stack overflow into a panic
+    /// that the runtime catches, instead of a crash of the host.
+    fn gen_stack_check(&mut self) {
+        let context = self.db.context();
+        let i32_type = context.i32_type();
+        let frame_address_fn = self
+            .module
+            .get_function("llvm.frameaddress")
+            .unwrap_or_else(|| {
+                let i8_ptr_type = context.i8_type().ptr_type(AddressSpace::Generic);
+                self.module.add_function(
+                    "llvm.frameaddress",
+                    i8_ptr_type.fn_type(&[i32_type.into()], false),
+                    None,
+                )
+            });
+        let frame_address = self
+            .builder
+            .build_call(frame_address_fn, &[i32_type.const_zero().into()], "")
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_pointer_value();
+
+        let stack_limit_fn_ptr = self.dispatch_table.gen_intrinsic_lookup(
+            self.external_globals.dispatch_table,
+            &self.builder,
+            &intrinsics::stack_limit,
+        );
+        let stack_limit = self
+            .builder
+            .build_call(stack_limit_fn_ptr, &[], "")
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_pointer_value();
+
+        // The stack grows downwards
+        let usize_type = self.db.target_data().ptr_sized_int_type(None);
+        let frame_address =
+            self.builder
+                .build_ptr_to_int(frame_address, usize_type, "frame_address");
+        let stack_limit = self
+            .builder
+            .build_ptr_to_int(stack_limit, usize_type, "stack_limit");
+        let is_overflow = self.builder.build_int_compare(
+            IntPredicate::ULT,
+            frame_address,
+            stack_limit,
+            "is_stack_overflow",
+        );
+        self.gen_panic_if(self.body.body_expr(), is_overflow, "stack overflow");
+    }
+
+    /// Generates IR that retrieves the pointer to the loop iteration counter of the current thread,
+    /// which is passed to `gen_interrupt_check`.
+    fn gen_interrupt_counter(&mut self) -> PointerValue {
+        let interrupt_counter_fn_ptr = self.dispatch_table.gen_intrinsic_lookup(
+            self.external_globals.dispatch_table,
+            &self.builder,
+            &intrinsics::interrupt_counter,
+        );
+        self.builder
+            .build_call(interrupt_counter_fn_ptr, &[], "")
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_pointer_value()
+    }
+
+    /// Generates IR that decrements the loop iteration `counter` at the start of every iteration
+    /// of a loop, and calls the `interrupt_check` intrinsic when it runs out. This enables the
+    /// runtime to abort an invocation that exceeds its budget, e.g. an accidental infinite loop,
+    /// while only the decrement is executed by most iterations.
+    fn gen_interrupt_check(&mut self, counter: PointerValue) {
+        let context = self.db.context();
+        let i64_type = context.i64_type();
+        let value = self
+            .builder
+            .build_load(counter, "interrupt_counter")
+            .into_int_value();
+        let value =
+            self.builder
+                .build_int_sub(value, i64_type.const_int(1, false), "interrupt_counter");
+        self.builder.build_store(counter, value);
+        let is_exhausted = self.builder.build_int_compare(
+            IntPredicate::SLT,
+            value,
+            i64_type.const_zero(),
+            "is_interrupt_check",
+        );
+
+        let check_block = context.append_basic_block(&self.fn_value, "interrupt_check");
+        let continue_block = context.append_basic_block(&self.fn_value, "continue");
+        self.builder
+            .build_conditional_branch(is_exhausted, &check_block, &continue_block);
+
+        self.builder.position_at_end(&check_block);
+        let interrupt_check_fn_ptr = self.dispatch_table.gen_intrinsic_lookup(
+            self.external_globals.dispatch_table,
+            &self.builder,
+            &intrinsics::interrupt_check,
+        );
+        self.builder.build_call(interrupt_check_fn_ptr, &[], "");
+        self.builder.build_unconditional_branch(&continue_block);
+
+        self.builder.position_at_end(&continue_block);
+    }
+
+    /// Returns true if the block that is currently being generated ends with a terminator, like a
+    /// branch or return instruction.
+    fn is_block_terminated(&self) -> bool {
+        self.builder
+            .get_insert_block()
+            .and_then(|block| block.get_terminator())
+            .is_some()
     }
 
     pub fn gen_fn_wrapper(&mut self) {
@@ -170,10 +386,7 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
             })
             .collect();
 
-        let ret_value = self
-            .gen_call(self.hir_function, &args)
-            .try_as_basic_value()
-            .left();
+        let ret_value = self.gen_call(self.hir_function, &args);
 
         let call_return_type = &self.infer[self.body.body_expr()];
         if !call_return_type.is_never() {
@@ -207,6 +420,19 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
     /// value is returned.
     fn gen_expr(&mut self, expr: ExprId) -> Option<inkwell::values::BasicValueEnum> {
         let body = self.body.clone();
+
+        if let Some(debug_info) = &self.debug_info {
+            if let Some(line_col) = self.hir_function.expr_line_col(self.db.upcast(), expr) {
+                debug_info.set_location(self.db, &self.builder, line_col);
+            }
+        }
+
+        // Expressions whose value is known at compile time are emitted as a literal
+        let const_eval = self.const_eval.clone();
+        if let Some(value) = const_eval.value(expr) {
+            return Some(self.gen_literal(value, expr));
+        }
+
         match &body[expr] {
             Expr::Block {
                 ref statements,
@@ -217,7 +443,7 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
                 Some(self.gen_path_expr(p, expr, &resolver))
             }
             Expr::Literal(lit) => Some(self.gen_literal(lit, expr)),
-            Expr::RecordLit { fields, .. } => Some(self.gen_record_lit(expr, fields)),
+            Expr::RecordLit { fields, spread, .. } => self.gen_record_lit(expr, fields, *spread),
             Expr::BinaryOp { lhs, rhs, op } => {
                 self.gen_binary_op(expr, *lhs, *rhs, op.expect("missing op"))
             }
@@ -229,15 +455,18 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
                 // Get the callable definition from the map
                 match self.infer[*callee].as_callable_def() {
                     Some(hir::CallableDef::Function(def)) => {
-                        // Get all the arguments
-                        let args: Vec<BasicValueEnum> = args
+                        // Get all the arguments, no call is made if one of them diverges
+                        let args = args
                             .iter()
-                            .map(|expr| self.gen_expr(*expr).expect("expected a value"))
-                            .collect();
+                            .map(|expr| self.gen_expr(*expr))
+                            .collect::<Option<Vec<BasicValueEnum>>>()?;
+
+                        if self.tail_calls.contains(&expr) {
+                            self.gen_tail_call(&args);
+                            return None;
+                        }
 
                         self.gen_call(def, &args)
-                            .try_as_basic_value()
-                            .left()
                             // If the called function is a void function it doesn't return anything.
                             // If this method (`gen_expr`) returns None we assume the return value
                             // is `never`. We return a const unit struct here to ensure that at
@@ -246,11 +475,15 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
                             //
                             // This unit value will also be optimized out.
                             .or_else(|| match self.infer[expr] {
-                                hir::ty_app!(hir::TypeCtor::Never) => None,
+                                hir::ty_app!(hir::TypeCtor::Never) => {
+                                    // Control never returns from the called function
+                                    self.builder.build_unreachable();
+                                    None
+                                }
                                 _ => Some(self.db.context().const_struct(&[], false).into()),
                             })
                     }
-                    Some(hir::CallableDef::Struct(_)) => Some(self.gen_named_tuple_lit(expr, args)),
+                    Some(hir::CallableDef::Struct(_)) => self.gen_named_tuple_lit(expr, args),
                     None => panic!("expected a callable expression"),
                 }
             }
@@ -260,17 +493,385 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
                 else_branch,
             } => self.gen_if(expr, *condition, *then_branch, *else_branch),
             Expr::Return { expr: ret_expr } => self.gen_return(expr, *ret_expr),
-            Expr::Loop { body } => self.gen_loop(expr, *body),
-            Expr::While { condition, body } => self.gen_while(expr, *condition, *body),
-            Expr::Break { expr: break_expr } => self.gen_break(expr, *break_expr),
+            Expr::Loop { body, label } => self.gen_loop(expr, *body, label.clone()),
+            Expr::While {
+                condition,
+                body,
+                label,
+            } => self.gen_while(expr, *condition, *body, label.clone()),
+            Expr::Break {
+                expr: break_expr,
+                label,
+            } => self.gen_break(expr, *break_expr, label.as_ref()),
+            Expr::Continue { label } => self.gen_continue(expr, label.as_ref()),
             Expr::Field {
                 expr: receiver_expr,
                 name,
             } => self.gen_field(expr, *receiver_expr, name),
+            Expr::MethodCall { receiver, args, .. } => self.gen_method_call(expr, *receiver, args),
+            Expr::SourceLocation => Some(self.gen_source_location(expr)),
+            Expr::Panic { message } => {
+                let message = self.panic_message(*message, "explicit panic");
+                self.gen_panic(expr, &message);
+                None
+            }
+            Expr::Assert { condition, message } => self.gen_assert(expr, *condition, *message),
+            Expr::Vector { elements } => self.gen_vector(elements),
+            Expr::MathIntrinsic { intrinsic, args } => {
+                self.gen_math_intrinsic(expr, *intrinsic, args)
+            }
+            Expr::Cast { expr: inner, .. } => self.gen_cast(expr, *inner),
             _ => unimplemented!("unimplemented expr type {:?}", &body[expr]),
         }
     }
 
+    /// Generates a `SourceLocation` with the one-based line and column of the expression and the
+    /// relative path of its file, which is stored as a global string.
+    fn gen_source_location(&mut self, expr: ExprId) -> BasicValueEnum {
+        let line_col = self
+            .hir_function
+            .expr_line_col(self.db.upcast(), expr)
+            .expect("could not find the source location of an expression");
+        let file_id = self.hir_function.module(self.db.upcast()).file_id();
+        let file_ptr = self
+            .builder
+            .build_global_string_ptr(self.db.file_relative_path(file_id).as_str(), "source_file")
+            .as_pointer_value();
+        let file = self.builder.build_ptr_to_int(
+            file_ptr,
+            int_ty_query(self.db, IntTy::usize()),
+            "source_file_handle",
+        );
+        let i32_type = self.db.context().i32_type();
+        let location = source_location_ty_query(self.db).const_named_struct(&[
+            i32_type
+                .const_int(u64::from(line_col.line + 1), false)
+                .into(),
+            i32_type
+                .const_int(u64::from(line_col.col + 1), false)
+                .into(),
+            int_ty_query(self.db, IntTy::usize()).get_undef().into(),
+        ]);
+        self.builder
+            .build_insert_value(
+                location,
+                file,
+                SourceLocationField::File.index(),
+                "location",
+            )
+            .expect("failed to initialize source location")
+            .into_struct_value()
+            .into()
+    }
+
+    /// Generates IR for the construction of a vector by inserting every element into its lane.
+    fn gen_vector(&mut self, elements: &[ExprId]) -> Option<BasicValueEnum> {
+        let mut vector = self
+            .db
+            .context()
+            .f32_type()
+            .vec_type(elements.len() as u32)
+            .get_undef();
+        for (index, element) in elements.iter().enumerate() {
+            let value = self
+                .gen_expr(*element)
+                .map(|value| self.opt_deref_value(*element, value))?;
+            vector = self.builder.build_insert_element(
+                vector,
+                value,
+                self.db.context().i32_type().const_int(index as u64, false),
+                "",
+            );
+        }
+        Some(vector.into())
+    }
+
+    /// Generates IR for a call to a math function. Floating point values are passed to the LLVM
+    /// intrinsic that implements the function, integer functions are computed with a comparison.
+    fn gen_math_intrinsic(
+        &mut self,
+        expr: ExprId,
+        intrinsic: MathIntrinsic,
+        args: &[ExprId],
+    ) -> Option<BasicValueEnum> {
+        let mut arg_values = Vec::with_capacity(args.len());
+        for arg in args {
+            arg_values.push(self.gen_expr(*arg)?);
+        }
+
+        let ty = self.infer[expr].clone();
+        let int_ty = match ty.as_simple() {
+            Some(TypeCtor::Int(int_ty)) => int_ty,
+            _ => {
+                let function =
+                    intrinsics::math::float_intrinsic(self.db, self.module, intrinsic, &ty);
+                return self
+                    .builder
+                    .build_call(function, &arg_values, intrinsic.name())
+                    .try_as_basic_value()
+                    .left();
+            }
+        };
+
+        let is_signed = int_ty.signedness == hir::Signedness::Signed;
+        let lhs = arg_values[0].into_int_value();
+        let value = match intrinsic {
+            MathIntrinsic::Abs if is_signed => {
+                let is_negative = self.builder.build_int_compare(
+                    IntPredicate::SLT,
+                    lhs,
+                    lhs.get_type().const_zero(),
+                    "is_negative",
+                );
+                let negated = self.builder.build_int_neg(lhs, "neg");
+                self.builder.build_select(is_negative, negated, lhs, "abs")
+            }
+            MathIntrinsic::Abs => lhs.into(),
+            MathIntrinsic::Min | MathIntrinsic::Max => {
+                let rhs = arg_values[1].into_int_value();
+                let predicate = match (intrinsic, is_signed) {
+                    (MathIntrinsic::Min, true) => IntPredicate::SLT,
+                    (MathIntrinsic::Min, false) => IntPredicate::ULT,
+                    (_, true) => IntPredicate::SGT,
+                    (_, false) => IntPredicate::UGT,
+                };
+                let is_lhs = self.builder.build_int_compare(predicate, lhs, rhs, "cmp");
+                self.builder
+                    .build_select(is_lhs, lhs, rhs, intrinsic.name())
+            }
+            MathIntrinsic::Sqrt | MathIntrinsic::Sin | MathIntrinsic::Cos | MathIntrinsic::Pow => {
+                unreachable!("{} only accepts floating point values", intrinsic.name())
+            }
+        };
+        Some(value)
+    }
+
+    /// Generates IR that panics with the location of `expr` if `condition` evaluates to `false`.
+    /// Without a `message`, the panic message contains the source text of the condition.
+    fn gen_assert(
+        &mut self,
+        expr: ExprId,
+        condition: ExprId,
+        message: Option<ExprId>,
+    ) -> Option<BasicValueEnum> {
+        let condition_ir = self
+            .gen_expr(condition)
+            .map(|value| self.opt_deref_value(condition, value))?
+            .into_int_value();
+        let failed = self.builder.build_not(condition_ir, "assert_failed");
+
+        let default_message = format!(
+            "assertion failed: {}",
+            self.hir_function
+                .expr_source_text(self.db.upcast(), condition)
+                .expect("could not find the source text of an expression")
+        );
+        let message = self.panic_message(message, &default_message);
+        self.gen_panic_if(expr, failed, &message);
+
+        Some(self.gen_empty())
+    }
+
+    /// Returns the text of the string literal `message` of a `core::panic()` or `core::assert()`
+    /// call, or `default` if no message was specified.
+    fn panic_message(&self, message: Option<ExprId>, default: &str) -> String {
+        match message.map(|message| &self.body[message]) {
+            Some(Expr::Literal(Literal::String(text))) => text.clone(),
+            Some(_) => unreachable!("panic messages are string literals"),
+            None => default.to_owned(),
+        }
+    }
+
+    /// Generates IR for a cast to or from `any`. A value that is cast to `any` is stored together
+    /// with a pointer to its `TypeInfo`. Casting an `any` back to a primitive type results in an
+    /// `Optional` that only holds a value if the `TypeInfo` matches the target type.
+    fn gen_cast(&mut self, expr: ExprId, inner: ExprId) -> Option<BasicValueEnum> {
+        let value = self.gen_expr(inner)?;
+        if self.infer[inner].as_simple() == Some(TypeCtor::Any) {
+            if self.infer[expr].as_simple() == Some(TypeCtor::Any) {
+                Some(value)
+            } else {
+                Some(self.gen_downcast(expr, value.into_struct_value()))
+            }
+        } else {
+            Some(self.gen_upcast(inner, value))
+        }
+    }
+
+    /// Generates IR that stores a primitive value in an `any`.
+    fn gen_upcast(&mut self, inner: ExprId, value: BasicValueEnum) -> BasicValueEnum {
+        let context = self.db.context();
+        let type_info_ptr = self.type_table.gen_type_info_lookup(
+            &self.builder,
+            &self.db.type_info(self.infer[inner].clone()),
+            self.external_globals.type_table,
+        );
+        let type_info_ptr = self.builder.build_bitcast(
+            type_info_ptr,
+            context.i8_type().ptr_type(AddressSpace::Generic),
+            "type_info_ptr_to_i8_ptr",
+        );
+
+        // The value is stored as its bit representation, zero-extended to 64 bits
+        let i64_type = context.i64_type();
+        let payload = match value {
+            BasicValueEnum::FloatValue(value) => {
+                let int_type = if value.get_type() == context.f32_type() {
+                    context.i32_type()
+                } else {
+                    i64_type
+                };
+                let bits = self
+                    .builder
+                    .build_bitcast(value, int_type, "float_bits")
+                    .into_int_value();
+                self.builder.build_int_z_extend(bits, i64_type, "payload")
+            }
+            BasicValueEnum::IntValue(value) => {
+                if value.get_type().get_bit_width() == 64 {
+                    value
+                } else {
+                    self.builder.build_int_z_extend(value, i64_type, "payload")
+                }
+            }
+            _ => unreachable!("only primitive types can be cast to `any`"),
+        };
+
+        let mut any_value: AggregateValueEnum = any_ty_query(self.db).get_undef().into();
+        any_value = self
+            .builder
+            .build_insert_value(any_value, type_info_ptr, 0, "type_info")
+            .expect("failed to initialize any");
+        any_value = self
+            .builder
+            .build_insert_value(any_value, payload, 1, "payload")
+            .expect("failed to initialize any");
+        any_value.into_struct_value().into()
+    }
+
+    /// Generates IR that tries to retrieve a primitive value from an `any`, resulting in an
+    /// `Optional`.
+    fn gen_downcast(&mut self, expr: ExprId, any_value: StructValue) -> BasicValueEnum {
+        let context = self.db.context();
+        let primitive_ty = match self.infer[expr].as_simple() {
+            Some(TypeCtor::Optional(ty)) => ty,
+            _ => unreachable!("a cast from `any` results in an `Optional`"),
+        };
+        let target_ty = hir::Ty::from(primitive_ty);
+        let target_guid = self.db.type_info(target_ty.clone()).guid;
+
+        let type_info_ptr = self
+            .builder
+            .build_extract_value(any_value, 0, "type_info")
+            .unwrap()
+            .into_pointer_value();
+        let payload = self
+            .builder
+            .build_extract_value(any_value, 1, "payload")
+            .unwrap()
+            .into_int_value();
+
+        // An `any` that was never assigned a value (e.g. zero-initialized by the host) does not
+        // point to a `TypeInfo`.
+        let is_null = self.builder.build_is_null(type_info_ptr, "is_null");
+        let null_block = self.builder.get_insert_block().unwrap();
+        let guid_block = context.append_basic_block(&self.fn_value, "guid_check");
+        let merge_block = context.append_basic_block(&self.fn_value, "downcast");
+        self.builder
+            .build_conditional_branch(is_null, &merge_block, &guid_block);
+
+        // Types are compared by their guid, because the `TypeInfo` of a type can reside in
+        // different assemblies.
+        self.builder.position_at_end(&guid_block);
+        let i64_type = context.i64_type();
+        let guid_ptr = self
+            .builder
+            .build_bitcast(
+                type_info_ptr,
+                i64_type.ptr_type(AddressSpace::Generic),
+                "guid_ptr",
+            )
+            .into_pointer_value();
+        let mut guid_matches = context.bool_type().const_int(1, false);
+        for (i, chunk) in target_guid.b.chunks(8).enumerate() {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(chunk);
+            let chunk_ptr = unsafe {
+                self.builder.build_in_bounds_gep(
+                    guid_ptr,
+                    &[i64_type.const_int(i as u64, false)],
+                    "guid_chunk_ptr",
+                )
+            };
+            let chunk_value = self
+                .builder
+                .build_load(chunk_ptr, "guid_chunk")
+                .into_int_value();
+            let chunk_matches = self.builder.build_int_compare(
+                IntPredicate::EQ,
+                chunk_value,
+                i64_type.const_int(u64::from_le_bytes(bytes), false),
+                "guid_chunk_eq",
+            );
+            guid_matches = self
+                .builder
+                .build_and(guid_matches, chunk_matches, "guid_eq");
+        }
+        let guid_block = self.builder.get_insert_block().unwrap();
+        self.builder.build_unconditional_branch(&merge_block);
+
+        self.builder.position_at_end(&merge_block);
+        let is_some = self.builder.build_phi(context.bool_type(), "is_some");
+        is_some.add_incoming(&[
+            (&context.bool_type().const_int(0, false), &null_block),
+            (&guid_matches, &guid_block),
+        ]);
+
+        // Reconstruct the value from its bit representation
+        let value_ty = try_convert_any_to_basic(self.db.type_ir(
+            target_ty,
+            CodeGenParams {
+                make_marshallable: false,
+            },
+        ))
+        .expect("expected a basic type");
+        let value: BasicValueEnum = match value_ty {
+            BasicTypeEnum::FloatType(float_type) => {
+                let int_type = if float_type == context.f32_type() {
+                    context.i32_type()
+                } else {
+                    i64_type
+                };
+                let bits =
+                    self.builder
+                        .build_int_truncate_or_bit_cast(payload, int_type, "float_bits");
+                self.builder.build_bitcast(bits, float_type, "value")
+            }
+            BasicTypeEnum::IntType(int_type) => {
+                if int_type.get_bit_width() == 64 {
+                    payload.into()
+                } else {
+                    self.builder
+                        .build_int_truncate(payload, int_type, "value")
+                        .into()
+                }
+            }
+            _ => unreachable!("only primitive types can be cast from `any`"),
+        };
+
+        let mut optional: AggregateValueEnum =
+            optional_ty_query(self.db, primitive_ty).get_undef().into();
+        optional = self
+            .builder
+            .build_insert_value(optional, is_some.as_basic_value(), 0, "is_some")
+            .expect("failed to initialize optional");
+        optional = self
+            .builder
+            .build_insert_value(optional, value, 1, "value")
+            .expect("failed to initialize optional");
+        optional.into_struct_value().into()
+    }
+
     /// Generates an IR value that represents the given `Literal`.
     fn gen_literal(&mut self, lit: &Literal, expr: ExprId) -> BasicValueEnum {
         match lit {
@@ -329,6 +930,13 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
                 }
             }
 
+            Literal::Char(value) => self
+                .db
+                .context()
+                .i32_type()
+                .const_int(u64::from(*value as u32), false)
+                .into(),
+
             Literal::String(_) => unimplemented!("string literals are not implemented yet"),
         }
     }
@@ -434,32 +1042,142 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
         struct_ptr_ptr.into()
     }
 
+    /// Releases the memory of the heap-allocated `object` with the `drop` intrinsic. The object must
+    /// no longer be referenced.
+    fn gen_drop(&mut self, object: BasicValueEnum) {
+        let drop_fn_ptr = self.dispatch_table.gen_intrinsic_lookup(
+            self.external_globals.dispatch_table,
+            &self.builder,
+            &intrinsics::drop,
+        );
+
+        // The intrinsic is struct type agnostic, it accepts a `*const *mut std::ffi::c_void`
+        let object_ptr = self.builder.build_bitcast(
+            object,
+            self.db
+                .context()
+                .i8_type()
+                .ptr_type(AddressSpace::Generic)
+                .ptr_type(AddressSpace::Generic),
+            "object_ptr",
+        );
+
+        let allocator_handle = self.builder.build_load(
+            self.external_globals
+                .alloc_handle
+                .expect("no allocator handle was specified, this is required for structs")
+                .as_pointer_value(),
+            "allocator_handle",
+        );
+
+        self.builder
+            .build_call(drop_fn_ptr, &[object_ptr, allocator_handle], "");
+    }
+
+    /// Notifies the allocator with the `write_barrier` intrinsic if the assignment to the place
+    /// expression `place_expr` stored references in a heap-allocated object.
+    fn gen_write_barrier(&mut self, place_expr: ExprId) {
+        if !needs_write_barrier(self.db, &self.body, &self.infer, place_expr) {
+            return;
+        }
+        let receiver_expr =
+            gc_object_receiver(self.db, &self.body, &self.infer, place_expr).unwrap();
+
+        let write_barrier_fn_ptr = self.dispatch_table.gen_intrinsic_lookup(
+            self.external_globals.dispatch_table,
+            &self.builder,
+            &intrinsics::write_barrier,
+        );
+
+        // The place of the receiver stores the object pointer of the heap-allocated object
+        let receiver_ptr = self.gen_place_expr(receiver_expr);
+        let object = self.builder.build_load(receiver_ptr, "object");
+
+        // The intrinsic is struct type agnostic, it accepts a `*const *mut std::ffi::c_void`
+        let object_ptr = self.builder.build_bitcast(
+            object,
+            self.db
+                .context()
+                .i8_type()
+                .ptr_type(AddressSpace::Generic)
+                .ptr_type(AddressSpace::Generic),
+            "object_ptr",
+        );
+
+        let allocator_handle = self.builder.build_load(
+            self.external_globals
+                .alloc_handle
+                .expect("no allocator handle was specified, this is required for structs")
+                .as_pointer_value(),
+            "allocator_handle",
+        );
+
+        self.builder
+            .build_call(write_barrier_fn_ptr, &[object_ptr, allocator_handle], "");
+    }
+
     /// Generates IR for a record literal, e.g. `Foo { a: 1.23, b: 4 }`
     fn gen_record_lit(
         &mut self,
         type_expr: ExprId,
         fields: &[hir::RecordLitField],
-    ) -> BasicValueEnum {
+        spread: Option<ExprId>,
+    ) -> Option<BasicValueEnum> {
         let struct_ty = self.infer[type_expr].clone();
         let hir_struct = struct_ty.as_struct().unwrap(); // Can only really get here if the type is a struct
-        let fields: Vec<BasicValueEnum> = fields
-            .iter()
-            .map(|field| self.gen_expr(field.expr).expect("expected a field value"))
+
+        // Field initializers are evaluated in the order in which they are specified, followed by
+        // the base value that provides the remaining fields.
+        let mut field_values: HashMap<Name, BasicValueEnum> = HashMap::new();
+        for field in fields.iter() {
+            let value = self.gen_expr(field.expr)?;
+            field_values.insert(field.name.clone(), value);
+        }
+        let base = match spread {
+            Some(spread) => {
+                let value = self.gen_expr(spread)?;
+                Some(self.opt_deref_value(spread, value).into_struct_value())
+            }
+            None => None,
+        };
+
+        let hir_struct_name = hir_struct.name(self.db.upcast());
+        let args = hir_struct
+            .fields(self.db.upcast())
+            .into_iter()
+            .enumerate()
+            .map(|(idx, field)| {
+                let name = field.name(self.db.upcast());
+                field_values.remove(&name).unwrap_or_else(|| {
+                    let base = base.expect("missing field without a base value");
+                    self.builder
+                        .build_extract_value(
+                            base,
+                            idx as u32,
+                            &format!("{}.{}", hir_struct_name, name),
+                        )
+                        .expect("could not extract field from base value")
+                })
+            })
             .collect();
 
-        self.gen_struct_alloc(hir_struct, fields)
+        Some(self.gen_struct_alloc(hir_struct, args))
     }
 
     /// Generates IR for a named tuple literal, e.g. `Foo(1.23, 4)`
-    fn gen_named_tuple_lit(&mut self, type_expr: ExprId, args: &[ExprId]) -> BasicValueEnum {
+    fn gen_named_tuple_lit(
+        &mut self,
+        type_expr: ExprId,
+        args: &[ExprId],
+    ) -> Option<BasicValueEnum> {
         let struct_ty = self.infer[type_expr].clone();
         let hir_struct = struct_ty.as_struct().unwrap(); // Can only really get here if the type is a struct
-        let args: Vec<BasicValueEnum> = args
+        let args = args
             .iter()
-            .map(|expr| self.gen_expr(*expr).expect("expected a field value"))
-            .collect();
+            .map(|expr| self.gen_expr(*expr))
+            .collect::<Option<Vec<BasicValueEnum>>>()?;
 
-        self.gen_struct_alloc(hir_struct, args)
+        Some(self.gen_struct_alloc(hir_struct, args))
     }
 
     /// Generates IR for a unit struct literal, e.g `Foo`
@@ -481,20 +1199,37 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
                 Statement::Let {
                     pat, initializer, ..
                 } => {
-                    self.gen_let_statement(*pat, *initializer);
+                    // No need to generate code after a binding with a diverging initializer.
+                    self.gen_let_statement(*pat, *initializer)?;
                 }
                 Statement::Expr(expr) => {
                     // No need to generate code after a statement that has a `never` return type.
-                    self.gen_expr(*expr)?;
+                    let value = self.gen_expr(*expr)?;
+                    if self.non_escaping.is_temporary(*expr) {
+                        self.gen_drop(value);
+                    }
                 }
             };
         }
 
-        if let Some(tail) = tail {
-            self.gen_expr(tail)
+        let value = if let Some(tail) = tail {
+            self.gen_expr(tail)?
         } else {
-            Some(self.gen_empty())
+            self.gen_empty()
+        };
+
+        // Objects that are bound in this block can no longer be referenced
+        for statement in statements.iter() {
+            if let Statement::Let { pat, .. } = statement {
+                if self.non_escaping.is_binding(*pat) {
+                    let ptr = self.pat_to_local[pat];
+                    let object = self.builder.build_load(ptr, &self.pat_to_name[pat]);
+                    self.gen_drop(object);
+                }
+            }
         }
+
+        Some(value)
     }
 
     /// Constructs a builder that should be used to emit an `alloca` instruction. These instructions
@@ -513,11 +1248,41 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
         temp_builder
     }
 
-    /// Generate IR for a let statement: `let a:int = 3`
-    fn gen_let_statement(&mut self, pat: PatId, initializer: Option<ExprId>) {
-        let initializer = initializer.and_then(|expr| self.gen_expr(expr));
-
-        match &self.body[pat] {
+    /// Describes the variable that is bound by `pat` and stored in `ptr` in the debug information,
+    /// if it is emitted. `arg_no` is the one-based index of a parameter.
+    fn declare_debug_variable(
+        &self,
+        pat: PatId,
+        ptr: PointerValue,
+        name: &str,
+        arg_no: Option<u32>,
+    ) {
+        let debug_info = match &self.debug_info {
+            Some(debug_info) => debug_info,
+            None => return,
+        };
+        if let Some(line_col) = self.hir_function.pat_line_col(self.db.upcast(), pat) {
+            debug_info.declare_variable(
+                self.db,
+                &self.builder,
+                ptr,
+                name,
+                &self.infer[pat],
+                arg_no,
+                line_col,
+            );
+        }
+    }
+
+    /// Generate IR for a let statement: `let a:int = 3`. Returns `None` if the initializer
+    /// diverges.
+    fn gen_let_statement(&mut self, pat: PatId, initializer: Option<ExprId>) -> Option<()> {
+        let initializer = match initializer {
+            Some(expr) => Some(self.gen_expr(expr)?),
+            None => None,
+        };
+
+        match &self.body[pat] {
             Pat::Bind { name } => {
                 let builder = self.new_alloca_builder();
                 let pat_ty = self.infer[pat].clone();
@@ -529,6 +1294,7 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
                 ))
                 .expect("expected basic type");
                 let ptr = builder.build_alloca(ty, &name.to_string());
+                self.declare_debug_variable(pat, ptr, &name.to_string(), None);
                 self.pat_to_local.insert(pat, ptr);
                 self.pat_to_name.insert(pat, name.to_string());
                 if !(pat_ty.is_empty() || pat_ty.is_never()) {
@@ -540,6 +1306,7 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
             Pat::Wild => {}
             Pat::Missing | Pat::Path(_) => unreachable!(),
         }
+        Some(())
     }
 
     /// Generates IR for looking up a certain path expression.
@@ -610,23 +1377,47 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
     /// Generates IR to calculate a binary operation between two expressions.
     fn gen_binary_op(
         &mut self,
-        _tgt_expr: ExprId,
+        tgt_expr: ExprId,
         lhs: ExprId,
         rhs: ExprId,
         op: BinaryOp,
     ) -> Option<BasicValueEnum> {
+        // Overloaded operators are calls to the function that implements them
+        if let Some(function) = self.infer.operator_resolution(tgt_expr) {
+            let args = [self.gen_expr(lhs)?, self.gen_expr(rhs)?];
+            return self.gen_call(function, &args);
+        }
+
         let lhs_type = self.infer[lhs].clone();
         match lhs_type.as_simple() {
+            // The left hand side diverges, so the operation is never executed
+            Some(TypeCtor::Never) => self.gen_expr(lhs),
             Some(TypeCtor::Bool) => self.gen_binary_op_bool(lhs, rhs, op),
+            Some(TypeCtor::Char) => self.gen_binary_op_char(lhs, rhs, op),
             Some(TypeCtor::Float(_ty)) => self.gen_binary_op_float(lhs, rhs, op),
-            Some(TypeCtor::Int(ty)) => self.gen_binary_op_int(lhs, rhs, op, ty.signedness),
+            Some(TypeCtor::FloatVector(_)) => self.gen_binary_op_float_vector(lhs, rhs, op),
+            Some(TypeCtor::Int(ty)) => {
+                self.gen_binary_op_int(tgt_expr, lhs, rhs, op, ty.signedness)
+            }
+            // Handles can only be compared for equality, which is the same as for integers
+            Some(TypeCtor::Handle) => {
+                self.gen_binary_op_int(tgt_expr, lhs, rhs, op, hir::Signedness::Unsigned)
+            }
             Some(TypeCtor::Struct(s)) => {
+                if let BinaryOp::CmpOp(op) = op {
+                    return self.gen_binary_op_struct_cmp(s, lhs, rhs, op);
+                }
                 if s.data(self.db.upcast()).memory_kind == hir::StructMemoryKind::Value {
                     self.gen_binary_op_value_struct(lhs, rhs, op)
                 } else {
                     self.gen_binary_op_heap_struct(lhs, rhs, op)
                 }
             }
+            // Dynamically typed values, their downcasts and source locations only support plain
+            // assignment
+            Some(TypeCtor::Any) | Some(TypeCtor::Optional(_)) | Some(TypeCtor::SourceLocation) => {
+                self.gen_binary_op_assign(lhs, rhs, op)
+            }
             _ => {
                 let rhs_type = self.infer[rhs].clone();
                 unimplemented!(
@@ -642,7 +1433,10 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
     fn gen_unary_op(&mut self, expr: ExprId, op: UnaryOp) -> Option<BasicValueEnum> {
         let ty = self.infer[expr].clone();
         match ty.as_simple() {
+            // The operand diverges, so the operation is never executed
+            Some(TypeCtor::Never) => self.gen_expr(expr),
             Some(TypeCtor::Float(_ty)) => self.gen_unary_op_float(expr, op),
+            Some(TypeCtor::FloatVector(_)) => self.gen_unary_op_float_vector(expr, op),
             Some(TypeCtor::Int(ty)) => self.gen_unary_op_int(expr, op, ty.signedness),
             Some(TypeCtor::Bool) => self.gen_unary_op_bool(expr, op),
             _ => unimplemented!(
@@ -656,8 +1450,7 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
     fn gen_unary_op_float(&mut self, expr: ExprId, op: UnaryOp) -> Option<BasicValueEnum> {
         let value: FloatValue = self
             .gen_expr(expr)
-            .map(|value| self.opt_deref_value(expr, value))
-            .expect("no value")
+            .map(|value| self.opt_deref_value(expr, value))?
             .into_float_value();
         match op {
             UnaryOp::Neg => Some(self.builder.build_float_neg(value, "neg").into()),
@@ -665,6 +1458,15 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
         }
     }
 
+    /// Generates IR to calculate a unary operation on every lane of a vector.
+    fn gen_unary_op_float_vector(&mut self, expr: ExprId, op: UnaryOp) -> Option<BasicValueEnum> {
+        let value: VectorValue = self.gen_expr(expr)?.into_vector_value();
+        match op {
+            UnaryOp::Neg => Some(self.builder.build_float_neg(value, "neg").into()),
+            _ => unimplemented!("Operator {:?} is not implemented for vectors", op),
+        }
+    }
+
     /// Generates IR to calculate a unary operation on an integer value.
     fn gen_unary_op_int(
         &mut self,
@@ -674,8 +1476,7 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
     ) -> Option<BasicValueEnum> {
         let value: IntValue = self
             .gen_expr(expr)
-            .map(|value| self.opt_deref_value(expr, value))
-            .expect("no value")
+            .map(|value| self.opt_deref_value(expr, value))?
             .into_int_value();
         match op {
             UnaryOp::Neg => {
@@ -694,8 +1495,7 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
     fn gen_unary_op_bool(&mut self, expr: ExprId, op: UnaryOp) -> Option<BasicValueEnum> {
         let value: IntValue = self
             .gen_expr(expr)
-            .map(|value| self.opt_deref_value(expr, value))
-            .expect("no value")
+            .map(|value| self.opt_deref_value(expr, value))?
             .into_int_value();
         match op {
             UnaryOp::Not => Some(self.builder.build_not(value, "not").into()),
@@ -712,13 +1512,11 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
     ) -> Option<BasicValueEnum> {
         let lhs: IntValue = self
             .gen_expr(lhs_expr)
-            .map(|value| self.opt_deref_value(lhs_expr, value))
-            .expect("no lhs value")
+            .map(|value| self.opt_deref_value(lhs_expr, value))?
             .into_int_value();
         let rhs: IntValue = self
             .gen_expr(rhs_expr)
-            .map(|value| self.opt_deref_value(rhs_expr, value))
-            .expect("no rhs value")
+            .map(|value| self.opt_deref_value(rhs_expr, value))?
             .into_int_value();
         match op {
             BinaryOp::ArithOp(op) => Some(self.gen_arith_bin_op_bool(lhs, rhs, op).into()),
@@ -739,6 +1537,37 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
         }
     }
 
+    /// Generates IR to calculate a binary operation between two character values. Characters are
+    /// represented by their unsigned 32-bit Unicode scalar value, so only comparisons and plain
+    /// assignments are supported.
+    fn gen_binary_op_char(
+        &mut self,
+        lhs_expr: ExprId,
+        rhs_expr: ExprId,
+        op: BinaryOp,
+    ) -> Option<BasicValueEnum> {
+        let lhs: IntValue = self
+            .gen_expr(lhs_expr)
+            .map(|value| self.opt_deref_value(lhs_expr, value))?
+            .into_int_value();
+        let rhs: IntValue = self
+            .gen_expr(rhs_expr)
+            .map(|value| self.opt_deref_value(rhs_expr, value))?
+            .into_int_value();
+        match op {
+            BinaryOp::CmpOp(op) => Some(
+                self.gen_cmp_bin_op_int(lhs, rhs, op, hir::Signedness::Unsigned)
+                    .into(),
+            ),
+            BinaryOp::Assignment { op: None } => {
+                let place = self.gen_place_expr(lhs_expr);
+                self.builder.build_store(place, rhs);
+                Some(self.gen_empty())
+            }
+            _ => unimplemented!("Operator {:?} is not implemented for char", op),
+        }
+    }
+
     /// Generates IR to calculate a binary operation between two floating point values.
     fn gen_binary_op_float(
         &mut self,
@@ -748,43 +1577,39 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
     ) -> Option<BasicValueEnum> {
         let lhs = self
             .gen_expr(lhs_expr)
-            .map(|value| self.opt_deref_value(lhs_expr, value))
-            .expect("no lhs value")
+            .map(|value| self.opt_deref_value(lhs_expr, value))?
             .into_float_value();
         let rhs = self
             .gen_expr(rhs_expr)
-            .map(|value| self.opt_deref_value(rhs_expr, value))
-            .expect("no rhs value")
+            .map(|value| self.opt_deref_value(rhs_expr, value))?
             .into_float_value();
         match op {
             BinaryOp::ArithOp(op) => Some(self.gen_arith_bin_op_float(lhs, rhs, op).into()),
-            BinaryOp::CmpOp(op) => {
-                let (name, predicate) = match op {
-                    CmpOp::Eq { negated: false } => ("eq", FloatPredicate::OEQ),
-                    CmpOp::Eq { negated: true } => ("neq", FloatPredicate::ONE),
-                    CmpOp::Ord {
-                        ordering: Ordering::Less,
-                        strict: false,
-                    } => ("lesseq", FloatPredicate::OLE),
-                    CmpOp::Ord {
-                        ordering: Ordering::Less,
-                        strict: true,
-                    } => ("less", FloatPredicate::OLT),
-                    CmpOp::Ord {
-                        ordering: Ordering::Greater,
-                        strict: false,
-                    } => ("greatereq", FloatPredicate::OGE),
-                    CmpOp::Ord {
-                        ordering: Ordering::Greater,
-                        strict: true,
-                    } => ("greater", FloatPredicate::OGT),
+            BinaryOp::CmpOp(op) => Some(self.gen_cmp_bin_op_float(lhs, rhs, op).into()),
+            BinaryOp::Assignment { op } => {
+                let rhs = match op {
+                    Some(op) => self.gen_arith_bin_op_float(lhs, rhs, op),
+                    None => rhs,
                 };
-                Some(
-                    self.builder
-                        .build_float_compare(predicate, lhs, rhs, name)
-                        .into(),
-                )
+                let place = self.gen_place_expr(lhs_expr);
+                self.builder.build_store(place, rhs);
+                Some(self.gen_empty())
             }
+            _ => unimplemented!("Operator {:?} is not implemented for float", op),
+        }
+    }
+
+    /// Generates IR to calculate a binary operation between every pair of lanes of two vectors.
+    fn gen_binary_op_float_vector(
+        &mut self,
+        lhs_expr: ExprId,
+        rhs_expr: ExprId,
+        op: BinaryOp,
+    ) -> Option<BasicValueEnum> {
+        let lhs = self.gen_expr(lhs_expr)?.into_vector_value();
+        let rhs = self.gen_expr(rhs_expr)?.into_vector_value();
+        match op {
+            BinaryOp::ArithOp(op) => Some(self.gen_arith_bin_op_float(lhs, rhs, op).into()),
             BinaryOp::Assignment { op } => {
                 let rhs = match op {
                     Some(op) => self.gen_arith_bin_op_float(lhs, rhs, op),
@@ -794,13 +1619,14 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
                 self.builder.build_store(place, rhs);
                 Some(self.gen_empty())
             }
-            _ => unimplemented!("Operator {:?} is not implemented for float", op),
+            _ => unimplemented!("Operator {:?} is not implemented for vectors", op),
         }
     }
 
     /// Generates IR to calculate a binary operation between two integer values.
     fn gen_binary_op_int(
         &mut self,
+        tgt_expr: ExprId,
         lhs_expr: ExprId,
         rhs_expr: ExprId,
         op: BinaryOp,
@@ -808,22 +1634,21 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
     ) -> Option<BasicValueEnum> {
         let lhs = self
             .gen_expr(lhs_expr)
-            .map(|value| self.opt_deref_value(lhs_expr, value))
-            .expect("no lhs value")
+            .map(|value| self.opt_deref_value(lhs_expr, value))?
             .into_int_value();
         let rhs = self
             .gen_expr(rhs_expr)
-            .map(|value| self.opt_deref_value(rhs_expr, value))
-            .expect("no rhs value")
+            .map(|value| self.opt_deref_value(rhs_expr, value))?
             .into_int_value();
         match op {
-            BinaryOp::ArithOp(op) => {
-                Some(self.gen_arith_bin_op_int(lhs, rhs, op, signedness).into())
-            }
+            BinaryOp::ArithOp(op) => Some(
+                self.gen_arith_bin_op_int(tgt_expr, lhs, rhs, op, signedness)
+                    .into(),
+            ),
             BinaryOp::CmpOp(op) => Some(self.gen_cmp_bin_op_int(lhs, rhs, op, signedness).into()),
             BinaryOp::Assignment { op } => {
                 let rhs = match op {
-                    Some(op) => self.gen_arith_bin_op_int(lhs, rhs, op, signedness),
+                    Some(op) => self.gen_arith_bin_op_int(tgt_expr, lhs, rhs, op, signedness),
                     None => rhs,
                 };
                 let place = self.gen_place_expr(lhs_expr);
@@ -834,6 +1659,29 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
         }
     }
 
+    /// Generates IR to assign a value to a place whose type supports no other binary operations,
+    /// e.g. an `any`.
+    fn gen_binary_op_assign(
+        &mut self,
+        lhs_expr: ExprId,
+        rhs_expr: ExprId,
+        op: BinaryOp,
+    ) -> Option<BasicValueEnum> {
+        let rhs = self.gen_expr(rhs_expr)?;
+        match op {
+            BinaryOp::Assignment { op: None } => {
+                let place = self.gen_place_expr(lhs_expr);
+                self.builder.build_store(place, rhs);
+                Some(self.gen_empty())
+            }
+            _ => unimplemented!(
+                "Operator {:?} is not implemented for {}",
+                op,
+                self.infer[lhs_expr].display(self.db.upcast())
+            ),
+        }
+    }
+
     /// Generates IR to calculate a binary operation between two heap struct values (e.g. a Mun
     /// `struct(gc)`).
     fn gen_binary_op_heap_struct(
@@ -842,10 +1690,7 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
         rhs_expr: ExprId,
         op: BinaryOp,
     ) -> Option<BasicValueEnum> {
-        let rhs = self
-            .gen_expr(rhs_expr)
-            .expect("no rhs value")
-            .into_pointer_value();
+        let rhs = self.gen_expr(rhs_expr)?.into_pointer_value();
         match op {
             BinaryOp::Assignment { op } => {
                 let rhs = match op {
@@ -857,6 +1702,7 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
                 };
                 let place = self.gen_place_expr(lhs_expr);
                 self.builder.build_store(place, rhs);
+                self.gen_write_barrier(lhs_expr);
                 Some(self.gen_empty())
             }
             _ => unimplemented!("Operator {:?} is not implemented for struct", op),
@@ -871,10 +1717,7 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
         rhs_expr: ExprId,
         op: BinaryOp,
     ) -> Option<BasicValueEnum> {
-        let rhs = self
-            .gen_expr(rhs_expr)
-            .expect("no rhs value")
-            .into_struct_value();
+        let rhs = self.gen_expr(rhs_expr)?.into_struct_value();
         match op {
             BinaryOp::Assignment { op } => {
                 let rhs = match op {
@@ -886,12 +1729,128 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
                 };
                 let place = self.gen_place_expr(lhs_expr);
                 self.builder.build_store(place, rhs);
+                self.gen_write_barrier(lhs_expr);
                 Some(self.gen_empty())
             }
             _ => unimplemented!("Operator {:?} is not implemented for struct", op),
         }
     }
 
+    /// Generates IR to compare two structs that derive the comparison, e.g. `#[derive(Eq)]`.
+    fn gen_binary_op_struct_cmp(
+        &mut self,
+        hir_struct: hir::Struct,
+        lhs_expr: ExprId,
+        rhs_expr: ExprId,
+        op: CmpOp,
+    ) -> Option<BasicValueEnum> {
+        let lhs = self
+            .gen_expr(lhs_expr)
+            .map(|value| self.opt_deref_value(lhs_expr, value))?
+            .into_struct_value();
+        let rhs = self
+            .gen_expr(rhs_expr)
+            .map(|value| self.opt_deref_value(rhs_expr, value))?
+            .into_struct_value();
+        Some(self.gen_cmp_struct(hir_struct, lhs, rhs, op).into())
+    }
+
+    /// Generates IR to compare the fields of two struct values in declaration order. Equality
+    /// holds if all fields are equal, whereas an ordering is determined by the first field that
+    /// differs.
+    fn gen_cmp_struct(
+        &mut self,
+        hir_struct: hir::Struct,
+        lhs: StructValue,
+        rhs: StructValue,
+        op: CmpOp,
+    ) -> IntValue {
+        let bool_type = self.db.context().bool_type();
+        let fields = hir_struct.fields(self.db.upcast());
+        let field_values = |builder: &Builder, idx: usize| {
+            let lhs = builder
+                .build_extract_value(lhs, idx as u32, "lhs_field")
+                .expect("could not extract field");
+            let rhs = builder
+                .build_extract_value(rhs, idx as u32, "rhs_field")
+                .expect("could not extract field");
+            (lhs, rhs)
+        };
+        match op {
+            CmpOp::Eq { negated } => {
+                let mut result = bool_type.const_int(1, false);
+                for (idx, field) in fields.iter().enumerate() {
+                    let (lhs, rhs) = field_values(&self.builder, idx);
+                    let ty = field.ty(self.db.upcast());
+                    let eq = self.gen_cmp_field(&ty, lhs, rhs, CmpOp::Eq { negated: false });
+                    result = self.builder.build_and(result, eq, "eq");
+                }
+                if negated {
+                    self.builder.build_not(result, "neq")
+                } else {
+                    result
+                }
+            }
+            CmpOp::Ord { ordering, strict } => {
+                // If all fields are equal, only a non-strict comparison holds
+                let mut result = bool_type.const_int((!strict).into(), false);
+                for (idx, field) in fields.iter().enumerate().rev() {
+                    let (lhs, rhs) = field_values(&self.builder, idx);
+                    let ty = field.ty(self.db.upcast());
+                    let ord = CmpOp::Ord {
+                        ordering,
+                        strict: true,
+                    };
+                    let ord = self.gen_cmp_field(&ty, lhs, rhs, ord);
+                    let eq = self.gen_cmp_field(&ty, lhs, rhs, CmpOp::Eq { negated: false });
+                    let eq_and_rest = self.builder.build_and(eq, result, "eq_and_rest");
+                    result = self.builder.build_or(ord, eq_and_rest, "ord");
+                }
+                result
+            }
+        }
+    }
+
+    /// Generates IR to compare two values of a field of a struct that derives a comparison.
+    fn gen_cmp_field(
+        &mut self,
+        ty: &hir::Ty,
+        lhs: BasicValueEnum,
+        rhs: BasicValueEnum,
+        op: CmpOp,
+    ) -> IntValue {
+        match ty.as_simple() {
+            Some(TypeCtor::Float(_)) => {
+                self.gen_cmp_bin_op_float(lhs.into_float_value(), rhs.into_float_value(), op)
+            }
+            Some(TypeCtor::Int(ty)) => self.gen_cmp_bin_op_int(
+                lhs.into_int_value(),
+                rhs.into_int_value(),
+                op,
+                ty.signedness,
+            ),
+            Some(TypeCtor::Bool) | Some(TypeCtor::Char) => self.gen_cmp_bin_op_int(
+                lhs.into_int_value(),
+                rhs.into_int_value(),
+                op,
+                hir::Signedness::Unsigned,
+            ),
+            Some(TypeCtor::Struct(s)) => {
+                let (lhs, rhs) =
+                    if s.data(self.db.upcast()).memory_kind == hir::StructMemoryKind::GC {
+                        (
+                            deref_heap_value(&self.builder, lhs),
+                            deref_heap_value(&self.builder, rhs),
+                        )
+                    } else {
+                        (lhs, rhs)
+                    };
+                self.gen_cmp_struct(s, lhs.into_struct_value(), rhs.into_struct_value(), op)
+            }
+            _ => unreachable!("the field of a derived comparison must be comparable"),
+        }
+    }
+
     fn gen_arith_bin_op_bool(&mut self, lhs: IntValue, rhs: IntValue, op: ArithOp) -> IntValue {
         match op {
             ArithOp::BitAnd => self.builder.build_and(lhs, rhs, "bit_and"),
@@ -904,6 +1863,30 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
         }
     }
 
+    fn gen_cmp_bin_op_float(&mut self, lhs: FloatValue, rhs: FloatValue, op: CmpOp) -> IntValue {
+        let (name, predicate) = match op {
+            CmpOp::Eq { negated: false } => ("eq", FloatPredicate::OEQ),
+            CmpOp::Eq { negated: true } => ("neq", FloatPredicate::ONE),
+            CmpOp::Ord {
+                ordering: Ordering::Less,
+                strict: false,
+            } => ("lesseq", FloatPredicate::OLE),
+            CmpOp::Ord {
+                ordering: Ordering::Less,
+                strict: true,
+            } => ("less", FloatPredicate::OLT),
+            CmpOp::Ord {
+                ordering: Ordering::Greater,
+                strict: false,
+            } => ("greatereq", FloatPredicate::OGE),
+            CmpOp::Ord {
+                ordering: Ordering::Greater,
+                strict: true,
+            } => ("greater", FloatPredicate::OGT),
+        };
+        self.builder.build_float_compare(predicate, lhs, rhs, name)
+    }
+
     fn gen_cmp_bin_op_int(
         &mut self,
         lhs: IntValue,
@@ -961,11 +1944,19 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
 
     fn gen_arith_bin_op_int(
         &mut self,
+        expr: ExprId,
         lhs: IntValue,
         rhs: IntValue,
         op: ArithOp,
         signedness: hir::Signedness,
     ) -> IntValue {
+        if is_overflow_checked(self.db, op) {
+            self.gen_overflow_check(expr, lhs, rhs, op, signedness);
+        }
+        if op == ArithOp::Divide || op == ArithOp::Remainder {
+            self.gen_division_check(expr, lhs, rhs, op, signedness);
+        }
+
         match op {
             ArithOp::Add => self.builder.build_int_add(lhs, rhs, "add"),
             ArithOp::Subtract => self.builder.build_int_sub(lhs, rhs, "sub"),
@@ -989,12 +1980,159 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
         }
     }
 
-    fn gen_arith_bin_op_float(
+    /// Generates IR that calls the `panic` intrinsic if the integer arithmetic operation `op`
+    /// overflows. The operation is computed with twice the bit width of its operands, so it
+    /// overflows if the result does not fit in the original type.
+    fn gen_overflow_check(
         &mut self,
-        lhs: FloatValue,
-        rhs: FloatValue,
+        expr: ExprId,
+        lhs: IntValue,
+        rhs: IntValue,
         op: ArithOp,
-    ) -> FloatValue {
+        signedness: hir::Signedness,
+    ) {
+        let context = self.db.context();
+        let int_type = lhs.get_type();
+        let wide_type = context.custom_width_int_type(int_type.get_bit_width() * 2);
+        let extend = |builder: &Builder, value: IntValue| {
+            if signedness.is_signed() {
+                builder.build_int_s_extend(value, wide_type, "wide")
+            } else {
+                builder.build_int_z_extend(value, wide_type, "wide")
+            }
+        };
+
+        let wide_lhs = extend(&self.builder, lhs);
+        let wide_rhs = extend(&self.builder, rhs);
+        let (wide_result, message) = match op {
+            ArithOp::Add => (
+                self.builder.build_int_add(wide_lhs, wide_rhs, "wide_add"),
+                "attempt to add with overflow",
+            ),
+            ArithOp::Subtract => (
+                self.builder.build_int_sub(wide_lhs, wide_rhs, "wide_sub"),
+                "attempt to subtract with overflow",
+            ),
+            ArithOp::Multiply => (
+                self.builder.build_int_mul(wide_lhs, wide_rhs, "wide_mul"),
+                "attempt to multiply with overflow",
+            ),
+            _ => unreachable!("{:?} is not checked for overflow", op),
+        };
+        let result = self
+            .builder
+            .build_int_truncate(wide_result, int_type, "result");
+        let overflows = self.builder.build_int_compare(
+            IntPredicate::NE,
+            extend(&self.builder, result),
+            wide_result,
+            "overflows",
+        );
+        self.gen_panic_if(expr, overflows, message);
+    }
+
+    /// Generates IR that calls the `panic` intrinsic if the integer division or remainder of `lhs`
+    /// by `rhs` is undefined. This is the case if `rhs` is zero, or if a signed division overflows
+    /// because the minimum value is divided by `-1`.
+    fn gen_division_check(
+        &mut self,
+        expr: ExprId,
+        lhs: IntValue,
+        rhs: IntValue,
+        op: ArithOp,
+        signedness: hir::Signedness,
+    ) {
+        let int_type = rhs.get_type();
+        let (zero_message, overflow_message) = match op {
+            ArithOp::Divide => (
+                "attempt to divide by zero",
+                "attempt to divide with overflow",
+            ),
+            ArithOp::Remainder => (
+                "attempt to calculate the remainder with a divisor of zero",
+                "attempt to calculate the remainder with overflow",
+            ),
+            _ => unreachable!("{:?} is not a division", op),
+        };
+
+        let is_zero = self.builder.build_int_compare(
+            IntPredicate::EQ,
+            rhs,
+            int_type.const_int(0, false),
+            "is_zero",
+        );
+        self.gen_panic_if(expr, is_zero, zero_message);
+
+        if signedness.is_signed() {
+            let min = self.builder.build_left_shift(
+                int_type.const_int(1, false),
+                int_type.const_int(u64::from(int_type.get_bit_width() - 1), false),
+                "min",
+            );
+            let is_min = self
+                .builder
+                .build_int_compare(IntPredicate::EQ, lhs, min, "is_min");
+            let is_minus_one = self.builder.build_int_compare(
+                IntPredicate::EQ,
+                rhs,
+                int_type.const_all_ones(),
+                "is_minus_one",
+            );
+            let overflows = self.builder.build_and(is_min, is_minus_one, "overflows");
+            self.gen_panic_if(expr, overflows, overflow_message);
+        }
+    }
+
+    /// Generates IR that calls the `panic` intrinsic with the specified `message` and the location
+    /// of `expr` if `condition` is true. Code generation continues in the block in which
+    /// `condition` is false.
+    fn gen_panic_if(&mut self, expr: ExprId, condition: IntValue, message: &str) {
+        let context = self.db.context();
+        let panic_block = context.append_basic_block(&self.fn_value, "panic");
+        let continue_block = context.append_basic_block(&self.fn_value, "continue");
+        self.builder
+            .build_conditional_branch(condition, &panic_block, &continue_block);
+
+        self.builder.position_at_end(&panic_block);
+        self.gen_panic(expr, message);
+
+        self.builder.position_at_end(&continue_block);
+    }
+
+    /// Generates IR that calls the `panic` intrinsic with the specified `message` and the one-based
+    /// line and column of `expr`. The code after the call is unreachable.
+    fn gen_panic(&mut self, expr: ExprId, message: &str) {
+        let panic_fn_ptr = self.dispatch_table.gen_intrinsic_lookup(
+            self.external_globals.dispatch_table,
+            &self.builder,
+            &intrinsics::panic,
+        );
+        let message_ptr = self
+            .builder
+            .build_global_string_ptr(message, "panic_message")
+            .as_pointer_value();
+        let line_col = self
+            .hir_function
+            .expr_line_col(self.db.upcast(), expr)
+            .expect("could not find the source location of an expression");
+        let i32_type = self.db.context().i32_type();
+        self.builder.build_call(
+            panic_fn_ptr,
+            &[
+                message_ptr.into(),
+                i32_type
+                    .const_int(u64::from(line_col.line + 1), false)
+                    .into(),
+                i32_type
+                    .const_int(u64::from(line_col.col + 1), false)
+                    .into(),
+            ],
+            "",
+        );
+        self.builder.build_unreachable();
+    }
+
+    fn gen_arith_bin_op_float<T: FloatMathValue>(&mut self, lhs: T, rhs: T, op: ArithOp) -> T {
         match op {
             ArithOp::Add => self.builder.build_float_add(lhs, rhs, "add"),
             ArithOp::Subtract => self.builder.build_float_sub(lhs, rhs, "sub"),
@@ -1052,7 +2190,38 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
     }
 
     /// Generates IR for a function call.
-    fn gen_call(&mut self, function: hir::Function, args: &[BasicValueEnum]) -> CallSiteValue {
+    /// Generates IR to call the specified function. Returns the value that is returned by the
+    /// function, or `None` if it doesn't return anything.
+    fn gen_call(
+        &mut self,
+        function: hir::Function,
+        args: &[BasicValueEnum],
+    ) -> Option<BasicValueEnum> {
+        let fn_sig = function
+            .ty(self.db.upcast())
+            .callable_sig(self.db.upcast())
+            .unwrap();
+        if !returns_by_pointer(self.db, fn_sig.ret()) {
+            return self
+                .gen_call_site(function, args)
+                .try_as_basic_value()
+                .left();
+        }
+
+        // The function writes its return value to memory that is provided by the caller
+        let ret_ty = self.db.struct_ty(fn_sig.ret().as_struct().unwrap());
+        let ret_ptr = self.new_alloca_builder().build_alloca(ret_ty, "ret");
+        let args: Vec<BasicValueEnum> = std::iter::once(ret_ptr.into())
+            .chain(args.iter().copied())
+            .collect();
+        let call = self.gen_call_site(function, &args);
+        call.add_attribute(AttributeLoc::Param(0), sret_attribute(self.db));
+        Some(self.builder.build_load(ret_ptr, "ret"))
+    }
+
+    /// Generates the IR instruction that calls the specified function with the specified
+    /// arguments.
+    fn gen_call_site(&mut self, function: hir::Function, args: &[BasicValueEnum]) -> CallSiteValue {
         if self.dispatch_table.contains(function) && self.should_use_dispatch_table() {
             let ptr_value = self.dispatch_table.gen_function_lookup(
                 self.db,
@@ -1080,6 +2249,20 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
         }
     }
 
+    /// Generates IR for a call of the function to itself in tail position, which replaces the
+    /// values of the parameters with `args` and jumps back to the start of the function.
+    fn gen_tail_call(&mut self, args: &[BasicValueEnum]) {
+        for (param_ptr, arg) in self.param_ptrs.iter().zip(args) {
+            if let Some(param_ptr) = param_ptr {
+                self.builder.build_store(*param_ptr, *arg);
+            }
+        }
+        let tail_call_block = self
+            .tail_call_block
+            .expect("expected a block to jump to for a tail call");
+        self.builder.build_unconditional_branch(&tail_call_block);
+    }
+
     /// Generates IR for an if statement.
     fn gen_if(
         &mut self,
@@ -1117,7 +2300,7 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
         // Fill the then block
         self.builder.position_at_end(&then_block);
         let then_block_ir = self.gen_expr(then_branch);
-        if !self.infer[then_branch].is_never() {
+        if !self.infer[then_branch].is_never() && !self.is_block_terminated() {
             self.builder.build_unconditional_branch(&merge_block);
         }
         then_block = self.builder.get_insert_block().unwrap();
@@ -1129,7 +2312,7 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
                 .expect("programmer error, then_block is invalid");
             self.builder.position_at_end(&else_block);
             let result_ir = self.gen_expr(*else_branch);
-            if !self.infer[*else_branch].is_never() {
+            if !self.infer[*else_branch].is_never() && !self.is_block_terminated() {
                 self.builder.build_unconditional_branch(&merge_block);
             }
             Some((result_ir, self.builder.get_insert_block().unwrap()))
@@ -1161,9 +2344,14 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
     fn gen_return(&mut self, _expr: ExprId, ret_expr: Option<ExprId>) -> Option<BasicValueEnum> {
         let ret_value = ret_expr.and_then(|expr| self.gen_expr(expr));
 
+        // The returned expression might have jumped elsewhere, like a tail call does
+        if self.is_block_terminated() {
+            return None;
+        }
+
         // Construct a return statement from the returned value of the body
         if let Some(value) = ret_value {
-            self.builder.build_return(Some(&value));
+            self.gen_return_value(value);
         } else {
             self.builder.build_return(None);
         }
@@ -1171,46 +2359,72 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
         None
     }
 
-    fn gen_break(&mut self, _expr: ExprId, break_expr: Option<ExprId>) -> Option<BasicValueEnum> {
+    /// Generates IR to return the specified value from the function, either directly or by
+    /// writing it to the `sret` pointer of the caller.
+    fn gen_return_value(&mut self, value: BasicValueEnum) {
+        if let Some(ret_ptr) = self.ret_ptr {
+            self.builder.build_store(ret_ptr, value);
+            self.builder.build_return(None);
+        } else {
+            self.builder.build_return(Some(&value));
+        }
+    }
+
+    /// Returns the index in `active_loops` of the loop that is targeted by a `break` or
+    /// `continue` with the specified label. Without a label the innermost loop is targeted.
+    fn target_loop(&self, label: Option<&Name>) -> usize {
+        match label {
+            Some(label) => self
+                .active_loops
+                .iter()
+                .rposition(|info| info.label.as_ref() == Some(label))
+                .expect("could not find loop with label"),
+            None => self
+                .active_loops
+                .len()
+                .checked_sub(1)
+                .expect("expected an active loop"),
+        }
+    }
+
+    fn gen_break(
+        &mut self,
+        _expr: ExprId,
+        break_expr: Option<ExprId>,
+        label: Option<&Name>,
+    ) -> Option<BasicValueEnum> {
         let break_value = break_expr.and_then(|expr| self.gen_expr(expr));
-        let loop_info = self.active_loop.as_mut().unwrap();
+        let loop_idx = self.target_loop(label);
         if let Some(break_value) = break_value {
-            loop_info
+            let insert_block = self.builder.get_insert_block().unwrap();
+            self.active_loops[loop_idx]
                 .break_values
-                .push((break_value, self.builder.get_insert_block().unwrap()));
+                .push((break_value, insert_block));
         }
         self.builder
-            .build_unconditional_branch(&loop_info.exit_block);
+            .build_unconditional_branch(&self.active_loops[loop_idx].exit_block);
+        None
+    }
+
+    fn gen_continue(&mut self, _expr: ExprId, label: Option<&Name>) -> Option<BasicValueEnum> {
+        let loop_idx = self.target_loop(label);
+        self.builder
+            .build_unconditional_branch(&self.active_loops[loop_idx].continue_block);
         None
     }
 
     fn gen_loop_block_expr(
         &mut self,
         block: ExprId,
-        exit_block: BasicBlock,
-    ) -> (
-        BasicBlock,
-        Vec<(BasicValueEnum, BasicBlock)>,
-        Option<BasicValueEnum>,
-    ) {
-        // Build a new loop info struct
-        let loop_info = LoopInfo {
-            exit_block,
-            break_values: Vec::new(),
-        };
-
-        // Replace previous loop info
-        let prev_loop = std::mem::replace(&mut self.active_loop, Some(loop_info));
+        loop_info: LoopInfo,
+    ) -> (LoopInfo, Option<BasicValueEnum>) {
+        // Push the loop info of the new loop
+        self.active_loops.push(loop_info);
 
         // Start generating code inside the loop
         let value = self.gen_expr(block);
 
-        let LoopInfo {
-            exit_block,
-            break_values,
-        } = std::mem::replace(&mut self.active_loop, prev_loop).unwrap();
-
-        (exit_block, break_values, value)
+        (self.active_loops.pop().unwrap(), value)
     }
 
     fn gen_while(
@@ -1218,6 +2432,7 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
         _expr: ExprId,
         condition_expr: ExprId,
         body_expr: ExprId,
+        label: Option<Name>,
     ) -> Option<BasicValueEnum> {
         let context = self.db.context();
         let cond_block = context.append_basic_block(&self.fn_value, "whilecond");
@@ -1225,10 +2440,12 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
         let exit_block = context.append_basic_block(&self.fn_value, "afterwhile");
 
         // Insert an explicit fall through from the current block to the condition check
+        let interrupt_counter = self.gen_interrupt_counter();
         self.builder.build_unconditional_branch(&cond_block);
 
         // Generate condition block
         self.builder.position_at_end(&cond_block);
+        self.gen_interrupt_check(interrupt_counter);
         let condition_ir = self
             .gen_expr(condition_expr)
             .map(|value| self.opt_deref_value(condition_expr, value));
@@ -1246,30 +2463,62 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
 
         // Generate loop block
         self.builder.position_at_end(&loop_block);
-        let (exit_block, _, value) = self.gen_loop_block_expr(body_expr, exit_block);
+        let (loop_info, value) = self.gen_loop_block_expr(
+            body_expr,
+            LoopInfo {
+                label,
+                break_values: Vec::new(),
+                exit_block,
+                continue_block: cond_block,
+            },
+        );
         if value.is_some() {
-            self.builder.build_unconditional_branch(&cond_block);
+            self.builder
+                .build_unconditional_branch(&loop_info.continue_block);
         }
 
         // Generate exit block
-        self.builder.position_at_end(&exit_block);
+        self.builder.position_at_end(&loop_info.exit_block);
 
         Some(self.gen_empty())
     }
 
-    fn gen_loop(&mut self, _expr: ExprId, body_expr: ExprId) -> Option<BasicValueEnum> {
+    fn gen_loop(
+        &mut self,
+        _expr: ExprId,
+        body_expr: ExprId,
+        label: Option<Name>,
+    ) -> Option<BasicValueEnum> {
         let context = self.db.context();
         let loop_block = context.append_basic_block(&self.fn_value, "loop");
         let exit_block = context.append_basic_block(&self.fn_value, "exit");
 
         // Insert an explicit fall through from the current block to the loop
+        let interrupt_counter = self.gen_interrupt_counter();
         self.builder.build_unconditional_branch(&loop_block);
 
         // Generate the body of the loop
         self.builder.position_at_end(&loop_block);
-        let (exit_block, break_values, value) = self.gen_loop_block_expr(body_expr, exit_block);
+        self.gen_interrupt_check(interrupt_counter);
+        let (
+            LoopInfo {
+                break_values,
+                exit_block,
+                continue_block,
+                ..
+            },
+            value,
+        ) = self.gen_loop_block_expr(
+            body_expr,
+            LoopInfo {
+                label,
+                break_values: Vec::new(),
+                exit_block,
+                continue_block: loop_block,
+            },
+        );
         if value.is_some() {
-            self.builder.build_unconditional_branch(&loop_block);
+            self.builder.build_unconditional_branch(&continue_block);
         }
 
         // Move the builder to the exit block
@@ -1293,20 +2542,21 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
         receiver_expr: ExprId,
         name: &Name,
     ) -> Option<BasicValueEnum> {
-        let hir_struct = self.infer[receiver_expr]
-            .as_struct()
-            .expect("expected a struct");
-
-        let hir_struct_name = hir_struct.name(self.db.upcast());
-
-        let field_idx = hir_struct
-            .field(self.db.upcast(), name)
-            .expect("expected a struct field")
-            .id()
-            .into_raw()
-            .into();
+        let (hir_struct_name, field_idx) = self.field_index(receiver_expr, name);
 
         let field_ir_name = &format!("{}.{}", hir_struct_name, name);
+        if let Some(TypeCtor::FloatVector(_)) = self.infer[receiver_expr].as_simple() {
+            let vector = self.gen_expr(receiver_expr)?.into_vector_value();
+            let index = self
+                .db
+                .context()
+                .i32_type()
+                .const_int(field_idx.into(), false);
+            return Some(
+                self.builder
+                    .build_extract_element(vector, index, field_ir_name),
+            );
+        }
         if self.is_place_expr(receiver_expr) {
             let receiver_ptr = self.gen_place_expr(receiver_expr);
             let receiver_ptr = self
@@ -1321,46 +2571,223 @@ impl<'a, 'b> BodyIrGenerator<'a, 'b> {
                 Some(self.builder.build_load(field_ptr, &field_ir_name))
             }
         } else {
-            let receiver_value = self.gen_expr(receiver_expr)?;
-            let receiver_value = self.opt_deref_value(receiver_expr, receiver_value);
+            let receiver_object = self.gen_expr(receiver_expr)?;
+            let receiver_value = self.opt_deref_value(receiver_expr, receiver_object);
             let receiver_struct = receiver_value.into_struct_value();
-            Some(
-                self.builder
-                    .build_extract_value(receiver_struct, field_idx, field_ir_name)
-                    .ok_or_else(|| {
-                        format!(
-                            "could not extract field {} (index: {}) from struct {}",
-                            name, field_idx, hir_struct_name
-                        )
-                    })
-                    .unwrap(),
-            )
+            let value = self
+                .builder
+                .build_extract_value(receiver_struct, field_idx, field_ir_name)
+                .ok_or_else(|| {
+                    format!(
+                        "could not extract field {} (index: {}) from struct {}",
+                        name, field_idx, hir_struct_name
+                    )
+                })
+                .unwrap();
+
+            // A temporary object is no longer referenced after its field has been read
+            if self.non_escaping.is_temporary(receiver_expr) {
+                self.gen_drop(receiver_object);
+            }
+            Some(value)
         }
     }
 
-    fn gen_place_field(
+    /// Generates IR for a call to a method of a builtin type.
+    fn gen_method_call(
         &mut self,
-        _expr: ExprId,
+        expr: ExprId,
         receiver_expr: ExprId,
-        name: &Name,
-    ) -> PointerValue {
-        let hir_struct = self.infer[receiver_expr]
+        args: &[ExprId],
+    ) -> Option<BasicValueEnum> {
+        let method = self
+            .infer
+            .method_resolution(expr)
+            .expect("expected a resolved method");
+
+        match method {
+            BuiltinMethod::OptionalIsSome | BuiltinMethod::OptionalUnwrapOr => {
+                self.gen_optional_method_call(method, receiver_expr, args)
+            }
+            _ => self.gen_atomic_method_call(method, receiver_expr, args),
+        }
+    }
+
+    /// Generates IR for a call to one of the methods of an `Optional`.
+    fn gen_optional_method_call(
+        &mut self,
+        method: BuiltinMethod,
+        receiver_expr: ExprId,
+        args: &[ExprId],
+    ) -> Option<BasicValueEnum> {
+        let optional = self.gen_expr(receiver_expr)?.into_struct_value();
+        let is_some = self
+            .builder
+            .build_extract_value(optional, 0, "is_some")
+            .unwrap()
+            .into_int_value();
+        match method {
+            BuiltinMethod::OptionalIsSome => Some(is_some.into()),
+            BuiltinMethod::OptionalUnwrapOr => {
+                let default = self.gen_expr(args[0])?;
+                let value = self
+                    .builder
+                    .build_extract_value(optional, 1, "value")
+                    .unwrap();
+                Some(
+                    self.builder
+                        .build_select(is_some, value, default, "unwrap_or"),
+                )
+            }
+            _ => unreachable!("expected an optional method"),
+        }
+    }
+
+    /// Generates IR for a call to one of the methods of an atomic field.
+    fn gen_atomic_method_call(
+        &mut self,
+        method: BuiltinMethod,
+        receiver_expr: ExprId,
+        args: &[ExprId],
+    ) -> Option<BasicValueEnum> {
+        // Atomic operations are performed directly on the memory of the field
+        let ptr = self.gen_atomic_ptr(receiver_expr);
+        let alignment = self
+            .db
+            .target_data()
+            .get_abi_alignment(&ptr.get_type().get_element_type());
+
+        match method {
+            BuiltinMethod::AtomicLoad => {
+                let value = self.builder.build_load(ptr, "atomic_load");
+                let instruction = value
+                    .as_instruction_value()
+                    .expect("expected a load instruction");
+                instruction
+                    .set_alignment(alignment)
+                    .expect("could not set alignment of atomic load");
+                instruction
+                    .set_atomic_ordering(AtomicOrdering::SequentiallyConsistent)
+                    .expect("could not set ordering of atomic load");
+                Some(value)
+            }
+            BuiltinMethod::AtomicStore => {
+                let value = self.gen_expr(args[0])?;
+                let instruction = self.builder.build_store(ptr, value);
+                instruction
+                    .set_alignment(alignment)
+                    .expect("could not set alignment of atomic store");
+                instruction
+                    .set_atomic_ordering(AtomicOrdering::SequentiallyConsistent)
+                    .expect("could not set ordering of atomic store");
+                Some(self.gen_empty())
+            }
+            BuiltinMethod::AtomicFetchAdd => {
+                let value = self.gen_expr(args[0])?.into_int_value();
+                let previous = self
+                    .builder
+                    .build_atomicrmw(
+                        AtomicRMWBinOp::Add,
+                        ptr,
+                        value,
+                        AtomicOrdering::SequentiallyConsistent,
+                    )
+                    .expect("could not build atomic add");
+                Some(previous.into())
+            }
+            _ => unreachable!("expected an atomic method"),
+        }
+    }
+
+    /// Generates IR that results in the memory address of an atomic value. Atomics can only be
+    /// stored in fields of GC structs, which live on the heap.
+    fn gen_atomic_ptr(&mut self, receiver_expr: ExprId) -> PointerValue {
+        let body = self.body.clone();
+        let (struct_expr, name) = match &body[receiver_expr] {
+            Expr::Field { expr, name } => (*expr, name),
+            _ => unreachable!("atomics can only be accessed through struct fields"),
+        };
+
+        let hir_struct = self.infer[struct_expr]
             .as_struct()
             .expect("expected a struct");
-
         let hir_struct_name = hir_struct.name(self.db.upcast());
+        let field_idx = hir_struct
+            .field(self.db.upcast(), name)
+            .expect("expected a struct field")
+            .id()
+            .into_raw()
+            .into();
+
+        // A GC struct value is a handle, which points to the memory of the struct
+        let handle = self
+            .gen_expr(struct_expr)
+            .expect("expected a struct value")
+            .into_pointer_value();
+        let struct_ptr = self
+            .builder
+            .build_load(handle, "mem_ptr")
+            .into_pointer_value();
+        unsafe {
+            self.builder.build_struct_gep(
+                struct_ptr,
+                field_idx,
+                &format!("{}.{}_ptr", hir_struct_name, name),
+            )
+        }
+    }
 
+    /// Returns the name of the type of the receiver and the index of the field with the specified
+    /// name.
+    fn field_index(&self, receiver_expr: ExprId, name: &Name) -> (String, u32) {
+        let receiver_ty = &self.infer[receiver_expr];
+        if let Some(TypeCtor::SourceLocation) = receiver_ty.as_simple() {
+            let field = SourceLocationField::from_name(name).expect("expected a field");
+            return ("SourceLocation".to_owned(), field.index());
+        }
+        if let Some(TypeCtor::FloatVector(lanes)) = receiver_ty.as_simple() {
+            let component = VectorComponent::from_name(name, lanes).expect("expected a component");
+            return (format!("float{}", lanes), component.index());
+        }
+
+        let hir_struct = receiver_ty.as_struct().expect("expected a struct");
         let field_idx = hir_struct
             .field(self.db.upcast(), name)
             .expect("expected a struct field")
             .id()
             .into_raw()
             .into();
+        (hir_struct.name(self.db.upcast()).to_string(), field_idx)
+    }
+
+    fn gen_place_field(
+        &mut self,
+        _expr: ExprId,
+        receiver_expr: ExprId,
+        name: &Name,
+    ) -> PointerValue {
+        let (hir_struct_name, field_idx) = self.field_index(receiver_expr, name);
 
         let receiver_ptr = self.gen_place_expr(receiver_expr);
         let receiver_ptr = self
             .opt_deref_value(receiver_expr, receiver_ptr.into())
             .into_pointer_value();
+
+        // The lanes of a vector are addressed by their index
+        if let Some(TypeCtor::FloatVector(_)) = self.infer[receiver_expr].as_simple() {
+            let i32_type = self.db.context().i32_type();
+            return unsafe {
+                self.builder.build_in_bounds_gep(
+                    receiver_ptr,
+                    &[
+                        i32_type.const_zero(),
+                        i32_type.const_int(field_idx.into(), false),
+                    ],
+                    &format!("{}.{}_ptr", hir_struct_name, name),
+                )
+            };
+        }
+
         unsafe {
             self.builder.build_struct_gep(
                 receiver_ptr,
@@ -0,0 +1,224 @@
+//! Generation of debug information, which maps the generated machine code back to the Mun source
+//! code. The information is emitted as DWARF, or as CodeView on Windows, so native debuggers and
+//! profilers attribute breakpoints and samples to lines of the source files.
+
+use super::try_convert_any_to_basic;
+use crate::{CodeGenParams, IrDatabase, Module, OptimizationLevel};
+use hir::{line_index::LineCol, HirDisplay, Signedness, Ty, TypeCtor};
+use inkwell::{
+    builder::Builder,
+    debug_info::{
+        AsDIScope, DICompileUnit, DIFlags, DIFlagsConstants, DISubprogram, DIType,
+        DWARFEmissionKind, DWARFSourceLanguage, DebugInfoBuilder,
+    },
+    module::FlagBehavior,
+    values::{FunctionValue, PointerValue},
+};
+
+/// The version of the debug information format that is understood by LLVM 7
+const DEBUG_METADATA_VERSION: u64 = 3;
+
+/// The version of DWARF that is emitted on targets that do not use CodeView
+const DWARF_VERSION: u64 = 4;
+
+// DWARF attribute type encodings, see section 7.8 of the DWARF 4 specification
+const DW_ATE_BOOLEAN: u32 = 0x02;
+const DW_ATE_FLOAT: u32 = 0x04;
+const DW_ATE_SIGNED: u32 = 0x05;
+const DW_ATE_UNSIGNED: u32 = 0x07;
+
+/// The debug information of the LLVM module that is generated for a single source file.
+pub(crate) struct DebugInfo {
+    builder: DebugInfoBuilder,
+    compile_unit: DICompileUnit,
+}
+
+impl DebugInfo {
+    /// Creates the debug information for the module that is generated for `file_id`.
+    pub fn new(db: &dyn IrDatabase, module: &Module, file_id: hir::FileId) -> Self {
+        let context = db.context();
+        module.add_basic_value_flag(
+            "Debug Info Version",
+            FlagBehavior::Warning,
+            context.i32_type().const_int(DEBUG_METADATA_VERSION, false),
+        );
+        if db.target().options.is_like_windows {
+            module.add_basic_value_flag(
+                "CodeView",
+                FlagBehavior::Warning,
+                context.i32_type().const_int(1, false),
+            );
+        } else {
+            module.add_basic_value_flag(
+                "Dwarf Version",
+                FlagBehavior::Warning,
+                context.i32_type().const_int(DWARF_VERSION, false),
+            );
+        }
+
+        // Paths are relative to the source directory of the package
+        let path = db.file_relative_path(file_id);
+        let (builder, compile_unit) = module.create_debug_info_builder(
+            true,
+            // There is no DWARF language code for Mun, C is the most neutral choice
+            DWARFSourceLanguage::C,
+            path.file_name().unwrap_or(""),
+            path.parent().map_or("", |dir| dir.as_str()),
+            "mun",
+            db.optimization_lvl() != OptimizationLevel::None,
+            "",
+            0,
+            "",
+            DWARFEmissionKind::Full,
+            0,
+            false,
+            false,
+        );
+        DebugInfo {
+            builder,
+            compile_unit,
+        }
+    }
+
+    /// Creates the debug information of the specified function and attaches it to `fn_value`.
+    pub fn create_function(
+        &self,
+        db: &dyn IrDatabase,
+        hir_function: hir::Function,
+        fn_value: FunctionValue,
+    ) -> FunctionDebugInfo {
+        let file = self.compile_unit.get_file();
+        let line = hir_function.line_col(db.upcast()).line + 1;
+        let subroutine_type = self
+            .builder
+            .create_subroutine_type(file, None, &[], DIFlags::PUBLIC);
+        let subprogram = self.builder.create_function(
+            self.compile_unit.as_debug_info_scope(),
+            &hir_function.name(db.upcast()).to_string(),
+            Some(&fn_value.get_name().to_string_lossy()),
+            file,
+            line,
+            subroutine_type,
+            hir_function.visibility(db.upcast()) == hir::Visibility::Private,
+            true,
+            line,
+            DIFlags::PUBLIC,
+            db.optimization_lvl() != OptimizationLevel::None,
+        );
+        fn_value.set_subprogram(subprogram);
+        FunctionDebugInfo {
+            debug_info: self,
+            subprogram,
+        }
+    }
+
+    /// Returns the debug information type of `ty`. Only primitive types are described, variables
+    /// of other types are omitted from the debug information.
+    fn ty(&self, db: &dyn IrDatabase, ty: &Ty) -> Option<DIType> {
+        let encoding = match ty.as_simple()? {
+            TypeCtor::Bool => DW_ATE_BOOLEAN,
+            TypeCtor::Float(_) => DW_ATE_FLOAT,
+            TypeCtor::Int(ity) if ity.signedness == Signedness::Signed => DW_ATE_SIGNED,
+            TypeCtor::Int(_) => DW_ATE_UNSIGNED,
+            _ => return None,
+        };
+        let ir_ty = try_convert_any_to_basic(db.type_ir(
+            ty.clone(),
+            CodeGenParams {
+                make_marshallable: false,
+            },
+        ))?;
+        let size_in_bits = db.target_data().get_store_size(&ir_ty) * 8;
+        self.builder
+            .create_basic_type(
+                &ty.display(db.upcast()).to_string(),
+                size_in_bits,
+                encoding,
+                DIFlags::PUBLIC,
+            )
+            .ok()
+            .map(|ty| ty.as_type())
+    }
+
+    /// Resolves all debug information of the module, this must be called before the module is
+    /// compiled.
+    pub fn finalize(&self) {
+        self.builder.finalize()
+    }
+}
+
+/// The debug information of a single function, which is used to attach source locations to the
+/// instructions of its body.
+pub(crate) struct FunctionDebugInfo<'a> {
+    debug_info: &'a DebugInfo,
+    subprogram: DISubprogram,
+}
+
+impl<'a> FunctionDebugInfo<'a> {
+    /// Attaches the zero-based `line_col` to all instructions that are subsequently generated by
+    /// `builder`.
+    pub fn set_location(&self, db: &dyn IrDatabase, builder: &Builder, line_col: LineCol) {
+        let location = self.debug_info.builder.create_debug_location(
+            &db.context(),
+            line_col.line + 1,
+            line_col.col + 1,
+            self.subprogram.as_debug_info_scope(),
+            None,
+        );
+        builder.set_current_debug_location(&db.context(), location);
+    }
+
+    /// Describes a local variable, or a parameter if `arg_no` is specified, that is stored in
+    /// `storage`.
+    pub fn declare_variable(
+        &self,
+        db: &dyn IrDatabase,
+        builder: &Builder,
+        storage: PointerValue,
+        name: &str,
+        ty: &Ty,
+        arg_no: Option<u32>,
+        line_col: LineCol,
+    ) {
+        let di_ty = match self.debug_info.ty(db, ty) {
+            Some(di_ty) => di_ty,
+            None => return,
+        };
+        let di_builder = &self.debug_info.builder;
+        let file = self.debug_info.compile_unit.get_file();
+        let scope = self.subprogram.as_debug_info_scope();
+        let variable = match arg_no {
+            Some(arg_no) => di_builder.create_parameter_variable(
+                scope,
+                name,
+                arg_no,
+                file,
+                line_col.line + 1,
+                di_ty,
+                true,
+                DIFlags::ZERO,
+            ),
+            None => di_builder.create_auto_variable(
+                scope,
+                name,
+                file,
+                line_col.line + 1,
+                di_ty,
+                true,
+                DIFlags::ZERO,
+                0,
+            ),
+        };
+        let location = di_builder.create_debug_location(
+            &db.context(),
+            line_col.line + 1,
+            line_col.col + 1,
+            scope,
+            None,
+        );
+        let block = builder
+            .get_insert_block()
+            .expect("the builder must be positioned in a block");
+        di_builder.insert_declare_at_end(storage, Some(variable), None, location, block);
+    }
+}
@@ -155,6 +155,8 @@ pub(crate) struct DispatchTableBuilder<'a> {
     context: Arc<Context>,
     // The module in which all values live
     module: &'a Module,
+    // The file for which the module is generated
+    file_id: hir::FileId,
     // The target for which to create the dispatch table
     target: Arc<TargetData>,
     // This contains the functions that map to the DispatchTable struct fields
@@ -179,12 +181,14 @@ impl<'a> DispatchTableBuilder<'a> {
     pub fn new(
         db: &'a dyn IrDatabase,
         module: &'a Module,
+        file_id: hir::FileId,
         intrinsics: &BTreeMap<FunctionPrototype, FunctionType>,
     ) -> Self {
         let mut table = DispatchTableBuilder {
             db,
             context: db.context(),
             module,
+            file_id,
             target: db.target_data(),
             function_to_idx: Default::default(),
             prototype_to_idx: Default::default(),
@@ -236,6 +240,11 @@ impl<'a> DispatchTableBuilder<'a> {
             }
         }
 
+        // Overloaded operators call the function that implements them
+        if let Some(function) = infer.operator_resolution(expr_id) {
+            self.collect_fn_def(function);
+        }
+
         // Recurse further
         expr.walk_child_exprs(|expr_id| self.collect_expr(expr_id, body, infer));
     }
@@ -243,11 +252,16 @@ impl<'a> DispatchTableBuilder<'a> {
     /// Collects function call expression from the given expression.
     #[allow(clippy::map_entry)]
     fn collect_fn_def(&mut self, function: hir::Function) {
+        if function::is_called_directly(self.db, self.file_id, function) {
+            return;
+        }
+
         self.ensure_table_ref();
 
         // If the function is not yet contained in the table, add it
         if !self.function_to_idx.contains_key(&function) {
-            let name = function.name(self.db.upcast()).to_string();
+            // The runtime links dispatch table entries to the function table by their export name
+            let name = function.export_name(self.db.upcast());
             let hir_type = function.ty(self.db.upcast());
             let sig = hir_type.callable_sig(self.db.upcast()).unwrap();
             let ir_type = self
@@ -286,7 +300,11 @@ impl<'a> DispatchTableBuilder<'a> {
             self.prototype_to_idx.insert(prototype, index);
             self.function_to_idx.insert(function, index);
 
-            // Recurse further
+            // Recurse further, the calls of functions of other modules are part of the dispatch
+            // table of their own module
+            if !self.is_defined_in_module(function) {
+                return;
+            }
             let fn_body = function.body(self.db.upcast());
             self.collect_expr(
                 fn_body.body_expr(),
@@ -296,6 +314,12 @@ impl<'a> DispatchTableBuilder<'a> {
         }
     }
 
+    /// Returns true if the specified function is defined in one of the files of the assembly for
+    /// which the module is generated.
+    fn is_defined_in_module(&self, function: hir::Function) -> bool {
+        function::is_in_same_assembly(self.db, self.file_id, function)
+    }
+
     /// Collect all the call expressions from the specified body with the given type inference
     /// result.
     pub fn collect_body(&mut self, body: &Arc<Body>, infer: &InferenceResult) {
@@ -330,6 +354,8 @@ impl<'a> DispatchTableBuilder<'a> {
                         // Case external function: Convert to typed null for the given function
                         None => function_type.const_null(),
                         Some(f) if f.is_extern(self.db.upcast()) => function_type.const_null(),
+                        // Case function of another module: Linked by the runtime
+                        Some(f) if !self.is_defined_in_module(f) => function_type.const_null(),
                         // Case mun function: Get the function location as the initializer
                         Some(f) => function::gen_signature(
                             self.db,
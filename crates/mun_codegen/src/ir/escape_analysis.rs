@@ -0,0 +1,138 @@
+//! Determines which heap allocations of a function body are provably not referenced after they go
+//! out of scope. These allocations can be released with the `drop` intrinsic instead of waiting for
+//! the next garbage collection.
+//!
+//! The analysis is deliberately conservative. An object does not escape if it is:
+//!   - a temporary that is only used to access one of its fields, e.g. `Foo { a: 1 }.a`, or that
+//!     is discarded by an expression statement;
+//!   - bound by a `let` statement whose binding is only ever used to access fields, e.g.
+//!     `let foo = Foo { a: 1 }; foo.a`. These objects are dropped at the end of the block that
+//!     contains the binding.
+//!
+//! Any other use of an object, like passing it to a function, returning it or assigning it to
+//! another variable, causes it to escape.
+
+use crate::IrDatabase;
+use hir::{Body, Expr, ExprId, InferenceResult, Pat, PatId, Resolution, Statement};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// The heap allocations of a function body that can be dropped deterministically.
+#[derive(Debug, Default)]
+pub(crate) struct NonEscapingAllocations {
+    temporaries: HashSet<ExprId>,
+    bindings: HashSet<PatId>,
+}
+
+impl NonEscapingAllocations {
+    /// Analyzes the specified body.
+    pub fn new(db: &dyn IrDatabase, body: &Arc<Body>, infer: &InferenceResult) -> Self {
+        let mut result = NonEscapingAllocations::default();
+        let mut escaping_uses = vec![body.body_expr()];
+
+        for (_, expr) in body.exprs() {
+            match expr {
+                Expr::Field { expr: receiver, .. } => {
+                    if is_heap_allocation(db, body, infer, *receiver) {
+                        result.temporaries.insert(*receiver);
+                    }
+                }
+                Expr::Block { statements, .. } => {
+                    for statement in statements {
+                        match statement {
+                            Statement::Let {
+                                pat,
+                                initializer: Some(initializer),
+                                ..
+                            } => {
+                                if let Pat::Bind { .. } = body[*pat] {
+                                    if is_heap_allocation(db, body, infer, *initializer) {
+                                        result.bindings.insert(*pat);
+                                    }
+                                }
+                            }
+                            Statement::Expr(expr) => {
+                                if is_heap_allocation(db, body, infer, *expr) {
+                                    result.temporaries.insert(*expr);
+                                }
+                            }
+                            Statement::Let { .. } => (),
+                        }
+                    }
+                }
+                _ => (),
+            }
+
+            // Accessing a field is the only use that does not let the receiver escape
+            expr.walk_child_exprs(|child| match expr {
+                Expr::Field { expr: receiver, .. } if *receiver == child => (),
+                _ => escaping_uses.push(child),
+            });
+        }
+
+        for expr in escaping_uses {
+            if let Expr::Path(path) = &body[expr] {
+                let resolver = hir::resolver_for_expr(body.clone(), db.upcast(), expr);
+                if let Some(Resolution::LocalBinding(pat)) = resolver
+                    .resolve_path_without_assoc_items(db.upcast(), path)
+                    .take_values()
+                {
+                    result.bindings.remove(&pat);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Returns true if there are no allocations that can be dropped.
+    pub fn is_empty(&self) -> bool {
+        self.temporaries.is_empty() && self.bindings.is_empty()
+    }
+
+    /// Returns true if the object that is allocated by the specified expression can be dropped
+    /// right after it is used.
+    pub fn is_temporary(&self, expr: ExprId) -> bool {
+        self.temporaries.contains(&expr)
+    }
+
+    /// Returns true if the object that is bound by the specified pattern can be dropped at the end
+    /// of the block that contains the binding.
+    pub fn is_binding(&self, pat: PatId) -> bool {
+        self.bindings.contains(&pat)
+    }
+}
+
+/// Returns true if the specified expression allocates a new garbage collected object.
+fn is_heap_allocation(
+    db: &dyn IrDatabase,
+    body: &Arc<Body>,
+    infer: &InferenceResult,
+    expr: ExprId,
+) -> bool {
+    let is_gc_struct = infer[expr].as_struct().map_or(false, |s| {
+        s.data(db.upcast()).memory_kind == hir::StructMemoryKind::GC
+    });
+    if !is_gc_struct {
+        return false;
+    }
+
+    match &body[expr] {
+        Expr::RecordLit { .. } => true,
+        Expr::Call { callee, .. } => match infer[*callee].as_callable_def() {
+            Some(hir::CallableDef::Struct(_)) => true,
+            _ => false,
+        },
+        Expr::Path(path) => {
+            let resolver = hir::resolver_for_expr(body.clone(), db.upcast(), expr);
+            match resolver
+                .resolve_path_without_assoc_items(db.upcast(), path)
+                .take_values()
+            {
+                Some(Resolution::Def(hir::ModuleDef::Struct(_))) => true,
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
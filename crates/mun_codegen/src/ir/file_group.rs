@@ -1,7 +1,7 @@
 use super::{
     adt,
     dispatch_table::{DispatchTable, DispatchTableBuilder},
-    intrinsics,
+    function, intrinsics,
     type_table::{TypeTable, TypeTableBuilder},
 };
 use crate::value::{IrTypeContext, IrValueContext};
@@ -24,18 +24,26 @@ pub struct FileGroupIR {
     pub(crate) allocator_handle_type: Option<PointerType>,
 }
 
+/// Returns the definitions of all files that are compiled into the same assembly as the specified
+/// file.
+pub(crate) fn assembly_definitions(db: &dyn IrDatabase, file_id: hir::FileId) -> Vec<ModuleDef> {
+    db.assembly_files(file_id)
+        .iter()
+        .flat_map(|file_id| db.module_data(*file_id).definitions().to_vec())
+        .collect()
+}
+
 /// Generates IR that is shared among the group's files.
-/// TODO: Currently, a group always consists of a single file. Need to add support for multiple
-/// files using something like `FileGroupId`.
 pub(crate) fn ir_query(db: &dyn IrDatabase, file_id: hir::FileId) -> Arc<FileGroupIR> {
     let llvm_module = db.context().create_module("group_name");
+    let definitions = assembly_definitions(db, file_id);
 
     // Use a `BTreeMap` to guarantee deterministically ordered output.
     let mut intrinsics_map = BTreeMap::new();
     let mut needs_alloc = false;
 
     // Collect all intrinsic functions, wrapper function, and generate struct declarations.
-    for def in db.module_data(file_id).definitions() {
+    for def in definitions.iter() {
         match def {
             ModuleDef::Function(f) if !f.is_extern(db.upcast()) => {
                 intrinsics::collect_fn_body(
@@ -45,6 +53,9 @@ pub(crate) fn ir_query(db: &dyn IrDatabase, file_id: hir::FileId) -> Arc<FileGro
                     &f.body(db.upcast()),
                     &f.infer(db.upcast()),
                 );
+                if function::has_stack_check(db, *f) {
+                    intrinsics::collect_stack_check(db, &mut intrinsics_map);
+                }
 
                 let fn_sig = f.ty(db.upcast()).callable_sig(db.upcast()).unwrap();
                 if !f.data(db.upcast()).visibility().is_private()
@@ -62,8 +73,9 @@ pub(crate) fn ir_query(db: &dyn IrDatabase, file_id: hir::FileId) -> Arc<FileGro
     }
 
     // Collect all exposed functions' bodies.
-    let mut dispatch_table_builder = DispatchTableBuilder::new(db, &llvm_module, &intrinsics_map);
-    for def in db.module_data(file_id).definitions() {
+    let mut dispatch_table_builder =
+        DispatchTableBuilder::new(db, &llvm_module, file_id, &intrinsics_map);
+    for def in definitions.iter() {
         if let ModuleDef::Function(f) = def {
             if !f.data(db.upcast()).visibility().is_private() && !f.is_extern(db.upcast()) {
                 let body = f.body(db.upcast());
@@ -91,7 +103,7 @@ pub(crate) fn ir_query(db: &dyn IrDatabase, file_id: hir::FileId) -> Arc<FileGro
         TypeTableBuilder::new(db, &value_context, intrinsics_map.keys(), &dispatch_table);
 
     // Collect all used types
-    for def in db.module_data(file_id).definitions() {
+    for def in definitions.iter() {
         match def {
             ModuleDef::Struct(s) => {
                 type_table_builder.collect_struct(*s);
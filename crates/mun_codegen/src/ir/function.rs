@@ -1,11 +1,154 @@
-use crate::ir::{body::BodyIrGenerator, dispatch_table::DispatchTable, type_table::TypeTable};
+use crate::ir::{
+    body::BodyIrGenerator,
+    debug_info::DebugInfo,
+    dispatch_table::DispatchTable,
+    file_group::{assembly_definitions, FileGroupIR},
+    ty::returns_by_pointer,
+    type_table::TypeTable,
+};
+use crate::value::Global;
 use crate::{CodeGenParams, IrDatabase, Module, OptimizationLevel};
+use hir::{InlineKind, ModuleDef};
+use inkwell::attributes::{Attribute, AttributeLoc};
 use inkwell::passes::{PassManager, PassManagerBuilder};
 use inkwell::types::AnyTypeEnum;
 use inkwell::values::FunctionValue;
 
 use super::body::ExternalGlobals;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// The IR generated for a single function.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FunctionIR {
+    /// The original function
+    pub function: hir::Function,
+    /// The LLVM module that contains the IR of the function and its wrapper, if it has one.
+    /// Other functions are only declared.
+    pub llvm_module: Module,
+}
+
+/// Generates IR for the specified function. Every function is generated into its own LLVM module,
+/// so editing the body of a function only regenerates the IR of that function. The modules of the
+/// functions of a file are linked together by the `file_ir` query.
+pub(crate) fn ir_query(db: &dyn IrDatabase, function: hir::Function) -> Arc<FunctionIR> {
+    let file_id = function.module(db.upcast()).file_id();
+    let llvm_module = db.context().create_module(&format!(
+        "{}::{}",
+        db.file_relative_path(file_id),
+        function.name(db.upcast())
+    ));
+
+    let group_ir = db.group_ir(file_id);
+
+    // Declare all functions of the assembly, so they can be called directly.
+    // Use a `BTreeMap` to guarantee deterministically ordered output.
+    let mut functions = BTreeMap::new();
+    for def in assembly_definitions(db, file_id).iter() {
+        if let ModuleDef::Function(f) = def {
+            if !f.is_extern(db.upcast()) {
+                let fun = gen_signature(
+                    db,
+                    *f,
+                    &llvm_module,
+                    CodeGenParams {
+                        make_marshallable: false,
+                    },
+                );
+                functions.insert(*f, fun);
+            }
+        }
+    }
+
+    let fn_sig = function.ty(db.upcast()).callable_sig(db.upcast()).unwrap();
+    let wrapper_function = if !function.data(db.upcast()).visibility().is_private()
+        && !fn_sig.marshallable(db.upcast())
+    {
+        Some(gen_signature(
+            db,
+            function,
+            &llvm_module,
+            CodeGenParams {
+                make_marshallable: true,
+            },
+        ))
+    } else {
+        None
+    };
+
+    let external_globals = declare_external_globals(&group_ir, &llvm_module);
+
+    // Construct requirements for generating the bodies
+    let fn_pass_manager = create_pass_manager(&llvm_module, db.optimization_lvl());
+    let debug_info = if db.debug_info() {
+        Some(DebugInfo::new(db, &llvm_module, file_id))
+    } else {
+        None
+    };
+
+    // Mun functions unwind when they panic, so their frames require unwind tables
+    let llvm_function = functions[&function];
+    add_uwtable_attribute(db, llvm_function);
+    if let Some(wrapper_function) = wrapper_function {
+        add_uwtable_attribute(db, wrapper_function);
+    }
+
+    gen_body(
+        db,
+        &llvm_module,
+        (function, llvm_function),
+        &functions,
+        &group_ir.dispatch_table,
+        &group_ir.type_table,
+        external_globals.clone(),
+        debug_info.as_ref(),
+    );
+    fn_pass_manager.run_on(&llvm_function);
+
+    if let Some(wrapper_function) = wrapper_function {
+        gen_wrapper_body(
+            db,
+            &llvm_module,
+            (function, wrapper_function),
+            &functions,
+            &group_ir.dispatch_table,
+            &group_ir.type_table,
+            external_globals,
+        );
+        fn_pass_manager.run_on(&wrapper_function);
+    }
+
+    if let Some(debug_info) = &debug_info {
+        debug_info.finalize();
+    }
+
+    Arc::new(FunctionIR {
+        function,
+        llvm_module,
+    })
+}
+
+/// Declares the globals of the group that are referenced by function bodies in the specified
+/// module.
+fn declare_external_globals(group_ir: &FileGroupIR, llvm_module: &Module) -> ExternalGlobals {
+    let alloc_handle = group_ir
+        .allocator_handle_type
+        .map(|ty| llvm_module.add_global(ty, None, "allocatorHandle"));
+    let dispatch_table = group_ir
+        .dispatch_table
+        .ty()
+        .map(|ty| llvm_module.add_global(ty, None, "dispatchTable"));
+    let type_table = if group_ir.type_table.is_empty() {
+        None
+    } else {
+        Some(llvm_module.add_global(group_ir.type_table.ty(), None, TypeTable::NAME))
+    };
+    ExternalGlobals {
+        alloc_handle,
+        dispatch_table,
+        type_table: type_table.map(|g| unsafe { Global::from_raw(g) }),
+    }
+}
 
 /// Constructs a PassManager to optimize functions for the given optimization level.
 pub(crate) fn create_pass_manager(
@@ -33,7 +176,7 @@ pub(crate) fn gen_signature(
     params: CodeGenParams,
 ) -> FunctionValue {
     let name = {
-        let name = f.name(db.upcast()).to_string();
+        let name = symbol_name(db, f);
         if params.make_marshallable {
             format!("{}_wrapper", name)
         } else {
@@ -41,24 +184,131 @@ pub(crate) fn gen_signature(
         }
     };
 
-    if let AnyTypeEnum::FunctionType(ty) = db.type_ir(f.ty(db.upcast()), params) {
+    let value = if let AnyTypeEnum::FunctionType(ty) = db.type_ir(f.ty(db.upcast()), params) {
         module.add_function(&name, ty, None)
     } else {
         panic!("not a function type")
+    };
+
+    // Wrappers only exist to be called from the runtime, so they are never inlined
+    if !params.make_marshallable {
+        let fn_sig = f.ty(db.upcast()).callable_sig(db.upcast()).unwrap();
+        if returns_by_pointer(db, fn_sig.ret()) {
+            value.add_attribute(AttributeLoc::Param(0), sret_attribute(db));
+        }
+
+        if let Some(inline) = f.inline(db.upcast()) {
+            let kind = match inline {
+                InlineKind::Hint => "inlinehint",
+                InlineKind::Always => "alwaysinline",
+                InlineKind::Never => "noinline",
+            };
+            value.add_attribute(
+                AttributeLoc::Function,
+                db.context()
+                    .create_enum_attribute(Attribute::get_named_enum_kind_id(kind), 0),
+            );
+        }
     }
+
+    value
+}
+
+/// Marks the specified function as requiring unwind tables, so the runtime can unwind its frame.
+fn add_uwtable_attribute(db: &dyn IrDatabase, function: FunctionValue) {
+    function.add_attribute(
+        AttributeLoc::Function,
+        db.context()
+            .create_enum_attribute(Attribute::get_named_enum_kind_id("uwtable"), 0),
+    );
+}
+
+/// Returns the attribute that marks the parameter through which a function returns its value.
+pub(crate) fn sret_attribute(db: &dyn IrDatabase) -> Attribute {
+    db.context()
+        .create_enum_attribute(Attribute::get_named_enum_kind_id("sret"), 0)
+}
+
+/// Returns true if the specified function checks at its entry whether the stack is about to
+/// overflow, in which case it panics. Only functions that can (indirectly) call themselves are
+/// checked, as runaway recursion is what exhausts the stack.
+pub(crate) fn has_stack_check(db: &dyn IrDatabase, f: hir::Function) -> bool {
+    let file_id = f.module(db.upcast()).file_id();
+    db.call_graph(db.file_source_root(file_id)).is_recursive(f)
+}
+
+/// Returns true if calls from the specified file to the specified function bypass the dispatch
+/// table. Functions that should be inlined are called directly, so LLVM is able to inline them.
+/// This is only possible if the function is part of the same assembly as its caller, functions of
+/// other assemblies are linked by the runtime. Calls to functions of other files of the assembly
+/// are resolved when the files are linked into the assembly module, after which the link-time
+/// optimization step inlines them.
+pub(crate) fn is_called_directly(
+    db: &dyn IrDatabase,
+    caller_file_id: hir::FileId,
+    f: hir::Function,
+) -> bool {
+    if f.is_extern(db.upcast()) || !is_in_same_assembly(db, caller_file_id, f) {
+        return false;
+    }
+    match f.inline(db.upcast()) {
+        Some(InlineKind::Hint) | Some(InlineKind::Always) => true,
+        Some(InlineKind::Never) | None => false,
+    }
+}
+
+/// Returns true if the specified function is compiled into the same assembly as the specified
+/// file.
+pub(crate) fn is_in_same_assembly(
+    db: &dyn IrDatabase,
+    file_id: hir::FileId,
+    f: hir::Function,
+) -> bool {
+    db.assembly_group(f.module(db.upcast()).file_id()) == db.assembly_group(file_id)
+}
+
+/// Returns the name of the LLVM function of the specified function. If all files are compiled
+/// into a single assembly, the name is prefixed with the path of the module that defines the
+/// function, so functions with the same name in different files do not collide.
+pub(crate) fn symbol_name(db: &dyn IrDatabase, f: hir::Function) -> String {
+    if db.single_assembly() {
+        qualified_name(db, f)
+    } else {
+        f.name(db.upcast()).to_string()
+    }
+}
+
+/// Returns the name of the specified function prefixed with the path of the module that defines
+/// it, e.g. `ai::attack`. Functions of the root module are not prefixed.
+pub(crate) fn qualified_name(db: &dyn IrDatabase, f: hir::Function) -> String {
+    let name = f.name(db.upcast()).to_string();
+    let file_id = f.module(db.upcast()).file_id();
+    let module_tree = db.module_tree(db.file_source_root(file_id));
+    let path = module_tree
+        .module_for_file(file_id)
+        .map(|module| module_tree.path(module))
+        .unwrap_or_default();
+    path.iter()
+        .map(ToString::to_string)
+        .chain(std::iter::once(name))
+        .collect::<Vec<_>>()
+        .join("::")
 }
 
 /// Generates the body of a `hir::Function` for an associated `FunctionValue`.
 pub(crate) fn gen_body<'a, 'b>(
     db: &'a dyn IrDatabase,
+    module: &'a Module,
     function: (hir::Function, FunctionValue),
-    llvm_functions: &'a HashMap<hir::Function, FunctionValue>,
+    llvm_functions: &'a BTreeMap<hir::Function, FunctionValue>,
     dispatch_table: &'b DispatchTable,
     type_table: &'b TypeTable,
     external_globals: ExternalGlobals,
+    debug_info: Option<&'b DebugInfo>,
 ) {
     let mut code_gen = BodyIrGenerator::new(
         db,
+        module,
         function,
         llvm_functions,
         dispatch_table,
@@ -68,6 +318,9 @@ pub(crate) fn gen_body<'a, 'b>(
         },
         external_globals,
     );
+    if let Some(debug_info) = debug_info {
+        code_gen.set_debug_info(debug_info.create_function(db, function.0, function.1));
+    }
 
     code_gen.gen_fn_body();
 }
@@ -76,14 +329,16 @@ pub(crate) fn gen_body<'a, 'b>(
 /// `FunctionValue`
 pub(crate) fn gen_wrapper_body<'a, 'b>(
     db: &'a dyn IrDatabase,
+    module: &'a Module,
     function: (hir::Function, FunctionValue),
-    llvm_functions: &'a HashMap<hir::Function, FunctionValue>,
+    llvm_functions: &'a BTreeMap<hir::Function, FunctionValue>,
     dispatch_table: &'b DispatchTable,
     type_table: &'b TypeTable,
     external_globals: ExternalGlobals,
 ) {
     let mut code_gen = BodyIrGenerator::new(
         db,
+        module,
         function,
         llvm_functions,
         dispatch_table,
@@ -1,5 +1,6 @@
 use crate::intrinsics::{self, Intrinsic};
 use crate::ir::dispatch_table::FunctionPrototype;
+use crate::ir::escape_analysis::NonEscapingAllocations;
 use crate::IrDatabase;
 use hir::{Body, Expr, ExprId, InferenceResult};
 use inkwell::types::FunctionType;
@@ -33,7 +34,6 @@ fn collect_expr(
         match infer[*callee].as_callable_def() {
             Some(hir::CallableDef::Struct(_)) => {
                 collect_intrinsic(db, entries, &intrinsics::new);
-                // self.collect_intrinsic(module, entries, &intrinsics::drop);
                 *needs_alloc = true;
             }
             Some(hir::CallableDef::Function(_)) => (),
@@ -41,9 +41,54 @@ fn collect_expr(
         }
     }
 
+    // Integer arithmetic calls `panic` when it overflows (if checked) or divides by zero
+    if let Expr::BinaryOp {
+        lhs, op: Some(op), ..
+    } = expr
+    {
+        let arith_op = match op {
+            hir::BinaryOp::ArithOp(op) | hir::BinaryOp::Assignment { op: Some(op) } => Some(*op),
+            _ => None,
+        };
+        let is_int = match infer[*lhs].as_simple() {
+            Some(hir::TypeCtor::Int(_)) => true,
+            _ => false,
+        };
+        if is_int
+            && infer.operator_resolution(expr_id).is_none()
+            && arith_op.map_or(false, |op| can_panic(db, op))
+        {
+            collect_intrinsic(db, entries, &intrinsics::panic);
+        }
+    }
+
+    match expr {
+        Expr::Panic { .. } | Expr::Assert { .. } => {
+            collect_intrinsic(db, entries, &intrinsics::panic);
+        }
+        // Every iteration of a loop checks whether the invocation was interrupted
+        Expr::Loop { .. } | Expr::While { .. } => {
+            collect_intrinsic(db, entries, &intrinsics::interrupt_counter);
+            collect_intrinsic(db, entries, &intrinsics::interrupt_check);
+        }
+        _ => (),
+    }
+
+    // Storing references in a heap-allocated object notifies the allocator
+    if let Expr::BinaryOp {
+        lhs,
+        op: Some(hir::BinaryOp::Assignment { .. }),
+        ..
+    } = expr
+    {
+        if needs_write_barrier(db, body, infer, *lhs) {
+            collect_intrinsic(db, entries, &intrinsics::write_barrier);
+            *needs_alloc = true;
+        }
+    }
+
     if let Expr::RecordLit { .. } = expr {
         collect_intrinsic(db, entries, &intrinsics::new);
-        // self.collect_intrinsic(module, entries, &intrinsics::drop);
         *needs_alloc = true;
     }
 
@@ -56,7 +101,6 @@ fn collect_expr(
 
         if let hir::Resolution::Def(hir::ModuleDef::Struct(_)) = resolution {
             collect_intrinsic(db, entries, &intrinsics::new);
-            // self.collect_intrinsic( module, entries, &intrinsics::drop);
             *needs_alloc = true;
         }
     }
@@ -65,6 +109,71 @@ fn collect_expr(
     expr.walk_child_exprs(|expr_id| collect_expr(db, entries, needs_alloc, expr_id, body, infer))
 }
 
+/// Returns true if assigning to the place expression `expr` stores references to heap-allocated
+/// objects in another heap-allocated object, which requires calling the `write_barrier` intrinsic.
+pub(crate) fn needs_write_barrier(
+    db: &dyn IrDatabase,
+    body: &Body,
+    infer: &InferenceResult,
+    expr: ExprId,
+) -> bool {
+    contains_gc_references(db, &infer[expr]) && gc_object_receiver(db, body, infer, expr).is_some()
+}
+
+/// Returns the receiver of the innermost field access of the place expression `expr` that refers
+/// to a heap-allocated object, if any.
+pub(crate) fn gc_object_receiver(
+    db: &dyn IrDatabase,
+    body: &Body,
+    infer: &InferenceResult,
+    expr: ExprId,
+) -> Option<ExprId> {
+    match &body[expr] {
+        Expr::Field { expr: receiver, .. } => {
+            let is_gc_struct = infer[*receiver].as_struct().map_or(false, |s| {
+                s.data(db.upcast()).memory_kind == hir::StructMemoryKind::GC
+            });
+            if is_gc_struct {
+                Some(*receiver)
+            } else {
+                gc_object_receiver(db, body, infer, *receiver)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Returns true if a value of type `ty` contains references to heap-allocated objects.
+fn contains_gc_references(db: &dyn IrDatabase, ty: &hir::Ty) -> bool {
+    match ty.as_struct() {
+        Some(s) => match s.data(db.upcast()).memory_kind {
+            hir::StructMemoryKind::GC => true,
+            hir::StructMemoryKind::Value => s
+                .fields(db.upcast())
+                .into_iter()
+                .any(|field| contains_gc_references(db, &field.ty(db.upcast()))),
+        },
+        None => false,
+    }
+}
+
+/// Returns true if the specified integer arithmetic operation is checked for overflow.
+pub(crate) fn is_overflow_checked(db: &dyn IrDatabase, op: hir::ArithOp) -> bool {
+    db.overflow_checks()
+        && match op {
+            hir::ArithOp::Add | hir::ArithOp::Subtract | hir::ArithOp::Multiply => true,
+            _ => false,
+        }
+}
+
+/// Returns true if the specified integer arithmetic operation can call the `panic` intrinsic.
+fn can_panic(db: &dyn IrDatabase, op: hir::ArithOp) -> bool {
+    match op {
+        hir::ArithOp::Divide | hir::ArithOp::Remainder => true,
+        op => is_overflow_checked(db, op),
+    }
+}
+
 pub fn collect_fn_body(
     db: &dyn IrDatabase,
     entries: &mut IntrinsicsMap,
@@ -73,6 +182,17 @@ pub fn collect_fn_body(
     infer: &InferenceResult,
 ) {
     collect_expr(db, entries, needs_alloc, body.body_expr(), body, infer);
+
+    // Heap allocations that do not escape the function are released with the `drop` intrinsic
+    if !NonEscapingAllocations::new(db, body, infer).is_empty() {
+        collect_intrinsic(db, entries, &intrinsics::drop);
+    }
+}
+
+/// Collects the intrinsics that are called to check whether a function overflows the stack.
+pub fn collect_stack_check(db: &dyn IrDatabase, entries: &mut IntrinsicsMap) {
+    collect_intrinsic(db, entries, &intrinsics::stack_limit);
+    collect_intrinsic(db, entries, &intrinsics::panic);
 }
 
 pub fn collect_wrapper_body(
@@ -81,6 +201,5 @@ pub fn collect_wrapper_body(
     needs_alloc: &mut bool,
 ) {
     collect_intrinsic(db, entries, &intrinsics::new);
-    // self.collect_intrinsic(entries, &intrinsics::drop, module);
     *needs_alloc = true;
 }
@@ -0,0 +1,57 @@
+//! Finds the calls of a function to itself whose result is immediately returned. These calls are
+//! compiled to a jump back to the start of the function instead of a call, so self-recursive
+//! functions run in constant stack space.
+//!
+//! A call is in tail position if it is:
+//!   - the value of a `return` expression;
+//!   - the tail of a block that is in tail position;
+//!   - a branch of an `if` expression that is in tail position.
+
+use hir::{Body, CallableDef, Expr, ExprId, InferenceResult};
+use std::collections::HashSet;
+
+/// Returns the calls to `function` in `body` that are in tail position.
+pub(crate) fn tail_self_calls(
+    function: hir::Function,
+    body: &Body,
+    infer: &InferenceResult,
+) -> HashSet<ExprId> {
+    let mut calls = HashSet::new();
+    collect_tail_calls(function, body, infer, body.body_expr(), &mut calls);
+    for (_, expr) in body.exprs() {
+        if let Expr::Return { expr: Some(expr) } = expr {
+            collect_tail_calls(function, body, infer, *expr, &mut calls);
+        }
+    }
+    calls
+}
+
+fn collect_tail_calls(
+    function: hir::Function,
+    body: &Body,
+    infer: &InferenceResult,
+    expr: ExprId,
+    calls: &mut HashSet<ExprId>,
+) {
+    match &body[expr] {
+        Expr::Block {
+            tail: Some(tail), ..
+        } => collect_tail_calls(function, body, infer, *tail, calls),
+        Expr::If {
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            collect_tail_calls(function, body, infer, *then_branch, calls);
+            if let Some(else_branch) = else_branch {
+                collect_tail_calls(function, body, infer, *else_branch, calls);
+            }
+        }
+        Expr::Call { callee, .. } => {
+            if infer[*callee].as_callable_def() == Some(CallableDef::Function(function)) {
+                calls.insert(expr);
+            }
+        }
+        _ => (),
+    }
+}
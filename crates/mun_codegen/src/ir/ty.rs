@@ -4,8 +4,8 @@ use crate::{
     CodeGenParams, IrDatabase,
 };
 use hir::{
-    ApplicationTy, CallableDef, FloatBitness, FloatTy, IntBitness, IntTy, ResolveBitness, Ty,
-    TypeCtor,
+    ApplicationTy, CallableDef, FloatBitness, FloatTy, IntBitness, IntTy, PrimitiveTy,
+    ResolveBitness, Ty, TypeCtor,
 };
 use inkwell::{
     types::{AnyTypeEnum, BasicType, BasicTypeEnum, FloatType, IntType, StructType},
@@ -22,6 +22,15 @@ pub(crate) fn ir_query(db: &dyn IrDatabase, ty: Ty, params: CodeGenParams) -> An
             TypeCtor::Float(fty) => float_ty_query(db, fty).into(),
             TypeCtor::Int(ity) => int_ty_query(db, ity).into(),
             TypeCtor::Bool => AnyTypeEnum::IntType(context.bool_type()),
+            TypeCtor::Char => AnyTypeEnum::IntType(context.i32_type()),
+            // An atomic has the same memory representation as its inner type
+            TypeCtor::Atomic(ity) => int_ty_query(db, ity).into(),
+            TypeCtor::SourceLocation => source_location_ty_query(db).into(),
+            TypeCtor::Any => any_ty_query(db).into(),
+            // A handle is stored as a pointer-sized integer that the host can interpret
+            TypeCtor::Handle => int_ty_query(db, IntTy::usize()).into(),
+            TypeCtor::FloatVector(lanes) => context.f32_type().vec_type(lanes).into(),
+            TypeCtor::Optional(ty) => optional_ty_query(db, ty).into(),
 
             TypeCtor::FnDef(def @ CallableDef::Function(_)) => {
                 let ty = db.callable_sig(def);
@@ -35,6 +44,17 @@ pub(crate) fn ir_query(db: &dyn IrDatabase, ty: Ty, params: CodeGenParams) -> An
 
                 let fn_type = match ty.ret() {
                     Ty::Empty => context.void_type().fn_type(&param_tys, false),
+                    // The value is written to memory of the caller, that is passed as the first
+                    // argument
+                    ret if !params.make_marshallable && returns_by_pointer(db, ret) => {
+                        let ret_ptr_ty = db
+                            .struct_ty(ret.as_struct().unwrap())
+                            .ptr_type(AddressSpace::Generic);
+                        let param_tys: Vec<BasicTypeEnum> = std::iter::once(ret_ptr_ty.into())
+                            .chain(param_tys)
+                            .collect();
+                        context.void_type().fn_type(&param_tys, false)
+                    }
                     ty => try_convert_any_to_basic(db.type_ir(ty.clone(), params))
                         .expect("could not convert return value")
                         .fn_type(&param_tys, false),
@@ -57,6 +77,29 @@ pub(crate) fn ir_query(db: &dyn IrDatabase, ty: Ty, params: CodeGenParams) -> An
     }
 }
 
+/// Returns true if a function that returns a value of type `ty` writes it to memory that is
+/// provided by the caller through an `sret` pointer, instead of returning it in registers. This
+/// follows the C ABI of the target, so small `value` structs are returned without touching memory:
+///   - on Windows only structs of 1, 2, 4 or 8 bytes are returned in a register;
+///   - on WebAssembly structs are always returned through a pointer;
+///   - on other targets structs of up to 16 bytes are returned in registers.
+pub(crate) fn returns_by_pointer(db: &dyn IrDatabase, ty: &Ty) -> bool {
+    let s = match ty.as_struct() {
+        Some(s) if s.data(db.upcast()).memory_kind == hir::StructMemoryKind::Value => s,
+        _ => return false,
+    };
+
+    let target = db.target();
+    let size = TypeSize::from_ir_type(&db.struct_ty(s), db.target_data().as_ref()).alloc_size;
+    if target.options.is_like_wasm {
+        true
+    } else if target.options.is_like_windows {
+        !(size == 1 || size == 2 || size == 4 || size == 8)
+    } else {
+        size > 16
+    }
+}
+
 /// Returns the LLVM IR type of the specified float type
 fn float_ty_query(db: &dyn IrDatabase, fty: FloatTy) -> FloatType {
     let context = db.context();
@@ -67,7 +110,7 @@ fn float_ty_query(db: &dyn IrDatabase, fty: FloatTy) -> FloatType {
 }
 
 /// Returns the LLVM IR type of the specified int type
-fn int_ty_query(db: &dyn IrDatabase, ity: IntTy) -> IntType {
+pub(crate) fn int_ty_query(db: &dyn IrDatabase, ity: IntTy) -> IntType {
     let context = db.context();
     match ity.bitness.resolve(&db.target_data_layout()) {
         IntBitness::X128 => context.i128_type(),
@@ -79,13 +122,63 @@ fn int_ty_query(db: &dyn IrDatabase, ity: IntTy) -> IntType {
     }
 }
 
+/// Returns the LLVM IR type of a `SourceLocation`, which stores the line and column as `u32`s,
+/// followed by the address of the path of the file as a `Handle`
+pub(crate) fn source_location_ty_query(db: &dyn IrDatabase) -> StructType {
+    let context = db.context();
+    context.struct_type(
+        &[
+            context.i32_type().into(),
+            context.i32_type().into(),
+            int_ty_query(db, IntTy::usize()).into(),
+        ],
+        false,
+    )
+}
+
+/// Returns the LLVM IR type of an `any`, which stores a pointer to the `TypeInfo` of the value it
+/// holds and the value itself, zero-extended to 64 bits
+pub(crate) fn any_ty_query(db: &dyn IrDatabase) -> StructType {
+    let context = db.context();
+    context.struct_type(
+        &[
+            context.i8_type().ptr_type(AddressSpace::Generic).into(),
+            context.i64_type().into(),
+        ],
+        false,
+    )
+}
+
+/// Returns the LLVM IR type of an `Optional<T>`, which stores whether it holds a value and the
+/// value itself
+pub(crate) fn optional_ty_query(db: &dyn IrDatabase, ty: PrimitiveTy) -> StructType {
+    let context = db.context();
+    let value_ty = try_convert_any_to_basic(db.type_ir(
+        ty.into(),
+        CodeGenParams {
+            make_marshallable: false,
+        },
+    ))
+    .expect("expected a basic type");
+    context.struct_type(&[context.bool_type().into(), value_ty], false)
+}
+
 /// Returns the LLVM IR type of the specified struct
 pub fn struct_ty_query(db: &dyn IrDatabase, s: hir::Struct) -> StructType {
     let name = s.name(db.upcast()).to_string();
     for field in s.fields(db.upcast()).iter() {
+        // `gc` structs are stored by reference, so their layout does not affect this struct. This
+        // also allows a struct to (indirectly) refer to itself.
+        let field_ty = field.ty(db.upcast());
+        if let Some(field_struct) = field_ty.as_struct() {
+            if field_struct.data(db.upcast()).memory_kind == hir::StructMemoryKind::GC {
+                continue;
+            }
+        }
+
         // Ensure that salsa's cached value incorporates the struct fields
         let _field_type_ir = db.type_ir(
-            field.ty(db.upcast()),
+            field_ty,
             CodeGenParams {
                 make_marshallable: false,
             },
@@ -121,6 +214,39 @@ pub fn type_info_query(db: &dyn IrDatabase, ty: Ty) -> TypeInfo {
                 let type_size = TypeSize::from_ir_type(&ir_ty, target.as_ref());
                 TypeInfo::new_fundamental("core::bool", type_size)
             }
+            TypeCtor::Char => {
+                let ir_ty = db.context().i32_type();
+                let type_size = TypeSize::from_ir_type(&ir_ty, target.as_ref());
+                TypeInfo::new_fundamental("core::char", type_size)
+            }
+            TypeCtor::Atomic(ty) => {
+                let ir_ty = int_ty_query(db, ty);
+                let type_size = TypeSize::from_ir_type(&ir_ty, target.as_ref());
+                TypeInfo::new_fundamental(
+                    format!("core::Atomic<{}>", ty.resolve(&db.target_data_layout())),
+                    type_size,
+                )
+            }
+            TypeCtor::SourceLocation => {
+                let ir_ty = source_location_ty_query(db);
+                let type_size = TypeSize::from_ir_type(&ir_ty, target.as_ref());
+                TypeInfo::new_fundamental("core::SourceLocation", type_size)
+            }
+            TypeCtor::Any => {
+                let ir_ty = any_ty_query(db);
+                let type_size = TypeSize::from_ir_type(&ir_ty, target.as_ref());
+                TypeInfo::new_fundamental("core::any", type_size)
+            }
+            TypeCtor::Handle => {
+                let ir_ty = int_ty_query(db, IntTy::usize());
+                let type_size = TypeSize::from_ir_type(&ir_ty, target.as_ref());
+                TypeInfo::new_fundamental("core::Handle", type_size)
+            }
+            TypeCtor::FloatVector(lanes) => {
+                let ir_ty = db.context().f32_type().vec_type(lanes);
+                let type_size = TypeSize::from_ir_type(&ir_ty, target.as_ref());
+                TypeInfo::new_fundamental(format!("core::float{}", lanes), type_size)
+            }
             TypeCtor::Struct(s) => {
                 let ir_ty = db.struct_ty(s);
                 let type_size = TypeSize::from_ir_type(&ir_ty, target.as_ref());
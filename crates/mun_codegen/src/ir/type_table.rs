@@ -1,11 +1,18 @@
 use super::types as ir;
 use crate::ir::dispatch_table::{DispatchTable, FunctionPrototype};
 use crate::type_info::{TypeGroup, TypeInfo};
-use crate::value::{AsValue, CanInternalize, Global, IrValueContext, IterAsIrValue, Value};
+use crate::value::{
+    AsValue, CanInternalize, Global, IrValueContext, IterAsIrValue, SizedValueType, Value,
+};
 use crate::IrDatabase;
 use hir::{Body, ExprId, InferenceResult};
 use inkwell::module::Linkage;
-use inkwell::{module::Module, targets::TargetData, types::ArrayType, values::PointerValue};
+use inkwell::{
+    module::Module,
+    targets::TargetData,
+    types::ArrayType,
+    values::{PointerValue, UnnamedAddress},
+};
 use std::collections::{BTreeSet, HashMap};
 use std::convert::TryInto;
 use std::ffi::CString;
@@ -138,6 +145,13 @@ impl<'a, 'ctx, 'm> TypeTableBuilder<'a, 'ctx, 'm> {
 
         // TODO: Collect used external `TypeInfo` for the type dispatch table
 
+        // Values that are cast to `any` store a pointer to their `TypeInfo`
+        if let hir::Expr::Cast { expr: inner, .. } = expr {
+            if infer[expr_id].as_simple() == Some(hir::TypeCtor::Any) {
+                self.collect_type(self.db.type_info(infer[*inner].clone()));
+            }
+        }
+
         // Recurse further
         expr.walk_child_exprs(|expr_id| self.collect_expr(expr_id, body, infer))
     }
@@ -174,7 +188,10 @@ impl<'a, 'ctx, 'm> TypeTableBuilder<'a, 'ctx, 'm> {
     /// Collects unique `TypeInfo` from the specified struct type.
     pub fn collect_struct(&mut self, hir_struct: hir::Struct) {
         let type_info = self.db.type_info(hir_struct.ty(self.db.upcast()));
-        self.entries.insert(type_info);
+        // A recursive struct refers to itself through its fields
+        if !self.entries.insert(type_info) {
+            return;
+        }
 
         let fields = hir_struct.fields(self.db.upcast());
         for field in fields.into_iter() {
@@ -224,15 +241,33 @@ impl<'a, 'ctx, 'm> TypeTableBuilder<'a, 'ctx, 'm> {
                 .as_value(self.value_context),
             TypeGroup::StructTypes(s) => {
                 // In case of a struct the `Global<ir::TypeInfo>` is actually a
-                // `Global<(ir::TypeInfo, ir::StructInfo)>`.
+                // `Global<(ir::TypeInfo, ir::StructInfo)>`. The global is declared before the
+                // `ir::StructInfo` is generated, so the fields of a recursive struct can refer to
+                // it.
+                let compound_global = self.value_context.module.add_global(
+                    self.value_context.context.struct_type(
+                        &[
+                            ir::TypeInfo::get_ir_type(self.value_context.type_context).into(),
+                            ir::StructInfo::get_ir_type(self.value_context.type_context).into(),
+                        ],
+                        false,
+                    ),
+                    None,
+                    &type_ir_name,
+                );
+                let value = Value::<*const ir::TypeInfo>::with_cast(
+                    compound_global.as_pointer_value(),
+                    self.value_context,
+                );
+                type_info_to_ir.insert(type_info.clone(), value);
+
                 let struct_info_ir = self.gen_struct_info(type_info_to_ir, s);
                 let compound_type_ir = (type_info_ir, struct_info_ir).as_value(self.value_context);
-                let compound_global =
-                    compound_type_ir.into_const_private_global(&type_ir_name, self.value_context);
-                Value::<*const ir::TypeInfo>::with_cast(
-                    compound_global.value.as_pointer_value(),
-                    self.value_context,
-                )
+                compound_global.set_linkage(Linkage::Private);
+                compound_global.set_constant(true);
+                compound_global.set_initializer(&compound_type_ir.value);
+                compound_global.set_unnamed_address(UnnamedAddress::Global);
+                value
             }
         };
 
@@ -295,6 +330,33 @@ impl<'a, 'ctx, 'm> TypeTableBuilder<'a, 'ctx, 'm> {
                 self.value_context,
             );
 
+        // Construct an array of the previous names of renamed fields (or null if none of the fields
+        // was renamed), which is used to map the values of renamed fields on hot reload
+        let field_renamed_from = if fields
+            .iter()
+            .any(|field| field.renamed_from(self.db.upcast()).is_some())
+        {
+            fields
+                .iter()
+                .enumerate()
+                .map(|(idx, field)| match field.renamed_from(self.db.upcast()) {
+                    Some(renamed_from) => CString::new(renamed_from.to_string())
+                        .expect("field name is not a valid CString")
+                        .intern(
+                            format!("struct_info::<{}>::field_renamed_from.{}", name, idx),
+                            self.value_context,
+                        )
+                        .as_value(self.value_context),
+                    None => Value::null(self.value_context),
+                })
+                .into_const_private_pointer(
+                    format!("struct_info::<{}>::field_renamed_from", name),
+                    self.value_context,
+                )
+        } else {
+            Value::null(self.value_context)
+        };
+
         ir::StructInfo {
             field_names,
             field_types,
@@ -304,6 +366,7 @@ impl<'a, 'ctx, 'm> TypeTableBuilder<'a, 'ctx, 'm> {
                 .try_into()
                 .expect("could not convert num_fields to smaller bit size"),
             memory_kind: hir_struct.data(self.db.upcast()).memory_kind.clone(),
+            field_renamed_from,
         }
         .as_value(self.value_context)
     }
@@ -67,6 +67,7 @@ pub struct FunctionPrototype {
 pub struct FunctionDefinition {
     pub prototype: FunctionPrototype,
     pub fn_ptr: Value<*const fn()>,
+    pub docs: Value<*const u8>,
 }
 
 #[derive(AsValue, TestIsAbiCompatible)]
@@ -78,6 +79,7 @@ pub struct StructInfo {
     pub field_offsets: Value<*const u16>,
     pub num_fields: u16,
     pub memory_kind: abi::StructMemoryKind,
+    pub field_renamed_from: Value<*const *const u8>,
 }
 
 #[derive(AsValue, TestIsAbiCompatible)]
@@ -108,6 +110,13 @@ pub struct AssemblyInfo {
     pub dispatch_table: DispatchTable,
     pub dependencies: Value<*const *const u8>,
     pub num_dependencies: u32,
+    pub compiler_version: Value<*const u8>,
+    pub source_hash: Value<*const u8>,
+    pub fingerprint: Value<*const u8>,
+    pub tests: Value<*const FunctionDefinition>,
+    pub num_tests: u32,
+    pub benches: Value<*const FunctionDefinition>,
+    pub num_benches: u32,
 }
 
 #[cfg(test)]
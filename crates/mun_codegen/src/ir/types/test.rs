@@ -123,6 +123,7 @@ fn test_function_definition_abi_compatible() {
             },
         },
         fn_ptr: std::ptr::null(),
+        docs: std::ptr::null(),
     };
 
     super::FunctionDefinition::test(&abi_type);
@@ -137,6 +138,7 @@ fn test_struct_info_abi_compatible() {
         field_offsets: std::ptr::null(),
         num_fields: 0,
         memory_kind: abi::StructMemoryKind::Value,
+        field_renamed_from: std::ptr::null(),
     };
 
     super::StructInfo::test(&abi_type);
@@ -186,6 +188,13 @@ fn test_assembly_info_abi_compatible() {
         },
         dependencies: std::ptr::null(),
         num_dependencies: 0,
+        compiler_version: std::ptr::null(),
+        source_hash: std::ptr::null(),
+        fingerprint: std::ptr::null(),
+        tests: std::ptr::null(),
+        num_tests: 0,
+        benches: std::ptr::null(),
+        num_benches: 0,
     };
 
     super::AssemblyInfo::test(&abi_type);
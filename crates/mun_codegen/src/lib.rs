@@ -18,8 +18,8 @@ pub(crate) mod type_info;
 pub use inkwell::{builder::Builder, context::Context, module::Module, OptimizationLevel};
 
 pub use crate::{
-    assembly::Assembly,
-    code_gen::ModuleBuilder,
+    assembly::{assembly_file_name, Assembly},
+    code_gen::{generate_c_header, EmitKind, JitAssembly, JitCode, ModuleBuilder, OutputKind},
     db::{IrDatabase, IrDatabaseStorage},
 };
 
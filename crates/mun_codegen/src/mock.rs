@@ -46,25 +46,38 @@ impl hir::Upcast<dyn hir::HirDatabase> for MockDatabase {
 impl MockDatabase {
     /// Creates a database from the given text.
     pub fn with_single_file(text: &str) -> (MockDatabase, FileId) {
+        let (db, file_ids) = MockDatabase::with_files(&[("main.mun", text)]);
+        (db, file_ids[0])
+    }
+
+    /// Creates a database with a file for every pair of relative path and text. The files are all
+    /// part of the same source root.
+    pub fn with_files(files: &[(&str, &str)]) -> (MockDatabase, Vec<FileId>) {
         let mut db: MockDatabase = Default::default();
 
         let mut source_root = SourceRoot::default();
         let source_root_id = SourceRootId(0);
 
-        let text = Arc::new(text.to_owned());
-        let rel_path = RelativePathBuf::from("main.mun");
-        let file_id = FileId(0);
-        db.set_file_relative_path(file_id, rel_path.clone());
-        db.set_file_text(file_id, Arc::new(text.to_string()));
-        db.set_file_source_root(file_id, source_root_id);
-        source_root.insert_file(file_id);
+        let mut file_ids = Vec::new();
+        for (idx, (path, text)) in files.iter().enumerate() {
+            let file_id = FileId(idx as u32);
+            db.set_file_relative_path(file_id, RelativePathBuf::from(*path));
+            db.set_file_text(file_id, Arc::new(text.to_string()));
+            db.set_file_source_root(file_id, source_root_id);
+            source_root.insert_file(file_id);
+            file_ids.push(file_id);
+        }
 
         db.set_source_root(source_root_id, Arc::new(source_root));
         db.set_optimization_lvl(OptimizationLevel::None);
+        db.set_overflow_checks(false);
+        db.set_debug_info(false);
+        db.set_single_assembly(false);
+        db.set_assembly_dependencies(Default::default());
 
         let context = crate::Context::create();
         db.set_context(Arc::new(context));
-        (db, file_id)
+        (db, file_ids)
     }
 
     pub fn log(&self, f: impl FnOnce()) -> Vec<salsa::Event> {
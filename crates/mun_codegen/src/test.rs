@@ -1,6 +1,8 @@
-use crate::{mock::MockDatabase, IrDatabase, ModuleBuilder};
+use crate::{ir::function, mock::MockDatabase, IrDatabase, ModuleBuilder};
 use hir::{
-    diagnostics::DiagnosticSink, line_index::LineIndex, HirDatabase, Module, SourceDatabase,
+    diagnostics::{DiagnosticSink, Severity},
+    line_index::LineIndex,
+    HirDatabase, Module, SourceDatabase,
 };
 use inkwell::OptimizationLevel;
 use mun_target::spec::Target;
@@ -786,6 +788,7 @@ fn incremental_compilation() {
     );
     db.set_optimization_lvl(OptimizationLevel::Default);
     db.set_target(Target::host_target().unwrap());
+    db.set_lint_levels(Default::default());
 
     {
         let events = db.log_executed(|| {
@@ -818,6 +821,228 @@ fn incremental_compilation() {
     // TODO: Add support for multiple files in a group
 }
 
+#[test]
+fn incremental_compilation_per_function() {
+    let (mut db, file_id) = MockDatabase::with_single_file(
+        r#"
+        pub fn foo(a: i32) -> i32 {
+            a + 1
+        }
+
+        pub fn bar(a: i32) -> i32 {
+            a + 2
+        }
+        "#,
+    );
+    db.set_target(Target::host_target().unwrap());
+    db.set_lint_levels(Default::default());
+
+    {
+        let events = db.log_executed(|| {
+            db.file_ir(file_id);
+        });
+        assert_eq!(
+            format!("{:?}", events).matches("function_ir").count(),
+            2,
+            "{:#?}",
+            events
+        );
+    }
+
+    // Only change the body of `bar`
+    db.set_file_text(
+        file_id,
+        Arc::new(
+            r#"
+        pub fn foo(a: i32) -> i32 {
+            a + 1
+        }
+
+        pub fn bar(a: i32) -> i32 {
+            a + 3
+        }
+        "#
+            .to_owned(),
+        ),
+    );
+
+    {
+        let events = db.log_executed(|| {
+            db.file_ir(file_id);
+        });
+        assert!(format!("{:?}", events).contains("file_ir"), "{:#?}", events);
+        assert_eq!(
+            format!("{:?}", events).matches("function_ir").count(),
+            1,
+            "{:#?}",
+            events
+        );
+    }
+}
+
+#[test]
+fn debug_info() {
+    let (mut db, file_id) = MockDatabase::with_single_file(
+        r#"
+        pub fn add(a: i32, b: i32) -> i32 {
+            let c = a + b;
+            c
+        }
+        "#,
+    );
+    db.set_target(Target::host_target().unwrap());
+    db.set_lint_levels(Default::default());
+    db.set_debug_info(true);
+
+    let ir = db
+        .file_ir(file_id)
+        .llvm_module
+        .print_to_string()
+        .to_string();
+    assert!(ir.contains("!DISubprogram(name: \"add\""), "{}", ir);
+    assert!(
+        ir.contains("!DILocalVariable(name: \"a\", arg: 1"),
+        "{}",
+        ir
+    );
+    assert!(ir.contains("!DILocalVariable(name: \"c\""), "{}", ir);
+    assert!(ir.contains("!DILocation(line: 3"), "{}", ir);
+}
+
+#[test]
+fn single_assembly() {
+    let (mut db, file_ids) = MockDatabase::with_files(&[
+        (
+            "main.mun",
+            r#"
+    pub fn main() -> i32 {
+        value() + ai::value()
+    }
+
+    fn value() -> i32 { 1 }
+    "#,
+        ),
+        (
+            "ai.mun",
+            r#"
+    pub fn value() -> i32 { 2 }
+    "#,
+        ),
+    ]);
+    db.set_target(Target::host_target().unwrap());
+    db.set_lint_levels(Default::default());
+    db.set_single_assembly(true);
+
+    assert_eq!(
+        *db.assembly_files(file_ids[0]),
+        vec![file_ids[1], file_ids[0]]
+    );
+    assert_eq!(db.assembly_group(file_ids[0]), file_ids[1]);
+
+    let file_ir = db.file_ir(file_ids[1]);
+    let mut api: Vec<_> = file_ir
+        .api
+        .iter()
+        .map(|f| f.name(db.upcast()).to_string())
+        .collect();
+    api.sort();
+    assert_eq!(api, vec!["main", "value"]);
+
+    // Functions with the same name in different files do not collide
+    let ir = file_ir.llvm_module.print_to_string().to_string();
+    assert!(ir.contains("@value("), "{}", ir);
+    assert!(ir.contains("@\"ai::value\"("), "{}", ir);
+
+    // The dispatch table is initialized with the functions of both files
+    let group_ir = db
+        .group_ir(file_ids[1])
+        .llvm_module
+        .print_to_string()
+        .to_string();
+    assert!(group_ir.contains("@value,"), "{}", group_ir);
+    assert!(group_ir.contains("@\"ai::value\" }"), "{}", group_ir);
+}
+
+/// Tests that code can be generated for every supported target, which also verifies that the data
+/// layouts of the targets match those of LLVM.
+#[test]
+fn cross_compilation() {
+    for triple in mun_target::spec::get_targets() {
+        let (mut db, file_id) = MockDatabase::with_single_file(
+            r#"
+    struct Foo { a: i64, b: f32 }
+
+    pub fn main(foo: Foo) -> usize {
+        foo.a as usize
+    }
+    "#,
+        );
+        db.set_target(Target::search(&triple).unwrap());
+        db.set_lint_levels(Default::default());
+
+        let module_builder =
+            ModuleBuilder::new(&db, file_id).expect("Failed to initialize module builder");
+        assert!(module_builder.build().is_ok(), "{}", triple);
+    }
+}
+
+#[test]
+fn test_and_bench_functions() {
+    let (mut db, file_ids) = MockDatabase::with_files(&[
+        (
+            "main.mun",
+            r#"
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+
+    #[test]
+    fn adds() { core::assert(add(1, 2) == 3); }
+
+    #[bench]
+    fn add_many() { add(add(1, 2), add(3, 4)); }
+    "#,
+        ),
+        (
+            "ai.mun",
+            r#"
+    #[test]
+    fn adds() {}
+    "#,
+        ),
+    ]);
+    db.set_target(Target::host_target().unwrap());
+    db.set_lint_levels(Default::default());
+
+    // Test and benchmark functions are not part of the API of the assembly
+    let file_ir = db.file_ir(file_ids[0]);
+    let api: Vec<_> = file_ir
+        .api
+        .iter()
+        .map(|f| f.name(db.upcast()).to_string())
+        .collect();
+    assert_eq!(api, vec!["add"]);
+    let tests: Vec<_> = file_ir
+        .tests
+        .iter()
+        .map(|f| function::qualified_name(&db, *f))
+        .collect();
+    assert_eq!(tests, vec!["adds"]);
+    let benches: Vec<_> = file_ir
+        .benches
+        .iter()
+        .map(|f| function::qualified_name(&db, *f))
+        .collect();
+    assert_eq!(benches, vec!["add_many"]);
+
+    // Test functions are named after the path of their module
+    let file_ir = db.file_ir(file_ids[1]);
+    let tests: Vec<_> = file_ir
+        .tests
+        .iter()
+        .map(|f| function::qualified_name(&db, *f))
+        .collect();
+    assert_eq!(tests, vec!["ai::adds"]);
+}
+
 #[test]
 fn nested_structs() {
     test_snapshot(
@@ -929,10 +1154,15 @@ fn test_snapshot_with_optimization(text: &str, opt: OptimizationLevel) {
     let (mut db, file_id) = MockDatabase::with_single_file(&text);
     db.set_optimization_lvl(opt);
     db.set_target(Target::host_target().unwrap());
+    db.set_lint_levels(Default::default());
 
     let line_index: Arc<LineIndex> = db.line_index(file_id);
     let messages = RefCell::new(Vec::new());
     let mut sink = DiagnosticSink::new(|diag| {
+        // Warnings do not prevent code generation
+        if diag.severity() == Severity::Warning {
+            return;
+        }
         let line_col = line_index.line_col(diag.highlight_range().start());
         messages.borrow_mut().push(format!(
             "error {}:{}: {}",
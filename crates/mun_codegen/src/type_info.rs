@@ -92,9 +92,7 @@ impl TypeInfo {
     pub fn new_fundamental<S: AsRef<str>>(name: S, type_size: TypeSize) -> TypeInfo {
         TypeInfo {
             name: name.as_ref().to_string(),
-            guid: Guid {
-                b: md5::compute(name.as_ref()).0,
-            },
+            guid: Guid::from_name(name.as_ref()),
             group: TypeGroup::FundamentalTypes,
             size: type_size,
         }
@@ -102,29 +100,25 @@ impl TypeInfo {
 
     pub fn new_struct(db: &dyn IrDatabase, s: hir::Struct, type_size: TypeSize) -> TypeInfo {
         let name = s.name(db.upcast()).to_string();
-        let guid_string = {
-            let fields: Vec<String> = s
-                .fields(db.upcast())
-                .into_iter()
-                .map(|f| {
-                    let ty_string = f
-                        .ty(db.upcast())
-                        .guid_string(db.upcast())
-                        .expect("type should be convertible to a string");
-                    format!("{}: {}", f.name(db.upcast()).to_string(), ty_string)
-                })
-                .collect();
-
-            format!(
-                "struct {name}{{{fields}}}",
-                name = &name,
-                fields = fields.join(",")
-            )
-        };
+        let fields: Vec<(String, Guid)> = s
+            .fields(db.upcast())
+            .into_iter()
+            .map(|f| {
+                let ty = f.ty(db.upcast());
+                let guid = match ty.as_struct() {
+                    Some(s) if s.data(db.upcast()).memory_kind == hir::StructMemoryKind::GC => {
+                        Guid::from_gc_struct_reference(&s.name(db.upcast()).to_string())
+                    }
+                    _ => db.type_info(ty).guid,
+                };
+                (f.name(db.upcast()).to_string(), guid)
+            })
+            .collect();
         Self {
-            guid: Guid {
-                b: md5::compute(&guid_string).0,
-            },
+            guid: Guid::from_struct(
+                &name,
+                fields.iter().map(|(name, guid)| (name.as_str(), *guid)),
+            ),
             name,
             group: TypeGroup::StructTypes(s),
             size: type_size,
@@ -179,7 +173,7 @@ macro_rules! impl_fundamental_static_type_info {
 }
 
 impl_fundamental_static_type_info!(
-    u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64, bool
+    u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64, bool, char
 );
 
 impl<T: HasStaticTypeName> HasStaticTypeInfo for *mut T {
@@ -1,7 +1,7 @@
 use crate::Config;
 use mun_codegen::IrDatabase;
 use mun_hir::{salsa, HirDatabase, Upcast};
-use std::sync::Arc;
+use std::{marker::PhantomData, sync::Arc};
 
 /// A compiler database is a salsa database that enables increment compilation.
 #[salsa::database(
@@ -47,6 +47,7 @@ impl CompilerDatabase {
 
         // Set the initial configuration
         db.set_context(Arc::new(mun_codegen::Context::create()));
+        db.set_assembly_dependencies(Default::default());
         db.set_config(config);
 
         db
@@ -56,7 +57,107 @@ impl CompilerDatabase {
     pub fn set_config(&mut self, config: &Config) {
         self.set_target(config.target.clone());
         self.set_optimization_lvl(config.optimization_lvl);
+        self.set_overflow_checks(config.overflow_checks());
+        self.set_debug_info(config.debug_info);
+        self.set_single_assembly(config.single_assembly);
+        self.set_lint_levels(Arc::new(config.lint_levels.clone()));
     }
 }
 
 impl salsa::Database for CompilerDatabase {}
+
+/// A handle to a `CompilerDatabase` that is fixed at the revision at which it was created, which
+/// can be sent to another thread to execute the queries of the `HirDatabase` in parallel. Inputs of
+/// the database cannot be set while a snapshot exists, because it borrows the database.
+pub(crate) struct HirSnapshot<'db> {
+    db: CompilerDatabase,
+    _database: PhantomData<&'db CompilerDatabase>,
+}
+
+// Safety: A `CompilerDatabase` is not `Send`, only because the values of the `IrDatabase` queries
+// refer to an LLVM context, which is not thread-safe. Sending a snapshot to another thread never
+// touches these values:
+//
+// - A snapshot only provides access to the `HirDatabase`, whose queries never depend on queries of
+//   the `IrDatabase`. Executing or validating a HIR query therefore only reads the storage of the
+//   `SourceDatabase`, `DefDatabase` and `HirDatabase` groups, whose values are `Send` and `Sync`,
+//   see the `hir_queries_are_thread_safe` test below. Salsa synchronizes the access to the storage
+//   of every query itself.
+// - Dropping a snapshot only releases its reference to the shared storage, never the storage
+//   itself, because the snapshot borrows the `CompilerDatabase` that it was created from for
+//   `'db`. It can therefore only be sent to a thread that is scoped to that borrow, which joins
+//   before the database can be dropped.
+unsafe impl Send for HirSnapshot<'_> {}
+
+impl HirSnapshot<'_> {
+    /// Returns the database of the snapshot.
+    pub(crate) fn db(&self) -> &dyn HirDatabase {
+        &self.db
+    }
+}
+
+impl CompilerDatabase {
+    /// Creates a snapshot of the database at its current revision.
+    pub(crate) fn hir_snapshot(&self) -> HirSnapshot<'_> {
+        HirSnapshot {
+            db: CompilerDatabase {
+                storage: self.storage.snapshot(),
+            },
+            _database: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{db::CompilerDatabase, Config};
+    use mun_hir::{salsa, Upcast};
+
+    /// A database with only the queries that a `HirSnapshot` provides access to.
+    #[salsa::database(
+        mun_hir::SourceDatabaseStorage,
+        mun_hir::DefDatabaseStorage,
+        mun_hir::HirDatabaseStorage
+    )]
+    #[derive(Default)]
+    struct HirDatabase {
+        storage: salsa::Storage<Self>,
+    }
+
+    impl salsa::Database for HirDatabase {}
+
+    impl Upcast<dyn mun_hir::SourceDatabase> for HirDatabase {
+        fn upcast(&self) -> &dyn mun_hir::SourceDatabase {
+            &*self
+        }
+    }
+
+    impl Upcast<dyn mun_hir::DefDatabase> for HirDatabase {
+        fn upcast(&self) -> &dyn mun_hir::DefDatabase {
+            &*self
+        }
+    }
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn hir_queries_are_thread_safe() {
+        // `HirSnapshot` can only be sent to another thread, if the storage of all queries that it
+        // can execute can be shared between threads
+        assert_send_sync::<HirDatabase>();
+        let db = HirDatabase::default();
+        std::thread::spawn(move || drop(db)).join().unwrap();
+    }
+
+    #[test]
+    fn snapshot_is_scoped_to_database() {
+        // A snapshot borrows the database, so it can only be used by threads that are joined
+        // before the database is dropped
+        let config = Config::default();
+        let db = CompilerDatabase::new(&config);
+        let snapshot = db.hir_snapshot();
+        let target =
+            std::thread::scope(|scope| scope.spawn(move || snapshot.db().target()).join().unwrap());
+        assert_eq!(target.llvm_target, config.target.llvm_target);
+    }
+}
@@ -1,11 +1,13 @@
-use mun_hir::diagnostics::DiagnosticSink;
-use mun_hir::{FileId, HirDatabase, Module};
+use mun_hir::diagnostics::{DiagnosticSink, SourceChange};
+use mun_hir::{FileId, HirDatabase, Module, ModuleDef, Upcast};
 
 use std::cell::RefCell;
 
 use crate::diagnostics_snippets;
 use annotate_snippets::{
-    display_list::DisplayList, formatter::DisplayListFormatter, snippet::Snippet,
+    display_list::DisplayList,
+    formatter::DisplayListFormatter,
+    snippet::{AnnotationType, Snippet},
 };
 
 /// Emits all specified diagnostic messages to the given stream
@@ -22,8 +24,57 @@ pub fn emit_diagnostics<'a>(
     Ok(())
 }
 
+/// Returns true if the specified diagnostic message is an error, rather than e.g. a warning.
+pub(crate) fn is_error(diagnostic: &Snippet) -> bool {
+    diagnostic
+        .title
+        .as_ref()
+        .map(|a| match a.annotation_type {
+            AnnotationType::Error => true,
+            _ => false,
+        })
+        .unwrap_or(false)
+}
+
+/// Returns the fixes that are suggested by the diagnostics of the given file.
+pub fn fixes(db: &dyn HirDatabase, file_id: FileId) -> Vec<SourceChange> {
+    let mut result = Vec::new();
+    let mut sink = DiagnosticSink::new(|d| result.extend(d.fix(db)));
+    Module::from(file_id).diagnostics(db, &mut sink);
+    drop(sink);
+    result
+}
+
+/// Parses the given file and lowers and infers all its definitions, so the timings of these phases
+/// are reported separately from the collection of the diagnostics.
+pub(crate) fn analyze(db: &dyn HirDatabase, file_id: FileId) {
+    let relative_file_path = db.file_relative_path(file_id).to_string();
+    tracing::info_span!("parse", file = %relative_file_path).in_scope(|| db.parse(file_id));
+
+    for def in Module::from(file_id).declarations(db) {
+        match def {
+            ModuleDef::Function(f) => {
+                let name = f.name(db);
+                tracing::info_span!("lower", file = %relative_file_path, name = %name)
+                    .in_scope(|| f.body(db));
+                tracing::info_span!("infer", file = %relative_file_path, name = %name)
+                    .in_scope(|| f.infer(db));
+            }
+            ModuleDef::Struct(s) => {
+                let name = s.name(db.upcast());
+                tracing::info_span!("lower", file = %relative_file_path, name = %name)
+                    .in_scope(|| s.lower(db));
+            }
+            ModuleDef::BuiltinType(_) => (),
+        }
+    }
+}
+
 /// Constructs diagnostic messages for the given file.
 pub fn diagnostics(db: &dyn HirDatabase, file_id: FileId) -> Vec<Snippet> {
+    analyze(db, file_id);
+
+    let relative_file_path = db.file_relative_path(file_id).to_string();
     let parse = db.parse(file_id);
 
     let mut result = Vec::new();
@@ -33,8 +84,6 @@ pub fn diagnostics(db: &dyn HirDatabase, file_id: FileId) -> Vec<Snippet> {
     // correctly replace each `\t` into 1-4 space.
     let source_code = db.file_text(file_id).to_string().replace("\t", " ");
 
-    let relative_file_path = db.file_relative_path(file_id).to_string();
-
     let line_index = db.line_index(file_id);
 
     result.extend(parse.errors().iter().map(|err| {
@@ -133,6 +182,18 @@ pub fn diagnostics(db: &dyn HirDatabase, file_id: FileId) -> Vec<Snippet> {
                 &line_index,
             ));
     })
+    .on::<mun_hir::diagnostics::UnusedVariable, _>(|d| {
+        result
+            .borrow_mut()
+            .push(diagnostics_snippets::unused_variable_warning(
+                d,
+                db,
+                &parse,
+                &relative_file_path,
+                &source_code,
+                &line_index,
+            ));
+    })
     .on::<mun_hir::diagnostics::AccessUnknownField, _>(|d| {
         result
             .borrow_mut()
@@ -155,6 +216,7 @@ pub fn diagnostics(db: &dyn HirDatabase, file_id: FileId) -> Vec<Snippet> {
 
 #[cfg(test)]
 mod tests {
+    use super::{emit_diagnostics, is_error};
     use crate::{Config, DisplayColor, Driver, PathOrInline, RelativePathBuf};
     use std::io::Cursor;
 
@@ -174,9 +236,14 @@ mod tests {
 
         let mut compilation_errors = Vec::<u8>::new();
 
-        let _ = driver
-            .emit_diagnostics(&mut Cursor::new(&mut compilation_errors))
-            .unwrap();
+        // Warnings, like unused variables, are not compilation errors
+        let diagnostics = driver.diagnostics();
+        emit_diagnostics(
+            &mut Cursor::new(&mut compilation_errors),
+            diagnostics.iter().filter(|d| is_error(d)),
+            false,
+        )
+        .unwrap();
 
         String::from_utf8(compilation_errors).unwrap()
     }
@@ -214,6 +281,13 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_mismatched_return_type_error() {
+        insta::assert_display_snapshot!(compilation_errors(
+            "\n\nfn main() -> i32 {\nreturn false;\n}"
+        ));
+    }
+
     #[test]
     fn test_duplicate_definition_error() {
         insta::assert_display_snapshot!(compilation_errors(
@@ -0,0 +1,242 @@
+//! Diagnostics in the format of `--message-format=json`, which enables other tools, like editors
+//! and build systems, to consume them without parsing the human-readable messages.
+
+use crate::diagnostics::analyze;
+use mun_hir::diagnostics::{Diagnostic as HirDiagnostic, DiagnosticSink, Severity};
+use mun_hir::error_codes;
+use mun_hir::line_index::LineIndex;
+use mun_hir::{FileId, HirDatabase, Module};
+use mun_syntax::{SyntaxError, TextRange};
+use serde_derive::Serialize;
+
+/// A diagnostic that is emitted as a JSON object on a line of its own.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JsonDiagnostic {
+    /// The message of the diagnostic
+    pub message: String,
+    /// The error code of the diagnostic, e.g. `E0002`, or the name of the lint that reported it,
+    /// e.g. `unused_variables`
+    pub code: Option<&'static str>,
+    /// Either `"error"` or `"warning"`
+    pub severity: &'static str,
+    /// The locations of the diagnostic, starting with the primary location
+    pub spans: Vec<JsonSpan>,
+    /// Free-form notes that provide more information
+    pub notes: Vec<String>,
+    /// Changes of the source code that resolve the diagnostic
+    pub suggestions: Vec<JsonSuggestion>,
+}
+
+impl JsonDiagnostic {
+    /// Returns true if the diagnostic is an error, rather than e.g. a warning.
+    pub fn is_error(&self) -> bool {
+        self.severity == "error"
+    }
+}
+
+/// A range of a source file. Lines and columns start at 1, columns are counted in bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JsonSpan {
+    /// The path of the file relative to the source directory
+    pub file: String,
+    pub byte_start: u32,
+    pub byte_end: u32,
+    pub line_start: u32,
+    pub column_start: u32,
+    pub line_end: u32,
+    pub column_end: u32,
+    /// Whether this is the primary location of the diagnostic
+    pub is_primary: bool,
+    /// Describes the role of the location, if it is not the primary location
+    pub label: Option<String>,
+}
+
+/// A change of the source code that resolves a diagnostic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JsonSuggestion {
+    /// A short description of the change, e.g. "add missing fields"
+    pub message: String,
+    /// The edits of the change, which do not overlap
+    pub edits: Vec<JsonEdit>,
+}
+
+/// Replaces the text of `span` by `replacement`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JsonEdit {
+    pub span: JsonSpan,
+    pub replacement: String,
+}
+
+/// Constructs the JSON diagnostics of the given file.
+pub fn diagnostics(db: &dyn HirDatabase, file_id: FileId) -> Vec<JsonDiagnostic> {
+    analyze(db, file_id);
+    let spans = Spans {
+        file: db.file_relative_path(file_id).to_string(),
+        line_index: &db.line_index(file_id),
+    };
+
+    let mut result: Vec<_> = db
+        .parse(file_id)
+        .errors()
+        .iter()
+        .map(|error| spans.syntax_error(error))
+        .collect();
+
+    let mut sink = DiagnosticSink::new(|diagnostic| {
+        result.push(spans.diagnostic(db, file_id, diagnostic));
+    });
+    Module::from(file_id).diagnostics(db, &mut sink);
+    drop(sink);
+
+    result
+}
+
+/// Converts the ranges of a file to `JsonSpan`s.
+struct Spans<'a> {
+    file: String,
+    line_index: &'a LineIndex,
+}
+
+impl Spans<'_> {
+    fn span(&self, range: TextRange, is_primary: bool, label: Option<String>) -> JsonSpan {
+        let start = self.line_index.line_col(range.start());
+        let end = self.line_index.line_col(range.end());
+        JsonSpan {
+            file: self.file.clone(),
+            byte_start: range.start().to_usize() as u32,
+            byte_end: range.end().to_usize() as u32,
+            line_start: start.line + 1,
+            column_start: start.col + 1,
+            line_end: end.line + 1,
+            column_end: end.col + 1,
+            is_primary,
+            label,
+        }
+    }
+
+    fn syntax_error(&self, error: &SyntaxError) -> JsonDiagnostic {
+        let location = error.location();
+        JsonDiagnostic {
+            message: format!("syntax error: {}", error),
+            code: Some(error_codes::E0001.code),
+            severity: "error",
+            spans: vec![self.span(
+                TextRange::from_to(location.offset(), location.end_offset()),
+                true,
+                None,
+            )],
+            notes: Vec::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    fn diagnostic(
+        &self,
+        db: &dyn HirDatabase,
+        file_id: FileId,
+        diagnostic: &dyn HirDiagnostic,
+    ) -> JsonDiagnostic {
+        let mut spans = vec![self.span(diagnostic.highlight_range(), true, None)];
+        spans.extend(
+            diagnostic
+                .secondary_labels()
+                .into_iter()
+                .map(|label| self.span(label.range, false, Some(label.message))),
+        );
+
+        let suggestions = diagnostic
+            .fix(db)
+            .filter(|fix| fix.file_id == file_id)
+            .map(|fix| JsonSuggestion {
+                message: fix.label,
+                edits: fix
+                    .edits
+                    .into_iter()
+                    .map(|edit| JsonEdit {
+                        span: self.span(edit.range, false, None),
+                        replacement: edit.replace_with,
+                    })
+                    .collect(),
+            })
+            .into_iter()
+            .collect();
+
+        JsonDiagnostic {
+            message: diagnostic.message(),
+            code: diagnostic
+                .code()
+                .map(|code| code.code)
+                .or_else(|| diagnostic.lint().map(|lint| lint.name)),
+            severity: match diagnostic.severity() {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            },
+            spans,
+            notes: diagnostic.notes(),
+            suggestions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, Driver, MessageFormat, PathOrInline, RelativePathBuf};
+
+    fn driver(source_code: &str) -> Driver {
+        let config = Config {
+            message_format: MessageFormat::Json,
+            ..Config::default()
+        };
+        let input = PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("main.mun"),
+            contents: source_code.to_owned(),
+        };
+        Driver::with_file(config, input).unwrap().0
+    }
+
+    #[test]
+    fn unused_variable() {
+        let diagnostics = driver("pub fn main() {\n    let a = 1;\n}").json_diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.code, Some("unused_variables"));
+        assert_eq!(diagnostic.severity, "warning");
+        assert!(!diagnostic.is_error());
+
+        let span = &diagnostic.spans[0];
+        assert_eq!(span.file, "main.mun");
+        assert_eq!((span.byte_start, span.byte_end), (24, 25));
+        assert_eq!((span.line_start, span.column_start), (2, 9));
+        assert_eq!((span.line_end, span.column_end), (2, 10));
+        assert!(span.is_primary);
+
+        let suggestion = &diagnostic.suggestions[0];
+        assert_eq!(suggestion.message, "rename to `_a`");
+        assert_eq!(suggestion.edits[0].span.byte_start, 24);
+        assert_eq!(suggestion.edits[0].replacement, "_");
+    }
+
+    #[test]
+    fn emits_a_json_object_per_line() {
+        let driver = driver("pub fn main() {\n    let a = b;\n}\nfn foo(");
+        let mut output = Vec::new();
+        assert!(driver.emit_diagnostics(&mut output).unwrap());
+
+        let output = String::from_utf8(output).unwrap();
+        let diagnostics: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(diagnostics.len(), driver.json_diagnostics().len());
+        assert!(diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic["code"] == "E0001"));
+        assert!(diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic["code"] == "E0002"));
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic["spans"][0]["is_primary"] == true));
+    }
+}
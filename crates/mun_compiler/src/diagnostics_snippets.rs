@@ -1,5 +1,5 @@
-use mun_hir::diagnostics::Diagnostic as HirDiagnostic;
-use mun_hir::{HirDatabase, HirDisplay};
+use mun_hir::diagnostics::{Diagnostic as HirDiagnostic, Severity};
+use mun_hir::{error_codes, HirDatabase, HirDisplay};
 use mun_syntax::{
     ast, AstNode, Parse, SourceFile, SyntaxError, SyntaxKind, SyntaxNodePtr, TextRange,
 };
@@ -10,7 +10,7 @@ use mun_hir::line_index::LineIndex;
 
 use crate::annotate::{AnnotationBuilder, SliceBuilder, SnippetBuilder};
 
-use annotate_snippets::snippet::{AnnotationType, Snippet};
+use annotate_snippets::snippet::{Annotation, AnnotationType, Snippet};
 
 fn text_range_to_tuple(text_range: TextRange) -> (usize, usize) {
     (text_range.start().to_usize(), text_range.end().to_usize())
@@ -46,6 +46,7 @@ pub(crate) fn syntax_error(
     let mut snippet = SnippetBuilder::new()
         .title(
             AnnotationBuilder::new(AnnotationType::Error)
+                .id(error_codes::E0001.code)
                 .label("syntax error")
                 .build(),
         )
@@ -69,6 +70,22 @@ pub(crate) fn syntax_error(
     snippet
 }
 
+/// Returns a builder for the title of a diagnostic, which shows its error code, if it has one.
+fn title(annotation_type: AnnotationType, diagnostic: &dyn HirDiagnostic) -> AnnotationBuilder {
+    let builder = AnnotationBuilder::new(annotation_type);
+    match diagnostic.code() {
+        Some(code) => builder.id(code.code),
+        None => builder,
+    }
+}
+
+fn severity_annotation_type(severity: Severity) -> AnnotationType {
+    match severity {
+        Severity::Error => AnnotationType::Error,
+        Severity::Warning => AnnotationType::Warning,
+    }
+}
+
 pub(crate) fn generic_error(
     diagnostic: &dyn HirDiagnostic,
     _: &dyn HirDatabase,
@@ -77,23 +94,62 @@ pub(crate) fn generic_error(
     source_code: &str,
     line_index: &Arc<LineIndex>,
 ) -> Snippet {
-    SnippetBuilder::new()
+    let annotation_type = severity_annotation_type(diagnostic.severity());
+    let slice = SliceBuilder::new(true)
+        .origin(relative_file_path)
+        .source_annotation(
+            text_range_to_tuple(diagnostic.highlight_range()),
+            &diagnostic.message(),
+            annotation_type,
+        );
+    let mut builder = SnippetBuilder::new()
         .title(
-            AnnotationBuilder::new(AnnotationType::Error)
+            title(annotation_type, diagnostic)
                 .label(&diagnostic.message())
                 .build(),
         )
-        .slice(
-            SliceBuilder::new(true)
-                .origin(relative_file_path)
-                .source_annotation(
-                    text_range_to_tuple(diagnostic.highlight_range()),
-                    &diagnostic.message(),
-                    AnnotationType::Error,
-                )
-                .build(&source_code, &line_index),
-        )
-        .build()
+        .slice(secondary_labels(slice, diagnostic).build(&source_code, &line_index));
+    builder = notes(builder, diagnostic);
+    if let Some(note) = lint_note(diagnostic) {
+        builder = builder.footer(note);
+    }
+    builder.build()
+}
+
+/// Adds the secondary labels of the diagnostic to the slice.
+fn secondary_labels(mut slice: SliceBuilder, diagnostic: &dyn HirDiagnostic) -> SliceBuilder {
+    for label in diagnostic.secondary_labels() {
+        slice = slice.source_annotation(
+            text_range_to_tuple(label.range),
+            &label.message,
+            AnnotationType::Info,
+        );
+    }
+    slice
+}
+
+/// Adds the notes of the diagnostic as footers to the snippet.
+fn notes(mut builder: SnippetBuilder, diagnostic: &dyn HirDiagnostic) -> SnippetBuilder {
+    for note in diagnostic.notes() {
+        builder = builder.footer(
+            AnnotationBuilder::new(AnnotationType::Note)
+                .label(&note)
+                .build(),
+        );
+    }
+    builder
+}
+
+/// Returns a note that explains how to silence the lint that reported the diagnostic, if any.
+fn lint_note(diagnostic: &dyn HirDiagnostic) -> Option<Annotation> {
+    diagnostic.lint().map(|lint| {
+        AnnotationBuilder::new(AnnotationType::Note)
+            .label(&format!(
+                "reported by the `{}` lint, which can be silenced with `#[allow({})]`",
+                lint.name, lint.name
+            ))
+            .build()
+    })
 }
 
 pub(crate) fn unresolved_value_error(
@@ -112,7 +168,7 @@ pub(crate) fn unresolved_value_error(
 
     SnippetBuilder::new()
         .title(
-            AnnotationBuilder::new(AnnotationType::Error)
+            title(AnnotationType::Error, diagnostic)
                 .label(&format!(
                     "cannot find value `{}` in this scope",
                     unresolved_value
@@ -149,7 +205,7 @@ pub(crate) fn unresolved_type_error(
 
     SnippetBuilder::new()
         .title(
-            AnnotationBuilder::new(AnnotationType::Error)
+            title(AnnotationType::Error, diagnostic)
                 .label(&format!(
                     "cannot find type `{}` in this scope",
                     unresolved_type
@@ -179,7 +235,7 @@ pub(crate) fn expected_function_error(
 ) -> Snippet {
     SnippetBuilder::new()
         .title(
-            AnnotationBuilder::new(AnnotationType::Error)
+            title(AnnotationType::Error, diagnostic)
                 .label(&diagnostic.message())
                 .build(),
         )
@@ -207,27 +263,25 @@ pub(crate) fn mismatched_type_error(
     source_code: &str,
     line_index: &Arc<LineIndex>,
 ) -> Snippet {
-    SnippetBuilder::new()
+    let slice = SliceBuilder::new(true)
+        .origin(relative_file_path)
+        .source_annotation(
+            text_range_to_tuple(diagnostic.highlight_range()),
+            &format!(
+                "expected `{}`, found `{}`",
+                diagnostic.expected.display(hir_database),
+                diagnostic.found.display(hir_database)
+            ),
+            AnnotationType::Error,
+        );
+    let builder = SnippetBuilder::new()
         .title(
-            AnnotationBuilder::new(AnnotationType::Error)
+            title(AnnotationType::Error, diagnostic)
                 .label(&diagnostic.message())
                 .build(),
         )
-        .slice(
-            SliceBuilder::new(true)
-                .origin(relative_file_path)
-                .source_annotation(
-                    text_range_to_tuple(diagnostic.highlight_range()),
-                    &format!(
-                        "expected `{}`, found `{}`",
-                        diagnostic.expected.display(hir_database),
-                        diagnostic.found.display(hir_database)
-                    ),
-                    AnnotationType::Error,
-                )
-                .build(&source_code, &line_index),
-        )
-        .build()
+        .slice(secondary_labels(slice, diagnostic).build(&source_code, &line_index));
+    notes(builder, diagnostic).build()
 }
 
 pub(crate) fn duplicate_definition_error(
@@ -252,7 +306,7 @@ pub(crate) fn duplicate_definition_error(
 
     SnippetBuilder::new()
         .title(
-            AnnotationBuilder::new(AnnotationType::Error)
+            title(AnnotationType::Error, diagnostic)
                 .label(&diagnostic.message())
                 .build(),
         )
@@ -299,7 +353,7 @@ pub(crate) fn possibly_uninitialized_variable_error(
 
     SnippetBuilder::new()
         .title(
-            AnnotationBuilder::new(AnnotationType::Error)
+            title(AnnotationType::Error, diagnostic)
                 .label(&format!("{}: `{}`", diagnostic.message(), variable_name))
                 .build(),
         )
@@ -316,6 +370,45 @@ pub(crate) fn possibly_uninitialized_variable_error(
         .build()
 }
 
+pub(crate) fn unused_variable_warning(
+    diagnostic: &mun_hir::diagnostics::UnusedVariable,
+    _: &dyn HirDatabase,
+    _: &Parse<SourceFile>,
+    relative_file_path: &str,
+    source_code: &str,
+    line_index: &Arc<LineIndex>,
+) -> Snippet {
+    let annotation_type = severity_annotation_type(diagnostic.severity());
+    let mut builder = SnippetBuilder::new()
+        .title(
+            title(annotation_type, diagnostic)
+                .label(&diagnostic.message())
+                .build(),
+        )
+        .slice(
+            SliceBuilder::new(true)
+                .origin(relative_file_path)
+                .source_annotation(
+                    text_range_to_tuple(diagnostic.highlight_range()),
+                    "unused variable",
+                    annotation_type,
+                )
+                .build(&source_code, &line_index),
+        )
+        .footer(
+            AnnotationBuilder::new(AnnotationType::Help)
+                .label(&format!(
+                    "if this is intentional, prefix it with an underscore: `_{}`",
+                    diagnostic.name
+                ))
+                .build(),
+        );
+    if let Some(note) = lint_note(diagnostic) {
+        builder = builder.footer(note);
+    }
+    builder.build()
+}
+
 pub(crate) fn access_unknown_field_error(
     diagnostic: &mun_hir::diagnostics::AccessUnknownField,
     hir_database: &dyn HirDatabase,
@@ -330,7 +423,7 @@ pub(crate) fn access_unknown_field_error(
 
     SnippetBuilder::new()
         .title(
-            AnnotationBuilder::new(AnnotationType::Error)
+            title(AnnotationType::Error, diagnostic)
                 .label(&format!(
                     "no field `{}` on type `{}`",
                     diagnostic.name,
@@ -4,29 +4,51 @@
 use crate::{
     compute_source_relative_path,
     db::CompilerDatabase,
-    diagnostics::{diagnostics, emit_diagnostics},
+    diagnostics::{diagnostics, emit_diagnostics, fixes, is_error},
+    diagnostics_json::{self, JsonDiagnostic},
     ensure_package_output_dir, is_source_file, PathOrInline, RelativePath,
 };
-use mun_codegen::{Assembly, IrDatabase};
-use mun_hir::{FileId, RelativePathBuf, SourceDatabase, SourceRoot, SourceRootId};
+use mun_codegen::{
+    assembly_file_name, generate_c_header, Assembly, EmitKind, IrDatabase, JitAssembly,
+    ModuleBuilder, OutputKind,
+};
+use mun_hir::{
+    diagnostics::apply_edits, FileId, HirDatabase, HirDisplay, RelativePathBuf, SourceDatabase,
+    SourceRoot, SourceRootId,
+};
+use rayon::prelude::*;
 
 use std::{path::PathBuf, sync::Arc};
 
+mod assembly_dependency;
 mod config;
 mod display_color;
 
-pub use self::config::Config;
+pub use self::config::{Config, MessageFormat};
 pub use self::display_color::DisplayColor;
 
-use annotate_snippets::snippet::{AnnotationType, Snippet};
-use mun_project::Package;
-use std::collections::HashMap;
+use crate::timings::Timings;
+use annotate_snippets::snippet::Snippet;
+use mun_project::{DependencyKind, Package};
+use mun_target::abi::TargetDataLayout;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryInto;
 use std::path::Path;
 use walkdir::WalkDir;
 
 pub const WORKSPACE: SourceRootId = SourceRootId(0);
 
+/// The reason why an assembly has to be rebuilt after source files changed, see
+/// [`Driver::affected_assemblies`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebuildReason {
+    /// The specified file of the assembly changed
+    Changed(FileId),
+    /// A file of the assembly uses definitions of the specified file that changed, directly or
+    /// through other files
+    DependsOn(FileId),
+}
+
 pub struct Driver {
     db: CompilerDatabase,
     out_dir: PathBuf,
@@ -36,14 +58,38 @@ pub struct Driver {
     file_id_to_path: HashMap<FileId, RelativePathBuf>,
     next_file_id: usize,
 
-    file_id_to_temp_assembly_path: HashMap<FileId, PathBuf>,
+    /// The bitcode that the written assemblies were compiled from
+    file_id_to_written_bitcode: HashMap<FileId, Arc<Vec<u8>>>,
+
+    /// The name of the assembly, if all source files are compiled into a single assembly
+    assembly_name: Option<String>,
+
+    /// The files that declare the public items of compiled assemblies that the source depends
+    /// on, mapped to the paths of the assemblies
+    assembly_dependencies: BTreeMap<FileId, String>,
 
     display_color: DisplayColor,
+    message_format: MessageFormat,
+    emit_hir: bool,
+    emit: Vec<EmitKind>,
+    output_kind: OutputKind,
+
+    /// The threads that source files are analyzed and assemblies are compiled on
+    thread_pool: rayon::ThreadPool,
+
+    /// Receives the spans of the compilation if `--timings` is enabled, see [`Timings`]
+    timings: Option<tracing::Dispatch>,
 }
 
 impl Driver {
     /// Constructs a driver with a specific configuration.
     pub fn with_config(config: Config, out_dir: PathBuf) -> Result<Self, anyhow::Error> {
+        // The layouts of all types, including those of the ABI, are derived from the data layout
+        // of the target, so it has to be consistent with the rest of its specification.
+        TargetDataLayout::parse(&config.target).map_err(|e| {
+            anyhow::anyhow!("invalid target `{}`: {}", config.target.llvm_target, e)
+        })?;
+
         Ok(Self {
             db: CompilerDatabase::new(&config),
             out_dir,
@@ -51,8 +97,23 @@ impl Driver {
             path_to_file_id: Default::default(),
             file_id_to_path: Default::default(),
             next_file_id: 0,
-            file_id_to_temp_assembly_path: Default::default(),
+            file_id_to_written_bitcode: Default::default(),
+            assembly_name: None,
+            assembly_dependencies: Default::default(),
             display_color: config.display_color,
+            message_format: config.message_format,
+            emit_hir: config.emit_hir,
+            emit: config.emit,
+            output_kind: config.output_kind,
+            thread_pool: rayon::ThreadPoolBuilder::new()
+                .num_threads(config.jobs.unwrap_or(0))
+                .build()
+                .map_err(|e| anyhow::anyhow!("could not create thread pool: {}", e))?,
+            timings: if config.timings {
+                Some(tracing::Dispatch::new(Timings::default()))
+            } else {
+                None
+            },
         })
     }
 
@@ -85,8 +146,8 @@ impl Driver {
         };
 
         // Store the file information in the database together with the source root
-        let file_id = FileId(driver.next_file_id as u32);
-        driver.next_file_id += 1;
+        let rel_path = rel_path.normalize();
+        let file_id = driver.alloc_file_id(&rel_path)?;
         driver.db.set_file_relative_path(file_id, rel_path);
         driver.db.set_file_text(file_id, Arc::new(text));
         driver.db.set_file_source_root(file_id, WORKSPACE);
@@ -105,13 +166,29 @@ impl Driver {
     ) -> Result<(Package, Driver), anyhow::Error> {
         // Load the manifest file as a package
         let package = Package::from_file(package_path)?;
+        let driver = Driver::with_package(&package, config)?;
+        Ok((package, driver))
+    }
 
+    /// Constructs a driver with all source files of a package
+    pub fn with_package(package: &Package, config: Config) -> Result<Driver, anyhow::Error> {
         // Determine output directory
-        let output_dir = ensure_package_output_dir(&package, &config)
+        let output_dir = ensure_package_output_dir(package, &config)
             .map_err(|e| anyhow::anyhow!("could not create package output directory: {}", e))?;
 
         // Construct the driver
+        let assembly_name = if config.single_assembly {
+            Some(
+                config
+                    .assembly_name
+                    .clone()
+                    .unwrap_or_else(|| package.name().to_owned()),
+            )
+        } else {
+            None
+        };
         let mut driver = Driver::with_config(config, output_dir)?;
+        driver.assembly_name = assembly_name;
 
         // Iterate over all files in the source directory of the package and store their information in
         // the database
@@ -120,7 +197,8 @@ impl Driver {
             .ok_or_else(|| anyhow::anyhow!("the source directory does not exist"))?;
 
         for source_file_path in iter_source_files(&source_directory) {
-            let relative_path = compute_source_relative_path(&source_directory, &source_file_path)?;
+            let relative_path =
+                compute_source_relative_path(&source_directory, &source_file_path)?.normalize();
 
             // Load the contents of the file
             let file_contents = std::fs::read_to_string(&source_file_path).map_err(|e| {
@@ -132,9 +210,7 @@ impl Driver {
             })?;
 
             let file_id = driver.alloc_file_id(&relative_path)?;
-            driver
-                .db
-                .set_file_relative_path(file_id, relative_path.clone());
+            driver.db.set_file_relative_path(file_id, relative_path);
             driver.db.set_file_text(file_id, Arc::new(file_contents));
             driver.db.set_file_source_root(file_id, WORKSPACE);
             driver.source_root.insert_file(file_id);
@@ -145,7 +221,56 @@ impl Driver {
             .db
             .set_source_root(WORKSPACE, Arc::new(driver.source_root.clone()));
 
-        Ok((package, driver))
+        for dependency in package.manifest().dependencies() {
+            if dependency.kind() == DependencyKind::Assembly {
+                driver.add_assembly_dependency(
+                    dependency.name(),
+                    &package.root().join(dependency.path()),
+                )?;
+            }
+        }
+
+        Ok(driver)
+    }
+
+    /// Makes the public items of the compiled assembly at `library_path` available to the source
+    /// as the items of the module `name`. Calls to its functions are linked by the runtime, which
+    /// loads the assembly before the assemblies that depend on it. Returns the `FileId` of the
+    /// file that declares the items.
+    pub fn add_assembly_dependency(
+        &mut self,
+        name: &str,
+        library_path: &Path,
+    ) -> Result<FileId, anyhow::Error> {
+        let library_path = library_path.canonicalize().map_err(|e| {
+            anyhow::anyhow!(
+                "could not find assembly '{}': {}",
+                library_path.display(),
+                e
+            )
+        })?;
+        let path = RelativePathBuf::from(format!("{}.mun", name));
+        if self.path_to_file_id.contains_key(&path) {
+            anyhow::bail!(
+                "dependency `{}` has the same name as the module of '{}'",
+                name,
+                path
+            );
+        }
+
+        let declarations = assembly_dependency::declarations(&library_path)?;
+        let file_id = self.add_file(path, declarations);
+        self.assembly_dependencies
+            .insert(file_id, library_path.to_string_lossy().into_owned());
+        self.db
+            .set_assembly_dependencies(Arc::new(self.assembly_dependencies.clone()));
+        Ok(file_id)
+    }
+
+    /// Returns true if the specified file declares the items of a compiled assembly that the
+    /// source depends on, in which case it is not compiled.
+    pub fn is_assembly_dependency(&self, file_id: FileId) -> bool {
+        self.assembly_dependencies.contains_key(&file_id)
     }
 }
 
@@ -158,8 +283,10 @@ impl Driver {
         &mut self,
         relative_path: P,
     ) -> Result<FileId, anyhow::Error> {
+        let relative_path = relative_path.as_ref().normalize();
+
         // Re-use existing id to get better caching performance
-        if let Some(id) = self.path_to_file_id.get(relative_path.as_ref()) {
+        if let Some(id) = self.path_to_file_id.get(&relative_path) {
             return Ok(*id);
         }
 
@@ -174,10 +301,8 @@ impl Driver {
         self.next_file_id += 1;
 
         // Update bookkeeping
-        self.path_to_file_id
-            .insert(relative_path.as_ref().to_relative_path_buf(), id);
-        self.file_id_to_path
-            .insert(id, relative_path.as_ref().to_relative_path_buf());
+        self.path_to_file_id.insert(relative_path.clone(), id);
+        self.file_id_to_path.insert(id, relative_path);
 
         Ok(id)
     }
@@ -192,52 +317,291 @@ impl Driver {
 }
 
 impl Driver {
-    /// Returns a vector containing all the diagnostic messages for the project.
+    /// Calls `f` for every file in the database, in parallel on the thread pool of the driver. The
+    /// results are ordered by the relative paths of the files, so they do not depend on the order
+    /// in which the threads finish.
+    fn par_map_files<T, F>(&self, f: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(&dyn HirDatabase, FileId) -> T + Send + Sync,
+    {
+        let mut files: Vec<_> = self.db.source_root(WORKSPACE).files().collect();
+        files.sort_by_key(|file_id| self.db.file_relative_path(*file_id));
+        let jobs: Vec<_> = files
+            .into_iter()
+            .map(|file_id| (self.db.hir_snapshot(), file_id))
+            .collect();
+        let mut results: Vec<Option<T>> = jobs.iter().map(|_| None).collect();
+        let timings = self.timings.as_ref();
+        let f = &f;
+
+        // The snapshots borrow the database, so they can only be sent to the threads of a scope,
+        // which all finish before this function returns
+        self.thread_pool.scope(|scope| {
+            for (result, (snapshot, file_id)) in results.iter_mut().zip(jobs) {
+                scope.spawn(move |_| {
+                    *result = Some(with_timings(timings, || f(snapshot.db(), file_id)));
+                });
+            }
+        });
+        results
+            .into_iter()
+            .map(|result| result.expect("every file is mapped"))
+            .collect()
+    }
+
+    /// Returns a vector containing all the diagnostic messages for the project, ordered by file.
     pub fn diagnostics(&self) -> Vec<Snippet> {
-        self.db
-            .source_root(WORKSPACE)
-            .files()
-            .map(|f| diagnostics(&self.db, f))
+        self.par_map_files(diagnostics)
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Returns a vector containing all the diagnostics for the project in the format of
+    /// `--message-format=json`, ordered by file.
+    pub fn json_diagnostics(&self) -> Vec<JsonDiagnostic> {
+        self.par_map_files(diagnostics_json::diagnostics)
+            .into_iter()
             .flatten()
             .collect()
     }
 
-    /// Emits all diagnostic messages currently in the database; returns true if errors were
-    /// emitted.
+    /// Emits all diagnostic messages currently in the database in the configured message format;
+    /// returns true if errors were emitted.
     pub fn emit_diagnostics(&self, writer: &mut dyn std::io::Write) -> Result<bool, anyhow::Error> {
+        if self.message_format == MessageFormat::Json {
+            let diagnostics = self.json_diagnostics();
+            for diagnostic in diagnostics.iter() {
+                writeln!(writer, "{}", serde_json::to_string(diagnostic)?)?;
+            }
+            return Ok(diagnostics.iter().any(JsonDiagnostic::is_error));
+        }
+
         let diagnostics = self.diagnostics();
 
         // Emit all diagnostics to the stream
         emit_diagnostics(writer, &diagnostics, self.display_color.should_enable())?;
 
         // Determine if one of the snippets is actually an error
-        Ok(diagnostics.iter().any(|d| {
-            d.title
-                .as_ref()
-                .map(|a| match a.annotation_type {
-                    AnnotationType::Error => true,
-                    _ => false,
-                })
-                .unwrap_or(false)
-        }))
+        Ok(diagnostics.iter().any(is_error))
+    }
+
+    /// Applies the fixes that are suggested by diagnostics to the files in `source_dir` and updates
+    /// the contents of the files accordingly. Returns the number of files that were changed.
+    pub fn apply_fixes(&mut self, source_dir: &Path) -> Result<usize, anyhow::Error> {
+        let file_ids: Vec<FileId> = self
+            .db
+            .source_root(WORKSPACE)
+            .files()
+            .filter(|file_id| !self.is_assembly_dependency(*file_id))
+            .collect();
+        let mut changed_files = 0;
+        for file_id in file_ids {
+            let changes = fixes(&self.db, file_id);
+            if changes.is_empty() {
+                continue;
+            }
+
+            let text = apply_edits(
+                &self.db.file_text(file_id),
+                changes
+                    .iter()
+                    .filter(|change| change.file_id == file_id)
+                    .flat_map(|change| change.edits.iter()),
+            );
+            let path = self.file_id_to_path[&file_id].to_path(source_dir);
+            std::fs::write(&path, &text).map_err(|e| {
+                anyhow::anyhow!("could not write fixes to '{}': {}", path.display(), e)
+            })?;
+            self.set_file_text(file_id, text);
+            changed_files += 1;
+        }
+        Ok(changed_files)
+    }
+}
+
+impl Driver {
+    /// Returns the time that was spent in every phase of the compilation so far, if `--timings`
+    /// is enabled.
+    pub fn timings(&self) -> Option<&Timings> {
+        self.timings.as_ref()?.downcast_ref()
+    }
+
+    /// Prints the time that was spent in every phase of the compilation to stderr, if `--timings`
+    /// is enabled.
+    pub fn print_timings(&self) {
+        if let Some(timings) = self.timings() {
+            eprint!("{}", timings);
+        }
+    }
+}
+
+/// Calls `f` with the subscriber of `--timings` as the default `tracing` subscriber of the current
+/// thread, if it is enabled.
+fn with_timings<T>(timings: Option<&tracing::Dispatch>, f: impl FnOnce() -> T) -> T {
+    match timings {
+        Some(dispatch) => tracing::dispatcher::with_default(dispatch, f),
+        None => f(),
     }
 }
 
 impl Driver {
+    /// Returns the name of the assembly that all source files are compiled into, if they are
+    /// compiled into a single assembly.
+    pub fn assembly_name(&self) -> Option<&str> {
+        self.assembly_name.as_deref()
+    }
+
+    /// Get the path of the output with the specified extension of the assembly that the specified
+    /// file is compiled into. If all files of a package are compiled into a single assembly, it is
+    /// named after the package, otherwise after the file.
+    fn output_path(&self, file_id: FileId, extension: &str) -> PathBuf {
+        match &self.assembly_name {
+            Some(name) => self.out_dir.join(format!("{}.{}", name, extension)),
+            None => self
+                .db
+                .file_relative_path(file_id)
+                .with_extension(extension)
+                .to_path(&self.out_dir),
+        }
+    }
+
     /// Get the path where the driver will write the assembly for the specified file.
     pub fn assembly_output_path(&self, file_id: FileId) -> PathBuf {
-        self.db
-            .file_relative_path(file_id)
-            .with_extension(Assembly::EXTENSION)
-            .to_path(&self.out_dir)
+        self.output_path(file_id, Assembly::EXTENSION)
     }
 
-    /// Writes all assemblies
+    /// Returns the files that identify the assemblies that the source files are compiled into.
+    pub fn assemblies(&self) -> Vec<FileId> {
+        let mut assemblies = self
+            .source_root
+            .files()
+            .filter(|file_id| !self.is_assembly_dependency(*file_id))
+            .map(|file_id| self.db.assembly_group(file_id))
+            .collect::<Vec<_>>();
+        assemblies.sort();
+        assemblies.dedup();
+        assemblies
+    }
+
+    /// Returns the assemblies that have to be rebuilt after the specified files changed, mapped to
+    /// the reason why. These are the assemblies that the files are compiled into and the
+    /// assemblies of which a file uses definitions of the changed files, directly or through other
+    /// files. All other assemblies are not affected by the changes.
+    pub fn affected_assemblies(&self, changed_files: &[FileId]) -> BTreeMap<FileId, RebuildReason> {
+        let mut affected = BTreeMap::new();
+        let is_compiled = |file_id: FileId| {
+            self.source_root.contains(file_id) && !self.is_assembly_dependency(file_id)
+        };
+
+        // Map every file to the files that use its definitions
+        let mut dependents: HashMap<FileId, Vec<FileId>> = HashMap::new();
+        for (file_id, dependencies) in
+            self.par_map_files(|db, file_id| (file_id, db.file_dependencies(file_id)))
+        {
+            for dependency in dependencies.iter() {
+                dependents.entry(*dependency).or_default().push(file_id);
+            }
+        }
+
+        for &changed in changed_files {
+            if is_compiled(changed) {
+                affected
+                    .entry(self.db.assembly_group(changed))
+                    .or_insert(RebuildReason::Changed(changed));
+            } else if self.assembly_name.is_some() && !self.is_assembly_dependency(changed) {
+                // A file was removed from the assembly of the package
+                for assembly in self.assemblies() {
+                    affected
+                        .entry(assembly)
+                        .or_insert(RebuildReason::Changed(changed));
+                }
+            }
+        }
+
+        for &changed in changed_files {
+            let mut visited = HashSet::new();
+            let mut queue = vec![changed];
+            while let Some(file_id) = queue.pop() {
+                for &dependent in dependents.get(&file_id).into_iter().flatten() {
+                    if visited.insert(dependent) && is_compiled(dependent) {
+                        affected
+                            .entry(self.db.assembly_group(dependent))
+                            .or_insert(RebuildReason::DependsOn(changed));
+                        queue.push(dependent);
+                    }
+                }
+            }
+        }
+
+        affected
+    }
+
+    /// Writes all assemblies that changed since they were last written, see
+    /// [`Driver::write_assemblies`].
     pub fn write_all_assemblies(&mut self) -> Result<(), anyhow::Error> {
-        // Create a copy of all current files
-        let files = self.source_root.files().collect::<Vec<_>>();
-        for file_id in files {
-            self.write_assembly(file_id, false)?;
+        let assemblies = self.assemblies();
+        self.write_assemblies(assemblies)
+    }
+
+    /// Writes the specified assemblies if they changed since they were last written. Their IR is
+    /// generated one after the other, but they are optimized, compiled to machine code and linked
+    /// in parallel on the thread pool of the driver.
+    pub fn write_assemblies(
+        &mut self,
+        assemblies: impl IntoIterator<Item = FileId>,
+    ) -> Result<(), anyhow::Error> {
+        let timings = self.timings.clone();
+        let mut changed = Vec::new();
+        for file_id in assemblies {
+            self.check_export_names(file_id)?;
+            if self.output_kind == OutputKind::StaticLibrary {
+                with_timings(timings.as_ref(), || self.write_static_library(file_id))?;
+            } else if let Some(bitcode) =
+                with_timings(timings.as_ref(), || self.changed_bitcode(file_id, false))
+            {
+                changed.push((file_id, assembly_file_name(&self.db, file_id), bitcode));
+            }
+        }
+
+        let target = self.db.target();
+        let optimization_lvl = self.db.optimization_lvl();
+        let assemblies: Vec<_> = self.thread_pool.install(|| {
+            changed
+                .par_iter()
+                .map(|(_, file_name, bitcode)| {
+                    with_timings(timings.as_ref(), || {
+                        Assembly::from_bitcode(&target, optimization_lvl, file_name, bitcode)
+                    })
+                })
+                .collect()
+        });
+
+        for ((file_id, _, bitcode), assembly) in changed.into_iter().zip(assemblies) {
+            self.store_assembly(file_id, bitcode, &assembly?)?;
+        }
+        Ok(())
+    }
+
+    /// Returns an error if multiple public functions of the assembly that the specified file is
+    /// compiled into have the same export name, in which case the runtime could not tell them
+    /// apart. This can only happen if the assembly is compiled from multiple files.
+    fn check_export_names(&self, file_id: FileId) -> Result<(), anyhow::Error> {
+        let mut exported_by = HashMap::new();
+        for function in self.db.file_ir(file_id).api.iter() {
+            let name = function.export_name(&self.db);
+            let path = self
+                .db
+                .file_relative_path(function.module(&self.db).file_id());
+            if let Some(other_path) = exported_by.insert(name.clone(), path.clone()) {
+                anyhow::bail!(
+                    "function `{}` is exported by both '{}' and '{}'",
+                    name,
+                    other_path,
+                    path
+                );
+            }
         }
         Ok(())
     }
@@ -248,47 +612,205 @@ impl Driver {
     pub fn write_assembly(&mut self, file_id: FileId, force: bool) -> Result<bool, anyhow::Error> {
         log::trace!("writing assembly for {:?}", file_id);
 
-        // Determine the location of the output file
-        let assembly_path = self.assembly_output_path(file_id);
+        let file_id = self.db.assembly_group(file_id);
+        self.check_export_names(file_id)?;
 
-        // Get the compiled assembly
-        let assembly = self.db.assembly(file_id);
+        if self.output_kind == OutputKind::StaticLibrary {
+            self.write_static_library(file_id)?;
+            return Ok(true);
+        }
 
-        // Did the assembly change since last time?
-        if !force
-            && assembly_path.is_file()
-            && self
-                .file_id_to_temp_assembly_path
-                .get(&file_id)
-                .map(AsRef::as_ref)
-                == Some(assembly.path())
-        {
-            return Ok(false);
+        let bitcode = match with_timings(self.timings.as_ref(), || {
+            self.changed_bitcode(file_id, force)
+        }) {
+            Some(bitcode) => bitcode,
+            None => return Ok(false),
+        };
+        let assembly = with_timings(self.timings.as_ref(), || {
+            Assembly::from_bitcode(
+                &self.db.target(),
+                self.db.optimization_lvl(),
+                &assembly_file_name(&self.db, file_id),
+                &bitcode,
+            )
+        })?;
+        self.store_assembly(file_id, bitcode, &assembly)?;
+
+        Ok(true)
+    }
+
+    /// Returns the bitcode of the assembly for the given file, or `None` if the assembly did not
+    /// change since it was last written and `force` is false.
+    fn changed_bitcode(&self, file_id: FileId, force: bool) -> Option<Arc<Vec<u8>>> {
+        let bitcode = self.db.assembly_bitcode(file_id);
+        let is_written = self.assembly_output_path(file_id).is_file()
+            && self.file_id_to_written_bitcode.get(&file_id) == Some(&bitcode);
+        if is_written && !force {
+            None
+        } else {
+            Some(bitcode)
         }
+    }
 
-        // It did change or we are forced, so write it to disk
-        assembly.copy_to(&assembly_path)?;
+    /// Writes the assembly for the given file, which was compiled from `bitcode`, to its output
+    /// location together with its artifacts.
+    fn store_assembly(
+        &mut self,
+        file_id: FileId,
+        bitcode: Arc<Vec<u8>>,
+        assembly: &Assembly,
+    ) -> Result<(), anyhow::Error> {
+        assembly.copy_to(self.assembly_output_path(file_id))?;
 
-        // Store the information so we maybe don't have to write it next time
-        self.file_id_to_temp_assembly_path
-            .insert(file_id, assembly.path().to_path_buf());
+        // Store the bitcode so we maybe don't have to write the assembly next time
+        self.file_id_to_written_bitcode.insert(file_id, bitcode);
 
-        Ok(true)
+        self.write_artifacts(file_id)
+    }
+
+    /// Compiles the assembly for the given file into the memory of the current process instead of
+    /// writing it to disk. The result can be loaded with `Runtime::load_in_process` of the
+    /// `mun_runtime` crate, which reduces the latency of hot reloading.
+    ///
+    /// The code is freed once all handles to it have been dropped, on any thread.
+    pub fn jit_assembly(&self, file_id: FileId) -> Result<JitAssembly, anyhow::Error> {
+        ModuleBuilder::new(&self.db, file_id)?.build_jit()
+    }
+
+    /// Get the path where the driver will write the C header of the statically linked assembly
+    /// for the specified file.
+    pub fn header_output_path(&self, file_id: FileId) -> PathBuf {
+        self.output_path(file_id, "h")
+    }
+
+    /// Writes the object file of the assembly for the specified file together with a C header
+    /// that declares its entry points, for statically linking the assembly into an executable.
+    fn write_static_library(&self, file_id: FileId) -> Result<(), anyhow::Error> {
+        let object_path = self.artifact_output_path(file_id, EmitKind::Object);
+        ModuleBuilder::new(&self.db, file_id)?.emit(EmitKind::Object, &object_path)?;
+        std::fs::write(
+            self.header_output_path(file_id),
+            generate_c_header(&self.db, file_id),
+        )?;
+
+        self.write_artifacts(file_id)
+    }
+
+    /// Get the path where the driver will write the specified kind of artifact for a file.
+    pub fn artifact_output_path(&self, file_id: FileId, kind: EmitKind) -> PathBuf {
+        self.output_path(file_id, kind.extension())
+    }
+
+    /// Writes the intermediate artifacts of the code generation for the specified file that were
+    /// requested in the configuration.
+    fn write_artifacts(&self, file_id: FileId) -> Result<(), anyhow::Error> {
+        for kind in self.emit.iter().copied() {
+            let path = self.artifact_output_path(file_id, kind);
+            ModuleBuilder::new(&self.db, file_id)?.emit(kind, &path)?;
+        }
+        Ok(())
+    }
+
+    /// Get the path where the driver will write the lowered HIR for the specified file.
+    pub fn hir_output_path(&self, file_id: FileId) -> PathBuf {
+        self.db
+            .file_relative_path(file_id)
+            .with_extension("hir")
+            .to_path(&self.out_dir)
+    }
+
+    /// Writes the lowered HIR of all files, if this was requested in the configuration. The HIR is
+    /// also written for files that contain errors, which makes it useful to debug type inference.
+    pub fn write_all_hir(&self) -> Result<(), anyhow::Error> {
+        if !self.emit_hir {
+            return Ok(());
+        }
+        for file_id in self.source_root.files() {
+            if self.is_assembly_dependency(file_id) {
+                continue;
+            }
+            let hir_path = self.hir_output_path(file_id);
+            if let Some(parent) = hir_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(hir_path, self.emit_hir(file_id))?;
+        }
+        Ok(())
+    }
+
+    /// Returns a textual representation of the lowered bodies of all functions in the specified
+    /// file, annotated with the inferred types of all expressions.
+    pub fn emit_hir(&self, file_id: FileId) -> String {
+        mun_hir::Module::from(file_id).pretty_print(&self.db)
+    }
+
+    /// Returns the inferred type of the tail expression of the function `function_name` in the
+    /// specified file, as it is written in source code, regardless of the declared return type of
+    /// the function. This is used to declare the return type of a function that is generated to
+    /// evaluate an expression. Returns `None` if there is no such function, if its body has no
+    /// tail expression, or if the type has no value that can be returned, like `()` or a function.
+    pub fn tail_expr_type(&self, file_id: FileId, function_name: &str) -> Option<String> {
+        let function = mun_hir::Module::from(file_id)
+            .declarations(&self.db)
+            .into_iter()
+            .find_map(|def| match def {
+                mun_hir::ModuleDef::Function(f)
+                    if f.name(&self.db).to_string() == function_name =>
+                {
+                    Some(f)
+                }
+                _ => None,
+            })?;
+        let body = function.body(&self.db);
+        let tail = match &body[body.body_expr()] {
+            mun_hir::Expr::Block {
+                tail: Some(tail), ..
+            } => *tail,
+            _ => return None,
+        };
+        let infer = function.infer(&self.db);
+        let ty = &infer[tail];
+        if ty.is_empty()
+            || ty.is_never()
+            || *ty == mun_hir::Ty::Unknown
+            || ty.as_callable_def().is_some()
+        {
+            return None;
+        }
+        Some(ty.display(&self.db).to_string())
     }
 }
 
 impl Driver {
-    /// Returns the `FileId` of the file with the given relative path
+    /// Returns the `FileId` of the file with the given relative path. The path is normalized
+    /// before it is looked up, so `foo/../bar.mun` and `./bar.mun` both refer to `bar.mun`.
     pub fn get_file_id_for_path<P: AsRef<RelativePath>>(&self, path: P) -> Option<FileId> {
-        self.path_to_file_id.get(path.as_ref()).copied()
+        self.path_to_file_id
+            .get(&path.as_ref().normalize())
+            .copied()
+    }
+
+    /// Returns the canonical package-relative path of the file with the given `FileId`. The
+    /// mapping between a path and its `FileId` stays the same for the lifetime of the driver,
+    /// even if the file is removed and added again.
+    pub fn get_path_for_file_id(&self, file_id: FileId) -> Option<&RelativePath> {
+        self.file_id_to_path.get(&file_id).map(AsRef::as_ref)
+    }
+
+    /// Returns an iterator over all files that are part of the compilation and their canonical
+    /// package-relative paths.
+    pub fn files(&self) -> impl Iterator<Item = (FileId, &RelativePath)> + '_ {
+        self.source_root.files().filter_map(move |file_id| {
+            self.get_path_for_file_id(file_id)
+                .map(|path| (file_id, path))
+        })
     }
 
     /// Tells the driver that the file at the specified `path` has changed its contents. Returns the
     /// `FileId` of the modified file.
     pub fn update_file<P: AsRef<RelativePath>>(&mut self, path: P, contents: String) -> FileId {
-        let file_id = *self
-            .path_to_file_id
-            .get(path.as_ref())
+        let file_id = self
+            .get_file_id_for_path(path)
             .expect("writing to a file that is not part of the source root should never happen");
         self.db.set_file_text(file_id, Arc::new(contents));
         file_id
@@ -296,11 +818,11 @@ impl Driver {
 
     /// Adds a new file to the driver. Returns the `FileId` of the new file.
     pub fn add_file<P: AsRef<RelativePath>>(&mut self, path: P, contents: String) -> FileId {
-        let file_id = self.alloc_file_id(path.as_ref()).unwrap();
+        let path = path.as_ref().normalize();
+        let file_id = self.alloc_file_id(&path).unwrap();
 
         // Insert the new file
-        self.db
-            .set_file_relative_path(file_id, path.as_ref().to_relative_path_buf());
+        self.db.set_file_relative_path(file_id, path);
         self.db.set_file_text(file_id, Arc::new(contents));
         self.db.set_file_source_root(file_id, WORKSPACE);
 
@@ -314,9 +836,8 @@ impl Driver {
 
     /// Removes the specified file from the driver.
     pub fn remove_file<P: AsRef<RelativePath>>(&mut self, path: P) -> FileId {
-        let file_id = *self
-            .path_to_file_id
-            .get(path.as_ref())
+        let file_id = self
+            .get_file_id_for_path(path)
             .expect("removing to a file that is not part of the source root should never happen");
 
         // Update the source root
@@ -333,26 +854,28 @@ impl Driver {
         from: P1,
         to: P2,
     ) -> FileId {
+        let from = from.as_ref().normalize();
+        let to = to.as_ref().normalize();
         let file_id = *self
             .path_to_file_id
-            .get(from.as_ref())
+            .get(&from)
             .expect("renaming from a file that is not part of the source root should never happen");
-        if let Some(previous) = self.path_to_file_id.get(to.as_ref()) {
+        if let Some(previous) = self.path_to_file_id.get(&to) {
             // If there was some other file with this path in the database, forget about it.
             self.file_id_to_path.remove(previous);
         }
 
-        self.file_id_to_path
-            .insert(file_id, to.as_ref().to_relative_path_buf());
-        self.path_to_file_id.remove(from.as_ref()); // FileId now belongs to to
+        self.file_id_to_path.insert(file_id, to.clone());
+        self.path_to_file_id.remove(&from); // FileId now belongs to to
+        self.path_to_file_id.insert(to.clone(), file_id);
 
-        self.db
-            .set_file_relative_path(file_id, to.as_ref().to_relative_path_buf());
+        self.db.set_file_relative_path(file_id, to);
 
         file_id
     }
 }
 
+/// Returns the paths of all Mun source files in `source_dir` and its subdirectories.
 pub fn iter_source_files(source_dir: &Path) -> impl Iterator<Item = PathBuf> {
     WalkDir::new(source_dir)
         .into_iter()
@@ -360,3 +883,201 @@ pub fn iter_source_files(source_dir: &Path) -> impl Iterator<Item = PathBuf> {
         .filter(|e| is_source_file(e.path()))
         .map(|e| e.path().to_path_buf())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Driver, RebuildReason};
+    use crate::{Config, FileId, PathOrInline, RelativePath, RelativePathBuf};
+    use mun_hir::SourceDatabase;
+
+    fn driver_with_file(rel_path: &str) -> (Driver, FileId) {
+        let input = PathOrInline::Inline {
+            rel_path: RelativePathBuf::from(rel_path),
+            contents: String::from("fn main() {}"),
+        };
+        Driver::with_file(Config::default(), input).unwrap()
+    }
+
+    #[test]
+    fn file_id_path_mapping() {
+        let (driver, file_id) = driver_with_file("./foo/../main.mun");
+        assert_eq!(
+            driver.get_path_for_file_id(file_id),
+            Some(RelativePath::new("main.mun"))
+        );
+        assert_eq!(driver.get_file_id_for_path("main.mun"), Some(file_id));
+        assert_eq!(
+            driver.get_file_id_for_path("bar/../main.mun"),
+            Some(file_id)
+        );
+        assert_eq!(driver.get_file_id_for_path("foo.mun"), None);
+    }
+
+    #[test]
+    fn file_id_path_mapping_is_stable() {
+        let (mut driver, main_id) = driver_with_file("main.mun");
+        let foo_id = driver.add_file("foo.mun", String::new());
+        assert_ne!(main_id, foo_id);
+
+        // Removing and adding a file again reuses its `FileId`
+        assert_eq!(driver.remove_file("foo.mun"), foo_id);
+        assert_eq!(driver.add_file("./foo.mun", String::new()), foo_id);
+
+        // Renaming a file moves its `FileId` to the new path
+        assert_eq!(driver.rename("foo.mun", "bar/foo.mun"), foo_id);
+        assert_eq!(driver.get_file_id_for_path("foo.mun"), None);
+        assert_eq!(driver.get_file_id_for_path("bar/foo.mun"), Some(foo_id));
+        assert_eq!(
+            driver.get_path_for_file_id(foo_id),
+            Some(RelativePath::new("bar/foo.mun"))
+        );
+
+        let mut files = driver.files().collect::<Vec<_>>();
+        files.sort_by_key(|(file_id, _)| *file_id);
+        assert_eq!(
+            files,
+            vec![
+                (main_id, RelativePath::new("main.mun")),
+                (foo_id, RelativePath::new("bar/foo.mun"))
+            ]
+        );
+    }
+
+    #[test]
+    fn diagnostics_are_ordered_by_path() {
+        let config = Config {
+            jobs: Some(4),
+            ..Config::default()
+        };
+        let input = PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("main.mun"),
+            contents: String::from("fn main() { a }"),
+        };
+        let (mut driver, _) = Driver::with_file(config, input).unwrap();
+        for path in &["d.mun", "b.mun", "foo/c.mun", "a.mun"] {
+            driver.add_file(path, String::from("fn main() { b }"));
+        }
+
+        let mut files: Vec<_> = driver
+            .json_diagnostics()
+            .into_iter()
+            .map(|diagnostic| diagnostic.spans[0].file.clone())
+            .collect();
+        files.dedup();
+        assert_eq!(files, ["a.mun", "b.mun", "d.mun", "foo/c.mun", "main.mun"]);
+    }
+
+    #[test]
+    fn timings() {
+        let config = Config {
+            timings: true,
+            ..Config::default()
+        };
+        let input = PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("main.mun"),
+            contents: String::from("fn main() { let a = 1; }"),
+        };
+        let (driver, _) = Driver::with_file(config, input).unwrap();
+        driver.diagnostics();
+
+        let timings = driver.timings().unwrap();
+        assert!(timings.duration("main.mun", "parse").is_some());
+        assert!(timings.duration("main.mun", "infer").is_some());
+        assert!(timings.duration("main.mun", "link").is_none());
+    }
+
+    #[test]
+    fn affected_assemblies() {
+        let input = PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("main.mun"),
+            contents: String::from("pub fn main() -> i32 { shapes::area() }"),
+        };
+        let (mut driver, main_id) = Driver::with_file(Config::default(), input).unwrap();
+        let shapes_id = driver.add_file(
+            "shapes.mun",
+            String::from("pub fn area() -> i32 { super::units::scale() }"),
+        );
+        let units_id = driver.add_file("units.mun", String::from("pub fn scale() -> i32 { 1 }"));
+        driver.add_file("other.mun", String::from("pub fn other() {}"));
+
+        let affected = driver.affected_assemblies(&[units_id]);
+        assert_eq!(
+            affected.into_iter().collect::<Vec<_>>(),
+            vec![
+                (main_id, RebuildReason::DependsOn(units_id)),
+                (shapes_id, RebuildReason::DependsOn(units_id)),
+                (units_id, RebuildReason::Changed(units_id)),
+            ]
+        );
+    }
+
+    #[test]
+    fn tail_expr_type() {
+        let input = PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("main.mun"),
+            contents: String::from(
+                r#"
+struct Foo { a: i32 }
+fn int() { 1 + 2 }
+fn float() { let a = 1.0; a }
+fn foo() { Foo { a: 1 } }
+fn empty() { let a = 1; }
+fn function() { int }
+"#,
+            ),
+        };
+        let (driver, file_id) = Driver::with_file(Config::default(), input).unwrap();
+
+        assert_eq!(
+            driver.tail_expr_type(file_id, "int"),
+            Some("i32".to_string())
+        );
+        assert_eq!(
+            driver.tail_expr_type(file_id, "float"),
+            Some("f64".to_string())
+        );
+        assert_eq!(
+            driver.tail_expr_type(file_id, "foo"),
+            Some("Foo".to_string())
+        );
+        assert_eq!(driver.tail_expr_type(file_id, "empty"), None);
+        assert_eq!(driver.tail_expr_type(file_id, "function"), None);
+        assert_eq!(driver.tail_expr_type(file_id, "unknown"), None);
+    }
+
+    #[test]
+    fn apply_fixes() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let input = PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("main.mun"),
+            contents: String::from("pub fn main() { let a = 1; }"),
+        };
+        let (mut driver, file_id) = Driver::with_file(Config::default(), input).unwrap();
+
+        assert_eq!(driver.apply_fixes(source_dir.path()).unwrap(), 1);
+        let expected = "pub fn main() { let _a = 1; }";
+        assert_eq!(
+            std::fs::read_to_string(source_dir.path().join("main.mun")).unwrap(),
+            expected
+        );
+        assert_eq!(*driver.db.file_text(file_id), expected);
+
+        // There is nothing left to fix
+        assert_eq!(driver.apply_fixes(source_dir.path()).unwrap(), 0);
+    }
+
+    #[test]
+    fn jit_code_outlives_driver() {
+        let input = PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("main.mun"),
+            contents: String::from("pub fn main() -> i32 { 5 }"),
+        };
+        let (driver, file_id) = Driver::with_file(Config::default(), input).unwrap();
+        let jit = driver.jit_assembly(file_id).unwrap();
+        drop(driver);
+
+        // The code stays valid without the driver and can be freed on another thread
+        assert_eq!((jit.get_version)(), mun_abi::ABI_VERSION);
+        std::thread::spawn(move || drop(jit.code)).join().unwrap();
+    }
+}
@@ -0,0 +1,189 @@
+use mun_abi::{AssemblyInfo, StructMemoryKind, TypeInfo};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Reads the information of the compiled assembly at `library_path` and returns Mun source code
+/// that declares its public functions as `extern` functions, together with the structs that they
+/// use. Functions of which the signature cannot be expressed in Mun are left out.
+///
+/// The declared structs have the same names and fields as the structs of the assembly, so their
+/// types are identical at runtime.
+pub(crate) fn declarations(library_path: &Path) -> Result<String, anyhow::Error> {
+    let library = libloading::Library::new(library_path).map_err(|e| {
+        anyhow::anyhow!(
+            "could not load assembly '{}': {}",
+            library_path.display(),
+            e
+        )
+    })?;
+
+    // Safety: Every munlib exports these functions with these signatures.
+    let get_version: libloading::Symbol<'_, extern "C" fn() -> u32> =
+        unsafe { library.get(mun_abi::GET_VERSION_FN_NAME.as_bytes()) }?;
+    let version = get_version();
+    if version != mun_abi::ABI_VERSION {
+        anyhow::bail!(
+            "ABI version mismatch. munlib '{}' is `{}` but the compiler is `{}`",
+            library_path.display(),
+            version,
+            mun_abi::ABI_VERSION
+        );
+    }
+    let get_info: libloading::Symbol<'_, extern "C" fn() -> AssemblyInfo> =
+        unsafe { library.get(mun_abi::GET_INFO_FN_NAME.as_bytes()) }?;
+
+    // The information refers to the memory of the library, so it is used before the library is
+    // unloaded.
+    let info = get_info();
+    Ok(Declarations::default().generate(&info, library_path))
+}
+
+/// The declarations of the public items of an assembly.
+#[derive(Default)]
+struct Declarations {
+    /// The declarations of the structs by their names, ordered to produce deterministic output
+    structs: BTreeMap<String, String>,
+}
+
+impl Declarations {
+    /// Generates the declarations of the functions of the assembly `info`.
+    fn generate(mut self, info: &AssemblyInfo, library_path: &Path) -> String {
+        let mut functions = String::new();
+        for function in info.symbols.functions() {
+            let name = function.prototype.name();
+            let signature = &function.prototype.signature;
+            let params: Option<Vec<String>> = signature
+                .arg_types()
+                .iter()
+                .enumerate()
+                .map(|(idx, ty)| Some(format!("arg{}: {}", idx, self.type_name(ty)?)))
+                .collect();
+            let ret = match signature.return_type() {
+                Some(ty) => self.type_name(ty).map(|ty| format!(" -> {}", ty)),
+                None => Some(String::new()),
+            };
+            if let (true, Some(params), Some(ret)) = (is_identifier(name), params, ret) {
+                writeln!(
+                    functions,
+                    "pub extern fn {}({}){};",
+                    name,
+                    params.join(", "),
+                    ret
+                )
+                .unwrap();
+            }
+        }
+
+        let mut source = format!(
+            "// The public items of the assembly '{}'.\n",
+            library_path.display()
+        );
+        for declaration in self.structs.values() {
+            writeln!(source, "{}", declaration).unwrap();
+        }
+        source.push_str(&functions);
+        source
+    }
+
+    /// Returns the name of the type `ty` in Mun source, declaring it if it is a struct. Returns
+    /// `None` if the type cannot be expressed in Mun.
+    fn type_name(&mut self, ty: &TypeInfo) -> Option<String> {
+        let struct_info = match ty.as_struct() {
+            Some(struct_info) => struct_info,
+            None => {
+                let name = ty.name().strip_prefix("core::")?;
+                return if is_identifier(name) {
+                    Some(name.to_owned())
+                } else {
+                    None
+                };
+            }
+        };
+
+        let name = ty.name();
+        if !is_identifier(name) {
+            return None;
+        }
+        if self.structs.contains_key(name) {
+            return Some(name.to_owned());
+        }
+
+        // Reserve the name before declaring the fields, which may refer to the struct itself
+        self.structs.insert(name.to_owned(), String::new());
+        let fields: Option<Vec<(&str, String)>> = struct_info
+            .field_names()
+            .zip(struct_info.field_types().iter())
+            .map(|(field_name, field_ty)| Some((field_name, self.type_name(field_ty)?)))
+            .collect();
+        let fields = match fields {
+            Some(fields) => fields,
+            None => {
+                self.structs.remove(name);
+                return None;
+            }
+        };
+
+        let memory_kind = match struct_info.memory_kind {
+            StructMemoryKind::GC => "gc",
+            StructMemoryKind::Value => "value",
+        };
+        let is_tuple = fields
+            .iter()
+            .enumerate()
+            .all(|(idx, (field_name, _))| *field_name == idx.to_string());
+        let declaration = if fields.is_empty() {
+            format!("pub struct({}) {};", memory_kind, name)
+        } else if is_tuple {
+            let field_types: Vec<_> = fields.into_iter().map(|(_, ty)| ty).collect();
+            format!(
+                "pub struct({}) {}({});",
+                memory_kind,
+                name,
+                field_types.join(", ")
+            )
+        } else if fields
+            .iter()
+            .all(|(field_name, _)| is_identifier(field_name))
+        {
+            let fields: Vec<_> = fields
+                .into_iter()
+                .map(|(field_name, ty)| format!("{}: {}", field_name, ty))
+                .collect();
+            format!(
+                "pub struct({}) {} {{ {} }}",
+                memory_kind,
+                name,
+                fields.join(", ")
+            )
+        } else {
+            self.structs.remove(name);
+            return None;
+        };
+        self.structs.insert(name.to_owned(), declaration);
+        Some(name.to_owned())
+    }
+}
+
+/// Returns true if `name` is a valid identifier in Mun source.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_identifier;
+
+    #[test]
+    fn identifiers() {
+        assert!(is_identifier("gravity"));
+        assert!(is_identifier("_Vec2"));
+        assert!(!is_identifier("0"));
+        assert!(!is_identifier("ai::attack"));
+        assert!(!is_identifier(""));
+    }
+}
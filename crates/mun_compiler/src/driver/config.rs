@@ -1,5 +1,6 @@
 use crate::DisplayColor;
-pub use mun_codegen::OptimizationLevel;
+pub use mun_codegen::{EmitKind, OptimizationLevel, OutputKind};
+use mun_hir::lint::LintLevels;
 use mun_target::spec::Target;
 use std::path::PathBuf;
 
@@ -18,6 +19,51 @@ pub struct Config {
 
     /// Whether or not to use colors in terminal output
     pub display_color: DisplayColor,
+
+    /// The format in which diagnostics are emitted.
+    pub message_format: MessageFormat,
+
+    /// Whether or not integer arithmetic is checked for overflow. If not specified, overflow
+    /// checks are only enabled when compiling without optimizations.
+    pub overflow_checks: Option<bool>,
+
+    /// Whether or not to emit debug information, which maps the generated machine code back to
+    /// the source code for native debuggers and profilers.
+    pub debug_info: bool,
+
+    /// The levels of lints that override their default levels.
+    pub lint_levels: LintLevels,
+
+    /// Whether or not to write a textual representation of the lowered bodies of all functions,
+    /// annotated with their inferred types, next to every assembly.
+    pub emit_hir: bool,
+
+    /// The intermediate artifacts of the code generation to write next to every assembly, e.g.
+    /// the LLVM IR.
+    pub emit: Vec<EmitKind>,
+
+    /// The kind of output to produce for every source file.
+    pub output_kind: OutputKind,
+
+    /// Whether or not to apply the fixes that are suggested by diagnostics to the source files
+    /// before compiling.
+    pub apply_fixes: bool,
+
+    /// Whether or not to compile all source files of a package into a single assembly, which is
+    /// named after the package, instead of an assembly for every source file.
+    pub single_assembly: bool,
+
+    /// The name of the assembly that all source files are compiled into if `single_assembly` is
+    /// set. If not specified, the assembly is named after the package.
+    pub assembly_name: Option<String>,
+
+    /// The number of threads that source files are analyzed and assemblies are compiled on. If not
+    /// specified, a thread is used for every logical CPU.
+    pub jobs: Option<usize>,
+
+    /// Whether or not to record the time that is spent in every phase of the compilation, see
+    /// [`Timings`](crate::timings::Timings).
+    pub timings: bool,
 }
 
 impl Default for Config {
@@ -30,6 +76,37 @@ impl Default for Config {
             optimization_lvl: OptimizationLevel::Default,
             out_dir: None,
             display_color: DisplayColor::Auto,
+            message_format: MessageFormat::Human,
+            overflow_checks: None,
+            debug_info: false,
+            lint_levels: LintLevels::default(),
+            emit_hir: false,
+            emit: Vec::new(),
+            output_kind: OutputKind::SharedLibrary,
+            apply_fixes: false,
+            single_assembly: false,
+            assembly_name: None,
+            jobs: None,
+            timings: false,
         }
     }
 }
+
+/// The format in which diagnostics are emitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// Human-readable messages that show the annotated source code
+    Human,
+    /// A JSON object per diagnostic on a line of its own, which is meant to be consumed by other
+    /// tools, like editors and build systems
+    Json,
+}
+
+impl Config {
+    /// Returns whether or not integer arithmetic is checked for overflow. By default, overflow
+    /// checks are only enabled when compiling without optimizations.
+    pub fn overflow_checks(&self) -> bool {
+        self.overflow_checks
+            .unwrap_or(self.optimization_lvl == OptimizationLevel::None)
+    }
+}
@@ -3,21 +3,25 @@ mod annotate;
 mod db;
 ///! This library contains the code required to go from source code to binaries.
 pub mod diagnostics;
+pub mod diagnostics_json;
 mod diagnostics_snippets;
 mod driver;
+pub mod timings;
 
-pub use mun_hir::{FileId, RelativePath, RelativePathBuf};
+pub use mun_hir::{
+    diagnostics::Severity, error_codes, lint, FileId, RelativePath, RelativePathBuf,
+};
 pub use mun_target::spec::Target;
 use std::path::{Path, PathBuf};
 
 pub use crate::driver::DisplayColor;
-pub use crate::driver::{Config, Driver};
+pub use crate::driver::{iter_source_files, Config, Driver, MessageFormat, RebuildReason};
 pub use annotate::{AnnotationBuilder, SliceBuilder, SnippetBuilder};
-pub use mun_codegen::OptimizationLevel;
+pub use mun_codegen::{EmitKind, JitAssembly, JitCode, OptimizationLevel, OutputKind};
 
 pub use crate::db::CompilerDatabase;
-pub use annotate_snippets::snippet::AnnotationType;
-use mun_project::Package;
+pub use annotate_snippets::snippet::{AnnotationType, Snippet};
+use mun_project::{Package, Workspace};
 use std::ffi::OsStr;
 use std::io::stderr;
 
@@ -66,31 +70,113 @@ pub fn is_source_file<P: AsRef<Path>>(p: P) -> bool {
     p.as_ref().extension() == Some(&OsStr::new("mun"))
 }
 
-/// Returns and creates the output dir for the specified package
+/// Returns and creates the output dir for the specified package. Unless the configuration specifies
+/// an output directory, this is the `target-dir` of the `build` section of its manifest.
 pub fn ensure_package_output_dir(
     package: &Package,
     config: &Config,
 ) -> Result<PathBuf, anyhow::Error> {
-    let out_dir = config
-        .out_dir
-        .clone()
-        .unwrap_or_else(|| package.root().join("target"));
+    let target_dir = package.manifest().build_settings().target_dir.as_deref();
+    let out_dir = config.out_dir.clone().unwrap_or_else(|| {
+        package
+            .root()
+            .join(target_dir.unwrap_or_else(|| Path::new("target")))
+    });
     std::fs::create_dir_all(&out_dir)?;
     Ok(out_dir)
 }
 
+/// Compiles all packages of the workspace of the manifest at `manifest_path`, see
+/// [`Workspace`]. Every package is compiled after the packages that it depends on. Returns `false`
+/// if a package failed to compile, in which case the remaining packages are not compiled.
+///
+/// If the workspace contains multiple packages and `config` or the `target-dir` of the root manifest
+/// specifies an output directory, the assemblies of every package are stored in a subdirectory
+/// named after the package.
 pub fn compile_manifest(manifest_path: &Path, config: Config) -> Result<bool, anyhow::Error> {
-    let (_package, mut driver) = Driver::with_package_path(manifest_path, config)?;
+    compile_manifest_assemblies(manifest_path, config).map(|assemblies| assemblies.is_some())
+}
+
+/// Compiles all packages of the workspace of the manifest at `manifest_path` like
+/// [`compile_manifest`]. Returns the paths of the assemblies of all packages, or `None` if a
+/// package failed to compile.
+pub fn compile_manifest_assemblies(
+    manifest_path: &Path,
+    config: Config,
+) -> Result<Option<Vec<PathBuf>>, anyhow::Error> {
+    let workspace = Workspace::from_file(manifest_path)?;
+    let is_multi_package = workspace.packages().len() > 1;
+    let out_dir = config.out_dir.clone().or_else(|| {
+        let target_dir = workspace.build_settings().target_dir.as_ref()?;
+        Some(workspace.root().join(target_dir))
+    });
+    let mut assemblies = Vec::new();
+    for package in workspace.packages() {
+        let mut config = config.clone();
+        config.out_dir = if is_multi_package {
+            out_dir.as_ref().map(|out_dir| out_dir.join(package.name()))
+        } else {
+            out_dir.clone()
+        };
+        match compile_package(package, config)? {
+            Some(paths) => assemblies.extend(paths),
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(assemblies))
+}
+
+/// Checks all packages of the workspace of the manifest at `manifest_path` for errors and emits
+/// their diagnostics, without generating code. Returns `false` if a package contains errors.
+pub fn check_manifest(manifest_path: &Path, config: Config) -> Result<bool, anyhow::Error> {
+    let workspace = Workspace::from_file(manifest_path)?;
+    let mut is_valid = true;
+    for package in workspace.packages() {
+        let driver = Driver::with_package(package, config.clone())?;
+        if driver.emit_diagnostics(&mut stderr())? {
+            is_valid = false;
+        }
+        driver.print_timings();
+    }
+    Ok(is_valid)
+}
+
+/// Compiles all source files of `package`. Returns the paths of the written assemblies, or `None`
+/// if the package failed to compile.
+fn compile_package(
+    package: &Package,
+    config: Config,
+) -> Result<Option<Vec<PathBuf>>, anyhow::Error> {
+    let apply_fixes = config.apply_fixes;
+    let mut driver = Driver::with_package(package, config)?;
+
+    if apply_fixes {
+        let source_directory = package
+            .source_directory()
+            .ok_or_else(|| anyhow::anyhow!("the source directory does not exist"))?;
+        driver.apply_fixes(&source_directory)?;
+    }
+
+    // Write the lowered HIR before checking for errors, it helps to understand them
+    driver.write_all_hir()?;
 
     // Emit diagnostics. If one of the snippets is an error, abort gracefully.
     if driver.emit_diagnostics(&mut stderr())? {
-        return Ok(false);
+        driver.print_timings();
+        return Ok(None);
     };
 
     // Write out all assemblies
     driver.write_all_assemblies()?;
-
-    Ok(true)
+    driver.print_timings();
+
+    Ok(Some(
+        driver
+            .assemblies()
+            .into_iter()
+            .map(|file_id| driver.assembly_output_path(file_id))
+            .collect(),
+    ))
 }
 
 /// Determines the relative path of a file to the source directory.
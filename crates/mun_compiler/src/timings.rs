@@ -0,0 +1,252 @@
+//! Self-profiling of the compiler, which is enabled with `--timings`. The time that is spent in
+//! every phase of the compilation is recorded per file from the `tracing` spans of the compiler
+//! and the code generation, so it shows whether a slow build is caused by the analysis of the
+//! source code or by LLVM.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Event, Metadata, Subscriber,
+};
+
+/// The phases of the compilation in the order in which they run, named after their spans.
+pub const PHASES: &[&str] = &["parse", "lower", "infer", "ir", "optimize", "emit", "link"];
+
+thread_local! {
+    /// The spans that are entered on the current thread, innermost last
+    static CURRENT_SPANS: RefCell<Vec<u64>> = RefCell::new(Vec::new());
+}
+
+/// A `tracing` subscriber that sums the time that is spent in every phase per file. Spans that
+/// do not specify a `file` belong to the file of their parent span.
+pub struct Timings {
+    next_id: AtomicU64,
+    spans: Mutex<HashMap<u64, SpanData>>,
+    durations: Mutex<BTreeMap<String, [Option<Duration>; PHASES.len()]>>,
+}
+
+struct SpanData {
+    /// The index of the phase in `PHASES`, if the span measures one
+    phase: Option<usize>,
+    file: Option<String>,
+    entered_at: Option<Instant>,
+    ref_count: usize,
+}
+
+impl Default for Timings {
+    fn default() -> Self {
+        Timings {
+            next_id: AtomicU64::new(1),
+            spans: Default::default(),
+            durations: Default::default(),
+        }
+    }
+}
+
+impl Timings {
+    /// Returns the time that was spent in `phase` for `file`, if any.
+    pub fn duration(&self, file: &str, phase: &str) -> Option<Duration> {
+        let phase = PHASES.iter().position(|name| *name == phase)?;
+        let durations = self.durations.lock().unwrap();
+        durations.get(file).and_then(|durations| durations[phase])
+    }
+}
+
+impl Subscriber for Timings {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.is_span()
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut visitor = FileVisitor(None);
+        span.record(&mut visitor);
+
+        let parent = span.parent().map(Id::into_u64).or_else(|| {
+            if span.is_contextual() {
+                CURRENT_SPANS.with(|spans| spans.borrow().last().copied())
+            } else {
+                None
+            }
+        });
+
+        let mut spans = self.spans.lock().unwrap();
+        let file = visitor.0.or_else(|| {
+            parent
+                .and_then(|parent| spans.get(&parent))
+                .and_then(|parent| parent.file.clone())
+        });
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        spans.insert(
+            id,
+            SpanData {
+                phase: PHASES
+                    .iter()
+                    .position(|name| *name == span.metadata().name()),
+                file,
+                entered_at: None,
+                ref_count: 1,
+            },
+        );
+        Id::from_u64(id)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, span: &Id) {
+        CURRENT_SPANS.with(|spans| spans.borrow_mut().push(span.into_u64()));
+        if let Some(data) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+            data.entered_at = Some(Instant::now());
+        }
+    }
+
+    fn exit(&self, span: &Id) {
+        CURRENT_SPANS.with(|spans| {
+            let mut spans = spans.borrow_mut();
+            if let Some(index) = spans.iter().rposition(|id| *id == span.into_u64()) {
+                spans.remove(index);
+            }
+        });
+
+        let mut spans = self.spans.lock().unwrap();
+        let data = match spans.get_mut(&span.into_u64()) {
+            Some(data) => data,
+            None => return,
+        };
+        if let (Some(phase), Some(entered_at)) = (data.phase, data.entered_at.take()) {
+            let file = data
+                .file
+                .clone()
+                .unwrap_or_else(|| String::from("<unknown>"));
+            let mut durations = self.durations.lock().unwrap();
+            let duration = &mut durations.entry(file).or_default()[phase];
+            *duration = Some(duration.unwrap_or_default() + entered_at.elapsed());
+        }
+    }
+
+    fn clone_span(&self, span: &Id) -> Id {
+        if let Some(data) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+            data.ref_count += 1;
+        }
+        span.clone()
+    }
+
+    fn try_close(&self, span: Id) -> bool {
+        let mut spans = self.spans.lock().unwrap();
+        let is_closed = match spans.get_mut(&span.into_u64()) {
+            Some(data) => {
+                data.ref_count -= 1;
+                data.ref_count == 0
+            }
+            None => false,
+        };
+        if is_closed {
+            spans.remove(&span.into_u64());
+        }
+        is_closed
+    }
+}
+
+/// Records the value of the `file` field of a span.
+struct FileVisitor(Option<String>);
+
+impl Visit for FileVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "file" {
+            self.0 = Some(value.to_owned());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "file" {
+            self.0 = Some(format!("{:?}", value));
+        }
+    }
+}
+
+/// Formats the timings as a table with a row per file and a column per phase. The time of a
+/// phase is summed over all threads, so the total can exceed the duration of the build.
+impl fmt::Display for Timings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let durations = self.durations.lock().unwrap();
+        let mut totals = [None; PHASES.len()];
+        for file_durations in durations.values() {
+            for (total, duration) in totals.iter_mut().zip(file_durations.iter()) {
+                if let Some(duration) = duration {
+                    *total = Some(total.unwrap_or_default() + *duration);
+                }
+            }
+        }
+
+        let file_width = durations
+            .keys()
+            .map(String::len)
+            .chain(std::iter::once("file".len()))
+            .max()
+            .unwrap_or_default();
+        let write_row = |f: &mut fmt::Formatter<'_>, file: &str, durations: &[Option<Duration>]| {
+            write!(f, "{:<width$}", file, width = file_width)?;
+            for duration in durations.iter() {
+                match duration {
+                    Some(duration) => write!(f, " {:>8.2}ms", duration.as_secs_f64() * 1000.0)?,
+                    None => write!(f, " {:>10}", "-")?,
+                }
+            }
+            let total: Duration = durations.iter().flatten().sum();
+            writeln!(f, " {:>8.2}ms", total.as_secs_f64() * 1000.0)
+        };
+
+        write!(f, "{:<width$}", "file", width = file_width)?;
+        for phase in PHASES.iter().chain(std::iter::once(&"total")) {
+            write!(f, " {:>10}", phase)?;
+        }
+        writeln!(f)?;
+        for (file, file_durations) in durations.iter() {
+            write_row(f, file, file_durations)?;
+        }
+        write_row(f, "total", &totals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Timings;
+
+    #[test]
+    fn records_phases_per_file() {
+        let dispatch = tracing::Dispatch::new(Timings::default());
+        tracing::dispatcher::with_default(&dispatch, || {
+            tracing::info_span!("parse", file = "main.mun").in_scope(|| ());
+            tracing::info_span!("codegen", file = "main.munlib").in_scope(|| {
+                tracing::info_span!("optimize").in_scope(|| ());
+            });
+        });
+
+        let timings = dispatch.downcast_ref::<Timings>().unwrap();
+        assert!(timings.duration("main.mun", "parse").is_some());
+        assert!(timings.duration("main.mun", "infer").is_none());
+        assert!(timings.duration("main.munlib", "optimize").is_some());
+        assert!(timings.duration("main.munlib", "codegen").is_none());
+
+        let report = timings.to_string();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("file"));
+        assert!(lines[1].starts_with("main.mun "));
+        assert!(lines[2].starts_with("main.munlib"));
+        assert!(lines[3].starts_with("total"));
+    }
+}
@@ -1,7 +1,9 @@
 use std::sync::mpsc::channel;
 use std::time::Duration;
 
-use mun_compiler::{compute_source_relative_path, is_source_file, Config, Driver};
+use mun_compiler::{
+    compute_source_relative_path, is_source_file, Config, Driver, FileId, RebuildReason,
+};
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 
 use std::io::stderr;
@@ -26,9 +28,8 @@ pub fn compile_and_watch_manifest(
     println!("Watching: {}", source_directory.display());
 
     // Emit all current errors, and write the assemblies if no errors occured
-    if !driver.emit_diagnostics(&mut stderr())? {
-        driver.write_all_assemblies()?
-    }
+    let mut rebuild = Rebuild::default();
+    rebuild.run(&mut driver)?;
 
     // Insert Ctrl+C handler so we can gracefully quit
     let should_quit = Arc::new(std::sync::atomic::AtomicBool::new(false));
@@ -47,30 +48,30 @@ pub fn compile_and_watch_manifest(
                     let relative_path = compute_source_relative_path(&source_directory, path)?;
                     let file_contents = std::fs::read_to_string(path)?;
                     log::info!("Modifying {}", relative_path);
-                    driver.update_file(relative_path, file_contents);
-                    if !driver.emit_diagnostics(&mut stderr())? {
-                        driver.write_all_assemblies()?;
-                    }
+                    rebuild.changed(driver.update_file(relative_path, file_contents));
+                    rebuild.run(&mut driver)?;
                 }
                 Create(ref path) if is_source_file(path) => {
                     let relative_path = compute_source_relative_path(&source_directory, path)?;
                     let file_contents = std::fs::read_to_string(path)?;
                     log::info!("Creating {}", relative_path);
-                    driver.add_file(relative_path, file_contents);
-                    if !driver.emit_diagnostics(&mut stderr())? {
-                        driver.write_all_assemblies()?;
-                    }
+                    rebuild.changed(driver.add_file(relative_path, file_contents));
+                    rebuild.run(&mut driver)?;
                 }
                 Remove(ref path) if is_source_file(path) => {
                     // Simply remove the source file from the source root
                     let relative_path = compute_source_relative_path(&source_directory, path)?;
                     log::info!("Removing {}", relative_path);
-                    let assembly_path = driver.assembly_output_path(driver.get_file_id_for_path(&relative_path).expect("cannot remove a file that was not part of the compilation in the first place"));
-                    if assembly_path.is_file() {
-                        std::fs::remove_file(assembly_path)?;
+                    if driver.assembly_name().is_none() {
+                        let assembly_path = driver.assembly_output_path(driver.get_file_id_for_path(&relative_path).expect("cannot remove a file that was not part of the compilation in the first place"));
+                        if assembly_path.is_file() {
+                            std::fs::remove_file(assembly_path)?;
+                        }
                     }
-                    driver.remove_file(relative_path);
-                    driver.emit_diagnostics(&mut stderr())?;
+                    // If all files are compiled into the assembly of the package, it is rebuilt
+                    // from the remaining files
+                    rebuild.changed(driver.remove_file(relative_path));
+                    rebuild.run(&mut driver)?;
                 }
                 Rename(ref from, ref to) => {
                     // Renaming is done by changing the relative path of the original source file but
@@ -80,10 +81,8 @@ pub fn compile_and_watch_manifest(
                     let to_relative_path = compute_source_relative_path(&source_directory, to)?;
 
                     log::info!("Renaming {} to {}", from_relative_path, to_relative_path,);
-                    driver.rename(from_relative_path, to_relative_path);
-                    if !driver.emit_diagnostics(&mut stderr())? {
-                        driver.write_all_assemblies()?;
-                    }
+                    rebuild.changed(driver.rename(from_relative_path, to_relative_path));
+                    rebuild.run(&mut driver)?;
                 }
                 _ => {}
             }
@@ -92,3 +91,64 @@ pub fn compile_and_watch_manifest(
 
     Ok(true)
 }
+
+/// Keeps track of the files that changed since the assemblies were last written, to only rebuild
+/// the assemblies that are affected by the changes.
+#[derive(Default)]
+struct Rebuild {
+    /// Whether all assemblies were written at least once
+    is_initialized: bool,
+    changed_files: Vec<FileId>,
+}
+
+impl Rebuild {
+    /// Marks the specified file as changed.
+    fn changed(&mut self, file_id: FileId) {
+        if !self.changed_files.contains(&file_id) {
+            self.changed_files.push(file_id);
+        }
+    }
+
+    /// Emits the diagnostics of all files and, if there are no errors, writes the assemblies that
+    /// are affected by the files that changed. Prints which assemblies are rebuilt and skipped,
+    /// and why. If there are errors, the changes are built once the errors are fixed.
+    fn run(&mut self, driver: &mut Driver) -> Result<(), anyhow::Error> {
+        driver.write_all_hir()?;
+        if driver.emit_diagnostics(&mut stderr())? {
+            return Ok(());
+        }
+
+        if !self.is_initialized {
+            driver.write_all_assemblies()?;
+            self.is_initialized = true;
+            self.changed_files.clear();
+            return Ok(());
+        }
+
+        let affected = driver.affected_assemblies(&self.changed_files);
+        let path = |file_id| {
+            driver
+                .get_path_for_file_id(file_id)
+                .map_or_else(|| format!("{:?}", file_id), ToString::to_string)
+        };
+        for assembly in driver.assemblies() {
+            let assembly_path = driver.assembly_output_path(assembly);
+            let name = assembly_path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            match affected.get(&assembly) {
+                Some(RebuildReason::Changed(file_id)) => {
+                    println!("Rebuilding {}: {} changed", name, path(*file_id))
+                }
+                Some(RebuildReason::DependsOn(file_id)) => {
+                    println!("Rebuilding {}: it depends on {}", name, path(*file_id))
+                }
+                None => println!("Skipping {}: it does not depend on the changes", name),
+            }
+        }
+        driver.write_assemblies(affected.keys().copied())?;
+        self.changed_files.clear();
+        Ok(())
+    }
+}
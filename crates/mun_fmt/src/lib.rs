@@ -0,0 +1,326 @@
+//! Formatting of Mun source code.
+//!
+//! The formatter works on the lossless syntax tree of `mun_syntax`, so comments are never lost.
+//! It keeps the line breaks of the source, but normalizes everything in between them:
+//!
+//! - tokens on the same line are separated by a single space where they need to be and by no
+//!   space otherwise, e.g. `foo( a ,b )` becomes `foo(a, b)`;
+//! - every line is indented by four spaces per open delimiter, and continuation lines of a
+//!   statement or item are indented once more;
+//! - at most one blank line separates two lines, and there are none directly after an opening
+//!   or before a closing delimiter;
+//! - opening braces and `else` are moved to the end of the previous line;
+//! - lists that end on their own line get a trailing comma, other lists lose it.
+
+use mun_syntax::{
+    AstNode, SourceFile, SyntaxError, SyntaxKind, SyntaxKind::*, SyntaxNode, SyntaxToken, TextRange,
+};
+use std::{collections::HashSet, fmt};
+
+#[cfg(test)]
+mod tests;
+
+/// The number of spaces per level of indentation
+const INDENT: &str = "    ";
+
+/// Formats the Mun source code `text`. Source code that contains syntax errors is not formatted,
+/// because its structure is unknown.
+pub fn format(text: &str) -> Result<String, FormatError> {
+    let parse = SourceFile::parse(text);
+    if !parse.errors().is_empty() {
+        return Err(FormatError {
+            errors: parse.errors().to_vec(),
+        });
+    }
+    Ok(Formatter::new(parse.tree().syntax()).format())
+}
+
+/// The error that is returned if source code cannot be formatted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormatError {
+    errors: Vec<SyntaxError>,
+}
+
+impl FormatError {
+    /// Returns the syntax errors of the source code.
+    pub fn errors(&self) -> &[SyntaxError] {
+        &self.errors
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the source code contains syntax errors")?;
+        if let Some(error) = self.errors.first() {
+            write!(f, ", e.g. {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+/// An opening delimiter of which the closing delimiter has not been formatted yet.
+struct Delimiter {
+    /// The node that the delimiter encloses
+    container: SyntaxNode,
+    /// The indentation of the line on which the delimiter was opened
+    indent: usize,
+}
+
+/// Formats a syntax tree token by token.
+struct Formatter {
+    root: SyntaxNode,
+    output: String,
+    delimiters: Vec<Delimiter>,
+    /// The indentation of the current line
+    line_indent: usize,
+    /// The ranges of the tokens that must be followed by a trailing comma
+    insert_comma_after: HashSet<TextRange>,
+    /// The ranges of the trailing commas that must be removed
+    remove_comma: HashSet<TextRange>,
+}
+
+impl Formatter {
+    fn new(root: &SyntaxNode) -> Self {
+        let mut formatter = Formatter {
+            root: root.clone(),
+            output: String::new(),
+            delimiters: Vec::new(),
+            line_indent: 0,
+            insert_comma_after: HashSet::new(),
+            remove_comma: HashSet::new(),
+        };
+        formatter.normalize_trailing_commas();
+        formatter
+    }
+
+    /// Determines which lists get or lose a trailing comma. A list gets one if its closing
+    /// delimiter is on its own line, otherwise it loses it.
+    fn normalize_trailing_commas(&mut self) {
+        for list in self.root.descendants().filter(|node| is_list(node.kind())) {
+            let close = match list
+                .children_with_tokens()
+                .filter_map(|element| element.into_token())
+                .find(|token| is_closing_delimiter(token.kind()))
+            {
+                Some(close) => close,
+                None => continue,
+            };
+            if list.children().next().is_none() {
+                continue;
+            }
+            let last = match prev_non_trivia_token(&close) {
+                Some(last) => last,
+                None => continue,
+            };
+            if starts_line(&close) {
+                if last.kind() != COMMA {
+                    self.insert_comma_after.insert(last.text_range());
+                }
+            } else if last.kind() == COMMA {
+                self.remove_comma.insert(last.text_range());
+            }
+        }
+    }
+
+    /// Formats all tokens of the syntax tree and returns the result.
+    fn format(mut self) -> String {
+        let mut prev: Option<SyntaxToken> = None;
+        let mut newlines = 0;
+        let tokens = self
+            .root
+            .descendants_with_tokens()
+            .filter_map(|element| element.into_token());
+        for token in tokens {
+            if token.kind() == WHITESPACE {
+                newlines += token.text().matches('\n').count();
+                continue;
+            }
+            if self.remove_comma.contains(&token.text_range()) {
+                continue;
+            }
+
+            let closed = if is_closing_delimiter(token.kind()) {
+                self.delimiters.pop()
+            } else {
+                None
+            };
+            if let Some(prev) = &prev {
+                let newlines = line_breaks(prev, &token, newlines);
+                if newlines > 0 {
+                    for _ in 0..newlines {
+                        self.output.push('\n');
+                    }
+                    // A closing delimiter is indented like the line that opened it
+                    self.line_indent = match &closed {
+                        Some(delimiter) => delimiter.indent,
+                        None => self.indent(prev, &token),
+                    };
+                    for _ in 0..self.line_indent {
+                        self.output.push_str(INDENT);
+                    }
+                } else if needs_space(prev, &token) {
+                    self.output.push(' ');
+                }
+            }
+
+            if token.kind() == COMMENT {
+                self.output.push_str(token.text().trim_end());
+            } else {
+                self.output.push_str(token.text());
+            }
+            if self.insert_comma_after.contains(&token.text_range()) {
+                self.output.push(',');
+            }
+            if is_opening_delimiter(token.kind()) {
+                self.delimiters.push(Delimiter {
+                    container: token.parent(),
+                    indent: self.line_indent,
+                });
+            }
+
+            prev = Some(token);
+            newlines = 0;
+        }
+
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+        self.output
+    }
+
+    /// Returns the indentation of the line that starts with `token`, which follows `prev` and is
+    /// not a closing delimiter.
+    fn indent(&self, prev: &SyntaxToken, token: &SyntaxToken) -> usize {
+        let (container, indent) = match self.delimiters.last() {
+            Some(delimiter) => (&delimiter.container, delimiter.indent + 1),
+            None => (&self.root, 0),
+        };
+
+        // A comment is indented like the code that follows it
+        let code = if token.kind() == COMMENT {
+            match next_non_trivia_token(token) {
+                Some(next) if is_closing_delimiter(next.kind()) && next.parent() == *container => {
+                    return indent;
+                }
+                Some(next) => next,
+                None => return indent,
+            }
+        } else {
+            token.clone()
+        };
+
+        if is_continuation(container, prev, &code) {
+            indent + 1
+        } else {
+            indent
+        }
+    }
+}
+
+/// Returns `true` if `code` continues the statement, item or element of `container` that
+/// contains it, i.e. if it is not the first token of that statement, item or element.
+fn is_continuation(container: &SyntaxNode, prev: &SyntaxToken, code: &SyntaxToken) -> bool {
+    let element = match code
+        .parent()
+        .ancestors()
+        .take_while(|node| node != container)
+        .last()
+    {
+        Some(element) => element,
+        None => return false,
+    };
+
+    // Attributes are on their own line before the item that they belong to
+    if let Some(prev_code) = if prev.kind() == COMMENT {
+        prev_non_trivia_token(prev)
+    } else {
+        Some(prev.clone())
+    } {
+        if prev_code.kind() == R_BRACKET && prev_code.parent().kind() == ATTR {
+            return false;
+        }
+    }
+
+    element
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .take_while(|token| token.text_range().start() < code.text_range().start())
+        .any(|token| !token.kind().is_trivia())
+}
+
+/// Returns the number of line breaks between `prev` and `token`, of which there are `newlines`
+/// in the source.
+fn line_breaks(prev: &SyntaxToken, token: &SyntaxToken, newlines: usize) -> usize {
+    let is_line_comment = prev.kind() == COMMENT && prev.text().starts_with("//");
+    if is_line_comment {
+        return newlines.max(1);
+    }
+
+    // Opening braces and `else` are on the same line as the code that precedes them
+    if prev.kind() != COMMENT
+        && (token.kind() == L_CURLY || (token.kind() == ELSE_KW && prev.kind() == R_CURLY))
+    {
+        return 0;
+    }
+
+    if is_opening_delimiter(prev.kind()) || is_closing_delimiter(token.kind()) {
+        newlines.min(1)
+    } else {
+        newlines.min(2)
+    }
+}
+
+/// Returns `true` if `prev` and `token`, which are on the same line, are separated by a space.
+fn needs_space(prev: &SyntaxToken, token: &SyntaxToken) -> bool {
+    let in_type_args = |token: &SyntaxToken| token.parent().kind() == TYPE_ARG_LIST;
+    match (prev.kind(), token.kind()) {
+        (_, COMMA) | (_, SEMI) | (_, COLON) | (_, DOT) | (_, INDEX) => false,
+        (DOT, _) | (COLONCOLON, _) | (_, COLONCOLON) | (HASH, _) => false,
+        (L_PAREN, _) | (L_BRACKET, _) | (_, R_PAREN) | (_, R_BRACKET) => false,
+        (L_CURLY, R_CURLY) => false,
+        (IDENT, L_PAREN) | (R_PAREN, L_PAREN) | (R_BRACKET, L_PAREN) => false,
+        (STRUCT_KW, L_PAREN) | (PUB_KW, L_PAREN) => false,
+        (IDENT, L_BRACKET) | (R_PAREN, L_BRACKET) | (R_BRACKET, L_BRACKET) => false,
+        (_, LT) | (_, GT) if in_type_args(token) => false,
+        (LT, _) if in_type_args(prev) => false,
+        (MINUS, _) | (EXCLAMATION, _) if prev.parent().kind() == PREFIX_EXPR => false,
+        _ => true,
+    }
+}
+
+/// Returns `true` if nodes of `kind` contain a comma-separated list between delimiters.
+fn is_list(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        PARAM_LIST | ARG_LIST | RECORD_FIELD_DEF_LIST | TUPLE_FIELD_DEF_LIST | RECORD_FIELD_LIST
+    )
+}
+
+fn is_opening_delimiter(kind: SyntaxKind) -> bool {
+    matches!(kind, L_PAREN | L_CURLY | L_BRACKET)
+}
+
+fn is_closing_delimiter(kind: SyntaxKind) -> bool {
+    matches!(kind, R_PAREN | R_CURLY | R_BRACKET)
+}
+
+/// Returns `true` if `token` is the first token on its line in the source.
+fn starts_line(token: &SyntaxToken) -> bool {
+    match token.prev_token() {
+        Some(prev) if prev.kind() == WHITESPACE => prev.text().contains('\n'),
+        Some(prev) => prev.kind() == COMMENT && prev.text().starts_with("//"),
+        None => true,
+    }
+}
+
+fn prev_non_trivia_token(token: &SyntaxToken) -> Option<SyntaxToken> {
+    std::iter::successors(token.prev_token(), |token| token.prev_token())
+        .find(|token| !token.kind().is_trivia())
+}
+
+fn next_non_trivia_token(token: &SyntaxToken) -> Option<SyntaxToken> {
+    std::iter::successors(token.next_token(), |token| token.next_token())
+        .find(|token| !token.kind().is_trivia())
+}
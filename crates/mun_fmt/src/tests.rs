@@ -0,0 +1,172 @@
+use crate::format;
+
+/// Asserts that `before` is formatted as `after` and that formatting `after` does not change it.
+fn check(before: &str, after: &str) {
+    assert_eq!(format(before).unwrap(), after);
+    assert_eq!(
+        format(after).unwrap(),
+        after,
+        "formatting is not idempotent"
+    );
+}
+
+#[test]
+fn spaces() {
+    check(
+        "pub  fn add( a :i32,b:  i32 )->i32{a+b}",
+        "pub fn add(a: i32, b: i32) -> i32 { a + b }\n",
+    );
+    check(
+        "fn main() { let a = - foo ( 1 ) . b . 0; let c = ! a ; bar :: baz( a ) }",
+        "fn main() { let a = -foo(1).b.0; let c = !a; bar::baz(a) }\n",
+    );
+    check(
+        "#[test]\nfn adds() {}\nstruct ( gc ) Foo(Bar < i32 >);",
+        "#[test]\nfn adds() {}\nstruct(gc) Foo(Bar<i32>);\n",
+    );
+    check(
+        "fn main() { 'outer : loop { break 'outer ; } }",
+        "fn main() { 'outer: loop { break 'outer; } }\n",
+    );
+}
+
+#[test]
+fn indentation() {
+    check(
+        r#"
+fn main() {
+let a = 1;
+      if a > 0 {
+  foo(a);
+          } else {
+bar(
+a,
+  b,
+);
+ }
+}
+"#,
+        r#"fn main() {
+    let a = 1;
+    if a > 0 {
+        foo(a);
+    } else {
+        bar(
+            a,
+            b,
+        );
+    }
+}
+"#,
+    );
+}
+
+#[test]
+fn continuation_lines() {
+    check(
+        r#"
+fn main() -> i32 {
+    let a = foo
+    + bar;
+        a
+}
+
+pub fn long(
+a: i32,
+    b: i32
+) -> i32 {
+    a *
+  b
+}
+"#,
+        r#"fn main() -> i32 {
+    let a = foo
+        + bar;
+    a
+}
+
+pub fn long(
+    a: i32,
+    b: i32,
+) -> i32 {
+    a *
+        b
+}
+"#,
+    );
+}
+
+#[test]
+fn blank_lines() {
+    check(
+        "\n\n\nfn a() {\n\n    b();\n\n\n\n    c();\n\n}\n\n\n\nfn d() {}\n\n\n",
+        "fn a() {\n    b();\n\n    c();\n}\n\nfn d() {}\n",
+    );
+}
+
+#[test]
+fn braces_and_else() {
+    check(
+        "fn main()\n{\n    if a\n    {\n        b\n    }\n    else\n    {\n        c\n    }\n}",
+        "fn main() {\n    if a {\n        b\n    } else {\n        c\n    }\n}\n",
+    );
+}
+
+#[test]
+fn trailing_commas() {
+    check(
+        "struct Foo { a: i32, b: f32, }\nfn main() { foo(1, 2,); Foo { a: 1, b: 2.0, }; }",
+        "struct Foo { a: i32, b: f32 }\nfn main() { foo(1, 2); Foo { a: 1, b: 2.0 }; }\n",
+    );
+    check(
+        "struct Foo {\n    a: i32,\n    b: f32\n}\nstruct Bar(\n    i32\n);",
+        "struct Foo {\n    a: i32,\n    b: f32,\n}\nstruct Bar(\n    i32,\n);\n",
+    );
+    check(
+        "fn main() {\n    foo(\n        1 // one\n    );\n}",
+        "fn main() {\n    foo(\n        1, // one\n    );\n}\n",
+    );
+}
+
+#[test]
+fn comments() {
+    check(
+        r#"
+// The entry point
+#[test]
+/// Adds numbers
+  fn main() {   // starts here
+      let a = 1;    // one
+  /* block */ let b = 2;
+        // the sum
+   a + b
+    // done
+}
+"#,
+        r#"// The entry point
+#[test]
+/// Adds numbers
+fn main() { // starts here
+    let a = 1; // one
+    /* block */ let b = 2;
+    // the sum
+    a + b
+    // done
+}
+"#,
+    );
+}
+
+#[test]
+fn strings_are_preserved() {
+    check(
+        "fn main() {  core::panic(\"a  ,  b\n  c\") }",
+        "fn main() { core::panic(\"a  ,  b\n  c\") }\n",
+    );
+}
+
+#[test]
+fn syntax_errors() {
+    let error = format("fn main( {").unwrap_err();
+    assert!(!error.errors().is_empty());
+}
@@ -3,10 +3,12 @@ use std::{fmt, sync::Arc};
 use crate::type_ref::{TypeRefBuilder, TypeRefId, TypeRefMap, TypeRefSourceMap};
 use crate::{
     arena::{Arena, RawId},
+    expr::CmpOp,
     ids::{AstItemDef, StructId},
-    AsName, DefDatabase, Name,
+    name::name,
+    AsName, Attrs, DefDatabase, Name, Struct,
 };
-use mun_syntax::ast::{self, NameOwner, TypeAscriptionOwner};
+use mun_syntax::ast::{self, AttrsOwner, NameOwner, TypeAscriptionOwner};
 
 pub use mun_syntax::ast::StructMemoryKind;
 
@@ -26,6 +28,9 @@ pub use mun_syntax::ast::StructMemoryKind;
 pub struct StructFieldData {
     pub name: Name,
     pub type_ref: TypeRefId,
+    /// The previous name of the field, as specified by a `#[rename_from(old_name)]` attribute.
+    /// When an assembly is hot reloaded, the value of the old field is moved to this field.
+    pub renamed_from: Option<Name>,
 }
 
 /// An identifier for a struct's or tuple's field
@@ -51,12 +56,65 @@ impl fmt::Display for StructKind {
     }
 }
 
+/// A comparison trait that can be derived for a struct, e.g. `#[derive(Eq, Ord)]`. Derived
+/// comparisons compare the fields of two structs in declaration order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DeriveTrait {
+    /// Enables `==` and `!=`
+    Eq,
+    /// Enables `<`, `<=`, `>` and `>=`
+    Ord,
+}
+
+impl DeriveTrait {
+    /// Returns the derivable trait with the specified name.
+    pub(crate) fn from_name(name: &Name) -> Option<DeriveTrait> {
+        if *name == name![Eq] {
+            Some(DeriveTrait::Eq)
+        } else if *name == name![Ord] {
+            Some(DeriveTrait::Ord)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the trait that is required to apply the specified comparison operator.
+    pub(crate) fn from_cmp_op(op: CmpOp) -> DeriveTrait {
+        match op {
+            CmpOp::Eq { .. } => DeriveTrait::Eq,
+            CmpOp::Ord { .. } => DeriveTrait::Ord,
+        }
+    }
+}
+
+impl fmt::Display for DeriveTrait {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeriveTrait::Eq => write!(f, "Eq"),
+            DeriveTrait::Ord => write!(f, "Ord"),
+        }
+    }
+}
+
+/// Returns the names listed in all `#[derive(..)]` attributes of an item.
+pub(crate) fn derive_names(owner: &impl AttrsOwner) -> impl Iterator<Item = (ast::Attr, Name)> {
+    owner
+        .attrs()
+        .filter(|attr| attr.simple_name().map_or(false, |name| name == "derive"))
+        .filter_map(|attr| attr.token_tree().map(|tt| (attr, tt)))
+        .flat_map(|(attr, tt)| {
+            tt.idents()
+                .map(move |ident| (attr.clone(), Name::new_from_text(ident.text())))
+        })
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct StructData {
     pub name: Name,
     pub fields: Arena<StructFieldId, StructFieldData>,
     pub kind: StructKind,
     pub memory_kind: StructMemoryKind,
+    pub derives: Vec<DeriveTrait>,
     type_ref_map: TypeRefMap,
     type_ref_source_map: TypeRefSourceMap,
 }
@@ -76,6 +134,13 @@ impl StructData {
             .map(|s| s.kind())
             .unwrap_or_default();
 
+        let derives = db
+            .attrs(Struct { id }.into())
+            .by_name(&name![derive])
+            .flat_map(|attr| attr.idents())
+            .filter_map(DeriveTrait::from_name)
+            .collect();
+
         let mut type_ref_builder = TypeRefBuilder::default();
         let (fields, kind) = match src.value.kind() {
             ast::StructKind::Record(r) => {
@@ -84,6 +149,7 @@ impl StructData {
                     .map(|fd| StructFieldData {
                         name: fd.name().map(|n| n.as_name()).unwrap_or_else(Name::missing),
                         type_ref: type_ref_builder.alloc_from_node_opt(fd.ascribed_type().as_ref()),
+                        renamed_from: Attrs::from_attrs_owner(&fd).renamed_from(),
                     })
                     .collect();
                 (fields, StructKind::Record)
@@ -95,6 +161,7 @@ impl StructData {
                     .map(|(index, fd)| StructFieldData {
                         name: Name::new_tuple_field(index),
                         type_ref: type_ref_builder.alloc_from_node_opt(fd.type_ref().as_ref()),
+                        renamed_from: None,
                     })
                     .collect();
                 (fields, StructKind::Tuple)
@@ -108,6 +175,7 @@ impl StructData {
             fields,
             kind,
             memory_kind,
+            derives,
             type_ref_map,
             type_ref_source_map,
         })
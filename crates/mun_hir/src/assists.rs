@@ -0,0 +1,208 @@
+//! Assists are changes of the source code that an editor offers for the code at the cursor,
+//! independent of any diagnostic, e.g. to introduce a variable for the selected expression.
+
+use crate::{
+    adt::StructKind,
+    completion::function_at,
+    diagnostics::{default_value, SourceChange},
+    expr::{resolver_for_expr, BodySourceMap},
+    Body, Expr, FileId, HirDatabase, InferenceResult, Name, Ty,
+};
+use mun_syntax::{
+    ast::{self, edit, make, AstNodeEdit},
+    AstNode, SyntaxElement, SyntaxNode, TextRange,
+};
+use std::sync::Arc;
+
+/// The state that is shared by all assists.
+struct AssistContext<'a> {
+    db: &'a dyn HirDatabase,
+    file_id: FileId,
+    /// The selected range, which is empty if nothing is selected
+    range: TextRange,
+    /// The innermost node that contains the range
+    covering_node: SyntaxNode,
+    body: Arc<Body>,
+    source_map: Arc<BodySourceMap>,
+    infer: Arc<InferenceResult>,
+}
+
+/// Returns the assists that are applicable to the specified range in a file.
+pub fn assists(db: &dyn HirDatabase, file_id: FileId, range: TextRange) -> Vec<SourceChange> {
+    // All assists apply to the body of a function
+    let function = match function_at(db, file_id, range.start()) {
+        Some(function) => function,
+        None => return Vec::new(),
+    };
+    let root = db.parse(file_id).syntax_node();
+    let covering_node = match root.covering_element(range) {
+        SyntaxElement::Node(node) => node,
+        SyntaxElement::Token(token) => token.parent(),
+    };
+    let (body, source_map) = db.body_with_source_map(function.into());
+    let ctx = AssistContext {
+        db,
+        file_id,
+        range,
+        covering_node,
+        body,
+        source_map,
+        infer: function.infer(db),
+    };
+
+    let assists: [fn(&AssistContext) -> Option<SourceChange>; 2] =
+        [add_missing_fields, introduce_variable];
+    assists.iter().filter_map(|assist| assist(&ctx)).collect()
+}
+
+/// Adds the fields of a struct that are missing from the record literal at the cursor. The values
+/// of the fields are taken from the spread expression, e.g. `b: foo.b` for `Foo { a: 1, ..foo }`,
+/// or are placeholder values otherwise.
+fn add_missing_fields(ctx: &AssistContext) -> Option<SourceChange> {
+    let record_lit = ctx
+        .covering_node
+        .ancestors()
+        .find_map(ast::RecordLit::cast)?;
+    let expr = ctx
+        .source_map
+        .node_expr(&ast::Expr::cast(record_lit.syntax().clone())?)?;
+    let (fields, spread) = match &ctx.body[expr] {
+        Expr::RecordLit { fields, spread, .. } => (fields, *spread),
+        _ => return None,
+    };
+    let s = ctx.infer[expr].as_struct()?;
+    if s.data(ctx.db.upcast()).kind != StructKind::Record {
+        return None;
+    }
+
+    // The spread expression is only repeated for every field if it has no side effects
+    let spread = match spread {
+        Some(spread) => match &ctx.body[spread] {
+            Expr::Path(_) => Some(
+                record_lit
+                    .record_field_list()?
+                    .spread()?
+                    .syntax()
+                    .text()
+                    .to_string(),
+            ),
+            _ => return None,
+        },
+        None => None,
+    };
+    let missing_fields = s
+        .fields(ctx.db)
+        .into_iter()
+        .filter(|field| fields.iter().all(|it| it.name != field.name(ctx.db)))
+        .map(|field| {
+            let name = field.name(ctx.db).to_string();
+            let value = match &spread {
+                Some(spread) => format!("{}.{}", spread, name),
+                None => default_value(&field.ty(ctx.db))?.to_string(),
+            };
+            Some(make::record_field(
+                make::name_ref(&name),
+                Some(make::expr_from_text(&value)),
+            ))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if missing_fields.is_empty() {
+        return None;
+    }
+
+    let field_list = record_lit.record_field_list()?;
+    let new_field_list = field_list.append_fields(missing_fields);
+    Some(SourceChange {
+        label: "add missing fields".to_string(),
+        file_id: ctx.file_id,
+        edits: vec![edit::text_edit(&field_list, &new_field_list)],
+    })
+}
+
+/// Binds the selected expression to a new variable before the statement that contains it and
+/// replaces the expression by the variable.
+fn introduce_variable(ctx: &AssistContext) -> Option<SourceChange> {
+    if ctx.range.is_empty() {
+        return None;
+    }
+    let expr = ctx.covering_node.ancestors().find_map(ast::Expr::cast)?;
+    let expr_id = ctx.source_map.node_expr(&expr)?;
+    let ty = &ctx.infer[expr_id];
+    // Functions are not values that can be bound to a variable
+    if ty.is_empty() || ty.is_never() || *ty == Ty::Unknown || ty.as_callable_def().is_some() {
+        return None;
+    }
+
+    // The statement or tail expression of the innermost block that contains the expression
+    let anchor = expr.syntax().ancestors().find(|node| {
+        node.parent()
+            .map_or(false, |it| ast::BlockExpr::can_cast(it.kind()))
+    })?;
+    // Binding the whole statement, the initializer of a `let` statement or the value of a block
+    // to a variable has no use
+    if anchor == *expr.syntax() || ast::BlockExpr::can_cast(expr.syntax().kind()) {
+        return None;
+    }
+    if expr.syntax().parent().as_ref() == Some(&anchor) {
+        return None;
+    }
+    if !is_evaluated_once(expr.syntax(), &anchor) {
+        return None;
+    }
+
+    // A name that does not shadow another binding
+    let resolver = resolver_for_expr(ctx.body.clone(), ctx.db, expr_id);
+    let name = std::iter::once("var".to_string())
+        .chain((1..).map(|index| format!("var{}", index)))
+        .find(|name| {
+            resolver
+                .resolve_name(ctx.db, &Name::new_from_text(name))
+                .take_values()
+                .is_none()
+        })?;
+
+    let block = ast::BlockExpr::cast(anchor.parent()?)?;
+    let anchor_index = block.syntax().children().position(|it| it == anchor)?;
+    let new_block = block.replace_descendant(&expr, make::expr_from_text(&name));
+    let new_anchor = new_block.syntax().children().nth(anchor_index)?;
+    let let_stmt = make::let_stmt(make::name(&name), expr.clone());
+    let new_block = new_block.insert_statement(&new_anchor, let_stmt.into());
+    Some(SourceChange {
+        label: "introduce variable".to_string(),
+        file_id: ctx.file_id,
+        edits: vec![edit::text_edit(&block, &new_block)],
+    })
+}
+
+/// Returns true if the expression is evaluated exactly once whenever `anchor`, one of its
+/// ancestors, is evaluated. This is not the case for e.g. the condition of a `while` loop or the
+/// right-hand side of `&&`.
+fn is_evaluated_once(expr: &SyntaxNode, anchor: &SyntaxNode) -> bool {
+    for node in expr.ancestors().take_while(|node| node != anchor) {
+        let parent = match node.parent() {
+            Some(parent) => parent,
+            None => return false,
+        };
+        if let Some(bin_expr) = ast::BinExpr::cast(parent.clone()) {
+            let is_lazy = matches!(
+                bin_expr.op_kind(),
+                Some(ast::BinOp::BooleanAnd) | Some(ast::BinOp::BooleanOr)
+            );
+            if is_lazy && bin_expr.rhs().map_or(false, |rhs| *rhs.syntax() == node) {
+                return false;
+            }
+        } else if ast::Condition::can_cast(parent.kind()) {
+            let is_loop = parent
+                .parent()
+                .map_or(false, |it| ast::WhileExpr::can_cast(it.kind()));
+            if is_loop {
+                return false;
+            }
+        } else if ast::Expr::can_cast(node.kind()) && ast::IfExpr::can_cast(parent.kind()) {
+            // The branches of an `if` expression, including an `else if`, are only evaluated
+            // depending on the condition
+            return false;
+        }
+    }
+    true
+}
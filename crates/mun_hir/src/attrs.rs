@@ -0,0 +1,181 @@
+use crate::code_model::src::HasSource;
+use crate::{name::name, AsName, DefDatabase, Function, Impl, Name, Struct};
+use mun_syntax::{ast, ast::AttrsOwner, AstNode, SmolStr, SyntaxKind, SyntaxToken};
+use std::sync::Arc;
+
+/// The definitions that can have attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttrDef {
+    Function(Function),
+    Struct(Struct),
+    Impl(Impl),
+}
+impl_froms!(AttrDef: Function, Struct, Impl);
+
+/// An attribute of an item, e.g. `#[inline(always)]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attr {
+    /// The name of the attribute, e.g. `inline`
+    pub name: Name,
+    /// The arguments of the attribute, e.g. `always`
+    pub args: Vec<AttrArg>,
+}
+
+/// An argument of an attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttrArg {
+    /// A single identifier, e.g. `always` in `#[inline(always)]`
+    Ident(Name),
+    /// A key with a literal value, e.g. `name = "foo"` in `#[export(name = "foo")]`. The quotes
+    /// of a string literal are not part of the value.
+    KeyValue { key: Name, value: SmolStr },
+}
+
+/// How a function should be inlined, as specified by an `#[inline]` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InlineKind {
+    /// `#[inline]`, suggests that the function should be inlined
+    Hint,
+    /// `#[inline(always)]`, the function should always be inlined
+    Always,
+    /// `#[inline(never)]`, the function should never be inlined
+    Never,
+}
+
+impl InlineKind {
+    /// Returns the kind of inlining requested by the specified `#[inline]` attribute, or `None` if
+    /// the arguments of the attribute are invalid.
+    pub(crate) fn from_attr(attr: &Attr) -> Option<InlineKind> {
+        match attr.args.as_slice() {
+            [] => Some(InlineKind::Hint),
+            [AttrArg::Ident(name)] if *name == name![always] => Some(InlineKind::Always),
+            [AttrArg::Ident(name)] if *name == name![never] => Some(InlineKind::Never),
+            _ => None,
+        }
+    }
+}
+
+impl Attr {
+    /// Lowers the specified attribute. Returns `None` if the attribute has no name.
+    pub(crate) fn from_ast(attr: &ast::Attr) -> Option<Attr> {
+        let name = attr.path()?.segment()?.name_ref()?.as_name();
+        let args = attr
+            .token_tree()
+            .map(|tt| parse_args(&tt))
+            .unwrap_or_default();
+        Some(Attr { name, args })
+    }
+
+    /// Returns the identifiers that are passed to the attribute, e.g. `Eq` and `Ord` in
+    /// `#[derive(Eq, Ord)]`.
+    pub fn idents(&self) -> impl Iterator<Item = &Name> {
+        self.args.iter().filter_map(|arg| match arg {
+            AttrArg::Ident(name) => Some(name),
+            AttrArg::KeyValue { .. } => None,
+        })
+    }
+
+    /// Returns the value of the argument with the specified key.
+    pub fn value(&self, key: &Name) -> Option<&SmolStr> {
+        self.args.iter().find_map(|arg| match arg {
+            AttrArg::KeyValue { key: k, value } if k == key => Some(value),
+            _ => None,
+        })
+    }
+}
+
+/// The attributes of an item in the order in which they are declared.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Attrs {
+    attrs: Vec<Attr>,
+}
+
+impl Attrs {
+    pub(crate) fn attrs_query(db: &dyn DefDatabase, def: AttrDef) -> Arc<Attrs> {
+        let attrs = match def {
+            AttrDef::Function(it) => Attrs::from_attrs_owner(&it.source(db).value),
+            AttrDef::Struct(it) => Attrs::from_attrs_owner(&it.source(db).value),
+            AttrDef::Impl(it) => Attrs::from_attrs_owner(&it.source(db).value),
+        };
+        Arc::new(attrs)
+    }
+
+    pub(crate) fn from_attrs_owner(owner: &impl AttrsOwner) -> Attrs {
+        let attrs = owner
+            .attrs()
+            .filter_map(|attr| Attr::from_ast(&attr))
+            .collect();
+        Attrs { attrs }
+    }
+
+    /// Returns all attributes.
+    pub fn iter(&self) -> impl Iterator<Item = &Attr> {
+        self.attrs.iter()
+    }
+
+    /// Returns all attributes with the specified name.
+    pub fn by_name(&self, name: &Name) -> impl Iterator<Item = &Attr> {
+        let name = name.clone();
+        self.attrs.iter().filter(move |attr| attr.name == name)
+    }
+
+    /// Returns true if an attribute with the specified name is present.
+    pub fn has(&self, name: &Name) -> bool {
+        self.by_name(name).next().is_some()
+    }
+
+    /// Returns the kind of inlining requested by the first valid `#[inline]` attribute.
+    pub fn inline(&self) -> Option<InlineKind> {
+        self.by_name(&name![inline]).find_map(InlineKind::from_attr)
+    }
+
+    /// Returns the previous name of a field as specified by the first `#[rename_from(old_name)]`
+    /// attribute with a single identifier.
+    pub fn renamed_from(&self) -> Option<Name> {
+        self.by_name(&name![rename_from])
+            .find_map(|attr| match attr.args.as_slice() {
+                [AttrArg::Ident(name)] => Some(name.clone()),
+                _ => None,
+            })
+    }
+}
+
+/// Parses the comma separated arguments of an attribute. Arguments that are neither an
+/// identifier, nor a key-value pair are ignored.
+fn parse_args(tt: &ast::TokenTree) -> Vec<AttrArg> {
+    let tokens: Vec<SyntaxToken> = tt
+        .syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| match it.kind() {
+            SyntaxKind::WHITESPACE
+            | SyntaxKind::COMMENT
+            | SyntaxKind::L_PAREN
+            | SyntaxKind::R_PAREN => false,
+            _ => true,
+        })
+        .collect();
+
+    tokens
+        .split(|token| token.kind() == SyntaxKind::COMMA)
+        .filter_map(|arg| match arg {
+            [ident] if ident.kind() == SyntaxKind::IDENT => {
+                Some(AttrArg::Ident(Name::new_from_text(ident.text())))
+            }
+            [key, eq, value] if key.kind() == SyntaxKind::IDENT && eq.kind() == SyntaxKind::EQ => {
+                let value = match value.kind() {
+                    SyntaxKind::STRING => {
+                        let text = value.text();
+                        SmolStr::new(text.get(1..text.len() - 1).unwrap_or_default())
+                    }
+                    _ => value.text().clone(),
+                };
+                Some(AttrArg::KeyValue {
+                    key: Name::new_from_text(key.text()),
+                    value,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
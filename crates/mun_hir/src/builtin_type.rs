@@ -1,3 +1,4 @@
+use crate::expr::ArithOp;
 use crate::name::{name, Name};
 use std::fmt;
 
@@ -45,12 +46,32 @@ pub enum BuiltinType {
     Float(BuiltinFloat),
     Int(BuiltinInt),
     Bool,
+    Char,
+    /// A value that can be safely accessed from multiple threads. Written as `Atomic<T>`.
+    Atomic,
+    /// A location in the source code, as returned by `core::source_location()`.
+    SourceLocation,
+    /// A dynamically typed value. Written as `any`.
+    Any,
+    /// An opaque value that is owned by the host. Written as `Handle`.
+    Handle,
+    /// A vector of `f32` values with the specified number of lanes. Written as `float2`, `float3`
+    /// or `float4`.
+    FloatVector(u32),
 }
 
 impl BuiltinType {
     #[rustfmt::skip]
     pub const ALL: &'static [(Name, BuiltinType)] = &[
         (name![bool],  BuiltinType::Bool),
+        (name![char],  BuiltinType::Char),
+        (name![any],   BuiltinType::Any),
+        (name![Atomic], BuiltinType::Atomic),
+        (name![SourceLocation], BuiltinType::SourceLocation),
+        (name![Handle], BuiltinType::Handle),
+        (name![float2], BuiltinType::FloatVector(2)),
+        (name![float3], BuiltinType::FloatVector(3)),
+        (name![float4], BuiltinType::FloatVector(4)),
 
         (name![isize], BuiltinType::Int(BuiltinInt::ISIZE)),
         (name![i8],    BuiltinType::Int(BuiltinInt::I8)),
@@ -71,10 +92,270 @@ impl BuiltinType {
     ];
 }
 
+/// A method that is defined on a builtin type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuiltinMethod {
+    /// `Atomic<T>::load(self) -> T`, atomically loads the value.
+    AtomicLoad,
+    /// `Atomic<T>::store(self, value: T)`, atomically stores a new value.
+    AtomicStore,
+    /// `Atomic<T>::fetch_add(self, value: T) -> T`, atomically adds to the value and returns the
+    /// previous value. Overflow wraps around.
+    AtomicFetchAdd,
+    /// `Optional<T>::is_some(self) -> bool`, returns true if the optional holds a value.
+    OptionalIsSome,
+    /// `Optional<T>::unwrap_or(self, default: T) -> T`, returns the value of the optional or
+    /// `default` if it does not hold a value.
+    OptionalUnwrapOr,
+}
+
+impl BuiltinMethod {
+    /// Returns the method of an `Atomic<T>` with the specified name.
+    pub(crate) fn atomic_from_name(name: &Name) -> Option<BuiltinMethod> {
+        if *name == name![load] {
+            Some(BuiltinMethod::AtomicLoad)
+        } else if *name == name![store] {
+            Some(BuiltinMethod::AtomicStore)
+        } else if *name == name![fetch_add] {
+            Some(BuiltinMethod::AtomicFetchAdd)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the method of an `Optional<T>` with the specified name.
+    pub(crate) fn optional_from_name(name: &Name) -> Option<BuiltinMethod> {
+        if *name == name![is_some] {
+            Some(BuiltinMethod::OptionalIsSome)
+        } else if *name == name![unwrap_or] {
+            Some(BuiltinMethod::OptionalUnwrapOr)
+        } else {
+            None
+        }
+    }
+}
+
+/// A math function of the `core` module, e.g. `core::sqrt(x)`. The arguments and the result of a
+/// math function all have the same type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MathIntrinsic {
+    /// `core::sqrt(x)`, the square root of a floating point value
+    Sqrt,
+    /// `core::sin(x)`, the sine of a floating point value in radians
+    Sin,
+    /// `core::cos(x)`, the cosine of a floating point value in radians
+    Cos,
+    /// `core::pow(x, y)`, a floating point value raised to a floating point power
+    Pow,
+    /// `core::abs(x)`, the absolute value of a number
+    Abs,
+    /// `core::min(a, b)`, the smallest of two numbers
+    Min,
+    /// `core::max(a, b)`, the largest of two numbers
+    Max,
+}
+
+impl MathIntrinsic {
+    /// Returns the math function with the specified name.
+    pub(crate) fn from_name(name: &Name) -> Option<MathIntrinsic> {
+        if *name == name![sqrt] {
+            Some(MathIntrinsic::Sqrt)
+        } else if *name == name![sin] {
+            Some(MathIntrinsic::Sin)
+        } else if *name == name![cos] {
+            Some(MathIntrinsic::Cos)
+        } else if *name == name![pow] {
+            Some(MathIntrinsic::Pow)
+        } else if *name == name![abs] {
+            Some(MathIntrinsic::Abs)
+        } else if *name == name![min] {
+            Some(MathIntrinsic::Min)
+        } else if *name == name![max] {
+            Some(MathIntrinsic::Max)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the name of the function in the `core` module.
+    pub fn name(self) -> &'static str {
+        match self {
+            MathIntrinsic::Sqrt => "sqrt",
+            MathIntrinsic::Sin => "sin",
+            MathIntrinsic::Cos => "cos",
+            MathIntrinsic::Pow => "pow",
+            MathIntrinsic::Abs => "abs",
+            MathIntrinsic::Min => "min",
+            MathIntrinsic::Max => "max",
+        }
+    }
+
+    /// Returns the number of arguments of the function.
+    pub fn arg_count(self) -> usize {
+        match self {
+            MathIntrinsic::Sqrt | MathIntrinsic::Sin | MathIntrinsic::Cos | MathIntrinsic::Abs => 1,
+            MathIntrinsic::Pow | MathIntrinsic::Min | MathIntrinsic::Max => 2,
+        }
+    }
+
+    /// Returns true if the function only accepts floating point values, including vectors of
+    /// floating point values. The other functions also accept integers.
+    pub fn is_float_only(self) -> bool {
+        match self {
+            MathIntrinsic::Sqrt | MathIntrinsic::Sin | MathIntrinsic::Cos | MathIntrinsic::Pow => {
+                true
+            }
+            MathIntrinsic::Abs | MathIntrinsic::Min | MathIntrinsic::Max => false,
+        }
+    }
+}
+
+/// A builtin trait that overloads an arithmetic operator for a struct, e.g.
+/// `impl Add for Vec2 { fn add(lhs: Vec2, rhs: Vec2) -> Vec2 { .. } }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperatorTrait {
+    /// `Add::add(lhs, rhs)`, overloads `lhs + rhs`
+    Add,
+    /// `Sub::sub(lhs, rhs)`, overloads `lhs - rhs`
+    Sub,
+    /// `Mul::mul(lhs, rhs)`, overloads `lhs * rhs`
+    Mul,
+    /// `Div::div(lhs, rhs)`, overloads `lhs / rhs`
+    Div,
+    /// `Rem::rem(lhs, rhs)`, overloads `lhs % rhs`
+    Rem,
+}
+
+impl OperatorTrait {
+    /// Returns the operator trait with the specified name.
+    pub(crate) fn from_name(name: &Name) -> Option<OperatorTrait> {
+        if *name == name![Add] {
+            Some(OperatorTrait::Add)
+        } else if *name == name![Sub] {
+            Some(OperatorTrait::Sub)
+        } else if *name == name![Mul] {
+            Some(OperatorTrait::Mul)
+        } else if *name == name![Div] {
+            Some(OperatorTrait::Div)
+        } else if *name == name![Rem] {
+            Some(OperatorTrait::Rem)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the operator trait that overloads the specified arithmetic operator.
+    pub(crate) fn from_arith_op(op: ArithOp) -> Option<OperatorTrait> {
+        match op {
+            ArithOp::Add => Some(OperatorTrait::Add),
+            ArithOp::Subtract => Some(OperatorTrait::Sub),
+            ArithOp::Multiply => Some(OperatorTrait::Mul),
+            ArithOp::Divide => Some(OperatorTrait::Div),
+            ArithOp::Remainder => Some(OperatorTrait::Rem),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the method that implements the operator.
+    pub(crate) fn method_name(self) -> Name {
+        match self {
+            OperatorTrait::Add => name![add],
+            OperatorTrait::Sub => name![sub],
+            OperatorTrait::Mul => name![mul],
+            OperatorTrait::Div => name![div],
+            OperatorTrait::Rem => name![rem],
+        }
+    }
+}
+
+/// A field of the builtin `SourceLocation` type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SourceLocationField {
+    /// The path of the source file relative to its source root, as a `Handle` that holds a
+    /// pointer to a NUL-terminated UTF-8 string. The string is part of the assembly, so it is only
+    /// valid until the assembly is reloaded.
+    File,
+    /// The one-based line number
+    Line,
+    /// The one-based column number
+    Column,
+}
+
+impl SourceLocationField {
+    /// Returns the field of a `SourceLocation` with the specified name.
+    pub fn from_name(name: &Name) -> Option<SourceLocationField> {
+        if *name == name![file] {
+            Some(SourceLocationField::File)
+        } else if *name == name![line] {
+            Some(SourceLocationField::Line)
+        } else if *name == name![column] {
+            Some(SourceLocationField::Column)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the index of the field in the memory layout of a `SourceLocation`.
+    pub fn index(self) -> u32 {
+        match self {
+            SourceLocationField::Line => 0,
+            SourceLocationField::Column => 1,
+            SourceLocationField::File => 2,
+        }
+    }
+}
+
+/// A component of a builtin vector type, e.g. `x` of a `float4`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VectorComponent {
+    X,
+    Y,
+    Z,
+    W,
+}
+
+impl VectorComponent {
+    /// Returns the component with the specified name of a vector with the specified number of
+    /// lanes.
+    pub fn from_name(name: &Name, lanes: u32) -> Option<VectorComponent> {
+        let component = if *name == name![x] {
+            VectorComponent::X
+        } else if *name == name![y] {
+            VectorComponent::Y
+        } else if *name == name![z] {
+            VectorComponent::Z
+        } else if *name == name![w] {
+            VectorComponent::W
+        } else {
+            return None;
+        };
+        if component.index() < lanes {
+            Some(component)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the index of the lane that holds the component.
+    pub fn index(self) -> u32 {
+        match self {
+            VectorComponent::X => 0,
+            VectorComponent::Y => 1,
+            VectorComponent::Z => 2,
+            VectorComponent::W => 3,
+        }
+    }
+}
+
 impl fmt::Display for BuiltinType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let type_name = match self {
             BuiltinType::Bool => "bool",
+            BuiltinType::Char => "char",
+            BuiltinType::Atomic => "Atomic",
+            BuiltinType::SourceLocation => "SourceLocation",
+            BuiltinType::Any => "any",
+            BuiltinType::Handle => "Handle",
             BuiltinType::Int(BuiltinInt {
                 signedness,
                 bitness,
@@ -97,6 +378,7 @@ impl fmt::Display for BuiltinType {
                 FloatBitness::X32 => "f32",
                 FloatBitness::X64 => "f64",
             },
+            BuiltinType::FloatVector(lanes) => return write!(f, "float{}", lanes),
         };
         f.write_str(type_name)
     }
@@ -0,0 +1,119 @@
+//! The call graph of a package describes which functions call which functions and construct which
+//! structs.
+
+use crate::{
+    code_model::ModuleDef, ty::lower::CallableDef, Expr, Function, HirDatabase, Module,
+    SourceRootId,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::sync::Arc;
+
+/// The functions of a package and the functions and struct constructors they call.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallGraph {
+    functions: Vec<Function>,
+    callees: FxHashMap<Function, Vec<CallableDef>>,
+}
+
+impl CallGraph {
+    pub(crate) fn call_graph_query(
+        db: &dyn HirDatabase,
+        source_root: SourceRootId,
+    ) -> Arc<CallGraph> {
+        let mut graph = CallGraph::default();
+        let mut file_ids: Vec<_> = db.source_root(source_root).files().collect();
+        file_ids.sort();
+        for file_id in file_ids {
+            let module = Module::from(file_id);
+            let declared_functions = module.declarations(db).into_iter().filter_map(|d| match d {
+                ModuleDef::Function(f) => Some(f),
+                _ => None,
+            });
+            let impl_functions = module.impls(db).into_iter().flat_map(|i| i.functions(db));
+            for function in declared_functions.chain(impl_functions) {
+                let callees = callees(db, function);
+                graph.functions.push(function);
+                graph.callees.insert(function, callees);
+            }
+        }
+        Arc::new(graph)
+    }
+
+    /// Returns all functions of the package, including functions of `impl` blocks.
+    pub fn functions(&self) -> &[Function] {
+        &self.functions
+    }
+
+    /// Returns the functions and struct constructors that are called by `function`, without
+    /// duplicates.
+    pub fn callees(&self, function: Function) -> &[CallableDef] {
+        self.callees
+            .get(&function)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the functions that call the specified function or struct constructor.
+    pub fn callers(&self, callee: CallableDef) -> impl Iterator<Item = Function> + '_ {
+        self.functions
+            .iter()
+            .copied()
+            .filter(move |f| self.callees(*f).contains(&callee))
+    }
+
+    /// Returns the functions that are transitively called by any of the `roots`, including the
+    /// roots themselves.
+    pub fn reachable_from(&self, roots: impl IntoIterator<Item = Function>) -> FxHashSet<Function> {
+        let mut reachable = FxHashSet::default();
+        let mut queue: Vec<Function> = roots.into_iter().collect();
+        while let Some(function) = queue.pop() {
+            if !reachable.insert(function) {
+                continue;
+            }
+            queue.extend(
+                self.callees(function)
+                    .iter()
+                    .filter_map(|callee| match callee {
+                        CallableDef::Function(f) => Some(*f),
+                        CallableDef::Struct(_) => None,
+                    }),
+            );
+        }
+        reachable
+    }
+
+    /// Returns true if the specified function can (indirectly) call itself.
+    pub fn is_recursive(&self, function: Function) -> bool {
+        let callees = self
+            .callees(function)
+            .iter()
+            .filter_map(|callee| match callee {
+                CallableDef::Function(f) => Some(*f),
+                CallableDef::Struct(_) => None,
+            });
+        self.reachable_from(callees).contains(&function)
+    }
+}
+
+/// Returns the functions and struct constructors that are called from the body of `function`. An
+/// overloaded operator calls the function that implements it and a record literal calls the
+/// constructor of its struct.
+fn callees(db: &dyn HirDatabase, function: Function) -> Vec<CallableDef> {
+    let body = function.body(db);
+    let infer = function.infer(db);
+    let mut callees = Vec::new();
+    for (expr, data) in body.exprs() {
+        let callee = match data {
+            Expr::Path(_) => infer[expr].as_callable_def(),
+            Expr::RecordLit { .. } => infer[expr].as_struct().map(CallableDef::Struct),
+            Expr::BinaryOp { .. } => infer.operator_resolution(expr).map(CallableDef::Function),
+            _ => None,
+        };
+        if let Some(callee) = callee {
+            if !callees.contains(&callee) {
+                callees.push(callee);
+            }
+        }
+    }
+    callees
+}
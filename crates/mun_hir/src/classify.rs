@@ -0,0 +1,266 @@
+//! Classifies the identifiers in a file by the kind of definition they refer to, e.g. to highlight
+//! them in an editor.
+
+use crate::{
+    references::last_name_ref_range,
+    resolve::Resolution,
+    type_ref::{TypeRef, TypeRefMap, TypeRefSourceMap},
+    BinaryOp, Expr, FileId, Function, HirDatabase, Module, ModuleDef, Pat, PatId, Resolver,
+};
+use mun_syntax::{
+    ast::{self, NameOwner},
+    AstNode, SyntaxNode, TextRange, TextUnit,
+};
+use std::{collections::HashSet, sync::Arc};
+
+/// The kind of definition an identifier refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IdentClass {
+    /// A local binding, e.g. `a` in `let a = 1`
+    Local,
+    /// A parameter of a function
+    Parameter,
+    Function,
+    Struct,
+    /// A field of a struct, e.g. in `foo.a`, `Foo { a: 1 }` or the declaration of the field
+    Field,
+    /// A builtin type, e.g. `i32`
+    BuiltinType,
+}
+
+/// An identifier in a file and the kind of definition it refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClassifiedIdent {
+    pub range: TextRange,
+    pub class: IdentClass,
+    /// Whether the identifier refers to a local binding or parameter that is assigned to after its
+    /// declaration, e.g. `a` in `let a = 1; a += 1;`
+    pub mutable: bool,
+}
+
+/// Returns the classification of all identifiers in the specified file that refer to a definition,
+/// ordered by position. This includes the names of declarations.
+pub(crate) fn classify_identifiers_query(
+    db: &dyn HirDatabase,
+    file_id: FileId,
+) -> Arc<Vec<ClassifiedIdent>> {
+    let root = db.parse(file_id).syntax_node();
+    let mut classifier = Classifier {
+        db,
+        root: root.clone(),
+        idents: Vec::new(),
+    };
+
+    // The names of declarations can be classified from the syntax alone
+    for node in root.descendants() {
+        if let Some(def) = ast::FunctionDef::cast(node.clone()) {
+            classifier.push_name(def.name(), IdentClass::Function);
+        } else if let Some(def) = ast::StructDef::cast(node.clone()) {
+            classifier.push_name(def.name(), IdentClass::Struct);
+        } else if let Some(def) = ast::RecordFieldDef::cast(node) {
+            classifier.push_name(def.name(), IdentClass::Field);
+        }
+    }
+
+    let module = Module::from(file_id);
+    for def in module.declarations(db) {
+        match def {
+            ModuleDef::Function(f) => classifier.classify_function(f),
+            ModuleDef::Struct(s) => {
+                let data = s.data(db.upcast());
+                classifier.classify_type_refs(
+                    &s.resolver(db),
+                    data.type_ref_map(),
+                    data.type_ref_source_map(),
+                );
+            }
+            ModuleDef::BuiltinType(_) => (),
+        }
+    }
+    for i in module.impls(db) {
+        let data = i.data(db.upcast());
+        classifier.classify_type_refs(
+            &i.resolver(db),
+            data.type_ref_map(),
+            data.type_ref_source_map(),
+        );
+        for f in i.functions(db) {
+            classifier.classify_function(f);
+        }
+    }
+
+    let mut idents = classifier.idents;
+    // A field shorthand like `Foo { a }` is both a local and a field, only the local is kept
+    idents.sort_by_key(|ident| (ident.range.start(), ident.class));
+    idents.dedup_by_key(|ident| ident.range);
+    Arc::new(idents)
+}
+
+struct Classifier<'a> {
+    db: &'a dyn HirDatabase,
+    root: SyntaxNode,
+    idents: Vec<ClassifiedIdent>,
+}
+
+impl<'a> Classifier<'a> {
+    fn classify_function(&mut self, function: Function) {
+        let db = self.db;
+        let data = function.data(db);
+        let resolver = function.resolver(db);
+        self.classify_type_refs(&resolver, data.type_ref_map(), data.type_ref_source_map());
+
+        let (body, source_map) = db.body_with_source_map(function.into());
+        self.classify_type_refs(&resolver, body.type_refs(), source_map.type_refs());
+
+        // The bindings that are the target of an assignment
+        let assigned: HashSet<PatId> = body
+            .exprs()
+            .filter_map(|(expr, data)| match data {
+                Expr::BinaryOp {
+                    lhs,
+                    op: Some(BinaryOp::Assignment { .. }),
+                    ..
+                } => match &body[*lhs] {
+                    Expr::Path(path) => {
+                        match crate::expr::resolver_for_expr(body.clone(), db, expr)
+                            .resolve_path_without_assoc_items(db, path)
+                            .take_values()
+                        {
+                            Some(Resolution::LocalBinding(pat)) => Some(pat),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+
+        for (pat, data) in body.pats() {
+            if let Pat::Bind { .. } = data {
+                let class = if body.params().iter().any(|(param, _)| *param == pat) {
+                    IdentClass::Parameter
+                } else {
+                    IdentClass::Local
+                };
+                if let Some(src) = source_map.pat_syntax(pat) {
+                    let node = src.value.syntax_node_ptr().to_node(&self.root);
+                    if let Some(name) = node.descendants().find_map(ast::Name::cast) {
+                        self.push_binding(
+                            name.syntax().text_range(),
+                            class,
+                            assigned.contains(&pat),
+                        );
+                    }
+                }
+            }
+        }
+
+        for (expr, data) in body.exprs() {
+            let node = match source_map.expr_syntax(expr) {
+                Some(src) => src
+                    .value
+                    .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr())
+                    .to_node(&self.root),
+                None => continue,
+            };
+            match data {
+                Expr::Path(path) => {
+                    let resolver = crate::expr::resolver_for_expr(body.clone(), db, expr);
+                    let range = last_name_ref_range(&node);
+                    let class = match resolver
+                        .resolve_path_without_assoc_items(db, path)
+                        .take_values()
+                    {
+                        Some(Resolution::LocalBinding(pat)) => {
+                            let class = if body.params().iter().any(|(param, _)| *param == pat) {
+                                IdentClass::Parameter
+                            } else {
+                                IdentClass::Local
+                            };
+                            self.push_binding(range, class, assigned.contains(&pat));
+                            continue;
+                        }
+                        Some(Resolution::Def(ModuleDef::Function(_))) => IdentClass::Function,
+                        Some(Resolution::Def(ModuleDef::Struct(_))) => IdentClass::Struct,
+                        Some(Resolution::Def(ModuleDef::BuiltinType(_))) => IdentClass::BuiltinType,
+                        None => continue,
+                    };
+                    self.push(range, class);
+                }
+                Expr::Field { .. } => {
+                    let range = ast::FieldExpr::cast(node).and_then(|it| {
+                        match it.field_access()? {
+                            ast::FieldKind::Name(name_ref) => Some(name_ref.syntax().text_range()),
+                            ast::FieldKind::Index(token) => {
+                                // The index token includes the leading `.`
+                                let range = token.text_range();
+                                Some(TextRange::from_to(
+                                    range.start() + TextUnit::from(1),
+                                    range.end(),
+                                ))
+                            }
+                        }
+                    });
+                    if let Some(range) = range {
+                        self.push(range, IdentClass::Field);
+                    }
+                }
+                Expr::RecordLit { fields, .. } => {
+                    for idx in 0..fields.len() {
+                        let record_field = source_map.field_syntax(expr, idx).to_node(&self.root);
+                        if let Some(name_ref) = record_field.name_ref() {
+                            self.push(name_ref.syntax().text_range(), IdentClass::Field);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Classifies the names of types in type references, e.g. `Foo` in `let a: Foo`.
+    fn classify_type_refs(
+        &mut self,
+        resolver: &Resolver,
+        type_ref_map: &TypeRefMap,
+        source_map: &TypeRefSourceMap,
+    ) {
+        for (id, type_ref) in type_ref_map.iter() {
+            let path = match type_ref {
+                TypeRef::Path(path) | TypeRef::Generic { path, .. } => path,
+                _ => continue,
+            };
+            let class = match resolver
+                .resolve_path_without_assoc_items(self.db, path)
+                .take_types()
+            {
+                Some(Resolution::Def(ModuleDef::Struct(_))) => IdentClass::Struct,
+                Some(Resolution::Def(ModuleDef::BuiltinType(_))) => IdentClass::BuiltinType,
+                _ => continue,
+            };
+            if let Some(ptr) = source_map.type_ref_syntax(id) {
+                let node = ptr.syntax_node_ptr().to_node(&self.root);
+                self.push(last_name_ref_range(&node), class);
+            }
+        }
+    }
+
+    fn push_name(&mut self, name: Option<ast::Name>, class: IdentClass) {
+        if let Some(name) = name {
+            self.push(name.syntax().text_range(), class);
+        }
+    }
+
+    fn push(&mut self, range: TextRange, class: IdentClass) {
+        self.push_binding(range, class, false);
+    }
+
+    fn push_binding(&mut self, range: TextRange, class: IdentClass, mutable: bool) {
+        self.idents.push(ClassifiedIdent {
+            range,
+            class,
+            mutable,
+        });
+    }
+}
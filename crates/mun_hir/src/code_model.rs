@@ -1,24 +1,41 @@
 pub(crate) mod src;
 
 use self::src::HasSource;
-use crate::adt::{StructData, StructFieldId};
-use crate::builtin_type::BuiltinType;
+use crate::adt::{derive_names, DeriveTrait, StructData, StructFieldId, StructMemoryKind};
+use crate::attrs::{Attr, Attrs, InlineKind};
+use crate::builtin_type::{BuiltinType, OperatorTrait};
 use crate::code_model::diagnostics::ModuleDefinitionDiagnostic;
-use crate::diagnostics::DiagnosticSink;
+use crate::const_eval::ConstEvalResult;
+use crate::diagnostics::{
+    AtomicOutsideGcStruct, DiagnosticSink, DuplicateExportName, InvalidDeriveField,
+    InvalidExportAttribute, InvalidInlineAttribute, InvalidOperatorImplTarget,
+    InvalidOperatorSignature, InvalidTestFunction, MissingOperatorMethod, PrivateExport,
+    RecursiveValueStruct, UnknownDerive, UnknownLint, UnknownOperatorTrait, UnusedFunction,
+};
+use crate::display::HirDisplay;
+use crate::docs::Documentation;
 use crate::expr::validator::ExprValidator;
-use crate::expr::{Body, BodySourceMap};
+use crate::expr::{ArithOp, Body, BodySourceMap, ExprId, Pat, PatId};
 use crate::ids::AstItemDef;
 use crate::ids::LocationCtx;
+use crate::in_file::InFile;
+use crate::line_index::LineCol;
+use crate::lint::{self, lint_level, LintLevel};
+use crate::name::name;
 use crate::name_resolution::Namespace;
 use crate::raw::{DefKind, RawFileItem};
 use crate::resolve::{Resolution, Resolver};
 use crate::ty::{lower::LowerBatchResult, InferenceResult};
 use crate::type_ref::{TypeRefBuilder, TypeRefId, TypeRefMap, TypeRefSourceMap};
 use crate::{
-    ids::{FunctionId, StructId},
-    AsName, DefDatabase, FileId, HirDatabase, Name, Ty,
+    ids::{FunctionId, ImplId, StructId},
+    AsName, DefDatabase, FileId, HirDatabase, Name, Path, Ty, TypeCtor,
+};
+use mun_syntax::ast::{
+    self, AttrsOwner, ExternOwner, FunctionDefOwner, NameOwner, TypeAscriptionOwner,
+    VisibilityOwner,
 };
-use mun_syntax::ast::{ExternOwner, NameOwner, TypeAscriptionOwner, VisibilityOwner};
+use mun_syntax::{AstNode, AstPtr, SyntaxNodePtr};
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
 
@@ -43,6 +60,17 @@ impl Module {
         db.module_data(self.file_id).definitions.clone()
     }
 
+    /// Returns all the impl blocks declared in this module.
+    pub fn impls(self, db: &dyn HirDatabase) -> Vec<Impl> {
+        db.module_data(self.file_id).impls.clone()
+    }
+
+    /// Returns a textual representation of the lowered bodies of all functions in this module,
+    /// annotated with the inferred types of all expressions.
+    pub fn pretty_print(self, db: &dyn HirDatabase) -> String {
+        crate::pretty::print_module(db, self)
+    }
+
     fn resolver(self, _db: &dyn DefDatabase) -> Resolver {
         Resolver::default().push_module_scope(self.file_id)
     }
@@ -59,12 +87,81 @@ impl Module {
                 _ => (),
             }
         }
+        for i in self.impls(db) {
+            i.diagnostics(db, sink);
+        }
+
+        // All public functions are added to the function table of the assembly under their
+        // export name, so these names must be unique.
+        let mut export_names = FxHashMap::default();
+        for decl in self.declarations(db) {
+            if let ModuleDef::Function(f) = decl {
+                if f.is_extern(db) || f.visibility(db).is_private() {
+                    continue;
+                }
+                let name = f.export_name(db);
+                if export_names.insert(name.clone(), f).is_some() {
+                    let src = f.source(db.upcast());
+                    sink.push(DuplicateExportName {
+                        file: src.file_id,
+                        name,
+                        definition: SyntaxNodePtr::new(src.value.syntax()),
+                    });
+                }
+            }
+        }
+
+        self.validate_unused_functions(db, sink);
+    }
+
+    /// Reports private functions that are never called. Public functions can be called by the
+    /// host, but private functions can only be called from within this module. A function that
+    /// only calls itself is still unused.
+    fn validate_unused_functions(self, db: &dyn HirDatabase, sink: &mut DiagnosticSink) {
+        let declared_functions: Vec<Function> = self
+            .declarations(db)
+            .into_iter()
+            .filter_map(|decl| match decl {
+                ModuleDef::Function(f) => Some(f),
+                _ => None,
+            })
+            .collect();
+
+        let call_graph = db.call_graph(db.file_source_root(self.file_id));
+        for f in declared_functions {
+            if f.is_extern(db)
+                || f.is_test(db)
+                || f.is_bench(db)
+                || !f.visibility(db).is_private()
+                || call_graph.callers(f.into()).any(|caller| caller != f)
+                || f.name(db).to_string().starts_with('_')
+            {
+                continue;
+            }
+            let severity = match lint_level(db, f, &lint::DEAD_CODE).severity() {
+                Some(severity) => severity,
+                None => continue,
+            };
+            let src = f.source(db.upcast());
+            let fn_name = src
+                .value
+                .name()
+                .map(|name| SyntaxNodePtr::new(name.syntax()))
+                .unwrap_or_else(|| SyntaxNodePtr::new(src.value.syntax()));
+            sink.push(UnusedFunction {
+                file: src.file_id,
+                fn_name,
+                name: f.name(db),
+                severity,
+            });
+        }
     }
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Default)]
 pub struct ModuleData {
     definitions: Vec<ModuleDef>,
+    impls: Vec<Impl>,
     diagnostics: Vec<ModuleDefinitionDiagnostic>,
 }
 
@@ -106,6 +203,16 @@ impl ModuleData {
                         }
                     }
                 }
+                RawFileItem::Impl(ast_id) => {
+                    let i = Impl {
+                        id: ImplId::from_ast_id(loc_ctx, *ast_id),
+                    };
+                    // The functions of an impl block are generated like any other function
+                    for (_, f) in db.impl_data(i).functions.iter() {
+                        data.definitions.push(ModuleDef::Function(*f));
+                    }
+                    data.impls.push(i);
+                }
             };
         }
         Arc::new(data)
@@ -232,6 +339,7 @@ pub struct FnData {
     type_ref_map: TypeRefMap,
     type_ref_source_map: TypeRefSourceMap,
     is_extern: bool,
+    is_associated: bool,
 }
 
 impl FnData {
@@ -244,6 +352,22 @@ impl FnData {
             .map(|n| n.as_name())
             .unwrap_or_else(Name::missing);
 
+        // Functions declared in an impl block are qualified by the type they are implemented for,
+        // e.g. `Vec2::add`, so they do not collide with other functions in the module.
+        let impl_def = src
+            .value
+            .syntax()
+            .parent()
+            .and_then(|it| it.parent())
+            .and_then(ast::ImplDef::cast);
+        let is_associated = impl_def.is_some();
+        let name = match impl_def.and_then(|it| it.target_type()) {
+            Some(target_type) => {
+                Name::new_from_text(&format!("{}::{}", target_type.syntax(), name))
+            }
+            None => name,
+        };
+
         let visibility = src
             .value
             .visibility()
@@ -276,6 +400,7 @@ impl FnData {
             type_ref_map,
             type_ref_source_map,
             is_extern,
+            is_associated,
         })
     }
 
@@ -335,10 +460,110 @@ impl Function {
         db.infer(self.into())
     }
 
+    /// Returns the values of the expressions in the body of this function that are known at
+    /// compile time.
+    pub fn const_eval(self, db: &dyn HirDatabase) -> Arc<ConstEvalResult> {
+        db.const_eval(self.into())
+    }
+
+    /// Returns a textual representation of the lowered body of this function, annotated with the
+    /// inferred types of all expressions.
+    pub fn pretty_print(self, db: &dyn HirDatabase) -> String {
+        crate::pretty::print_function(db, self)
+    }
+
     pub fn is_extern(self, db: &dyn HirDatabase) -> bool {
         db.fn_data(self).is_extern
     }
 
+    /// Returns the attributes of this function, e.g. `#[inline]`.
+    pub fn attrs(self, db: &dyn DefDatabase) -> Arc<Attrs> {
+        db.attrs(self.into())
+    }
+
+    /// Returns the documentation of this function.
+    pub fn docs(self, db: &dyn DefDatabase) -> Option<Documentation> {
+        db.documentation(self.into())
+    }
+
+    /// Returns the kind of inlining requested by an `#[inline]` attribute on this function.
+    pub fn inline(self, db: &dyn HirDatabase) -> Option<InlineKind> {
+        self.attrs(db.upcast()).inline()
+    }
+
+    /// Returns the name under which this function appears in the function table of the assembly.
+    /// This is either the name specified by an `#[export(name = "...")]` attribute, or the name of
+    /// the function.
+    pub fn export_name(self, db: &dyn HirDatabase) -> String {
+        let attrs = self.attrs(db.upcast());
+        let export_name = attrs
+            .by_name(&name![export])
+            .find_map(|attr| attr.value(&name![name]));
+        match export_name {
+            Some(name) => name.to_string(),
+            None => self.name(db).to_string(),
+        }
+    }
+
+    /// Returns true if this function is marked with a `#[test]` attribute. Test functions are run
+    /// by `mun test` and are not part of the function table of the assembly.
+    pub fn is_test(self, db: &dyn HirDatabase) -> bool {
+        self.attrs(db.upcast()).has(&name![test])
+    }
+
+    /// Returns true if this function is marked with a `#[bench]` attribute. Benchmark functions
+    /// are run by `mun bench` and are not part of the function table of the assembly.
+    pub fn is_bench(self, db: &dyn HirDatabase) -> bool {
+        self.attrs(db.upcast()).has(&name![bench])
+    }
+
+    /// Returns true if this function is declared inside an impl block. These functions are not
+    /// part of the module scope.
+    pub fn is_associated(self, db: &dyn HirDatabase) -> bool {
+        db.fn_data(self).is_associated
+    }
+
+    /// Returns the zero-based line and column at which the specified expression of the body of
+    /// this function starts.
+    pub fn expr_line_col(self, db: &dyn HirDatabase, expr: ExprId) -> Option<LineCol> {
+        let source = self.body_source_map(db).expr_syntax(expr)?;
+        let offset = source
+            .value
+            .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr())
+            .range()
+            .start();
+        Some(db.line_index(source.file_id).line_col(offset))
+    }
+
+    /// Returns the zero-based line and column at which the specified pattern of the body of this
+    /// function starts, e.g. the name of a local binding.
+    pub fn pat_line_col(self, db: &dyn HirDatabase, pat: PatId) -> Option<LineCol> {
+        let source = self.body_source_map(db).pat_syntax(pat)?;
+        let offset = source.value.syntax_node_ptr().range().start();
+        Some(db.line_index(source.file_id).line_col(offset))
+    }
+
+    /// Returns the zero-based line and column of the name of this function.
+    pub fn line_col(self, db: &dyn HirDatabase) -> LineCol {
+        let src = self.source(db.upcast());
+        let offset = match src.value.name() {
+            Some(name) => name.syntax().text_range().start(),
+            None => src.value.syntax().text_range().start(),
+        };
+        db.line_index(src.file_id).line_col(offset)
+    }
+
+    /// Returns the source text of the specified expression of the body of this function.
+    pub fn expr_source_text(self, db: &dyn HirDatabase, expr: ExprId) -> Option<String> {
+        let source = self.body_source_map(db).expr_syntax(expr)?;
+        let root = db.parse(source.file_id).tree();
+        let node = source
+            .value
+            .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr())
+            .to_node(root.syntax());
+        Some(node.text().to_string())
+    }
+
     pub(crate) fn body_source_map(self, db: &dyn HirDatabase) -> Arc<BodySourceMap> {
         db.body_with_source_map(self.into()).1
     }
@@ -355,6 +580,60 @@ impl Function {
         infer.add_diagnostics(db, self, sink);
         let validator = ExprValidator::new(self, db);
         validator.validate_body(sink);
+
+        let src = self.source(db.upcast());
+        for ast_attr in src.value.attrs() {
+            let attr = match Attr::from_ast(&ast_attr) {
+                Some(attr) => attr,
+                None => continue,
+            };
+            if attr.name == name![inline] && InlineKind::from_attr(&attr).is_none() {
+                sink.push(InvalidInlineAttribute {
+                    file: src.file_id,
+                    attr: AstPtr::new(&ast_attr),
+                });
+            } else if LintLevel::from_attr(&attr).is_some() {
+                for name in lint::unknown_lints(&attr) {
+                    sink.push(UnknownLint {
+                        file: src.file_id,
+                        attr: AstPtr::new(&ast_attr),
+                        name: name.clone(),
+                    });
+                }
+            } else if attr.name == name![test] || attr.name == name![bench] {
+                let fn_data = db.fn_data(self);
+                let returns_value = self
+                    .ty(db)
+                    .callable_sig(db)
+                    .map_or(false, |sig| !sig.ret().is_empty());
+                if fn_data.is_extern
+                    || fn_data.is_associated
+                    || !fn_data.params().is_empty()
+                    || returns_value
+                {
+                    sink.push(InvalidTestFunction {
+                        file: src.file_id,
+                        attr: AstPtr::new(&ast_attr),
+                        attr_name: attr.name.clone(),
+                    });
+                }
+            } else if attr.name == name![export] {
+                if attr
+                    .value(&name![name])
+                    .map_or(true, |name| name.is_empty())
+                {
+                    sink.push(InvalidExportAttribute {
+                        file: src.file_id,
+                        attr: AstPtr::new(&ast_attr),
+                    });
+                } else if self.visibility(db).is_private() {
+                    sink.push(PrivateExport {
+                        file: src.file_id,
+                        attr: AstPtr::new(&ast_attr),
+                    });
+                }
+            }
+        }
     }
 }
 
@@ -381,9 +660,51 @@ impl StructField {
         self.parent.data(db.upcast()).fields[self.id].name.clone()
     }
 
+    /// Returns the previous name of this field, if it was renamed with a `#[rename_from]`
+    /// attribute.
+    pub fn renamed_from(self, db: &dyn HirDatabase) -> Option<Name> {
+        self.parent.data(db.upcast()).fields[self.id]
+            .renamed_from
+            .clone()
+    }
+
     pub fn id(self) -> StructFieldId {
         self.id
     }
+
+    /// Returns the documentation of this field.
+    pub fn docs(self, db: &dyn DefDatabase) -> Option<Documentation> {
+        db.documentation(self.into())
+    }
+}
+
+/// A local binding of a function, e.g. a parameter or a `let` binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Local {
+    pub(crate) parent: Function,
+    pub(crate) pat_id: PatId,
+}
+
+impl Local {
+    pub fn new(parent: Function, pat_id: PatId) -> Local {
+        Local { parent, pat_id }
+    }
+
+    /// Returns the function in which the binding is declared.
+    pub fn parent(self) -> Function {
+        self.parent
+    }
+
+    pub fn pat_id(self) -> PatId {
+        self.pat_id
+    }
+
+    pub fn name(self, db: &dyn HirDatabase) -> Option<Name> {
+        match &self.parent.body(db)[self.pat_id] {
+            Pat::Bind { name } => Some(name.clone()),
+            _ => None,
+        }
+    }
 }
 
 impl Struct {
@@ -401,6 +722,16 @@ impl Struct {
         self.data(db).name.clone()
     }
 
+    /// Returns the documentation of this struct.
+    pub fn docs(self, db: &dyn DefDatabase) -> Option<Documentation> {
+        db.documentation(self.into())
+    }
+
+    /// Returns the attributes of this struct, e.g. `#[derive(Eq)]`.
+    pub fn attrs(self, db: &dyn DefDatabase) -> Arc<Attrs> {
+        db.attrs(self.into())
+    }
+
     pub fn fields(self, db: &dyn HirDatabase) -> Vec<StructField> {
         self.data(db.upcast())
             .fields
@@ -425,6 +756,23 @@ impl Struct {
         db.lower_struct(self)
     }
 
+    /// Returns true if the struct derives the specified comparison trait.
+    pub fn derives(self, db: &dyn HirDatabase, derive: DeriveTrait) -> bool {
+        self.data(db.upcast()).derives.contains(&derive)
+    }
+
+    /// Returns the function that overloads the specified arithmetic operator for this struct, if
+    /// any.
+    pub fn operator_impl(self, db: &dyn HirDatabase, op: ArithOp) -> Option<Function> {
+        let operator = OperatorTrait::from_arith_op(op)?;
+        let ty = self.ty(db);
+        self.module(db.upcast())
+            .impls(db)
+            .into_iter()
+            .filter(|i| i.target_trait(db) == Some(operator) && i.target_ty(db) == ty)
+            .find_map(|i| i.function(db, &operator.method_name()))
+    }
+
     pub(crate) fn resolver(self, db: &dyn HirDatabase) -> Resolver {
         // take the outer scope...
         self.module(db.upcast()).resolver(db.upcast())
@@ -433,12 +781,294 @@ impl Struct {
     pub fn diagnostics(self, db: &dyn HirDatabase, sink: &mut DiagnosticSink) {
         let data = self.data(db.upcast());
         let lower = self.lower(db);
-        lower.add_diagnostics(
-            db,
-            self.module(db.upcast()).file_id,
-            data.type_ref_source_map(),
-            sink,
-        );
+        let file_id = self.module(db.upcast()).file_id;
+        lower.add_diagnostics(db, file_id, data.type_ref_source_map(), sink);
+
+        // Value structs are copied when they are passed around, so they cannot contain atomics
+        if data.memory_kind == StructMemoryKind::Value {
+            for (_, field) in data.fields.iter() {
+                if lower[field.type_ref].as_atomic().is_some() {
+                    if let Some(ptr) = data.type_ref_source_map().type_ref_syntax(field.type_ref) {
+                        sink.push(AtomicOutsideGcStruct {
+                            ptr: InFile::new(file_id, ptr.syntax_node_ptr()),
+                        });
+                    }
+                }
+            }
+        }
+
+        // A value struct is stored inline, so it can only contain itself through a `gc` struct
+        if data.memory_kind == StructMemoryKind::Value {
+            for (_, field) in data.fields.iter() {
+                let mut visited = Vec::new();
+                if contains_value_struct(db, &lower[field.type_ref], self, &mut visited) {
+                    if let Some(ptr) = data.type_ref_source_map().type_ref_syntax(field.type_ref) {
+                        sink.push(RecursiveValueStruct {
+                            file: file_id,
+                            type_ref: ptr,
+                            name: data.name.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let src = self.source(db.upcast());
+        for (attr, name) in derive_names(&src.value) {
+            if DeriveTrait::from_name(&name).is_none() {
+                sink.push(UnknownDerive {
+                    file: file_id,
+                    attr: AstPtr::new(&attr),
+                    name: name.to_string(),
+                });
+            }
+        }
+
+        // A derived comparison compares all fields, so all fields must support the comparison
+        for derive in data.derives.iter() {
+            for (_, field) in data.fields.iter() {
+                let ty = &lower[field.type_ref];
+                if !is_comparable(db, ty, *derive) {
+                    if let Some(ptr) = data.type_ref_source_map().type_ref_syntax(field.type_ref) {
+                        sink.push(InvalidDeriveField {
+                            file: file_id,
+                            type_ref: ptr,
+                            derive: *derive,
+                            ty: ty.display(db).to_string(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns true if the specified type stores a value of struct `target` inline, i.e. through a
+/// chain of value struct fields. Fields of `gc` structs are references, which end such a chain.
+fn contains_value_struct(
+    db: &dyn HirDatabase,
+    ty: &Ty,
+    target: Struct,
+    visited: &mut Vec<Struct>,
+) -> bool {
+    let s = match ty.as_struct() {
+        Some(s) if s.data(db.upcast()).memory_kind == StructMemoryKind::Value => s,
+        _ => return false,
+    };
+    if s == target {
+        return true;
+    }
+    if visited.contains(&s) {
+        return false;
+    }
+    visited.push(s);
+    s.fields(db)
+        .into_iter()
+        .any(|field| contains_value_struct(db, &field.ty(db), target, visited))
+}
+
+/// Returns true if values of the specified type can be compared by a derived comparison.
+fn is_comparable(db: &dyn HirDatabase, ty: &Ty, derive: DeriveTrait) -> bool {
+    match ty {
+        Ty::Unknown => true,
+        _ => match ty.as_simple() {
+            Some(TypeCtor::Int(_))
+            | Some(TypeCtor::Float(_))
+            | Some(TypeCtor::Bool)
+            | Some(TypeCtor::Char) => true,
+            Some(TypeCtor::Struct(s)) => s.derives(db, derive),
+            _ => false,
+        },
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Impl {
+    pub(crate) id: ImplId,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ImplData {
+    target_trait: Option<Name>,
+    target_type: TypeRefId,
+    functions: Vec<(Name, Function)>,
+    type_ref_map: TypeRefMap,
+    type_ref_source_map: TypeRefSourceMap,
+}
+
+impl ImplData {
+    pub(crate) fn impl_data_query(db: &dyn DefDatabase, i: Impl) -> Arc<ImplData> {
+        let src = i.id.source(db);
+        let mut type_ref_builder = TypeRefBuilder::default();
+
+        // The trait is not a type, so it is resolved by name instead of being lowered
+        let target_trait = match src.value.target_trait().map(|it| it.kind()) {
+            Some(ast::TypeRefKind::PathType(path_type)) => path_type
+                .path()
+                .and_then(Path::from_ast)
+                .and_then(|path| path.as_ident().cloned()),
+            _ => None,
+        };
+        let target_type = type_ref_builder.alloc_from_node_opt(src.value.target_type().as_ref());
+
+        let loc_ctx = LocationCtx::new(db, src.file_id);
+        let functions = src
+            .value
+            .item_list()
+            .map(|item_list| {
+                item_list
+                    .functions()
+                    .filter_map(|f| {
+                        let name = f.name()?.as_name();
+                        Some((
+                            name,
+                            Function {
+                                id: FunctionId::from_ast(loc_ctx, &f),
+                            },
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let (type_ref_map, type_ref_source_map) = type_ref_builder.finish();
+        Arc::new(ImplData {
+            target_trait,
+            target_type,
+            functions,
+            type_ref_map,
+            type_ref_source_map,
+        })
+    }
+
+    pub fn type_ref_source_map(&self) -> &TypeRefSourceMap {
+        &self.type_ref_source_map
+    }
+
+    pub fn type_ref_map(&self) -> &TypeRefMap {
+        &self.type_ref_map
+    }
+}
+
+impl Impl {
+    pub fn module(self, db: &dyn DefDatabase) -> Module {
+        Module {
+            file_id: self.id.file_id(db),
+        }
+    }
+
+    pub fn data(self, db: &dyn DefDatabase) -> Arc<ImplData> {
+        db.impl_data(self)
+    }
+
+    /// Returns the documentation of this impl block.
+    pub fn docs(self, db: &dyn DefDatabase) -> Option<Documentation> {
+        db.documentation(self.into())
+    }
+
+    /// Returns the attributes of this impl block.
+    pub fn attrs(self, db: &dyn DefDatabase) -> Arc<Attrs> {
+        db.attrs(self.into())
+    }
+
+    /// Returns the operator trait that this impl block implements, if it is a known trait.
+    pub fn target_trait(self, db: &dyn HirDatabase) -> Option<OperatorTrait> {
+        self.data(db.upcast())
+            .target_trait
+            .as_ref()
+            .and_then(OperatorTrait::from_name)
+    }
+
+    /// Returns the type for which the trait is implemented.
+    pub fn target_ty(self, db: &dyn HirDatabase) -> Ty {
+        let data = self.data(db.upcast());
+        self.lower(db)[data.target_type].clone()
+    }
+
+    /// Returns all functions declared in this impl block.
+    pub fn functions(self, db: &dyn HirDatabase) -> Vec<Function> {
+        self.data(db.upcast())
+            .functions
+            .iter()
+            .map(|(_, f)| *f)
+            .collect()
+    }
+
+    /// Returns the function with the specified (unqualified) name.
+    pub fn function(self, db: &dyn HirDatabase, name: &Name) -> Option<Function> {
+        self.data(db.upcast())
+            .functions
+            .iter()
+            .find(|(function_name, _)| function_name == name)
+            .map(|(_, f)| *f)
+    }
+
+    pub fn lower(self, db: &dyn HirDatabase) -> Arc<LowerBatchResult> {
+        db.lower_impl(self)
+    }
+
+    pub(crate) fn resolver(self, db: &dyn HirDatabase) -> Resolver {
+        // take the outer scope...
+        self.module(db.upcast()).resolver(db.upcast())
+    }
+
+    pub fn diagnostics(self, db: &dyn HirDatabase, sink: &mut DiagnosticSink) {
+        let data = self.data(db.upcast());
+        let lower = self.lower(db);
+        let src = self.id.source(db.upcast());
+        lower.add_diagnostics(db, src.file_id, data.type_ref_source_map(), sink);
+
+        let operator = match (data.target_trait.as_ref(), src.value.target_trait()) {
+            (Some(name), Some(trait_ref)) => match OperatorTrait::from_name(name) {
+                Some(operator) => operator,
+                None => {
+                    sink.push(UnknownOperatorTrait {
+                        file: src.file_id,
+                        trait_ref: AstPtr::new(&trait_ref),
+                        name: name.to_string(),
+                    });
+                    return;
+                }
+            },
+            _ => return,
+        };
+
+        let target_ty = lower[data.target_type].clone();
+        if target_ty.as_struct().is_none() {
+            if target_ty != Ty::Unknown {
+                if let Some(ptr) = data.type_ref_source_map().type_ref_syntax(data.target_type) {
+                    sink.push(InvalidOperatorImplTarget {
+                        file: src.file_id,
+                        type_ref: ptr,
+                    });
+                }
+            }
+            return;
+        }
+
+        let method_name = operator.method_name();
+        match self.function(db, &method_name) {
+            Some(f) => {
+                // The method receives both operands, the first of which is the target type
+                let sig = f.ty(db).callable_sig(db);
+                let valid = sig.map_or(false, |sig| {
+                    sig.params().len() == 2 && sig.params()[0] == target_ty
+                });
+                if !valid {
+                    sink.push(InvalidOperatorSignature {
+                        file: src.file_id,
+                        function: AstPtr::new(&f.source(db.upcast()).value),
+                        method: method_name.to_string(),
+                        ty: target_ty.display(db).to_string(),
+                    });
+                }
+            }
+            None => sink.push(MissingOperatorMethod {
+                file: src.file_id,
+                impl_def: AstPtr::new(&src.value),
+                method: method_name.to_string(),
+            }),
+        }
     }
 }
 
@@ -1,4 +1,4 @@
-use crate::code_model::{Function, Struct, StructField};
+use crate::code_model::{Function, Impl, Struct, StructField};
 use crate::ids::AstItemDef;
 use crate::in_file::InFile;
 use crate::DefDatabase;
@@ -23,6 +23,13 @@ impl HasSource for Struct {
     }
 }
 
+impl HasSource for Impl {
+    type Ast = ast::ImplDef;
+    fn source(self, db: &dyn DefDatabase) -> InFile<ast::ImplDef> {
+        self.id.source(db)
+    }
+}
+
 impl HasSource for StructField {
     type Ast = ast::RecordFieldDef;
 
@@ -0,0 +1,428 @@
+//! Computes the completions at a position in a file, e.g. for code completion in an editor.
+
+use crate::{
+    code_model::src::HasSource,
+    expr::{scope::ScopeId, BodySourceMap},
+    Body, Expr, ExprId, ExprScopes, FileId, Function, HirDatabase, HirDisplay, InferenceResult,
+    Module, ModuleDef, Name, Statement, Ty, TypeCtor,
+};
+use mun_syntax::{
+    ast::{self, TypeAscriptionOwner},
+    AstNode, SyntaxKind, SyntaxNode, SyntaxNodePtr, SyntaxToken, TextUnit, T,
+};
+use std::{collections::HashSet, sync::Arc};
+
+/// The kind of definition that a completion inserts, in the order in which they are ranked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CompletionKind {
+    /// A local binding, e.g. `a` in `let a = 1`
+    Local,
+    /// A parameter of a function
+    Parameter,
+    /// A field of a struct, after `foo.`
+    Field,
+    Function,
+    Struct,
+    /// A builtin type, e.g. `i32`
+    BuiltinType,
+    Keyword,
+}
+
+/// A completion at a position in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletionItem {
+    /// The text that is inserted
+    pub label: String,
+    pub kind: CompletionKind,
+    /// The type of the completed definition, e.g. `i32` or `function foo(i32) -> bool`
+    pub detail: Option<String>,
+    /// Whether the completion has the type that is expected at the position, e.g. the type of the
+    /// parameter of a call
+    pub type_matches: bool,
+}
+
+const EXPR_KEYWORDS: &[&str] = &[
+    "break", "continue", "else", "false", "if", "let", "loop", "return", "true", "while",
+];
+const ITEM_KEYWORDS: &[&str] = &["fn", "impl", "pub", "struct"];
+
+/// Returns the completions at the specified offset in a file, ordered by relevance.
+///
+/// After `foo.` the fields of the type of `foo` are completed. In a type, e.g. after `let a: `,
+/// structs and builtin types are completed. Everywhere else in a function the local bindings that
+/// are visible at the offset, the items of the module and keywords are completed. Completions
+/// whose type is the type that is expected at the offset are ranked first.
+pub fn completions(db: &dyn HirDatabase, file_id: FileId, offset: TextUnit) -> Vec<CompletionItem> {
+    let root = db.parse(file_id).syntax_node();
+    let token = match root.token_at_offset(offset).left_biased() {
+        Some(token) => token,
+        None => return Vec::new(),
+    };
+    if (token.kind() == SyntaxKind::COMMENT || token.kind() == SyntaxKind::STRING)
+        && token.text_range().start() < offset
+    {
+        return Vec::new();
+    }
+
+    // The name that is being typed is replaced by the completion, so the context is determined by
+    // the token before it.
+    let is_name = token.kind() == SyntaxKind::IDENT || token.kind().is_keyword();
+    let previous = if is_name || token.kind().is_trivia() {
+        previous_non_trivia_token(&token)
+    } else {
+        Some(token.clone())
+    };
+    let previous_kind = previous.as_ref().map(SyntaxToken::kind);
+    // Whitespace is not necessarily part of the node it appears in, e.g. the whitespace after
+    // `let a = ` in an incomplete statement, so the context is the token before it.
+    let token = match (&previous, token.kind().is_trivia()) {
+        (Some(previous), true) => previous.clone(),
+        _ => token,
+    };
+
+    let function = function_at(db, file_id, offset);
+    let mut completions = Completions {
+        db,
+        items: Vec::new(),
+        seen: HashSet::new(),
+        expected_ty: None,
+    };
+
+    if previous_kind == Some(T![::]) {
+        // Paths that refer to other modules are not completed
+        return Vec::new();
+    } else if previous_kind == Some(T![.]) {
+        let function = match function {
+            Some(function) => function,
+            None => return Vec::new(),
+        };
+        let receiver = previous
+            .and_then(|dot| dot.parent().ancestors().find_map(ast::FieldExpr::cast))
+            .and_then(|field_expr| field_expr.expr());
+        let ctx = FunctionContext::new(db, function, root.clone());
+        completions.expected_ty = ctx.expected_ty(db, &token, offset);
+        if let Some(ty) = receiver
+            .and_then(|receiver| ctx.source_map.node_expr(&receiver))
+            .map(|receiver| &ctx.infer[receiver])
+        {
+            if let Some(s) = ty.as_struct() {
+                for field in s.fields(db) {
+                    let ty = field.ty(db);
+                    let detail = ty.display(db).to_string();
+                    completions.add(
+                        &field.name(db),
+                        CompletionKind::Field,
+                        Some(ty),
+                        Some(detail),
+                    );
+                }
+            }
+        }
+    } else if is_type_position(&token, previous_kind) {
+        completions.add_module_items(file_id, true);
+    } else if let Some(function) = function {
+        let ctx = FunctionContext::new(db, function, root.clone());
+        completions.expected_ty = ctx.expected_ty(db, &token, offset);
+        ctx.add_locals(db, &mut completions, offset);
+        completions.add_module_items(file_id, false);
+        completions.add_keywords(EXPR_KEYWORDS);
+    } else {
+        completions.add_keywords(ITEM_KEYWORDS);
+    }
+
+    let mut items = completions.items;
+    items.sort_by(|a, b| {
+        (!a.type_matches, a.kind, &a.label).cmp(&(!b.type_matches, b.kind, &b.label))
+    });
+    items
+}
+
+struct Completions<'a> {
+    db: &'a dyn HirDatabase,
+    items: Vec<CompletionItem>,
+    /// The names that are already completed, a name that is seen again is shadowed
+    seen: HashSet<String>,
+    /// The type that is expected at the offset
+    expected_ty: Option<Ty>,
+}
+
+impl<'a> Completions<'a> {
+    /// Adds a completion, unless a completion with the same name was already added. `ty` is the
+    /// type that the completion evaluates to, which is compared to the expected type.
+    fn add(&mut self, name: &Name, kind: CompletionKind, ty: Option<Ty>, detail: Option<String>) {
+        let label = name.to_string();
+        if !self.seen.insert(label.clone()) {
+            return;
+        }
+        let type_matches = match (&ty, &self.expected_ty) {
+            (Some(ty), Some(expected_ty)) => ty == expected_ty && *ty != Ty::Unknown,
+            _ => false,
+        };
+        self.items.push(CompletionItem {
+            label,
+            kind,
+            detail,
+            type_matches,
+        });
+    }
+
+    /// Adds the items of the module and the builtin types. If `types_only` is set, only the
+    /// definitions that can be used as a type are added.
+    fn add_module_items(&mut self, file_id: FileId, types_only: bool) {
+        let db = self.db;
+        let scope = db.module_scope(file_id);
+        for (name, resolution) in scope.entries() {
+            match (resolution.def.values, resolution.def.types) {
+                (Some(ModuleDef::Function(f)), _) if !types_only => {
+                    // A call of the function evaluates to its return type
+                    let ty = f.ty(db);
+                    let ret = ty.callable_sig(db).map(|sig| sig.ret().clone());
+                    let detail = ty.display(db).to_string();
+                    self.add(name, CompletionKind::Function, ret, Some(detail));
+                }
+                (_, Some(ModuleDef::Struct(s))) => {
+                    self.add(name, CompletionKind::Struct, Some(s.ty(db)), None)
+                }
+                (_, Some(ModuleDef::BuiltinType(_))) if types_only => {
+                    self.add(name, CompletionKind::BuiltinType, None, None)
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn add_keywords(&mut self, keywords: &[&str]) {
+        for keyword in keywords {
+            let ty = match *keyword {
+                "true" | "false" => Some(Ty::simple(TypeCtor::Bool)),
+                _ => None,
+            };
+            self.add(
+                &Name::new_from_text(keyword),
+                CompletionKind::Keyword,
+                ty,
+                None,
+            );
+        }
+    }
+}
+
+/// The lowered body of the function that contains the offset.
+struct FunctionContext {
+    function: Function,
+    root: SyntaxNode,
+    body: Arc<Body>,
+    source_map: Arc<BodySourceMap>,
+    infer: Arc<InferenceResult>,
+    scopes: Arc<ExprScopes>,
+}
+
+impl FunctionContext {
+    fn new(db: &dyn HirDatabase, function: Function, root: SyntaxNode) -> Self {
+        let (body, source_map) = db.body_with_source_map(function.into());
+        FunctionContext {
+            function,
+            root,
+            body,
+            source_map,
+            infer: function.infer(db),
+            scopes: db.expr_scopes(function.into()),
+        }
+    }
+
+    /// Returns the type that is expected at the offset, e.g. the type of the parameter of a call
+    /// or the ascribed type of a `let` binding.
+    fn expected_ty(
+        &self,
+        db: &dyn HirDatabase,
+        token: &SyntaxToken,
+        offset: TextUnit,
+    ) -> Option<Ty> {
+        let is_after = |token: Option<SyntaxToken>| {
+            token.map_or(false, |token| token.text_range().end() <= offset)
+        };
+        let expr_ty = |expr: Option<ast::Expr>| {
+            self.source_map
+                .node_expr(&expr?)
+                .map(|expr| self.infer[expr].clone())
+        };
+
+        for node in token.parent().ancestors() {
+            if let Some(let_stmt) = ast::LetStmt::cast(node.clone()) {
+                let_stmt.ascribed_type()?;
+                if !is_after(child_token(let_stmt.syntax(), T![=])) {
+                    return None;
+                }
+                let pat = self.source_map.node_pat(&let_stmt.pat()?)?;
+                return Some(self.infer[pat].clone());
+            } else if let Some(arg_list) = ast::ArgList::cast(node.clone()) {
+                let index = arg_list
+                    .syntax()
+                    .children_with_tokens()
+                    .filter(|child| child.kind() == T![,] && child.text_range().end() <= offset)
+                    .count();
+                let call = node.parent().and_then(ast::CallExpr::cast)?;
+                let sig = expr_ty(call.expr())?.callable_sig(db)?;
+                return sig.params().get(index).cloned();
+            } else if let Some(bin_expr) = ast::BinExpr::cast(node.clone()) {
+                if !is_after(bin_expr.op_token()) {
+                    return None;
+                }
+                return expr_ty(bin_expr.lhs());
+            } else if let Some(record_field) = ast::RecordField::cast(node.clone()) {
+                if !is_after(child_token(record_field.syntax(), T![:])) {
+                    return None;
+                }
+                let field_name = record_field.name_ref()?;
+                let record_lit = node.ancestors().find_map(ast::RecordLit::cast)?;
+                let s = expr_ty(Some(ast::Expr::from(record_lit)))?.as_struct()?;
+                let field = s.field(db, &Name::new_from_text(field_name.text()))?;
+                return Some(field.ty(db));
+            } else if ast::ReturnExpr::cast(node.clone()).is_some() {
+                let sig = self.function.ty(db).callable_sig(db)?;
+                return Some(sig.ret().clone());
+            } else if ast::Condition::cast(node.clone()).is_some() {
+                return Some(Ty::simple(TypeCtor::Bool));
+            } else if ast::BlockExpr::cast(node.clone()).is_some()
+                || ast::ExprStmt::cast(node.clone()).is_some()
+            {
+                return None;
+            }
+        }
+        None
+    }
+
+    /// Returns the scope of the local bindings that are visible at the offset.
+    fn scope_at(&self, offset: TextUnit) -> Option<ScopeId> {
+        let innermost = self
+            .scopes
+            .scope_by_expr()
+            .keys()
+            .filter_map(|&expr| {
+                let range = self.expr_syntax(expr)?.range();
+                let contains = match &self.body[expr] {
+                    // The bindings of a block are not visible after its closing brace
+                    Expr::Block { .. } => range.start() < offset && offset < range.end(),
+                    _ => range.contains_inclusive(offset),
+                };
+                if contains {
+                    Some((expr, range.len()))
+                } else {
+                    None
+                }
+            })
+            .min_by_key(|(_, len)| *len)
+            .map(|(expr, _)| expr)?;
+
+        let mut scope = self.scopes.scope_for(innermost);
+        // If the offset is between the statements of a block, the bindings of the `let`
+        // statements before it are visible.
+        if let Expr::Block { statements, .. } = &self.body[innermost] {
+            for statement in statements {
+                if let Statement::Let { pat, .. } = statement {
+                    let let_stmt = self
+                        .source_map
+                        .pat_syntax(*pat)
+                        .map(|src| src.value.syntax_node_ptr().to_node(&self.root))
+                        .and_then(|node| node.ancestors().find_map(ast::LetStmt::cast));
+                    let is_before = let_stmt.map_or(false, |let_stmt| {
+                        let is_incomplete = let_stmt.initializer().is_none()
+                            && child_token(let_stmt.syntax(), T![=]).is_some();
+                        !is_incomplete && let_stmt.syntax().text_range().end() <= offset
+                    });
+                    if !is_before {
+                        break;
+                    }
+                    if let Some(let_scope) = self.scopes.scope_for_pat(*pat) {
+                        scope = Some(let_scope);
+                    }
+                }
+            }
+        }
+        scope
+    }
+
+    /// Adds the local bindings and parameters that are visible at the offset.
+    fn add_locals(&self, db: &dyn HirDatabase, completions: &mut Completions, offset: TextUnit) {
+        let scope = self.scope_at(offset);
+        for scope in self.scopes.scope_chain(scope) {
+            for entry in self.scopes.entries(scope).iter().rev() {
+                let kind = if self
+                    .body
+                    .params()
+                    .iter()
+                    .any(|(param, _)| *param == entry.pat())
+                {
+                    CompletionKind::Parameter
+                } else {
+                    CompletionKind::Local
+                };
+                let ty = self.infer[entry.pat()].clone();
+                let detail = ty.display(db).to_string();
+                completions.add(entry.name(), kind, Some(ty), Some(detail));
+            }
+        }
+    }
+
+    fn expr_syntax(&self, expr: ExprId) -> Option<SyntaxNodePtr> {
+        self.source_map.expr_syntax(expr).map(|src| {
+            src.value
+                .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr())
+        })
+    }
+}
+
+/// Returns the function whose definition contains the offset.
+pub(crate) fn function_at(
+    db: &dyn HirDatabase,
+    file_id: FileId,
+    offset: TextUnit,
+) -> Option<Function> {
+    let module = Module::from(file_id);
+    module
+        .declarations(db)
+        .into_iter()
+        .filter_map(|def| match def {
+            ModuleDef::Function(f) => Some(f),
+            _ => None,
+        })
+        .chain(module.impls(db).into_iter().flat_map(|i| i.functions(db)))
+        .find(|f| {
+            f.source(db.upcast())
+                .value
+                .syntax()
+                .text_range()
+                .contains_inclusive(offset)
+        })
+}
+
+/// Returns true if the token before the offset is followed by a type, e.g. after `let a: `.
+fn is_type_position(token: &SyntaxToken, previous_kind: Option<SyntaxKind>) -> bool {
+    previous_kind == Some(T![->])
+        || (previous_kind == Some(T![:])
+            && token.parent().ancestors().any(|node| {
+                ast::LetStmt::can_cast(node.kind())
+                    || ast::Param::can_cast(node.kind())
+                    || ast::RecordFieldDef::can_cast(node.kind())
+            })
+            && !token
+                .parent()
+                .ancestors()
+                .any(|node| ast::RecordLit::can_cast(node.kind())))
+        || token
+            .parent()
+            .ancestors()
+            .take_while(|node| !ast::Expr::can_cast(node.kind()))
+            .any(|node| ast::PathType::can_cast(node.kind()))
+}
+
+pub(crate) fn previous_non_trivia_token(token: &SyntaxToken) -> Option<SyntaxToken> {
+    std::iter::successors(token.prev_token(), SyntaxToken::prev_token)
+        .find(|token| !token.kind().is_trivia())
+}
+
+fn child_token(node: &SyntaxNode, kind: SyntaxKind) -> Option<SyntaxToken> {
+    node.children_with_tokens()
+        .filter_map(|child| child.into_token())
+        .find(|token| token.kind() == kind)
+}
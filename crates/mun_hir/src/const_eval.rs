@@ -0,0 +1,388 @@
+//! Evaluation of expressions whose value is known at compile time.
+//!
+//! The evaluator folds arithmetic, comparisons and logical operations on literals and propagates
+//! the values of local bindings that are initialized with a constant and never assigned to. An
+//! operation is only folded if its result is well-defined: operations that overflow, divide by
+//! zero, or shift by more than the bit width of their type are left to be evaluated at runtime,
+//! so folding never changes the behavior of a program.
+
+use crate::{
+    arena::map::ArenaMap,
+    code_model::DefWithBody,
+    expr::{LiteralFloat, LiteralFloatKind, LiteralInt, LiteralIntKind},
+    resolve::Resolution,
+    ty::ResolveBitness,
+    ArithOp, BinaryOp, Body, CmpOp, Expr, ExprId, FloatBitness, HirDatabase, InferenceResult,
+    IntBitness, IntTy, Literal, LogicOp, Ordering, Pat, PatId, Signedness, Statement, TypeCtor,
+    UnaryOp,
+};
+use mun_target::abi::TargetDataLayout;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::sync::Arc;
+
+/// The values of all expressions of a body that can be evaluated at compile time.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ConstEvalResult {
+    values: ArenaMap<ExprId, Literal>,
+}
+
+impl ConstEvalResult {
+    /// Returns the value of the specified expression, if it is known at compile time.
+    pub fn value(&self, expr: ExprId) -> Option<&Literal> {
+        self.values.get(expr)
+    }
+
+    pub(crate) fn const_eval_query(db: &dyn HirDatabase, def: DefWithBody) -> Arc<ConstEvalResult> {
+        let body = def.body(db);
+        let infer = def.infer(db);
+        let data_layout = db.target_data_layout();
+
+        let mut evaluator = ConstEvaluator {
+            db,
+            body: body.clone(),
+            infer: &infer,
+            data_layout: &data_layout,
+            initializers: FxHashMap::default(),
+            assigned_patterns: FxHashSet::default(),
+            bindings: FxHashMap::default(),
+            result: ConstEvalResult::default(),
+        };
+        evaluator.collect_bindings();
+        for (expr, _) in body.exprs() {
+            evaluator.eval(expr);
+        }
+
+        Arc::new(evaluator.result)
+    }
+}
+
+/// A value that is known at compile time. Integers are stored as `i128`, which covers all values
+/// of all integer types except for the upper half of `u128`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ConstValue {
+    Int(i128),
+    Float(f64),
+    Bool(bool),
+}
+
+struct ConstEvaluator<'a> {
+    db: &'a dyn HirDatabase,
+    body: Arc<Body>,
+    infer: &'a InferenceResult,
+    data_layout: &'a TargetDataLayout,
+    /// The initializers of all `let` bindings
+    initializers: FxHashMap<PatId, ExprId>,
+    /// The bindings that are assigned to after their initialization
+    assigned_patterns: FxHashSet<PatId>,
+    /// The already evaluated values of bindings, or `None` if a binding is not constant
+    bindings: FxHashMap<PatId, Option<ConstValue>>,
+    result: ConstEvalResult,
+}
+
+impl<'a> ConstEvaluator<'a> {
+    /// Collects the initializers of all `let` bindings and the bindings that are assigned to.
+    fn collect_bindings(&mut self) {
+        let body = self.body.clone();
+        for (_, data) in body.exprs() {
+            match data {
+                Expr::Block { statements, .. } => {
+                    for statement in statements.iter() {
+                        if let Statement::Let {
+                            pat,
+                            initializer: Some(initializer),
+                            ..
+                        } = statement
+                        {
+                            self.initializers.insert(*pat, *initializer);
+                        }
+                    }
+                }
+                Expr::BinaryOp {
+                    lhs,
+                    op: Some(BinaryOp::Assignment { .. }),
+                    ..
+                } => {
+                    if let Some(pat) = self.resolve_local(*lhs) {
+                        self.assigned_patterns.insert(pat);
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Returns the local binding that the specified expression refers to, if any.
+    fn resolve_local(&self, expr: ExprId) -> Option<PatId> {
+        let path = match &self.body[expr] {
+            Expr::Path(path) => path,
+            _ => return None,
+        };
+        let resolver = crate::expr::resolver_for_expr(self.body.clone(), self.db, expr);
+        match resolver
+            .resolve_path_without_assoc_items(self.db, path)
+            .take_values()?
+        {
+            Resolution::LocalBinding(pat) => Some(pat),
+            Resolution::Def(_) => None,
+        }
+    }
+
+    /// Evaluates the specified expression and stores its value, if it is known at compile time.
+    fn eval(&mut self, expr: ExprId) -> Option<ConstValue> {
+        if let Some(value) = self.result.values.get(expr) {
+            return self.eval_literal(expr, value);
+        }
+
+        let value = self.eval_inner(expr)?;
+        let literal = self.to_literal(value);
+        self.result.values.insert(expr, literal);
+        Some(value)
+    }
+
+    fn eval_inner(&mut self, expr: ExprId) -> Option<ConstValue> {
+        // Overloaded operators call a function, so their result is not known
+        if self.infer.operator_resolution(expr).is_some() {
+            return None;
+        }
+
+        let body = self.body.clone();
+        match &body[expr] {
+            Expr::Literal(literal) => self.eval_literal(expr, literal),
+            Expr::Path(_) => {
+                let pat = self.resolve_local(expr)?;
+                self.eval_binding(pat)
+            }
+            Expr::Block {
+                statements,
+                tail: Some(tail),
+            } if statements.is_empty() => self.eval(*tail),
+            Expr::UnaryOp { expr: operand, op } => {
+                let value = self.eval(*operand)?;
+                self.eval_unary_op(expr, value, *op)
+            }
+            Expr::BinaryOp {
+                lhs,
+                rhs,
+                op: Some(op),
+            } => {
+                let op = *op;
+                if let BinaryOp::Assignment { .. } = op {
+                    return None;
+                }
+                let lhs = self.eval(*lhs)?;
+                let rhs = self.eval(*rhs)?;
+                self.eval_binary_op(expr, lhs, rhs, op)
+            }
+            _ => None,
+        }
+    }
+
+    /// Evaluates the value of a local binding, which is only constant if it is initialized with a
+    /// constant and never assigned to.
+    fn eval_binding(&mut self, pat: PatId) -> Option<ConstValue> {
+        if let Some(value) = self.bindings.get(&pat) {
+            return *value;
+        }
+
+        let value = match (&self.body[pat], self.initializers.get(&pat)) {
+            (Pat::Bind { .. }, Some(initializer)) if !self.assigned_patterns.contains(&pat) => {
+                // Guard against bindings that (erroneously) refer to themselves
+                self.bindings.insert(pat, None);
+                self.eval(*initializer)
+            }
+            _ => None,
+        };
+        self.bindings.insert(pat, value);
+        value
+    }
+
+    fn eval_unary_op(&self, expr: ExprId, value: ConstValue, op: UnaryOp) -> Option<ConstValue> {
+        match (value, op) {
+            (ConstValue::Int(value), UnaryOp::Neg) => self.checked_int(expr, value.checked_neg()?),
+            (ConstValue::Int(value), UnaryOp::Not) => {
+                let int_ty = self.int_ty(expr)?;
+                match int_ty.signedness {
+                    Signedness::Signed => self.checked_int(expr, !value),
+                    Signedness::Unsigned => self.checked_int(expr, max_value(int_ty) - value),
+                }
+            }
+            (ConstValue::Float(value), UnaryOp::Neg) => Some(self.float(expr, -value)),
+            (ConstValue::Bool(value), UnaryOp::Not) => Some(ConstValue::Bool(!value)),
+            _ => None,
+        }
+    }
+
+    fn eval_binary_op(
+        &self,
+        expr: ExprId,
+        lhs: ConstValue,
+        rhs: ConstValue,
+        op: BinaryOp,
+    ) -> Option<ConstValue> {
+        match (lhs, rhs, op) {
+            (ConstValue::Int(lhs), ConstValue::Int(rhs), BinaryOp::ArithOp(op)) => {
+                let int_ty = self.int_ty(expr)?;
+                let bit_width = i128::from(bits(int_ty.bitness));
+                let result = match op {
+                    ArithOp::Add => lhs.checked_add(rhs)?,
+                    ArithOp::Subtract => lhs.checked_sub(rhs)?,
+                    ArithOp::Multiply => lhs.checked_mul(rhs)?,
+                    ArithOp::Divide => lhs.checked_div(rhs)?,
+                    ArithOp::Remainder => lhs.checked_rem(rhs)?,
+                    ArithOp::LeftShift | ArithOp::RightShift if rhs < 0 || rhs >= bit_width => {
+                        return None
+                    }
+                    ArithOp::LeftShift => {
+                        // Shifting out bits is an overflow
+                        let result = lhs.checked_mul(1i128.checked_shl(rhs as u32)?)?;
+                        self.checked_int(expr, result)?;
+                        result
+                    }
+                    ArithOp::RightShift => lhs >> rhs,
+                    ArithOp::BitAnd => lhs & rhs,
+                    ArithOp::BitOr => lhs | rhs,
+                    ArithOp::BitXor => lhs ^ rhs,
+                };
+                self.checked_int(expr, result)
+            }
+            (ConstValue::Float(lhs), ConstValue::Float(rhs), BinaryOp::ArithOp(op)) => {
+                let result = match op {
+                    ArithOp::Add => lhs + rhs,
+                    ArithOp::Subtract => lhs - rhs,
+                    ArithOp::Multiply => lhs * rhs,
+                    ArithOp::Divide => lhs / rhs,
+                    ArithOp::Remainder => lhs % rhs,
+                    _ => return None,
+                };
+                Some(self.float(expr, result))
+            }
+            (ConstValue::Int(lhs), ConstValue::Int(rhs), BinaryOp::CmpOp(op)) => {
+                Some(ConstValue::Bool(eval_cmp_op(lhs.partial_cmp(&rhs)?, op)))
+            }
+            (ConstValue::Float(lhs), ConstValue::Float(rhs), BinaryOp::CmpOp(op)) => {
+                // Comparisons with NaN are left to the runtime
+                Some(ConstValue::Bool(eval_cmp_op(lhs.partial_cmp(&rhs)?, op)))
+            }
+            (ConstValue::Bool(lhs), ConstValue::Bool(rhs), BinaryOp::CmpOp(op)) => {
+                Some(ConstValue::Bool(eval_cmp_op(lhs.cmp(&rhs), op)))
+            }
+            (ConstValue::Bool(lhs), ConstValue::Bool(rhs), BinaryOp::LogicOp(op)) => {
+                Some(ConstValue::Bool(match op {
+                    LogicOp::And => lhs && rhs,
+                    LogicOp::Or => lhs || rhs,
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the integer type of the specified expression with a resolved bitness.
+    fn int_ty(&self, expr: ExprId) -> Option<IntTy> {
+        match self.infer[expr].as_simple()? {
+            TypeCtor::Int(int_ty) => Some(int_ty.resolve(self.data_layout)),
+            _ => None,
+        }
+    }
+
+    /// Returns the specified integer as the value of `expr`, if it fits in the type of `expr`.
+    fn checked_int(&self, expr: ExprId, value: i128) -> Option<ConstValue> {
+        let int_ty = self.int_ty(expr)?;
+        let min_value = match int_ty.signedness {
+            Signedness::Signed => -max_value(int_ty) - 1,
+            Signedness::Unsigned => 0,
+        };
+        if value >= min_value && value <= max_value(int_ty) {
+            Some(ConstValue::Int(value))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the specified float as the value of `expr`, rounded to the precision of its type.
+    fn float(&self, expr: ExprId, value: f64) -> ConstValue {
+        let is_f32 = match self.infer[expr].as_simple() {
+            Some(TypeCtor::Float(float_ty)) => {
+                float_ty.bitness.resolve(self.data_layout) == FloatBitness::X32
+            }
+            _ => false,
+        };
+        if is_f32 {
+            ConstValue::Float(f64::from(value as f32))
+        } else {
+            ConstValue::Float(value)
+        }
+    }
+
+    fn eval_literal(&self, expr: ExprId, literal: &Literal) -> Option<ConstValue> {
+        match literal {
+            Literal::Int(LiteralInt { value, .. }) => {
+                if *value > i128::MAX as u128 {
+                    return None;
+                }
+                self.checked_int(expr, *value as i128)
+            }
+            Literal::Float(LiteralFloat { value, .. }) => Some(self.float(expr, *value)),
+            Literal::Bool(value) => Some(ConstValue::Bool(*value)),
+            Literal::Char(_) | Literal::String(_) => None,
+        }
+    }
+
+    fn to_literal(&self, value: ConstValue) -> Literal {
+        match value {
+            // Negative values are stored in two's complement
+            ConstValue::Int(value) => Literal::Int(LiteralInt {
+                kind: LiteralIntKind::Unsuffixed,
+                value: value as u128,
+            }),
+            ConstValue::Float(value) => Literal::Float(LiteralFloat {
+                kind: LiteralFloatKind::Unsuffixed,
+                value,
+            }),
+            ConstValue::Bool(value) => Literal::Bool(value),
+        }
+    }
+}
+
+/// Returns the largest value of the specified integer type.
+fn max_value(int_ty: IntTy) -> i128 {
+    let bits = bits(int_ty.bitness);
+    match int_ty.signedness {
+        Signedness::Signed => i128::MAX >> (128 - bits),
+        // The upper half of `u128` cannot be represented
+        Signedness::Unsigned if bits == 128 => i128::MAX,
+        Signedness::Unsigned => (1i128 << bits) - 1,
+    }
+}
+
+/// Returns the result of the comparison `op` given the `ordering` of its operands.
+fn eval_cmp_op(ordering: std::cmp::Ordering, op: CmpOp) -> bool {
+    match op {
+        CmpOp::Eq { negated } => (ordering == std::cmp::Ordering::Equal) != negated,
+        CmpOp::Ord {
+            ordering: Ordering::Less,
+            strict,
+        } => {
+            ordering == std::cmp::Ordering::Less
+                || (!strict && ordering == std::cmp::Ordering::Equal)
+        }
+        CmpOp::Ord {
+            ordering: Ordering::Greater,
+            strict,
+        } => {
+            ordering == std::cmp::Ordering::Greater
+                || (!strict && ordering == std::cmp::Ordering::Equal)
+        }
+    }
+}
+
+/// Returns the number of bits of a resolved integer bitness.
+fn bits(bitness: IntBitness) -> u32 {
+    match bitness {
+        IntBitness::X8 => 8,
+        IntBitness::X16 => 16,
+        IntBitness::X32 => 32,
+        IntBitness::X64 => 64,
+        IntBitness::X128 => 128,
+        IntBitness::Xsize => unreachable!("bitness must be resolved"),
+    }
+}
@@ -1,15 +1,22 @@
 #![allow(clippy::type_repetition_in_bounds)]
 
+use crate::call_graph::CallGraph;
+use crate::classify::ClassifiedIdent;
+use crate::const_eval::ConstEvalResult;
 use crate::input::{SourceRoot, SourceRootId};
+use crate::lint::LintLevels;
 use crate::name_resolution::Namespace;
+use crate::references::{Reference, ReferenceDef};
 use crate::ty::lower::LowerBatchResult;
 use crate::ty::{CallableDef, FnSig, Ty, TypableDef};
 use crate::{
     adt::StructData,
-    code_model::{DefWithBody, FnData, Function, ModuleData},
+    attrs::{AttrDef, Attrs},
+    code_model::{DefWithBody, FnData, Function, Impl, ImplData, ModuleData},
+    docs::{DocDef, Documentation},
     ids,
     line_index::LineIndex,
-    name_resolution::ModuleScope,
+    name_resolution::{ModuleScope, ModuleTree},
     ty::InferenceResult,
     AstIdMap, ExprScopes, FileId, RawItems, Struct,
 };
@@ -69,6 +76,21 @@ pub trait DefDatabase: SourceDatabase + Upcast<dyn SourceDatabase> {
     #[salsa::invoke(crate::FnData::fn_data_query)]
     fn fn_data(&self, func: Function) -> Arc<FnData>;
 
+    /// Returns the attributes of the specified definition
+    #[salsa::invoke(crate::attrs::Attrs::attrs_query)]
+    fn attrs(&self, def: AttrDef) -> Arc<Attrs>;
+
+    /// Returns the documentation of the specified definition
+    #[salsa::invoke(crate::docs::Documentation::documentation_query)]
+    fn documentation(&self, def: DocDef) -> Option<Documentation>;
+
+    #[salsa::invoke(crate::code_model::ImplData::impl_data_query)]
+    fn impl_data(&self, def: Impl) -> Arc<ImplData>;
+
+    /// Returns the module tree of the package in the specified source root
+    #[salsa::invoke(crate::name_resolution::ModuleTree::module_tree_query)]
+    fn module_tree(&self, source_root: SourceRootId) -> Arc<ModuleTree>;
+
     /// Returns the module data of the specified file
     #[salsa::invoke(crate::code_model::ModuleData::module_data_query)]
     fn module_data(&self, file_id: FileId) -> Arc<ModuleData>;
@@ -80,6 +102,10 @@ pub trait DefDatabase: SourceDatabase + Upcast<dyn SourceDatabase> {
     /// Interns a struct definition
     #[salsa::interned]
     fn intern_struct(&self, loc: ids::ItemLoc<ast::StructDef>) -> ids::StructId;
+
+    /// Interns an impl block
+    #[salsa::interned]
+    fn intern_impl(&self, loc: ids::ItemLoc<ast::ImplDef>) -> ids::ImplId;
 }
 
 #[salsa::query_group(HirDatabaseStorage)]
@@ -88,6 +114,10 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::input]
     fn target(&self) -> Target;
 
+    /// Returns the package-wide levels of lints.
+    #[salsa::input]
+    fn lint_levels(&self) -> Arc<LintLevels>;
+
     /// Returns the `TargetDataLayout` for the current target
     #[salsa::invoke(target_data_layout)]
     fn target_data_layout(&self) -> Arc<abi::TargetDataLayout>;
@@ -101,9 +131,15 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::invoke(crate::ty::infer_query)]
     fn infer(&self, def: DefWithBody) -> Arc<InferenceResult>;
 
+    #[salsa::invoke(crate::const_eval::ConstEvalResult::const_eval_query)]
+    fn const_eval(&self, def: DefWithBody) -> Arc<ConstEvalResult>;
+
     #[salsa::invoke(crate::ty::lower::lower_struct_query)]
     fn lower_struct(&self, def: Struct) -> Arc<LowerBatchResult>;
 
+    #[salsa::invoke(crate::ty::lower::lower_impl_query)]
+    fn lower_impl(&self, def: Impl) -> Arc<LowerBatchResult>;
+
     #[salsa::invoke(crate::ty::callable_item_sig)]
     fn callable_sig(&self, def: CallableDef) -> FnSig;
 
@@ -113,6 +149,23 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::invoke(crate::expr::body_hir_query)]
     fn body(&self, def: DefWithBody) -> Arc<crate::expr::Body>;
 
+    /// Returns the call graph of the package with the specified source root.
+    #[salsa::invoke(crate::call_graph::CallGraph::call_graph_query)]
+    fn call_graph(&self, source_root: SourceRootId) -> Arc<CallGraph>;
+
+    /// Returns the other files of which the specified file uses definitions, ordered by their
+    /// `FileId`.
+    #[salsa::invoke(crate::dependencies::file_dependencies_query)]
+    fn file_dependencies(&self, file_id: FileId) -> Arc<Vec<FileId>>;
+
+    /// Returns all references to the specified definition within its package.
+    #[salsa::invoke(crate::references::find_all_references_query)]
+    fn find_all_references(&self, def: ReferenceDef) -> Arc<Vec<Reference>>;
+
+    /// Returns the kinds of definitions that the identifiers in the specified file refer to.
+    #[salsa::invoke(crate::classify::classify_identifiers_query)]
+    fn classify_identifiers(&self, file_id: FileId) -> Arc<Vec<ClassifiedIdent>>;
+
     #[salsa::invoke(crate::expr::body_with_source_map_query)]
     fn body_with_source_map(
         &self,
@@ -0,0 +1,278 @@
+//! Finds the definition that a name in a file refers to, e.g. to go to the definition of an
+//! identifier in an editor.
+
+use crate::{
+    builtin_type::BuiltinType,
+    code_model::{src::HasSource, Local},
+    completion::function_at,
+    expr::resolver_for_expr,
+    references::last_name_ref_range,
+    resolve::Resolution,
+    Expr, FileId, Function, HirDatabase, Module, ModuleDef, Name, Path, ReferenceDef, Resolver,
+};
+use mun_syntax::{ast, AstNode, SyntaxKind, SyntaxNode, SyntaxToken, TextRange, TextUnit};
+
+/// A definition that a name can refer to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Definition {
+    Def(ReferenceDef),
+    BuiltinType(BuiltinType),
+}
+
+/// The location of a definition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NavigationTarget {
+    /// The file that contains the definition
+    pub file_id: FileId,
+    /// The range of the whole definition, e.g. including the body of a function
+    pub full_range: TextRange,
+    /// The range of the name of the definition
+    pub focus_range: TextRange,
+}
+
+/// Returns the location of the definition that the name at the specified offset in a file refers
+/// to. Builtin types have no location.
+pub fn goto_definition(
+    db: &dyn HirDatabase,
+    file_id: FileId,
+    offset: TextUnit,
+) -> Option<NavigationTarget> {
+    let root = db.parse(file_id).syntax_node();
+    let token = token_at(&root, offset)?;
+    match definition_for_token(db, file_id, &token)?.1 {
+        Definition::Def(def) => navigation_target(db, def),
+        Definition::BuiltinType(_) => None,
+    }
+}
+
+/// Returns the token at the offset, preferring a name over the punctuation next to it.
+pub(crate) fn token_at(root: &SyntaxNode, offset: TextUnit) -> Option<SyntaxToken> {
+    let token = root
+        .token_at_offset(offset)
+        .max_by_key(|token| match token.kind() {
+            SyntaxKind::IDENT | SyntaxKind::INT_NUMBER => 2,
+            kind if kind.is_trivia() => 0,
+            _ => 1,
+        })?;
+    if token.kind().is_trivia() {
+        None
+    } else {
+        Some(token)
+    }
+}
+
+/// Returns the range of the name that contains the token and the definition that the name refers
+/// to or declares.
+pub(crate) fn definition_for_token(
+    db: &dyn HirDatabase,
+    file_id: FileId,
+    token: &SyntaxToken,
+) -> Option<(TextRange, Definition)> {
+    if let Some(name) = token.parent().ancestors().find_map(ast::Name::cast) {
+        let def = declared_definition(db, file_id, &name)?;
+        return Some((name.syntax().text_range(), Definition::Def(def)));
+    }
+
+    let token_range = token.text_range();
+    // Returns true if the token is part of the name with the specified range
+    let is_on_name =
+        |range: TextRange| range.start() < token_range.end() && token_range.start() < range.end();
+    let function = function_at(db, file_id, token_range.start());
+    if let Some(path_type) = token
+        .parent()
+        .ancestors()
+        .take_while(|node| !ast::Expr::can_cast(node.kind()))
+        .find_map(ast::PathType::cast)
+    {
+        let path = Path::from_ast(path_type.path()?)?;
+        let resolution = Resolver::default()
+            .push_module_scope(file_id)
+            .resolve_path_without_assoc_items(db, &path)
+            .take_types()?;
+        let range = last_name_ref_range(path_type.syntax());
+        return Some((range, definition_for_resolution(resolution, None)?));
+    }
+
+    // Names in the body of a function
+    let function = function?;
+    let (body, source_map) = db.body_with_source_map(function.into());
+    let infer = function.infer(db);
+    let (expr, node) = token
+        .parent()
+        .ancestors()
+        .filter_map(ast::Expr::cast)
+        .find_map(|node| Some((source_map.node_expr(&node)?, node)))?;
+    match &body[expr] {
+        Expr::Path(path) => {
+            let range = last_name_ref_range(node.syntax());
+            if !is_on_name(range) {
+                return None;
+            }
+            let resolution = resolver_for_expr(body.clone(), db, expr)
+                .resolve_path_without_assoc_items(db, path);
+            let resolution = resolution
+                .clone()
+                .take_values()
+                .or_else(|| resolution.take_types())?;
+            Some((
+                range,
+                definition_for_resolution(resolution, Some(function))?,
+            ))
+        }
+        Expr::Field {
+            expr: receiver,
+            name,
+        } => {
+            let range = match ast::FieldExpr::cast(node.syntax().clone())?.field_access()? {
+                ast::FieldKind::Name(name_ref) => name_ref.syntax().text_range(),
+                ast::FieldKind::Index(token) => {
+                    // The index token includes the leading `.`
+                    let range = token.text_range();
+                    TextRange::from_to(range.start() + TextUnit::from(1), range.end())
+                }
+            };
+            if !is_on_name(range) {
+                return None;
+            }
+            let field = infer[*receiver].as_struct()?.field(db, name)?;
+            Some((range, Definition::Def(field.into())))
+        }
+        Expr::RecordLit { .. } => {
+            let name_ref = token.parent().ancestors().find_map(ast::NameRef::cast)?;
+            let record_field = ast::RecordField::cast(name_ref.syntax().parent()?)?;
+            let name = Name::new_from_text(name_ref.text());
+            // The name of a field shorthand like `Foo { a }` refers to the local binding
+            if record_field.expr().is_none() {
+                let resolution = resolver_for_expr(body.clone(), db, expr)
+                    .resolve_name(db, &name)
+                    .take_values()?;
+                return Some((
+                    name_ref.syntax().text_range(),
+                    definition_for_resolution(resolution, Some(function))?,
+                ));
+            }
+            let field = infer[expr].as_struct()?.field(db, &name)?;
+            Some((
+                name_ref.syntax().text_range(),
+                Definition::Def(field.into()),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the definition that the specified name declares.
+fn declared_definition(
+    db: &dyn HirDatabase,
+    file_id: FileId,
+    name: &ast::Name,
+) -> Option<ReferenceDef> {
+    let parent = name.syntax().parent()?;
+    let module = Module::from(file_id);
+    let struct_for = |struct_def: &SyntaxNode| {
+        module
+            .declarations(db)
+            .into_iter()
+            .find_map(|def| match def {
+                ModuleDef::Struct(s) if s.source(db.upcast()).value.syntax() == struct_def => {
+                    Some(s)
+                }
+                _ => None,
+            })
+    };
+
+    if ast::FunctionDef::can_cast(parent.kind()) {
+        module
+            .declarations(db)
+            .into_iter()
+            .filter_map(|def| match def {
+                ModuleDef::Function(f) => Some(f),
+                _ => None,
+            })
+            .chain(module.impls(db).into_iter().flat_map(|i| i.functions(db)))
+            .find(|f| f.source(db.upcast()).value.syntax() == &parent)
+            .map(ReferenceDef::from)
+    } else if ast::StructDef::can_cast(parent.kind()) {
+        struct_for(&parent).map(ReferenceDef::from)
+    } else if ast::RecordFieldDef::can_cast(parent.kind()) {
+        let struct_def = parent
+            .ancestors()
+            .find(|node| ast::StructDef::can_cast(node.kind()))?;
+        let field = struct_for(&struct_def)?.field(db, &Name::new_from_text(name.text()))?;
+        Some(field.into())
+    } else if let Some(pat) = parent.ancestors().find_map(ast::Pat::cast) {
+        let function = function_at(db, file_id, name.syntax().text_range().start())?;
+        let pat = function.body_source_map(db).node_pat(&pat)?;
+        Some(Local::new(function, pat).into())
+    } else {
+        None
+    }
+}
+
+fn definition_for_resolution(
+    resolution: Resolution,
+    function: Option<Function>,
+) -> Option<Definition> {
+    match resolution {
+        Resolution::LocalBinding(pat) => Some(Definition::Def(Local::new(function?, pat).into())),
+        Resolution::Def(ModuleDef::Function(f)) => Some(Definition::Def(f.into())),
+        Resolution::Def(ModuleDef::Struct(s)) => Some(Definition::Def(s.into())),
+        Resolution::Def(ModuleDef::BuiltinType(ty)) => Some(Definition::BuiltinType(ty)),
+    }
+}
+
+/// Returns the location of the specified definition, from the source of items and the source map
+/// of bodies.
+pub(crate) fn navigation_target(
+    db: &dyn HirDatabase,
+    def: ReferenceDef,
+) -> Option<NavigationTarget> {
+    let (file_id, node) = match def {
+        ReferenceDef::Function(f) => {
+            let src = f.source(db.upcast());
+            (src.file_id, src.value.syntax().clone())
+        }
+        ReferenceDef::Struct(s) => {
+            let src = s.source(db.upcast());
+            (src.file_id, src.value.syntax().clone())
+        }
+        ReferenceDef::StructField(field) => {
+            let src = field.parent.source(db.upcast());
+            let index = field
+                .parent
+                .data(db.upcast())
+                .fields
+                .iter()
+                .position(|(id, _)| id == field.id)?;
+            let node = match src.value.kind() {
+                ast::StructKind::Record(r) => r.fields().nth(index)?.syntax().clone(),
+                ast::StructKind::Tuple(t) => t.fields().nth(index)?.syntax().clone(),
+                ast::StructKind::Unit => return None,
+            };
+            (src.file_id, node)
+        }
+        ReferenceDef::Local(local) => {
+            let file_id = local.parent.source(db.upcast()).file_id;
+            let root = db.parse(file_id).syntax_node();
+            let ptr = local
+                .parent
+                .body_source_map(db)
+                .pat_syntax(local.pat_id)?
+                .value
+                .syntax_node_ptr();
+            (file_id, ptr.to_node(&root))
+        }
+    };
+
+    let full_range = node.text_range();
+    let focus_range = node
+        .children()
+        .find_map(ast::Name::cast)
+        .or_else(|| node.descendants().find_map(ast::Name::cast))
+        .map_or(full_range, |name| name.syntax().text_range());
+    Some(NavigationTarget {
+        file_id,
+        full_range,
+        focus_range,
+    })
+}
@@ -0,0 +1,87 @@
+//! The dependencies of a file are the other files of which it uses definitions. The code that is
+//! generated for a file refers to these definitions, so it has to be regenerated when they change.
+
+use crate::{
+    code_model::ModuleDef, ty::lower::CallableDef, FileId, Function, HirDatabase, Module, Ty,
+    TypeCtor,
+};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+/// Returns the other files of which the definitions are used by the specified file, i.e. of which
+/// it calls functions, uses structs or implements operators for structs.
+pub(crate) fn file_dependencies_query(db: &dyn HirDatabase, file_id: FileId) -> Arc<Vec<FileId>> {
+    let mut dependencies = Dependencies {
+        db,
+        files: BTreeSet::new(),
+    };
+
+    let module = Module::from(file_id);
+    for def in module.declarations(db) {
+        match def {
+            ModuleDef::Function(function) => dependencies.function(function),
+            ModuleDef::Struct(s) => {
+                for field in s.fields(db) {
+                    dependencies.ty(&field.ty(db));
+                }
+            }
+            ModuleDef::BuiltinType(_) => (),
+        }
+    }
+    for imp in module.impls(db) {
+        dependencies.ty(&imp.target_ty(db));
+        for function in imp.functions(db) {
+            dependencies.function(function);
+        }
+    }
+
+    dependencies.files.remove(&file_id);
+    Arc::new(dependencies.files.into_iter().collect())
+}
+
+/// Collects the files that define the functions and structs that are used by a file.
+struct Dependencies<'a> {
+    db: &'a dyn HirDatabase,
+    files: BTreeSet<FileId>,
+}
+
+impl Dependencies<'_> {
+    fn function(&mut self, function: Function) {
+        if let Some(sig) = function.ty(self.db).callable_sig(self.db) {
+            for ty in sig.params().iter().chain(std::iter::once(sig.ret())) {
+                self.ty(ty);
+            }
+        }
+
+        let infer = function.infer(self.db);
+        let expr_types = infer.type_of_expr.iter().map(|(_, ty)| ty);
+        let pat_types = infer.type_of_pat.iter().map(|(_, ty)| ty);
+        for ty in expr_types.chain(pat_types) {
+            self.ty(ty);
+        }
+        for operator in infer.operator_resolutions.values() {
+            self.callable(CallableDef::Function(*operator));
+        }
+    }
+
+    fn ty(&mut self, ty: &Ty) {
+        if let Ty::Apply(a_ty) = ty {
+            match a_ty.ctor {
+                TypeCtor::Struct(s) => self.callable(CallableDef::Struct(s)),
+                TypeCtor::FnDef(def) => self.callable(def),
+                _ => (),
+            }
+            for parameter in a_ty.parameters.iter() {
+                self.ty(parameter);
+            }
+        }
+    }
+
+    fn callable(&mut self, def: CallableDef) {
+        let module = match def {
+            CallableDef::Function(function) => function.module(self.db.upcast()),
+            CallableDef::Struct(s) => s.module(self.db),
+        };
+        self.files.insert(module.file_id());
+    }
+}
@@ -1,7 +1,13 @@
-use crate::adt::StructKind;
+use crate::adt::{DeriveTrait, StructKind};
+use crate::error_codes::{self, ErrorCode};
 use crate::in_file::InFile;
-use crate::{FileId, HirDatabase, IntTy, Name, Ty};
-use mun_syntax::{ast, AstPtr, SmolStr, SyntaxNode, SyntaxNodePtr, TextRange};
+use crate::lint::{self, Lint};
+use crate::{FileId, HirDatabase, IntTy, MathIntrinsic, Name, Ty, TypeCtor};
+pub use mun_syntax::TextEdit;
+use mun_syntax::{
+    ast::{self, edit, make},
+    AstNode, AstPtr, SmolStr, SyntaxNode, SyntaxNodePtr, TextRange,
+};
 use std::{any::Any, fmt};
 
 /// Diagnostic defines hir API for errors and warnings.
@@ -19,9 +25,78 @@ pub trait Diagnostic: Any + Send + Sync + fmt::Debug + 'static {
     fn highlight_range(&self) -> TextRange {
         self.source().value.range()
     }
+    /// Returns whether the diagnostic is an error or a warning. Only errors prevent compilation.
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    /// Returns the lint that reported the diagnostic, if any.
+    fn lint(&self) -> Option<&'static Lint> {
+        None
+    }
+    /// Returns the error code that identifies the kind of the diagnostic. Diagnostics that are
+    /// reported by a lint are identified by the lint instead.
+    fn code(&self) -> Option<&'static ErrorCode> {
+        None
+    }
+    /// Returns additional locations in the same file that explain the diagnostic, e.g. the
+    /// declaration that caused an expected type.
+    fn secondary_labels(&self) -> Vec<DiagnosticLabel> {
+        Vec::new()
+    }
+    /// Returns free-form notes that provide more information about the diagnostic.
+    fn notes(&self) -> Vec<String> {
+        Vec::new()
+    }
+    /// Returns a change of the source code that resolves the diagnostic, if one is known.
+    fn fix(&self, _db: &dyn HirDatabase) -> Option<SourceChange> {
+        None
+    }
     fn as_any(&self) -> &(dyn Any + Send + 'static);
 }
 
+/// A secondary location of a `Diagnostic` together with a message that describes it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticLabel {
+    pub range: TextRange,
+    pub message: String,
+}
+
+/// A machine-applicable change of a file that resolves a `Diagnostic`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceChange {
+    /// A short description of the change, e.g. "add missing fields"
+    pub label: String,
+    pub file_id: FileId,
+    pub edits: Vec<TextEdit>,
+}
+
+/// Applies the edits to `text`. Edits that overlap with an earlier edit are skipped.
+pub fn apply_edits<'a>(text: &str, edits: impl IntoIterator<Item = &'a TextEdit>) -> String {
+    let mut edits: Vec<&TextEdit> = edits.into_iter().collect();
+    edits.sort_by_key(|edit| (edit.range.start(), edit.range.end()));
+
+    let mut result = String::with_capacity(text.len());
+    let mut offset = 0;
+    for edit in edits {
+        let start = edit.range.start().to_usize();
+        if start < offset {
+            continue;
+        }
+        result.push_str(&text[offset..start]);
+        result.push_str(&edit.replace_with);
+        offset = edit.range.end().to_usize();
+    }
+    result.push_str(&text[offset..]);
+    result
+}
+
+/// The severity of a `Diagnostic`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
 pub trait AstDiagnostic {
     type AST;
     fn ast(&self, db: &dyn HirDatabase) -> Self::AST;
@@ -88,6 +163,10 @@ impl Diagnostic for UnresolvedValue {
         "undefined value".to_string()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0002)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.expr)
     }
@@ -108,6 +187,63 @@ impl Diagnostic for UnresolvedType {
         "undefined type".to_string()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0003)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.type_ref.syntax_node_ptr())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct TypeArgsCountMismatch {
+    pub file: FileId,
+    pub type_ref: AstPtr<ast::TypeRef>,
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl Diagnostic for TypeArgsCountMismatch {
+    fn message(&self) -> String {
+        format!(
+            "this type takes {} type arguments but {} type arguments were supplied",
+            self.expected, self.found
+        )
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0004)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.type_ref.syntax_node_ptr())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidAtomicType {
+    pub file: FileId,
+    pub type_ref: AstPtr<ast::TypeRef>,
+}
+
+impl Diagnostic for InvalidAtomicType {
+    fn message(&self) -> String {
+        "only `i32` and `u64` can be used as the type of an `Atomic`".to_string()
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0005)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.type_ref.syntax_node_ptr())
     }
@@ -129,6 +265,10 @@ impl Diagnostic for ExpectedFunction {
         "expected function type".to_string()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0006)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.expr)
     }
@@ -154,6 +294,10 @@ impl Diagnostic for ParameterCountMismatch {
         )
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0007)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.expr)
     }
@@ -169,6 +313,8 @@ pub struct MismatchedType {
     pub expr: SyntaxNodePtr,
     pub expected: Ty,
     pub found: Ty,
+    /// The return type of the function, if the expected type originates from it
+    pub return_type: Option<SyntaxNodePtr>,
 }
 
 impl Diagnostic for MismatchedType {
@@ -176,10 +322,41 @@ impl Diagnostic for MismatchedType {
         "mismatched type".to_string()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0008)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.expr)
     }
 
+    fn secondary_labels(&self) -> Vec<DiagnosticLabel> {
+        self.return_type
+            .iter()
+            .map(|ptr| DiagnosticLabel {
+                range: ptr.range(),
+                message: "expected because of this return type".to_string(),
+            })
+            .collect()
+    }
+
+    fn fix(&self, db: &dyn HirDatabase) -> Option<SourceChange> {
+        // An assignment where a `bool` is expected, e.g. `if a = b`, is likely meant as comparison
+        if self.expected != Ty::simple(TypeCtor::Bool) {
+            return None;
+        }
+        let root = db.parse(self.file).syntax_node();
+        let (op_token, op) = ast::BinExpr::cast(self.expr.to_node(&root))?.op_details()?;
+        if op != ast::BinOp::Assign {
+            return None;
+        }
+        Some(SourceChange {
+            label: "change `=` to `==`".to_string(),
+            file_id: self.file,
+            edits: vec![TextEdit::replace(op_token.text_range(), "==".to_string())],
+        })
+    }
+
     fn as_any(&self) -> &(dyn Any + Send + 'static) {
         self
     }
@@ -198,6 +375,10 @@ impl Diagnostic for IncompatibleBranch {
         "mismatched branches".to_string()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0009)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.if_expr)
     }
@@ -224,6 +405,10 @@ impl Diagnostic for InvalidLHS {
         "invalid left hand side of expression".to_string()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0010)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.lhs)
     }
@@ -245,10 +430,18 @@ impl Diagnostic for MissingElseBranch {
         "missing else branch".to_string()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0011)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.if_expr)
     }
 
+    fn notes(&self) -> Vec<String> {
+        vec!["an `if` without an `else` branch does not evaluate to a value".to_string()]
+    }
+
     fn as_any(&self) -> &(dyn Any + Send + 'static) {
         self
     }
@@ -267,6 +460,10 @@ impl Diagnostic for CannotApplyBinaryOp {
         "cannot apply binary operator".to_string()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0012)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.expr)
     }
@@ -288,6 +485,46 @@ impl Diagnostic for CannotApplyUnaryOp {
         "cannot apply unary operator".to_string()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0013)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidIntrinsicArgument {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub intrinsic: MathIntrinsic,
+    pub ty: Ty,
+}
+
+impl Diagnostic for InvalidIntrinsicArgument {
+    fn message(&self) -> String {
+        if self.intrinsic.is_float_only() {
+            format!(
+                "`core::{}` expects floating point arguments",
+                self.intrinsic.name()
+            )
+        } else {
+            format!(
+                "`core::{}` expects numeric arguments",
+                self.intrinsic.name()
+            )
+        }
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0014)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.expr)
     }
@@ -310,6 +547,10 @@ impl Diagnostic for DuplicateDefinition {
         format!("the name `{}` is defined multiple times", self.name)
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0015)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.definition)
     }
@@ -330,6 +571,10 @@ impl Diagnostic for ReturnMissingExpression {
         "`return;` in a function whose return type is not `()`".to_owned()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0016)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.return_expr)
     }
@@ -339,6 +584,30 @@ impl Diagnostic for ReturnMissingExpression {
     }
 }
 
+#[derive(Debug)]
+pub struct PanicMessageNotALiteral {
+    pub file: FileId,
+    pub message_expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for PanicMessageNotALiteral {
+    fn message(&self) -> String {
+        "panic message must be a string literal".to_owned()
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0017)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.message_expr)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct BreakOutsideLoop {
     pub file: FileId,
@@ -350,6 +619,10 @@ impl Diagnostic for BreakOutsideLoop {
         "`break` outside of a loop".to_owned()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0018)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.break_expr)
     }
@@ -359,6 +632,55 @@ impl Diagnostic for BreakOutsideLoop {
     }
 }
 
+#[derive(Debug)]
+pub struct ContinueOutsideLoop {
+    pub file: FileId,
+    pub continue_expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for ContinueOutsideLoop {
+    fn message(&self) -> String {
+        "`continue` outside of a loop".to_owned()
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0019)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.continue_expr)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct UndeclaredLabel {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub label: Name,
+}
+
+impl Diagnostic for UndeclaredLabel {
+    fn message(&self) -> String {
+        format!("use of undeclared label `{}`", self.label)
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0020)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct BreakWithValueOutsideLoop {
     pub file: FileId,
@@ -370,6 +692,10 @@ impl Diagnostic for BreakWithValueOutsideLoop {
         "`break` with value can only appear in a `loop`".to_owned()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0021)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.break_expr)
     }
@@ -392,6 +718,62 @@ impl Diagnostic for AccessUnknownField {
         "attempted to access a non-existent field in a struct.".to_string()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0022)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct NoSuchMethod {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub receiver_ty: Ty,
+    pub name: Name,
+}
+
+impl Diagnostic for NoSuchMethod {
+    fn message(&self) -> String {
+        format!("no method named `{}` found for this type", self.name)
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0023)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidCast {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub expr_ty: Ty,
+    pub cast_ty: Ty,
+}
+
+impl Diagnostic for InvalidCast {
+    fn message(&self) -> String {
+        "invalid cast, only primitive types can be cast to and from `any`".to_string()
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0024)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.expr)
     }
@@ -420,6 +802,10 @@ impl Diagnostic for FieldCountMismatch {
         )
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0025)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.expr)
     }
@@ -434,6 +820,8 @@ pub struct MissingFields {
     pub file: FileId,
     pub fields: SyntaxNodePtr,
     pub field_names: Vec<Name>,
+    /// The types of the fields in `field_names`
+    pub field_types: Vec<Ty>,
 }
 
 impl Diagnostic for MissingFields {
@@ -446,10 +834,38 @@ impl Diagnostic for MissingFields {
         message
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0026)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.fields)
     }
 
+    fn fix(&self, db: &dyn HirDatabase) -> Option<SourceChange> {
+        let root = db.parse(self.file).syntax_node();
+        let field_list = ast::RecordLit::cast(self.fields.to_node(&root))?.record_field_list()?;
+        let missing_fields = self
+            .field_names
+            .iter()
+            .zip(self.field_types.iter())
+            .map(|(name, ty)| {
+                Some(make::record_field(
+                    make::name_ref(&name.to_string()),
+                    Some(make::expr_from_text(default_value(ty)?)),
+                ))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let new_field_list = field_list.append_fields(missing_fields);
+        let edit = edit::text_edit(&field_list, &new_field_list);
+        Some(SourceChange {
+            label: "add missing fields".to_string(),
+            file_id: self.file,
+            edits: vec![edit],
+        })
+    }
+
     fn as_any(&self) -> &(dyn Any + Send + 'static) {
         self
     }
@@ -471,6 +887,10 @@ impl Diagnostic for MismatchedStructLit {
         )
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0027)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.expr)
     }
@@ -492,6 +912,10 @@ impl Diagnostic for NoFields {
         "attempted to access a field on a primitive type.".to_string()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0028)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.receiver_expr)
     }
@@ -511,6 +935,10 @@ impl Diagnostic for NoSuchField {
         "no such field".to_string()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0029)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.field)
     }
@@ -531,6 +959,10 @@ impl Diagnostic for PossiblyUninitializedVariable {
         "use of possibly-uninitialized variable".to_string()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0030)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         InFile::new(self.file, self.pat)
     }
@@ -550,6 +982,10 @@ impl Diagnostic for ExternCannotHaveBody {
         "extern functions cannot have bodies".to_string()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0031)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         self.func
     }
@@ -569,6 +1005,10 @@ impl Diagnostic for ExternNonPrimitiveParam {
         "extern functions can only have primitives as parameter- and return types".to_string()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0032)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         self.param
     }
@@ -578,19 +1018,24 @@ impl Diagnostic for ExternNonPrimitiveParam {
     }
 }
 
-/// An error that is emitted if a literal is too large to even parse
+/// An error that is emitted if an atomic value is stored anywhere else than in a field of a `gc`
+/// struct, e.g. in a local binding
 #[derive(Debug)]
-pub struct IntLiteralTooLarge {
-    pub literal: InFile<AstPtr<ast::Literal>>,
+pub struct AtomicOutsideGcStruct {
+    pub ptr: InFile<SyntaxNodePtr>,
 }
 
-impl Diagnostic for IntLiteralTooLarge {
+impl Diagnostic for AtomicOutsideGcStruct {
     fn message(&self) -> String {
-        "int literal is too large".to_owned()
+        "atomic values can only be stored in fields of `gc` structs".to_string()
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0033)
     }
 
     fn source(&self) -> InFile<SyntaxNodePtr> {
-        self.literal.map(|ptr| ptr.into())
+        self.ptr
     }
 
     fn as_any(&self) -> &(dyn Any + Send + 'static) {
@@ -598,20 +1043,24 @@ impl Diagnostic for IntLiteralTooLarge {
     }
 }
 
-/// An error that is emitted if a literal is too large for its suffix
+/// An error that is emitted if an atomic value is used other than by calling one of its methods
 #[derive(Debug)]
-pub struct LiteralOutOfRange {
-    pub literal: InFile<AstPtr<ast::Literal>>,
-    pub int_ty: IntTy,
+pub struct AtomicValueCopied {
+    pub expr: InFile<SyntaxNodePtr>,
 }
 
-impl Diagnostic for LiteralOutOfRange {
+impl Diagnostic for AtomicValueCopied {
     fn message(&self) -> String {
-        format!("literal out of range for `{}`", self.int_ty.as_str())
+        "atomic values cannot be copied, use `load`, `store` or `fetch_add` to access the value"
+            .to_string()
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0034)
     }
 
     fn source(&self) -> InFile<SyntaxNodePtr> {
-        self.literal.map(|ptr| ptr.into())
+        self.expr
     }
 
     fn as_any(&self) -> &(dyn Any + Send + 'static) {
@@ -619,19 +1068,72 @@ impl Diagnostic for LiteralOutOfRange {
     }
 }
 
-/// An error that is emitted for a literal with an invalid suffix (e.g. `123_foo`)
+/// An error that is emitted if a literal is too large to even parse
 #[derive(Debug)]
-pub struct InvalidLiteralSuffix {
+pub struct IntLiteralTooLarge {
     pub literal: InFile<AstPtr<ast::Literal>>,
-    pub suffix: SmolStr,
 }
 
-impl Diagnostic for InvalidLiteralSuffix {
+impl Diagnostic for IntLiteralTooLarge {
     fn message(&self) -> String {
-        format!("invalid suffix `{}`", self.suffix)
+        "int literal is too large".to_owned()
     }
 
-    fn source(&self) -> InFile<SyntaxNodePtr> {
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0035)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        self.literal.map(|ptr| ptr.into())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// An error that is emitted if a literal is too large for its suffix
+#[derive(Debug)]
+pub struct LiteralOutOfRange {
+    pub literal: InFile<AstPtr<ast::Literal>>,
+    pub int_ty: IntTy,
+}
+
+impl Diagnostic for LiteralOutOfRange {
+    fn message(&self) -> String {
+        format!("literal out of range for `{}`", self.int_ty.as_str())
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0036)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        self.literal.map(|ptr| ptr.into())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// An error that is emitted for a literal with an invalid suffix (e.g. `123_foo`)
+#[derive(Debug)]
+pub struct InvalidLiteralSuffix {
+    pub literal: InFile<AstPtr<ast::Literal>>,
+    pub suffix: SmolStr,
+}
+
+impl Diagnostic for InvalidLiteralSuffix {
+    fn message(&self) -> String {
+        format!("invalid suffix `{}`", self.suffix)
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0037)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
         self.literal.map(|ptr| ptr.into())
     }
 
@@ -658,6 +1160,10 @@ impl Diagnostic for InvalidFloatingPointLiteral {
         }
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0038)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         self.literal.map(|ptr| ptr.into())
     }
@@ -678,6 +1184,10 @@ impl Diagnostic for InvalidLiteral {
         "invalid literal value".to_owned()
     }
 
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0039)
+    }
+
     fn source(&self) -> InFile<SyntaxNodePtr> {
         self.literal.map(|ptr| ptr.into())
     }
@@ -686,3 +1196,580 @@ impl Diagnostic for InvalidLiteral {
         self
     }
 }
+
+/// An error that is emitted for a character literal that does not contain exactly one character
+/// (e.g. `''` or `'ab'`)
+#[derive(Debug)]
+pub struct InvalidCharLiteral {
+    pub literal: InFile<AstPtr<ast::Literal>>,
+    pub len: usize,
+}
+
+impl Diagnostic for InvalidCharLiteral {
+    fn message(&self) -> String {
+        if self.len == 0 {
+            "empty character literal".to_owned()
+        } else {
+            "character literal may only contain one codepoint".to_owned()
+        }
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0040)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        self.literal.map(|ptr| ptr.into())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// An error that is emitted for an invalid escape sequence in a string or character literal (e.g.
+/// `"\q"` or `'\u{110000}'`)
+#[derive(Debug)]
+pub struct InvalidEscapeSequence {
+    pub literal: InFile<AstPtr<ast::Literal>>,
+    /// The range of the escape sequence relative to the start of the literal
+    pub range: TextRange,
+}
+
+impl Diagnostic for InvalidEscapeSequence {
+    fn message(&self) -> String {
+        "invalid escape sequence".to_owned()
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0041)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        self.literal.map(|ptr| ptr.into())
+    }
+
+    fn highlight_range(&self) -> TextRange {
+        let literal_start = self.source().value.range().start();
+        TextRange::offset_len(literal_start + self.range.start(), self.range.len())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownOperatorTrait {
+    pub file: FileId,
+    pub trait_ref: AstPtr<ast::TypeRef>,
+    pub name: String,
+}
+
+impl Diagnostic for UnknownOperatorTrait {
+    fn message(&self) -> String {
+        format!(
+            "unknown operator trait `{}`, expected one of `Add`, `Sub`, `Mul`, `Div` or `Rem`",
+            self.name
+        )
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0044)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.trait_ref.syntax_node_ptr())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidOperatorImplTarget {
+    pub file: FileId,
+    pub type_ref: AstPtr<ast::TypeRef>,
+}
+
+impl Diagnostic for InvalidOperatorImplTarget {
+    fn message(&self) -> String {
+        "operators can only be implemented for structs".to_owned()
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0045)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.type_ref.syntax_node_ptr())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct MissingOperatorMethod {
+    pub file: FileId,
+    pub impl_def: AstPtr<ast::ImplDef>,
+    pub method: String,
+}
+
+impl Diagnostic for MissingOperatorMethod {
+    fn message(&self) -> String {
+        format!("missing operator method `{}`", self.method)
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0046)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.impl_def.syntax_node_ptr())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidOperatorSignature {
+    pub file: FileId,
+    pub function: AstPtr<ast::FunctionDef>,
+    pub method: String,
+    pub ty: String,
+}
+
+impl Diagnostic for InvalidOperatorSignature {
+    fn message(&self) -> String {
+        format!(
+            "operator method `{}` must take two parameters, the first of type `{}`",
+            self.method, self.ty
+        )
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0047)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.function.syntax_node_ptr())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownDerive {
+    pub file: FileId,
+    pub attr: AstPtr<ast::Attr>,
+    pub name: String,
+}
+
+impl Diagnostic for UnknownDerive {
+    fn message(&self) -> String {
+        format!("cannot derive `{}`, expected `Eq` or `Ord`", self.name)
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0048)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.attr.syntax_node_ptr())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidDeriveField {
+    pub file: FileId,
+    pub type_ref: AstPtr<ast::TypeRef>,
+    pub derive: DeriveTrait,
+    pub ty: String,
+}
+
+impl Diagnostic for InvalidDeriveField {
+    fn message(&self) -> String {
+        format!(
+            "cannot derive `{}` because a field of type `{}` does not implement `{}`",
+            self.derive, self.ty, self.derive
+        )
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0049)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.type_ref.syntax_node_ptr())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// An error that is emitted if a value struct contains itself, either directly or through other
+/// value structs, which would give it an infinite size.
+#[derive(Debug)]
+pub struct RecursiveValueStruct {
+    pub file: FileId,
+    pub type_ref: AstPtr<ast::TypeRef>,
+    pub name: String,
+}
+
+impl Diagnostic for RecursiveValueStruct {
+    fn message(&self) -> String {
+        format!("recursive value struct `{}` has infinite size", self.name)
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0050)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.type_ref.syntax_node_ptr())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidInlineAttribute {
+    pub file: FileId,
+    pub attr: AstPtr<ast::Attr>,
+}
+
+impl Diagnostic for InvalidInlineAttribute {
+    fn message(&self) -> String {
+        "invalid argument, expected `#[inline]`, `#[inline(always)]` or `#[inline(never)]`"
+            .to_string()
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0051)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.attr.syntax_node_ptr())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidExportAttribute {
+    pub file: FileId,
+    pub attr: AstPtr<ast::Attr>,
+}
+
+impl Diagnostic for InvalidExportAttribute {
+    fn message(&self) -> String {
+        "invalid export attribute, expected `#[export(name = \"...\")]`".to_string()
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0052)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.attr.syntax_node_ptr())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct PrivateExport {
+    pub file: FileId,
+    pub attr: AstPtr<ast::Attr>,
+}
+
+impl Diagnostic for PrivateExport {
+    fn message(&self) -> String {
+        "only public functions can be exported".to_string()
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0053)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.attr.syntax_node_ptr())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidTestFunction {
+    pub file: FileId,
+    pub attr: AstPtr<ast::Attr>,
+    pub attr_name: Name,
+}
+
+impl Diagnostic for InvalidTestFunction {
+    fn message(&self) -> String {
+        format!(
+            "`#[{}]` functions cannot be extern, associated, take parameters or return a value",
+            self.attr_name
+        )
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0054)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.attr.syntax_node_ptr())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct DuplicateExportName {
+    pub file: FileId,
+    pub name: String,
+    pub definition: SyntaxNodePtr,
+}
+
+impl Diagnostic for DuplicateExportName {
+    fn message(&self) -> String {
+        format!("the export name `{}` is used multiple times", self.name)
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0055)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.definition)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct UnusedVariable {
+    pub file: FileId,
+    pub pat: SyntaxNodePtr,
+    pub name: Name,
+    pub severity: Severity,
+}
+
+impl Diagnostic for UnusedVariable {
+    fn message(&self) -> String {
+        format!("unused variable: `{}`", self.name)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.pat)
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn lint(&self) -> Option<&'static Lint> {
+        Some(&lint::UNUSED_VARIABLES)
+    }
+
+    fn fix(&self, _db: &dyn HirDatabase) -> Option<SourceChange> {
+        Some(SourceChange {
+            label: format!("rename to `_{}`", self.name),
+            file_id: self.file,
+            edits: vec![TextEdit::insert(self.pat.range().start(), "_".to_string())],
+        })
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct UnusedFunction {
+    pub file: FileId,
+    pub fn_name: SyntaxNodePtr,
+    pub name: Name,
+    pub severity: Severity,
+}
+
+impl Diagnostic for UnusedFunction {
+    fn message(&self) -> String {
+        format!("function `{}` is never used", self.name)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.fn_name)
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn lint(&self) -> Option<&'static Lint> {
+        Some(&lint::DEAD_CODE)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct ShadowedBinding {
+    pub file: FileId,
+    pub pat: SyntaxNodePtr,
+    pub name: Name,
+    pub severity: Severity,
+}
+
+impl Diagnostic for ShadowedBinding {
+    fn message(&self) -> String {
+        format!("`{}` shadows an earlier binding", self.name)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.pat)
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn lint(&self) -> Option<&'static Lint> {
+        Some(&lint::SHADOWING)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct SelfComparison {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub name: Name,
+    pub severity: Severity,
+}
+
+impl Diagnostic for SelfComparison {
+    fn message(&self) -> String {
+        format!(
+            "comparing `{}` with itself always has the same result",
+            self.name
+        )
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr)
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn lint(&self) -> Option<&'static Lint> {
+        Some(&lint::SELF_COMPARISON)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A warning that is emitted for code that is never executed, because it follows an expression
+/// that never finishes.
+#[derive(Debug)]
+pub struct UnreachableCode {
+    pub file: FileId,
+    pub code: SyntaxNodePtr,
+    pub severity: Severity,
+}
+
+impl Diagnostic for UnreachableCode {
+    fn message(&self) -> String {
+        "unreachable code".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.code)
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn lint(&self) -> Option<&'static Lint> {
+        Some(&lint::UNREACHABLE_CODE)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownLint {
+    pub file: FileId,
+    pub attr: AstPtr<ast::Attr>,
+    pub name: Name,
+}
+
+impl Diagnostic for UnknownLint {
+    fn message(&self) -> String {
+        format!("unknown lint: `{}`", self.name)
+    }
+
+    fn code(&self) -> Option<&'static ErrorCode> {
+        Some(&error_codes::E0056)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.attr.syntax_node_ptr())
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// Returns the source of a value of the specified type that can be used as a placeholder, if there
+/// is one.
+pub(crate) fn default_value(ty: &Ty) -> Option<&'static str> {
+    match ty.as_simple()? {
+        TypeCtor::Bool => Some("false"),
+        TypeCtor::Int(_) => Some("0"),
+        TypeCtor::Float(_) => Some("0.0"),
+        _ => None,
+    }
+}
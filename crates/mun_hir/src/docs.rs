@@ -0,0 +1,40 @@
+use crate::code_model::src::HasSource;
+use crate::code_model::StructField;
+use crate::{DefDatabase, Function, Impl, Struct};
+use mun_syntax::ast::DocCommentsOwner;
+use std::sync::Arc;
+
+/// The definitions that can be documented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DocDef {
+    Function(Function),
+    Struct(Struct),
+    StructField(StructField),
+    Impl(Impl),
+}
+impl_froms!(DocDef: Function, Struct, StructField, Impl);
+
+/// The documentation of an item, collected from its doc comments (e.g. `/// ...`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Documentation(Arc<str>);
+
+impl Documentation {
+    fn new(text: &str) -> Documentation {
+        Documentation(text.into())
+    }
+
+    /// Returns the text of the documentation.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub(crate) fn documentation_query(db: &dyn DefDatabase, def: DocDef) -> Option<Documentation> {
+        let text = match def {
+            DocDef::Function(it) => it.source(db).value.doc_comment_text(),
+            DocDef::Struct(it) => it.source(db).value.doc_comment_text(),
+            DocDef::StructField(it) => it.source(db).value.doc_comment_text(),
+            DocDef::Impl(it) => it.source(db).value.doc_comment_text(),
+        };
+        text.map(|text| Documentation::new(&text))
+    }
+}
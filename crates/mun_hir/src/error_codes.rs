@@ -0,0 +1,228 @@
+//! Error codes identify the kinds of diagnostics that are not reported by a lint, e.g. `E0002`.
+//!
+//! Every error code has an extended explanation with examples, which `mun explain` prints. The
+//! explanations are stored as Markdown files in the `error_codes` directory. Codes are never
+//! reused, so they remain stable across releases.
+
+use crate::lint::find_lint;
+
+/// An error code that identifies a kind of diagnostic.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ErrorCode {
+    /// The code as shown in diagnostics, e.g. `E0002`
+    pub code: &'static str,
+    /// An extended description of the diagnostic with examples, formatted as Markdown
+    pub explanation: &'static str,
+}
+
+macro_rules! declare_error_codes {
+    ($($(#[$attr:meta])* $ident:ident;)*) => {
+        $(
+            $(#[$attr])*
+            pub static $ident: ErrorCode = ErrorCode {
+                code: stringify!($ident),
+                explanation: include_str!(concat!("error_codes/", stringify!($ident), ".md")),
+            };
+        )*
+
+        /// All error codes that are known to the compiler.
+        pub static ERROR_CODES: &[&ErrorCode] = &[$(&$ident),*];
+    };
+}
+
+declare_error_codes! {
+    /// A syntax error
+    E0001;
+    /// `UnresolvedValue`
+    E0002;
+    /// `UnresolvedType`
+    E0003;
+    /// `TypeArgsCountMismatch`
+    E0004;
+    /// `InvalidAtomicType`
+    E0005;
+    /// `ExpectedFunction`
+    E0006;
+    /// `ParameterCountMismatch`
+    E0007;
+    /// `MismatchedType`
+    E0008;
+    /// `IncompatibleBranch`
+    E0009;
+    /// `InvalidLHS`
+    E0010;
+    /// `MissingElseBranch`
+    E0011;
+    /// `CannotApplyBinaryOp`
+    E0012;
+    /// `CannotApplyUnaryOp`
+    E0013;
+    /// `InvalidIntrinsicArgument`
+    E0014;
+    /// `DuplicateDefinition`
+    E0015;
+    /// `ReturnMissingExpression`
+    E0016;
+    /// `PanicMessageNotALiteral`
+    E0017;
+    /// `BreakOutsideLoop`
+    E0018;
+    /// `ContinueOutsideLoop`
+    E0019;
+    /// `UndeclaredLabel`
+    E0020;
+    /// `BreakWithValueOutsideLoop`
+    E0021;
+    /// `AccessUnknownField`
+    E0022;
+    /// `NoSuchMethod`
+    E0023;
+    /// `InvalidCast`
+    E0024;
+    /// `FieldCountMismatch`
+    E0025;
+    /// `MissingFields`
+    E0026;
+    /// `MismatchedStructLit`
+    E0027;
+    /// `NoFields`
+    E0028;
+    /// `NoSuchField`
+    E0029;
+    /// `PossiblyUninitializedVariable`
+    E0030;
+    /// `ExternCannotHaveBody`
+    E0031;
+    /// `ExternNonPrimitiveParam`
+    E0032;
+    /// `AtomicOutsideGcStruct`
+    E0033;
+    /// `AtomicValueCopied`
+    E0034;
+    /// `IntLiteralTooLarge`
+    E0035;
+    /// `LiteralOutOfRange`
+    E0036;
+    /// `InvalidLiteralSuffix`
+    E0037;
+    /// `InvalidFloatingPointLiteral`
+    E0038;
+    /// `InvalidLiteral`
+    E0039;
+    /// `InvalidCharLiteral`
+    E0040;
+    /// `InvalidEscapeSequence`
+    E0041;
+    /// `UnknownOperatorTrait`
+    E0044;
+    /// `InvalidOperatorImplTarget`
+    E0045;
+    /// `MissingOperatorMethod`
+    E0046;
+    /// `InvalidOperatorSignature`
+    E0047;
+    /// `UnknownDerive`
+    E0048;
+    /// `InvalidDeriveField`
+    E0049;
+    /// `RecursiveValueStruct`
+    E0050;
+    /// `InvalidInlineAttribute`
+    E0051;
+    /// `InvalidExportAttribute`
+    E0052;
+    /// `PrivateExport`
+    E0053;
+    /// `InvalidTestFunction`
+    E0054;
+    /// `DuplicateExportName`
+    E0055;
+    /// `UnknownLint`
+    E0056;
+}
+
+/// Returns the error code with the specified code, ignoring case, e.g. `E0002` or `e0002`.
+pub fn find_error_code(code: &str) -> Option<&'static ErrorCode> {
+    ERROR_CODES
+        .iter()
+        .copied()
+        .find(|error_code| error_code.code.eq_ignore_ascii_case(code))
+}
+
+/// Returns the extended explanation of an error code or the description of a lint, e.g. for
+/// `E0002` or `unused_variables`.
+pub fn explain(code_or_lint: &str) -> Option<String> {
+    if let Some(error_code) = find_error_code(code_or_lint) {
+        return Some(error_code.explanation.to_owned());
+    }
+    find_lint(code_or_lint).map(|lint| {
+        format!(
+            "The `{}` lint {}. Its default level is `{}`, which can be changed with \
+             `#[allow({})]`, `#[warn({})]` or `#[deny({})]`.\n",
+            lint.name, lint.description, lint.default_level, lint.name, lint.name, lint.name
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{explain, find_error_code, ERROR_CODES};
+    use crate::diagnostics::{DiagnosticSink, Severity};
+    use crate::mock::MockDatabase;
+    use crate::{Module, SourceDatabase};
+
+    /// Returns the codes of the errors in `text`, and the codes of its warnings that are not
+    /// reported by a lint.
+    fn codes(text: &str) -> Vec<&'static str> {
+        let (db, file_id) = MockDatabase::with_single_file(text);
+        let mut codes = Vec::new();
+        if !db.parse(file_id).errors().is_empty() {
+            codes.push(super::E0001.code);
+        }
+        let mut sink = DiagnosticSink::new(|diag| match diag.code() {
+            Some(code) => codes.push(code.code),
+            None if diag.severity() == Severity::Error => codes.push("unknown"),
+            None => (),
+        });
+        Module::from(file_id).diagnostics(&db, &mut sink);
+        drop(sink);
+        codes
+    }
+
+    /// Tests that the first example of every explanation reports the error code and that the
+    /// second one, which fixes it, compiles.
+    #[test]
+    fn examples() {
+        for error_code in ERROR_CODES {
+            let examples: Vec<&str> = error_code
+                .explanation
+                .split("```mun\n")
+                .skip(1)
+                .map(|example| example.split("```").next().unwrap())
+                .collect();
+            assert_eq!(examples.len(), 2, "{}", error_code.code);
+            assert!(
+                codes(examples[0]).contains(&error_code.code),
+                "the example of {} does not report it",
+                error_code.code
+            );
+            assert_eq!(
+                codes(examples[1]),
+                Vec::<&str>::new(),
+                "the fixed example of {} does not compile",
+                error_code.code
+            );
+        }
+    }
+
+    #[test]
+    fn find() {
+        assert_eq!(find_error_code("E0002").unwrap().code, "E0002");
+        assert_eq!(find_error_code("e0002").unwrap().code, "E0002");
+        assert!(find_error_code("E9999").is_none());
+        assert!(explain("unused_variables")
+            .unwrap()
+            .contains("detects variables that are never used"));
+        assert!(explain("unknown").is_none());
+    }
+}
@@ -1,18 +1,19 @@
 use crate::{
     arena::map::ArenaMap,
     arena::{Arena, RawId},
+    builtin_type::MathIntrinsic,
     code_model::DefWithBody,
     FileId, HirDatabase, Name, Path,
 };
 
 //pub use mun_syntax::ast::PrefixOp as UnaryOp;
 use crate::code_model::src::HasSource;
-use crate::name::AsName;
+use crate::name::{name, AsName};
 use crate::type_ref::{TypeRef, TypeRefBuilder, TypeRefId, TypeRefMap, TypeRefSourceMap};
 use either::Either;
 pub use mun_syntax::ast::PrefixOp as UnaryOp;
 use mun_syntax::ast::{ArgListOwner, BinOp, LoopBodyOwner, NameOwner, TypeAscriptionOwner};
-use mun_syntax::{ast, AstNode, AstPtr, SmolStr, T};
+use mun_syntax::{ast, AstNode, AstPtr, SmolStr, TextRange, TextUnit, T};
 use rustc_hash::FxHashMap;
 use std::ops::Index;
 use std::sync::Arc;
@@ -39,6 +40,7 @@ impl_arena_id!(PatId);
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum ExprDiagnostic {
     LiteralError { expr: ExprId, err: LiteralError },
+    UndeclaredLabel { expr: ExprId, label: Name },
 }
 
 /// The body of an item (function, const etc.).
@@ -159,6 +161,10 @@ impl BodySourceMap {
         self.type_refs.type_ref_syntax(type_ref)
     }
 
+    pub(crate) fn type_ref_source_map(&self) -> &TypeRefSourceMap {
+        &self.type_refs
+    }
+
     pub(crate) fn syntax_expr(&self, ptr: ExprPtr) -> Option<ExprId> {
         self.expr_map.get(&ptr).cloned()
     }
@@ -203,6 +209,7 @@ pub enum Statement {
 #[derive(Debug, Clone, PartialEq)]
 pub enum Literal {
     String(String),
+    Char(char),
     Bool(bool),
     Int(LiteralInt),
     Float(LiteralFloat),
@@ -224,6 +231,14 @@ pub enum LiteralError {
 
     /// Trying to add floating point suffix to a literal that is not a floating point number
     NonDecimalFloat(u32),
+
+    /// A character literal does not contain exactly one character. Stores the number of characters
+    /// that were found.
+    InvalidCharLength(usize),
+
+    /// Encountered an invalid escape sequence in a string or character literal. Stores the range
+    /// of the escape sequence relative to the start of the literal.
+    InvalidEscape(TextRange),
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -260,6 +275,11 @@ pub enum Expr {
         callee: ExprId,
         args: Vec<ExprId>,
     },
+    MethodCall {
+        receiver: ExprId,
+        method_name: Name,
+        args: Vec<ExprId>,
+    },
     Path(Path),
     If {
         condition: ExprId,
@@ -270,6 +290,11 @@ pub enum Expr {
         expr: ExprId,
         op: UnaryOp,
     },
+    /// A cast of a value to another type, e.g. `value as any`
+    Cast {
+        expr: ExprId,
+        type_ref: TypeRefId,
+    },
     BinaryOp {
         lhs: ExprId,
         rhs: ExprId,
@@ -284,13 +309,19 @@ pub enum Expr {
     },
     Break {
         expr: Option<ExprId>,
+        label: Option<Name>,
+    },
+    Continue {
+        label: Option<Name>,
     },
     Loop {
         body: ExprId,
+        label: Option<Name>,
     },
     While {
         condition: ExprId,
         body: ExprId,
+        label: Option<Name>,
     },
     RecordLit {
         type_id: TypeRefId,
@@ -302,6 +333,29 @@ pub enum Expr {
         name: Name,
     },
     Literal(Literal),
+    /// A call to the `core::source_location()` intrinsic, which evaluates to the location of the
+    /// call in the source code.
+    SourceLocation,
+    /// A call to the `core::panic()` intrinsic, which aborts the execution of the current function
+    /// with an optional string literal `message`.
+    Panic {
+        message: Option<ExprId>,
+    },
+    /// A call to the `core::assert()` intrinsic, which panics if `condition` evaluates to `false`.
+    Assert {
+        condition: ExprId,
+        message: Option<ExprId>,
+    },
+    /// A call to the constructor of a builtin vector type with a value for every lane, e.g.
+    /// `float4(1.0, 2.0, 3.0, 4.0)`.
+    Vector {
+        elements: Vec<ExprId>,
+    },
+    /// A call to one of the math functions of the `core` module, e.g. `core::sqrt(x)`.
+    MathIntrinsic {
+        intrinsic: MathIntrinsic,
+        args: Vec<ExprId>,
+    },
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -370,14 +424,41 @@ impl Expr {
                     f(*arg);
                 }
             }
+            Expr::MethodCall { receiver, args, .. } => {
+                f(*receiver);
+                for arg in args {
+                    f(*arg);
+                }
+            }
             Expr::BinaryOp { lhs, rhs, .. } => {
                 f(*lhs);
                 f(*rhs);
             }
-            Expr::Field { expr, .. } | Expr::UnaryOp { expr, .. } => {
+            Expr::Field { expr, .. } | Expr::UnaryOp { expr, .. } | Expr::Cast { expr, .. } => {
                 f(*expr);
             }
-            Expr::Literal(_) => {}
+            Expr::Literal(_) | Expr::SourceLocation => {}
+            Expr::Panic { message } => {
+                if let Some(message) = message {
+                    f(*message);
+                }
+            }
+            Expr::Assert { condition, message } => {
+                f(*condition);
+                if let Some(message) = message {
+                    f(*message);
+                }
+            }
+            Expr::Vector { elements } => {
+                for element in elements {
+                    f(*element);
+                }
+            }
+            Expr::MathIntrinsic { args, .. } => {
+                for arg in args {
+                    f(*arg);
+                }
+            }
             Expr::If {
                 condition,
                 then_branch,
@@ -394,15 +475,18 @@ impl Expr {
                     f(*expr);
                 }
             }
-            Expr::Break { expr } => {
+            Expr::Break { expr, .. } => {
                 if let Some(expr) = expr {
                     f(*expr);
                 }
             }
-            Expr::Loop { body } => {
+            Expr::Continue { .. } => {}
+            Expr::Loop { body, .. } => {
                 f(*body);
             }
-            Expr::While { condition, body } => {
+            Expr::While {
+                condition, body, ..
+            } => {
                 f(*condition);
                 f(*body);
             }
@@ -445,6 +529,9 @@ pub(crate) struct ExprCollector<'a> {
     type_ref_builder: TypeRefBuilder,
     current_file_id: FileId,
     diagnostics: Vec<ExprDiagnostic>,
+    /// The labels of the loops that enclose the expression that is currently being collected, the
+    /// innermost loop is last.
+    loop_labels: Vec<Option<Name>>,
 }
 
 impl<'a> ExprCollector<'a> {
@@ -461,6 +548,7 @@ impl<'a> ExprCollector<'a> {
             type_ref_builder: TypeRefBuilder::default(),
             current_file_id: file_id,
             diagnostics: Vec::new(),
+            loop_labels: Vec::new(),
         }
     }
 
@@ -581,6 +669,7 @@ impl<'a> ExprCollector<'a> {
             ast::ExprKind::WhileExpr(expr) => self.collect_while(expr),
             ast::ExprKind::ReturnExpr(r) => self.collect_return(r),
             ast::ExprKind::BreakExpr(r) => self.collect_break(r),
+            ast::ExprKind::ContinueExpr(r) => self.collect_continue(r),
             ast::ExprKind::BlockExpr(b) => self.collect_block(b),
             ast::ExprKind::Literal(e) => match e.kind() {
                 ast::LiteralKind::Bool => {
@@ -612,8 +701,28 @@ impl<'a> ExprCollector<'a> {
                     expr_id
                 }
                 ast::LiteralKind::String => {
-                    let lit = Literal::String(Default::default());
-                    self.alloc_expr(Expr::Literal(lit), syntax_ptr)
+                    let (text, _) = e.text_and_suffix();
+                    let (lit, errors) = string_lit(&text);
+                    let expr_id = self.alloc_expr(Expr::Literal(lit), syntax_ptr);
+
+                    for err in errors {
+                        self.diagnostics
+                            .push(ExprDiagnostic::LiteralError { expr: expr_id, err })
+                    }
+
+                    expr_id
+                }
+                ast::LiteralKind::Char => {
+                    let (text, _) = e.text_and_suffix();
+                    let (lit, errors) = char_lit(&text);
+                    let expr_id = self.alloc_expr(Expr::Literal(lit), syntax_ptr);
+
+                    for err in errors {
+                        self.diagnostics
+                            .push(ExprDiagnostic::LiteralError { expr: expr_id, err })
+                    }
+
+                    expr_id
                 }
             },
             ast::ExprKind::PrefixExpr(e) => {
@@ -797,6 +906,13 @@ impl<'a> ExprCollector<'a> {
                 };
                 self.alloc_expr(Expr::Field { expr, name }, syntax_ptr)
             }
+            ast::ExprKind::CastExpr(e) => {
+                let expr = self.collect_expr_opt(e.expr());
+                let type_ref = self
+                    .type_ref_builder
+                    .alloc_from_node_opt(e.type_ref().as_ref());
+                self.alloc_expr(Expr::Cast { expr, type_ref }, syntax_ptr)
+            }
             ast::ExprKind::IfExpr(e) => {
                 let then_branch = self.collect_block_opt(e.then_branch());
 
@@ -826,6 +942,28 @@ impl<'a> ExprCollector<'a> {
                 self.source_map.expr_map.insert(src, inner);
                 inner
             }
+            ast::ExprKind::CallExpr(e) if is_source_location_call(&e) => {
+                self.alloc_expr(Expr::SourceLocation, syntax_ptr)
+            }
+            ast::ExprKind::CallExpr(e) if is_panic_call(&e) => {
+                let message = call_args(&e).next().map(|arg| self.collect_expr(arg));
+                self.alloc_expr(Expr::Panic { message }, syntax_ptr)
+            }
+            ast::ExprKind::CallExpr(e) if math_intrinsic(&e).is_some() => {
+                let intrinsic = math_intrinsic(&e).unwrap();
+                let args = call_args(&e).map(|arg| self.collect_expr(arg)).collect();
+                self.alloc_expr(Expr::MathIntrinsic { intrinsic, args }, syntax_ptr)
+            }
+            ast::ExprKind::CallExpr(e) if is_vector_constructor_call(&e) => {
+                let elements = call_args(&e).map(|arg| self.collect_expr(arg)).collect();
+                self.alloc_expr(Expr::Vector { elements }, syntax_ptr)
+            }
+            ast::ExprKind::CallExpr(e) if is_assert_call(&e) => {
+                let mut args = call_args(&e);
+                let condition = self.collect_expr_opt(args.next());
+                let message = args.next().map(|arg| self.collect_expr(arg));
+                self.alloc_expr(Expr::Assert { condition, message }, syntax_ptr)
+            }
             ast::ExprKind::CallExpr(e) => {
                 let callee = self.collect_expr_opt(e.expr());
                 let args = if let Some(arg_list) = e.arg_list() {
@@ -835,6 +973,26 @@ impl<'a> ExprCollector<'a> {
                 };
                 self.alloc_expr(Expr::Call { callee, args }, syntax_ptr)
             }
+            ast::ExprKind::MethodCallExpr(e) => {
+                let receiver = self.collect_expr_opt(e.expr());
+                let method_name = e
+                    .name_ref()
+                    .map(|nr| nr.as_name())
+                    .unwrap_or_else(Name::missing);
+                let args = if let Some(arg_list) = e.arg_list() {
+                    arg_list.args().map(|e| self.collect_expr(e)).collect()
+                } else {
+                    Vec::new()
+                };
+                self.alloc_expr(
+                    Expr::MethodCall {
+                        receiver,
+                        method_name,
+                        args,
+                    },
+                    syntax_ptr,
+                )
+            }
         }
     }
 
@@ -876,21 +1034,89 @@ impl<'a> ExprCollector<'a> {
 
     fn collect_break(&mut self, expr: ast::BreakExpr) -> ExprId {
         let syntax_node_ptr = AstPtr::new(&expr.clone().into());
+        let label = expr
+            .label_ident_token()
+            .map(|token| Name::new_label(token.text()));
         let expr = expr.expr().map(|e| self.collect_expr(e));
-        self.alloc_expr(Expr::Break { expr }, syntax_node_ptr)
+        let id = self.alloc_expr(
+            Expr::Break {
+                expr,
+                label: label.clone(),
+            },
+            syntax_node_ptr,
+        );
+        self.resolve_label(id, label);
+        id
+    }
+
+    fn collect_continue(&mut self, expr: ast::ContinueExpr) -> ExprId {
+        let syntax_node_ptr = AstPtr::new(&expr.clone().into());
+        let label = expr
+            .label_ident_token()
+            .map(|token| Name::new_label(token.text()));
+        let id = self.alloc_expr(
+            Expr::Continue {
+                label: label.clone(),
+            },
+            syntax_node_ptr,
+        );
+        self.resolve_label(id, label);
+        id
+    }
+
+    /// Verifies that the specified label of a `break` or `continue` expression refers to one of
+    /// the enclosing loops.
+    fn resolve_label(&mut self, expr: ExprId, label: Option<Name>) {
+        if let Some(label) = label {
+            if !self
+                .loop_labels
+                .iter()
+                .any(|it| it.as_ref() == Some(&label))
+            {
+                self.diagnostics
+                    .push(ExprDiagnostic::UndeclaredLabel { expr, label });
+            }
+        }
+    }
+
+    /// Collects the body of a loop with the specified label.
+    fn collect_loop_body<T>(&mut self, label: Option<Name>, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.loop_labels.push(label);
+        let result = f(self);
+        self.loop_labels.pop();
+        result
     }
 
     fn collect_loop(&mut self, expr: ast::LoopExpr) -> ExprId {
         let syntax_node_ptr = AstPtr::new(&expr.clone().into());
-        let body = self.collect_block_opt(expr.loop_body());
-        self.alloc_expr(Expr::Loop { body }, syntax_node_ptr)
+        let label = expr
+            .label()
+            .and_then(|label| label.label_ident_token())
+            .map(|token| Name::new_label(token.text()));
+        let body = self.collect_loop_body(label.clone(), |this| {
+            this.collect_block_opt(expr.loop_body())
+        });
+        self.alloc_expr(Expr::Loop { body, label }, syntax_node_ptr)
     }
 
     fn collect_while(&mut self, expr: ast::WhileExpr) -> ExprId {
         let syntax_node_ptr = AstPtr::new(&expr.clone().into());
+        let label = expr
+            .label()
+            .and_then(|label| label.label_ident_token())
+            .map(|token| Name::new_label(token.text()));
         let condition = self.collect_condition_opt(expr.condition());
-        let body = self.collect_block_opt(expr.loop_body());
-        self.alloc_expr(Expr::While { condition, body }, syntax_node_ptr)
+        let body = self.collect_loop_body(label.clone(), |this| {
+            this.collect_block_opt(expr.loop_body())
+        });
+        self.alloc_expr(
+            Expr::While {
+                condition,
+                body,
+                label,
+            },
+            syntax_node_ptr,
+        )
     }
 
     fn finish(mut self) -> (Body, BodySourceMap) {
@@ -1052,13 +1278,151 @@ fn integer_lit(str: &str, suffix: Option<&str>) -> (Literal, Vec<LiteralError>)
     (Literal::Int(LiteralInt { kind, value }), errors)
 }
 
+/// Parses the given string, including its surrounding quotes, into a string literal. Raw strings
+/// (e.g. `r#"..."#`) are returned verbatim, for other strings all escape sequences are replaced.
+fn string_lit(str: &str) -> (Literal, Vec<LiteralError>) {
+    let mut chars = str.chars();
+    if chars.next() == Some('r') {
+        let raw = chars.as_str();
+        let hashes = raw.chars().take_while(|&c| c == '#').count();
+        let quoted = &raw[hashes..];
+        let terminator = format!("\"{}", "#".repeat(hashes));
+        // An unterminated raw string is emitted as a single token by the lexer
+        if quoted.len() < 1 + terminator.len()
+            || !quoted.starts_with('"')
+            || !quoted.ends_with(&terminator)
+        {
+            return (
+                Literal::String(String::new()),
+                vec![LiteralError::LexerError],
+            );
+        }
+        let inner = &quoted[1..quoted.len() - terminator.len()];
+        return (Literal::String(inner.to_owned()), vec![]);
+    }
+
+    // An unterminated string is emitted as a single token by the lexer, in that case the last
+    // quote (if any) is escaped by an odd number of backslashes.
+    if str.len() < 2 || !str.starts_with('"') || !str.ends_with('"') {
+        return (
+            Literal::String(String::new()),
+            vec![LiteralError::LexerError],
+        );
+    }
+    let inner = &str[1..str.len() - 1];
+    if inner.chars().rev().take_while(|&c| c == '\\').count() % 2 == 1 {
+        return (
+            Literal::String(String::new()),
+            vec![LiteralError::LexerError],
+        );
+    }
+
+    let (values, errors) = unescape(inner, 1);
+    (Literal::String(values.into_iter().collect()), errors)
+}
+
+/// Parses the given string, including its surrounding quotes, into a character literal
+fn char_lit(str: &str) -> (Literal, Vec<LiteralError>) {
+    // An unclosed character literal is emitted as a single token by the lexer
+    if str.len() < 2 || !str.starts_with('\'') || !str.ends_with('\'') {
+        return (Literal::Char('\0'), vec![LiteralError::LexerError]);
+    }
+
+    let (values, errors) = unescape(&str[1..str.len() - 1], 1);
+    let first = values.first().copied().unwrap_or('\0');
+    if !errors.is_empty() {
+        (Literal::Char(first), errors)
+    } else if values.len() != 1 {
+        (
+            Literal::Char(first),
+            vec![LiteralError::InvalidCharLength(values.len())],
+        )
+    } else {
+        (Literal::Char(first), vec![])
+    }
+}
+
+/// Replaces all escape sequences in the contents of a string or character literal. `offset`
+/// is the offset of `text` in the literal, it is used to compute the range of invalid escape
+/// sequences relative to the start of the literal.
+fn unescape(text: &str, offset: usize) -> (Vec<char>, Vec<LiteralError>) {
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            values.push(c);
+            continue;
+        }
+
+        // A `\` at the end of a line skips the newline and all leading whitespace of the next line
+        if chars.as_str().starts_with('\n') || chars.as_str().starts_with("\r\n") {
+            chars = chars.as_str().trim_start().chars();
+            continue;
+        }
+
+        let start = text.len() - chars.as_str().len() - 1;
+        match unescape_char(&mut chars) {
+            Some(value) => values.push(value),
+            None => {
+                let end = text.len() - chars.as_str().len();
+                errors.push(LiteralError::InvalidEscape(TextRange::from_to(
+                    TextUnit::from_usize(offset + start),
+                    TextUnit::from_usize(offset + end),
+                )));
+            }
+        }
+    }
+
+    (values, errors)
+}
+
+/// Parses the remainder of an escape sequence (the part after the `\`) from the specified
+/// iterator. Returns `None` if the escape sequence is invalid.
+fn unescape_char(chars: &mut std::str::Chars) -> Option<char> {
+    let value = match chars.next()? {
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        '0' => '\0',
+        '\\' => '\\',
+        '\'' => '\'',
+        '"' => '"',
+        'u' => {
+            if !chars.as_str().starts_with('{') {
+                return None;
+            }
+            chars.next();
+            // Consume the entire escape sequence, even if it contains invalid characters, so the
+            // error covers the whole sequence
+            let mut digits = String::new();
+            let mut is_valid = true;
+            loop {
+                match chars.next()? {
+                    '}' => break,
+                    '_' => {}
+                    c if c.is_ascii_hexdigit() => digits.push(c),
+                    _ => is_valid = false,
+                }
+            }
+            if !is_valid || digits.is_empty() || digits.len() > 6 {
+                return None;
+            }
+            std::char::from_u32(u32::from_str_radix(&digits, 16).ok()?)?
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
 #[cfg(test)]
 mod test {
     use crate::builtin_type::{BuiltinFloat, BuiltinInt};
+    use crate::expr::{char_lit, integer_lit, string_lit, LiteralInt, LiteralIntKind};
     use crate::expr::{float_lit, LiteralError, LiteralFloat, LiteralFloatKind};
-    use crate::expr::{integer_lit, LiteralInt, LiteralIntKind};
     use crate::Literal;
-    use mun_syntax::SmolStr;
+    use mun_syntax::{SmolStr, TextRange};
 
     #[test]
     fn test_integer_literals() {
@@ -1368,14 +1732,192 @@ mod test {
             )
         );
     }
+
+    #[test]
+    fn test_char_literals() {
+        assert_eq!(char_lit("'a'"), (Literal::Char('a'), vec![]));
+        assert_eq!(char_lit("'€'"), (Literal::Char('€'), vec![]));
+        assert_eq!(char_lit("'\\n'"), (Literal::Char('\n'), vec![]));
+        assert_eq!(char_lit("'\\''"), (Literal::Char('\''), vec![]));
+        assert_eq!(char_lit("'\\\\'"), (Literal::Char('\\'), vec![]));
+        assert_eq!(char_lit("'\\u{1F600}'"), (Literal::Char('😀'), vec![]));
+        assert_eq!(
+            char_lit("''"),
+            (
+                Literal::Char('\0'),
+                vec![LiteralError::InvalidCharLength(0)]
+            )
+        );
+        assert_eq!(
+            char_lit("'ab'"),
+            (Literal::Char('a'), vec![LiteralError::InvalidCharLength(2)])
+        );
+        assert_eq!(
+            char_lit("'\\q'"),
+            (
+                Literal::Char('\0'),
+                vec![LiteralError::InvalidEscape(TextRange::from_to(
+                    1.into(),
+                    3.into()
+                ))]
+            )
+        );
+        assert_eq!(
+            char_lit("'\\u{110000}'"),
+            (
+                Literal::Char('\0'),
+                vec![LiteralError::InvalidEscape(TextRange::from_to(
+                    1.into(),
+                    11.into()
+                ))]
+            )
+        );
+        assert_eq!(
+            char_lit("'a"),
+            (Literal::Char('\0'), vec![LiteralError::LexerError])
+        );
+    }
+
+    #[test]
+    fn test_string_literals() {
+        assert_eq!(
+            string_lit(r#""Hello, world!""#),
+            (Literal::String("Hello, world!".to_owned()), vec![])
+        );
+        assert_eq!(
+            string_lit(r#""\t\"\\\n\u{1F600}""#),
+            (Literal::String("\t\"\\\n😀".to_owned()), vec![])
+        );
+        assert_eq!(
+            string_lit("\"multi\\\n    line\""),
+            (Literal::String("multiline".to_owned()), vec![])
+        );
+        assert_eq!(
+            string_lit(r#""a\qb\u{zz}c""#),
+            (
+                Literal::String("abc".to_owned()),
+                vec![
+                    LiteralError::InvalidEscape(TextRange::from_to(2.into(), 4.into())),
+                    LiteralError::InvalidEscape(TextRange::from_to(5.into(), 11.into())),
+                ]
+            )
+        );
+        assert_eq!(
+            string_lit(r#""\\""#),
+            (Literal::String("\\".to_owned()), vec![])
+        );
+        assert_eq!(
+            string_lit(r#""unterminated\""#),
+            (
+                Literal::String(String::new()),
+                vec![LiteralError::LexerError]
+            )
+        );
+        assert_eq!(
+            string_lit(r#"r"C:\path\to\file""#),
+            (Literal::String(r"C:\path\to\file".to_owned()), vec![])
+        );
+        assert_eq!(
+            string_lit(r###"r#"a "quoted" string"#"###),
+            (Literal::String(r#"a "quoted" string"#.to_owned()), vec![])
+        );
+        assert_eq!(
+            string_lit(r###"r#"unterminated""###),
+            (
+                Literal::String(String::new()),
+                vec![LiteralError::LexerError]
+            )
+        );
+    }
+}
+
+/// Returns the name of the intrinsic that is called by `call`, if its callee is a path of the
+/// form `core::name`.
+fn core_intrinsic_name(call: &ast::CallExpr) -> Option<Name> {
+    /// Returns the name of the last segment of the path, unless it is prefixed with `::`
+    fn segment_name(path: &ast::Path) -> Option<Name> {
+        let segment = path.segment()?;
+        if segment.has_colon_colon() {
+            return None;
+        }
+        segment.name_ref().map(|name_ref| name_ref.as_name())
+    }
+
+    let path = match call.expr().map(|expr| expr.kind()) {
+        Some(ast::ExprKind::PathExpr(path_expr)) => path_expr.path(),
+        _ => None,
+    }?;
+    let qualifier = path.qualifier()?;
+    if qualifier.qualifier().is_some() || segment_name(&qualifier) != Some(name![core]) {
+        return None;
+    }
+    segment_name(&path)
+}
+
+/// Returns the arguments of `call`.
+fn call_args(call: &ast::CallExpr) -> impl Iterator<Item = ast::Expr> {
+    call.arg_list()
+        .into_iter()
+        .flat_map(|arg_list| arg_list.args())
+}
+
+/// Returns true if `call` is a call to `core::source_location()`.
+fn is_source_location_call(call: &ast::CallExpr) -> bool {
+    core_intrinsic_name(call) == Some(name![source_location]) && call_args(call).next().is_none()
+}
+
+/// Returns true if `call` is a call to `core::panic()` with at most a message.
+fn is_panic_call(call: &ast::CallExpr) -> bool {
+    core_intrinsic_name(call) == Some(name![panic]) && call_args(call).count() <= 1
+}
+
+/// Returns true if `call` is a call to `core::assert()` with a condition and an optional message.
+fn is_assert_call(call: &ast::CallExpr) -> bool {
+    let num_args = call_args(call).count();
+    core_intrinsic_name(call) == Some(name![assert]) && (1..=2).contains(&num_args)
+}
+
+/// Returns the math function that is called by `call`, if it is a call to one of the math
+/// functions of the `core` module with the right number of arguments.
+fn math_intrinsic(call: &ast::CallExpr) -> Option<MathIntrinsic> {
+    let intrinsic = MathIntrinsic::from_name(&core_intrinsic_name(call)?)?;
+    if call_args(call).count() == intrinsic.arg_count() {
+        Some(intrinsic)
+    } else {
+        None
+    }
+}
+
+/// Returns true if `call` is a call to the constructor of a builtin vector type, like `float4`,
+/// with a value for every lane. The names of the vector types are reserved, so the constructor is
+/// recognized without resolving the callee.
+fn is_vector_constructor_call(call: &ast::CallExpr) -> bool {
+    let path = match call.expr().map(|expr| expr.kind()) {
+        Some(ast::ExprKind::PathExpr(path_expr)) => path_expr.path(),
+        _ => None,
+    };
+    let name = match path {
+        Some(path) if path.qualifier().is_none() => path
+            .segment()
+            .and_then(|segment| segment.name_ref())
+            .map(|name_ref| name_ref.as_name()),
+        _ => core_intrinsic_name(call),
+    };
+    let lanes = match name {
+        Some(name) if name == name![float2] => 2,
+        Some(name) if name == name![float3] => 3,
+        Some(name) if name == name![float4] => 4,
+        _ => return false,
+    };
+    call_args(call).count() == lanes
 }
 
 mod diagnostics {
     use super::{ExprDiagnostic, LiteralError};
     use crate::code_model::DefWithBody;
     use crate::diagnostics::{
-        DiagnosticSink, IntLiteralTooLarge, InvalidFloatingPointLiteral, InvalidLiteral,
-        InvalidLiteralSuffix,
+        DiagnosticSink, IntLiteralTooLarge, InvalidCharLiteral, InvalidEscapeSequence,
+        InvalidFloatingPointLiteral, InvalidLiteral, InvalidLiteralSuffix, UndeclaredLabel,
     };
     use crate::HirDatabase;
 
@@ -1419,8 +1961,28 @@ mod diagnostics {
                                 base: *base,
                             })
                         }
+                        LiteralError::InvalidCharLength(len) => {
+                            sink.push(InvalidCharLiteral { literal, len: *len })
+                        }
+                        LiteralError::InvalidEscape(range) => sink.push(InvalidEscapeSequence {
+                            literal,
+                            range: *range,
+                        }),
                     }
                 }
+                ExprDiagnostic::UndeclaredLabel { expr, label } => {
+                    let expr = source_map
+                        .expr_syntax(*expr)
+                        .expect("could not retrieve expr from source map")
+                        .map(|expr_src| {
+                            expr_src.either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr())
+                        });
+                    sink.push(UndeclaredLabel {
+                        file: expr.file_id,
+                        expr: expr.value,
+                        label: label.clone(),
+                    })
+                }
             }
         }
     }
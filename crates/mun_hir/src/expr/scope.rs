@@ -75,6 +75,15 @@ impl ExprScopes {
         self.scope_by_expr.get(&expr).copied()
     }
 
+    /// Returns the scope that contains the bindings of the specified pattern, e.g. the scope that
+    /// follows a `let` statement.
+    pub(crate) fn scope_for_pat(&self, pat: PatId) -> Option<ScopeId> {
+        self.scopes
+            .iter()
+            .find(|(_, data)| data.entries.iter().any(|entry| entry.pat == pat))
+            .map(|(scope, _)| scope)
+    }
+
     pub(crate) fn scope_by_expr(&self) -> &FxHashMap<ExprId, ScopeId> {
         &self.scope_by_expr
     }
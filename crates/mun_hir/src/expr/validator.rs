@@ -6,8 +6,13 @@ use crate::{diagnostics::DiagnosticSink, Body, Expr, Function, HirDatabase, Infe
 use mun_syntax::{AstNode, SyntaxNodePtr};
 use std::sync::Arc;
 
+mod atomic_usage;
 mod literal_out_of_range;
+mod self_comparison;
+mod shadowing;
 mod uninitialized_access;
+mod unreachable_code;
+mod unused_variables;
 
 #[cfg(test)]
 mod tests;
@@ -36,6 +41,11 @@ impl<'a> ExprValidator<'a> {
         self.validate_literal_ranges(sink);
         self.validate_uninitialized_access(sink);
         self.validate_extern(sink);
+        self.validate_atomic_usage(sink);
+        self.validate_unused_variables(sink);
+        self.validate_shadowing(sink);
+        self.validate_self_comparison(sink);
+        self.validate_unreachable_code(sink);
     }
 
     pub fn validate_extern(&self, sink: &mut DiagnosticSink) {
@@ -0,0 +1,91 @@
+use super::ExprValidator;
+use crate::code_model::src::HasSource;
+use crate::diagnostics::{AtomicOutsideGcStruct, AtomicValueCopied, DiagnosticSink};
+use crate::in_file::InFile;
+use crate::{Expr, ExprId, PatId, Statement};
+
+impl<'a> ExprValidator<'a> {
+    /// Validates that atomic values are only stored in fields of `gc` structs and that they are
+    /// only accessed through their methods. This ensures that an atomic is never copied, so all
+    /// threads that access an atomic field refer to the same memory location.
+    pub(super) fn validate_atomic_usage(&self, sink: &mut DiagnosticSink) {
+        for (pat, _) in self.body.params.iter() {
+            self.validate_atomic_pat(sink, *pat);
+        }
+
+        let returns_atomic = self
+            .func
+            .ty(self.db)
+            .callable_sig(self.db)
+            .map_or(false, |sig| sig.ret().as_atomic().is_some());
+        if returns_atomic {
+            if let Some(ptr) = self.body_source_map.type_ref_syntax(self.body.ret_type()) {
+                let file = self.func.source(self.db.upcast()).file_id;
+                sink.push(AtomicOutsideGcStruct {
+                    ptr: InFile::new(file, ptr.syntax_node_ptr()),
+                });
+            }
+        }
+
+        self.validate_atomic_expr(sink, self.body.body_expr, false);
+    }
+
+    /// Validates that the specified pattern does not bind an atomic value.
+    fn validate_atomic_pat(&self, sink: &mut DiagnosticSink, pat: PatId) {
+        if self.infer[pat].as_atomic().is_none() {
+            return;
+        }
+
+        if let Some(src) = self.body_source_map.pat_syntax(pat) {
+            sink.push(AtomicOutsideGcStruct {
+                ptr: src.map(|ptr| ptr.syntax_node_ptr()),
+            });
+        }
+    }
+
+    /// Validates that the specified expression and its sub-expressions only refer to an atomic
+    /// value if it is a struct field on which a method is called.
+    fn validate_atomic_expr(&self, sink: &mut DiagnosticSink, expr: ExprId, is_receiver: bool) {
+        let body = self.body.clone();
+        match &body[expr] {
+            Expr::MethodCall { receiver, args, .. } => {
+                self.validate_atomic_expr(sink, *receiver, true);
+                for arg in args.iter() {
+                    self.validate_atomic_expr(sink, *arg, false);
+                }
+            }
+            Expr::Block { statements, .. } => {
+                for statement in statements.iter() {
+                    // A binding with an initializer is reported through its initializer
+                    if let Statement::Let {
+                        pat,
+                        initializer: None,
+                        ..
+                    } = statement
+                    {
+                        self.validate_atomic_pat(sink, *pat);
+                    }
+                }
+                body[expr].walk_child_exprs(|child| self.validate_atomic_expr(sink, child, false));
+            }
+            e => e.walk_child_exprs(|child| self.validate_atomic_expr(sink, child, false)),
+        }
+
+        // Atomic values originate from struct fields, local bindings are reported separately and
+        // other expressions (e.g. blocks) only pass on the value of a field.
+        let is_field = match &body[expr] {
+            Expr::Field { .. } => true,
+            _ => false,
+        };
+
+        if is_field && !is_receiver && self.infer[expr].as_atomic().is_some() {
+            if let Some(src) = self.body_source_map.expr_syntax(expr) {
+                sink.push(AtomicValueCopied {
+                    expr: src.map(|ptr| {
+                        ptr.either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr())
+                    }),
+                });
+            }
+        }
+    }
+}
@@ -2,15 +2,14 @@ use super::ExprValidator;
 use crate::diagnostics::{DiagnosticSink, LiteralOutOfRange};
 use crate::ty::ResolveBitness;
 use crate::{ty_app, TypeCtor};
-use crate::{Expr, HirDisplay, Literal};
+use crate::{Expr, HirDisplay, Literal, Ty};
 
 impl<'a> ExprValidator<'a> {
     /// Iterates over all expressions to determine if one of the literals has a value that is out of
     /// range of its type.
     pub fn validate_literal_ranges(&self, sink: &mut DiagnosticSink) {
-        self.body[self.body.body_expr].walk_child_exprs(move |expr_id| {
-            let expr = &self.body[expr_id];
-            if let Expr::Literal(Literal::Int(lit)) = &expr {
+        for (expr_id, expr) in self.body.exprs() {
+            if let Expr::Literal(Literal::Int(lit)) = expr {
                 let ty = &self.infer[expr_id];
                 match ty {
                     ty_app!(TypeCtor::Int(int_ty)) => {
@@ -32,12 +31,14 @@ impl<'a> ExprValidator<'a> {
                             })
                         }
                     }
+                    // Literals in erroneous code, like superfluous arguments, are not inferred
+                    Ty::Unknown => {}
                     _ => panic!(
                         "expected int literal to have int ty while instead it is `{}`",
                         ty.display(self.db)
                     ),
                 }
             }
-        })
+        }
     }
 }
@@ -0,0 +1,59 @@
+use super::ExprValidator;
+use crate::diagnostics::{DiagnosticSink, SelfComparison};
+use crate::lint::{self, lint_level};
+use crate::{BinaryOp, Expr, Resolution, TypeCtor};
+
+impl<'a> ExprValidator<'a> {
+    /// Validates that no local binding is compared with itself. Floating-point bindings are
+    /// excluded, because comparing a float with itself is a way to check for NaN.
+    pub(super) fn validate_self_comparison(&self, sink: &mut DiagnosticSink) {
+        let severity = match lint_level(self.db, self.func, &lint::SELF_COMPARISON).severity() {
+            Some(severity) => severity,
+            None => return,
+        };
+
+        for (expr, data) in self.body.exprs() {
+            let (lhs, rhs) = match data {
+                Expr::BinaryOp {
+                    lhs,
+                    rhs,
+                    op: Some(BinaryOp::CmpOp(_)),
+                } => (*lhs, *rhs),
+                _ => continue,
+            };
+            let (path, other_path) = match (&self.body[lhs], &self.body[rhs]) {
+                (Expr::Path(path), Expr::Path(other_path)) => (path, other_path),
+                _ => continue,
+            };
+            if let Some(TypeCtor::Float(_)) = self.infer[lhs].as_simple() {
+                continue;
+            }
+
+            let resolver = crate::expr::resolver_for_expr(self.body.clone(), self.db, expr);
+            let resolve = |path| {
+                resolver
+                    .resolve_path_without_assoc_items(self.db, path)
+                    .take_values()
+            };
+            match (resolve(path), resolve(other_path)) {
+                (Some(Resolution::LocalBinding(a)), Some(Resolution::LocalBinding(b)))
+                    if a == b => {}
+                _ => continue,
+            }
+            let name = match path.as_ident() {
+                Some(name) => name.clone(),
+                None => continue,
+            };
+            if let Some(src) = self.body_source_map.expr_syntax(expr) {
+                sink.push(SelfComparison {
+                    file: src.file_id,
+                    expr: src
+                        .value
+                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr()),
+                    name,
+                    severity,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use super::ExprValidator;
+use crate::diagnostics::{DiagnosticSink, ShadowedBinding};
+use crate::lint::{self, lint_level};
+use crate::{Expr, Pat, Statement};
+
+impl<'a> ExprValidator<'a> {
+    /// Validates that no `let` binding shadows a binding with the same name that is still in
+    /// scope, including the parameters.
+    pub(super) fn validate_shadowing(&self, sink: &mut DiagnosticSink) {
+        let severity = match lint_level(self.db, self.func, &lint::SHADOWING).severity() {
+            Some(severity) => severity,
+            None => return,
+        };
+
+        let scopes = self.db.expr_scopes(self.func.into());
+        for (_, expr) in self.body.exprs() {
+            let statements = match expr {
+                Expr::Block { statements, .. } => statements,
+                _ => continue,
+            };
+            for statement in statements {
+                let (pat, initializer) = match statement {
+                    Statement::Let {
+                        pat,
+                        initializer: Some(initializer),
+                        ..
+                    } => (*pat, *initializer),
+                    _ => continue,
+                };
+                let name = match &self.body[pat] {
+                    Pat::Bind { name } => name,
+                    _ => continue,
+                };
+
+                // The scope of the initializer does not yet contain the new binding
+                let is_shadowing = scopes
+                    .scope_chain(scopes.scope_for(initializer))
+                    .any(|scope| scopes.entries(scope).iter().any(|e| e.name() == name));
+                if !is_shadowing || name.to_string().starts_with('_') {
+                    continue;
+                }
+                if let Some(src) = self.body_source_map.pat_syntax(pat) {
+                    sink.push(ShadowedBinding {
+                        file: src.file_id,
+                        pat: src.value.syntax_node_ptr(),
+                        name: name.clone(),
+                        severity,
+                    });
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::db::{SourceDatabase, Upcast};
+use crate::diagnostics::{DiagnosticSink, Severity};
 use crate::expr::validator::ExprValidator;
-use crate::{diagnostics::DiagnosticSink, ids::LocationCtx, mock::MockDatabase, Function};
+use crate::{ids::LocationCtx, mock::MockDatabase, Function, Struct};
 use mun_syntax::{ast, AstNode};
 use std::fmt::Write;
 
@@ -66,14 +67,142 @@ fn test_uninitialized_access_while() {
     )
 }
 
-fn diagnostics(content: &str) -> String {
+#[test]
+fn test_uninitialized_access_loop() {
+    diagnostics_snapshot(
+        r#"
+    fn foo(b: i64) -> i64 {
+        let a: i64;
+        loop {
+            if b > 4 { a = b; break; }
+            b += 1;
+        }
+        a               // correct, `a` is initialized at every `break`
+    }
+
+    fn bar(b: i64) -> i64 {
+        let a: i64;
+        loop {
+            if b > 4 { break; }
+            a = b;
+            b += 1;
+        }
+        a               // `a` is possibly-uninitialized
+    }
+
+    fn baz(b: i64) -> i64 {
+        let a: i64;
+        'outer: loop {
+            loop {
+                if b > 4 { break 'outer; }
+                a = b;
+                break;
+            }
+            a = 5;
+            break;
+        }
+        a               // `a` is possibly-uninitialized
+    }
+
+    fn foz(b: bool) -> i64 {
+        let a: i64;
+        b && { a = 3; true };
+        a               // `a` is possibly-uninitialized
+    }
+    "#,
+    )
+}
+
+#[test]
+fn test_atomic_usage() {
+    diagnostics_snapshot(
+        r#"
+    struct(gc) Counter { hits: Atomic<i32> }
+    struct(value) Value { hits: Atomic<i32> }   // atomics cannot be copied
+    struct Invalid(Atomic<f32>, Atomic, Atomic<i32, i32>, i32<i32>);
+
+    fn increment(counter: Counter) -> i32 {
+        counter.hits.store(counter.hits.load() + 1);
+        counter.hits.fetch_add(1)
+    }
+
+    fn copy(counter: Counter) {
+        let hits = counter.hits;                // atomics cannot be copied
+        let other: Atomic<i32>;                 // atomics cannot be stored in locals
+        counter.hits = counter.hits;            // atomics cannot be copied
+    }
+
+    fn param(hits: Atomic<i32>) {}              // atomics cannot be passed by value
+    fn ret(counter: Counter) -> Atomic<i32> {   // atomics cannot be returned
+        counter.hits                            // atomics cannot be copied
+    }
+    "#,
+    )
+}
+
+#[test]
+fn test_unused_variables() {
+    warnings_snapshot(
+        r#"
+    fn foo(a: i32, b: i32, _c: i32) -> i32 {
+        let d = 5;
+        let e = a + 1;
+        let _f = 3;
+        let g;
+        g = e;
+        e
+    }
+
+    extern fn bar(a: i32);
+    "#,
+    )
+}
+
+#[test]
+fn test_unreachable_code() {
+    warnings_snapshot(
+        r#"
+    fn foo(a: i32) -> i32 {
+        return a;
+        let b = a + 1;  // unreachable
+        b
+    }
+
+    fn bar(a: i32) -> i32 {
+        loop {
+            if a > 5 {
+                break;
+                a += 1;     // unreachable
+            }
+        }
+        let _c = core::panic("stop");
+        a               // unreachable
+    }
+
+    fn baz(a: bool) -> i32 {
+        if a { return 1 } else { return 2 };
+        3               // unreachable
+    }
+
+    #[allow(unreachable_code)]
+    fn allowed() {
+        return;
+        allowed();
+    }
+    "#,
+    )
+}
+
+fn diagnostics(content: &str, severity: Severity) -> String {
     let (db, file_id) = MockDatabase::with_single_file(content);
     let source_file = db.parse(file_id).ok().unwrap();
 
     let mut diags = String::new();
 
     let mut diag_sink = DiagnosticSink::new(|diag| {
-        write!(diags, "{}: {}\n", diag.highlight_range(), diag.message()).unwrap();
+        if diag.severity() == severity {
+            write!(diags, "{}: {}\n", diag.highlight_range(), diag.message()).unwrap();
+        }
     });
 
     let ctx = LocationCtx::new(db.upcast(), file_id);
@@ -83,6 +212,11 @@ fn diagnostics(content: &str) -> String {
                 id: ctx.to_def(&def),
             };
             ExprValidator::new(fun, &db).validate_body(&mut diag_sink);
+        } else if let Some(def) = ast::StructDef::cast(node.clone()) {
+            let s = Struct {
+                id: ctx.to_def(&def),
+            };
+            s.diagnostics(&db, &mut diag_sink);
         }
     }
     drop(diag_sink);
@@ -91,5 +225,18 @@ fn diagnostics(content: &str) -> String {
 
 fn diagnostics_snapshot(text: &str) {
     let text = text.trim().replace("\n    ", "\n");
-    insta::assert_snapshot!(insta::_macro_support::AutoName, diagnostics(&text), &text);
+    insta::assert_snapshot!(
+        insta::_macro_support::AutoName,
+        diagnostics(&text, Severity::Error),
+        &text
+    );
+}
+
+fn warnings_snapshot(text: &str) {
+    let text = text.trim().replace("\n    ", "\n");
+    insta::assert_snapshot!(
+        insta::_macro_support::AutoName,
+        diagnostics(&text, Severity::Warning),
+        &text
+    );
 }
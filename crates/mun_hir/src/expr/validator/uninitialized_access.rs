@@ -1,6 +1,6 @@
 use super::ExprValidator;
 use crate::diagnostics::{DiagnosticSink, PossiblyUninitializedVariable};
-use crate::{BinaryOp, Expr, ExprId, PatId, Path, Resolution, Resolver, Statement};
+use crate::{BinaryOp, Expr, ExprId, Name, PatId, Path, Resolution, Resolver, Statement};
 use std::collections::HashSet;
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -10,6 +10,34 @@ enum ExprKind {
     Both,
 }
 
+/// The bindings that are initialized when a loop is left through a `break`.
+struct LoopExit {
+    label: Option<Name>,
+    /// The bindings that are initialized at every `break` of the loop, or `None` if no `break` has
+    /// been encountered yet.
+    initialized: Option<HashSet<PatId>>,
+}
+
+impl LoopExit {
+    fn new(label: Option<Name>) -> Self {
+        LoopExit {
+            label,
+            initialized: None,
+        }
+    }
+
+    /// Records a `break` at which the specified bindings are initialized.
+    fn add_exit(&mut self, initialized_patterns: &HashSet<PatId>) {
+        self.initialized = Some(match self.initialized.take() {
+            Some(initialized) => initialized
+                .intersection(initialized_patterns)
+                .copied()
+                .collect(),
+            None => initialized_patterns.clone(),
+        });
+    }
+}
+
 impl<'d> ExprValidator<'d> {
     /// Validates that all binding access has previously been initialized.
     pub(super) fn validate_uninitialized_access(&self, sink: &mut DiagnosticSink) {
@@ -24,6 +52,7 @@ impl<'d> ExprValidator<'d> {
         self.validate_expr_access(
             sink,
             &mut initialized_patterns,
+            &mut Vec::new(),
             self.body.body_expr,
             ExprKind::Normal,
         );
@@ -34,15 +63,34 @@ impl<'d> ExprValidator<'d> {
         &self,
         sink: &mut DiagnosticSink,
         initialized_patterns: &mut HashSet<PatId>,
+        loops: &mut Vec<LoopExit>,
         expr: ExprId,
         expr_side: ExprKind,
     ) {
         let body = self.body.clone();
         match &body[expr] {
             Expr::Call { callee, args } => {
-                self.validate_expr_access(sink, initialized_patterns, *callee, expr_side);
+                self.validate_expr_access(sink, initialized_patterns, loops, *callee, expr_side);
+                for arg in args.iter() {
+                    self.validate_expr_access(sink, initialized_patterns, loops, *arg, expr_side);
+                }
+            }
+            Expr::MethodCall { receiver, args, .. } => {
+                self.validate_expr_access(
+                    sink,
+                    initialized_patterns,
+                    loops,
+                    *receiver,
+                    ExprKind::Normal,
+                );
                 for arg in args.iter() {
-                    self.validate_expr_access(sink, initialized_patterns, *arg, expr_side);
+                    self.validate_expr_access(
+                        sink,
+                        initialized_patterns,
+                        loops,
+                        *arg,
+                        ExprKind::Normal,
+                    );
                 }
             }
             Expr::Path(p) => {
@@ -61,11 +109,18 @@ impl<'d> ExprValidator<'d> {
                 then_branch,
                 else_branch,
             } => {
-                self.validate_expr_access(sink, initialized_patterns, *condition, ExprKind::Normal);
+                self.validate_expr_access(
+                    sink,
+                    initialized_patterns,
+                    loops,
+                    *condition,
+                    ExprKind::Normal,
+                );
                 let mut then_branch_initialized_patterns = initialized_patterns.clone();
                 self.validate_expr_access(
                     sink,
                     &mut then_branch_initialized_patterns,
+                    loops,
                     *then_branch,
                     ExprKind::Normal,
                 );
@@ -74,6 +129,7 @@ impl<'d> ExprValidator<'d> {
                     self.validate_expr_access(
                         sink,
                         &mut else_branch_initialized_patterns,
+                        loops,
                         *else_branch,
                         ExprKind::Normal,
                     );
@@ -96,8 +152,14 @@ impl<'d> ExprValidator<'d> {
                     };
                 }
             }
-            Expr::UnaryOp { expr, .. } => {
-                self.validate_expr_access(sink, initialized_patterns, *expr, ExprKind::Normal);
+            Expr::UnaryOp { expr, .. } | Expr::Cast { expr, .. } => {
+                self.validate_expr_access(
+                    sink,
+                    initialized_patterns,
+                    loops,
+                    *expr,
+                    ExprKind::Normal,
+                );
             }
             Expr::BinaryOp { lhs, rhs, op } => {
                 let lhs_expr_kind = match op {
@@ -105,8 +167,26 @@ impl<'d> ExprValidator<'d> {
                     Some(BinaryOp::Assignment { op: None }) => ExprKind::Place,
                     _ => ExprKind::Normal,
                 };
-                self.validate_expr_access(sink, initialized_patterns, *lhs, lhs_expr_kind);
-                self.validate_expr_access(sink, initialized_patterns, *rhs, ExprKind::Normal)
+                self.validate_expr_access(sink, initialized_patterns, loops, *lhs, lhs_expr_kind);
+                if let Some(BinaryOp::LogicOp(_)) = op {
+                    // The right hand side is not evaluated if the left hand side already determines
+                    // the result, so its assignments are conditional
+                    self.validate_expr_access(
+                        sink,
+                        &mut initialized_patterns.clone(),
+                        loops,
+                        *rhs,
+                        ExprKind::Normal,
+                    )
+                } else {
+                    self.validate_expr_access(
+                        sink,
+                        initialized_patterns,
+                        loops,
+                        *rhs,
+                        ExprKind::Normal,
+                    )
+                }
             }
             Expr::Block { statements, tail } => {
                 for statement in statements.iter() {
@@ -118,6 +198,7 @@ impl<'d> ExprValidator<'d> {
                                 self.validate_expr_access(
                                     sink,
                                     initialized_patterns,
+                                    loops,
                                     *initializer,
                                     ExprKind::Normal,
                                 );
@@ -128,6 +209,7 @@ impl<'d> ExprValidator<'d> {
                             self.validate_expr_access(
                                 sink,
                                 initialized_patterns,
+                                loops,
                                 *expr,
                                 ExprKind::Normal,
                             );
@@ -138,48 +220,157 @@ impl<'d> ExprValidator<'d> {
                     }
                 }
                 if let Some(tail) = tail {
-                    self.validate_expr_access(sink, initialized_patterns, *tail, ExprKind::Normal)
+                    self.validate_expr_access(
+                        sink,
+                        initialized_patterns,
+                        loops,
+                        *tail,
+                        ExprKind::Normal,
+                    )
                 }
             }
             Expr::Return { expr } => {
                 if let Some(expr) = expr {
-                    self.validate_expr_access(sink, initialized_patterns, *expr, ExprKind::Normal)
+                    self.validate_expr_access(
+                        sink,
+                        initialized_patterns,
+                        loops,
+                        *expr,
+                        ExprKind::Normal,
+                    )
                 }
             }
-            Expr::Break { expr } => {
+            Expr::Break { expr, label } => {
                 if let Some(expr) = expr {
-                    self.validate_expr_access(sink, initialized_patterns, *expr, ExprKind::Normal)
+                    self.validate_expr_access(
+                        sink,
+                        initialized_patterns,
+                        loops,
+                        *expr,
+                        ExprKind::Normal,
+                    )
+                }
+                let exit = match label {
+                    Some(label) => loops
+                        .iter_mut()
+                        .rev()
+                        .find(|exit| exit.label.as_ref() == Some(label)),
+                    None => loops.last_mut(),
+                };
+                if let Some(exit) = exit {
+                    exit.add_exit(initialized_patterns);
                 }
             }
-            Expr::Loop { body } => {
-                self.validate_expr_access(sink, initialized_patterns, *body, ExprKind::Normal)
+            Expr::Loop { body, label } => {
+                loops.push(LoopExit::new(label.clone()));
+                self.validate_expr_access(
+                    sink,
+                    &mut initialized_patterns.clone(),
+                    loops,
+                    *body,
+                    ExprKind::Normal,
+                );
+                // A loop can only be left through a `break`, so a binding is initialized after the
+                // loop if it is initialized at every `break`. Without a `break` the loop never
+                // finishes.
+                if let Some(initialized_at_exits) = loops.pop().and_then(|exit| exit.initialized) {
+                    *initialized_patterns = initialized_at_exits;
+                }
             }
-            Expr::While { condition, body } => {
-                self.validate_expr_access(sink, initialized_patterns, *condition, ExprKind::Normal);
+            Expr::While {
+                condition,
+                body,
+                label,
+            } => {
+                self.validate_expr_access(
+                    sink,
+                    initialized_patterns,
+                    loops,
+                    *condition,
+                    ExprKind::Normal,
+                );
+                // The body might not be executed at all, so the bindings it initializes remain
+                // uninitialized after the loop
+                loops.push(LoopExit::new(label.clone()));
                 self.validate_expr_access(
                     sink,
                     &mut initialized_patterns.clone(),
+                    loops,
                     *body,
                     ExprKind::Normal,
                 );
+                loops.pop();
             }
             Expr::RecordLit { fields, spread, .. } => {
                 for field in fields.iter() {
                     self.validate_expr_access(
                         sink,
                         initialized_patterns,
+                        loops,
                         field.expr,
                         ExprKind::Normal,
                     );
                 }
                 if let Some(expr) = spread {
-                    self.validate_expr_access(sink, initialized_patterns, *expr, ExprKind::Normal);
+                    self.validate_expr_access(
+                        sink,
+                        initialized_patterns,
+                        loops,
+                        *expr,
+                        ExprKind::Normal,
+                    );
                 }
             }
             Expr::Field { expr, .. } => {
-                self.validate_expr_access(sink, initialized_patterns, *expr, ExprKind::Normal);
+                self.validate_expr_access(
+                    sink,
+                    initialized_patterns,
+                    loops,
+                    *expr,
+                    ExprKind::Normal,
+                );
+            }
+            Expr::Panic { message } => {
+                if let Some(message) = message {
+                    self.validate_expr_access(
+                        sink,
+                        initialized_patterns,
+                        loops,
+                        *message,
+                        ExprKind::Normal,
+                    );
+                }
+            }
+            Expr::Assert { condition, message } => {
+                self.validate_expr_access(
+                    sink,
+                    initialized_patterns,
+                    loops,
+                    *condition,
+                    ExprKind::Normal,
+                );
+                if let Some(message) = message {
+                    self.validate_expr_access(
+                        sink,
+                        initialized_patterns,
+                        loops,
+                        *message,
+                        ExprKind::Normal,
+                    );
+                }
+            }
+            Expr::Vector { elements: args } | Expr::MathIntrinsic { args, .. } => {
+                for arg in args.iter() {
+                    self.validate_expr_access(
+                        sink,
+                        initialized_patterns,
+                        loops,
+                        *arg,
+                        ExprKind::Normal,
+                    );
+                }
             }
-            Expr::Literal(_) => {}
+            Expr::Literal(_) | Expr::Continue { .. } | Expr::SourceLocation => {}
             Expr::Missing => {}
         }
     }
@@ -0,0 +1,67 @@
+use super::ExprValidator;
+use crate::diagnostics::{DiagnosticSink, UnreachableCode};
+use crate::lint::{self, lint_level};
+use crate::{Expr, Statement};
+use mun_syntax::{ast, AstNode, SyntaxNodePtr};
+
+impl<'a> ExprValidator<'a> {
+    /// Validates that blocks contain no code after a statement that diverges, e.g. a `return`.
+    /// Only the first unreachable statement of every block is reported.
+    pub(super) fn validate_unreachable_code(&self, sink: &mut DiagnosticSink) {
+        let severity = match lint_level(self.db, self.func, &lint::UNREACHABLE_CODE).severity() {
+            Some(severity) => severity,
+            None => return,
+        };
+
+        for (expr, data) in self.body.exprs() {
+            let (statements, tail) = match data {
+                Expr::Block { statements, tail } => (statements, tail),
+                _ => continue,
+            };
+            let diverging = statements.iter().position(|statement| match statement {
+                Statement::Let {
+                    initializer: Some(initializer),
+                    ..
+                } => self.infer[*initializer].is_never(),
+                Statement::Let { .. } => false,
+                Statement::Expr(expr) => self.infer[*expr].is_never(),
+            });
+            let unreachable = match diverging {
+                Some(idx) => idx + 1,
+                None => continue,
+            };
+
+            let block_src = match self.body_source_map.expr_syntax(expr) {
+                Some(src) => src,
+                None => continue,
+            };
+            let code = if unreachable < statements.len() {
+                // Statements do not have a source of their own, so take it from the syntax
+                // tree of the block, which has the same statements
+                let root = block_src.file_syntax(self.db.upcast());
+                let statement = block_src
+                    .value
+                    .left()
+                    .map(|ptr| ptr.syntax_node_ptr().to_node(&root))
+                    .and_then(ast::BlockExpr::cast)
+                    .and_then(|block| block.statements().nth(unreachable));
+                match statement {
+                    Some(statement) => SyntaxNodePtr::new(statement.syntax()),
+                    None => continue,
+                }
+            } else {
+                match tail.and_then(|tail| self.body_source_map.expr_syntax(tail)) {
+                    Some(src) => src
+                        .value
+                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr()),
+                    None => continue,
+                }
+            };
+            sink.push(UnreachableCode {
+                file: block_src.file_id,
+                code,
+                severity,
+            });
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use super::ExprValidator;
+use crate::diagnostics::{DiagnosticSink, UnusedVariable};
+use crate::lint::{self, lint_level};
+use crate::{Expr, Pat, Resolution};
+use std::collections::HashSet;
+
+impl<'a> ExprValidator<'a> {
+    /// Validates that every local binding, including the parameters, is referred to at least once.
+    /// Bindings whose name starts with an underscore are intentionally unused.
+    pub(super) fn validate_unused_variables(&self, sink: &mut DiagnosticSink) {
+        if self.func.is_extern(self.db) {
+            return;
+        }
+        let severity = match lint_level(self.db, self.func, &lint::UNUSED_VARIABLES).severity() {
+            Some(severity) => severity,
+            None => return,
+        };
+
+        let mut used_patterns = HashSet::new();
+        for (expr, data) in self.body.exprs() {
+            if let Expr::Path(path) = data {
+                let resolver = crate::expr::resolver_for_expr(self.body.clone(), self.db, expr);
+                if let Some(Resolution::LocalBinding(pat)) = resolver
+                    .resolve_path_without_assoc_items(self.db, path)
+                    .take_values()
+                {
+                    used_patterns.insert(pat);
+                }
+            }
+        }
+
+        for (pat, data) in self.body.pats() {
+            let name = match data {
+                Pat::Bind { name } => name,
+                _ => continue,
+            };
+            if used_patterns.contains(&pat) || name.to_string().starts_with('_') {
+                continue;
+            }
+            if let Some(src) = self.body_source_map.pat_syntax(pat) {
+                sink.push(UnusedVariable {
+                    file: src.file_id,
+                    pat: src.value.syntax_node_ptr(),
+                    name: name.clone(),
+                    severity,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Computes the information that is shown when hovering over code in an editor: the inferred type
+//! of an expression or the signature and documentation of the definition that a name refers to.
+
+use crate::{
+    adt::StructKind,
+    code_model::{Local, StructField},
+    completion::function_at,
+    definition::{definition_for_token, token_at, Definition},
+    Documentation, FileId, Function, HirDatabase, HirDisplay, Pat, ReferenceDef, Struct,
+    Visibility,
+};
+use mun_syntax::{ast, AstNode, TextRange, TextUnit};
+
+/// The information about the code at a position in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HoverResult {
+    /// The range of the code that the information is about
+    pub range: TextRange,
+    /// The signature of the definition, e.g. `fn foo(a: i32) -> bool`, or the type of an
+    /// expression
+    pub signature: String,
+    /// The documentation of the definition
+    pub docs: Option<Documentation>,
+}
+
+/// Returns the information about the name or expression at the specified offset in a file, if
+/// any.
+pub fn hover(db: &dyn HirDatabase, file_id: FileId, offset: TextUnit) -> Option<HoverResult> {
+    let root = db.parse(file_id).syntax_node();
+    let token = token_at(&root, offset)?;
+    if let Some((range, def)) = definition_for_token(db, file_id, &token) {
+        return Some(match def {
+            Definition::Def(ReferenceDef::Function(f)) => hover_function(db, f, range),
+            Definition::Def(ReferenceDef::Struct(s)) => hover_struct(db, s, range),
+            Definition::Def(ReferenceDef::StructField(field)) => hover_field(db, field, range),
+            Definition::Def(ReferenceDef::Local(local)) => hover_local(db, local, range),
+            Definition::BuiltinType(ty) => HoverResult {
+                range,
+                signature: ty.to_string(),
+                docs: None,
+            },
+        });
+    }
+
+    // The type of the innermost expression that contains the token
+    let function = function_at(db, file_id, offset)?;
+    let source_map = function.body_source_map(db);
+    let (expr, node) = token
+        .parent()
+        .ancestors()
+        .filter_map(ast::Expr::cast)
+        .find_map(|node| Some((source_map.node_expr(&node)?, node)))?;
+    Some(HoverResult {
+        range: node.syntax().text_range(),
+        signature: function.infer(db)[expr].display(db).to_string(),
+        docs: None,
+    })
+}
+
+fn hover_function(db: &dyn HirDatabase, function: Function, range: TextRange) -> HoverResult {
+    HoverResult {
+        range,
+        signature: function_signature(db, function).0,
+        docs: function.docs(db.upcast()),
+    }
+}
+
+/// Returns the signature of a function, e.g. `fn foo(a: i32) -> bool`, together with the ranges of
+/// its parameters within the signature.
+pub(crate) fn function_signature(
+    db: &dyn HirDatabase,
+    function: Function,
+) -> (String, Vec<TextRange>) {
+    let body = function.body(db);
+    let infer = function.infer(db);
+    let mut signature = format!(
+        "{}fn {}(",
+        visibility_prefix(function.visibility(db)),
+        function.name(db)
+    );
+    let mut params = Vec::new();
+    for (index, (pat, _)) in body.params().iter().enumerate() {
+        if index > 0 {
+            signature.push_str(", ");
+        }
+        let name = match &body[*pat] {
+            Pat::Bind { name } => name.to_string(),
+            _ => String::from("_"),
+        };
+        let start = TextUnit::of_str(&signature);
+        signature.push_str(&format!("{}: {}", name, infer[*pat].display(db)));
+        params.push(TextRange::from_to(start, TextUnit::of_str(&signature)));
+    }
+    signature.push(')');
+    if let Some(sig) = function.ty(db).callable_sig(db) {
+        if !sig.ret().is_empty() {
+            signature.push_str(&format!(" -> {}", sig.ret().display(db)));
+        }
+    }
+    (signature, params)
+}
+
+fn hover_struct(db: &dyn HirDatabase, s: Struct, range: TextRange) -> HoverResult {
+    let data = s.data(db.upcast());
+    let fields = s.fields(db);
+    let field = |field: &StructField| field.ty(db).display(db).to_string();
+    let signature = match data.kind {
+        StructKind::Record => format!(
+            "struct {} {{ {} }}",
+            data.name,
+            fields
+                .iter()
+                .map(|f| format!("{}: {}", f.name(db), field(f)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        StructKind::Tuple => format!(
+            "struct {}({})",
+            data.name,
+            fields.iter().map(field).collect::<Vec<_>>().join(", ")
+        ),
+        StructKind::Unit => format!("struct {}", data.name),
+    };
+    HoverResult {
+        range,
+        signature,
+        docs: s.docs(db.upcast()),
+    }
+}
+
+fn hover_field(db: &dyn HirDatabase, field: StructField, range: TextRange) -> HoverResult {
+    // Only the fields of record structs can be documented
+    let docs = if field.parent.data(db.upcast()).kind == StructKind::Record {
+        field.docs(db.upcast())
+    } else {
+        None
+    };
+    HoverResult {
+        range,
+        signature: format!("{}: {}", field.name(db), field.ty(db).display(db)),
+        docs,
+    }
+}
+
+fn hover_local(db: &dyn HirDatabase, local: Local, range: TextRange) -> HoverResult {
+    let (function, pat) = (local.parent(), local.pat_id());
+    let body = function.body(db);
+    let name = match &body[pat] {
+        Pat::Bind { name } => name.to_string(),
+        _ => String::from("_"),
+    };
+    let ty = function.infer(db)[pat].display(db).to_string();
+    let is_param = body.params().iter().any(|(param, _)| *param == pat);
+    HoverResult {
+        range,
+        signature: if is_param {
+            format!("{}: {}", name, ty)
+        } else {
+            format!("let {}: {}", name, ty)
+        },
+        docs: None,
+    }
+}
+
+fn visibility_prefix(visibility: Visibility) -> &'static str {
+    match visibility {
+        Visibility::Public => "pub ",
+        Visibility::Private => "",
+    }
+}
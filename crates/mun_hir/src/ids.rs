@@ -119,3 +119,17 @@ impl AstItemDef<ast::StructDef> for StructId {
         db.lookup_intern_struct(self)
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ImplId(salsa::InternId);
+impl_intern_key!(ImplId);
+
+impl AstItemDef<ast::ImplDef> for ImplId {
+    fn intern(db: &dyn DefDatabase, loc: ItemLoc<ast::ImplDef>) -> Self {
+        db.intern_impl(loc)
+    }
+
+    fn lookup_intern(self, db: &dyn DefDatabase) -> ItemLoc<ast::ImplDef> {
+        db.lookup_intern_impl(self)
+    }
+}
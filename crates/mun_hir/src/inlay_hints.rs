@@ -0,0 +1,127 @@
+//! Computes the hints that an editor shows inline with the code, e.g. the inferred types of
+//! variables and the names of the parameters of a call.
+
+use crate::{
+    CallableDef, Expr, FileId, Function, HirDatabase, HirDisplay, Module, ModuleDef, Pat,
+    Statement, Ty,
+};
+use mun_syntax::TextRange;
+
+/// Determines which kinds of hints are computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InlayHintsConfig {
+    /// Whether to show the inferred type of `let` bindings without a type annotation
+    pub type_hints: bool,
+    /// Whether to show the names of the parameters that the arguments of a call are passed to
+    pub parameter_hints: bool,
+}
+
+impl Default for InlayHintsConfig {
+    fn default() -> Self {
+        InlayHintsConfig {
+            type_hints: true,
+            parameter_hints: true,
+        }
+    }
+}
+
+/// The kind of an `InlayHint`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InlayKind {
+    /// The inferred type of a binding, shown after the binding
+    Type,
+    /// The name of a parameter, shown before the argument that is passed to it
+    ParameterName,
+}
+
+/// A hint that is shown inline with the code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlayHint {
+    /// The range of the binding or argument that the hint is about
+    pub range: TextRange,
+    pub kind: InlayKind,
+    pub label: String,
+}
+
+/// Returns the hints of all function bodies in a file, ordered by position.
+pub fn inlay_hints(
+    db: &dyn HirDatabase,
+    file_id: FileId,
+    config: &InlayHintsConfig,
+) -> Vec<InlayHint> {
+    let mut hints = Vec::new();
+    for def in Module::from(file_id).declarations(db) {
+        if let ModuleDef::Function(f) = def {
+            function_hints(db, f, config, &mut hints);
+        }
+    }
+    hints.sort_by_key(|hint| (hint.range.start(), hint.range.end()));
+    hints
+}
+
+fn function_hints(
+    db: &dyn HirDatabase,
+    function: Function,
+    config: &InlayHintsConfig,
+    hints: &mut Vec<InlayHint>,
+) {
+    let (body, source_map) = db.body_with_source_map(function.into());
+    let infer = function.infer(db);
+    for (_, expr) in body.exprs() {
+        match expr {
+            Expr::Block { statements, .. } if config.type_hints => {
+                for statement in statements {
+                    let pat = match statement {
+                        Statement::Let {
+                            pat,
+                            type_ref: None,
+                            ..
+                        } => *pat,
+                        _ => continue,
+                    };
+                    let ty = &infer[pat];
+                    if !matches!(body[pat], Pat::Bind { .. }) || *ty == Ty::Unknown {
+                        continue;
+                    }
+                    if let Some(src) = source_map.pat_syntax(pat) {
+                        hints.push(InlayHint {
+                            range: src.value.syntax_node_ptr().range(),
+                            kind: InlayKind::Type,
+                            label: ty.display(db).to_string(),
+                        });
+                    }
+                }
+            }
+            Expr::Call { callee, args } if config.parameter_hints => {
+                let callee = match infer[*callee].as_callable_def() {
+                    Some(CallableDef::Function(f)) => f,
+                    _ => continue,
+                };
+                let callee_body = callee.body(db);
+                for ((param, _), arg) in callee_body.params().iter().zip(args.iter()) {
+                    let name = match &callee_body[*param] {
+                        Pat::Bind { name } => name,
+                        _ => continue,
+                    };
+                    // An argument that is named like the parameter needs no hint
+                    if let Expr::Path(path) = &body[*arg] {
+                        if path.as_ident() == Some(name) {
+                            continue;
+                        }
+                    }
+                    if let Some(src) = source_map.expr_syntax(*arg) {
+                        hints.push(InlayHint {
+                            range: src
+                                .value
+                                .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr())
+                                .range(),
+                            kind: InlayKind::ParameterName,
+                            label: name.to_string(),
+                        });
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
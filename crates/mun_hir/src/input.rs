@@ -36,4 +36,7 @@ impl SourceRoot {
     pub fn files(&self) -> impl Iterator<Item = FileId> + '_ {
         self.files.iter().copied()
     }
+    pub fn contains(&self, file_id: FileId) -> bool {
+        self.files.contains(&file_id)
+    }
 }
@@ -10,23 +10,41 @@ mod macros;
 #[macro_use]
 mod arena;
 mod adt;
+mod assists;
+mod attrs;
 mod builtin_type;
+mod call_graph;
+mod classify;
 mod code_model;
+mod completion;
+mod const_eval;
 mod db;
+mod definition;
+mod dependencies;
 pub mod diagnostics;
 mod display;
+mod docs;
+pub mod error_codes;
 mod expr;
+mod hover;
 mod ids;
 mod in_file;
+mod inlay_hints;
 mod input;
 pub mod line_index;
+pub mod lint;
 mod model;
 mod name;
 mod name_resolution;
 mod path;
+mod pretty;
 mod raw;
+mod references;
+mod rename;
 mod resolve;
+mod signature_help;
 mod source_id;
+mod symbols;
 mod ty;
 mod type_ref;
 mod utils;
@@ -42,26 +60,43 @@ pub use relative_path::{RelativePath, RelativePathBuf};
 
 pub use crate::{
     arena::{ArenaId, RawId},
-    builtin_type::{FloatBitness, IntBitness, Signedness},
+    assists::assists,
+    attrs::{Attr, AttrArg, AttrDef, Attrs, InlineKind},
+    builtin_type::{
+        BuiltinMethod, FloatBitness, IntBitness, MathIntrinsic, OperatorTrait, Signedness,
+        SourceLocationField, VectorComponent,
+    },
+    call_graph::CallGraph,
+    classify::{ClassifiedIdent, IdentClass},
+    completion::{completions, CompletionItem, CompletionKind},
+    const_eval::ConstEvalResult,
     db::{
         DefDatabase, DefDatabaseStorage, HirDatabase, HirDatabaseStorage, SourceDatabase,
         SourceDatabaseStorage, Upcast,
     },
+    definition::{goto_definition, NavigationTarget},
     display::HirDisplay,
+    docs::{DocDef, Documentation},
     expr::{
         resolver_for_expr, ArithOp, BinaryOp, Body, CmpOp, Expr, ExprId, ExprScopes, Literal,
         LogicOp, Ordering, Pat, PatId, RecordLitField, Statement, UnaryOp,
     },
+    hover::{hover, HoverResult},
     ids::ItemLoc,
+    inlay_hints::{inlay_hints, InlayHint, InlayHintsConfig, InlayKind},
     input::{FileId, SourceRoot, SourceRootId},
     name::Name,
-    name_resolution::PerNs,
+    name_resolution::{ModuleId, ModuleTree, PerNs},
     path::{Path, PathKind},
     raw::RawItems,
+    references::{find_references, Reference, ReferenceDef},
+    rename::{rename, RenameError},
     resolve::{Resolution, Resolver},
+    signature_help::{signature_help, SignatureHelp},
+    symbols::{document_symbols, DocumentSymbol, SymbolKind},
     ty::{
-        lower::CallableDef, ApplicationTy, FloatTy, InferenceResult, IntTy, ResolveBitness, Ty,
-        TypeCtor,
+        lower::CallableDef, ApplicationTy, FloatTy, InferenceResult, IntTy, PrimitiveTy,
+        ResolveBitness, Ty, TypeCtor,
     },
 };
 
@@ -71,5 +106,7 @@ use crate::{
     source_id::{AstIdMap, FileAstId},
 };
 
-pub use self::adt::StructMemoryKind;
-pub use self::code_model::{FnData, Function, Module, ModuleDef, Struct, Visibility};
+pub use self::adt::{DeriveTrait, StructMemoryKind};
+pub use self::code_model::{
+    FnData, Function, Impl, Local, Module, ModuleDef, Struct, StructField, Visibility,
+};
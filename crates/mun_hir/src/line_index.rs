@@ -38,6 +38,13 @@ impl LineIndex {
         }
     }
 
+    /// Returns the offset of the specified line and column, the inverse of `line_col`. Returns
+    /// `None` if the line does not exist.
+    pub fn offset(&self, line_col: LineCol) -> Option<TextUnit> {
+        let line_start_offset = self.newlines.get(line_col.line as usize)?;
+        Some(*line_start_offset + TextUnit::from(line_col.col))
+    }
+
     /// Retrieves the text between `first_line` and `last_line`, if any.
     pub fn text_part<'a>(
         &self,
@@ -74,6 +81,8 @@ mod tests {
         assert_eq!(index.line_col(5.into()), LineCol { line: 0, col: 5 });
         assert_eq!(index.line_col(6.into()), LineCol { line: 1, col: 0 });
         assert_eq!(index.line_col(7.into()), LineCol { line: 1, col: 1 });
+        assert_eq!(index.offset(LineCol { line: 1, col: 1 }), Some(7.into()));
+        assert_eq!(index.offset(LineCol { line: 2, col: 0 }), None);
     }
     #[test]
     fn test_text_part() {
@@ -0,0 +1,150 @@
+//! Lints are optional checks that warn about code that compiles but is likely to be a mistake.
+//!
+//! Every lint has a default level which can be overridden for a whole package (see
+//! `LintLevels`) or for a single function with the `#[allow(..)]`, `#[warn(..)]` and
+//! `#[deny(..)]` attributes. Attributes take precedence over the package-wide levels.
+
+use crate::{diagnostics::Severity, name::name, Attr, AttrArg, Function, HirDatabase, Name};
+use std::fmt;
+
+/// The level of a lint, which determines whether it is reported and how.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintLevel {
+    /// The lint is not reported
+    Allow,
+    /// The lint is reported as a warning
+    Warn,
+    /// The lint is reported as an error, which prevents compilation
+    Deny,
+}
+
+impl LintLevel {
+    /// Returns the level that is specified by the attribute if it is a lint attribute, e.g.
+    /// `#[allow(dead_code)]`.
+    pub(crate) fn from_attr(attr: &Attr) -> Option<LintLevel> {
+        let name = &attr.name;
+        if *name == name![allow] {
+            Some(LintLevel::Allow)
+        } else if *name == name![warn] {
+            Some(LintLevel::Warn)
+        } else if *name == name![deny] {
+            Some(LintLevel::Deny)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the severity with which a lint of this level is reported, or `None` if the lint is
+    /// not reported.
+    pub fn severity(self) -> Option<Severity> {
+        match self {
+            LintLevel::Allow => None,
+            LintLevel::Warn => Some(Severity::Warning),
+            LintLevel::Deny => Some(Severity::Error),
+        }
+    }
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LintLevel::Allow => "allow",
+            LintLevel::Warn => "warn",
+            LintLevel::Deny => "deny",
+        })
+    }
+}
+
+/// A lint, identified by its name.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Lint {
+    /// The name of the lint as used in attributes and on the command line, e.g. `unused_variables`
+    pub name: &'static str,
+    /// The level of the lint if it is not overridden
+    pub default_level: LintLevel,
+    /// A short description of what the lint detects
+    pub description: &'static str,
+}
+
+macro_rules! declare_lints {
+    ($($(#[$attr:meta])* $ident:ident, $name:literal, $level:ident, $description:literal;)*) => {
+        $(
+            $(#[$attr])*
+            pub static $ident: Lint = Lint {
+                name: $name,
+                default_level: LintLevel::$level,
+                description: $description,
+            };
+        )*
+
+        /// All lints that are known to the compiler.
+        pub static LINTS: &[&Lint] = &[$(&$ident),*];
+    };
+}
+
+declare_lints! {
+    /// Local bindings that are never referred to
+    UNUSED_VARIABLES, "unused_variables", Warn, "detects variables that are never used";
+    /// Private functions that are never called
+    DEAD_CODE, "dead_code", Warn, "detects functions that are never used";
+    /// Local bindings that shadow another binding of the same function
+    SHADOWING, "shadowing", Allow, "detects bindings that shadow an earlier binding";
+    /// Comparisons of a binding with itself, which always have the same result
+    SELF_COMPARISON, "self_comparison", Warn, "detects comparisons of a variable with itself";
+    /// Code after an expression that never finishes, like a `return`
+    UNREACHABLE_CODE, "unreachable_code", Warn, "detects code that can never be executed";
+}
+
+/// Returns the lint with the specified name.
+pub fn find_lint(name: &str) -> Option<&'static Lint> {
+    LINTS.iter().copied().find(|lint| lint.name == name)
+}
+
+/// Package-wide overrides of the default levels of lints, e.g. specified on the command line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintLevels {
+    levels: Vec<(&'static Lint, LintLevel)>,
+}
+
+impl LintLevels {
+    /// Overrides the level of the specified lint.
+    pub fn set(&mut self, lint: &'static Lint, level: LintLevel) {
+        self.levels.retain(|(it, _)| *it != lint);
+        self.levels.push((lint, level));
+    }
+
+    /// Returns the level of the specified lint.
+    pub fn level(&self, lint: &'static Lint) -> LintLevel {
+        self.levels
+            .iter()
+            .find(|(it, _)| *it == lint)
+            .map(|(_, level)| *level)
+            .unwrap_or(lint.default_level)
+    }
+}
+
+/// Returns the level of the specified lint within a function, taking lint attributes of the
+/// function into account. The last attribute that mentions the lint wins.
+pub(crate) fn lint_level(
+    db: &dyn HirDatabase,
+    function: Function,
+    lint: &'static Lint,
+) -> LintLevel {
+    let lint_name = Name::new_from_text(lint.name);
+    function
+        .attrs(db.upcast())
+        .iter()
+        .filter_map(|attr| LintLevel::from_attr(attr).map(|level| (attr, level)))
+        .filter(|(attr, _)| attr.idents().any(|name| *name == lint_name))
+        .map(|(_, level)| level)
+        .last()
+        .unwrap_or_else(|| db.lint_levels().level(lint))
+}
+
+/// Returns the names of the lints that are passed to a lint attribute which are not known.
+pub(crate) fn unknown_lints(attr: &Attr) -> impl Iterator<Item = &Name> {
+    attr.args.iter().filter_map(|arg| match arg {
+        AttrArg::Ident(name) if find_lint(&name.to_string()).is_none() => Some(name),
+        _ => None,
+    })
+}
@@ -29,6 +29,17 @@ impl Name {
         Name(Repr::Text(text))
     }
 
+    /// Creates a name from text that does not originate from a token in the syntax tree, e.g. the
+    /// name of the function that implements an operator.
+    pub(crate) fn new_from_text(text: &str) -> Name {
+        Name::resolve(&SmolStr::new(text))
+    }
+
+    /// Creates a name for a loop label from the text of its token, e.g. `'outer`.
+    pub(crate) fn new_label(text: &SmolStr) -> Name {
+        Name::new_text(text.clone())
+    }
+
     pub(crate) fn new_tuple_field(idx: usize) -> Name {
         Name(Repr::TupleField(idx))
     }
@@ -99,10 +110,20 @@ pub mod known {
         };
     }
 
+    #[rustfmt::skip]
     known_names!(
         // Primitives
         int, isize, i8, i16, i32, i64, i128, uint, usize, u8, u16, u32, u64, u128, float, f32, f64,
-        bool,
+        bool, char, any, Atomic, SourceLocation, Handle, float2, float3, float4,
+        // Builtin methods and fields
+        load, store, fetch_add, is_some, unwrap_or, file, line, column, x, y, z, w,
+        // Operator traits and their methods
+        Add, Sub, Mul, Div, Rem, add, sub, mul, div, rem,
+        // Attributes and derivable traits
+        derive, Eq, Ord, inline, always, never, export, name, allow, warn, deny, rename_from,
+        test, bench,
+        // Intrinsics
+        core, source_location, panic, assert, sqrt, sin, cos, pow, abs, min, max,
     );
 
     #[macro_export]
@@ -1,5 +1,7 @@
+mod module_tree;
 mod per_ns;
 
+pub use self::module_tree::{ModuleId, ModuleTree};
 pub use self::per_ns::{Namespace, PerNs};
 use crate::{builtin_type::BuiltinType, FileId, HirDatabase, ModuleDef, Name};
 use once_cell::sync::Lazy;
@@ -41,6 +43,18 @@ impl ModuleScope {
     pub fn get(&self, name: &Name) -> Option<&Resolution> {
         self.items.get(name).or_else(|| BUILTIN_SCOPE.get(name))
     }
+
+    /// Returns the item with the specified name that is declared in the module, ignoring the
+    /// builtin types.
+    pub fn get_declared(&self, name: &Name) -> Option<&Resolution> {
+        self.items.get(name)
+    }
+
+    /// Returns the builtin type with the specified name, e.g. `SourceLocation` in
+    /// `core::SourceLocation`.
+    pub fn get_builtin(name: &Name) -> Option<&'static Resolution> {
+        BUILTIN_SCOPE.get(name)
+    }
 }
 
 pub(crate) fn module_scope_query(db: &dyn HirDatabase, file_id: FileId) -> Arc<ModuleScope> {
@@ -48,6 +62,8 @@ pub(crate) fn module_scope_query(db: &dyn HirDatabase, file_id: FileId) -> Arc<M
     let defs = db.module_data(file_id);
     for def in defs.definitions() {
         match def {
+            // Functions of an impl block are only reachable through the operator they implement
+            ModuleDef::Function(f) if f.is_associated(db) => {}
             ModuleDef::Function(f) => {
                 scope.items.insert(
                     f.name(db),
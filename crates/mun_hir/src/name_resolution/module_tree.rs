@@ -0,0 +1,172 @@
+use crate::{Arena, DefDatabase, FileId, Name, PathKind, RawId, SourceRootId};
+use relative_path::{Component, RelativePath};
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+/// Id of a module in a `ModuleTree`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModuleId(RawId);
+impl_arena_id!(ModuleId);
+
+/// The modules of a package, derived from the layout of its source files. The file `main.mun` at
+/// the root of the source root defines the root module, a file named `mod.mun` defines the module
+/// of its directory and any other file defines a module named after the file. The items of
+/// `ai/enemy.mun` therefore live in the module `ai::enemy`. A directory without a `mod.mun` is a
+/// module without items.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ModuleTree {
+    modules: Arena<ModuleId, ModuleNode>,
+    root: ModuleId,
+    modules_by_file: FxHashMap<FileId, ModuleId>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ModuleNode {
+    parent: Option<ModuleId>,
+    children: FxHashMap<Name, ModuleId>,
+    file: Option<FileId>,
+}
+
+impl ModuleTree {
+    pub(crate) fn module_tree_query(
+        db: &dyn DefDatabase,
+        source_root: SourceRootId,
+    ) -> Arc<ModuleTree> {
+        let mut modules = Arena::default();
+        let root = modules.alloc(ModuleNode::default());
+        let mut tree = ModuleTree {
+            modules,
+            root,
+            modules_by_file: FxHashMap::default(),
+        };
+
+        // Sort the files by path so that the first of two files that define the same module, like
+        // `ai.mun` and `ai/mod.mun`, is chosen consistently.
+        let mut files: Vec<_> = db
+            .source_root(source_root)
+            .files()
+            .map(|file_id| (db.file_relative_path(file_id), file_id))
+            .collect();
+        files.sort();
+        for (path, file_id) in files {
+            let module_path = match module_path(&path) {
+                Some(module_path) => module_path,
+                None => continue,
+            };
+            let module = module_path
+                .into_iter()
+                .fold(tree.root, |module, name| tree.child_or_insert(module, name));
+            if tree.modules[module].file.is_none() {
+                tree.modules[module].file = Some(file_id);
+                tree.modules_by_file.insert(file_id, module);
+            }
+        }
+
+        Arc::new(tree)
+    }
+
+    fn child_or_insert(&mut self, module: ModuleId, name: Name) -> ModuleId {
+        if let Some(child) = self.modules[module].children.get(&name) {
+            return *child;
+        }
+        let child = self.modules.alloc(ModuleNode {
+            parent: Some(module),
+            ..ModuleNode::default()
+        });
+        self.modules[module].children.insert(name, child);
+        child
+    }
+
+    /// Returns the root module of the package.
+    pub fn root(&self) -> ModuleId {
+        self.root
+    }
+
+    /// Returns the module that is defined by the specified file, if any.
+    pub fn module_for_file(&self, file_id: FileId) -> Option<ModuleId> {
+        self.modules_by_file.get(&file_id).copied()
+    }
+
+    /// Returns the file that defines the items of the specified module, if any.
+    pub fn file(&self, module: ModuleId) -> Option<FileId> {
+        self.modules[module].file
+    }
+
+    /// Returns the parent of the specified module, or `None` for the root module.
+    pub fn parent(&self, module: ModuleId) -> Option<ModuleId> {
+        self.modules[module].parent
+    }
+
+    /// Returns the submodule of `module` with the specified name.
+    pub fn child(&self, module: ModuleId, name: &Name) -> Option<ModuleId> {
+        self.modules[module].children.get(name).copied()
+    }
+
+    /// Returns the names of the modules from the root to the specified module, e.g. `[ai, enemy]`
+    /// for the module `ai::enemy`.
+    pub fn path(&self, module: ModuleId) -> Vec<Name> {
+        let mut path = Vec::new();
+        let mut current = module;
+        while let Some(parent) = self.parent(current) {
+            let name = self.modules[parent]
+                .children
+                .iter()
+                .find(|(_, child)| **child == current)
+                .map(|(name, _)| name.clone())
+                .expect("a module must be a child of its parent");
+            path.push(name);
+            current = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Resolves the module that is referred to by the qualifier of a path, e.g. `ai::enemy` in
+    /// `ai::enemy::attack`, as seen from the module `from`. The first segment of a plain path
+    /// refers to a submodule of `from` or, if there is none, of the root module.
+    pub(crate) fn resolve_module<'a>(
+        &self,
+        from: ModuleId,
+        kind: &PathKind,
+        segments: impl IntoIterator<Item = &'a Name>,
+    ) -> Option<ModuleId> {
+        let mut segments = segments.into_iter();
+        let mut module = match kind {
+            PathKind::Abs => self.root,
+            PathKind::Self_ => from,
+            PathKind::Super => self.parent(from)?,
+            PathKind::Plain => match segments.next() {
+                Some(name) => self
+                    .child(from, name)
+                    .or_else(|| self.child(self.root, name))?,
+                None => from,
+            },
+        };
+        for name in segments {
+            module = self.child(module, name)?;
+        }
+        Some(module)
+    }
+}
+
+/// Returns the names of the modules from the root to the module that is defined by the file at
+/// `path`, or `None` if the file is not a Mun source file.
+fn module_path(path: &RelativePath) -> Option<Vec<Name>> {
+    if path.extension() != Some("mun") {
+        return None;
+    }
+    let mut module_path = Vec::new();
+    for component in path.parent()?.components() {
+        match component {
+            Component::Normal(name) => module_path.push(Name::new_from_text(name)),
+            Component::CurDir => (),
+            Component::ParentDir => return None,
+        }
+    }
+    let stem = path.file_stem()?;
+    let is_directory_module = stem == "mod" || (stem == "main" && module_path.is_empty());
+    if !is_directory_module {
+        module_path.push(Name::new_from_text(stem));
+    }
+    Some(module_path)
+}
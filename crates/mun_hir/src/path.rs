@@ -21,35 +21,40 @@ pub enum PathKind {
 }
 
 impl Path {
-    /// Converts an `ast::Path` to `Path`.
-    pub fn from_ast(path: ast::Path) -> Option<Path> {
+    /// Converts an `ast::Path` to `Path`. The `self` and `super` keywords are only allowed as the
+    /// first segment of a path.
+    pub fn from_ast(mut path: ast::Path) -> Option<Path> {
         let mut kind = PathKind::Plain;
         let mut segments = Vec::new();
-        //        loop {
-        let segment = path.segment()?;
+        loop {
+            let segment = path.segment()?;
 
-        if segment.has_colon_colon() {
-            kind = PathKind::Abs;
-        }
-
-        match segment.kind()? {
-            ast::PathSegmentKind::Name(name) => {
-                let segment = PathSegment {
-                    name: name.as_name(),
-                };
-                segments.push(segment);
-            }
-            ast::PathSegmentKind::SelfKw => {
-                kind = PathKind::Self_;
-                //                    break;
+            if segment.has_colon_colon() {
+                kind = PathKind::Abs;
             }
-            ast::PathSegmentKind::SuperKw => {
-                kind = PathKind::Super;
-                //                    break;
+
+            let qualifier = path.qualifier();
+            match segment.kind()? {
+                ast::PathSegmentKind::Name(name) => {
+                    let segment = PathSegment {
+                        name: name.as_name(),
+                    };
+                    segments.push(segment);
+                }
+                ast::PathSegmentKind::SelfKw if qualifier.is_none() => {
+                    kind = PathKind::Self_;
+                }
+                ast::PathSegmentKind::SuperKw if qualifier.is_none() => {
+                    kind = PathKind::Super;
+                }
+                ast::PathSegmentKind::SelfKw | ast::PathSegmentKind::SuperKw => return None,
             }
+
+            path = match qualifier {
+                Some(qualifier) => qualifier,
+                None => break,
+            };
         }
-        //            break;
-        //        }
         segments.reverse();
         Some(Path { kind, segments })
     }
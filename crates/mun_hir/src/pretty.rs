@@ -0,0 +1,241 @@
+//! A textual representation of lowered function bodies, annotated with the types that were inferred
+//! for every expression and pattern. This is mainly useful to debug lowering and type inference.
+
+use crate::{
+    code_model::ModuleDef, expr::Body, ArithOp, BinaryOp, CmpOp, Expr, ExprId, Function,
+    HirDatabase, HirDisplay, InferenceResult, Literal, LogicOp, Module, Ordering, Pat, PatId, Path,
+    PathKind, Statement, UnaryOp,
+};
+use std::{fmt::Write, sync::Arc};
+
+/// Returns the lowered bodies of all functions in `module`, including the functions of `impl`
+/// blocks, separated by empty lines.
+pub(crate) fn print_module(db: &dyn HirDatabase, module: Module) -> String {
+    let declared_functions = module
+        .declarations(db)
+        .into_iter()
+        .filter_map(|def| match def {
+            ModuleDef::Function(f) => Some(f),
+            _ => None,
+        });
+    let impl_functions = module.impls(db).into_iter().flat_map(|i| i.functions(db));
+    declared_functions
+        .chain(impl_functions)
+        .filter(|f| !f.is_extern(db))
+        .map(|f| print_function(db, f))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the lowered body of `function`. Every expression is printed on its own line, followed by
+/// its inferred type, with its subexpressions indented below it.
+pub(crate) fn print_function(db: &dyn HirDatabase, function: Function) -> String {
+    let mut printer = Printer {
+        db,
+        body: function.body(db),
+        infer: function.infer(db),
+        out: String::new(),
+    };
+    printer.print_signature(function);
+    let body_expr = printer.body.body_expr();
+    printer.print_expr(body_expr, 1);
+    printer.out
+}
+
+struct Printer<'a> {
+    db: &'a dyn HirDatabase,
+    body: Arc<Body>,
+    infer: Arc<InferenceResult>,
+    out: String,
+}
+
+impl<'a> Printer<'a> {
+    fn print_signature(&mut self, function: Function) {
+        let params = self
+            .body
+            .params()
+            .iter()
+            .map(|(pat, _)| format!("{}: {}", self.pat(*pat), self.infer[*pat].display(self.db)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let ret_type = function
+            .ty(self.db)
+            .callable_sig(self.db)
+            .map(|sig| sig.ret().display(self.db).to_string());
+        write!(self.out, "fn {}({})", function.name(self.db), params).unwrap();
+        if let Some(ret_type) = ret_type {
+            write!(self.out, " -> {}", ret_type).unwrap();
+        }
+        self.out.push('\n');
+    }
+
+    fn line(&mut self, indent: usize, text: &str) {
+        for _ in 0..indent {
+            self.out.push_str("    ");
+        }
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn pat(&self, pat: PatId) -> String {
+        match &self.body[pat] {
+            Pat::Missing => "{missing}".to_string(),
+            Pat::Wild => "_".to_string(),
+            Pat::Path(path) => path_to_string(path),
+            Pat::Bind { name } => name.to_string(),
+        }
+    }
+
+    fn print_expr(&mut self, expr: ExprId, indent: usize) {
+        let label = match &self.body[expr] {
+            Expr::Missing => "Missing".to_string(),
+            Expr::Call { .. } => "Call".to_string(),
+            Expr::MethodCall { method_name, .. } => format!("MethodCall({})", method_name),
+            Expr::Path(path) => format!("Path({})", path_to_string(path)),
+            Expr::If { .. } => "If".to_string(),
+            Expr::UnaryOp { op, .. } => format!("UnaryOp({})", unary_op_to_str(*op)),
+            Expr::Cast { .. } => "Cast".to_string(),
+            Expr::BinaryOp { op, .. } => match op {
+                Some(op) => format!("BinaryOp({})", binary_op_to_string(*op)),
+                None => "BinaryOp(?)".to_string(),
+            },
+            Expr::Block { .. } => "Block".to_string(),
+            Expr::Return { .. } => "Return".to_string(),
+            Expr::Break { label, .. } => match label {
+                Some(label) => format!("Break('{})", label),
+                None => "Break".to_string(),
+            },
+            Expr::Continue { label } => match label {
+                Some(label) => format!("Continue('{})", label),
+                None => "Continue".to_string(),
+            },
+            Expr::Loop { label, .. } => match label {
+                Some(label) => format!("Loop('{})", label),
+                None => "Loop".to_string(),
+            },
+            Expr::While { label, .. } => match label {
+                Some(label) => format!("While('{})", label),
+                None => "While".to_string(),
+            },
+            Expr::RecordLit { .. } => "RecordLit".to_string(),
+            Expr::Field { name, .. } => format!("Field({})", name),
+            Expr::Literal(literal) => format!("Literal({})", literal_to_string(literal)),
+            Expr::SourceLocation => "SourceLocation".to_string(),
+            Expr::Panic { .. } => "Panic".to_string(),
+            Expr::Assert { .. } => "Assert".to_string(),
+            Expr::Vector { .. } => "Vector".to_string(),
+            Expr::MathIntrinsic { intrinsic, .. } => format!("MathIntrinsic({})", intrinsic.name()),
+        };
+        let text = format!("{}: {}", label, self.infer[expr].display(self.db));
+        self.line(indent, &text);
+
+        let body = self.body.clone();
+        match &body[expr] {
+            Expr::Block { statements, tail } => {
+                for statement in statements {
+                    match statement {
+                        Statement::Let {
+                            pat, initializer, ..
+                        } => {
+                            let text = format!(
+                                "Let {}: {}",
+                                self.pat(*pat),
+                                self.infer[*pat].display(self.db)
+                            );
+                            self.line(indent + 1, &text);
+                            if let Some(initializer) = initializer {
+                                self.print_expr(*initializer, indent + 2);
+                            }
+                        }
+                        Statement::Expr(expr) => self.print_expr(*expr, indent + 1),
+                    }
+                }
+                if let Some(tail) = tail {
+                    self.print_expr(*tail, indent + 1);
+                }
+            }
+            Expr::RecordLit { fields, spread, .. } => {
+                for field in fields {
+                    self.line(indent + 1, &format!("{}:", field.name));
+                    self.print_expr(field.expr, indent + 2);
+                }
+                if let Some(spread) = spread {
+                    self.line(indent + 1, "..");
+                    self.print_expr(*spread, indent + 2);
+                }
+            }
+            expr => expr.walk_child_exprs(|child| self.print_expr(child, indent + 1)),
+        }
+    }
+}
+
+fn path_to_string(path: &Path) -> String {
+    let prefix = match path.kind {
+        PathKind::Plain => "",
+        PathKind::Self_ => "self::",
+        PathKind::Super => "super::",
+        PathKind::Abs => "::",
+    };
+    let segments = path
+        .segments
+        .iter()
+        .map(|segment| segment.name.to_string())
+        .collect::<Vec<_>>()
+        .join("::");
+    format!("{}{}", prefix, segments)
+}
+
+fn literal_to_string(literal: &Literal) -> String {
+    match literal {
+        Literal::String(value) => format!("{:?}", value),
+        Literal::Char(value) => format!("{:?}", value),
+        Literal::Bool(value) => value.to_string(),
+        Literal::Int(int) => int.value.to_string(),
+        Literal::Float(float) => format!("{:?}", float.value),
+    }
+}
+
+fn unary_op_to_str(op: UnaryOp) -> &'static str {
+    match op {
+        UnaryOp::Not => "!",
+        UnaryOp::Neg => "-",
+    }
+}
+
+fn arith_op_to_str(op: ArithOp) -> &'static str {
+    match op {
+        ArithOp::Add => "+",
+        ArithOp::Multiply => "*",
+        ArithOp::Subtract => "-",
+        ArithOp::Divide => "/",
+        ArithOp::Remainder => "%",
+        ArithOp::LeftShift => "<<",
+        ArithOp::RightShift => ">>",
+        ArithOp::BitAnd => "&",
+        ArithOp::BitOr => "|",
+        ArithOp::BitXor => "^",
+    }
+}
+
+fn binary_op_to_string(op: BinaryOp) -> String {
+    match op {
+        BinaryOp::LogicOp(LogicOp::And) => "&&".to_string(),
+        BinaryOp::LogicOp(LogicOp::Or) => "||".to_string(),
+        BinaryOp::ArithOp(op) => arith_op_to_str(op).to_string(),
+        BinaryOp::CmpOp(CmpOp::Eq { negated: false }) => "==".to_string(),
+        BinaryOp::CmpOp(CmpOp::Eq { negated: true }) => "!=".to_string(),
+        BinaryOp::CmpOp(CmpOp::Ord { ordering, strict }) => {
+            let op = match ordering {
+                Ordering::Less => "<",
+                Ordering::Greater => ">",
+            };
+            if strict {
+                op.to_string()
+            } else {
+                format!("{}=", op)
+            }
+        }
+        BinaryOp::Assignment { op: None } => "=".to_string(),
+        BinaryOp::Assignment { op: Some(op) } => format!("{}=", arith_op_to_str(op)),
+    }
+}
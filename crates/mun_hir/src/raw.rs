@@ -32,6 +32,7 @@ pub(super) enum DefKind {
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub(super) enum RawFileItem {
     Definition(DefId),
+    Impl(FileAstId<ast::ImplDef>),
 }
 
 impl Index<DefId> for RawItems {
@@ -58,6 +59,14 @@ impl RawItems {
                 ast::ModuleItemKind::StructDef(it) => {
                     (DefKind::Struct((*ast_id_map).ast_id(&it)), it.name())
                 }
+                ast::ModuleItemKind::ImplDef(it) => {
+                    // Impl blocks are not named, the functions they contain are only reachable
+                    // through the trait they implement.
+                    items
+                        .items
+                        .push(RawFileItem::Impl((*ast_id_map).ast_id(&it)));
+                    continue;
+                }
             };
 
             // If no name is provided an error is already emitted
@@ -0,0 +1,317 @@
+//! Finds all references to a definition within the package that contains it.
+
+use crate::{
+    code_model::{Local, StructField},
+    definition::{definition_for_token, navigation_target, token_at, Definition},
+    ids::AstItemDef,
+    resolve::Resolution,
+    type_ref::{TypeRef, TypeRefMap, TypeRefSourceMap},
+    Expr, FileId, Function, HirDatabase, Module, ModuleDef, Name, Resolver, Struct,
+};
+use mun_syntax::{ast, AstNode, SyntaxNode, TextRange, TextUnit};
+use std::sync::Arc;
+
+/// The definitions that can be referred to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferenceDef {
+    Function(Function),
+    Struct(Struct),
+    StructField(StructField),
+    Local(Local),
+}
+impl_froms!(ReferenceDef: Function, Struct, StructField, Local);
+
+/// The location of a reference to a definition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Reference {
+    /// The file that contains the reference
+    pub file_id: FileId,
+    /// The range of the name that refers to the definition
+    pub range: TextRange,
+}
+
+impl ReferenceDef {
+    /// Returns the name by which the definition is referred to.
+    fn name(self, db: &dyn HirDatabase) -> Option<Name> {
+        match self {
+            ReferenceDef::Function(it) => Some(it.name(db)),
+            ReferenceDef::Struct(it) => Some(it.name(db.upcast())),
+            ReferenceDef::StructField(it) => Some(it.name(db)),
+            ReferenceDef::Local(it) => it.name(db),
+        }
+    }
+
+    /// Returns the file that contains the definition.
+    pub(crate) fn file_id(self, db: &dyn HirDatabase) -> FileId {
+        match self {
+            ReferenceDef::Function(it) => it.id.file_id(db.upcast()),
+            ReferenceDef::Struct(it) => it.id.file_id(db.upcast()),
+            ReferenceDef::StructField(it) => it.parent.id.file_id(db.upcast()),
+            ReferenceDef::Local(it) => it.parent.id.file_id(db.upcast()),
+        }
+    }
+}
+
+/// Returns all references to the definition that the name at the specified offset in a file refers
+/// to, or declares, ordered by file and position. The location of the name of the declaration is
+/// included if `include_declaration` is true. Builtin types have no references.
+pub fn find_references(
+    db: &dyn HirDatabase,
+    file_id: FileId,
+    offset: TextUnit,
+    include_declaration: bool,
+) -> Option<Vec<Reference>> {
+    let root = db.parse(file_id).syntax_node();
+    let token = token_at(&root, offset)?;
+    let def = match definition_for_token(db, file_id, &token)?.1 {
+        Definition::Def(def) => def,
+        Definition::BuiltinType(_) => return None,
+    };
+
+    let mut references = db.find_all_references(def).to_vec();
+    if include_declaration {
+        if let Some(declaration) = navigation_target(db, def) {
+            references.push(Reference {
+                file_id: declaration.file_id,
+                range: declaration.focus_range,
+            });
+            references.sort_by_key(|reference| (reference.file_id, reference.range.start()));
+        }
+    }
+    Some(references)
+}
+
+/// Returns all references to the specified definition, ordered by file and position. The
+/// declaration of the definition itself is not a reference.
+pub(crate) fn find_all_references_query(
+    db: &dyn HirDatabase,
+    def: ReferenceDef,
+) -> Arc<Vec<Reference>> {
+    let name = match def.name(db) {
+        Some(name) => name,
+        None => return Arc::new(Vec::new()),
+    };
+    let mut collector = ReferenceCollector {
+        db,
+        def,
+        name,
+        references: Vec::new(),
+    };
+
+    match def {
+        // Local bindings can only be referred to from within their own function
+        ReferenceDef::Local(local) => collector.collect_body(local.parent),
+        _ => {
+            let source_root = db.source_root(db.file_source_root(def.file_id(db)));
+            for file_id in source_root.files() {
+                collector.collect_module(Module::from(file_id));
+            }
+        }
+    }
+
+    let mut references = collector.references;
+    references.sort_by_key(|reference| (reference.file_id, reference.range.start()));
+    references.dedup();
+    Arc::new(references)
+}
+
+struct ReferenceCollector<'a> {
+    db: &'a dyn HirDatabase,
+    def: ReferenceDef,
+    name: Name,
+    references: Vec<Reference>,
+}
+
+impl<'a> ReferenceCollector<'a> {
+    fn collect_module(&mut self, module: Module) {
+        let db = self.db;
+        let file_id = module.file_id();
+        for def in module.declarations(db) {
+            match def {
+                ModuleDef::Function(f) => self.collect_function(f),
+                ModuleDef::Struct(s) => {
+                    let data = s.data(db.upcast());
+                    self.collect_type_refs(
+                        file_id,
+                        &s.resolver(db),
+                        data.type_ref_map(),
+                        data.type_ref_source_map(),
+                    );
+                }
+                ModuleDef::BuiltinType(_) => (),
+            }
+        }
+        for i in module.impls(db) {
+            let data = i.data(db.upcast());
+            self.collect_type_refs(
+                file_id,
+                &i.resolver(db),
+                data.type_ref_map(),
+                data.type_ref_source_map(),
+            );
+            for f in i.functions(db) {
+                self.collect_function(f);
+            }
+        }
+    }
+
+    fn collect_function(&mut self, function: Function) {
+        let data = function.data(self.db);
+        self.collect_type_refs(
+            function.id.file_id(self.db.upcast()),
+            &function.resolver(self.db),
+            data.type_ref_map(),
+            data.type_ref_source_map(),
+        );
+        self.collect_body(function);
+    }
+
+    /// Collects the references in the body of a function.
+    fn collect_body(&mut self, function: Function) {
+        let db = self.db;
+        let file_id = function.id.file_id(db.upcast());
+        let (body, source_map) = db.body_with_source_map(function.into());
+        let infer = function.infer(db);
+        let root = db.parse(file_id).syntax_node();
+
+        self.collect_type_refs(
+            file_id,
+            &function.resolver(db),
+            body.type_refs(),
+            source_map.type_refs(),
+        );
+
+        for (expr, data) in body.exprs() {
+            let node = || {
+                source_map.expr_syntax(expr).map(|src| {
+                    src.value
+                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr())
+                        .to_node(&root)
+                })
+            };
+            match (data, self.def) {
+                (Expr::Path(path), _) if path.as_ident() == Some(&self.name) => {
+                    let resolver = crate::expr::resolver_for_expr(body.clone(), db, expr);
+                    let resolution = resolver
+                        .resolve_path_without_assoc_items(db, path)
+                        .take_values();
+                    let is_reference = match (self.def, resolution) {
+                        (ReferenceDef::Local(local), Some(Resolution::LocalBinding(pat))) => {
+                            local.parent == function && local.pat_id == pat
+                        }
+                        (
+                            ReferenceDef::Function(f),
+                            Some(Resolution::Def(ModuleDef::Function(it))),
+                        ) => it == f,
+                        (ReferenceDef::Struct(s), Some(Resolution::Def(ModuleDef::Struct(it)))) => {
+                            it == s
+                        }
+                        _ => false,
+                    };
+                    if is_reference {
+                        if let Some(node) = node() {
+                            self.push(file_id, last_name_ref_range(&node));
+                        }
+                    }
+                }
+                (
+                    Expr::Field {
+                        expr: receiver,
+                        name,
+                    },
+                    ReferenceDef::StructField(field),
+                ) if *name == self.name && infer[*receiver].as_struct() == Some(field.parent) => {
+                    let range = node().and_then(ast::FieldExpr::cast).and_then(|it| {
+                        match it.field_access()? {
+                            ast::FieldKind::Name(name_ref) => Some(name_ref.syntax().text_range()),
+                            ast::FieldKind::Index(token) => {
+                                // The index token includes the leading `.`
+                                let range = token.text_range();
+                                Some(TextRange::from_to(
+                                    range.start() + TextUnit::from(1),
+                                    range.end(),
+                                ))
+                            }
+                        }
+                    });
+                    if let Some(range) = range {
+                        self.push(file_id, range);
+                    }
+                }
+                (Expr::RecordLit { fields, .. }, ReferenceDef::StructField(field))
+                    if infer[expr].as_struct() == Some(field.parent) =>
+                {
+                    for (idx, record_lit_field) in fields.iter().enumerate() {
+                        if record_lit_field.name != self.name {
+                            continue;
+                        }
+                        let record_field = source_map.field_syntax(expr, idx).to_node(&root);
+                        if let Some(name_ref) = record_field.name_ref() {
+                            self.push(file_id, name_ref.syntax().text_range());
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Collects the references to a struct from type references, e.g. in `let a: Foo`.
+    fn collect_type_refs(
+        &mut self,
+        file_id: FileId,
+        resolver: &Resolver,
+        type_ref_map: &TypeRefMap,
+        source_map: &TypeRefSourceMap,
+    ) {
+        let target = match self.def {
+            ReferenceDef::Struct(s) => s,
+            _ => return,
+        };
+        let root = self.db.parse(file_id).syntax_node();
+        for (id, type_ref) in type_ref_map.iter() {
+            let path = match type_ref {
+                TypeRef::Path(path) | TypeRef::Generic { path, .. } => path,
+                _ => continue,
+            };
+            if path.as_ident() != Some(&self.name)
+                || !is_struct_path(self.db, resolver, path, target)
+            {
+                continue;
+            }
+            if let Some(ptr) = source_map.type_ref_syntax(id) {
+                let node = ptr.syntax_node_ptr().to_node(&root);
+                self.push(file_id, last_name_ref_range(&node));
+            }
+        }
+    }
+
+    fn push(&mut self, file_id: FileId, range: TextRange) {
+        self.references.push(Reference { file_id, range });
+    }
+}
+
+/// Returns true if the path resolves to the specified struct in the type namespace.
+fn is_struct_path(
+    db: &dyn HirDatabase,
+    resolver: &Resolver,
+    path: &crate::Path,
+    target: Struct,
+) -> bool {
+    match resolver
+        .resolve_path_without_assoc_items(db, path)
+        .take_types()
+    {
+        Some(Resolution::Def(ModuleDef::Struct(s))) => s == target,
+        _ => false,
+    }
+}
+
+/// Returns the range of the last name in the specified node, e.g. `b` in `a::b`.
+pub(crate) fn last_name_ref_range(node: &SyntaxNode) -> TextRange {
+    node.descendants()
+        .filter_map(ast::NameRef::cast)
+        .last()
+        .map(|name_ref| name_ref.syntax().text_range())
+        .unwrap_or_else(|| node.text_range())
+}
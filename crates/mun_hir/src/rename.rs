@@ -0,0 +1,258 @@
+//! Renames a definition together with all references to it, e.g. to rename an identifier in an
+//! editor.
+
+use crate::{
+    adt::StructKind,
+    code_model::Local,
+    completion::function_at,
+    definition::{definition_for_token, navigation_target, token_at, Definition},
+    diagnostics::SourceChange,
+    expr::resolver_for_expr,
+    resolve::Resolution,
+    Expr, ExprId, FileId, Function, HirDatabase, Module, ModuleDef, Name, Reference, ReferenceDef,
+};
+use mun_syntax::{
+    ast::{self, edit, make},
+    tokenize, AstNode, SyntaxElement, SyntaxKind, SyntaxNode, TextEdit, TextRange, TextUnit,
+};
+use std::{collections::BTreeMap, fmt};
+
+/// The reason why a definition cannot be renamed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenameError(String);
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for RenameError {}
+
+/// Returns the changes that rename the definition that the name at the specified offset in a file
+/// refers to, or declares, to `new_name`. There is one change for every file that contains the
+/// definition or a reference to it.
+///
+/// Fails if `new_name` is not a valid identifier or if renaming would change the meaning of the
+/// code, e.g. because `new_name` is already bound in the scope of one of the references.
+pub fn rename(
+    db: &dyn HirDatabase,
+    file_id: FileId,
+    offset: TextUnit,
+    new_name: &str,
+) -> Result<Vec<SourceChange>, RenameError> {
+    let no_definition = || RenameError("there is no definition to rename here".to_string());
+    let root = db.parse(file_id).syntax_node();
+    let token = token_at(&root, offset).ok_or_else(no_definition)?;
+    let def = match definition_for_token(db, file_id, &token)
+        .ok_or_else(no_definition)?
+        .1
+    {
+        Definition::Def(def) => def,
+        Definition::BuiltinType(ty) => {
+            return Err(RenameError(format!("cannot rename builtin type `{}`", ty)))
+        }
+    };
+
+    if !is_identifier(new_name) {
+        return Err(RenameError(format!(
+            "`{}` is not a valid identifier",
+            new_name
+        )));
+    }
+    let new = Name::new_from_text(new_name);
+    let references = db.find_all_references(def);
+    check_conflicts(db, def, &new, &references)?;
+
+    // The declaration itself and all references, grouped by file
+    let declaration = navigation_target(db, def).ok_or_else(no_definition)?;
+    let mut edits: BTreeMap<FileId, Vec<TextEdit>> = BTreeMap::new();
+    for (file_id, range) in std::iter::once((declaration.file_id, declaration.focus_range))
+        .chain(references.iter().map(|r| (r.file_id, r.range)))
+    {
+        let root = db.parse(file_id).syntax_node();
+        if let Some(edit) = rename_edit(&root, range, def, new_name) {
+            edits.entry(file_id).or_default().push(edit);
+        }
+    }
+
+    Ok(edits
+        .into_iter()
+        .map(|(file_id, edits)| SourceChange {
+            label: format!("rename to `{}`", new_name),
+            file_id,
+            edits,
+        })
+        .collect())
+}
+
+/// Returns true if `text` is a single identifier, rather than e.g. a keyword.
+fn is_identifier(text: &str) -> bool {
+    match tokenize(text).as_slice() {
+        [token] => token.kind == SyntaxKind::IDENT,
+        _ => false,
+    }
+}
+
+/// Returns the edit that renames the name at `range`, which is either the declaration of `def` or
+/// a reference to it.
+fn rename_edit(
+    root: &SyntaxNode,
+    range: TextRange,
+    def: ReferenceDef,
+    new_name: &str,
+) -> Option<TextEdit> {
+    let node = match root.covering_element(range) {
+        SyntaxElement::Node(node) => node,
+        SyntaxElement::Token(token) => token.parent(),
+    };
+    if let Some(name) = node.ancestors().find_map(ast::Name::cast) {
+        return Some(edit::text_edit(&name, &make::name(new_name)));
+    }
+    let name_ref = node.ancestors().find_map(ast::NameRef::cast)?;
+
+    // A field shorthand like `Foo { a }` refers to both the field and the local binding, so only
+    // one of them is renamed by expanding it into `Foo { a: a }` first.
+    if let Some(record_field) = name_ref
+        .syntax()
+        .parent()
+        .and_then(ast::RecordField::cast)
+        .filter(|field| field.expr().is_none())
+    {
+        let old_name = name_ref.text().to_string();
+        let (field_name, local_name) = match def {
+            ReferenceDef::StructField(_) => (new_name, old_name.as_str()),
+            _ => (old_name.as_str(), new_name),
+        };
+        let new_field = make::record_field(
+            make::name_ref(field_name),
+            Some(make::expr_from_text(local_name)),
+        );
+        return Some(edit::text_edit(&record_field, &new_field));
+    }
+    Some(edit::text_edit(&name_ref, &make::name_ref(new_name)))
+}
+
+/// Returns an error if renaming `def` to `new` would change what a name refers to.
+fn check_conflicts(
+    db: &dyn HirDatabase,
+    def: ReferenceDef,
+    new: &Name,
+    references: &[Reference],
+) -> Result<(), RenameError> {
+    let already_bound = || RenameError(format!("`{}` is already bound in this scope", new));
+    match def {
+        ReferenceDef::Function(_) | ReferenceDef::Struct(_) => {
+            let is_declared = Module::from(def.file_id(db))
+                .declarations(db)
+                .into_iter()
+                .any(|decl| match decl {
+                    ModuleDef::Function(f) => f.name(db) == *new,
+                    ModuleDef::Struct(s) => s.name(db.upcast()) == *new,
+                    ModuleDef::BuiltinType(_) => false,
+                });
+            if is_declared {
+                return Err(RenameError(format!(
+                    "a definition named `{}` already exists in this module",
+                    new
+                )));
+            }
+
+            // A local binding with the new name would shadow the definition at a reference
+            for reference in references {
+                if let Some((function, expr)) = expr_at(db, reference.file_id, reference.range) {
+                    let body = function.body(db);
+                    if let Some(Resolution::LocalBinding(_)) = resolver_for_expr(body, db, expr)
+                        .resolve_name(db, new)
+                        .take_values()
+                    {
+                        return Err(already_bound());
+                    }
+                }
+            }
+            Ok(())
+        }
+        ReferenceDef::StructField(field) => {
+            if field.parent.data(db.upcast()).kind != StructKind::Record {
+                return Err(RenameError(
+                    "cannot rename the field of a tuple struct".to_string(),
+                ));
+            }
+            if field.parent.field(db, new).is_some() {
+                return Err(RenameError(format!(
+                    "a field named `{}` already exists in `{}`",
+                    new,
+                    field.parent.name(db.upcast())
+                )));
+            }
+            Ok(())
+        }
+        ReferenceDef::Local(local) => {
+            if has_local_conflict(db, local, new) {
+                Err(already_bound())
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Returns true if the new name is bound next to the local binding, if it is bound to another
+/// local binding where the binding is referred to, or if a name that refers to something else
+/// would refer to the renamed binding instead.
+fn has_local_conflict(db: &dyn HirDatabase, local: Local, new: &Name) -> bool {
+    let (function, pat) = (local.parent(), local.pat_id());
+    let body = function.body(db);
+    let scopes = db.expr_scopes(function.into());
+    let old = match local.name(db) {
+        Some(name) => name,
+        None => return false,
+    };
+
+    // Another binding of the same pattern or parameter list
+    if let Some(scope) = scopes.scope_for_pat(pat) {
+        if scopes
+            .entries(scope)
+            .iter()
+            .any(|entry| entry.name() == new && entry.pat() != pat)
+        {
+            return true;
+        }
+    }
+
+    for (expr, data) in body.exprs() {
+        let path = match data {
+            Expr::Path(path) => path,
+            _ => continue,
+        };
+        let resolver = resolver_for_expr(body.clone(), db, expr);
+        let refers_to_local = |name: &Name| match resolver.resolve_name(db, name).take_values() {
+            Some(Resolution::LocalBinding(it)) => Some(it == pat),
+            _ => None,
+        };
+        if path.as_ident() == Some(&old) && refers_to_local(&old) == Some(true) {
+            // The reference would refer to another binding
+            if refers_to_local(new).is_some() {
+                return true;
+            }
+        } else if path.as_ident() == Some(new) && refers_to_local(&old) == Some(true) {
+            // The name would refer to the renamed binding
+            return true;
+        }
+    }
+    false
+}
+
+/// Returns the innermost expression in the body of a function that contains the name at `range`.
+fn expr_at(db: &dyn HirDatabase, file_id: FileId, range: TextRange) -> Option<(Function, ExprId)> {
+    let function = function_at(db, file_id, range.start())?;
+    let source_map = function.body_source_map(db);
+    let root = db.parse(file_id).syntax_node();
+    let token = token_at(&root, range.start())?;
+    let expr = token
+        .parent()
+        .ancestors()
+        .filter_map(ast::Expr::cast)
+        .find_map(|node| source_map.node_expr(&node))?;
+    Some((function, expr))
+}
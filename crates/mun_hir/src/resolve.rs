@@ -1,6 +1,6 @@
 use crate::{
-    expr::scope::ScopeId, expr::PatId, ExprScopes, FileId, HirDatabase, ModuleDef, Name, Path,
-    PerNs,
+    expr::scope::ScopeId, expr::PatId, name::name, name_resolution::ModuleScope, ExprScopes,
+    FileId, HirDatabase, ModuleDef, Name, Path, PathKind, PerNs,
 };
 use std::sync::Arc;
 
@@ -81,9 +81,71 @@ impl Resolver {
         if let Some(name) = path.as_ident() {
             self.resolve_name(db, name)
         } else {
-            PerNs::none()
+            self.resolve_qualified_path(db, path)
         }
     }
+
+    /// Resolves a path that refers to an item of a module, like `ai::enemy::attack` or
+    /// `super::attack`. Functions of another module are linked through their export name, so only
+    /// public functions can be referred to from other modules. A path that starts with `core::`
+    /// and does not refer to a module of the package refers to a builtin type.
+    fn resolve_qualified_path(&self, db: &dyn HirDatabase, path: &Path) -> PerNs<Resolution> {
+        let (name, qualifier) = match path.segments.split_last() {
+            Some((last, qualifier)) => (&last.name, qualifier),
+            None => return PerNs::none(),
+        };
+        let file_id = match self.module_file_id() {
+            Some(file_id) => file_id,
+            None => return PerNs::none(),
+        };
+        let module_tree = db.module_tree(db.file_source_root(file_id));
+        let module = module_tree.module_for_file(file_id).and_then(|module| {
+            module_tree.resolve_module(
+                module,
+                &path.kind,
+                qualifier.iter().map(|segment| &segment.name),
+            )
+        });
+        let target_file_id = match module {
+            Some(module) => match module_tree.file(module) {
+                Some(target_file_id) => target_file_id,
+                None => return PerNs::none(),
+            },
+            None => {
+                return if path.kind == PathKind::Plain
+                    && qualifier.len() == 1
+                    && qualifier[0].name == name![core]
+                {
+                    ModuleScope::get_builtin(name)
+                        .map(|r| r.def)
+                        .unwrap_or_else(PerNs::none)
+                        .map(Resolution::Def)
+                } else {
+                    PerNs::none()
+                };
+            }
+        };
+
+        let def = db
+            .module_scope(target_file_id)
+            .get_declared(name)
+            .map(|r| r.def)
+            .unwrap_or_else(PerNs::none);
+        if let Some(ModuleDef::Function(f)) = def.values {
+            if target_file_id != file_id && f.visibility(db).is_private() {
+                return PerNs::none();
+            }
+        }
+        def.map(Resolution::Def)
+    }
+
+    /// Returns the file of the innermost module scope.
+    fn module_file_id(&self) -> Option<FileId> {
+        self.scopes.iter().rev().find_map(|scope| match scope {
+            Scope::ModuleScope(m) => Some(m.file_id),
+            _ => None,
+        })
+    }
 }
 
 impl Scope {
@@ -0,0 +1,112 @@
+//! Computes the signature of the function that is called at a position in a file, e.g. to show
+//! the parameters of a call in an editor while its arguments are typed.
+
+use crate::{
+    completion::{function_at, previous_non_trivia_token},
+    hover::function_signature,
+    CallableDef, Documentation, FileId, HirDatabase, HirDisplay, Struct,
+};
+use mun_syntax::{
+    ast::{self, ArgListOwner},
+    AstNode, TextRange, TextUnit, T,
+};
+
+/// The signature of a called function and the parameter that is being passed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureHelp {
+    /// The signature of the callee, e.g. `fn foo(a: i32, b: bool)`
+    pub label: String,
+    /// The ranges of the parameters within `label`
+    pub parameters: Vec<TextRange>,
+    /// The index of the parameter of the argument at the offset, or `None` if there are more
+    /// arguments than parameters
+    pub active_parameter: Option<usize>,
+    /// The documentation of the callee
+    pub docs: Option<Documentation>,
+}
+
+/// Returns the signature of the function or tuple struct constructor that is called by the
+/// innermost call whose argument list contains the specified offset.
+pub fn signature_help(
+    db: &dyn HirDatabase,
+    file_id: FileId,
+    offset: TextUnit,
+) -> Option<SignatureHelp> {
+    let root = db.parse(file_id).syntax_node();
+    let mut token = root.token_at_offset(offset).left_biased()?;
+    // Whitespace after an unclosed argument list, e.g. in `foo(a, `, is not part of the call
+    if token.kind().is_trivia() {
+        token = previous_non_trivia_token(&token)?;
+    }
+    let (call, arg_list) = token
+        .parent()
+        .ancestors()
+        .filter_map(ast::CallExpr::cast)
+        .find_map(|call| {
+            let arg_list = call.arg_list()?;
+            if is_in_arg_list(&arg_list, offset) {
+                Some((call, arg_list))
+            } else {
+                None
+            }
+        })?;
+
+    let function = function_at(db, file_id, offset)?;
+    let source_map = function.body_source_map(db);
+    let callee = source_map.node_expr(&call.expr()?)?;
+    let (label, parameters, docs) = match function.infer(db)[callee].as_callable_def()? {
+        CallableDef::Function(f) => {
+            let (label, parameters) = function_signature(db, f);
+            (label, parameters, f.docs(db.upcast()))
+        }
+        CallableDef::Struct(s) => {
+            let (label, parameters) = constructor_signature(db, s);
+            (label, parameters, s.docs(db.upcast()))
+        }
+    };
+
+    // The argument at the offset is preceded by one comma for every argument before it
+    let index = arg_list
+        .syntax()
+        .children_with_tokens()
+        .filter(|element| element.kind() == T![,] && element.text_range().end() <= offset)
+        .count();
+    Some(SignatureHelp {
+        label,
+        active_parameter: if index < parameters.len() {
+            Some(index)
+        } else {
+            None
+        },
+        parameters,
+        docs,
+    })
+}
+
+/// Returns true if the offset is between the parentheses of the argument list. The closing
+/// parenthesis is missing while the call is being typed.
+fn is_in_arg_list(arg_list: &ast::ArgList, offset: TextUnit) -> bool {
+    let range = arg_list.syntax().text_range();
+    let is_closed = arg_list
+        .syntax()
+        .last_token()
+        .map_or(false, |token| token.kind() == T![')']);
+    range.start() < offset && (offset < range.end() || !is_closed)
+}
+
+/// Returns the signature of the constructor of a tuple struct, e.g. `struct Foo(i32, f32)`,
+/// together with the ranges of its fields within the signature.
+fn constructor_signature(db: &dyn HirDatabase, s: Struct) -> (String, Vec<TextRange>) {
+    let mut signature = format!("struct {}(", s.name(db.upcast()));
+    let mut fields = Vec::new();
+    for (index, field) in s.fields(db).into_iter().enumerate() {
+        if index > 0 {
+            signature.push_str(", ");
+        }
+        let start = TextUnit::of_str(&signature);
+        signature.push_str(&field.ty(db).display(db).to_string());
+        fields.push(TextRange::from_to(start, TextUnit::of_str(&signature)));
+    }
+    signature.push(')');
+    (signature, fields)
+}
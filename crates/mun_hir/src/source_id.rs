@@ -1,6 +1,7 @@
 use crate::in_file::InFile;
 use crate::{db::DefDatabase, Arena, FileId, RawId};
 use mun_syntax::{ast, AstNode, AstPtr, SyntaxNode, SyntaxNodePtr};
+use rustc_hash::FxHashMap;
 use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 use std::sync::Arc;
@@ -51,11 +52,21 @@ impl<N: AstNode> FileAstId<N> {
 }
 
 /// Maps items' `SyntaxNode`s to `ErasedFileAstId`s and back.
-#[derive(Debug, PartialEq, Eq, Default)]
+#[derive(Debug, Default)]
 pub struct AstIdMap {
     arena: Arena<ErasedFileAstId, SyntaxNodePtr>,
+    /// The reverse of `arena`, to look up the id of a node in constant time
+    map: FxHashMap<SyntaxNodePtr, ErasedFileAstId>,
 }
 
+impl PartialEq for AstIdMap {
+    fn eq(&self, other: &Self) -> bool {
+        // `map` is derived from `arena`
+        self.arena == other.arena
+    }
+}
+impl Eq for AstIdMap {}
+
 /// An id of an AST node in a specific file.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ErasedFileAstId(RawId);
@@ -78,8 +89,8 @@ impl AstIdMap {
 
     pub(crate) fn ast_id<N: AstNode>(&self, item: &N) -> FileAstId<N> {
         let ptr = SyntaxNodePtr::new(item.syntax());
-        let raw = match self.arena.iter().find(|(_id, i)| **i == ptr) {
-            Some((it, _)) => it,
+        let raw = match self.map.get(&ptr) {
+            Some(it) => *it,
             None => panic!(
                 "Can't find {:?} in AstIdMap:\n{:?}",
                 item.syntax(),
@@ -104,8 +115,8 @@ impl AstIdMap {
         // change parent's id. This means that, say, adding a new function to a
         // trait does not change ids of top-level items, which helps caching.
         bfs(node, |it| {
-            if let Some(module_item) = ast::ModuleItem::cast(it) {
-                res.alloc(module_item.syntax());
+            if is_item(&it) {
+                res.alloc(&it);
             }
         });
         res
@@ -118,10 +129,21 @@ impl AstIdMap {
 
     /// Constructs a new `ErasedFileAstId` from a `SyntaxNode`
     fn alloc(&mut self, item: &SyntaxNode) -> ErasedFileAstId {
-        self.arena.alloc(SyntaxNodePtr::new(item))
+        let ptr = SyntaxNodePtr::new(item);
+        let id = self.arena.alloc(ptr);
+        self.map.insert(ptr, id);
+        id
     }
 }
 
+/// Returns true if the node is an item that gets an id: a declaration at the top level of a file,
+/// a function in an impl block or a field of a struct.
+fn is_item(node: &SyntaxNode) -> bool {
+    ast::ModuleItem::can_cast(node.kind())
+        || ast::RecordFieldDef::can_cast(node.kind())
+        || ast::TupleFieldDef::can_cast(node.kind())
+}
+
 /// Walks the subtree in bfs order, calling `f` for each node.
 fn bfs(node: &SyntaxNode, mut f: impl FnMut(SyntaxNode)) {
     let mut curr_layer = vec![node.clone()];
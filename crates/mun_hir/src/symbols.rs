@@ -0,0 +1,145 @@
+//! Builds the outline of the items in a file, e.g. to show the structure of a file in an editor.
+
+use crate::{
+    raw::{DefKind, RawFileItem},
+    FileId, HirDatabase,
+};
+use mun_syntax::{
+    ast::{self, FunctionDefOwner, NameOwner, TypeAscriptionOwner},
+    AstNode, TextRange,
+};
+
+/// The kind of a `DocumentSymbol`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    Function,
+    Struct,
+    Field,
+    Impl,
+}
+
+/// An item in the outline of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Additional information about the symbol, e.g. the parameters of a function or the type of a
+    /// field
+    pub detail: Option<String>,
+    /// The range of the whole item, e.g. including the body of a function
+    pub full_range: TextRange,
+    /// The range of the name of the item
+    pub focus_range: TextRange,
+    /// The symbols that are nested in the item, e.g. the fields of a struct
+    pub children: Vec<DocumentSymbol>,
+}
+
+/// Returns the outline of the items of a file in the order in which they are declared. Structs
+/// contain their fields and impl blocks contain their functions.
+pub fn document_symbols(db: &dyn HirDatabase, file_id: FileId) -> Vec<DocumentSymbol> {
+    let raw_items = db.raw_items(file_id);
+    let ast_id_map = db.ast_id_map(file_id);
+    let root = db.parse(file_id).syntax_node();
+    raw_items
+        .items()
+        .iter()
+        .filter_map(|item| match *item {
+            RawFileItem::Definition(def) => match raw_items[def].kind {
+                DefKind::Function(ast_id) => {
+                    function_symbol(&ast_id_map.get(ast_id).to_node(&root))
+                }
+                DefKind::Struct(ast_id) => struct_symbol(&ast_id_map.get(ast_id).to_node(&root)),
+            },
+            RawFileItem::Impl(ast_id) => Some(impl_symbol(&ast_id_map.get(ast_id).to_node(&root))),
+        })
+        .collect()
+}
+
+fn function_symbol(function: &ast::FunctionDef) -> Option<DocumentSymbol> {
+    let mut detail = format!("fn{}", function.param_list()?.syntax());
+    if let Some(ty) = function.ret_type().and_then(|ret| ret.type_ref()) {
+        detail.push_str(&format!(" -> {}", ty.syntax()));
+    }
+    symbol(function, SymbolKind::Function, Some(detail), Vec::new())
+}
+
+fn struct_symbol(struct_def: &ast::StructDef) -> Option<DocumentSymbol> {
+    let fields = match struct_def.kind() {
+        ast::StructKind::Record(fields) => fields
+            .fields()
+            .filter_map(|field| {
+                let detail = field.ascribed_type().map(|ty| ty.syntax().to_string());
+                symbol(&field, SymbolKind::Field, detail, Vec::new())
+            })
+            .collect(),
+        // The fields of a tuple struct are named by their index
+        ast::StructKind::Tuple(fields) => fields
+            .fields()
+            .enumerate()
+            .map(|(index, field)| {
+                let range = field.syntax().text_range();
+                DocumentSymbol {
+                    name: index.to_string(),
+                    kind: SymbolKind::Field,
+                    detail: field.type_ref().map(|ty| ty.syntax().to_string()),
+                    full_range: range,
+                    focus_range: range,
+                    children: Vec::new(),
+                }
+            })
+            .collect(),
+        ast::StructKind::Unit => Vec::new(),
+    };
+    symbol(struct_def, SymbolKind::Struct, None, fields)
+}
+
+fn impl_symbol(impl_def: &ast::ImplDef) -> DocumentSymbol {
+    let type_text = |ty: Option<ast::TypeRef>| {
+        ty.map_or_else(|| "{unknown}".to_string(), |ty| ty.syntax().to_string())
+    };
+    let name = match impl_def.target_trait() {
+        Some(target_trait) => format!(
+            "impl {} for {}",
+            target_trait.syntax(),
+            type_text(impl_def.target_type())
+        ),
+        None => format!("impl {}", type_text(impl_def.target_type())),
+    };
+    let functions = impl_def
+        .item_list()
+        .map(|items| {
+            items
+                .functions()
+                .filter_map(|f| function_symbol(&f))
+                .collect()
+        })
+        .unwrap_or_default();
+    let range = impl_def.syntax().text_range();
+    DocumentSymbol {
+        name,
+        kind: SymbolKind::Impl,
+        detail: None,
+        full_range: range,
+        focus_range: range,
+        children: functions,
+    }
+}
+
+/// Returns the symbol of a named item, or `None` if the item has no name because of a syntax
+/// error.
+fn symbol<N: NameOwner>(
+    node: &N,
+    kind: SymbolKind,
+    detail: Option<String>,
+    children: Vec<DocumentSymbol>,
+) -> Option<DocumentSymbol> {
+    let name = node.name()?;
+    Some(DocumentSymbol {
+        name: name.text().to_string(),
+        kind,
+        detail,
+        full_range: node.syntax().text_range(),
+        focus_range: name.syntax().text_range(),
+        children,
+    })
+}
@@ -46,3 +46,1380 @@ fn check_module_data_does_not_change() {
         )
     }
 }
+
+#[test]
+fn item_attributes_are_collected() {
+    use crate::{attrs::AttrArg, ModuleDef, Name};
+
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    #[derive(Eq, Ord)]
+    struct Foo;
+
+    #[inline(always)]
+    #[export(name = "bar", version = 2)]
+    fn foo() {}
+    "#,
+    );
+
+    let definitions = db.module_data(file_id).definitions().to_vec();
+    let (s, f) = match definitions.as_slice() {
+        [ModuleDef::Struct(s), ModuleDef::Function(f)] => (*s, *f),
+        _ => panic!("expected a struct and a function"),
+    };
+
+    let struct_attrs = s.attrs(&db);
+    let derive = struct_attrs
+        .by_name(&Name::new_from_text("derive"))
+        .next()
+        .expect("expected a derive attribute");
+    assert_eq!(
+        derive.args,
+        vec![
+            AttrArg::Ident(Name::new_from_text("Eq")),
+            AttrArg::Ident(Name::new_from_text("Ord"))
+        ]
+    );
+
+    let fn_attrs = f.attrs(&db);
+    assert_eq!(fn_attrs.iter().count(), 2);
+    assert!(fn_attrs.has(&Name::new_from_text("inline")));
+    let export = fn_attrs
+        .by_name(&Name::new_from_text("export"))
+        .next()
+        .expect("expected an export attribute");
+    assert_eq!(
+        export
+            .value(&Name::new_from_text("name"))
+            .map(|v| v.as_str()),
+        Some("bar")
+    );
+    assert_eq!(
+        export
+            .value(&Name::new_from_text("version"))
+            .map(|v| v.as_str()),
+        Some("2")
+    );
+}
+
+#[test]
+fn function_export_name() {
+    use crate::ModuleDef;
+
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    #[export(name = "on_update")]
+    pub fn update() {}
+
+    pub fn main() {}
+    "#,
+    );
+
+    let names: Vec<String> = db
+        .module_data(file_id)
+        .definitions()
+        .iter()
+        .filter_map(|def| match def {
+            ModuleDef::Function(f) => Some(f.export_name(&db)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(names, vec!["on_update".to_string(), "main".to_string()]);
+}
+
+#[test]
+fn duplicate_export_name() {
+    use crate::{diagnostics::DiagnosticSink, Module};
+
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    #[export(name = "on_update")]
+    pub fn update() {}
+
+    pub fn on_update() {}
+    "#,
+    );
+
+    let mut messages = Vec::new();
+    let mut sink = DiagnosticSink::new(|diag| messages.push(diag.message()));
+    Module::from(file_id).diagnostics(&db, &mut sink);
+    drop(sink);
+
+    assert_eq!(
+        messages,
+        vec!["the export name `on_update` is used multiple times".to_string()]
+    );
+}
+
+#[test]
+fn unused_private_functions() {
+    use crate::{diagnostics::DiagnosticSink, Module};
+
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    pub fn main() -> i32 { helper() }
+
+    fn helper() -> i32 { 5 }
+
+    fn unused() {}
+
+    fn recursive(n: i32) -> i32 { recursive(n) }
+
+    fn _ignored() {}
+
+    extern fn host_fn();
+    "#,
+    );
+
+    let mut messages = Vec::new();
+    let mut sink = DiagnosticSink::new(|diag| messages.push(diag.message()));
+    Module::from(file_id).diagnostics(&db, &mut sink);
+    drop(sink);
+
+    assert_eq!(
+        messages,
+        vec![
+            "function `unused` is never used".to_string(),
+            "function `recursive` is never used".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn item_docs_are_collected() {
+    use crate::ModuleDef;
+
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    /// A point in space
+    ///
+    /// Points are value types.
+    struct(value) Point {
+        /// The horizontal coordinate
+        x: f32,
+        y: f32,
+    }
+
+    /** Creates a point */
+    // A regular comment
+    fn new_point() -> Point { Point { x: 0.0, y: 0.0 } }
+
+    // A regular comment
+    fn foo() {}
+    "#,
+    );
+
+    let definitions = db.module_data(file_id).definitions().to_vec();
+    let (s, new_point, foo) = match definitions.as_slice() {
+        [ModuleDef::Struct(s), ModuleDef::Function(a), ModuleDef::Function(b)] => (*s, *a, *b),
+        _ => panic!("expected a struct and two functions"),
+    };
+
+    let docs = |docs: Option<crate::Documentation>| docs.map(|it| it.as_str().to_string());
+    assert_eq!(
+        docs(s.docs(&db)),
+        Some("A point in space\n\nPoints are value types.".to_string())
+    );
+    let fields: Vec<_> = s
+        .fields(&db)
+        .into_iter()
+        .map(|f| docs(f.docs(&db)))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![Some("The horizontal coordinate".to_string()), None]
+    );
+    assert_eq!(
+        docs(new_point.docs(&db)),
+        Some("Creates a point".to_string())
+    );
+    assert_eq!(docs(foo.docs(&db)), None);
+}
+
+#[test]
+fn field_renames_are_collected() {
+    use crate::{ModuleDef, Name};
+
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    struct Point {
+        /// The horizontal coordinate
+        #[rename_from(x)]
+        left: f32,
+        #[rename_from]
+        top: f32,
+        z: f32,
+    }
+    "#,
+    );
+
+    let definitions = db.module_data(file_id).definitions().to_vec();
+    let s = match definitions.as_slice() {
+        [ModuleDef::Struct(s)] => *s,
+        _ => panic!("expected a struct"),
+    };
+
+    let fields: Vec<_> = s
+        .fields(&db)
+        .into_iter()
+        .map(|f| (f.renamed_from(&db), f.docs(&db).is_some()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            (Some(Name::new_from_text("x")), true),
+            (None, false),
+            (None, false)
+        ]
+    );
+}
+
+#[test]
+fn constant_expressions_are_folded() {
+    use crate::{expr::LiteralInt, Expr, Literal, ModuleDef};
+
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    fn arithmetic() -> i32 { (1 + 2) * 3 - 4 / 2 }
+    fn negative() -> i32 { -(10 % 3) }
+    fn bits() -> u8 { !0 & (1 << 4) }
+    fn propagated() -> i32 { let a = 2; let b = a * 21; b }
+    fn comparison() -> bool { 1 < 2 && !(3.0 == 4.0) }
+    fn float() -> f64 { 1.5 * 2.0 }
+    fn assigned() -> i32 { let a = 2; a = 3; a }
+    fn overflow() -> u8 { 255 + 1 }
+    fn division_by_zero() -> i32 { 1 / 0 }
+    fn shift_out_of_range() -> i32 { 1 << 32 }
+    fn parameter(a: i32) -> i32 { a + 1 }
+    "#,
+    );
+
+    let values: Vec<_> = db
+        .module_data(file_id)
+        .definitions()
+        .iter()
+        .map(|def| {
+            let function = match def {
+                ModuleDef::Function(function) => *function,
+                _ => panic!("expected a function"),
+            };
+            let body = function.body(&db);
+            let tail = match &body[body.body_expr()] {
+                Expr::Block {
+                    tail: Some(tail), ..
+                } => *tail,
+                _ => panic!("expected a block with a tail expression"),
+            };
+            function.const_eval(&db).value(tail).cloned()
+        })
+        .collect();
+
+    let int = |value: u128| {
+        Some(Literal::Int(LiteralInt {
+            kind: crate::expr::LiteralIntKind::Unsuffixed,
+            value,
+        }))
+    };
+    assert_eq!(
+        values,
+        vec![
+            int(7),
+            int(-1i128 as u128),
+            int(16),
+            int(42),
+            Some(Literal::Bool(true)),
+            Some(Literal::Float(crate::expr::LiteralFloat {
+                kind: crate::expr::LiteralFloatKind::Unsuffixed,
+                value: 3.0
+            })),
+            None,
+            None,
+            None,
+            None,
+            None,
+        ]
+    );
+}
+
+#[test]
+fn lint_levels() {
+    use crate::{
+        diagnostics::{DiagnosticSink, Severity},
+        lint::{self, LintLevel, LintLevels},
+        HirDatabase, Module,
+    };
+
+    let (mut db, file_id) = MockDatabase::with_single_file(
+        r#"
+    pub fn main(a: i32) -> bool {
+        let unused = 1;
+        a == a
+    }
+
+    #[allow(unused_variables)]
+    #[warn(shadowing)]
+    pub fn shadowing(a: i32) {
+        let a = a + 1;
+        let b = 2;
+    }
+
+    #[deny(self_comparison, unknown)]
+    pub fn self_comparison(a: i32, b: f32) -> bool {
+        a <= a && b != b
+    }
+
+    #[allow(dead_code)]
+    fn unused() {}
+    "#,
+    );
+
+    let diagnostics = |db: &MockDatabase| {
+        let mut messages = Vec::new();
+        let mut sink = DiagnosticSink::new(|diag| {
+            let severity = match diag.severity() {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            messages.push(format!("{}: {}", severity, diag.message()))
+        });
+        Module::from(file_id).diagnostics(db, &mut sink);
+        drop(sink);
+        messages
+    };
+
+    assert_eq!(
+        diagnostics(&db),
+        vec![
+            "warning: unused variable: `unused`",
+            "warning: comparing `a` with itself always has the same result",
+            "warning: `a` shadows an earlier binding",
+            "error: comparing `a` with itself always has the same result",
+            "warning: unknown lint: `unknown`",
+        ]
+    );
+
+    // Package-wide levels are overridden by attributes
+    let mut levels = LintLevels::default();
+    levels.set(&lint::UNUSED_VARIABLES, LintLevel::Deny);
+    levels.set(&lint::SELF_COMPARISON, LintLevel::Allow);
+    levels.set(&lint::DEAD_CODE, LintLevel::Deny);
+    db.set_lint_levels(Arc::new(levels));
+
+    assert_eq!(
+        diagnostics(&db),
+        vec![
+            "error: unused variable: `unused`",
+            "warning: `a` shadows an earlier binding",
+            "error: comparing `a` with itself always has the same result",
+            "warning: unknown lint: `unknown`",
+        ]
+    );
+}
+
+#[test]
+fn find_all_references() {
+    use crate::{HirDatabase, Local, ModuleDef, Pat, ReferenceDef};
+
+    let text = r#"
+    struct Point { x: f32, y: f32 }
+    struct Meter(f32);
+
+    fn new_point(x: f32) -> Point {
+        Point { x, y: 0.0 }
+    }
+
+    pub fn main() -> f32 {
+        let point: Point = new_point(1.0);
+        let length = Meter(point.x);
+        point.x + length.0 + new_point(point.y).x
+    }
+    "#;
+    let (db, file_id) = MockDatabase::with_single_file(text);
+
+    let definitions = db.module_data(file_id).definitions().to_vec();
+    let (point, meter, new_point, main) = match definitions.as_slice() {
+        [ModuleDef::Struct(point), ModuleDef::Struct(meter), ModuleDef::Function(new_point), ModuleDef::Function(main)] => {
+            (*point, *meter, *new_point, *main)
+        }
+        _ => panic!("expected two structs and two functions"),
+    };
+    let body = main.body(&db);
+    let local_point = body
+        .pats()
+        .find_map(|(pat, data)| match data {
+            Pat::Bind { name } if name.to_string() == "point" => Some(Local::new(main, pat)),
+            _ => None,
+        })
+        .unwrap();
+
+    let references = |def: ReferenceDef| {
+        db.find_all_references(def)
+            .iter()
+            .map(|reference| {
+                assert_eq!(reference.file_id, file_id);
+                let start = reference.range.start().to_usize();
+                let end = reference.range.end().to_usize();
+                format!("{}: {}", start, &text[start..end])
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        references(point.into()),
+        vec!["89: Point", "105: Point", "178: Point"]
+    );
+    assert_eq!(references(meter.into()), vec!["223: Meter"]);
+    assert_eq!(
+        references(new_point.into()),
+        vec!["186: new_point", "268: new_point"]
+    );
+    let x = point.field(&db, &crate::Name::new_from_text("x")).unwrap();
+    assert_eq!(
+        references(x.into()),
+        vec!["113: x", "235: x", "253: x", "287: x"]
+    );
+    let field_0 = meter.field(&db, &crate::Name::new_tuple_field(0)).unwrap();
+    assert_eq!(references(field_0.into()), vec!["264: 0"]);
+    assert_eq!(
+        references(local_point.into()),
+        vec!["229: point", "247: point", "278: point"]
+    );
+}
+
+#[test]
+fn call_graph() {
+    use crate::{CallableDef, HirDatabase, ModuleDef, SourceRootId};
+
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    struct Point { x: f32, y: f32 }
+    struct Meter(f32);
+
+    pub fn main() -> f32 {
+        let point = new_point();
+        helper(point.x) + helper(point.y)
+    }
+
+    fn new_point() -> Point { Point { x: 1.0, y: 2.0 } }
+
+    fn helper(x: f32) -> f32 { Meter(x).0 }
+
+    fn unused() { unused() }
+    "#,
+    );
+
+    let definitions = db.module_data(file_id).definitions().to_vec();
+    let (point, meter, main, new_point, helper, unused) = match definitions.as_slice() {
+        [ModuleDef::Struct(point), ModuleDef::Struct(meter), ModuleDef::Function(main), ModuleDef::Function(new_point), ModuleDef::Function(helper), ModuleDef::Function(unused)] => {
+            (*point, *meter, *main, *new_point, *helper, *unused)
+        }
+        _ => panic!("expected two structs and four functions"),
+    };
+
+    let call_graph = db.call_graph(SourceRootId(0));
+    assert_eq!(call_graph.functions(), &[main, new_point, helper, unused]);
+
+    let sorted = |mut callees: Vec<CallableDef>| {
+        callees.sort_by_key(|callee| format!("{:?}", callee));
+        callees
+    };
+    assert_eq!(
+        sorted(call_graph.callees(main).to_vec()),
+        sorted(vec![new_point.into(), helper.into()])
+    );
+    assert_eq!(call_graph.callees(new_point), &[point.into()]);
+    assert_eq!(call_graph.callees(helper), &[meter.into()]);
+    assert_eq!(call_graph.callees(unused), &[unused.into()]);
+    assert_eq!(
+        call_graph.callers(helper.into()).collect::<Vec<_>>(),
+        vec![main]
+    );
+
+    let reachable = call_graph.reachable_from(vec![main]);
+    assert!(reachable.contains(&main));
+    assert!(reachable.contains(&new_point));
+    assert!(reachable.contains(&helper));
+    assert!(!reachable.contains(&unused));
+
+    assert!(call_graph.is_recursive(unused));
+    assert!(!call_graph.is_recursive(main));
+}
+
+#[test]
+fn file_dependencies() {
+    use crate::HirDatabase;
+
+    let (db, file_ids) = MockDatabase::with_files(&[
+        (
+            "main.mun",
+            r#"
+    pub fn main() -> i32 {
+        let square = shapes::square(2);
+        square.side
+    }
+    "#,
+        ),
+        (
+            "shapes.mun",
+            r#"
+    pub struct Square { side: i32 }
+
+    pub fn square(side: i32) -> Square { Square { side: side * super::units::scale() } }
+    "#,
+        ),
+        ("units.mun", "pub fn scale() -> i32 { 1 }"),
+        (
+            "plane.mun",
+            "pub struct Plane { side: super::shapes::Square }",
+        ),
+    ]);
+
+    assert_eq!(*db.file_dependencies(file_ids[0]), vec![file_ids[1]]);
+    assert_eq!(*db.file_dependencies(file_ids[1]), vec![file_ids[2]]);
+    assert_eq!(*db.file_dependencies(file_ids[2]), vec![]);
+    assert_eq!(*db.file_dependencies(file_ids[3]), vec![file_ids[1]]);
+}
+
+#[test]
+fn pretty_print_module() {
+    use crate::Module;
+
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    struct Foo { a: i32 }
+
+    fn main(b: i32) -> i32 {
+        let foo = Foo { a: b };
+        let c = foo.a * 2;
+        if c > 10 {
+            return -c;
+        }
+        loop {
+            break c + 1;
+        }
+    }
+
+    fn unknown() {
+        let x = y;
+    }
+    "#,
+    );
+
+    insta::assert_snapshot!(Module::from(file_id).pretty_print(&db));
+}
+
+#[test]
+fn classify_identifiers() {
+    use crate::HirDatabase;
+
+    let text = r#"
+    struct Foo { a: i32 }
+
+    fn main(b: i32) -> Foo {
+        let a = b + helper();
+        b += a;
+        let foo = Foo { a };
+        foo.a = Foo { a: 1 }.a;
+        foo
+    }
+
+    fn helper() -> i32 { 5 }
+    "#;
+    let (db, file_id) = MockDatabase::with_single_file(text);
+
+    let idents = db
+        .classify_identifiers(file_id)
+        .iter()
+        .map(|ident| {
+            let range = ident.range.start().to_usize()..ident.range.end().to_usize();
+            let mutable = if ident.mutable { " mutable" } else { "" };
+            format!("{} {:?}{}", &text[range], ident.class, mutable)
+        })
+        .collect::<Vec<_>>();
+    insta::assert_debug_snapshot!(idents);
+}
+
+#[test]
+fn mismatched_return_type_label() {
+    use crate::{
+        diagnostics::{DiagnosticSink, Severity},
+        Module,
+    };
+
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    fn foo() -> i32 {
+        return false;
+    }
+
+    fn bar() -> i32 {
+        let a: bool = 3;
+        0
+    }
+    "#,
+    );
+
+    let text = db.file_text(file_id);
+    let mut labels = Vec::new();
+    let mut sink = DiagnosticSink::new(|diag| {
+        if diag.severity() == Severity::Error {
+            labels.push(
+                diag.secondary_labels()
+                    .into_iter()
+                    .map(|label| (text[label.range].to_string(), label.message))
+                    .collect::<Vec<_>>(),
+            )
+        }
+    });
+    Module::from(file_id).diagnostics(&db, &mut sink);
+    drop(sink);
+
+    assert_eq!(
+        labels,
+        vec![
+            vec![(
+                "i32".to_string(),
+                "expected because of this return type".to_string()
+            )],
+            vec![]
+        ]
+    );
+}
+
+#[test]
+fn diagnostic_fixes() {
+    use crate::{
+        diagnostics::{apply_edits, DiagnosticSink},
+        Module,
+    };
+
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    struct Foo { a: i32, b: f64, c: bool }
+
+    pub fn main(n: i32) -> Foo {
+        let unused = 1;
+        if n = 3 {
+            Foo { a: n }
+        } else {
+            Foo {}
+        }
+    }
+    "#,
+    );
+
+    let mut labels = Vec::new();
+    let mut edits = Vec::new();
+    let mut sink = DiagnosticSink::new(|diag| {
+        if let Some(fix) = diag.fix(&db) {
+            assert_eq!(fix.file_id, file_id);
+            labels.push(fix.label);
+            edits.extend(fix.edits);
+        }
+    });
+    Module::from(file_id).diagnostics(&db, &mut sink);
+    drop(sink);
+
+    labels.sort();
+    assert_eq!(
+        labels,
+        vec![
+            "add missing fields".to_string(),
+            "add missing fields".to_string(),
+            "change `=` to `==`".to_string(),
+            "rename to `_unused`".to_string(),
+        ]
+    );
+    assert_eq!(
+        apply_edits(&db.file_text(file_id), &edits),
+        r#"
+    struct Foo { a: i32, b: f64, c: bool }
+
+    pub fn main(n: i32) -> Foo {
+        let _unused = 1;
+        if n == 3 {
+            Foo { a: n, b: 0.0, c: false }
+        } else {
+            Foo { a: 0, b: 0.0, c: false }
+        }
+    }
+    "#
+    );
+}
+
+#[test]
+fn module_tree_resolution() {
+    use crate::{
+        diagnostics::{DiagnosticSink, Severity},
+        Module,
+    };
+
+    let (db, file_ids) = MockDatabase::with_files(&[
+        (
+            "main.mun",
+            r#"
+    pub fn main() -> i32 {
+        let loc: core::SourceLocation = core::source_location();
+        ai::enemy::attack(ai::Target { health: 10 }) + ::ai::enemy::speed()
+    }
+
+    fn health() -> i32 { 100 }
+    "#,
+        ),
+        (
+            "ai/mod.mun",
+            r#"
+    pub struct Target { health: i32 }
+
+    pub fn max_health() -> i32 { super::health() }   // error: private function
+    "#,
+        ),
+        (
+            "ai/enemy.mun",
+            r#"
+    pub fn attack(target: super::Target) -> i32 { target.health - damage() }
+
+    pub fn speed() -> i32 { self::damage() * 2 }
+
+    fn damage() -> i32 { 5 }
+
+    pub fn retreat() { ai::enemy::hide() }             // error: unknown function
+    "#,
+        ),
+    ]);
+
+    let tree = db.module_tree(db.file_source_root(file_ids[0]));
+    let enemy = tree.module_for_file(file_ids[2]).unwrap();
+    assert_eq!(tree.module_for_file(file_ids[0]), Some(tree.root()));
+    assert_eq!(
+        tree.path(enemy)
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>(),
+        vec!["ai".to_string(), "enemy".to_string()]
+    );
+    assert_eq!(tree.file(tree.parent(enemy).unwrap()), Some(file_ids[1]));
+
+    let mut messages = Vec::new();
+    let mut sink = DiagnosticSink::new(|diag| {
+        if diag.severity() == Severity::Error {
+            messages.push(diag.message())
+        }
+    });
+    for file_id in file_ids {
+        Module::from(file_id).diagnostics(&db, &mut sink);
+    }
+    drop(sink);
+
+    assert_eq!(
+        messages,
+        vec![
+            "undefined value".to_string(),
+            "expected function type".to_string(),
+            "undefined value".to_string(),
+            "expected function type".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn completions() {
+    use mun_syntax::TextUnit;
+
+    // Returns the completions at the `$0` marker in `text`, the completions whose type matches
+    // the expected type are marked with a `*`.
+    let completions = |text: &str| {
+        let offset = text.find("$0").unwrap();
+        let text = text.replace("$0", "");
+        let (db, file_id) = MockDatabase::with_single_file(&text);
+        crate::completions(&db, file_id, TextUnit::from_usize(offset))
+            .into_iter()
+            .map(|item| {
+                format!(
+                    "{}{} {:?}{}",
+                    if item.type_matches { "*" } else { "" },
+                    item.label,
+                    item.kind,
+                    item.detail.map(|d| format!(" {}", d)).unwrap_or_default()
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let text = r#"
+    struct Foo { a: i32, b: bool }
+
+    fn helper(foo: Foo) -> i32 { foo.a }
+
+    fn main(x: i32) {
+        let y = true;
+        let foo = Foo { a: x, b: y };
+        let z: i32 = $0
+        let w = 1;
+    }
+    "#;
+    assert_eq!(
+        completions(text),
+        vec![
+            "*x Parameter i32",
+            "*helper Function function helper(Foo) -> i32",
+            "foo Local Foo",
+            "y Local bool",
+            "main Function function main(i32) -> nothing",
+            "Foo Struct",
+            "break Keyword",
+            "continue Keyword",
+            "else Keyword",
+            "false Keyword",
+            "if Keyword",
+            "let Keyword",
+            "loop Keyword",
+            "return Keyword",
+            "true Keyword",
+            "while Keyword",
+        ]
+    );
+
+    let text = r#"
+    struct Foo { a: i32, b: bool }
+
+    fn main(foo: Foo) -> bool {
+        if foo.$0 { true } else { false }
+    }
+    "#;
+    assert_eq!(completions(text), vec!["*b Field bool", "a Field i32"]);
+
+    let text = r#"
+    struct Foo { a: i32 }
+
+    fn main() {
+        let a: F$0
+    }
+    "#;
+    let types = completions(text);
+    assert!(types.contains(&"Foo Struct".to_string()));
+    assert!(types.contains(&"i32 BuiltinType".to_string()));
+    assert!(!types.iter().any(|item| item.starts_with("main")));
+
+    // Bindings are only visible after their declaration and inside their block
+    let text = r#"
+    fn main(a: i32) {
+        {
+            let b = a;
+        }
+        let c = $0;
+        let d = 1;
+    }
+    "#;
+    let locals = completions(text)
+        .into_iter()
+        .filter(|item| item.contains("Local") || item.contains("Parameter"))
+        .collect::<Vec<_>>();
+    assert_eq!(locals, vec!["a Parameter i32"]);
+
+    assert_eq!(
+        completions("\n$0\nfn main() {}\n"),
+        vec![
+            "fn Keyword",
+            "impl Keyword",
+            "pub Keyword",
+            "struct Keyword"
+        ]
+    );
+    assert!(completions("fn main() { // a$0\n}").is_empty());
+}
+
+#[test]
+fn hover() {
+    use mun_syntax::TextUnit;
+
+    let text = r#"
+    /// A point in space
+    struct Point { x: f32, y: f32 }
+
+    /// Creates a point on the x-axis
+    pub fn new_point(x: f32) -> Point {
+        Point { x, y: 0.0 }
+    }
+
+    fn main() {
+        let point: Point = new_point(1.0);
+        let sum = point.x + point.y;
+    }
+    "#;
+    let (db, file_id) = MockDatabase::with_single_file(text);
+
+    // Returns the hovered text, the signature and the documentation at the first occurrence of
+    // `pattern` in the text, offset by `offset`.
+    let hover = |pattern: &str, offset: usize| {
+        let offset = text.find(pattern).unwrap() + offset;
+        crate::hover(&db, file_id, TextUnit::from_usize(offset)).map(|result| {
+            let range = result.range.start().to_usize()..result.range.end().to_usize();
+            format!(
+                "{} | {} | {}",
+                &text[range],
+                result.signature,
+                result.docs.as_ref().map_or("", |docs| docs.as_str())
+            )
+        })
+    };
+
+    assert_eq!(
+        hover("new_point(1.0)", 0).unwrap(),
+        "new_point | pub fn new_point(x: f32) -> Point | Creates a point on the x-axis"
+    );
+    assert_eq!(
+        hover("Point {", 0).unwrap(),
+        "Point | struct Point { x: f32, y: f32 } | A point in space"
+    );
+    assert_eq!(
+        hover("Point = ", 0).unwrap(),
+        "Point | struct Point { x: f32, y: f32 } | A point in space"
+    );
+    assert_eq!(hover("x + ", 0).unwrap(), "x | x: f32 | ");
+    assert_eq!(hover("point.y", 0).unwrap(), "point | let point: Point | ");
+    assert_eq!(hover("sum", 0).unwrap(), "sum | let sum: f32 | ");
+    assert_eq!(hover("x: f32)", 0).unwrap(), "x | x: f32 | ");
+    assert_eq!(hover("y: 0.0", 0).unwrap(), "y | y: f32 | ");
+    assert_eq!(hover("1.0", 1).unwrap(), "1.0 | f32 | ");
+    assert_eq!(hover("f32 }", 0).unwrap(), "f32 | f32 | ");
+    assert_eq!(hover("\n    fn main", 0), None);
+}
+
+#[test]
+fn goto_definition() {
+    use mun_syntax::TextUnit;
+
+    let text = r#"
+    struct Point { x: f32, y: f32 }
+    struct Meter(f32);
+
+    fn new_point(x: f32) -> Point {
+        Point { x, y: 0.0 }
+    }
+
+    fn main() {
+        let point: Point = new_point(1.0);
+        let length = Meter(point.x);
+        let sum = length.0 + point.y;
+    }
+    "#;
+    let (db, file_id) = MockDatabase::with_single_file(text);
+
+    // Returns the name and the whole definition that the first occurrence of `pattern` refers to
+    let definition = |pattern: &str| {
+        let offset = text.find(pattern).unwrap();
+        crate::goto_definition(&db, file_id, TextUnit::from_usize(offset)).map(|target| {
+            assert_eq!(target.file_id, file_id);
+            let full = target.full_range.start().to_usize()..target.full_range.end().to_usize();
+            let focus = target.focus_range.start().to_usize()..target.focus_range.end().to_usize();
+            format!("{} | {}", &text[focus], &text[full])
+        })
+    };
+
+    assert_eq!(
+        definition("Point = ").unwrap(),
+        "Point | struct Point { x: f32, y: f32 }"
+    );
+    assert_eq!(
+        definition("Meter(point").unwrap(),
+        "Meter | struct Meter(f32);"
+    );
+    assert_eq!(definition("x);").unwrap(), "x | x: f32");
+    assert_eq!(definition("y;").unwrap(), "y | y: f32");
+    assert_eq!(definition("0 + ").unwrap(), "f32 | f32");
+    assert_eq!(definition("point.y").unwrap(), "point | point");
+    assert_eq!(
+        definition("new_point(1.0)")
+            .unwrap()
+            .lines()
+            .next()
+            .unwrap(),
+        "new_point | fn new_point(x: f32) -> Point {"
+    );
+    assert_eq!(definition("x, y: 0.0").unwrap(), "x | x");
+    assert_eq!(definition("f32 }"), None);
+}
+
+#[test]
+fn rename() {
+    use mun_syntax::TextUnit;
+
+    let text = r#"
+    struct Point { x: f32, y: f32 }
+
+    fn new_point(x: f32) -> Point {
+        Point { x, y: 0.0 }
+    }
+
+    fn main() {
+        let point: Point = new_point(1.0);
+        let length = point.x;
+        let sum = length + point.y;
+    }
+    "#;
+    let (db, file_id) = MockDatabase::with_single_file(text);
+
+    // Renames the definition that the first occurrence of `pattern` refers to and returns the
+    // renamed text or the reason why it cannot be renamed
+    let rename = |pattern: &str, new_name: &str| {
+        let offset = text.find(pattern).unwrap();
+        crate::rename(&db, file_id, TextUnit::from_usize(offset), new_name)
+            .map(|changes| {
+                assert_eq!(changes.len(), 1);
+                assert_eq!(changes[0].file_id, file_id);
+                crate::diagnostics::apply_edits(text, &changes[0].edits)
+            })
+            .map_err(|err| err.to_string())
+    };
+
+    let renamed = rename("Point =", "Vec2").unwrap();
+    assert!(renamed.contains("struct Vec2 { x: f32, y: f32 }"));
+    assert!(renamed.contains("fn new_point(x: f32) -> Vec2 {"));
+    assert!(renamed.contains("Vec2 { x, y: 0.0 }"));
+    assert!(renamed.contains("let point: Vec2 = new_point(1.0);"));
+
+    // Field shorthands are expanded so that only the field or the local binding is renamed
+    let renamed = rename("x: f32, y", "a").unwrap();
+    assert!(renamed.contains("struct Point { a: f32, y: f32 }"));
+    assert!(renamed.contains("Point { a: x, y: 0.0 }"));
+    assert!(renamed.contains("let length = point.a;"));
+    let renamed = rename("x: f32)", "a").unwrap();
+    assert!(renamed.contains("fn new_point(a: f32) -> Point {"));
+    assert!(renamed.contains("Point { x: a, y: 0.0 }"));
+
+    let renamed = rename("length =", "len").unwrap();
+    assert!(renamed.contains("let len = point.x;"));
+    assert!(renamed.contains("let sum = len + point.y;"));
+
+    assert_eq!(
+        rename("point.x", "length").unwrap_err(),
+        "`length` is already bound in this scope"
+    );
+    assert_eq!(
+        rename("length =", "point").unwrap_err(),
+        "`point` is already bound in this scope"
+    );
+    assert_eq!(
+        rename("new_point(1.0)", "main").unwrap_err(),
+        "a definition named `main` already exists in this module"
+    );
+    assert_eq!(
+        rename("y: 0.0", "x").unwrap_err(),
+        "a field named `x` already exists in `Point`"
+    );
+    assert_eq!(
+        rename("sum", "fn").unwrap_err(),
+        "`fn` is not a valid identifier"
+    );
+    assert_eq!(
+        rename("f32 }", "f64").unwrap_err(),
+        "cannot rename builtin type `f32`"
+    );
+}
+
+#[test]
+fn document_symbols() {
+    let text = r#"
+    struct Point { x: f32, y: f32 }
+    struct Meter(f32);
+
+    fn new_point(x: f32) -> Point {
+        Point { x, y: 0.0 }
+    }
+
+    impl Add for Meter {
+        fn add(lhs: Meter, rhs: Meter) -> Meter { Meter(lhs.0 + rhs.0) }
+    }
+    "#;
+    let (db, file_id) = MockDatabase::with_single_file(text);
+
+    // Formats the symbols as an indented tree of their names, details and focus ranges
+    fn format_symbols(text: &str, symbols: &[crate::DocumentSymbol], indent: usize) -> String {
+        let mut result = String::new();
+        for symbol in symbols {
+            let focus = symbol.focus_range.start().to_usize()..symbol.focus_range.end().to_usize();
+            result.push_str(&format!(
+                "{}{:?} {} | {} | {}\n",
+                "  ".repeat(indent),
+                symbol.kind,
+                symbol.name,
+                symbol.detail.as_deref().unwrap_or(""),
+                &text[focus].lines().next().unwrap()
+            ));
+            result.push_str(&format_symbols(text, &symbol.children, indent + 1));
+        }
+        result
+    }
+
+    assert_eq!(
+        format_symbols(text, &crate::document_symbols(&db, file_id), 0),
+        "\
+Struct Point |  | Point
+  Field x | f32 | x
+  Field y | f32 | y
+Struct Meter |  | Meter
+  Field 0 | f32 | f32
+Function new_point | fn(x: f32) -> Point | new_point
+Impl impl Add for Meter |  | impl Add for Meter {
+  Function add | fn(lhs: Meter, rhs: Meter) -> Meter | add
+"
+    );
+}
+
+#[test]
+fn signature_help() {
+    use mun_syntax::TextUnit;
+
+    // Returns the signature of the call at the `$0` marker in `text` with the active parameter
+    // enclosed in brackets
+    let signature_help = |text: &str| {
+        let offset = text.find("$0").unwrap();
+        let text = text.replace("$0", "");
+        let (db, file_id) = MockDatabase::with_single_file(&text);
+        crate::signature_help(&db, file_id, TextUnit::from_usize(offset)).map(|help| {
+            let mut label = help.label.clone();
+            if let Some(active) = help.active_parameter {
+                let range = help.parameters[active];
+                label.insert(range.end().to_usize(), ']');
+                label.insert(range.start().to_usize(), '[');
+            }
+            label
+        })
+    };
+
+    let text = r#"
+    /// Adds two numbers
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+    struct Meter(f32, f32);
+    "#;
+    assert_eq!(
+        signature_help(&format!("{}fn main() {{ add($0) }}", text)).unwrap(),
+        "pub fn add([a: i32], b: i32) -> i32"
+    );
+    assert_eq!(
+        signature_help(&format!("{}fn main() {{ add(1, $0) }}", text)).unwrap(),
+        "pub fn add(a: i32, [b: i32]) -> i32"
+    );
+    assert_eq!(
+        signature_help(&format!("{}fn main() {{ add(1, 2, $0) }}", text)).unwrap(),
+        "pub fn add(a: i32, b: i32) -> i32"
+    );
+    assert_eq!(
+        signature_help(&format!("{}fn main() {{ add(1, add(2$0, 3)) }}", text)).unwrap(),
+        "pub fn add([a: i32], b: i32) -> i32"
+    );
+    assert_eq!(
+        signature_help(&format!("{}fn main() {{ Meter(1.0, $0) }}", text)).unwrap(),
+        "struct Meter(f32, [f32])"
+    );
+    assert_eq!(
+        signature_help(&format!("{}fn main() {{\n    let a = add(1, $0\n}}", text)).unwrap(),
+        "pub fn add(a: i32, [b: i32]) -> i32"
+    );
+    assert_eq!(
+        signature_help(&format!("{}fn main() {{ add(1, 2)$0 }}", text)),
+        None
+    );
+}
+
+#[test]
+fn inlay_hints() {
+    let text = r#"
+    fn add(a: i32, b: i32) -> i32 { a + b }
+
+    fn main() {
+        let a = 1;
+        let sum = add(a, 2 + 3);
+        let ascribed: i64 = 0;
+        let unknown = foo;
+    }
+    "#;
+    let (db, file_id) = MockDatabase::with_single_file(text);
+
+    // Formats the hints as the text they are about and their label
+    let hints = |config: crate::InlayHintsConfig| {
+        crate::inlay_hints(&db, file_id, &config)
+            .into_iter()
+            .map(|hint| {
+                let range = hint.range.start().to_usize()..hint.range.end().to_usize();
+                format!("{:?} {} | {}", hint.kind, &text[range], hint.label)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        hints(crate::InlayHintsConfig::default()),
+        vec!["Type a | i32", "Type sum | i32", "ParameterName 2 + 3 | b",]
+    );
+    assert_eq!(
+        hints(crate::InlayHintsConfig {
+            type_hints: false,
+            parameter_hints: true,
+        }),
+        vec!["ParameterName 2 + 3 | b"]
+    );
+    assert_eq!(
+        hints(crate::InlayHintsConfig {
+            type_hints: true,
+            parameter_hints: false,
+        }),
+        vec!["Type a | i32", "Type sum | i32"]
+    );
+}
+
+#[test]
+fn assists() {
+    use mun_syntax::{TextRange, TextUnit};
+
+    let text = r#"
+    struct Point { x: f32, y: f32, visible: bool }
+
+    fn main() {
+        let origin = Point { x: 0.0 };
+        let point = Point { x: 1.0, ..origin };
+        let length = point.x * point.x + point.y * point.y;
+        if length > 1.0 && point.visible {
+            point.x
+        } else {
+            point.y
+        };
+    }
+    "#;
+    let (db, file_id) = MockDatabase::with_single_file(text);
+
+    // Applies the assist with the specified label to the first occurrence of `pattern`, or the
+    // cursor before it if `select` is false
+    let assist = |pattern: &str, select: bool, label: &str| {
+        let start = TextUnit::from_usize(text.find(pattern).unwrap());
+        let range = if select {
+            TextRange::offset_len(start, TextUnit::of_str(pattern))
+        } else {
+            TextRange::offset_len(start, 0.into())
+        };
+        crate::assists(&db, file_id, range)
+            .into_iter()
+            .find(|change| change.label == label)
+            .map(|change| crate::diagnostics::apply_edits(text, &change.edits))
+    };
+
+    let fixed = assist("0.0 }", false, "add missing fields").unwrap();
+    assert!(fixed.contains("Point { x: 0.0, y: 0.0, visible: false }"));
+    let fixed = assist("1.0,", false, "add missing fields").unwrap();
+    assert!(fixed.contains("Point { x: 1.0, y: origin.y, visible: origin.visible, ..origin }"));
+
+    let fixed = assist("point.y * point.y", true, "introduce variable").unwrap();
+    assert!(fixed
+        .contains("let var = point.y * point.y;\n        let length = point.x * point.x + var;"));
+    let fixed = assist("length > 1.0", true, "introduce variable").unwrap();
+    assert!(fixed.contains("let var = length > 1.0;\n        if var && point.visible {"));
+
+    // The right-hand side of `&&` and the branches of an `if` are not always evaluated
+    assert_eq!(assist("point.visible", true, "introduce variable"), None);
+    assert_eq!(assist("point.x\n", true, "introduce variable"), None);
+    // Binding the initializer of a `let` statement has no use
+    assert_eq!(
+        assist(
+            "point.x * point.x + point.y * point.y",
+            true,
+            "introduce variable"
+        ),
+        None
+    );
+    assert_eq!(
+        assist("point.y * point.y", false, "introduce variable"),
+        None
+    );
+}
+
+#[test]
+fn find_references() {
+    use mun_syntax::TextUnit;
+
+    let text = r#"
+    fn square(x: f32) -> f32 {
+        x * x
+    }
+
+    fn main() {
+        let a = square(1.0);
+        let b = square(a) + a;
+    }
+    "#;
+    let (db, file_id) = MockDatabase::with_single_file(text);
+
+    // Returns the references to the definition that the first occurrence of `pattern` refers to
+    let references = |pattern: &str, include_declaration: bool| {
+        let offset = TextUnit::from_usize(text.find(pattern).unwrap());
+        crate::find_references(&db, file_id, offset, include_declaration).map(|references| {
+            references
+                .iter()
+                .map(|reference| {
+                    assert_eq!(reference.file_id, file_id);
+                    let start = reference.range.start().to_usize();
+                    let end = reference.range.end().to_usize();
+                    format!("{}: {}", start, &text[start..end])
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+
+    assert_eq!(
+        references("square(1.0)", false).unwrap(),
+        vec!["85: square", "114: square"]
+    );
+    assert_eq!(
+        references("square(1.0)", true).unwrap(),
+        vec!["8: square", "85: square", "114: square"]
+    );
+    assert_eq!(
+        references("a =", true).unwrap(),
+        vec!["81: a", "121: a", "126: a"]
+    );
+    assert_eq!(references("x * x", false).unwrap(), vec!["40: x", "44: x"]);
+    assert_eq!(references("f32)", true), None);
+}
+
+#[test]
+fn ast_id_map_covers_nested_items() {
+    use crate::source_id::AstIdMap;
+    use mun_syntax::{ast, AstNode, SyntaxNode};
+
+    let text = r#"
+    struct Foo { a: i32, b: bool }
+    struct Bar(f32);
+
+    impl Foo {
+        fn new() -> Foo {
+            Foo { a: 0, b: false }
+        }
+    }
+    "#;
+    let (db, file_id) = MockDatabase::with_single_file(text);
+    let ast_id_map = db.ast_id_map(file_id);
+    let root = db.parse(file_id).syntax_node();
+
+    // Checks that every node of type `N` has an id that refers back to the node and returns the
+    // number of nodes
+    fn check_round_trip<N: AstNode + PartialEq + std::fmt::Debug>(
+        ast_id_map: &AstIdMap,
+        root: &SyntaxNode,
+    ) -> usize {
+        let nodes: Vec<N> = root.descendants().filter_map(N::cast).collect();
+        for node in nodes.iter() {
+            let ast_id = ast_id_map.ast_id(node);
+            assert_eq!(ast_id_map.get(ast_id).to_node(root), *node);
+        }
+        nodes.len()
+    }
+
+    assert_eq!(check_round_trip::<ast::ModuleItem>(&ast_id_map, &root), 4);
+    assert_eq!(
+        check_round_trip::<ast::RecordFieldDef>(&ast_id_map, &root),
+        2
+    );
+    assert_eq!(
+        check_round_trip::<ast::TupleFieldDef>(&ast_id_map, &root),
+        1
+    );
+}
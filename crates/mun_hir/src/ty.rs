@@ -8,11 +8,11 @@ use crate::display::{HirDisplay, HirFormatter};
 use crate::ty::infer::InferTy;
 use crate::ty::lower::fn_sig_for_struct_constructor;
 use crate::utils::make_mut_slice;
-use crate::{HirDatabase, Struct, StructMemoryKind};
+use crate::{HirDatabase, IntBitness, Struct, StructMemoryKind};
 pub(crate) use infer::infer_query;
 pub use infer::InferenceResult;
 pub(crate) use lower::{callable_item_sig, fn_sig_for_fn, type_for_def, CallableDef, TypableDef};
-pub use primitives::{FloatTy, IntTy};
+pub use primitives::{FloatTy, IntTy, PrimitiveTy};
 pub use resolve::ResolveBitness;
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
@@ -61,6 +61,34 @@ pub enum TypeCtor {
     /// The primitive boolean type. Written as `bool`.
     Bool,
 
+    /// The primitive character type. Written as `char`. A `char` is a Unicode scalar value.
+    Char,
+
+    /// An integer that can be safely accessed from multiple threads. Written as `Atomic<i32>`.
+    /// Only `i32` and `u64` can be used as the inner type.
+    Atomic(IntTy),
+
+    /// A location in the source code with a `file`, a `line` and a `column` field, as returned by
+    /// `core::source_location()`. Written as `SourceLocation`.
+    SourceLocation,
+
+    /// A dynamically typed value that holds a primitive value together with its runtime type
+    /// information. Written as `any`.
+    Any,
+
+    /// An opaque value that is owned by the host, like the id of an entity of a game engine. A
+    /// handle can be stored and passed back to the host, but it can only be compared for equality.
+    /// Written as `Handle`.
+    Handle,
+
+    /// A vector of `f32` values with the specified number of lanes, whose arithmetic is performed
+    /// on all lanes at once. Written as `float2`, `float3` or `float4`.
+    FloatVector(u32),
+
+    /// The result of a checked downcast of an `any` value, e.g. `value as f32`. Holds a value of
+    /// the inner type if the downcast succeeded.
+    Optional(PrimitiveTy),
+
     /// An abstract datatype (structures, tuples, or enumerations)
     /// TODO: Add tuples and enumerations
     Struct(Struct),
@@ -83,6 +111,17 @@ pub enum TypeCtor {
     FnDef(CallableDef),
 }
 
+impl From<PrimitiveTy> for Ty {
+    fn from(ty: PrimitiveTy) -> Self {
+        Ty::simple(match ty {
+            PrimitiveTy::Bool => TypeCtor::Bool,
+            PrimitiveTy::Char => TypeCtor::Char,
+            PrimitiveTy::Int(ty) => TypeCtor::Int(ty),
+            PrimitiveTy::Float(ty) => TypeCtor::Float(ty),
+        })
+    }
+}
+
 impl Ty {
     pub fn simple(ctor: TypeCtor) -> Ty {
         Ty::Apply(ApplicationTy {
@@ -121,6 +160,26 @@ impl Ty {
         }
     }
 
+    /// Returns the inner integer type if this is an atomic type.
+    pub fn as_atomic(&self) -> Option<IntTy> {
+        match self.as_simple() {
+            Some(TypeCtor::Atomic(ty)) => Some(ty),
+            _ => None,
+        }
+    }
+
+    /// Returns the primitive type if this type can be stored in an `any` value. 128-bit integers
+    /// are too large to be stored in an `any`.
+    pub fn as_primitive(&self) -> Option<PrimitiveTy> {
+        match self.as_simple()? {
+            TypeCtor::Bool => Some(PrimitiveTy::Bool),
+            TypeCtor::Char => Some(PrimitiveTy::Char),
+            TypeCtor::Int(ty) if ty.bitness != IntBitness::X128 => Some(PrimitiveTy::Int(ty)),
+            TypeCtor::Float(ty) => Some(PrimitiveTy::Float(ty)),
+            _ => None,
+        }
+    }
+
     pub fn as_struct(&self) -> Option<Struct> {
         match self {
             Ty::Apply(a_ty) => match a_ty.ctor {
@@ -140,43 +199,6 @@ impl Ty {
             _ => None,
         }
     }
-
-    /// Returns the type's name as a string, if one exists.
-    ///
-    /// This name needs to be unique as it is used to generate a type's `Guid`.
-    pub fn guid_string(&self, db: &dyn HirDatabase) -> Option<String> {
-        self.as_simple().and_then(|ty_ctor| match ty_ctor {
-            TypeCtor::Struct(s) => {
-                let name = s.name(db.upcast()).to_string();
-
-                Some(if s.data(db.upcast()).memory_kind == StructMemoryKind::GC {
-                    format!("struct {}", name)
-                } else {
-                    let fields: Vec<String> = s
-                        .fields(db)
-                        .into_iter()
-                        .map(|f| {
-                            let ty_string = f
-                                .ty(db)
-                                .guid_string(db)
-                                .expect("type should be convertible to a string");
-                            format!("{}: {}", f.name(db).to_string(), ty_string)
-                        })
-                        .collect();
-
-                    format!(
-                        "struct {name}{{{fields}}}",
-                        name = name,
-                        fields = fields.join(",")
-                    )
-                })
-            }
-            TypeCtor::Bool => Some("core::bool".to_string()),
-            TypeCtor::Float(ty) => Some(format!("core::{}", ty.as_str())),
-            TypeCtor::Int(ty) => Some(format!("core::{}", ty.as_str())),
-            _ => None,
-        })
-    }
 }
 
 /// A list of substitutions for generic parameters.
@@ -263,6 +285,13 @@ impl HirDisplay for ApplicationTy {
             TypeCtor::Float(ty) => write!(f, "{}", ty),
             TypeCtor::Int(ty) => write!(f, "{}", ty),
             TypeCtor::Bool => write!(f, "bool"),
+            TypeCtor::Char => write!(f, "char"),
+            TypeCtor::Atomic(ty) => write!(f, "Atomic<{}>", ty),
+            TypeCtor::SourceLocation => write!(f, "SourceLocation"),
+            TypeCtor::Any => write!(f, "any"),
+            TypeCtor::Handle => write!(f, "Handle"),
+            TypeCtor::FloatVector(lanes) => write!(f, "float{}", lanes),
+            TypeCtor::Optional(ty) => write!(f, "Optional<{}>", ty),
             TypeCtor::Struct(def) => write!(f, "{}", def.name(f.db.upcast())),
             TypeCtor::Never => write!(f, "never"),
             TypeCtor::FnDef(CallableDef::Function(def)) => {
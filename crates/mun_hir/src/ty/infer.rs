@@ -1,6 +1,7 @@
 use crate::{
-    adt::StructKind,
+    adt::{DeriveTrait, StructKind},
     arena::map::ArenaMap,
+    builtin_type::{BuiltinMethod, MathIntrinsic, SourceLocationField, VectorComponent},
     code_model::{DefWithBody, DefWithStruct, Struct},
     diagnostics::DiagnosticSink,
     expr,
@@ -11,11 +12,11 @@ use crate::{
     ty::infer::type_variable::TypeVariableTable,
     ty::lower::LowerDiagnostic,
     ty::op,
-    ty::{Ty, TypableDef},
+    ty::{FnSig, Ty, TypableDef},
     type_ref::TypeRefId,
     ApplicationTy, BinaryOp, Function, HirDatabase, Name, Path, TypeCtor,
 };
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::ops::Index;
 use std::sync::Arc;
 
@@ -51,6 +52,8 @@ mod coerce;
 pub struct InferenceResult {
     pub(crate) type_of_expr: ArenaMap<ExprId, Ty>,
     pub(crate) type_of_pat: ArenaMap<PatId, Ty>,
+    pub(crate) method_resolutions: FxHashMap<ExprId, BuiltinMethod>,
+    pub(crate) operator_resolutions: FxHashMap<ExprId, Function>,
     pub(crate) diagnostics: Vec<diagnostics::InferenceDiagnostic>,
 }
 
@@ -69,6 +72,17 @@ impl Index<PatId> for InferenceResult {
 }
 
 impl InferenceResult {
+    /// Returns the method that is called by the specified method call expression.
+    pub fn method_resolution(&self, expr: ExprId) -> Option<BuiltinMethod> {
+        self.method_resolutions.get(&expr).copied()
+    }
+
+    /// Returns the function that implements the overloaded operator of the specified binary
+    /// expression.
+    pub fn operator_resolution(&self, expr: ExprId) -> Option<Function> {
+        self.operator_resolutions.get(&expr).copied()
+    }
+
     /// Adds all the `InferenceDiagnostic`s of the result to the `DiagnosticSink`.
     pub(crate) fn add_diagnostics(
         &self,
@@ -98,6 +112,15 @@ pub fn infer_query(db: &dyn HirDatabase, def: DefWithBody) -> Arc<InferenceResul
     Arc::new(ctx.resolve_all())
 }
 
+/// Returns the type of the value that initializes a struct field of the specified type. Atomic
+/// fields are initialized with a value of their inner type.
+fn field_init_ty(ty: Ty) -> Ty {
+    match ty.as_atomic() {
+        Some(int_ty) => Ty::simple(TypeCtor::Int(int_ty)),
+        None => ty,
+    }
+}
+
 /// Placeholders required during type inferencing. There are seperate values for integer and
 /// floating-point types and for generic type variables. The first being used to distinguish
 /// literals; e.g `100` can be represented by a lot of different integer types.
@@ -138,15 +161,17 @@ struct InferenceResultBuilder<'a> {
 
     type_of_expr: ArenaMap<ExprId, Ty>,
     type_of_pat: ArenaMap<PatId, Ty>,
+    method_resolutions: FxHashMap<ExprId, BuiltinMethod>,
+    operator_resolutions: FxHashMap<ExprId, Function>,
     diagnostics: Vec<InferenceDiagnostic>,
 
     type_variables: TypeVariableTable,
 
-    /// Information on the current loop that we're processing (or None if we're not in a loop) the
-    /// entry contains the current type of the loop statement (initially `never`) and the expected
-    /// type of the loop expression. Both these values are updated when a break statement is
-    /// encountered.
-    active_loop: Option<ActiveLoop>,
+    /// Information on the loops that we're currently processing together with their labels, the
+    /// innermost loop is last. A `loop` entry contains the current type of the loop statement
+    /// (initially `never`) and the expected type of the loop expression. Both these values are
+    /// updated when a break statement that targets the loop is encountered.
+    active_loops: Vec<(Option<Name>, ActiveLoop)>,
 
     /// The return type of the function being inferred.
     return_ty: Ty,
@@ -158,8 +183,10 @@ impl<'a> InferenceResultBuilder<'a> {
         InferenceResultBuilder {
             type_of_expr: ArenaMap::default(),
             type_of_pat: ArenaMap::default(),
+            method_resolutions: FxHashMap::default(),
+            operator_resolutions: FxHashMap::default(),
             diagnostics: Vec::default(),
-            active_loop: None,
+            active_loops: Vec::new(),
             type_variables: TypeVariableTable::default(),
             db,
             body,
@@ -196,6 +223,7 @@ impl<'a> InferenceResultBuilder<'a> {
                 LowerDiagnostic::UnresolvedType { id } => {
                     InferenceDiagnostic::UnresolvedType { id }
                 }
+                diag => InferenceDiagnostic::TypeLowering(diag),
             };
             self.diagnostics.push(diag);
         }
@@ -244,7 +272,8 @@ impl<'a> InferenceResultBuilder<'a> {
     /// Infers the type of the `tgt_expr`
     fn infer_expr(&mut self, tgt_expr: ExprId, expected: &Expectation) -> Ty {
         let ty = self.infer_expr_inner(tgt_expr, expected, &CheckParams::default());
-        if !self.unify(&ty, &expected.ty) {
+        // A diverging expression never produces a value, so it can take the place of any type
+        if !ty.is_never() && !self.unify(&ty, &expected.ty) {
             self.diagnostics.push(InferenceDiagnostic::MismatchedTypes {
                 expected: expected.ty.clone(),
                 found: ty.clone(),
@@ -319,25 +348,54 @@ impl<'a> InferenceResultBuilder<'a> {
                             })
                         }
                     };
-                    let rhs_expected = op::binary_op_rhs_expectation(*op, lhs_ty.clone());
-                    if lhs_ty != Ty::Unknown && rhs_expected == Ty::Unknown {
-                        self.diagnostics
-                            .push(InferenceDiagnostic::CannotApplyBinaryOp {
-                                id: tgt_expr,
-                                lhs: lhs_ty,
-                                rhs: rhs_expected.clone(),
-                            })
+                    if lhs_ty.is_never() {
+                        // The operation is never executed if the left hand side diverges
+                        self.infer_expr(*rhs, &Expectation::none());
+                        lhs_ty
+                    } else if let Some(ty) = self.infer_operator_call(tgt_expr, *op, &lhs_ty, *rhs)
+                    {
+                        ty
+                    } else {
+                        let rhs_expected = match (op, lhs_ty.as_struct()) {
+                            // Structs can be compared if they derive the comparison
+                            (BinaryOp::CmpOp(cmp_op), Some(s))
+                                if s.derives(self.db, DeriveTrait::from_cmp_op(*cmp_op)) =>
+                            {
+                                lhs_ty.clone()
+                            }
+                            _ => op::binary_op_rhs_expectation(*op, lhs_ty.clone()),
+                        };
+                        if lhs_ty != Ty::Unknown && rhs_expected == Ty::Unknown {
+                            self.diagnostics
+                                .push(InferenceDiagnostic::CannotApplyBinaryOp {
+                                    id: tgt_expr,
+                                    lhs: lhs_ty,
+                                    rhs: rhs_expected.clone(),
+                                })
+                        }
+                        let rhs_ty =
+                            self.infer_expr(*rhs, &Expectation::has_type(rhs_expected.clone()));
+                        if rhs_ty.is_never() {
+                            // A diverging right hand side takes the place of the expected type
+                            op::binary_op_return_ty(*op, rhs_expected)
+                        } else {
+                            op::binary_op_return_ty(*op, rhs_ty)
+                        }
                     }
-                    let rhs_ty = self.infer_expr(*rhs, &Expectation::has_type(rhs_expected));
-                    op::binary_op_return_ty(*op, rhs_ty)
                 }
                 _ => Ty::Unknown,
             },
             Expr::Block { statements, tail } => self.infer_block(statements, *tail, expected),
             Expr::Call { callee: call, args } => self.infer_call(tgt_expr, *call, args, expected),
+            Expr::MethodCall {
+                receiver,
+                method_name,
+                args,
+            } => self.infer_method_call(tgt_expr, *receiver, method_name, args),
             Expr::Literal(lit) => match lit {
                 Literal::String(_) => Ty::Unknown,
                 Literal::Bool(_) => Ty::simple(TypeCtor::Bool),
+                Literal::Char(_) => Ty::simple(TypeCtor::Char),
                 Literal::Int(LiteralInt {
                     kind: LiteralIntKind::Suffixed(suffix),
                     ..
@@ -351,14 +409,22 @@ impl<'a> InferenceResultBuilder<'a> {
                 }) => Ty::simple(TypeCtor::Float(FloatTy {
                     bitness: suffix.bitness,
                 })),
+                // An unsuffixed literal adopts the numeric type that is demanded by its use site.
+                // Otherwise it is inferred later on or falls back to a default type.
                 Literal::Int(LiteralInt {
                     kind: LiteralIntKind::Unsuffixed,
                     ..
-                }) => self.type_variables.new_integer_var(),
+                }) => match self.resolve_ty_as_far_as_possible(expected.ty.clone()) {
+                    ty @ ty_app!(TypeCtor::Int(_)) | ty @ Ty::Infer(InferTy::IntVar(_)) => ty,
+                    _ => self.type_variables.new_integer_var(),
+                },
                 Literal::Float(LiteralFloat {
                     kind: LiteralFloatKind::Unsuffixed,
                     ..
-                }) => self.type_variables.new_float_var(),
+                }) => match self.resolve_ty_as_far_as_possible(expected.ty.clone()) {
+                    ty @ ty_app!(TypeCtor::Float(_)) | ty @ Ty::Infer(InferTy::FloatVar(_)) => ty,
+                    _ => self.type_variables.new_float_var(),
+                },
             },
             Expr::Return { expr } => {
                 if let Some(expr) = expr {
@@ -370,11 +436,16 @@ impl<'a> InferenceResultBuilder<'a> {
 
                 Ty::simple(TypeCtor::Never)
             }
-            Expr::Break { expr } => self.infer_break(tgt_expr, *expr),
-            Expr::Loop { body } => self.infer_loop_expr(tgt_expr, *body, expected),
-            Expr::While { condition, body } => {
-                self.infer_while_expr(tgt_expr, *condition, *body, expected)
+            Expr::Break { expr, label } => self.infer_break(tgt_expr, *expr, label.as_ref()),
+            Expr::Continue { label } => self.infer_continue(tgt_expr, label.as_ref()),
+            Expr::Loop { body, label } => {
+                self.infer_loop_expr(tgt_expr, *body, label.clone(), expected)
             }
+            Expr::While {
+                condition,
+                body,
+                label,
+            } => self.infer_while_expr(tgt_expr, *condition, *body, label.clone(), expected),
             Expr::RecordLit {
                 type_id,
                 fields,
@@ -397,14 +468,14 @@ impl<'a> InferenceResultBuilder<'a> {
                                 None
                             }
                         })
-                        .map_or(Ty::Unknown, |field| field.ty(self.db));
+                        .map_or(Ty::Unknown, |field| field_init_ty(field.ty(self.db)));
                     self.infer_expr_coerce(field.expr, &Expectation::has_type(field_ty));
                 }
                 if let Some(expr) = spread {
                     self.infer_expr(*expr, &Expectation::has_type(ty.clone()));
                 }
                 if let Some(s) = ty.as_struct() {
-                    self.check_record_lit(tgt_expr, s, &fields);
+                    self.check_record_lit(tgt_expr, s, fields, spread.is_some());
                 }
                 ty
             }
@@ -426,6 +497,34 @@ impl<'a> InferenceResultBuilder<'a> {
                             }
                         }
                     }
+                    ty_app!(TypeCtor::SourceLocation) => {
+                        match SourceLocationField::from_name(name) {
+                            Some(SourceLocationField::File) => Ty::simple(TypeCtor::Handle),
+                            Some(_) => Ty::simple(TypeCtor::Int(IntTy::u32())),
+                            None => {
+                                self.diagnostics
+                                    .push(InferenceDiagnostic::AccessUnknownField {
+                                        id: tgt_expr,
+                                        receiver_ty,
+                                        name: name.clone(),
+                                    });
+                                Ty::Unknown
+                            }
+                        }
+                    }
+                    ty_app!(TypeCtor::FloatVector(lanes)) => {
+                        if VectorComponent::from_name(name, lanes).is_some() {
+                            Ty::simple(TypeCtor::Float(FloatTy::f32()))
+                        } else {
+                            self.diagnostics
+                                .push(InferenceDiagnostic::AccessUnknownField {
+                                    id: tgt_expr,
+                                    receiver_ty,
+                                    name: name.clone(),
+                                });
+                            Ty::Unknown
+                        }
+                    }
                     _ => {
                         self.diagnostics.push(InferenceDiagnostic::NoFields {
                             id: *expr,
@@ -435,6 +534,34 @@ impl<'a> InferenceResultBuilder<'a> {
                     }
                 }
             }
+            Expr::SourceLocation => Ty::simple(TypeCtor::SourceLocation),
+            Expr::MathIntrinsic { intrinsic, args } => {
+                self.infer_math_intrinsic(*intrinsic, args, expected)
+            }
+            Expr::Vector { elements } => {
+                let element_ty = Ty::simple(TypeCtor::Float(FloatTy::f32()));
+                for element in elements {
+                    self.infer_expr_coerce(*element, &Expectation::has_type(element_ty.clone()));
+                }
+                Ty::simple(TypeCtor::FloatVector(elements.len() as u32))
+            }
+            Expr::Panic { message } => {
+                if let Some(message) = message {
+                    self.infer_panic_message(*message);
+                }
+                Ty::simple(TypeCtor::Never)
+            }
+            Expr::Assert { condition, message } => {
+                self.infer_expr(
+                    *condition,
+                    &Expectation::has_type(Ty::simple(TypeCtor::Bool)),
+                );
+                if let Some(message) = message {
+                    self.infer_panic_message(*message);
+                }
+                Ty::Empty
+            }
+            Expr::Cast { expr, type_ref } => self.infer_cast(tgt_expr, *expr, *type_ref),
             Expr::UnaryOp { expr, op } => {
                 let inner_ty =
                     self.infer_expr_inner(*expr, &Expectation::none(), &CheckParams::default());
@@ -449,6 +576,8 @@ impl<'a> InferenceResultBuilder<'a> {
                             ..
                         })
                         | Ty::Infer(InferTy::IntVar(..)) => inner_ty,
+                        // The operation is never executed if the operand diverges
+                        ty_app!(TypeCtor::Never) => inner_ty,
                         _ => {
                             self.diagnostics
                                 .push(InferenceDiagnostic::CannotApplyUnaryOp {
@@ -463,12 +592,17 @@ impl<'a> InferenceResultBuilder<'a> {
                             ctor: TypeCtor::Float(_),
                             ..
                         })
+                        | Ty::Apply(ApplicationTy {
+                            ctor: TypeCtor::FloatVector(_),
+                            ..
+                        })
                         | Ty::Apply(ApplicationTy {
                             ctor: TypeCtor::Int(_),
                             ..
                         })
                         | Ty::Infer(InferTy::IntVar(..))
                         | Ty::Infer(InferTy::FloatVar(..)) => inner_ty,
+                        ty_app!(TypeCtor::Never) => inner_ty,
                         _ => {
                             self.diagnostics
                                 .push(InferenceDiagnostic::CannotApplyUnaryOp {
@@ -575,7 +709,12 @@ impl<'a> InferenceResultBuilder<'a> {
                     param_tys.len(),
                 );
                 for (&arg, param_ty) in args.iter().zip(param_tys.iter()) {
-                    self.infer_expr_coerce(arg, &Expectation::has_type(param_ty.clone()));
+                    let param_ty = if def.is_struct() {
+                        field_init_ty(param_ty.clone())
+                    } else {
+                        param_ty.clone()
+                    };
+                    self.infer_expr_coerce(arg, &Expectation::has_type(param_ty));
                 }
 
                 ret_ty
@@ -591,6 +730,168 @@ impl<'a> InferenceResultBuilder<'a> {
         }
     }
 
+    /// Infers the type of a call to a math function. All arguments must have the type of the first
+    /// argument, which is also the type of the result.
+    fn infer_math_intrinsic(
+        &mut self,
+        intrinsic: MathIntrinsic,
+        args: &[ExprId],
+        expected: &Expectation,
+    ) -> Ty {
+        let ty = self.infer_expr(args[0], expected);
+        let is_valid = match &ty {
+            ty_app!(TypeCtor::Float(_))
+            | ty_app!(TypeCtor::FloatVector(_))
+            | Ty::Infer(InferTy::FloatVar(..))
+            | ty_app!(TypeCtor::Never)
+            | Ty::Unknown => true,
+            ty_app!(TypeCtor::Int(_)) | Ty::Infer(InferTy::IntVar(..)) => {
+                !intrinsic.is_float_only()
+            }
+            _ => false,
+        };
+        if !is_valid {
+            self.diagnostics
+                .push(InferenceDiagnostic::InvalidIntrinsicArgument {
+                    id: args[0],
+                    intrinsic,
+                    ty,
+                });
+            return Ty::Unknown;
+        }
+
+        for arg in &args[1..] {
+            self.infer_expr_coerce(*arg, &Expectation::has_type(ty.clone()));
+        }
+        ty
+    }
+
+    /// Infers the type of a cast expression. A primitive value can be converted to an `any`, and
+    /// an `any` can be downcast to a primitive type which results in an `Optional` that only holds
+    /// a value if the `any` holds a value of that type.
+    fn infer_cast(&mut self, tgt_expr: ExprId, expr: ExprId, type_ref: TypeRefId) -> Ty {
+        let cast_ty = self.resolve_type(type_ref);
+        let expr_ty = self.infer_expr(expr, &Expectation::none());
+        let expr_ty = self
+            .type_variables
+            .replace_if_possible(&expr_ty)
+            .into_owned();
+
+        let is_primitive = match &expr_ty {
+            Ty::Infer(InferTy::IntVar(..)) | Ty::Infer(InferTy::FloatVar(..)) => true,
+            ty => ty.as_primitive().is_some(),
+        };
+
+        match (expr_ty.as_simple(), cast_ty.as_simple()) {
+            _ if expr_ty == Ty::Unknown || cast_ty == Ty::Unknown => Ty::Unknown,
+            (_, Some(TypeCtor::Any)) if is_primitive => cast_ty,
+            (Some(TypeCtor::Any), Some(TypeCtor::Any)) => cast_ty,
+            (Some(TypeCtor::Any), _) if cast_ty.as_primitive().is_some() => {
+                Ty::simple(TypeCtor::Optional(cast_ty.as_primitive().unwrap()))
+            }
+            _ => {
+                self.diagnostics.push(InferenceDiagnostic::InvalidCast {
+                    id: tgt_expr,
+                    expr_ty,
+                    cast_ty,
+                });
+                Ty::Unknown
+            }
+        }
+    }
+
+    /// Infers the type of a binary operation that is overloaded for the struct on its left-hand
+    /// side. Returns `None` if the operator is not overloaded.
+    fn infer_operator_call(
+        &mut self,
+        tgt_expr: ExprId,
+        op: BinaryOp,
+        lhs_ty: &Ty,
+        rhs: ExprId,
+    ) -> Option<Ty> {
+        let op = match op {
+            BinaryOp::ArithOp(op) => op,
+            _ => return None,
+        };
+        let function = lhs_ty.as_struct()?.operator_impl(self.db, op)?;
+        let sig = function.ty(self.db).callable_sig(self.db)?;
+
+        // Invalid signatures are reported by the impl block
+        if sig.params().len() != 2 || sig.params()[0] != *lhs_ty {
+            return None;
+        }
+
+        self.infer_expr_coerce(rhs, &Expectation::has_type(sig.params()[1].clone()));
+        self.operator_resolutions.insert(tgt_expr, function);
+        Some(sig.ret().clone())
+    }
+
+    /// Infers the type of a method call. Methods are only defined for builtin types.
+    fn infer_method_call(
+        &mut self,
+        tgt_expr: ExprId,
+        receiver: ExprId,
+        method_name: &Name,
+        args: &[ExprId],
+    ) -> Ty {
+        let receiver_ty = self.infer_expr(receiver, &Expectation::none());
+        let method = match receiver_ty.as_simple() {
+            Some(TypeCtor::Atomic(int_ty)) => {
+                BuiltinMethod::atomic_from_name(method_name).map(|method| {
+                    let value_ty = Ty::simple(TypeCtor::Int(int_ty));
+                    let sig = match method {
+                        BuiltinMethod::AtomicStore => {
+                            FnSig::from_params_and_return(vec![value_ty], Ty::Empty)
+                        }
+                        BuiltinMethod::AtomicFetchAdd => {
+                            FnSig::from_params_and_return(vec![value_ty.clone()], value_ty)
+                        }
+                        _ => FnSig::from_params_and_return(vec![], value_ty),
+                    };
+                    (method, sig)
+                })
+            }
+            Some(TypeCtor::Optional(ty)) => {
+                BuiltinMethod::optional_from_name(method_name).map(|method| {
+                    let sig = match method {
+                        BuiltinMethod::OptionalUnwrapOr => {
+                            FnSig::from_params_and_return(vec![ty.into()], ty.into())
+                        }
+                        _ => FnSig::from_params_and_return(vec![], Ty::simple(TypeCtor::Bool)),
+                    };
+                    (method, sig)
+                })
+            }
+            _ => None,
+        };
+
+        match method {
+            Some((method, sig)) => {
+                self.method_resolutions.insert(tgt_expr, method);
+                self.check_call_argument_count(tgt_expr, false, args.len(), sig.params().len());
+                for (&arg, param_ty) in args.iter().zip(sig.params().iter()) {
+                    self.infer_expr_coerce(arg, &Expectation::has_type(param_ty.clone()));
+                }
+                sig.ret().clone()
+            }
+            None => {
+                if receiver_ty != Ty::Unknown {
+                    self.diagnostics.push(InferenceDiagnostic::NoSuchMethod {
+                        id: tgt_expr,
+                        receiver_ty,
+                        name: method_name.clone(),
+                    });
+                }
+
+                // Still derive subtypes
+                for arg in args.iter() {
+                    self.infer_expr(*arg, &Expectation::none());
+                }
+                Ty::Unknown
+            }
+        }
+    }
+
     /// Checks whether the specified struct type is a unit struct.
     fn check_unit_struct_lit(&mut self, tgt_expr: ExprId, expected: Struct) {
         let struct_data = expected.data(self.db.upcast());
@@ -631,7 +932,15 @@ impl<'a> InferenceResultBuilder<'a> {
     }
 
     // Checks whether the passed fields match the fields of a struct definition.
-    fn check_record_lit(&mut self, tgt_expr: ExprId, expected: Struct, fields: &[RecordLitField]) {
+    /// Checks that a record literal of the `expected` struct initializes all of its fields. Fields
+    /// that are not specified are copied from the base value of a spread, if there is one.
+    fn check_record_lit(
+        &mut self,
+        tgt_expr: ExprId,
+        expected: Struct,
+        fields: &[RecordLitField],
+        has_spread: bool,
+    ) {
         let struct_data = expected.data(self.db.upcast());
         if struct_data.kind != StructKind::Record {
             self.diagnostics
@@ -657,10 +966,19 @@ impl<'a> InferenceResultBuilder<'a> {
             })
             .collect();
 
-        if !missed_fields.is_empty() {
+        if !has_spread && !missed_fields.is_empty() {
+            let tys = missed_fields
+                .iter()
+                .map(|name| {
+                    expected
+                        .field(self.db, name)
+                        .map_or(Ty::Unknown, |field| field.ty(self.db))
+                })
+                .collect();
             self.diagnostics.push(InferenceDiagnostic::MissingFields {
                 id: tgt_expr,
                 names: missed_fields,
+                tys,
             });
         }
     }
@@ -730,7 +1048,8 @@ impl<'a> InferenceResultBuilder<'a> {
             *ty = resolved;
         }
         InferenceResult {
-            //            method_resolutions: self.method_resolutions,
+            method_resolutions: self.method_resolutions,
+            operator_resolutions: self.operator_resolutions,
             //            field_resolutions: self.field_resolutions,
             //            variant_resolutions: self.variant_resolutions,
             //            assoc_resolutions: self.assoc_resolutions,
@@ -831,19 +1150,38 @@ impl<'a> InferenceResultBuilder<'a> {
         }
     }
 
-    fn infer_break(&mut self, tgt_expr: ExprId, expr: Option<ExprId>) -> Ty {
-        let expected = match &self.active_loop {
-            Some(ActiveLoop::Loop(_, info)) => info.clone(),
-            Some(_) => {
-                if expr.is_some() {
+    /// Returns the index in `active_loops` of the loop that is targeted by a `break` or `continue`
+    /// with the specified label. Without a label the innermost loop is targeted.
+    fn find_active_loop(&self, label: Option<&Name>) -> Option<usize> {
+        match label {
+            Some(label) => self
+                .active_loops
+                .iter()
+                .rposition(|(loop_label, _)| loop_label.as_ref() == Some(label)),
+            None => self.active_loops.len().checked_sub(1),
+        }
+    }
+
+    fn infer_break(&mut self, tgt_expr: ExprId, expr: Option<ExprId>, label: Option<&Name>) -> Ty {
+        let loop_idx = match self.find_active_loop(label) {
+            Some(idx) => idx,
+            None => {
+                // An undeclared label is already reported when the body is lowered
+                if label.is_none() {
                     self.diagnostics
-                        .push(InferenceDiagnostic::BreakWithValueOutsideLoop { id: tgt_expr });
+                        .push(InferenceDiagnostic::BreakOutsideLoop { id: tgt_expr });
                 }
                 return Ty::simple(TypeCtor::Never);
             }
-            None => {
-                self.diagnostics
-                    .push(InferenceDiagnostic::BreakOutsideLoop { id: tgt_expr });
+        };
+
+        let expected = match &self.active_loops[loop_idx].1 {
+            ActiveLoop::Loop(_, info) => info.clone(),
+            _ => {
+                if expr.is_some() {
+                    self.diagnostics
+                        .push(InferenceDiagnostic::BreakWithValueOutsideLoop { id: tgt_expr });
+                }
                 return Ty::simple(TypeCtor::Never);
             }
         };
@@ -868,14 +1206,30 @@ impl<'a> InferenceResultBuilder<'a> {
         };
 
         // Update the expected type for the rest of the loop
-        self.active_loop = Some(ActiveLoop::Loop(ty.clone(), Expectation::has_type(ty)));
+        self.active_loops[loop_idx].1 = ActiveLoop::Loop(ty.clone(), Expectation::has_type(ty));
+
+        Ty::simple(TypeCtor::Never)
+    }
 
+    fn infer_continue(&mut self, tgt_expr: ExprId, label: Option<&Name>) -> Ty {
+        // An undeclared label is already reported when the body is lowered
+        if label.is_none() && self.find_active_loop(None).is_none() {
+            self.diagnostics
+                .push(InferenceDiagnostic::ContinueOutsideLoop { id: tgt_expr });
+        }
         Ty::simple(TypeCtor::Never)
     }
 
-    fn infer_loop_expr(&mut self, _tgt_expr: ExprId, body: ExprId, expected: &Expectation) -> Ty {
+    fn infer_loop_expr(
+        &mut self,
+        _tgt_expr: ExprId,
+        body: ExprId,
+        label: Option<Name>,
+        expected: &Expectation,
+    ) -> Ty {
         if let ActiveLoop::Loop(ty, _) = self.infer_loop_block(
             body,
+            label,
             ActiveLoop::Loop(Ty::simple(TypeCtor::Never), expected.clone()),
         ) {
             ty
@@ -884,14 +1238,19 @@ impl<'a> InferenceResultBuilder<'a> {
         }
     }
 
-    fn infer_loop_block(&mut self, body: ExprId, lp: ActiveLoop) -> ActiveLoop {
-        let top_level_loop = std::mem::replace(&mut self.active_loop, Some(lp));
+    fn infer_loop_block(
+        &mut self,
+        body: ExprId,
+        label: Option<Name>,
+        lp: ActiveLoop,
+    ) -> ActiveLoop {
+        self.active_loops.push((label, lp));
 
         // Infer the body of the loop
         self.infer_expr_coerce(body, &Expectation::has_type(Ty::Empty));
 
-        // Take the result of the loop information and replace with top level loop
-        std::mem::replace(&mut self.active_loop, top_level_loop).unwrap()
+        // Take the result of the loop information
+        self.active_loops.pop().unwrap().1
     }
 
     fn infer_while_expr(
@@ -899,6 +1258,7 @@ impl<'a> InferenceResultBuilder<'a> {
         _tgt_expr: ExprId,
         condition: ExprId,
         body: ExprId,
+        label: Option<Name>,
         _expected: &Expectation,
     ) -> Ty {
         self.infer_expr(
@@ -906,10 +1266,24 @@ impl<'a> InferenceResultBuilder<'a> {
             &Expectation::has_type(Ty::simple(TypeCtor::Bool)),
         );
 
-        self.infer_loop_block(body, ActiveLoop::While);
+        self.infer_loop_block(body, label, ActiveLoop::While);
         Ty::Empty
     }
 
+    /// Infers the message of a `core::panic()` or `core::assert()` call, which has to be a string
+    /// literal because its text is embedded in the assembly.
+    fn infer_panic_message(&mut self, message: ExprId) {
+        self.infer_expr(message, &Expectation::none());
+        let is_literal = match &self.body[message] {
+            Expr::Literal(Literal::String(_)) => true,
+            _ => false,
+        };
+        if !is_literal {
+            self.diagnostics
+                .push(InferenceDiagnostic::PanicMessageNotALiteral { id: message });
+        }
+    }
+
     pub fn report_pat_inference_failure(&mut self, _pat: PatId) {
         //        self.diagnostics.push(InferenceDiagnostic::PatInferenceFailed {
         //            pat
@@ -991,18 +1365,22 @@ impl From<PatId> for ExprOrPatId {
 mod diagnostics {
     use crate::diagnostics::{
         AccessUnknownField, BreakOutsideLoop, BreakWithValueOutsideLoop, CannotApplyBinaryOp,
-        CannotApplyUnaryOp, ExpectedFunction, FieldCountMismatch, IncompatibleBranch, InvalidLHS,
-        LiteralOutOfRange, MismatchedStructLit, MismatchedType, MissingElseBranch, MissingFields,
-        NoFields, NoSuchField, ParameterCountMismatch, ReturnMissingExpression,
+        CannotApplyUnaryOp, ContinueOutsideLoop, ExpectedFunction, FieldCountMismatch,
+        IncompatibleBranch, InvalidCast, InvalidIntrinsicArgument, InvalidLHS, LiteralOutOfRange,
+        MismatchedStructLit, MismatchedType, MissingElseBranch, MissingFields, NoFields,
+        NoSuchField, NoSuchMethod, PanicMessageNotALiteral, ParameterCountMismatch,
+        ReturnMissingExpression,
     };
     use crate::{
         adt::StructKind,
         code_model::src::HasSource,
         diagnostics::{DiagnosticSink, UnresolvedType, UnresolvedValue},
         ty::infer::ExprOrPatId,
+        ty::lower::LowerDiagnostic,
         type_ref::TypeRefId,
-        ExprId, Function, HirDatabase, IntTy, Name, Ty,
+        Body, Expr, ExprId, Function, HirDatabase, IntTy, MathIntrinsic, Name, Ty,
     };
+    use mun_syntax::{AstNode, SyntaxNodePtr};
 
     #[derive(Debug, PartialEq, Eq, Clone)]
     pub(crate) enum InferenceDiagnostic {
@@ -1044,6 +1422,11 @@ mod diagnostics {
             id: ExprId,
             ty: Ty,
         },
+        InvalidIntrinsicArgument {
+            id: ExprId,
+            intrinsic: MathIntrinsic,
+            ty: Ty,
+        },
         InvalidLHS {
             id: ExprId,
             lhs: ExprId,
@@ -1051,12 +1434,18 @@ mod diagnostics {
         ReturnMissingExpression {
             id: ExprId,
         },
+        PanicMessageNotALiteral {
+            id: ExprId,
+        },
         BreakOutsideLoop {
             id: ExprId,
         },
         BreakWithValueOutsideLoop {
             id: ExprId,
         },
+        ContinueOutsideLoop {
+            id: ExprId,
+        },
         AccessUnknownField {
             id: ExprId,
             receiver_ty: Ty,
@@ -1070,6 +1459,7 @@ mod diagnostics {
         MissingFields {
             id: ExprId,
             names: Vec<Name>,
+            tys: Vec<Ty>,
         },
         MismatchedStructLit {
             id: ExprId,
@@ -1088,6 +1478,17 @@ mod diagnostics {
             id: ExprId,
             literal_ty: IntTy,
         },
+        NoSuchMethod {
+            id: ExprId,
+            receiver_ty: Ty,
+            name: Name,
+        },
+        InvalidCast {
+            id: ExprId,
+            expr_ty: Ty,
+            cast_ty: Ty,
+        },
+        TypeLowering(LowerDiagnostic),
     }
 
     impl InferenceDiagnostic {
@@ -1157,11 +1558,22 @@ mod diagnostics {
                         .unwrap()
                         .value
                         .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    let return_type = if is_returned(&owner.body(db), *id) {
+                        owner
+                            .source(db.upcast())
+                            .value
+                            .ret_type()
+                            .and_then(|ret_type| ret_type.type_ref())
+                            .map(|type_ref| SyntaxNodePtr::new(type_ref.syntax()))
+                    } else {
+                        None
+                    };
                     sink.push(MismatchedType {
                         file,
                         expr,
                         found: found.clone(),
                         expected: expected.clone(),
+                        return_type,
                     });
                 }
                 InferenceDiagnostic::IncompatibleBranches {
@@ -1218,6 +1630,19 @@ mod diagnostics {
                         ty: ty.clone(),
                     });
                 }
+                InferenceDiagnostic::InvalidIntrinsicArgument { id, intrinsic, ty } => {
+                    let expr = body
+                        .expr_syntax(*id)
+                        .unwrap()
+                        .value
+                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    sink.push(InvalidIntrinsicArgument {
+                        file,
+                        expr,
+                        intrinsic: *intrinsic,
+                        ty: ty.clone(),
+                    });
+                }
                 InferenceDiagnostic::InvalidLHS { id, lhs } => {
                     let id = body
                         .expr_syntax(*id)
@@ -1246,6 +1671,17 @@ mod diagnostics {
                         return_expr: id,
                     });
                 }
+                InferenceDiagnostic::PanicMessageNotALiteral { id } => {
+                    let id = body
+                        .expr_syntax(*id)
+                        .unwrap()
+                        .value
+                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    sink.push(PanicMessageNotALiteral {
+                        file,
+                        message_expr: id,
+                    });
+                }
                 InferenceDiagnostic::BreakOutsideLoop { id } => {
                     let id = body
                         .expr_syntax(*id)
@@ -1268,6 +1704,17 @@ mod diagnostics {
                         break_expr: id,
                     });
                 }
+                InferenceDiagnostic::ContinueOutsideLoop { id } => {
+                    let id = body
+                        .expr_syntax(*id)
+                        .unwrap()
+                        .value
+                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    sink.push(ContinueOutsideLoop {
+                        file,
+                        continue_expr: id,
+                    });
+                }
                 InferenceDiagnostic::AccessUnknownField {
                     id,
                     receiver_ty,
@@ -1302,7 +1749,7 @@ mod diagnostics {
                         found: *found,
                     })
                 }
-                InferenceDiagnostic::MissingFields { id, names } => {
+                InferenceDiagnostic::MissingFields { id, names, tys } => {
                     let fields = body
                         .expr_syntax(*id)
                         .unwrap()
@@ -1313,6 +1760,7 @@ mod diagnostics {
                         file,
                         fields,
                         field_names: names.to_vec(),
+                        field_types: tys.to_vec(),
                     });
                 }
                 InferenceDiagnostic::MismatchedStructLit {
@@ -1364,7 +1812,67 @@ mod diagnostics {
                         int_ty: *literal_ty,
                     })
                 }
+                InferenceDiagnostic::NoSuchMethod {
+                    id,
+                    receiver_ty,
+                    name,
+                } => {
+                    let expr = body
+                        .expr_syntax(*id)
+                        .unwrap()
+                        .value
+                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    sink.push(NoSuchMethod {
+                        file,
+                        expr,
+                        receiver_ty: receiver_ty.clone(),
+                        name: name.clone(),
+                    });
+                }
+                InferenceDiagnostic::InvalidCast {
+                    id,
+                    expr_ty,
+                    cast_ty,
+                } => {
+                    let expr = body
+                        .expr_syntax(*id)
+                        .unwrap()
+                        .value
+                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    sink.push(InvalidCast {
+                        file,
+                        expr,
+                        expr_ty: expr_ty.clone(),
+                        cast_ty: cast_ty.clone(),
+                    });
+                }
+                InferenceDiagnostic::TypeLowering(diag) => {
+                    diag.add_to(db, file, body.type_ref_source_map(), sink)
+                }
             }
         }
     }
+
+    /// Returns true if the value of `expr` is returned from the function, either because it is the
+    /// body of the function, the tail of that body, or the operand of a `return` expression.
+    fn is_returned(body: &Body, expr: ExprId) -> bool {
+        let mut returned = body.body_expr();
+        loop {
+            if returned == expr {
+                return true;
+            }
+            match &body[returned] {
+                Expr::Block {
+                    tail: Some(tail), ..
+                } => returned = *tail,
+                _ => break,
+            }
+        }
+        body.exprs().any(|(_, data)| match data {
+            Expr::Return {
+                expr: Some(returned),
+            } => *returned == expr,
+            _ => false,
+        })
+    }
 }
@@ -5,9 +5,9 @@ use crate::builtin_type::BuiltinType;
 use crate::diagnostics::DiagnosticSink;
 use crate::name_resolution::Namespace;
 use crate::resolve::{Resolution, Resolver};
-use crate::ty::{FnSig, Ty, TypeCtor};
+use crate::ty::{FnSig, IntTy, Ty, TypeCtor};
 use crate::type_ref::{TypeRef, TypeRefId, TypeRefMap, TypeRefSourceMap};
-use crate::{FileId, Function, HirDatabase, ModuleDef, Path, Struct};
+use crate::{FileId, Function, HirDatabase, Impl, ModuleDef, Path, Struct};
 use std::ops::Index;
 use std::sync::Arc;
 
@@ -66,7 +66,27 @@ impl Ty {
         type_ref: TypeRefId,
     ) -> Ty {
         let res = match &type_ref_map[type_ref] {
-            TypeRef::Path(path) => Ty::from_hir_path(db, resolver, path),
+            TypeRef::Path(path) => match Ty::from_hir_path(db, resolver, path) {
+                // Generic builtin types require type arguments
+                Some(ty) if ty == Ty::Unknown && is_atomic_path(db, resolver, path) => {
+                    diagnostics.push(LowerDiagnostic::TypeArgsCountMismatch {
+                        id: type_ref,
+                        expected: 1,
+                        found: 0,
+                    });
+                    Some(Ty::Unknown)
+                }
+                res => res,
+            },
+            TypeRef::Generic { path, args } => Ty::from_hir_generic_path(
+                db,
+                resolver,
+                type_ref_map,
+                diagnostics,
+                type_ref,
+                path,
+                args,
+            ),
             TypeRef::Error => Some(Ty::Unknown),
             TypeRef::Empty => Some(Ty::Empty),
             TypeRef::Never => Some(Ty::simple(TypeCtor::Never)),
@@ -79,6 +99,58 @@ impl Ty {
         }
     }
 
+    /// Lowers a path with type arguments, e.g. `Atomic<i32>`. Only builtin types can have type
+    /// arguments.
+    fn from_hir_generic_path(
+        db: &dyn HirDatabase,
+        resolver: &Resolver,
+        type_ref_map: &TypeRefMap,
+        diagnostics: &mut Vec<LowerDiagnostic>,
+        type_ref: TypeRefId,
+        path: &Path,
+        args: &[TypeRefId],
+    ) -> Option<Self> {
+        let resolution = resolver
+            .resolve_path_without_assoc_items(db, path)
+            .take_types();
+
+        match resolution {
+            Some(Resolution::Def(ModuleDef::BuiltinType(BuiltinType::Atomic))) => (),
+            Some(_) => {
+                diagnostics.push(LowerDiagnostic::TypeArgsCountMismatch {
+                    id: type_ref,
+                    expected: 0,
+                    found: args.len(),
+                });
+                return Some(Ty::Unknown);
+            }
+            None => return None,
+        }
+
+        if args.len() != 1 {
+            diagnostics.push(LowerDiagnostic::TypeArgsCountMismatch {
+                id: type_ref,
+                expected: 1,
+                found: args.len(),
+            });
+            return Some(Ty::Unknown);
+        }
+
+        // The diagnostics of the type argument are reported when the argument itself is lowered
+        let arg = args[0];
+        let arg_ty = Ty::from_hir(db, resolver, type_ref_map, arg).ty;
+        match arg_ty.as_simple() {
+            Some(TypeCtor::Int(int_ty)) if int_ty == IntTy::i32() || int_ty == IntTy::u64() => {
+                Some(Ty::simple(TypeCtor::Atomic(int_ty)))
+            }
+            _ if arg_ty == Ty::Unknown => Some(Ty::Unknown),
+            _ => {
+                diagnostics.push(LowerDiagnostic::InvalidAtomicType { id: arg });
+                Some(Ty::Unknown)
+            }
+        }
+    }
+
     pub(crate) fn from_hir_path(
         db: &dyn HirDatabase,
         resolver: &Resolver,
@@ -107,6 +179,17 @@ impl Ty {
     }
 }
 
+/// Returns true if the specified path refers to the builtin `Atomic` type.
+fn is_atomic_path(db: &dyn HirDatabase, resolver: &Resolver, path: &Path) -> bool {
+    match resolver
+        .resolve_path_without_assoc_items(db, path)
+        .take_types()
+    {
+        Some(Resolution::Def(ModuleDef::BuiltinType(BuiltinType::Atomic))) => true,
+        _ => false,
+    }
+}
+
 pub fn types_from_hir(
     db: &dyn HirDatabase,
     resolver: &Resolver,
@@ -129,6 +212,11 @@ pub fn lower_struct_query(db: &dyn HirDatabase, s: Struct) -> Arc<LowerBatchResu
     types_from_hir(db, &s.resolver(db), data.type_ref_map())
 }
 
+pub fn lower_impl_query(db: &dyn HirDatabase, i: Impl) -> Arc<LowerBatchResult> {
+    let data = i.data(db.upcast());
+    types_from_hir(db, &i.resolver(db), data.type_ref_map())
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum TypableDef {
     Function(Function),
@@ -210,6 +298,13 @@ fn type_for_builtin(def: BuiltinType) -> Ty {
         BuiltinType::Float(f) => TypeCtor::Float(f.into()),
         BuiltinType::Int(i) => TypeCtor::Int(i.into()),
         BuiltinType::Bool => TypeCtor::Bool,
+        BuiltinType::Char => TypeCtor::Char,
+        BuiltinType::SourceLocation => TypeCtor::SourceLocation,
+        BuiltinType::Any => TypeCtor::Any,
+        BuiltinType::Handle => TypeCtor::Handle,
+        BuiltinType::FloatVector(lanes) => TypeCtor::FloatVector(lanes),
+        // Atomics can only be constructed from a path with type arguments
+        BuiltinType::Atomic => return Ty::Unknown,
     })
 }
 
@@ -265,7 +360,7 @@ fn type_for_struct(_db: &dyn HirDatabase, def: Struct) -> Ty {
 }
 
 pub mod diagnostics {
-    use crate::diagnostics::UnresolvedType;
+    use crate::diagnostics::{InvalidAtomicType, TypeArgsCountMismatch, UnresolvedType};
     use crate::{
         diagnostics::DiagnosticSink,
         type_ref::{TypeRefId, TypeRefSourceMap},
@@ -274,7 +369,17 @@ pub mod diagnostics {
 
     #[derive(Debug, PartialEq, Eq, Clone)]
     pub(crate) enum LowerDiagnostic {
-        UnresolvedType { id: TypeRefId },
+        UnresolvedType {
+            id: TypeRefId,
+        },
+        TypeArgsCountMismatch {
+            id: TypeRefId,
+            expected: usize,
+            found: usize,
+        },
+        InvalidAtomicType {
+            id: TypeRefId,
+        },
     }
 
     impl LowerDiagnostic {
@@ -290,6 +395,20 @@ pub mod diagnostics {
                     file: file_id,
                     type_ref: source_map.type_ref_syntax(*id).unwrap(),
                 }),
+                LowerDiagnostic::TypeArgsCountMismatch {
+                    id,
+                    expected,
+                    found,
+                } => sink.push(TypeArgsCountMismatch {
+                    file: file_id,
+                    type_ref: source_map.type_ref_syntax(*id).unwrap(),
+                    expected: *expected,
+                    found: *found,
+                }),
+                LowerDiagnostic::InvalidAtomicType { id } => sink.push(InvalidAtomicType {
+                    file: file_id,
+                    type_ref: source_map.type_ref_syntax(*id).unwrap(),
+                }),
             }
         }
     }
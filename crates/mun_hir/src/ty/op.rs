@@ -1,5 +1,5 @@
 use crate::ty::infer::InferTy;
-use crate::{ApplicationTy, ArithOp, BinaryOp, Ty, TypeCtor};
+use crate::{ApplicationTy, ArithOp, BinaryOp, CmpOp, Ty, TypeCtor};
 
 /// Given a binary operation and the type on the left of that operation, returns the expected type
 /// for the right hand side of the operation or `Ty::Unknown` if such an operation is invalid.
@@ -7,10 +7,15 @@ pub(super) fn binary_op_rhs_expectation(op: BinaryOp, lhs_ty: Ty) -> Ty {
     match op {
         BinaryOp::LogicOp(..) => Ty::simple(TypeCtor::Bool),
 
-        // Compare operations are allowed for all scalar types
-        BinaryOp::CmpOp(..) => match lhs_ty {
+        // Compare operations are allowed for all scalar types, handles can only be compared for
+        // equality
+        BinaryOp::CmpOp(op) => match lhs_ty {
             Ty::Apply(ApplicationTy { ctor, .. }) => match ctor {
-                TypeCtor::Int(_) | TypeCtor::Float(_) | TypeCtor::Bool => lhs_ty,
+                TypeCtor::Int(_) | TypeCtor::Float(_) | TypeCtor::Bool | TypeCtor::Char => lhs_ty,
+                TypeCtor::Handle => match op {
+                    CmpOp::Eq { .. } => lhs_ty,
+                    CmpOp::Ord { .. } => Ty::Unknown,
+                },
                 _ => Ty::Unknown,
             },
             Ty::Infer(InferTy::IntVar(..)) | Ty::Infer(InferTy::FloatVar(..)) => lhs_ty,
@@ -19,9 +24,16 @@ pub(super) fn binary_op_rhs_expectation(op: BinaryOp, lhs_ty: Ty) -> Ty {
 
         BinaryOp::Assignment { op: None } => match lhs_ty {
             Ty::Apply(ApplicationTy { ctor, .. }) => match ctor {
-                TypeCtor::Int(_) | TypeCtor::Float(_) | TypeCtor::Bool | TypeCtor::Struct(_) => {
-                    lhs_ty
-                }
+                TypeCtor::Int(_)
+                | TypeCtor::Float(_)
+                | TypeCtor::Bool
+                | TypeCtor::Char
+                | TypeCtor::SourceLocation
+                | TypeCtor::Any
+                | TypeCtor::Handle
+                | TypeCtor::FloatVector(_)
+                | TypeCtor::Optional(_)
+                | TypeCtor::Struct(_) => lhs_ty,
                 _ => Ty::Unknown,
             },
             Ty::Infer(InferTy::IntVar(..)) | Ty::Infer(InferTy::FloatVar(..)) => lhs_ty,
@@ -55,10 +67,10 @@ pub(super) fn binary_op_rhs_expectation(op: BinaryOp, lhs_ty: Ty) -> Ty {
             _ => Ty::Unknown,
         },
 
-        // Arithmetic operations are supported only on number types
+        // Arithmetic operations are supported only on number types and, lane-wise, on vectors
         BinaryOp::Assignment { op: Some(_) } | BinaryOp::ArithOp(_) => match lhs_ty {
             Ty::Apply(ApplicationTy { ctor, .. }) => match ctor {
-                TypeCtor::Int(_) | TypeCtor::Float(_) => lhs_ty,
+                TypeCtor::Int(_) | TypeCtor::Float(_) | TypeCtor::FloatVector(_) => lhs_ty,
                 _ => Ty::Unknown,
             },
             Ty::Infer(InferTy::IntVar(..)) | Ty::Infer(InferTy::FloatVar(..)) => lhs_ty,
@@ -73,7 +85,7 @@ pub(super) fn binary_op_return_ty(op: BinaryOp, rhs_ty: Ty) -> Ty {
     match op {
         BinaryOp::ArithOp(_) => match rhs_ty {
             Ty::Apply(ApplicationTy { ctor, .. }) => match ctor {
-                TypeCtor::Int(_) | TypeCtor::Float(_) => rhs_ty,
+                TypeCtor::Int(_) | TypeCtor::Float(_) | TypeCtor::FloatVector(_) => rhs_ty,
                 _ => Ty::Unknown,
             },
             Ty::Infer(InferTy::IntVar(..)) | Ty::Infer(InferTy::FloatVar(..)) => rhs_ty,
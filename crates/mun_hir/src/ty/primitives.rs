@@ -211,3 +211,23 @@ impl From<BuiltinFloat> for FloatTy {
         FloatTy { bitness: t.bitness }
     }
 }
+
+/// A primitive type that can be stored in an `any` value.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum PrimitiveTy {
+    Bool,
+    Char,
+    Int(IntTy),
+    Float(FloatTy),
+}
+
+impl fmt::Display for PrimitiveTy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PrimitiveTy::Bool => write!(f, "bool"),
+            PrimitiveTy::Char => write!(f, "char"),
+            PrimitiveTy::Int(ty) => write!(f, "{}", ty),
+            PrimitiveTy::Float(ty) => write!(f, "{}", ty),
+        }
+    }
+}
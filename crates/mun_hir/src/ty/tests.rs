@@ -1,9 +1,9 @@
 use crate::db::SourceDatabase;
-use crate::diagnostics::DiagnosticSink;
+use crate::diagnostics::{DiagnosticSink, Severity};
 use crate::expr::BodySourceMap;
 use crate::ids::LocationCtx;
 use crate::mock::MockDatabase;
-use crate::{Function, HirDisplay, InferenceResult};
+use crate::{Function, HirDisplay, Impl, InferenceResult, Struct};
 use mun_syntax::{ast, AstNode};
 use std::fmt::Write;
 use std::sync::Arc;
@@ -41,6 +41,82 @@ fn infer_literals() {
     )
 }
 
+#[test]
+fn infer_literals_from_context() {
+    infer_snapshot(
+        r"
+        struct Foo { a: u8, b: f32 }
+
+        fn take(a: u16, b: f32) {}
+
+        fn main() -> u64 {
+            take(1, 2.0);
+            let foo = Foo { a: 3, b: 4.0 };
+            foo.a = 5;
+            let x = 6;
+            let y: u32 = x + 1;
+            let z = if true { 7 } else { 8 };
+            take(z, 9.0);
+            let w = 10 * 11;
+            let v: i8 = -w;
+            take(65536, 1.0);            // literal out of range for `u16`
+            Foo { a: 256, b: 1.0 };      // literal out of range for `u8`
+            let unconstrained = 12;
+            let unconstrained_float = 13.0;
+            14
+        }
+    ",
+    )
+}
+
+#[test]
+fn infer_escape_sequences() {
+    infer_snapshot(
+        r#"
+    fn main() {
+        "tab\tnewline\n\u{1F600}";
+        "invalid \q escape";
+        r"raw \q string";
+        '\u{110000}';
+        '\u{zz}';
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_atomics() {
+    infer_snapshot(
+        r#"
+    struct Counter { hits: Atomic<i32>, total: Atomic<u64> }
+
+    fn main(counter: Counter) -> u64 {
+        let c = Counter { hits: 0, total: 3 };
+        c.hits.store(counter.hits.load());
+        c.hits.load(1);                         // too many arguments
+        c.hits.fetch_sub(1);                    // no method named `fetch_sub`
+        c.total.fetch_add(1)
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_char_literals() {
+    infer_snapshot(
+        r"
+    fn main() -> char {
+        let a = 'a';
+        let b: char = '\u{1F600}';
+        a == b;
+        ''; // empty character literal
+        'ab'; // more than one character
+        a
+    }
+    ",
+    )
+}
+
 #[test]
 fn infer_suffix_literals() {
     infer_snapshot(
@@ -257,6 +333,199 @@ fn infer_break() {
     )
 }
 
+#[test]
+fn infer_loop_break_values() {
+    infer_snapshot(
+        r#"
+    struct Foo { a: i32 }
+
+    fn foo(n: u8) -> u8 {
+        let a = loop {
+            if n > 5 {
+                break n;
+            }
+            break 3;
+        };
+        let b: i64 = loop { break 3; };
+        let c = loop {
+            if n > 5 {
+                break Foo { a: 1 };
+            }
+            break Foo { a: 2 };
+        };
+        loop { break a; }
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_labeled_loops() {
+    infer_snapshot(
+        r#"
+    fn foo(n: i32) -> i32 {
+        let a = 'outer: loop {
+            'inner: while n < 3 {
+                if n > 2 {
+                    break 'outer n;
+                }
+                continue 'inner;
+            }
+            loop {
+                break 'outer 3.0;   // error: mismatched type
+            }
+        };
+        'label: while true {
+            break 'label 3;         // error: break with value can only appear in a loop
+        }
+        break 'missing;             // error: undeclared label
+        continue;                   // error: not in a loop
+        a
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_any() {
+    infer_snapshot(
+        r#"
+    struct Foo;
+
+    fn foo(a: any) -> f32 {
+        let b = 3 as any;
+        let c = true as any;
+        let d = a as f32;
+        let e = d.is_some();
+        let f = b as i64;
+        let g = Foo as any;         // error: invalid cast
+        let h = a as Foo;           // error: invalid cast
+        let i = 3 as f32;           // error: invalid cast
+        d.unwrap_or(1.0)
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_source_location() {
+    infer_snapshot(
+        r#"
+    extern fn log_location(file: Handle, line: u32, column: u32);
+
+    fn log(loc: core::SourceLocation) {
+        log_location(loc.file, loc.line, loc.column);
+    }
+
+    fn foo() -> u32 {
+        let loc = core::source_location();
+        log(loc);
+        log(core::source_location());
+        loc.path;                       // error: no such field
+        source_location();              // error: undefined value
+        core::source_location().column
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_float_vectors() {
+    infer_snapshot(
+        r#"
+    fn scale(v: float4, s: f32) -> float4 {
+        v * float4(s, s, s, s)
+    }
+
+    fn foo() -> f32 {
+        let a = float3(1.0, 2.0, 3.0);
+        let b = -a + float3(0.5, 0.5, 0.5);
+        b.x = 4.0;
+        let c = float2(1, 2);           // error: mismatched types
+        a.w;                            // error: no such field
+        a + float2(1.0, 2.0);           // error: mismatched types
+        b.x + b.y * b.z
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_handles() {
+    infer_snapshot(
+        r#"
+    extern fn spawn() -> Handle;
+    extern fn destroy(entity: Handle);
+
+    struct Player {
+        entity: Handle,
+    }
+
+    fn foo(player: Player) -> bool {
+        let entity = spawn();
+        destroy(player.entity);
+        entity < player.entity;         // error: invalid operator
+        entity + 1;                     // error: invalid operator
+        entity != player.entity
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_math_intrinsics() {
+    infer_snapshot(
+        r#"
+    fn length(x: f32, y: f32) -> f32 {
+        core::sqrt(x * x + y * y)
+    }
+
+    fn foo(a: i32, v: float2) -> f64 {
+        let b = core::abs(a - 10);
+        let c = core::max(b, 3);
+        let d = core::min(v, float2(0.0, 1.0));
+        core::sqrt(4);                  // error: expects floating point arguments
+        core::pow(2.0, core::cos(0.5) + core::sin(0.5))
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_panic_and_assert() {
+    infer_snapshot(
+        r#"
+    fn checked_div(a: i32, b: i32) -> i32 {
+        core::assert(b != 0, "division by zero");
+        a / b
+    }
+
+    fn positive(a: i32) -> i32 {
+        core::assert(a > 0);
+        a
+    }
+
+    fn never_returns() -> i32 {
+        core::panic()
+    }
+
+    fn unreachable(a: bool) -> i32 {
+        if a {
+            1
+        } else {
+            core::panic("unreachable")
+        }
+    }
+
+    fn errors(a: i32) {
+        core::assert(a);                // error: mismatched type
+        core::assert(true, "a", "b");   // error: undefined value
+        core::panic(a);                 // error: message must be a string literal
+    }
+    "#,
+    )
+}
+
 #[test]
 fn infer_while() {
     infer_snapshot(
@@ -334,6 +603,188 @@ fn struct_lit() {
     )
 }
 
+#[test]
+fn struct_lit_spread() {
+    infer_snapshot(
+        r#"
+    struct Foo {
+        a: f64,
+        b: i32,
+    }
+    struct Bar {
+        a: f64,
+    }
+
+    fn main() {
+        let foo = Foo { a: 1.23, b: 4 };
+        let a = Foo { b: 5, ..foo };
+        let b = Foo { ..foo };
+        let c = Foo { a: 3.0, ..Bar { a: 1.0 } }; // error: mismatched type
+        let d = Foo { c: 3, ..foo }; // error: no such field
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_operator_overloading() {
+    infer_snapshot(
+        r#"
+    struct(value) Vec2 {
+        x: f32,
+        y: f32,
+    }
+
+    impl Add for Vec2 {
+        fn add(lhs: Vec2, rhs: Vec2) -> Vec2 {
+            Vec2 { x: lhs.x + rhs.x, y: lhs.y + rhs.y }
+        }
+    }
+
+    impl Mul for Vec2 {
+        fn mul(lhs: Vec2, rhs: f32) -> Vec2 {
+            Vec2 { x: lhs.x * rhs, y: lhs.y * rhs }
+        }
+    }
+
+    impl Sub for Vec2 {} // error: missing operator method
+    impl Div for Vec2 {
+        fn div(lhs: f32, rhs: f32) -> Vec2 { Vec2 { x: lhs, y: rhs } } // error: invalid signature
+    }
+    impl Neg for Vec2 {} // error: unknown operator trait
+    impl Add for f32 {} // error: not a struct
+    impl Add for Foo {} // error: undefined type
+
+    fn main() {
+        let a = Vec2 { x: 1.0, y: 2.0 };
+        let b = a + a;
+        let c = a * 2.0;
+        let d = a - a; // error: cannot apply binary operator
+        let e = a * a; // error: mismatched type
+        let f = add(a, a); // error: undefined value
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_derived_comparison() {
+    infer_snapshot(
+        r#"
+    #[derive(Eq, Ord)]
+    struct(value) Version {
+        major: u32,
+        minor: u32,
+    }
+
+    #[derive(Eq)]
+    struct(gc) Package {
+        version: Version,
+        stable: bool,
+    }
+
+    struct Foo;
+
+    #[derive(Eq)]
+    struct Bar {
+        foo: Foo, // error: field does not implement `Eq`
+    }
+
+    #[derive(Hash)] // error: cannot derive `Hash`
+    struct Baz;
+
+    fn main() {
+        let a = Version { major: 1, minor: 2 };
+        let b = Version { major: 1, minor: 3 };
+        let c = a == b;
+        let d = a < b;
+        let e = Package { version: a, stable: true };
+        let f = e != e;
+        let g = e < e; // error: cannot apply binary operator
+        let h = Foo == Foo; // error: cannot apply binary operator
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_inline_attribute() {
+    infer_snapshot(
+        r#"
+    #[inline]
+    fn square(a: f32) -> f32 { a * a }
+
+    #[inline(always)]
+    fn cube(a: f32) -> f32 { a * a * a }
+
+    #[inline(never)]
+    fn twice(a: f32) -> f32 { a + a }
+
+    #[inline(sometimes)] // error: invalid argument
+    fn half(a: f32) -> f32 { a / 2.0 }
+    "#,
+    )
+}
+
+#[test]
+fn infer_export_attribute() {
+    infer_snapshot(
+        r#"
+    #[export(name = "on_update")]
+    pub fn update(delta: f32) -> f32 { delta }
+
+    #[export]   // error: invalid export attribute
+    pub fn foo() {}
+
+    #[export(name = "bar")] // error: only public functions can be exported
+    fn baz() {}
+    "#,
+    )
+}
+
+#[test]
+fn infer_test_and_bench_attributes() {
+    infer_snapshot(
+        r#"
+    #[test]
+    fn adds() { core::assert(1 + 1 == 2); }
+
+    #[test] // error: test function takes a parameter
+    fn with_param(a: i32) {}
+
+    #[test] // error: test function returns a value
+    fn with_return() -> bool { true }
+
+    #[bench]
+    fn sums() { let a = 1 + 2; }
+
+    #[bench] // error: benchmark function takes a parameter
+    fn sums_param(a: i32) {}
+    "#,
+    )
+}
+
+#[test]
+fn struct_lit_field_shorthand() {
+    infer_snapshot(
+        r#"
+    struct Foo {
+        a: f64,
+        b: i32,
+    }
+
+    fn main(a: f64) {
+        let b = 4;
+        let foo = Foo { a, b };
+        let bar = Foo { b, a: 1.0 };
+        let baz = Foo { a, c }; // error: unresolved value `c`, no such field and missing field `b`
+        let b = 1.0;
+        let qux = Foo { a, b }; // error: mismatched type
+    }
+    "#,
+    )
+}
+
 #[test]
 fn struct_field_index() {
     infer_snapshot(
@@ -450,6 +901,10 @@ fn infer(content: &str) -> String {
     let mut diags = String::new();
 
     let mut diag_sink = DiagnosticSink::new(|diag| {
+        // Warnings, like unused variables, are tested separately
+        if diag.severity() == Severity::Warning {
+            return;
+        }
         write!(diags, "{}: {}\n", diag.highlight_range(), diag.message()).unwrap();
     });
 
@@ -465,6 +920,16 @@ fn infer(content: &str) -> String {
             fun.diagnostics(&db, &mut diag_sink);
 
             infer_def(infer_result, source_map);
+        } else if let Some(def) = ast::StructDef::cast(node.clone()) {
+            let s = Struct {
+                id: ctx.to_def(&def),
+            };
+            s.diagnostics(&db, &mut diag_sink);
+        } else if let Some(def) = ast::ImplDef::cast(node.clone()) {
+            let i = Impl {
+                id: ctx.to_def(&def),
+            };
+            i.diagnostics(&db, &mut diag_sink);
         }
     }
 
@@ -492,3 +957,50 @@ fn ellipsize(mut text: String, max_len: usize) -> String {
     text.replace_range(prefix_len..text.len() - suffix_len, ellipsis);
     text
 }
+
+#[test]
+fn infer_diverging_expressions() {
+    infer_snapshot(
+        r#"
+    struct Foo { a: i32 }
+    fn take(a: i32) -> i32 { a }
+    fn diverge() -> never { loop {} }
+
+    fn foo(a: bool) -> i32 {
+        let b: i32 = return 1;
+        let c = take(return 2);
+        let d = Foo { a: return 3 };
+        let e = 1 + return 4;
+        let f = (return 5) * 2;
+        let g = !return 6;
+        let h = -(return 7);
+        let i: f32 = { return 8; };
+        let j: Foo = if a { return 9 } else { diverge() };
+        let k: bool = diverge();
+        b
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_recursive_structs() {
+    infer_snapshot(
+        r#"
+    struct(gc) Node { value: i64, next: Node }
+    struct(gc) Tree { left: Branch, right: Branch }
+    struct(value) Branch { tree: Tree, weight: f32 }
+    struct(value) Infinite { value: i64, inner: Infinite }
+    struct(value) Foo { bar: Bar }
+    struct(value) Bar { foo: Foo }
+
+    fn next(node: Node) -> Node {
+        node.next.next
+    }
+
+    fn left(tree: Tree) -> Tree {
+        tree.left.tree
+    }
+    "#,
+    )
+}
@@ -16,6 +16,11 @@ impl_arena_id!(TypeRefId);
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum TypeRef {
     Path(Path),
+    /// A path with type arguments, e.g. `Atomic<i32>`
+    Generic {
+        path: Path,
+        args: Vec<TypeRefId>,
+    },
     Never,
     Empty,
     Error,
@@ -108,11 +113,23 @@ impl TypeRefBuilder {
         use mun_syntax::ast::TypeRefKind::*;
         let ptr = AstPtr::new(node);
         let type_ref = match node.kind() {
-            PathType(path) => path
-                .path()
-                .and_then(Path::from_ast)
-                .map(TypeRef::Path)
-                .unwrap_or(TypeRef::Error),
+            PathType(path_type) => match path_type.path() {
+                Some(path) => {
+                    let type_args = path.segment().and_then(|s| s.type_arg_list());
+                    match (Path::from_ast(path), type_args) {
+                        (Some(path), Some(type_args)) => {
+                            let args = type_args
+                                .type_args()
+                                .map(|arg| self.alloc_from_node_opt(arg.type_ref().as_ref()))
+                                .collect();
+                            TypeRef::Generic { path, args }
+                        }
+                        (Some(path), None) => TypeRef::Path(path),
+                        (None, _) => TypeRef::Error,
+                    }
+                }
+                None => TypeRef::Error,
+            },
             NeverType(_) => TypeRef::Never,
         };
         self.alloc_type_ref(type_ref, ptr)
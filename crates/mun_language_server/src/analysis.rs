@@ -3,8 +3,10 @@ use crate::change::AnalysisChange;
 use crate::db::AnalysisDatabase;
 use crate::diagnostics;
 use crate::diagnostics::Diagnostic;
+use hir::diagnostics::SourceChange;
 use hir::line_index::LineIndex;
-use hir::SourceDatabase;
+use hir::{HirDatabase, SourceDatabase};
+use mun_syntax::{TextRange, TextUnit};
 use salsa::{ParallelDatabase, Snapshot};
 use std::sync::Arc;
 
@@ -66,6 +68,105 @@ impl AnalysisSnapshot {
         })
     }
 
+    /// Returns the formatted text of the given file, or `None` if it cannot be formatted because
+    /// it contains syntax errors.
+    pub fn format(&self, file_id: hir::FileId) -> Cancelable<Option<String>> {
+        self.with_db(|db| mun_fmt::format(&db.file_text(file_id)).ok())
+    }
+
+    /// Returns the completions at the specified offset in a file, ordered by relevance.
+    pub fn completions(
+        &self,
+        file_id: hir::FileId,
+        offset: TextUnit,
+    ) -> Cancelable<Vec<hir::CompletionItem>> {
+        self.with_db(|db| hir::completions(db, file_id, offset))
+    }
+
+    /// Returns the type or signature and documentation of the code at the specified offset in a
+    /// file, if any.
+    pub fn hover(
+        &self,
+        file_id: hir::FileId,
+        offset: TextUnit,
+    ) -> Cancelable<Option<hir::HoverResult>> {
+        self.with_db(|db| hir::hover(db, file_id, offset))
+    }
+
+    /// Returns the signature of the function that is called at the specified offset in a file, if
+    /// any.
+    pub fn signature_help(
+        &self,
+        file_id: hir::FileId,
+        offset: TextUnit,
+    ) -> Cancelable<Option<hir::SignatureHelp>> {
+        self.with_db(|db| hir::signature_help(db, file_id, offset))
+    }
+
+    /// Returns the location of the definition that the name at the specified offset in a file
+    /// refers to, if any.
+    pub fn goto_definition(
+        &self,
+        file_id: hir::FileId,
+        offset: TextUnit,
+    ) -> Cancelable<Option<hir::NavigationTarget>> {
+        self.with_db(|db| hir::goto_definition(db, file_id, offset))
+    }
+
+    /// Returns all references to the definition that the name at the specified offset in a file
+    /// refers to, optionally including its declaration.
+    pub fn find_references(
+        &self,
+        file_id: hir::FileId,
+        offset: TextUnit,
+        include_declaration: bool,
+    ) -> Cancelable<Option<Vec<hir::Reference>>> {
+        self.with_db(|db| hir::find_references(db, file_id, offset, include_declaration))
+    }
+
+    /// Returns the hints that are shown inline with the code of the specified file
+    pub fn inlay_hints(
+        &self,
+        file_id: hir::FileId,
+        config: &hir::InlayHintsConfig,
+    ) -> Cancelable<Vec<hir::InlayHint>> {
+        self.with_db(|db| hir::inlay_hints(db, file_id, config))
+    }
+
+    /// Returns the outline of the items of the specified file
+    pub fn document_symbols(&self, file_id: hir::FileId) -> Cancelable<Vec<hir::DocumentSymbol>> {
+        self.with_db(|db| hir::document_symbols(db, file_id))
+    }
+
+    /// Returns the changes that rename the definition that the name at the specified offset in a
+    /// file refers to, or the reason why it cannot be renamed.
+    pub fn rename(
+        &self,
+        file_id: hir::FileId,
+        offset: TextUnit,
+        new_name: &str,
+    ) -> Cancelable<Result<Vec<SourceChange>, hir::RenameError>> {
+        self.with_db(|db| hir::rename(db, file_id, offset, new_name))
+    }
+
+    /// Returns the classification of all identifiers in the specified file, ordered by position
+    pub fn classify_identifiers(
+        &self,
+        file_id: hir::FileId,
+    ) -> Cancelable<Arc<Vec<hir::ClassifiedIdent>>> {
+        self.with_db(|db| db.classify_identifiers(file_id))
+    }
+
+    /// Returns the assists that are applicable to the specified range in a file
+    pub fn assists(&self, file_id: hir::FileId, range: TextRange) -> Cancelable<Vec<SourceChange>> {
+        self.with_db(|db| hir::assists(db, file_id, range))
+    }
+
+    /// Returns the text of the specified file
+    pub fn file_text(&self, file_id: hir::FileId) -> Cancelable<Arc<String>> {
+        self.with_db(|db| db.file_text(file_id))
+    }
+
     /// Returns the line index for the specified file
     pub fn file_line_index(&self, file_id: hir::FileId) -> Cancelable<Arc<LineIndex>> {
         self.with_db(|db| db.line_index(file_id))
@@ -5,6 +5,8 @@ use std::path::PathBuf;
 pub struct Config {
     pub watcher: FilesWatcher,
     pub workspace_roots: Vec<PathBuf>,
+    /// The kinds of hints that are shown inline with the code
+    pub inlay_hints: hir::InlayHintsConfig,
 }
 
 impl Default for Config {
@@ -12,6 +14,7 @@ impl Default for Config {
         Self {
             watcher: FilesWatcher::Notify,
             workspace_roots: Vec::new(),
+            inlay_hints: hir::InlayHintsConfig::default(),
         }
     }
 }
@@ -1,3 +1,6 @@
+use crate::diagnostics::Diagnostic;
+use crate::lsp_ext;
+use hir::diagnostics::Severity;
 use lsp_types::Url;
 use mun_syntax::{TextRange, TextUnit};
 use std::path::{Component, Path, Prefix};
@@ -53,6 +56,92 @@ pub fn convert_range(
     }
 }
 
+pub fn convert_position(
+    position: lsp_types::Position,
+    line_index: &hir::line_index::LineIndex,
+) -> Option<TextUnit> {
+    line_index.offset(hir::line_index::LineCol {
+        line: position.line as u32,
+        col: position.character as u32,
+    })
+}
+
+pub fn convert_diagnostic(
+    diagnostic: &Diagnostic,
+    line_index: &hir::line_index::LineIndex,
+) -> lsp_types::Diagnostic {
+    lsp_types::Diagnostic {
+        range: convert_range(diagnostic.range, line_index),
+        severity: Some(match diagnostic.severity {
+            Severity::Error => lsp_types::DiagnosticSeverity::Error,
+            Severity::Warning => lsp_types::DiagnosticSeverity::Warning,
+        }),
+        code: diagnostic
+            .code
+            .map(|code| lsp_types::NumberOrString::String(code.to_owned())),
+        source: Some("mun".to_string()),
+        message: diagnostic.message.clone(),
+        related_information: None,
+        tags: None,
+    }
+}
+
+pub fn convert_completion_kind(kind: hir::CompletionKind) -> lsp_types::CompletionItemKind {
+    match kind {
+        hir::CompletionKind::Local | hir::CompletionKind::Parameter => {
+            lsp_types::CompletionItemKind::Variable
+        }
+        hir::CompletionKind::Field => lsp_types::CompletionItemKind::Field,
+        hir::CompletionKind::Function => lsp_types::CompletionItemKind::Function,
+        hir::CompletionKind::Struct | hir::CompletionKind::BuiltinType => {
+            lsp_types::CompletionItemKind::Struct
+        }
+        hir::CompletionKind::Keyword => lsp_types::CompletionItemKind::Keyword,
+    }
+}
+
+pub fn convert_symbol_kind(kind: hir::SymbolKind) -> lsp_types::SymbolKind {
+    match kind {
+        hir::SymbolKind::Function => lsp_types::SymbolKind::Function,
+        hir::SymbolKind::Struct => lsp_types::SymbolKind::Struct,
+        hir::SymbolKind::Field => lsp_types::SymbolKind::Field,
+        hir::SymbolKind::Impl => lsp_types::SymbolKind::Object,
+    }
+}
+
+pub fn convert_document_symbol(
+    symbol: hir::DocumentSymbol,
+    line_index: &hir::line_index::LineIndex,
+) -> lsp_types::DocumentSymbol {
+    let children = if symbol.children.is_empty() {
+        None
+    } else {
+        Some(
+            symbol
+                .children
+                .into_iter()
+                .map(|child| convert_document_symbol(child, line_index))
+                .collect(),
+        )
+    };
+    lsp_types::DocumentSymbol {
+        name: symbol.name,
+        detail: symbol.detail,
+        kind: convert_symbol_kind(symbol.kind),
+        deprecated: None,
+        range: convert_range(symbol.full_range, line_index),
+        selection_range: convert_range(symbol.focus_range, line_index),
+        children,
+    }
+}
+
+pub fn convert_inlay_kind(kind: hir::InlayKind) -> lsp_ext::InlayKind {
+    match kind {
+        hir::InlayKind::Type => lsp_ext::InlayKind::TypeHint,
+        hir::InlayKind::ParameterName => lsp_ext::InlayKind::ParameterHint,
+    }
+}
+
 pub fn convert_unit(
     range: TextUnit,
     line_index: &hir::line_index::LineIndex,
@@ -29,6 +29,7 @@ impl AnalysisDatabase {
         };
 
         db.set_target(Target::host_target().expect("could not determine host target spec"));
+        db.set_lint_levels(Default::default());
 
         db
     }
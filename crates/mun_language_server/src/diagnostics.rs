@@ -1,14 +1,19 @@
 use crate::db::AnalysisDatabase;
-use hir::SourceDatabase;
+use hir::{
+    diagnostics::{Severity, SourceChange},
+    SourceDatabase,
+};
 use mun_syntax::{Location, TextRange};
 use std::cell::RefCell;
 
 #[derive(Debug)]
 pub struct Diagnostic {
     pub message: String,
+    /// The error code of the diagnostic, e.g. `E0002`, or the name of the lint that reported it
+    pub code: Option<&'static str>,
     pub range: TextRange,
-    // pub fix: Option<SourceChange>,
-    // pub severity: Severity,
+    pub fix: Option<SourceChange>,
+    pub severity: Severity,
 }
 
 /// Converts a location to a a range for use in diagnostics
@@ -27,7 +32,10 @@ pub(crate) fn diagnostics(db: &AnalysisDatabase, file_id: hir::FileId) -> Vec<Di
     let parse = db.parse(file_id);
     result.extend(parse.errors().iter().map(|err| Diagnostic {
         message: err.to_string(),
+        code: Some(hir::error_codes::E0001.code),
         range: location_to_range(err.location()),
+        fix: None,
+        severity: Severity::Error,
     }));
 
     // Add all HIR diagnostics
@@ -35,7 +43,13 @@ pub(crate) fn diagnostics(db: &AnalysisDatabase, file_id: hir::FileId) -> Vec<Di
     let mut sink = hir::diagnostics::DiagnosticSink::new(|d| {
         result.borrow_mut().push(Diagnostic {
             message: d.message(),
+            code: d
+                .code()
+                .map(|code| code.code)
+                .or_else(|| d.lint().map(|lint| lint.name)),
             range: d.highlight_range(),
+            fix: d.fix(db),
+            severity: d.severity(),
         })
     });
     hir::Module::from(file_id).diagnostics(db, &mut sink);
@@ -6,8 +6,10 @@ mod config;
 mod conversion;
 mod db;
 mod diagnostics;
+mod lsp_ext;
 mod main_loop;
 pub mod protocol;
+mod semantic_tokens;
 
 pub use config::Config;
 pub use main_loop::main_loop;
@@ -79,6 +81,22 @@ pub async fn run_server_async() -> Result<()> {
             config.watcher = FilesWatcher::Client;
         }
 
+        // The client can disable kinds of inlay hints, e.g. with
+        // `{ "inlayHints": { "typeHints": false } }` as initialization options
+        if let Some(inlay_hints) = initialize_params
+            .initialization_options
+            .as_ref()
+            .and_then(|options| options.get("inlayHints"))
+        {
+            let enabled = |key: &str| inlay_hints.get(key).and_then(|it| it.as_bool());
+            if let Some(type_hints) = enabled("typeHints") {
+                config.inlay_hints.type_hints = type_hints;
+            }
+            if let Some(parameter_hints) = enabled("parameterHints") {
+                config.inlay_hints.parameter_hints = parameter_hints;
+            }
+        }
+
         // Get the current working directory as fallback
         let cwd = std::env::current_dir()?;
         // Convert the root uri to a PathBuf
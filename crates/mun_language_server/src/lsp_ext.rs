@@ -0,0 +1,56 @@
+//! Extensions of the language server protocol that are specific to the Mun language server.
+
+use lsp_types::{
+    Range, SemanticTokens, SemanticTokensEdits, SemanticTokensEditsParams, TextDocumentIdentifier,
+};
+use serde::{Deserialize, Serialize};
+
+/// Requests the hints that are shown inline with the code of a document, e.g. the inferred types
+/// of variables.
+pub enum InlayHints {}
+
+impl lsp_types::request::Request for InlayHints {
+    type Params = InlayHintsParams;
+    type Result = Vec<InlayHint>;
+    const METHOD: &'static str = "mun/inlayHints";
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHintsParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum InlayKind {
+    /// The inferred type of a binding, which is shown after the binding
+    TypeHint,
+    /// The name of a parameter, which is shown before the argument that is passed to it
+    ParameterHint,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct InlayHint {
+    pub range: Range,
+    pub kind: InlayKind,
+    pub label: String,
+}
+
+/// Requests the edits of the semantic tokens of a document since a previous result. This replaces
+/// the request of `lsp_types`, which represents the edits as a single `SemanticTokensEdit` without
+/// a result id instead of what the protocol specifies.
+pub enum SemanticTokensEditsRequest {}
+
+impl lsp_types::request::Request for SemanticTokensEditsRequest {
+    type Params = SemanticTokensEditsParams;
+    type Result = Option<SemanticTokensEditResult>;
+    const METHOD: &'static str = "textDocument/semanticTokens/edits";
+}
+
+/// The result of a `SemanticTokensEditsRequest`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum SemanticTokensEditResult {
+    Tokens(SemanticTokens),
+    TokensEdits(SemanticTokensEdits),
+}
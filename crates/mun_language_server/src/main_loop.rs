@@ -1,18 +1,26 @@
 use crate::analysis::{Analysis, AnalysisSnapshot};
+use crate::cancelation::Canceled;
 use crate::change::AnalysisChange;
 use crate::config::{Config, FilesWatcher};
-use crate::conversion::{convert_range, url_from_path_with_drive_lowercasing};
-use crate::protocol::{Connection, Message, Notification, Request, RequestId};
+use crate::conversion::{
+    convert_completion_kind, convert_diagnostic, convert_document_symbol, convert_inlay_kind,
+    convert_position, convert_range, url_from_path_with_drive_lowercasing,
+};
+use crate::lsp_ext;
+use crate::protocol::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use crate::semantic_tokens;
 use crate::Result;
 use anyhow::anyhow;
-use async_std::sync::RwLock;
+use async_std::sync::{Mutex, RwLock};
 use futures::channel::mpsc::{unbounded, Sender, UnboundedReceiver, UnboundedSender};
-use futures::{SinkExt, StreamExt};
+use futures::{Future, SinkExt, StreamExt};
+use hir::diagnostics::SourceChange;
 use lsp_types::notification::PublishDiagnostics;
 use lsp_types::{PublishDiagnosticsParams, Url};
+use mun_syntax::{TextRange, TextUnit};
 use ra_vfs::{RootEntry, Vfs, VfsChange, VfsFile};
 use serde::{de::DeserializeOwned, Serialize};
-use std::collections::HashSet;
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 use std::sync::Arc;
 
 /// A `Task` is something that is send from async tasks to the entry point for processing. This
@@ -20,6 +28,7 @@ use std::sync::Arc;
 #[derive(Debug)]
 enum Task {
     Notify(Notification),
+    Respond(Response),
 }
 
 #[derive(Debug)]
@@ -42,6 +51,12 @@ struct LanguageServerState {
 
     /// All the roots in the workspace
     pub local_source_roots: Vec<hir::SourceRootId>,
+
+    /// The kinds of hints that are shown inline with the code
+    pub inlay_hints_config: hir::InlayHintsConfig,
+
+    /// The semantic tokens that were last sent for every document, to compute the edits since then
+    pub semantic_tokens_cache: Arc<Mutex<HashMap<Url, lsp_types::SemanticTokens>>>,
 }
 
 /// A snapshot of the state of the language server
@@ -54,6 +69,12 @@ struct LanguageServerSnapshot {
 
     /// All the roots in the workspace
     pub local_source_roots: Vec<hir::SourceRootId>,
+
+    /// The kinds of hints that are shown inline with the code
+    pub inlay_hints_config: hir::InlayHintsConfig,
+
+    /// The semantic tokens that were last sent for every document, to compute the edits since then
+    pub semantic_tokens_cache: Arc<Mutex<HashMap<Url, lsp_types::SemanticTokens>>>,
 }
 
 /// State maintained for the connection. This includes everything that is required to be able to
@@ -133,6 +154,8 @@ impl LanguageServerState {
             vfs_task_receiver: task_receiver,
             analysis,
             local_source_roots: source_roots,
+            inlay_hints_config: config.inlay_hints,
+            semantic_tokens_cache: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 }
@@ -232,13 +255,590 @@ enum LoopState {
 }
 
 /// Handles a received request
-async fn handle_request(request: Request, connection: &mut ConnectionState) -> Result<LoopState> {
+async fn handle_request(
+    request: Request,
+    connection: &mut ConnectionState,
+    state: &LanguageServerState,
+    pool: &rayon::ThreadPool,
+    task_sender: &UnboundedSender<Task>,
+) -> Result<LoopState> {
     if connection.connection.handle_shutdown(&request).await? {
         return Ok(LoopState::Shutdown);
     };
+
+    RequestDispatcher {
+        request: Some(request),
+        state,
+        pool,
+        task_sender,
+    }
+    .on::<lsp_types::request::CodeActionRequest, _, _>(handle_code_action)
+    .on::<lsp_types::request::Formatting, _, _>(handle_formatting)
+    .on::<lsp_types::request::Completion, _, _>(handle_completion)
+    .on::<lsp_types::request::HoverRequest, _, _>(handle_hover)
+    .on::<lsp_types::request::GotoDefinition, _, _>(handle_goto_definition)
+    .on::<lsp_types::request::References, _, _>(handle_references)
+    .on::<lsp_types::request::Rename, _, _>(handle_rename)
+    .on::<lsp_types::request::DocumentSymbolRequest, _, _>(handle_document_symbols)
+    .on::<lsp_types::request::SignatureHelpRequest, _, _>(handle_signature_help)
+    .on::<lsp_ext::InlayHints, _, _>(handle_inlay_hints)
+    .on::<lsp_types::request::SemanticTokensRequest, _, _>(handle_semantic_tokens)
+    .on::<lsp_ext::SemanticTokensEditsRequest, _, _>(handle_semantic_tokens_edits);
+
     Ok(LoopState::Continue)
 }
 
+/// Dispatches a request to the handler of its type. The handler runs on the thread pool with a
+/// snapshot of the state, so that multiple requests are answered at the same time. When the state
+/// changes, the analysis of the running handlers is canceled and they respond with
+/// `ContentModified`, which asks the client to request the result again.
+struct RequestDispatcher<'a> {
+    /// The request, or `None` if it has been dispatched
+    request: Option<Request>,
+    state: &'a LanguageServerState,
+    pool: &'a rayon::ThreadPool,
+    task_sender: &'a UnboundedSender<Task>,
+}
+
+impl RequestDispatcher<'_> {
+    /// Dispatches the request to `handler` if it is of type `R`.
+    fn on<R, F, Fut>(&mut self, handler: F) -> &mut Self
+    where
+        R: lsp_types::request::Request + 'static,
+        R::Params: DeserializeOwned + Send + 'static,
+        R::Result: Serialize,
+        F: FnOnce(LanguageServerSnapshot, R::Params) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<R::Result>>,
+    {
+        let (id, params) = match self.request.take().map(cast_request::<R>) {
+            Some(Ok(request)) => request,
+            Some(Err(request)) => {
+                self.request = Some(request);
+                return self;
+            }
+            None => return self,
+        };
+
+        let snapshot = self.state.snapshot();
+        let mut task_sender = self.task_sender.clone();
+        self.pool.spawn(move || {
+            async_std::task::block_on(async move {
+                let response = result_to_response(id, handler(snapshot, params).await);
+                task_sender.send(Task::Respond(response)).await.unwrap();
+            })
+        });
+        self
+    }
+}
+
+/// Converts the result of a request handler to a response. Failed requests are reported to the
+/// client instead of shutting down the server.
+fn result_to_response<T: Serialize>(id: RequestId, result: Result<T>) -> Response {
+    match result {
+        Ok(result) => Response::new_ok(id, result),
+        Err(err) => {
+            if err.is::<Canceled>() {
+                Response::new_err(
+                    id,
+                    ErrorCode::ContentModified as i32,
+                    "content modified".to_string(),
+                )
+            } else if let Some(err) = err.downcast_ref::<hir::RenameError>() {
+                // A rename that would change the meaning of the code is reported to the user
+                Response::new_err(id, ErrorCode::InvalidParams as i32, err.to_string())
+            } else {
+                log::error!("failed to handle request: {}", err);
+                Response::new_err(id, ErrorCode::InternalError as i32, err.to_string())
+            }
+        }
+    }
+}
+
+/// Returns the semantic tokens of the requested file and remembers them to compute the edits of a
+/// later request.
+async fn handle_semantic_tokens(
+    state: LanguageServerSnapshot,
+    params: lsp_types::SemanticTokensParams,
+) -> Result<Option<lsp_types::SemanticTokensResult>> {
+    let uri = params.text_document.uri;
+    let tokens = match semantic_tokens_of_document(&state, &uri).await? {
+        Some(tokens) => tokens,
+        None => return Ok(None),
+    };
+    state
+        .semantic_tokens_cache
+        .lock()
+        .await
+        .insert(uri, tokens.clone());
+    Ok(Some(tokens.into()))
+}
+
+/// Returns the edits of the semantic tokens of the requested file since the previous result of the
+/// request, or all tokens if the previous result is not known.
+async fn handle_semantic_tokens_edits(
+    state: LanguageServerSnapshot,
+    params: lsp_types::SemanticTokensEditsParams,
+) -> Result<Option<lsp_ext::SemanticTokensEditResult>> {
+    let uri = params.text_document.uri;
+    let tokens = match semantic_tokens_of_document(&state, &uri).await? {
+        Some(tokens) => tokens,
+        None => return Ok(None),
+    };
+    let mut cache = state.semantic_tokens_cache.lock().await;
+    let previous = cache.insert(uri, tokens.clone());
+    let result = match previous {
+        Some(previous) if previous.result_id == Some(params.previous_result_id) => {
+            lsp_ext::SemanticTokensEditResult::TokensEdits(lsp_types::SemanticTokensEdits {
+                edits: semantic_tokens::diff_tokens(&previous.data, &tokens.data),
+                result_id: tokens.result_id,
+            })
+        }
+        _ => lsp_ext::SemanticTokensEditResult::Tokens(tokens),
+    };
+    Ok(Some(result))
+}
+
+/// Returns the semantic tokens of the identifiers of a document, or `None` if the document is not
+/// known.
+async fn semantic_tokens_of_document(
+    state: &LanguageServerSnapshot,
+    uri: &Url,
+) -> Result<Option<lsp_types::SemanticTokens>> {
+    let path = uri
+        .to_file_path()
+        .map_err(|()| anyhow!("invalid uri: {}", uri))?;
+    let file_id = match state.vfs.read().await.path2file(&path) {
+        Some(file) => hir::FileId(file.0),
+        None => return Ok(None),
+    };
+    let line_index = state.analysis.file_line_index(file_id)?;
+    let idents = state.analysis.classify_identifiers(file_id)?;
+    Ok(Some(semantic_tokens::semantic_tokens(&idents, &line_index)))
+}
+
+/// Returns the hints of the requested file of the kinds that are enabled in the configuration.
+async fn handle_inlay_hints(
+    state: LanguageServerSnapshot,
+    params: lsp_ext::InlayHintsParams,
+) -> Result<Vec<lsp_ext::InlayHint>> {
+    let uri = params.text_document.uri;
+    let path = uri
+        .to_file_path()
+        .map_err(|()| anyhow!("invalid uri: {}", uri))?;
+    let file_id = match state.vfs.read().await.path2file(&path) {
+        Some(file) => hir::FileId(file.0),
+        None => return Ok(Vec::new()),
+    };
+    let line_index = state.analysis.file_line_index(file_id)?;
+    let hints = state
+        .analysis
+        .inlay_hints(file_id, &state.inlay_hints_config)?;
+    Ok(hints
+        .into_iter()
+        .map(|hint| lsp_ext::InlayHint {
+            range: convert_range(hint.range, &line_index),
+            kind: convert_inlay_kind(hint.kind),
+            label: hint.label,
+        })
+        .collect())
+}
+
+/// Returns the signature of the call that surrounds the position of the request, with the
+/// parameter of the argument at that position as the active parameter.
+async fn handle_signature_help(
+    state: LanguageServerSnapshot,
+    params: lsp_types::SignatureHelpParams,
+) -> Result<Option<lsp_types::SignatureHelp>> {
+    let lsp_types::TextDocumentPositionParams {
+        text_document,
+        position,
+    } = params.text_document_position_params;
+    let uri = text_document.uri;
+    let path = uri
+        .to_file_path()
+        .map_err(|()| anyhow!("invalid uri: {}", uri))?;
+    let file_id = match state.vfs.read().await.path2file(&path) {
+        Some(file) => hir::FileId(file.0),
+        None => return Ok(None),
+    };
+    let line_index = state.analysis.file_line_index(file_id)?;
+    let offset = match convert_position(position, &line_index) {
+        Some(offset) => offset,
+        None => return Ok(None),
+    };
+    let help = match state.analysis.signature_help(file_id, offset)? {
+        Some(help) => help,
+        None => return Ok(None),
+    };
+
+    // The offsets of the parameters are counted in UTF-16 code units, like positions
+    let utf16_offset = |offset: TextUnit| help.label[..offset.to_usize()].encode_utf16().count();
+    let parameters = help
+        .parameters
+        .iter()
+        .map(|range| lsp_types::ParameterInformation {
+            label: lsp_types::ParameterLabel::LabelOffsets([
+                utf16_offset(range.start()) as u64,
+                utf16_offset(range.end()) as u64,
+            ]),
+            documentation: None,
+        })
+        .collect();
+    let documentation = help.docs.map(|docs| {
+        lsp_types::Documentation::MarkupContent(lsp_types::MarkupContent {
+            kind: lsp_types::MarkupKind::Markdown,
+            value: docs.as_str().to_string(),
+        })
+    });
+    Ok(Some(lsp_types::SignatureHelp {
+        signatures: vec![lsp_types::SignatureInformation {
+            label: help.label,
+            documentation,
+            parameters: Some(parameters),
+        }],
+        active_signature: Some(0),
+        active_parameter: help.active_parameter.map(|index| index as i64),
+    }))
+}
+
+/// Returns the outline of the functions, structs and impl blocks of the requested file.
+async fn handle_document_symbols(
+    state: LanguageServerSnapshot,
+    params: lsp_types::DocumentSymbolParams,
+) -> Result<Option<lsp_types::DocumentSymbolResponse>> {
+    let uri = params.text_document.uri;
+    let path = uri
+        .to_file_path()
+        .map_err(|()| anyhow!("invalid uri: {}", uri))?;
+    let file_id = match state.vfs.read().await.path2file(&path) {
+        Some(file) => hir::FileId(file.0),
+        None => return Ok(None),
+    };
+    let line_index = state.analysis.file_line_index(file_id)?;
+    let symbols = state.analysis.document_symbols(file_id)?;
+
+    Ok(Some(lsp_types::DocumentSymbolResponse::Nested(
+        symbols
+            .into_iter()
+            .map(|symbol| convert_document_symbol(symbol, &line_index))
+            .collect(),
+    )))
+}
+
+/// Returns the edits that rename the definition that the name at the position of the request
+/// refers to, in all files that refer to it.
+async fn handle_rename(
+    state: LanguageServerSnapshot,
+    params: lsp_types::RenameParams,
+) -> Result<Option<lsp_types::WorkspaceEdit>> {
+    let lsp_types::TextDocumentPositionParams {
+        text_document,
+        position,
+    } = params.text_document_position;
+    let uri = text_document.uri;
+    let path = uri
+        .to_file_path()
+        .map_err(|()| anyhow!("invalid uri: {}", uri))?;
+    let file_id = match state.vfs.read().await.path2file(&path) {
+        Some(file) => hir::FileId(file.0),
+        None => return Ok(None),
+    };
+    let line_index = state.analysis.file_line_index(file_id)?;
+    let offset = match convert_position(position, &line_index) {
+        Some(offset) => offset,
+        None => return Ok(None),
+    };
+    let changes = state.analysis.rename(file_id, offset, &params.new_name)??;
+
+    Ok(Some(state.workspace_edit(changes).await?))
+}
+
+/// Returns the locations of all references to the definition that the name at the position of the
+/// request refers to, in all files of the package.
+async fn handle_references(
+    state: LanguageServerSnapshot,
+    params: lsp_types::ReferenceParams,
+) -> Result<Option<Vec<lsp_types::Location>>> {
+    let lsp_types::TextDocumentPositionParams {
+        text_document,
+        position,
+    } = params.text_document_position;
+    let uri = text_document.uri;
+    let path = uri
+        .to_file_path()
+        .map_err(|()| anyhow!("invalid uri: {}", uri))?;
+    let file_id = match state.vfs.read().await.path2file(&path) {
+        Some(file) => hir::FileId(file.0),
+        None => return Ok(None),
+    };
+    let line_index = state.analysis.file_line_index(file_id)?;
+    let offset = match convert_position(position, &line_index) {
+        Some(offset) => offset,
+        None => return Ok(None),
+    };
+    let references =
+        match state
+            .analysis
+            .find_references(file_id, offset, params.context.include_declaration)?
+        {
+            Some(references) => references,
+            None => return Ok(None),
+        };
+
+    // The line index and uri of a file are only looked up once
+    let mut files = HashMap::new();
+    let mut locations = Vec::with_capacity(references.len());
+    for reference in references {
+        let (line_index, uri) = match files.entry(reference.file_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let line_index = state.analysis.file_line_index(reference.file_id)?;
+                let uri = state.file_id_to_uri(reference.file_id).await?;
+                entry.insert((line_index, uri))
+            }
+        };
+        locations.push(lsp_types::Location {
+            uri: uri.clone(),
+            range: convert_range(reference.range, line_index),
+        });
+    }
+    Ok(Some(locations))
+}
+
+/// Returns the location of the name of the definition that the name at the position of the
+/// request refers to.
+async fn handle_goto_definition(
+    state: LanguageServerSnapshot,
+    params: lsp_types::GotoDefinitionParams,
+) -> Result<Option<lsp_types::GotoDefinitionResponse>> {
+    let lsp_types::TextDocumentPositionParams {
+        text_document,
+        position,
+    } = params.text_document_position_params;
+    let uri = text_document.uri;
+    let path = uri
+        .to_file_path()
+        .map_err(|()| anyhow!("invalid uri: {}", uri))?;
+    let file_id = match state.vfs.read().await.path2file(&path) {
+        Some(file) => hir::FileId(file.0),
+        None => return Ok(None),
+    };
+    let line_index = state.analysis.file_line_index(file_id)?;
+    let offset = match convert_position(position, &line_index) {
+        Some(offset) => offset,
+        None => return Ok(None),
+    };
+    let target = match state.analysis.goto_definition(file_id, offset)? {
+        Some(target) => target,
+        None => return Ok(None),
+    };
+
+    // The definition can be in another file
+    let line_index = state.analysis.file_line_index(target.file_id)?;
+    let uri = state.file_id_to_uri(target.file_id).await?;
+    Ok(Some(lsp_types::GotoDefinitionResponse::Scalar(
+        lsp_types::Location {
+            uri,
+            range: convert_range(target.focus_range, &line_index),
+        },
+    )))
+}
+
+/// Returns the signature and documentation of the definition at the position of the request, or
+/// the type of the expression at the position, as markdown.
+async fn handle_hover(
+    state: LanguageServerSnapshot,
+    params: lsp_types::HoverParams,
+) -> Result<Option<lsp_types::Hover>> {
+    let lsp_types::TextDocumentPositionParams {
+        text_document,
+        position,
+    } = params.text_document_position_params;
+    let uri = text_document.uri;
+    let path = uri
+        .to_file_path()
+        .map_err(|()| anyhow!("invalid uri: {}", uri))?;
+    let file_id = match state.vfs.read().await.path2file(&path) {
+        Some(file) => hir::FileId(file.0),
+        None => return Ok(None),
+    };
+    let line_index = state.analysis.file_line_index(file_id)?;
+    let offset = match convert_position(position, &line_index) {
+        Some(offset) => offset,
+        None => return Ok(None),
+    };
+    let result = match state.analysis.hover(file_id, offset)? {
+        Some(result) => result,
+        None => return Ok(None),
+    };
+
+    let mut value = format!("```mun\n{}\n```", result.signature);
+    if let Some(docs) = result.docs {
+        value.push_str("\n\n");
+        value.push_str(docs.as_str());
+    }
+    Ok(Some(lsp_types::Hover {
+        contents: lsp_types::HoverContents::Markup(lsp_types::MarkupContent {
+            kind: lsp_types::MarkupKind::Markdown,
+            value,
+        }),
+        range: Some(convert_range(result.range, &line_index)),
+    }))
+}
+
+/// Returns the completions at the position of the request. The completions are already ordered by
+/// relevance, which is passed on to the client through their sort text.
+async fn handle_completion(
+    state: LanguageServerSnapshot,
+    params: lsp_types::CompletionParams,
+) -> Result<Option<lsp_types::CompletionResponse>> {
+    let lsp_types::TextDocumentPositionParams {
+        text_document,
+        position,
+    } = params.text_document_position;
+    let uri = text_document.uri;
+    let path = uri
+        .to_file_path()
+        .map_err(|()| anyhow!("invalid uri: {}", uri))?;
+    let file_id = match state.vfs.read().await.path2file(&path) {
+        Some(file) => hir::FileId(file.0),
+        None => return Ok(None),
+    };
+    let line_index = state.analysis.file_line_index(file_id)?;
+    let offset = match convert_position(position, &line_index) {
+        Some(offset) => offset,
+        None => return Ok(None),
+    };
+    let completions = state.analysis.completions(file_id, offset)?;
+
+    let items = completions
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| lsp_types::CompletionItem {
+            label: item.label,
+            kind: Some(convert_completion_kind(item.kind)),
+            detail: item.detail,
+            preselect: if item.type_matches { Some(true) } else { None },
+            sort_text: Some(format!("{:05}", index)),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    Ok(Some(items.into()))
+}
+
+/// Returns an edit that replaces the text of the document with its formatted text. No edit is
+/// returned if the document cannot be formatted because it contains syntax errors.
+async fn handle_formatting(
+    state: LanguageServerSnapshot,
+    params: lsp_types::DocumentFormattingParams,
+) -> Result<Option<Vec<lsp_types::TextEdit>>> {
+    let uri = params.text_document.uri;
+    let path = uri
+        .to_file_path()
+        .map_err(|()| anyhow!("invalid uri: {}", uri))?;
+    let file_id = match state.vfs.read().await.path2file(&path) {
+        Some(file) => hir::FileId(file.0),
+        None => return Ok(None),
+    };
+    let line_index = state.analysis.file_line_index(file_id)?;
+    let text = state.analysis.file_text(file_id)?;
+    let formatted = match state.analysis.format(file_id)? {
+        Some(formatted) => formatted,
+        None => return Ok(None),
+    };
+
+    if formatted == *text {
+        return Ok(Some(Vec::new()));
+    }
+    let range = TextRange::from_to(TextUnit::from(0), TextUnit::of_str(&text));
+    Ok(Some(vec![lsp_types::TextEdit {
+        range: convert_range(range, &line_index),
+        new_text: formatted,
+    }]))
+}
+
+/// Returns the fixes of the diagnostics that overlap with the range of the request as quick fixes,
+/// followed by the assists that apply to the range.
+async fn handle_code_action(
+    state: LanguageServerSnapshot,
+    params: lsp_types::CodeActionParams,
+) -> Result<Option<lsp_types::CodeActionResponse>> {
+    let lsp_types::CodeActionParams {
+        text_document,
+        range: requested_range,
+        ..
+    } = params;
+    let uri = text_document.uri;
+    let path = uri
+        .to_file_path()
+        .map_err(|()| anyhow!("invalid uri: {}", uri))?;
+    let file_id = match state.vfs.read().await.path2file(&path) {
+        Some(file) => hir::FileId(file.0),
+        None => return Ok(None),
+    };
+    let line_index = state.analysis.file_line_index(file_id)?;
+    let diagnostics = state.analysis.diagnostics(file_id)?;
+    let range = match (
+        convert_position(requested_range.start, &line_index),
+        convert_position(requested_range.end, &line_index),
+    ) {
+        (Some(start), Some(end)) if start <= end => TextRange::from_to(start, end),
+        _ => return Ok(None),
+    };
+
+    let mut actions = Vec::new();
+    let mut fixes = Vec::new();
+    for diagnostic in diagnostics {
+        let diagnostic_range = convert_range(diagnostic.range, &line_index);
+        if diagnostic_range.start > requested_range.end
+            || requested_range.start > diagnostic_range.end
+        {
+            continue;
+        }
+        let lsp_diagnostic = convert_diagnostic(&diagnostic, &line_index);
+        let fix = match diagnostic.fix {
+            Some(fix) => fix,
+            None => continue,
+        };
+        let edit = state.workspace_edit(vec![fix.clone()]).await?;
+        actions.push(
+            lsp_types::CodeAction {
+                title: fix.label.clone(),
+                kind: Some(lsp_types::code_action_kind::QUICKFIX.to_owned()),
+                diagnostics: Some(vec![lsp_diagnostic]),
+                edit: Some(edit),
+                command: None,
+                is_preferred: Some(true),
+            }
+            .into(),
+        );
+        fixes.push(fix);
+    }
+
+    let assists = state.analysis.assists(file_id, range)?;
+    // An assist that does the same as the fix of a diagnostic is only offered once
+    for assist in assists.into_iter().filter(|assist| {
+        fixes
+            .iter()
+            .all(|fix| fix.file_id != assist.file_id || fix.edits != assist.edits)
+    }) {
+        let title = assist.label.clone();
+        let edit = state.workspace_edit(vec![assist]).await?;
+        actions.push(
+            lsp_types::CodeAction {
+                title,
+                kind: Some(lsp_types::code_action_kind::REFACTOR.to_owned()),
+                diagnostics: None,
+                edit: Some(edit),
+                command: None,
+                is_preferred: None,
+            }
+            .into(),
+        );
+    }
+    Ok(Some(actions))
+}
+
 /// Handles a received notification
 async fn on_notification(
     notification: Notification,
@@ -285,6 +885,7 @@ async fn on_notification(
                 {
                     // TODO: Keep track of opened files
                 }
+                state.semantic_tokens_cache.lock().await.remove(&uri);
                 let params = lsp_types::PublishDiagnosticsParams {
                     uri,
                     diagnostics: Vec::new(),
@@ -359,7 +960,9 @@ async fn handle_event(
     // Process the incoming event
     let loop_state = match event {
         Event::Task(task) => handle_task(task, &mut connection_state.connection.sender).await?,
-        Event::Msg(msg) => handle_lsp_message(msg, connection_state, state).await?,
+        Event::Msg(msg) => {
+            handle_lsp_message(msg, connection_state, state, pool, task_sender).await?
+        }
         Event::Vfs(task) => handle_vfs_task(task, state).await?,
     };
 
@@ -401,16 +1004,8 @@ async fn handle_diagnostics(state: LanguageServerSnapshot, mut sender: Unbounded
             };
 
             let diagnostics = diagnostics
-                .into_iter()
-                .map(|d| lsp_types::Diagnostic {
-                    range: convert_range(d.range, &line_index),
-                    severity: Some(lsp_types::DiagnosticSeverity::Error),
-                    code: None,
-                    source: Some("mun".to_string()),
-                    message: d.message,
-                    related_information: None,
-                    tags: None,
-                })
+                .iter()
+                .map(|d| convert_diagnostic(d, &line_index))
                 .collect();
 
             sender
@@ -431,6 +1026,7 @@ async fn handle_diagnostics(state: LanguageServerSnapshot, mut sender: Unbounded
 async fn handle_task(task: Task, sender: &mut Sender<Message>) -> Result<LoopState> {
     match task {
         Task::Notify(notification) => sender.send(notification.into()).await?,
+        Task::Respond(response) => sender.send(response.into()).await?,
     }
 
     Ok(LoopState::Continue)
@@ -448,9 +1044,13 @@ async fn handle_lsp_message(
     msg: Message,
     connection_state: &mut ConnectionState,
     state: &LanguageServerState,
+    pool: &rayon::ThreadPool,
+    task_sender: &UnboundedSender<Task>,
 ) -> Result<LoopState> {
     match msg {
-        Message::Request(req) => handle_request(req, connection_state).await,
+        Message::Request(req) => {
+            handle_request(req, connection_state, state, pool, task_sender).await
+        }
         Message::Response(response) => {
             let removed = connection_state.pending_responses.remove(&response.id);
             if !removed {
@@ -483,6 +1083,15 @@ where
     Notification::new(N::METHOD.to_string(), params)
 }
 
+/// Casts a request to the specified type.
+fn cast_request<R>(request: Request) -> std::result::Result<(RequestId, R::Params), Request>
+where
+    R: lsp_types::request::Request,
+    R::Params: DeserializeOwned,
+{
+    request.try_extract(R::METHOD)
+}
+
 /// Casts a notification to the specified type.
 fn cast_notification<N>(notification: Notification) -> std::result::Result<N::Params, Notification>
 where
@@ -499,6 +1108,8 @@ impl LanguageServerState {
             analysis: self.analysis.snapshot(),
             local_source_roots: self.local_source_roots.clone(),
             vfs: self.vfs.clone(),
+            inlay_hints_config: self.inlay_hints_config,
+            semantic_tokens_cache: self.semantic_tokens_cache.clone(),
         }
     }
 
@@ -557,6 +1168,31 @@ impl LanguageServerState {
 }
 
 impl LanguageServerSnapshot {
+    /// Converts the specified changes to a `WorkspaceEdit`
+    pub async fn workspace_edit(
+        &self,
+        changes: Vec<SourceChange>,
+    ) -> Result<lsp_types::WorkspaceEdit> {
+        let mut edits = HashMap::new();
+        for change in changes {
+            let line_index = self.analysis.file_line_index(change.file_id)?;
+            let uri = self.file_id_to_uri(change.file_id).await?;
+            let text_edits = change
+                .edits
+                .into_iter()
+                .map(|edit| lsp_types::TextEdit {
+                    range: convert_range(edit.range, &line_index),
+                    new_text: edit.replace_with,
+                })
+                .collect();
+            edits.insert(uri, text_edits);
+        }
+        Ok(lsp_types::WorkspaceEdit {
+            changes: Some(edits),
+            ..Default::default()
+        })
+    }
+
     /// Converts the specified `FileId` to a `Url`
     pub async fn file_id_to_uri(&self, id: hir::FileId) -> Result<Url> {
         let path = self.vfs.read().await.file2path(VfsFile(id.0));
@@ -5,4 +5,4 @@ mod stdio;
 
 pub use connection::Connection;
 pub use error::ProtocolError;
-pub use message::{Message, Notification, Request, RequestId, Response, ResponseError};
+pub use message::{ErrorCode, Message, Notification, Request, RequestId, Response, ResponseError};
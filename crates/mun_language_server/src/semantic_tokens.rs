@@ -0,0 +1,171 @@
+//! Conversion of the classified identifiers of a file to the semantic tokens of the language server
+//! protocol, and the computation of the edits between two sets of tokens.
+
+use hir::{line_index::LineIndex, ClassifiedIdent, IdentClass};
+use lsp_types::{
+    SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens, SemanticTokensEdit,
+    SemanticTokensLegend,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The modifier of a local binding or parameter that is assigned to after its declaration
+const MUTABLE: SemanticTokenModifier = SemanticTokenModifier::new("mutable");
+
+/// The token types that are reported by the server. The type of a token is its index in this list.
+const SUPPORTED_TYPES: &[SemanticTokenType] = &[
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::STRUCT,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::TYPE,
+];
+
+/// The token modifiers that are reported by the server. The modifiers of a token are a bitset of
+/// the indices in this list.
+const SUPPORTED_MODIFIERS: &[SemanticTokenModifier] = &[MUTABLE];
+
+/// Returns the legend of the token types and modifiers that the server reports.
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: SUPPORTED_TYPES.to_vec(),
+        token_modifiers: SUPPORTED_MODIFIERS.to_vec(),
+    }
+}
+
+/// Returns the semantic tokens of the identifiers of a file, which must be ordered by position,
+/// with a new result id.
+pub fn semantic_tokens(idents: &[ClassifiedIdent], line_index: &LineIndex) -> SemanticTokens {
+    let mut data = Vec::with_capacity(idents.len());
+    let (mut prev_line, mut prev_col) = (0, 0);
+    for ident in idents {
+        let start = line_index.line_col(ident.range.start());
+        let end = line_index.line_col(ident.range.end());
+        // Identifiers never span multiple lines
+        debug_assert_eq!(start.line, end.line);
+
+        // Tokens are encoded relative to the start of the previous token
+        let delta_line = start.line - prev_line;
+        let delta_start = if delta_line == 0 {
+            start.col - prev_col
+        } else {
+            start.col
+        };
+        data.push(SemanticToken {
+            delta_line,
+            delta_start,
+            length: end.col - start.col,
+            token_type: token_type(ident.class),
+            token_modifiers_bitset: if ident.mutable {
+                modifier_bit(&MUTABLE)
+            } else {
+                0
+            },
+        });
+        prev_line = start.line;
+        prev_col = start.col;
+    }
+
+    SemanticTokens {
+        result_id: Some(next_result_id()),
+        data,
+    }
+}
+
+/// Returns the edits that change the tokens `old` into `new`. The unchanged tokens at the start
+/// and at the end are kept, so that a single edit replaces the tokens in between.
+pub fn diff_tokens(old: &[SemanticToken], new: &[SemanticToken]) -> Vec<SemanticTokensEdit> {
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(old, new)| old == new)
+        .count();
+    let (old, new) = (&old[prefix..], &new[prefix..]);
+    let suffix = old
+        .iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+    let (old, new) = (&old[..old.len() - suffix], &new[..new.len() - suffix]);
+    if old.is_empty() && new.is_empty() {
+        return Vec::new();
+    }
+
+    // The start and length of an edit are counted in integers, of which every token has five
+    vec![SemanticTokensEdit {
+        start: 5 * prefix as u32,
+        delete_count: 5 * old.len() as u32,
+        data: Some(new.to_vec()),
+    }]
+}
+
+/// Returns the index of the token type of an identifier in `SUPPORTED_TYPES`.
+fn token_type(class: IdentClass) -> u32 {
+    let ty = match class {
+        IdentClass::Local => SemanticTokenType::VARIABLE,
+        IdentClass::Parameter => SemanticTokenType::PARAMETER,
+        IdentClass::Function => SemanticTokenType::FUNCTION,
+        IdentClass::Struct => SemanticTokenType::STRUCT,
+        IdentClass::Field => SemanticTokenType::PROPERTY,
+        IdentClass::BuiltinType => SemanticTokenType::TYPE,
+    };
+    SUPPORTED_TYPES
+        .iter()
+        .position(|it| *it == ty)
+        .expect("token type must be supported") as u32
+}
+
+/// Returns the bit of a modifier in the bitset of the modifiers of a token.
+fn modifier_bit(modifier: &SemanticTokenModifier) -> u32 {
+    let index = SUPPORTED_MODIFIERS
+        .iter()
+        .position(|it| it == modifier)
+        .expect("token modifier must be supported");
+    1 << index
+}
+
+/// Returns a result id that is different from all previous ones, so that a client can request the
+/// edits since a previous result.
+fn next_result_id() -> String {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+    NEXT_ID.fetch_add(1, Ordering::SeqCst).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diff_tokens;
+    use lsp_types::{SemanticToken, SemanticTokensEdit};
+
+    fn token(delta_start: u32) -> SemanticToken {
+        SemanticToken {
+            delta_line: 0,
+            delta_start,
+            length: 1,
+            token_type: 0,
+            token_modifiers_bitset: 0,
+        }
+    }
+
+    #[test]
+    fn diff_tokens_replaces_changed_tokens() {
+        let old = [token(1), token(2), token(3)];
+        assert_eq!(diff_tokens(&old, &old), Vec::new());
+        assert_eq!(
+            diff_tokens(&old, &[token(1), token(5), token(6), token(3)]),
+            vec![SemanticTokensEdit {
+                start: 5,
+                delete_count: 5,
+                data: Some(vec![token(5), token(6)]),
+            }]
+        );
+        assert_eq!(
+            diff_tokens(&old, &[token(1)]),
+            vec![SemanticTokensEdit {
+                start: 5,
+                delete_count: 10,
+                data: Some(Vec::new()),
+            }]
+        );
+    }
+}
@@ -242,7 +242,11 @@ fn append_struct_mapping<T>(
             // ASSUMPTION: Don't use recursion, because all types are individually checked for
             // differences.
             // TODO: Support value struct vs heap struct?
-            let diff = field_diff(&old_ty.fields(), &new_ty.fields());
+            let diff = field_diff(
+                &old_ty.fields(),
+                &new_ty.fields(),
+                &new_ty.fields_renamed_from(),
+            );
 
             // Edit the struct, potentially moving it in the process.
             Diff::Edit {
@@ -285,8 +289,13 @@ fn append_struct_mapping<T>(
     }
 }
 
-/// Given an `old` and a `new` set of fields, calculates the difference.
-fn field_diff<T>(old: &[(&str, T)], new: &[(&str, T)]) -> Vec<FieldDiff>
+/// Given an `old` and a `new` set of fields, calculates the difference. A new field for which
+/// `renamed_from` contains the name of a deleted field always takes the place of that field.
+fn field_diff<T>(
+    old: &[(&str, T)],
+    new: &[(&str, T)],
+    renamed_from: &[Option<&str>],
+) -> Vec<FieldDiff>
 where
     T: Eq,
 {
@@ -294,9 +303,52 @@ where
     let (deletions, insertions) = myers::split_diff(&diff);
     let mut insertions: Vec<Option<usize>> = insertions.into_iter().map(Some).collect();
 
+    // Reserve all insertions that were explicitly renamed from a deleted field, so they cannot be
+    // matched with another field.
+    let mut renames = Vec::new();
+    for insertion in insertions.iter_mut() {
+        if let Some(new_idx) = insertion {
+            if let Some(Some(old_name)) = renamed_from.get(*new_idx) {
+                let old_idx = deletions.iter().find(|old_idx| {
+                    let old_ty = unsafe { old.get_unchecked(**old_idx) };
+                    old_ty.0 == *old_name && renames.iter().all(|(idx, _)| idx != *old_idx)
+                });
+                if let Some(old_idx) = old_idx {
+                    renames.push((*old_idx, *new_idx));
+                    *insertion = None;
+                }
+            }
+        }
+    }
+
     let mut mapping = Vec::with_capacity(diff.len());
     // For all deletions,
     'outer: for old_idx in deletions {
+        // was the field explicitly renamed?
+        if let Some((_, new_idx)) = renames.iter().find(|(idx, _)| *idx == old_idx) {
+            let old_ty = unsafe { old.get_unchecked(old_idx) };
+            let new_ty = unsafe { new.get_unchecked(*new_idx) };
+            // If so, rename it, converting its type if necessary.
+            let kind = if old_ty.1 == new_ty.1 {
+                FieldEditKind::Rename
+            } else {
+                FieldEditKind::ConvertType
+            };
+            mapping.push(if old_idx == *new_idx {
+                FieldDiff::Edit {
+                    index: old_idx,
+                    kind,
+                }
+            } else {
+                FieldDiff::Move {
+                    old_index: old_idx,
+                    new_index: *new_idx,
+                    edit: Some(kind),
+                }
+            });
+            continue 'outer;
+        }
+
         let old_ty = unsafe { old.get_unchecked(old_idx) };
         // is there an insertion with the same name and type `T`?
         for insertion in insertions.iter_mut() {
@@ -3,16 +3,23 @@ mod ptr;
 mod root_ptr;
 
 use crate::TypeMemory;
-use std::marker::PhantomData;
+use std::{alloc::Layout, fmt, marker::PhantomData, time::Duration};
 
 pub use mark_sweep::MarkSweep;
-pub use ptr::{GcPtr, HasIndirectionPtr, RawGcPtr};
+pub use ptr::{GcPtr, HasIndirectionPtr, RawGcPtr, WeakGcPtr};
 pub use root_ptr::GcRootPtr;
 
 /// Contains stats about the current state of a GC implementation
 #[derive(Debug, Clone, Default)]
 pub struct Stats {
+    /// The number of bytes that are allocated for objects
     pub allocated_memory: usize,
+    /// The number of objects that are allocated
+    pub allocated_objects: usize,
+    /// The number of bytes that were allocated since the last completed collection
+    pub allocated_since_collection: usize,
+    /// The duration of the last collection, or of the last step of an incremental collection
+    pub last_pause: Duration,
 }
 
 /// A trait used to trace an object type.
@@ -42,10 +49,84 @@ pub trait GcRuntime<T: TypeMemory + TypeTrace>: Send + Sync {
     /// the same number of times as `root` was called before the object can be collected.
     fn unroot(&self, obj: GcPtr);
 
+    /// Deallocates the specified `obj` immediately, without waiting for the next collection. This
+    /// must only be called for an object that is no longer referenced. Rooted objects are not
+    /// deallocated.
+    fn dealloc(&self, obj: GcPtr);
+
+    /// Notifies the runtime that the references of the specified `obj` to other objects were
+    /// modified. This must be called after every modification, so an incremental collection that
+    /// is in progress also traces the new references.
+    fn write_barrier(&self, obj: GcPtr);
+
+    /// Returns a weak reference to the specified `obj`, which does not keep it alive.
+    fn downgrade(&self, obj: GcPtr) -> WeakGcPtr;
+
+    /// Returns the object that `weak` refers to, if it has not been collected yet. The returned
+    /// `GcPtr` is not rooted.
+    fn upgrade(&self, weak: WeakGcPtr) -> Option<GcPtr>;
+
     /// Returns stats about the current state of the runtime.
     fn stats(&self) -> Stats;
 }
 
+/// An allocator that a `GcRuntime` uses to allocate the memory of objects. This enables routing
+/// the memory of objects through a custom heap, e.g. to track memory usage or to enforce a memory
+/// budget.
+pub trait Allocator: Send + Sync {
+    /// Allocates memory as described by `layout`. Returns a null pointer if the memory could not
+    /// be allocated, which aborts the program.
+    ///
+    /// # Safety
+    ///
+    /// This function has the same safety requirements as [`std::alloc::GlobalAlloc::alloc`].
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8;
+
+    /// Behaves like `alloc`, but also ensures that the contents are set to zero.
+    ///
+    /// # Safety
+    ///
+    /// This function has the same safety requirements as `alloc`.
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.alloc(layout);
+        if !ptr.is_null() {
+            std::ptr::write_bytes(ptr, 0, layout.size());
+        }
+        ptr
+    }
+
+    /// Deallocates the memory at `ptr`, which was allocated with the same `layout`.
+    ///
+    /// # Safety
+    ///
+    /// This function has the same safety requirements as [`std::alloc::GlobalAlloc::dealloc`].
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout);
+}
+
+impl fmt::Debug for dyn Allocator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Allocator")
+    }
+}
+
+/// An `Allocator` that allocates memory through the global allocator.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemAllocator;
+
+impl Allocator for SystemAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        std::alloc::alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        std::alloc::alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        std::alloc::dealloc(ptr, layout)
+    }
+}
+
 /// The `Observer` trait allows receiving of `Event`s.
 pub trait Observer: Send + Sync {
     type Event;
@@ -63,7 +144,8 @@ pub enum Event {
     /// A GC cycle started
     Start,
 
-    /// A deallocation took place
+    /// A deallocation took place. The type of the object can still be retrieved while the event is
+    /// handled, see [`MarkSweep::ptr_type_unchecked`].
     Deallocation(GcPtr),
 
     /// A GC cycle ended
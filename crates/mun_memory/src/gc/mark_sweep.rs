@@ -1,21 +1,34 @@
 use crate::{
     cast,
-    gc::{Event, GcPtr, GcRuntime, Observer, RawGcPtr, Stats, TypeTrace},
+    gc::{
+        Allocator, Event, GcPtr, GcRuntime, Observer, RawGcPtr, Stats, SystemAllocator, TypeTrace,
+        WeakGcPtr,
+    },
     mapping::{self, FieldMapping, MemoryMapper},
     TypeDesc, TypeMemory,
 };
 use mapping::{Conversion, Mapping};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::{
+    alloc::Layout,
     collections::{HashMap, VecDeque},
     hash::Hash,
     ops::Deref,
     pin::Pin,
     ptr::NonNull,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Instant,
 };
 
 /// Implements a simple mark-sweep type garbage collector.
-#[derive(Debug)]
+///
+/// Memory can either be collected at once with [`MarkSweep::collect`], or incrementally with
+/// [`MarkSweep::collect_incremental`], which performs a bounded amount of work per call. Between
+/// incremental steps, the references of objects may change, so every modification of the
+/// references of an object must be followed by a call to [`GcRuntime::write_barrier`].
 pub struct MarkSweep<T, O>
 where
     T: TypeMemory + TypeTrace + Clone,
@@ -23,7 +36,13 @@ where
 {
     objects: RwLock<HashMap<GcPtr, Pin<Box<ObjectInfo<T>>>>>,
     observer: O,
+    allocator: Arc<dyn Allocator>,
     stats: RwLock<Stats>,
+    phase: Mutex<Phase>,
+    is_marking: AtomicBool,
+    next_id: AtomicU64,
+    pinned: AtomicUsize,
+    memory_limit: AtomicUsize,
 }
 
 impl<T, O> Default for MarkSweep<T, O>
@@ -32,11 +51,7 @@ where
     O: Observer<Event = Event> + Default,
 {
     fn default() -> Self {
-        MarkSweep {
-            objects: RwLock::new(HashMap::new()),
-            observer: O::default(),
-            stats: RwLock::new(Stats::default()),
-        }
+        MarkSweep::with_observer(O::default())
     }
 }
 
@@ -47,11 +62,83 @@ where
 {
     /// Creates a `MarkSweep` memory collector with the specified `Observer`.
     pub fn with_observer(observer: O) -> Self {
+        Self::with_allocator(observer, Arc::new(SystemAllocator))
+    }
+
+    /// Creates a `MarkSweep` memory collector with the specified `Observer` that allocates the
+    /// memory of objects through the specified `Allocator`.
+    pub fn with_allocator(observer: O, allocator: Arc<dyn Allocator>) -> Self {
         Self {
             objects: RwLock::new(HashMap::new()),
             observer,
+            allocator,
             stats: RwLock::new(Stats::default()),
+            phase: Mutex::new(Phase::Idle),
+            is_marking: AtomicBool::new(false),
+            next_id: AtomicU64::new(0),
+            pinned: AtomicUsize::new(0),
+            memory_limit: AtomicUsize::new(usize::MAX),
+        }
+    }
+
+    /// Allocates memory for an object with the specified `layout`, optionally zero-initialized.
+    fn alloc_memory(&self, layout: Layout, zeroed: bool) -> NonNull<u8> {
+        let ptr = unsafe {
+            if zeroed {
+                self.allocator.alloc_zeroed(layout)
+            } else {
+                self.allocator.alloc(layout)
+            }
+        };
+        NonNull::new(ptr).unwrap_or_else(|| std::alloc::handle_alloc_error(layout))
+    }
+
+    /// Allocates a new object of type `ty`.
+    fn alloc_obj(&self, ty: T) -> Pin<Box<ObjectInfo<T>>> {
+        let ptr = self.alloc_memory(ty.layout(), false).as_ptr();
+        Box::pin(ObjectInfo {
+            ptr,
+            ty,
+            roots: 0,
+            color: Color::White,
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+        })
+    }
+
+    /// Allocates a new object of type `ty` and registers it with the collector, without updating
+    /// the statistics.
+    fn insert_obj(&self, ty: T) -> GcPtr {
+        let mut object = self.alloc_obj(ty);
+
+        // We want to return a pointer to the `ObjectInfo`, to be used as handle.
+        let handle = (object.as_ref().deref() as *const _ as RawGcPtr).into();
+
+        {
+            let mut objects = self.objects.write();
+
+            // Objects that are allocated during a collection cycle are kept alive until the next
+            // cycle. During the mark phase, they still need to be traced, because references to
+            // other objects might be stored in them before the next incremental step.
+            let mut phase = self.phase.lock();
+            let color = match &mut *phase {
+                Phase::Idle => Color::White,
+                Phase::Mark { gray, .. } => {
+                    gray.push_back(handle);
+                    Color::Gray
+                }
+                Phase::Sweep { .. } => Color::Black,
+            };
+            unsafe { object.as_mut().get_unchecked_mut().color = color };
+
+            objects.insert(handle, object);
         }
+
+        handle
+    }
+
+    /// Deallocates the memory of an object with the specified `layout`.
+    fn dealloc_memory(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { self.allocator.dealloc(ptr, layout) }
     }
 
     /// Logs an allocation
@@ -59,6 +146,7 @@ where
         {
             let mut stats = self.stats.write();
             stats.allocated_memory += ty.layout().size();
+            stats.allocated_since_collection += ty.layout().size();
         }
 
         self.observer.event(Event::Allocation(handle));
@@ -68,16 +156,18 @@ where
     pub fn observer(&self) -> &O {
         &self.observer
     }
-}
 
-fn alloc_obj<T: Clone + TypeMemory + TypeTrace>(ty: T) -> Pin<Box<ObjectInfo<T>>> {
-    let ptr = unsafe { std::alloc::alloc(ty.layout()) };
-    Box::pin(ObjectInfo {
-        ptr,
-        ty,
-        roots: 0,
-        color: Color::White,
-    })
+    /// Returns the type of the object `handle` without acquiring a lock, e.g. from an `Observer`
+    /// that receives an `Event` while the collector is locked.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must refer to an object of a `MarkSweep` with the same type `T`, which is either
+    /// alive or being deallocated during an `Event::Deallocation`.
+    pub unsafe fn ptr_type_unchecked(handle: GcPtr) -> T {
+        let object_info: *const ObjectInfo<T> = handle.into();
+        (*object_info).ty.clone()
+    }
 }
 
 impl<T, O> GcRuntime<T> for MarkSweep<T, O>
@@ -86,16 +176,7 @@ where
     O: Observer<Event = Event>,
 {
     fn alloc(&self, ty: T) -> GcPtr {
-        let object = alloc_obj(ty.clone());
-
-        // We want to return a pointer to the `ObjectInfo`, to be used as handle.
-        let handle = (object.as_ref().deref() as *const _ as RawGcPtr).into();
-
-        {
-            let mut objects = self.objects.write();
-            objects.insert(handle, object);
-        }
-
+        let handle = self.insert_obj(ty.clone());
         self.log_alloc(handle, ty);
         handle
     }
@@ -111,12 +192,17 @@ where
     }
 
     fn root(&self, handle: GcPtr) {
-        let _ = self.objects.write();
+        let _objects = self.objects.write();
 
         // Convert the handle to our internal representation
         let object_info: *mut ObjectInfo<T> = handle.into();
 
         unsafe { (*object_info).roots += 1 };
+
+        // A root that is added during the mark phase must still be traced
+        if let Phase::Mark { gray, .. } = &mut *self.phase.lock() {
+            unsafe { shade(&mut *object_info, handle, gray) };
+        }
     }
 
     fn unroot(&self, handle: GcPtr) {
@@ -128,8 +214,73 @@ where
         unsafe { (*object_info).roots -= 1 };
     }
 
+    fn dealloc(&self, handle: GcPtr) {
+        let mut objects = self.objects.write();
+
+        // Rooted objects are still referenced
+        if objects.get(&handle).map_or(true, |obj| obj.roots > 0) {
+            return;
+        }
+
+        let obj = objects.remove(&handle).unwrap();
+        self.dealloc_memory(obj.ptr, obj.ty.layout());
+        self.observer.event(Event::Deallocation(handle));
+        {
+            let mut stats = self.stats.write();
+            stats.allocated_memory -= obj.ty.layout().size();
+        }
+    }
+
+    fn write_barrier(&self, handle: GcPtr) {
+        if !self.is_marking.load(Ordering::Acquire) {
+            return;
+        }
+
+        let _objects = self.objects.write();
+        if let Phase::Mark { gray, .. } = &mut *self.phase.lock() {
+            // Convert the handle to our internal representation
+            let object_info: *mut ObjectInfo<T> = handle.into();
+
+            // An object that was already visited has to be visited again, to trace the references
+            // that were stored in it.
+            unsafe {
+                if (*object_info).color == Color::Black {
+                    (*object_info).color = Color::Gray;
+                    gray.push_back(handle);
+                }
+            }
+        }
+    }
+
+    fn downgrade(&self, handle: GcPtr) -> WeakGcPtr {
+        let _objects = self.objects.read();
+
+        // Convert the handle to our internal representation
+        let object_info: *const ObjectInfo<T> = handle.into();
+
+        WeakGcPtr::new(handle, unsafe { (*object_info).id })
+    }
+
+    fn upgrade(&self, weak: WeakGcPtr) -> Option<GcPtr> {
+        let objects = self.objects.read();
+        let object_info = objects.get(&weak.handle())?;
+        if object_info.id != weak.id() {
+            return None;
+        }
+
+        // An unmarked object that has not been swept yet is no longer referenced
+        match &*self.phase.lock() {
+            Phase::Sweep { .. } if object_info.color == Color::White => None,
+            _ => Some(weak.handle()),
+        }
+    }
+
     fn stats(&self) -> Stats {
-        self.stats.read().clone()
+        let objects = self.objects.read();
+        Stats {
+            allocated_objects: objects.len(),
+            ..self.stats.read().clone()
+        }
     }
 }
 
@@ -140,68 +291,253 @@ where
 {
     /// Collects all memory that is no longer referenced by rooted objects. Returns `true` if memory
     /// was reclaimed, `false` otherwise.
+    ///
+    /// A collection cycle that is in progress is aborted, so all unreferenced memory is collected.
     pub fn collect(&self) -> bool {
-        self.observer.event(Event::Start);
+        let start = Instant::now();
+        let mut objects = self.objects.write();
+        let mut phase = self.phase.lock();
+        self.abort_cycle(&mut phase);
+        let reclaimed = self
+            .collect_step(&mut objects, &mut phase, usize::MAX)
+            .expect("an unbounded collection step must complete the cycle");
+        self.stats.write().last_pause = start.elapsed();
+        reclaimed
+    }
 
+    /// Performs a step of an incremental collection cycle, during which at most `budget` objects
+    /// are traced or swept. If no cycle is in progress, a new cycle is started. Returns `true` if
+    /// the cycle was completed, `false` otherwise.
+    ///
+    /// This bounds the duration of a collection, so it can be spread over multiple frames of a
+    /// real-time application. Starting a cycle requires a pass over all objects, which is cheap
+    /// compared to tracing them. Objects that are allocated during a cycle are only collected in
+    /// the next cycle.
+    pub fn collect_incremental(&self, budget: usize) -> bool {
+        let start = Instant::now();
         let mut objects = self.objects.write();
+        let mut phase = self.phase.lock();
+        let completed = self
+            .collect_step(&mut objects, &mut phase, budget.max(1))
+            .is_some();
+        self.stats.write().last_pause = start.elapsed();
+        completed
+    }
 
-        // Get all roots
-        let mut roots = objects
-            .iter()
-            .filter_map(|(_, obj)| {
-                if obj.roots > 0 {
-                    Some(obj.as_ref().get_ref() as *const _ as *mut ObjectInfo<T>)
-                } else {
-                    None
+    /// Performs at most `budget` units of work of the current collection cycle, starting a new
+    /// cycle if none is in progress. Returns whether memory was reclaimed, if the cycle was
+    /// completed.
+    fn collect_step(
+        &self,
+        objects: &mut HashMap<GcPtr, Pin<Box<ObjectInfo<T>>>>,
+        phase: &mut Phase,
+        budget: usize,
+    ) -> Option<bool> {
+        if let Phase::Idle = phase {
+            *phase = self.start_cycle(objects);
+        }
+
+        let mut work = 0;
+        if let Phase::Mark { gray, swept } = phase {
+            while work < budget {
+                let handle = match gray.pop_front() {
+                    Some(handle) => handle,
+                    None => break,
+                };
+                work += 1;
+
+                // The object might have been deallocated, or already been visited if it was
+                // deallocated and its handle was reused.
+                let next: *mut ObjectInfo<T> = match objects.get(&handle) {
+                    Some(object) if object.color == Color::Gray => handle.into(),
+                    _ => continue,
+                };
+
+                // Trace all other objects
+                for reference in unsafe { (*next).ty.trace(handle) } {
+                    let ref_ptr = objects
+                        .get_mut(&reference)
+                        .expect("found invalid reference");
+                    let ref_ptr =
+                        unsafe { ref_ptr.as_mut().get_unchecked_mut() } as *mut ObjectInfo<T>;
+                    unsafe { shade(&mut *ref_ptr, reference, gray) };
                 }
-            })
-            .collect::<VecDeque<_>>();
-
-        // Iterate over all roots
-        while let Some(next) = roots.pop_front() {
-            let handle = (next as *const _ as RawGcPtr).into();
-
-            // Trace all other objects
-            for reference in unsafe { (*next).ty.trace(handle) } {
-                let ref_ptr = objects
-                    .get_mut(&reference)
-                    .expect("found invalid reference");
-                if ref_ptr.color == Color::White {
-                    let ptr = ref_ptr.as_ref().get_ref() as *const _ as *mut ObjectInfo<T>;
-                    unsafe { (*ptr).color = Color::Gray };
-                    roots.push_back(ptr);
+
+                // This object has been traced
+                unsafe {
+                    (*next).color = Color::Black;
                 }
             }
 
-            // This object has been traced
-            unsafe {
-                (*next).color = Color::Black;
+            if !gray.is_empty() {
+                return None;
             }
+
+            self.is_marking.store(false, Ordering::Release);
+            *phase = Phase::Sweep {
+                pending: std::mem::take(swept),
+                reclaimed: false,
+            };
         }
 
-        // Sweep all non-reachable objects
-        let size_before = objects.len();
-        objects.retain(|h, obj| {
-            if obj.color == Color::Black {
-                unsafe {
-                    obj.as_mut().get_unchecked_mut().color = Color::White;
+        if let Phase::Sweep { pending, reclaimed } = phase {
+            // Sweep all non-reachable objects
+            while work < budget {
+                let handle = match pending.pop() {
+                    Some(handle) => handle,
+                    None => break,
+                };
+                work += 1;
+
+                if objects
+                    .get(&handle)
+                    .map_or(true, |obj| obj.color != Color::White)
+                {
+                    continue;
                 }
-                true
-            } else {
-                unsafe { std::alloc::dealloc(obj.ptr, obj.ty.layout()) };
-                self.observer.event(Event::Deallocation(*h));
+
+                let obj = objects.remove(&handle).unwrap();
+                self.dealloc_memory(obj.ptr, obj.ty.layout());
+                self.observer.event(Event::Deallocation(handle));
                 {
                     let mut stats = self.stats.write();
                     stats.allocated_memory -= obj.ty.layout().size();
                 }
-                false
+                *reclaimed = true;
+            }
+
+            if !pending.is_empty() {
+                return None;
             }
-        });
-        let size_after = objects.len();
 
+            let reclaimed = *reclaimed;
+            *phase = Phase::Idle;
+            self.stats.write().allocated_since_collection = 0;
+            self.observer.event(Event::End);
+            return Some(reclaimed);
+        }
+
+        unreachable!("a collection cycle is in progress")
+    }
+
+    /// Starts a new collection cycle, during which all objects that currently exist are either
+    /// traced or swept.
+    fn start_cycle(&self, objects: &mut HashMap<GcPtr, Pin<Box<ObjectInfo<T>>>>) -> Phase {
+        self.observer.event(Event::Start);
+
+        let mut gray = VecDeque::new();
+        let mut swept = Vec::with_capacity(objects.len());
+        for (handle, obj) in objects.iter_mut() {
+            let obj = unsafe { obj.as_mut().get_unchecked_mut() };
+            obj.color = Color::White;
+            if obj.roots > 0 {
+                shade(obj, *handle, &mut gray);
+            }
+            swept.push(*handle);
+        }
+
+        self.is_marking.store(true, Ordering::Release);
+        Phase::Mark { gray, swept }
+    }
+
+    /// Aborts the collection cycle that is in progress, if any.
+    fn abort_cycle(&self, phase: &mut Phase) {
+        if let Phase::Idle = phase {
+            return;
+        }
+
+        *phase = Phase::Idle;
+        self.is_marking.store(false, Ordering::Release);
         self.observer.event(Event::End);
+    }
 
-        size_before != size_after
+    /// Returns `true` if an incremental collection cycle is in progress.
+    pub fn is_collecting(&self) -> bool {
+        match *self.phase.lock() {
+            Phase::Idle => false,
+            _ => true,
+        }
+    }
+
+    /// Sets the maximum number of bytes that can be allocated for objects by `try_alloc`. If
+    /// `None`, the memory is unlimited.
+    pub fn set_memory_limit(&self, limit: Option<usize>) {
+        self.memory_limit
+            .store(limit.unwrap_or(usize::MAX), Ordering::Release);
+    }
+
+    /// Allocates an object of the given type, unless the memory limit would be exceeded, in which
+    /// case `None` is returned.
+    pub fn try_alloc(&self, ty: T) -> Option<GcPtr> {
+        let size = ty.layout().size();
+        {
+            // The memory is reserved while holding the lock that is used to check the limit, so
+            // concurrent allocations cannot exceed it together.
+            let mut stats = self.stats.write();
+            let limit = self.memory_limit.load(Ordering::Acquire);
+            if stats.allocated_memory.saturating_add(size) > limit {
+                return None;
+            }
+            stats.allocated_memory += size;
+            stats.allocated_since_collection += size;
+        }
+
+        let handle = self.insert_obj(ty);
+        self.observer.event(Event::Allocation(handle));
+        Some(handle)
+    }
+
+    /// Pins the specified `obj`, which roots it and guarantees that the address of its memory
+    /// remains stable until it is unpinned. An object can be pinned multiple times, but you must
+    /// make sure to call `unpin` an equal number of times.
+    ///
+    /// The collector never moves memory, except when it is mapped to new types, so memory must not
+    /// be mapped while objects are pinned.
+    pub fn pin(&self, obj: GcPtr) {
+        self.root(obj);
+        self.pinned.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Unpins the specified `obj`, which was pinned with `pin`.
+    pub fn unpin(&self, obj: GcPtr) {
+        self.pinned.fetch_sub(1, Ordering::AcqRel);
+        self.unroot(obj);
+    }
+
+    /// Returns the number of pins of objects that have not been released.
+    pub fn pinned_objects(&self) -> usize {
+        self.pinned.load(Ordering::Acquire)
+    }
+
+    /// Returns the number of allocated objects per type.
+    pub fn count_objects_per_type(&self) -> HashMap<T, usize>
+    where
+        T: Eq + Hash,
+    {
+        let mut counts = HashMap::new();
+        for obj in self.objects.read().values() {
+            *counts.entry(obj.ty.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Returns the handle, type and number of roots of every allocated object, e.g. to inspect the
+    /// heap.
+    pub fn objects(&self) -> Vec<(GcPtr, T, u32)> {
+        self.objects
+            .read()
+            .iter()
+            .map(|(handle, obj)| (*handle, obj.ty.clone(), obj.roots))
+            .collect()
+    }
+
+    /// Returns the number of allocated objects of which the type satisfies `predicate`.
+    pub fn count_objects<F: Fn(&T) -> bool>(&self, predicate: F) -> usize {
+        self.objects
+            .read()
+            .values()
+            .filter(|obj| predicate(&obj.ty))
+            .count()
     }
 }
 
@@ -211,8 +547,17 @@ where
     O: Observer<Event = Event>,
 {
     fn map_memory(&self, mapping: Mapping<T, T>) -> Vec<GcPtr> {
+        debug_assert_eq!(
+            self.pinned_objects(),
+            0,
+            "memory cannot be mapped while objects are pinned"
+        );
         let mut objects = self.objects.write();
 
+        // The objects and their references are replaced, so an incremental collection cycle has
+        // to start over.
+        self.abort_cycle(&mut self.phase.lock());
+
         // Determine which types are still allocated with deleted types
         let deleted = objects
             .iter()
@@ -234,6 +579,7 @@ where
                         roots: object_info.roots,
                         color: object_info.color,
                         ty: new_ty.clone(),
+                        id: object_info.id,
                     });
                 }
             }
@@ -245,26 +591,26 @@ where
             for object_info in objects.values_mut() {
                 if object_info.ty == *old_ty {
                     let src = unsafe { NonNull::new_unchecked(object_info.ptr) };
-                    let dest = unsafe {
-                        NonNull::new_unchecked(std::alloc::alloc_zeroed(conversion.new_ty.layout()))
-                    };
+                    let dest = self.alloc_memory(conversion.new_ty.layout(), true);
 
                     map_fields(
                         self,
                         &mut new_allocations,
                         &mapping.conversions,
-                        &conversion.field_mapping,
+                        old_ty,
+                        conversion,
                         src,
                         dest,
                     );
 
-                    unsafe { std::alloc::dealloc(src.as_ptr(), old_ty.layout()) };
+                    self.dealloc_memory(src.as_ptr(), old_ty.layout());
 
                     object_info.set(ObjectInfo {
                         ptr: dest.as_ptr(),
                         roots: object_info.roots,
                         color: object_info.color,
                         ty: conversion.new_ty.clone(),
+                        id: object_info.id,
                     });
                 }
             }
@@ -288,7 +634,8 @@ where
             gc: &MarkSweep<T, O>,
             new_allocations: &mut Vec<Pin<Box<ObjectInfo<T>>>>,
             conversions: &HashMap<T, Conversion<T>>,
-            mapping: &[FieldMapping<T>],
+            old_struct_ty: &T,
+            conversion: &Conversion<T>,
             src: NonNull<u8>,
             dest: NonNull<u8>,
         ) where
@@ -299,7 +646,7 @@ where
                 new_ty,
                 new_offset,
                 action,
-            } in mapping.iter()
+            } in conversion.field_mapping.iter()
             {
                 let field_dest = {
                     let mut dest = dest.as_ptr() as usize;
@@ -318,13 +665,14 @@ where
                         if old_ty.group().is_struct() {
                             debug_assert!(new_ty.group().is_struct());
 
-                            // When the name is the same, we are dealing with the same struct,
-                            // but different internals
-                            let is_same_struct = old_ty.name() == new_ty.name();
-
                             // If the same struct changed, there must also be a conversion
                             let conversion = conversions.get(old_ty);
 
+                            // When there is a conversion to the new type, we are dealing with the
+                            // same struct, but different internals
+                            let is_same_struct =
+                                conversion.map_or(false, |conversion| conversion.new_ty == *new_ty);
+
                             if old_ty.is_stack_allocated() {
                                 if new_ty.is_stack_allocated() {
                                     // struct(value) -> struct(value)
@@ -334,7 +682,8 @@ where
                                             gc,
                                             new_allocations,
                                             conversions,
-                                            &conversion.as_ref().unwrap().field_mapping,
+                                            old_ty,
+                                            conversion.unwrap(),
                                             unsafe { NonNull::new_unchecked(field_src) },
                                             unsafe { NonNull::new_unchecked(field_dest) },
                                         );
@@ -343,7 +692,7 @@ where
                                     }
                                 } else {
                                     // struct(value) -> struct(gc)
-                                    let object = alloc_obj(new_ty.clone());
+                                    let object = gc.alloc_obj(new_ty.clone());
 
                                     // We want to return a pointer to the `ObjectInfo`, to be used as handle.
                                     let handle =
@@ -355,7 +704,8 @@ where
                                             gc,
                                             new_allocations,
                                             conversions,
-                                            &conversion.as_ref().unwrap().field_mapping,
+                                            old_ty,
+                                            conversion.unwrap(),
                                             unsafe { NonNull::new_unchecked(field_src) },
                                             unsafe { NonNull::new_unchecked(object.ptr) },
                                         );
@@ -387,7 +737,7 @@ where
                                         *field_dest = *field_src;
                                     }
                                 } else {
-                                    let object = alloc_obj(new_ty.clone());
+                                    let object = gc.alloc_obj(new_ty.clone());
 
                                     // We want to return a pointer to the `ObjectInfo`, to
                                     // be used as handle.
@@ -424,7 +774,8 @@ where
                                             gc,
                                             new_allocations,
                                             conversions,
-                                            &conversion.as_ref().unwrap().field_mapping,
+                                            old_ty,
+                                            conversion.unwrap(),
                                             unsafe { NonNull::new_unchecked(obj.ptr) },
                                             unsafe { NonNull::new_unchecked(field_dest) },
                                         );
@@ -471,7 +822,7 @@ where
                     }
                     mapping::Action::Insert => {
                         if !new_ty.is_stack_allocated() {
-                            let object = alloc_obj(new_ty.clone());
+                            let object = gc.alloc_obj(new_ty.clone());
 
                             // We want to return a pointer to the `ObjectInfo`, to be used as
                             // handle.
@@ -493,10 +844,49 @@ where
                     }
                 }
             }
+
+            // Migrate the data that could not be mapped automatically
+            if let Some(migration) = &conversion.migration {
+                migration(old_struct_ty, src, &conversion.new_ty, dest);
+            }
         }
     }
 }
 
+/// The phase of a collection cycle.
+#[derive(Debug)]
+enum Phase {
+    /// No collection cycle is in progress
+    Idle,
+
+    /// The objects that are reachable from roots are traced. `gray` contains the objects that have
+    /// been seen but not yet visited, and `swept` contains the objects that existed at the start
+    /// of the cycle and have to be swept.
+    Mark {
+        gray: VecDeque<GcPtr>,
+        swept: Vec<GcPtr>,
+    },
+
+    /// The objects that were not reached during the mark phase are deallocated. `pending`
+    /// contains the objects that still have to be swept.
+    Sweep {
+        pending: Vec<GcPtr>,
+        reclaimed: bool,
+    },
+}
+
+/// Marks `object` gray if it has not been seen yet by the mark phase, so it is visited later.
+fn shade<T: TypeMemory + TypeTrace + Clone>(
+    object: &mut ObjectInfo<T>,
+    handle: GcPtr,
+    gray: &mut VecDeque<GcPtr>,
+) {
+    if object.color == Color::White {
+        object.color = Color::Gray;
+        gray.push_back(handle);
+    }
+}
+
 /// Coloring used in the Mark Sweep phase.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum Color {
@@ -519,6 +909,8 @@ struct ObjectInfo<T: TypeMemory + TypeTrace + Clone> {
     pub roots: u32,
     pub color: Color,
     pub ty: T,
+    /// Uniquely identifies the allocation, as handles are reused after deallocation
+    pub id: u64,
 }
 
 /// An `ObjectInfo` is thread-safe.
@@ -57,3 +57,33 @@ impl GcPtr {
         self.0
     }
 }
+
+/// A `WeakGcPtr` refers to an object without keeping it alive. It can be upgraded to a `GcPtr`
+/// through [`GcRuntime::upgrade`] as long as the object has not been collected.
+///
+/// A `GcPtr` can be reused for a new object after the previous object is collected, so a
+/// `WeakGcPtr` additionally identifies the allocation it was created for.
+///
+/// [`GcRuntime::upgrade`]: trait.GcRuntime.html#tymethod.upgrade
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct WeakGcPtr {
+    handle: GcPtr,
+    id: u64,
+}
+
+impl WeakGcPtr {
+    /// Constructs a `WeakGcPtr` for the allocation with the specified `id`.
+    pub fn new(handle: GcPtr, id: u64) -> Self {
+        Self { handle, id }
+    }
+
+    /// Returns the handle of the object, which is only valid if the object is still alive.
+    pub fn handle(&self) -> GcPtr {
+        self.handle
+    }
+
+    /// Returns the identifier of the allocation.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
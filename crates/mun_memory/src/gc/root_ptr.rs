@@ -1,5 +1,5 @@
 use crate::{
-    gc::{GcPtr, GcRuntime, HasIndirectionPtr, TypeTrace},
+    gc::{GcPtr, GcRuntime, HasIndirectionPtr, TypeTrace, WeakGcPtr},
     TypeMemory,
 };
 use std::marker::PhantomData;
@@ -41,6 +41,20 @@ impl<T: TypeMemory + TypeTrace, G: GcRuntime<T>> GcRootPtr<T, G> {
         self.handle
     }
 
+    /// Roots the object that `weak` refers to, if it has not been collected yet.
+    pub fn upgrade(runtime: &Arc<G>, weak: WeakGcPtr) -> Option<Self> {
+        runtime
+            .upgrade(weak)
+            .map(|handle| Self::new(runtime, handle))
+    }
+
+    /// Returns a weak reference to the object, which does not keep it alive.
+    pub fn downgrade(&self) -> Option<WeakGcPtr> {
+        self.runtime
+            .upgrade()
+            .map(|runtime| runtime.downgrade(self.handle))
+    }
+
     /// Unroots the handle consuming self and returning the unrooted handle
     pub fn unroot(self) -> GcPtr {
         self.handle
@@ -34,4 +34,9 @@ pub trait TypeFields<T>: Send + Sync {
     fn fields(&self) -> Vec<(&str, T)>;
     /// Returns the type's fields' offsets.
     fn offsets(&self) -> &[u16];
+    /// Returns the previous names of the type's fields, or `None` for fields that were not
+    /// renamed.
+    fn fields_renamed_from(&self) -> Vec<Option<&str>> {
+        self.fields().iter().map(|_| None).collect()
+    }
 }
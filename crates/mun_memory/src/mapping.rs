@@ -6,8 +6,18 @@ use crate::{
 use std::{
     collections::{HashMap, HashSet},
     hash::Hash,
+    ptr::NonNull,
+    sync::Arc,
 };
 
+/// A user-defined function that migrates an object from the old to the new layout of its type. It
+/// receives the old type and memory, and the new type and memory of the object, and is invoked
+/// after the fields that could be mapped automatically have been mapped.
+pub type MigrationFn<T> = Arc<dyn Fn(&T, NonNull<u8>, &T, NonNull<u8>) + Send + Sync>;
+
+/// The migration functions, keyed by the `Guid`s of the old and the new type.
+pub type Migrations<T> = HashMap<(abi::Guid, abi::Guid), MigrationFn<T>>;
+
 pub struct Mapping<T: Eq + Hash, U: TypeDesc + TypeMemory> {
     pub deletions: HashSet<T>,
     pub conversions: HashMap<T, Conversion<U>>,
@@ -17,6 +27,7 @@ pub struct Mapping<T: Eq + Hash, U: TypeDesc + TypeMemory> {
 pub struct Conversion<T: TypeDesc + TypeMemory> {
     pub field_mapping: Vec<FieldMapping<T>>,
     pub new_ty: T,
+    pub migration: Option<MigrationFn<T>>,
 }
 
 /// Description of the mapping of a single field. When stored together with the new index, this
@@ -41,6 +52,14 @@ where
 {
     ///
     pub fn new(old: &[T], new: &[T]) -> Self {
+        Self::with_migrations(old, new, &Migrations::new())
+    }
+
+    /// Calculates the mapping from the `old` to the `new` types, using the `migrations` for the
+    /// pairs of types with the same `Guid`s. A migration also applies to an old type that would
+    /// otherwise be deleted, in which case all fields of the new type are zero-initialized before
+    /// the migration is invoked.
+    pub fn with_migrations(old: &[T], new: &[T], migrations: &Migrations<T>) -> Self {
         let diff = diff(old, new);
 
         let mut conversions = HashMap::new();
@@ -123,6 +142,35 @@ where
         // We should have matched all remaining candidates
         debug_assert!(new_candidates.is_empty());
 
+        for (old_ty, conversion) in conversions.iter_mut() {
+            conversion.migration = migrations
+                .get(&(*old_ty.guid(), *conversion.new_ty.guid()))
+                .cloned();
+        }
+
+        // Migrate deleted types to inserted types
+        for ((old_guid, new_guid), migration) in migrations.iter() {
+            let old_ty = deletions
+                .iter()
+                .find(|ty| ty.group().is_struct() && ty.guid() == old_guid)
+                .cloned();
+            let new_ty = insertions
+                .iter()
+                .find(|ty| ty.group().is_struct() && ty.guid() == new_guid)
+                .cloned();
+            if let (Some(old_ty), Some(new_ty)) = (old_ty, new_ty) {
+                let diff: Vec<FieldDiff> = (0..old_ty.fields().len())
+                    .map(|index| FieldDiff::Delete { index })
+                    .chain((0..new_ty.fields().len()).map(|index| FieldDiff::Insert { index }))
+                    .collect();
+                let mut conversion = unsafe { field_mapping(old_ty, new_ty, &diff) };
+                conversion.migration = Some(migration.clone());
+
+                deletions.remove(&old_ty);
+                conversions.insert(old_ty, conversion);
+            }
+        }
+
         Self {
             deletions,
             conversions,
@@ -261,6 +309,7 @@ pub unsafe fn field_mapping<T: Clone + TypeDesc + TypeFields<T> + TypeMemory>(
             })
             .collect(),
         new_ty,
+        migration: None,
     }
 }
 
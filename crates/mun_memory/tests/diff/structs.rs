@@ -509,3 +509,78 @@ fn rename_field2() {
     );
     assert_eq_struct(&apply_diff(old, new, diff), &vec![struct2.clone()]);
 }
+
+#[test]
+fn rename_field_with_attribute() {
+    let int = TypeInfo::new_fundamental::<i64>();
+
+    let struct1 = TypeInfo::new_struct(
+        STRUCT1_NAME,
+        STRUCT1_GUID,
+        StructInfo::new(&[("a", &int), ("b", &int)]),
+    );
+    let struct2 = TypeInfo::new_struct(
+        STRUCT1_NAME,
+        STRUCT2_GUID,
+        StructInfo::new(&[("c", &int), ("d", &int)]).with_renamed_from("c", "b"),
+    );
+
+    let old = &[&struct1];
+    let new = &[&struct2];
+
+    let diff = diff(old, new);
+    assert_eq!(
+        diff,
+        vec![Diff::Edit {
+            diff: vec![
+                FieldDiff::Move {
+                    old_index: 0,
+                    new_index: 1,
+                    edit: Some(FieldEditKind::Rename),
+                },
+                FieldDiff::Move {
+                    old_index: 1,
+                    new_index: 0,
+                    edit: Some(FieldEditKind::Rename),
+                }
+            ],
+            old_index: 0,
+            new_index: 0,
+        }]
+    );
+    assert_eq_struct(&apply_diff(old, new, diff), &vec![struct2.clone()]);
+}
+
+#[test]
+fn rename_and_cast_field_with_attribute() {
+    let int = TypeInfo::new_fundamental::<i64>();
+    let float = TypeInfo::new_fundamental::<f64>();
+
+    let struct1 = TypeInfo::new_struct(
+        STRUCT1_NAME,
+        STRUCT1_GUID,
+        StructInfo::new(&[("a", &int), ("b", &float)]),
+    );
+    let struct2 = TypeInfo::new_struct(
+        STRUCT1_NAME,
+        STRUCT2_GUID,
+        StructInfo::new(&[("c", &float), ("b", &float)]).with_renamed_from("c", "a"),
+    );
+
+    let old = &[&struct1];
+    let new = &[&struct2];
+
+    let diff = diff(old, new);
+    assert_eq!(
+        diff,
+        vec![Diff::Edit {
+            diff: vec![FieldDiff::Edit {
+                index: 0,
+                kind: FieldEditKind::ConvertType,
+            }],
+            old_index: 0,
+            new_index: 0,
+        }]
+    );
+    assert_eq_struct(&apply_diff(old, new, diff), &vec![struct2.clone()]);
+}
@@ -21,6 +21,7 @@ pub const STRUCT2_GUID: abi::Guid = abi::Guid {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct StructInfo {
     fields: Vec<(String, TypeInfo)>,
+    renamed_from: Vec<Option<String>>,
 }
 
 impl StructInfo {
@@ -30,9 +31,21 @@ impl StructInfo {
                 .iter()
                 .map(|(name, ty)| (name.to_string(), (*ty).clone()))
                 .collect(),
+            renamed_from: vec![None; fields.len()],
         }
     }
 
+    /// Marks the field with the specified `name` as having been renamed from `old_name`.
+    pub fn with_renamed_from(mut self, name: &str, old_name: &str) -> Self {
+        let idx = self
+            .fields
+            .iter()
+            .position(|(field_name, _)| field_name == name)
+            .expect("unknown field");
+        self.renamed_from[idx] = Some(old_name.to_string());
+        self
+    }
+
     pub fn layout(&self) -> Layout {
         // NOTE: This implementation is naive, but it is merely a test
         let size = self.fields.iter().map(|ty| ty.1.layout.size()).sum();
@@ -133,6 +146,13 @@ impl<'t> TypeFields<&'t TypeInfo> for &'t TypeInfo {
         // This is a stub, as we don't do any actual memory mapping
         &[]
     }
+
+    fn fields_renamed_from(&self) -> Vec<Option<&str>> {
+        match &self.tail {
+            TypeInfoTail::Empty => Vec::new(),
+            TypeInfoTail::Struct(s) => s.renamed_from.iter().map(|n| n.as_deref()).collect(),
+        }
+    }
 }
 
 pub fn apply_myers_diff<'t, T: Copy + Eq>(old: &[T], new: &[T], diff: Vec<myers::Diff>) -> Vec<T> {
@@ -252,7 +272,7 @@ fn apply_mapping<'t>(old: &mut TypeInfo, new: &TypeInfo, mapping: &[FieldDiff])
                 new_field: &(String, TypeInfo),
             ) {
                 match *kind {
-                    FieldEditKind::ConvertType => old_field.1 = new_field.1.clone(),
+                    FieldEditKind::ConvertType => *old_field = new_field.clone(),
                     FieldEditKind::Rename => old_field.0 = new_field.0.clone(),
                 }
             }
@@ -266,12 +286,12 @@ fn apply_mapping<'t>(old: &mut TypeInfo, new: &TypeInfo, mapping: &[FieldDiff])
                         unsafe { new_struct.fields.get_unchecked(*index) },
                     ),
                     FieldDiff::Move {
-                        old_index,
                         new_index,
                         edit: Some(kind),
+                        ..
                     } => edit_field(
                         kind,
-                        unsafe { combined.get_unchecked_mut(*old_index) },
+                        unsafe { combined.get_unchecked_mut(*new_index) },
                         unsafe { new_struct.fields.get_unchecked(*new_index) },
                     ),
                     _ => (),
@@ -279,6 +299,7 @@ fn apply_mapping<'t>(old: &mut TypeInfo, new: &TypeInfo, mapping: &[FieldDiff])
             }
 
             old_struct.fields = combined;
+            old_struct.renamed_from = new_struct.renamed_from.clone();
             old.layout = old_struct.layout();
         } else {
             unreachable!()
@@ -1,6 +1,14 @@
 use super::util::{EventAggregator, HasTypeInfo, TypeInfo};
-use mun_memory::gc::{Event, GcRootPtr, GcRuntime, MarkSweep};
-use std::sync::Arc;
+use mun_memory::gc::{
+    Allocator, Event, GcRootPtr, GcRuntime, HasIndirectionPtr, MarkSweep, Observer, SystemAllocator,
+};
+use std::{
+    alloc::Layout,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
 
 #[test]
 fn alloc() {
@@ -14,6 +22,39 @@ fn alloc() {
     assert_eq!(events.next(), None);
 }
 
+/// Records the types of the objects that are deallocated.
+#[derive(Default)]
+struct DeallocatedTypes(Mutex<Vec<&'static TypeInfo>>);
+
+impl Observer for DeallocatedTypes {
+    type Event = Event;
+
+    fn event(&self, event: Event) {
+        if let Event::Deallocation(handle) = event {
+            let ty = unsafe {
+                MarkSweep::<&'static TypeInfo, DeallocatedTypes>::ptr_type_unchecked(handle)
+            };
+            self.0.lock().unwrap().push(ty);
+        }
+    }
+}
+
+#[test]
+fn ptr_type_unchecked() {
+    let runtime = MarkSweep::<&'static TypeInfo, DeallocatedTypes>::default();
+    let handle = runtime.alloc(i64::type_info());
+
+    let ty =
+        unsafe { MarkSweep::<&'static TypeInfo, DeallocatedTypes>::ptr_type_unchecked(handle) };
+    assert!(std::ptr::eq(ty, i64::type_info()));
+
+    runtime.collect();
+
+    let types = runtime.observer().0.lock().unwrap();
+    assert_eq!(types.len(), 1);
+    assert!(std::ptr::eq(types[0], i64::type_info()));
+}
+
 #[test]
 fn collect_simple() {
     let runtime = MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default();
@@ -63,3 +104,250 @@ fn collect_rooted() {
     assert_eq!(events.next(), Some(Event::End));
     assert_eq!(events.next(), None);
 }
+
+#[test]
+fn dealloc() {
+    let runtime = Arc::new(MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default());
+    let handle = runtime.alloc(i64::type_info());
+    let rooted = GcRootPtr::new(&runtime, runtime.alloc(i64::type_info()));
+
+    // Only the object that is not rooted is deallocated
+    runtime.dealloc(handle);
+    runtime.dealloc(rooted.handle());
+    assert_eq!(runtime.stats().allocated_memory, std::mem::size_of::<i64>());
+
+    // A collection cycle should not deallocate the object again
+    runtime.collect();
+
+    let rooted_handle = rooted.unroot();
+    let mut events = runtime.observer().take_all().into_iter();
+    assert_eq!(events.next(), Some(Event::Allocation(handle)));
+    assert_eq!(events.next(), Some(Event::Allocation(rooted_handle)));
+    assert_eq!(events.next(), Some(Event::Deallocation(handle)));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), None);
+}
+
+#[test]
+fn count_objects() {
+    let runtime = Arc::new(MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default());
+
+    let _handle = runtime.alloc(i64::type_info());
+    let _rooted = GcRootPtr::new(&runtime, runtime.alloc(i64::type_info()));
+    let is_i64 = |ty: &&'static TypeInfo| std::ptr::eq(*ty, i64::type_info());
+    assert_eq!(runtime.count_objects(is_i64), 2);
+
+    // Only the rooted object survives a collection
+    runtime.collect();
+    assert_eq!(runtime.count_objects(is_i64), 1);
+    assert_eq!(runtime.count_objects(|_| false), 0);
+}
+
+#[test]
+fn objects() {
+    let runtime = Arc::new(MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default());
+
+    let handle = runtime.alloc(i64::type_info());
+    let rooted = GcRootPtr::new(&runtime, runtime.alloc(i64::type_info()));
+
+    let mut objects = runtime.objects();
+    objects.sort_by_key(|(_, _, roots)| *roots);
+    assert_eq!(objects.len(), 2);
+    assert_eq!(objects[0].0, handle);
+    assert_eq!(objects[0].2, 0);
+    assert_eq!(objects[1].0, rooted.handle());
+    assert_eq!(objects[1].2, 1);
+    assert!(objects
+        .iter()
+        .all(|(_, ty, _)| std::ptr::eq(*ty, i64::type_info())));
+}
+
+#[derive(Default)]
+struct CountingAllocator {
+    allocated: AtomicUsize,
+}
+
+impl Allocator for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.allocated.fetch_add(layout.size(), Ordering::SeqCst);
+        SystemAllocator.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.allocated.fetch_sub(layout.size(), Ordering::SeqCst);
+        SystemAllocator.dealloc(ptr, layout)
+    }
+}
+
+#[test]
+fn custom_allocator() {
+    let allocator = Arc::new(CountingAllocator::default());
+    let runtime = MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::with_allocator(
+        EventAggregator::default(),
+        allocator.clone(),
+    );
+
+    let handle = runtime.alloc(i64::type_info());
+    assert_eq!(
+        allocator.allocated.load(Ordering::SeqCst),
+        std::mem::size_of::<i64>()
+    );
+
+    runtime.collect();
+    assert_eq!(allocator.allocated.load(Ordering::SeqCst), 0);
+
+    let mut events = runtime.observer().take_all().into_iter();
+    assert_eq!(events.next(), Some(Event::Allocation(handle)));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_eq!(events.next(), Some(Event::Deallocation(handle)));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), None);
+}
+
+#[test]
+fn collect_incremental() {
+    let runtime = Arc::new(MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default());
+    let rooted = GcRootPtr::new(&runtime, runtime.alloc(i64::type_info()));
+    let handle = runtime.alloc(i64::type_info());
+
+    // Every step traces or sweeps a single object
+    let mut steps = 1;
+    while !runtime.collect_incremental(1) {
+        steps += 1;
+    }
+    assert_eq!(steps, 3);
+
+    // Objects that are allocated during a cycle are not collected in that cycle
+    assert!(!runtime.collect_incremental(1));
+    let allocated = runtime.alloc(i64::type_info());
+    while !runtime.collect_incremental(1) {}
+
+    let mut events = runtime.observer().take_all().into_iter();
+    assert_eq!(events.next(), Some(Event::Allocation(rooted.handle())));
+    assert_eq!(events.next(), Some(Event::Allocation(handle)));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_eq!(events.next(), Some(Event::Deallocation(handle)));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_eq!(events.next(), Some(Event::Allocation(allocated)));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), None);
+}
+
+#[test]
+fn stats() {
+    let runtime = Arc::new(MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default());
+    let _rooted = GcRootPtr::new(&runtime, runtime.alloc(i64::type_info()));
+    runtime.alloc(i32::type_info());
+    runtime.alloc(i32::type_info());
+
+    let stats = runtime.stats();
+    assert_eq!(stats.allocated_objects, 3);
+    assert_eq!(stats.allocated_memory, 16);
+    assert_eq!(stats.allocated_since_collection, 16);
+
+    runtime.collect();
+
+    let stats = runtime.stats();
+    assert_eq!(stats.allocated_objects, 1);
+    assert_eq!(stats.allocated_memory, 8);
+    assert_eq!(stats.allocated_since_collection, 0);
+}
+
+#[test]
+fn weak_ptr() {
+    let runtime = Arc::new(MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default());
+    let rooted = GcRootPtr::new(&runtime, runtime.alloc(i64::type_info()));
+    let weak = rooted.downgrade().unwrap();
+
+    // A weak reference does not keep the object alive
+    runtime.collect();
+    let upgraded = GcRootPtr::upgrade(&runtime, weak).expect("object should still be alive");
+    assert_eq!(upgraded.handle(), rooted.handle());
+
+    drop(rooted);
+    drop(upgraded);
+    runtime.collect();
+    assert!(runtime.upgrade(weak).is_none());
+
+    // A new object that reuses the handle, is not referred to by the weak reference
+    let _rooted = GcRootPtr::new(&runtime, runtime.alloc(i64::type_info()));
+    assert!(runtime.upgrade(weak).is_none());
+}
+
+#[test]
+fn pin() {
+    let runtime = MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default();
+    let handle = runtime.alloc(i64::type_info());
+    let ptr = unsafe { handle.deref::<u8>() };
+
+    // A pinned object is kept alive at the same address
+    runtime.pin(handle);
+    assert_eq!(runtime.pinned_objects(), 1);
+    runtime.collect();
+    assert_eq!(unsafe { handle.deref::<u8>() }, ptr);
+
+    // An unpinned object can be collected again
+    runtime.unpin(handle);
+    assert_eq!(runtime.pinned_objects(), 0);
+    runtime.collect();
+
+    let mut events = runtime.observer().take_all().into_iter();
+    assert_eq!(events.next(), Some(Event::Allocation(handle)));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_eq!(events.next(), Some(Event::Deallocation(handle)));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), None);
+}
+
+#[test]
+fn memory_limit() {
+    let runtime = MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default();
+    runtime.set_memory_limit(Some(std::mem::size_of::<i64>()));
+
+    let handle = runtime
+        .try_alloc(i64::type_info())
+        .expect("object should fit in memory");
+    assert!(runtime.try_alloc(i64::type_info()).is_none());
+
+    // Deallocated memory can be reused
+    runtime.dealloc(handle);
+    assert!(runtime.try_alloc(i64::type_info()).is_some());
+
+    runtime.set_memory_limit(None);
+    assert!(runtime.try_alloc(i64::type_info()).is_some());
+}
+
+#[test]
+fn memory_limit_concurrent() {
+    const LIMIT: usize = 16 * std::mem::size_of::<i64>();
+    let runtime = Arc::new(MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default());
+    runtime.set_memory_limit(Some(LIMIT));
+
+    // Threads that race to allocate must never exceed the limit together
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            let runtime = runtime.clone();
+            std::thread::spawn(move || {
+                let mut allocated = 0;
+                for _ in 0..100 {
+                    if runtime.try_alloc(i64::type_info()).is_some() {
+                        allocated += 1;
+                    }
+                    assert!(runtime.stats().allocated_memory <= LIMIT);
+                }
+                allocated
+            })
+        })
+        .collect();
+    let allocated: usize = threads
+        .into_iter()
+        .map(|thread| thread.join().unwrap())
+        .sum();
+
+    assert_eq!(allocated, LIMIT / std::mem::size_of::<i64>());
+    assert_eq!(runtime.stats().allocated_memory, LIMIT);
+}
@@ -93,3 +93,44 @@ fn trace_cycle() {
     assert_eq!(events.next(), Some(Event::End));
     assert_eq!(events.next(), None);
 }
+
+#[test]
+fn incremental_write_barrier() {
+    let runtime = Arc::new(MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default());
+    let mut foo = GcRootPtr::new(&runtime, runtime.alloc(Foo::type_info()));
+    let bar = runtime.alloc(i64::type_info());
+    let baz = runtime.alloc(i64::type_info());
+
+    // Assign bar to foo.bar
+    unsafe {
+        (*foo.deref_mut::<Foo>()).bar = bar;
+    }
+
+    // Start a collection cycle and trace foo
+    assert!(!runtime.collect_incremental(1));
+    assert!(runtime.is_collecting());
+
+    // Assign baz to foo.bar, after foo was traced
+    unsafe {
+        (*foo.deref_mut::<Foo>()).bar = baz;
+    }
+    runtime.write_barrier(foo.handle());
+
+    // Finish the cycle, baz should not be collected
+    while !runtime.collect_incremental(1) {}
+    assert!(!runtime.is_collecting());
+
+    // Collect garbage, bar is no longer referenced
+    runtime.collect();
+
+    let mut events = runtime.observer().take_all().into_iter();
+    assert_eq!(events.next(), Some(Event::Allocation(foo.handle())));
+    assert_eq!(events.next(), Some(Event::Allocation(bar)));
+    assert_eq!(events.next(), Some(Event::Allocation(baz)));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_eq!(events.next(), Some(Event::Deallocation(bar)));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), None);
+}
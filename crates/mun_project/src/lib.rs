@@ -1,7 +1,11 @@
 mod manifest;
 mod package;
+mod workspace;
 
-pub use manifest::{Manifest, ManifestMetadata, PackageId};
+pub use manifest::{
+    BuildSettings, Dependency, DependencyKind, Manifest, ManifestMetadata, PackageId,
+};
 pub use package::Package;
+pub use workspace::Workspace;
 
 pub const MANIFEST_FILENAME: &str = "mun.toml";
@@ -1,14 +1,19 @@
 use std::fmt;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 mod toml;
 
+pub(crate) use self::toml::TomlManifest;
+
 /// Contains all information of a package. Usually this information is read from a mun.toml file.
 #[derive(PartialEq, Clone, Debug)]
 pub struct Manifest {
     package_id: PackageId,
     metadata: ManifestMetadata,
+    source_dir: PathBuf,
+    build: BuildSettings,
+    dependencies: Vec<Dependency>,
 }
 
 /// General metadata for a package.
@@ -17,6 +22,38 @@ pub struct ManifestMetadata {
     pub authors: Vec<String>,
 }
 
+/// The settings that are used to build a package, as specified in the `build` section of a
+/// mun.toml file. Settings that are specified on the command line take precedence.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct BuildSettings {
+    /// The target triple to compile the package for
+    pub target: Option<String>,
+    /// The optimization level, from 0 to 3
+    pub opt_level: Option<u8>,
+    /// The directory that outputs are written to, relative to the manifest. Defaults to `target`.
+    pub target_dir: Option<PathBuf>,
+}
+
+/// A package or assembly that another package depends on, as specified in the `dependencies`
+/// section of a mun.toml file.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Dependency {
+    name: String,
+    kind: DependencyKind,
+    path: PathBuf,
+}
+
+/// Specifies whether a dependency is built together with the package that depends on it, or was
+/// compiled before.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum DependencyKind {
+    /// A package that is built before the package that depends on it
+    Package,
+    /// A compiled assembly, of which the public items can be used by the package that depends on
+    /// it
+    Assembly,
+}
+
 /// Unique identifier of a package and version
 #[derive(PartialEq, Clone, Debug)]
 pub struct PackageId {
@@ -52,6 +89,40 @@ impl Manifest {
     pub fn metadata(&self) -> &ManifestMetadata {
         &self.metadata
     }
+
+    /// Returns the directory that contains the source files of the package, relative to the
+    /// manifest
+    pub fn source_dir(&self) -> &Path {
+        &self.source_dir
+    }
+
+    /// Returns the settings that are used to build the package
+    pub fn build_settings(&self) -> &BuildSettings {
+        &self.build
+    }
+
+    /// Returns the packages and assemblies that this package depends on
+    pub fn dependencies(&self) -> &[Dependency] {
+        &self.dependencies
+    }
+}
+
+impl Dependency {
+    /// Returns the name of the package or assembly that is depended on
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns whether a package or an assembly is depended on
+    pub fn kind(&self) -> DependencyKind {
+        self.kind
+    }
+
+    /// Returns the directory of the package, or the path of the assembly, that is depended on,
+    /// relative to the manifest of the dependent package
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
 }
 
 impl PackageId {
@@ -77,15 +148,14 @@ impl std::str::FromStr for Manifest {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // Parse the contents of the file to toml manifest
-        let manifest = ::toml::from_str::<toml::TomlManifest>(s)
-            .map_err(|e| anyhow::anyhow!("could not parse manifest: {}", e))?;
-        manifest.into_real_manifest()
+        TomlManifest::parse(s)?.into_real_manifest()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::Manifest;
+    use crate::{BuildSettings, DependencyKind, Manifest};
+    use std::path::{Path, PathBuf};
     use std::str::FromStr;
 
     #[test]
@@ -107,5 +177,103 @@ mod tests {
         );
         assert_eq!(manifest.metadata().authors, vec!["Mun Team"]);
         assert_eq!(format!("{}", manifest.package_id()), "test v0.2.0");
+        assert_eq!(manifest.source_dir(), Path::new("src"));
+        assert_eq!(manifest.build_settings(), &BuildSettings::default());
+        assert!(manifest.dependencies().is_empty());
+    }
+
+    #[test]
+    fn parse_settings_and_dependencies() {
+        let manifest = Manifest::from_str(
+            r#"
+        [package]
+        name="game"
+        version="0.1.0"
+        source-dir="scripts"
+
+        [build]
+        target="x86_64-unknown-linux-gnu"
+        opt-level=3
+        target-dir="../assets/scripts"
+
+        [dependencies]
+        physics = { path = "../physics" }
+        audio = { assembly = "../libs/audio.munlib" }
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.source_dir(), Path::new("scripts"));
+        assert_eq!(
+            manifest.build_settings(),
+            &BuildSettings {
+                target: Some("x86_64-unknown-linux-gnu".to_owned()),
+                opt_level: Some(3),
+                target_dir: Some(PathBuf::from("../assets/scripts")),
+            }
+        );
+        assert_eq!(manifest.dependencies().len(), 2);
+        assert_eq!(manifest.dependencies()[0].name(), "audio");
+        assert_eq!(manifest.dependencies()[0].kind(), DependencyKind::Assembly);
+        assert_eq!(
+            manifest.dependencies()[0].path(),
+            Path::new("../libs/audio.munlib")
+        );
+        assert_eq!(manifest.dependencies()[1].name(), "physics");
+        assert_eq!(manifest.dependencies()[1].kind(), DependencyKind::Package);
+        assert_eq!(manifest.dependencies()[1].path(), Path::new("../physics"));
+    }
+
+    #[test]
+    fn parse_ambiguous_dependency() {
+        let err = Manifest::from_str(
+            r#"
+        [package]
+        name="test"
+        version="0.1.0"
+
+        [dependencies]
+        physics = { path = "../physics", assembly = "../physics.munlib" }
+        "#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "dependency `physics` must specify either a `path` or an `assembly`"
+        );
+    }
+
+    #[test]
+    fn parse_invalid_opt_level() {
+        let err = Manifest::from_str(
+            r#"
+        [package]
+        name="test"
+        version="0.1.0"
+
+        [build]
+        opt-level=4
+        "#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid opt-level `4`, only levels 0-3 are supported"
+        );
+    }
+
+    #[test]
+    fn parse_workspace_without_package() {
+        let err = Manifest::from_str(
+            r#"
+        [workspace]
+        members=["game"]
+        "#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "manifest does not contain a `package` section"
+        );
     }
 }
@@ -1,37 +1,135 @@
-use super::{Manifest, ManifestMetadata, PackageId};
+use super::{BuildSettings, Dependency, DependencyKind, Manifest, ManifestMetadata, PackageId};
 use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 
 /// A manifest as specified in a mun.toml file.
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct TomlManifest {
-    package: TomlProject,
+    package: Option<TomlProject>,
+    workspace: Option<TomlWorkspace>,
+    build: Option<TomlBuild>,
+    dependencies: Option<BTreeMap<String, TomlDependency>>,
 }
 
 /// Represents the `package` section of a mun.toml file.
 #[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "kebab-case")]
 pub struct TomlProject {
     name: String,
     version: semver::Version,
     authors: Option<Vec<String>>,
+    source_dir: Option<PathBuf>,
+}
+
+/// Represents the `workspace` section of a mun.toml file.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub struct TomlWorkspace {
+    members: Vec<PathBuf>,
+}
+
+/// Represents the `build` section of a mun.toml file.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub struct TomlBuild {
+    target: Option<String>,
+    opt_level: Option<u8>,
+    target_dir: Option<PathBuf>,
+}
+
+/// Represents an entry of the `dependencies` section of a mun.toml file, which specifies either the
+/// directory of a package or the path of a compiled assembly.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub struct TomlDependency {
+    path: Option<PathBuf>,
+    assembly: Option<PathBuf>,
 }
 
 impl TomlManifest {
+    /// Parses the contents of a mun.toml file.
+    pub fn parse(s: &str) -> Result<Self, anyhow::Error> {
+        ::toml::from_str::<TomlManifest>(s)
+            .map_err(|e| anyhow::anyhow!("could not parse manifest: {}", e))
+    }
+
+    /// Returns the paths of the members of the workspace, relative to the manifest, or `None` if
+    /// the manifest does not define a workspace.
+    pub fn workspace_members(&self) -> Option<&[PathBuf]> {
+        self.workspace
+            .as_ref()
+            .map(|workspace| workspace.members.as_slice())
+    }
+
+    /// Returns `true` if the manifest defines a package.
+    pub fn has_package(&self) -> bool {
+        self.package.is_some()
+    }
+
+    /// Convert the `build` section of this toml manifest into build settings.
+    pub fn build_settings(&self) -> Result<BuildSettings, anyhow::Error> {
+        let build = match &self.build {
+            Some(build) => build,
+            None => return Ok(BuildSettings::default()),
+        };
+        if let Some(opt_level) = build.opt_level {
+            if opt_level > 3 {
+                anyhow::bail!(
+                    "invalid opt-level `{}`, only levels 0-3 are supported",
+                    opt_level
+                );
+            }
+        }
+        Ok(BuildSettings {
+            target: build.target.clone(),
+            opt_level: build.opt_level,
+            target_dir: build.target_dir.clone(),
+        })
+    }
+
     /// Convert this toml manifest into a "real" manifest.
     pub fn into_real_manifest(self) -> Result<Manifest, anyhow::Error> {
-        let name = self.package.name.trim();
+        let build = self.build_settings()?;
+        let package = match self.package {
+            Some(package) => package,
+            None => anyhow::bail!("manifest does not contain a `package` section"),
+        };
+
+        let name = package.name.trim();
         if name.is_empty() {
             anyhow::bail!("package name cannot be an empty string");
         }
 
+        let dependencies = self
+            .dependencies
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, dependency)| {
+                let (kind, path) = match (dependency.path, dependency.assembly) {
+                    (Some(path), None) => (DependencyKind::Package, path),
+                    (None, Some(path)) => (DependencyKind::Assembly, path),
+                    _ => anyhow::bail!(
+                        "dependency `{}` must specify either a `path` or an `assembly`",
+                        name
+                    ),
+                };
+                Ok(Dependency { name, kind, path })
+            })
+            .collect::<Result<_, anyhow::Error>>()?;
+
         Ok(Manifest {
             package_id: PackageId {
                 name: name.to_owned(),
-                version: self.package.version,
+                version: package.version,
             },
             metadata: ManifestMetadata {
-                authors: self.package.authors.unwrap_or_default(),
+                authors: package.authors.unwrap_or_default(),
             },
+            source_dir: package.source_dir.unwrap_or_else(|| PathBuf::from("src")),
+            build,
+            dependencies,
         })
     }
 }
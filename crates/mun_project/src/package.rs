@@ -59,7 +59,7 @@ impl Package {
 
     /// Returns the source directory of the package, or None if no such directory exists.
     pub fn source_directory(&self) -> Option<PathBuf> {
-        let source_dir = self.root().join("src");
+        let source_dir = self.root().join(self.manifest().source_dir());
         if source_dir.is_dir() {
             Some(source_dir)
         } else {
@@ -0,0 +1,155 @@
+use crate::{manifest::TomlManifest, BuildSettings, DependencyKind, Package, MANIFEST_FILENAME};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A set of packages that are built together. A workspace is defined by a mun.toml file that
+/// contains a `workspace` section, a `package` section, or both. Its packages are the members of
+/// the workspace, the package of the manifest, and all packages that they depend on. Compiled
+/// assemblies that packages depend on are not part of the workspace.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Workspace {
+    // The location of the manifest which marks the root of the workspace
+    manifest_path: PathBuf,
+    // The settings of the root manifest, which apply to all packages
+    build: BuildSettings,
+    // The packages of the workspace, every package after the packages that it depends on
+    packages: Vec<Package>,
+}
+
+impl Workspace {
+    /// Creates a workspace by loading the manifest at `path` and the manifests of all its members
+    /// and their dependencies.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let manifest_path = canonicalize_manifest_path(path.as_ref())?;
+        let file_contents = std::fs::read_to_string(&manifest_path)
+            .map_err(|e| anyhow::anyhow!("could not read manifest file: {}", e))?;
+        let manifest = TomlManifest::parse(&file_contents)?;
+        let root = manifest_path.parent().unwrap();
+
+        let mut member_paths = Vec::new();
+        if manifest.has_package() {
+            member_paths.push(manifest_path.clone());
+        }
+        for member in manifest.workspace_members().unwrap_or_default() {
+            member_paths.push(canonicalize_manifest_path(
+                &root.join(member).join(MANIFEST_FILENAME),
+            )?);
+        }
+        if member_paths.is_empty() {
+            anyhow::bail!("manifest contains neither a `package` nor a `workspace` section");
+        }
+
+        let mut loader = Loader::default();
+        for member_path in member_paths {
+            loader.load(&member_path, &mut Vec::new())?;
+        }
+
+        Ok(Self {
+            build: manifest.build_settings()?,
+            manifest_path,
+            packages: loader.packages,
+        })
+    }
+
+    /// Returns the path of the manifest
+    pub fn manifest_path(&self) -> &Path {
+        &self.manifest_path
+    }
+
+    /// Returns the root folder of the workspace
+    pub fn root(&self) -> &Path {
+        self.manifest_path().parent().unwrap()
+    }
+
+    /// Returns the build settings of the workspace, which are specified by its root manifest
+    pub fn build_settings(&self) -> &BuildSettings {
+        &self.build
+    }
+
+    /// Returns the packages of the workspace. Every package is preceded by the packages that it
+    /// depends on.
+    pub fn packages(&self) -> &[Package] {
+        &self.packages
+    }
+}
+
+/// Loads packages and their dependencies, ordering every package after its dependencies.
+#[derive(Default)]
+struct Loader {
+    packages: Vec<Package>,
+    // The indices of the loaded packages by the paths of their manifests
+    indices: HashMap<PathBuf, usize>,
+}
+
+impl Loader {
+    /// Loads the package of the manifest at the canonical `manifest_path` after its dependencies,
+    /// if it was not loaded before. `dependents` contains the packages that transitively depend
+    /// on the package, to detect cycles. Returns the index of the package.
+    fn load(
+        &mut self,
+        manifest_path: &Path,
+        dependents: &mut Vec<PathBuf>,
+    ) -> anyhow::Result<usize> {
+        if let Some(index) = self.indices.get(manifest_path) {
+            return Ok(*index);
+        }
+        if let Some(position) = dependents.iter().position(|path| path == manifest_path) {
+            let cycle = dependents[position..]
+                .iter()
+                .chain(std::iter::once(&manifest_path.to_path_buf()))
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            anyhow::bail!("cyclic dependency between packages: {}", cycle);
+        }
+
+        let package = Package::from_file(manifest_path)?;
+        dependents.push(manifest_path.to_path_buf());
+        for dependency in package.manifest().dependencies() {
+            if dependency.kind() != DependencyKind::Package {
+                continue;
+            }
+            let dependency_path = canonicalize_manifest_path(
+                &package
+                    .root()
+                    .join(dependency.path())
+                    .join(MANIFEST_FILENAME),
+            )?;
+            let index = self.load(&dependency_path, dependents)?;
+            let name = self.packages[index].name();
+            if name != dependency.name() {
+                anyhow::bail!(
+                    "dependency `{}` of package `{}` refers to package `{}`",
+                    dependency.name(),
+                    package.name(),
+                    name
+                );
+            }
+        }
+        dependents.pop();
+
+        if let Some(other) = self
+            .packages
+            .iter()
+            .find(|other| other.name() == package.name())
+        {
+            anyhow::bail!(
+                "multiple packages are named `{}`: '{}' and '{}'",
+                package.name(),
+                other.manifest_path().display(),
+                manifest_path.display()
+            );
+        }
+
+        let index = self.packages.len();
+        self.packages.push(package);
+        self.indices.insert(manifest_path.to_path_buf(), index);
+        Ok(index)
+    }
+}
+
+/// Returns the canonical form of the manifest path `path`, which is used to identify a package.
+fn canonicalize_manifest_path(path: &Path) -> anyhow::Result<PathBuf> {
+    path.canonicalize()
+        .map_err(|e| anyhow::anyhow!("could not read manifest file '{}': {}", path.display(), e))
+}
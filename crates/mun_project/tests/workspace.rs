@@ -0,0 +1,42 @@
+use mun_project::{BuildSettings, Workspace};
+use std::path::Path;
+
+#[test]
+fn workspace_from_file() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/resources/workspace")
+        .canonicalize()
+        .unwrap();
+    let workspace = Workspace::from_file(root.join("mun.toml")).expect("could not load workspace");
+    assert_eq!(workspace.root(), root);
+    assert_eq!(
+        workspace.build_settings(),
+        &BuildSettings {
+            target: None,
+            opt_level: Some(3),
+            target_dir: None,
+        }
+    );
+
+    // The dependency precedes the package that depends on it
+    let names: Vec<_> = workspace.packages().iter().map(|p| p.name()).collect();
+    assert_eq!(names, vec!["physics", "game"]);
+    assert_eq!(
+        workspace.packages()[0].source_directory(),
+        Some(root.join("physics/scripts"))
+    );
+}
+
+#[test]
+fn package_is_workspace() {
+    let manifest_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/resources/workspace/game/mun.toml")
+        .canonicalize()
+        .unwrap();
+    let workspace = Workspace::from_file(&manifest_path).expect("could not load workspace");
+    assert_eq!(workspace.manifest_path(), manifest_path);
+    assert_eq!(workspace.build_settings(), &BuildSettings::default());
+
+    let names: Vec<_> = workspace.packages().iter().map(|p| p.name()).collect();
+    assert_eq!(names, vec!["physics", "game"]);
+}
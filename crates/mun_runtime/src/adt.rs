@@ -1,4 +1,4 @@
-use crate::garbage_collector::{GcPtr, GcRootPtr, UnsafeTypeInfo};
+use crate::garbage_collector::{GarbageCollector, GcPtr, GcRootPtr, UnsafeTypeInfo};
 use crate::{
     marshal::Marshal,
     reflection::{
@@ -6,7 +6,7 @@ use crate::{
     },
     Runtime,
 };
-use memory::gc::{GcRuntime, HasIndirectionPtr};
+use memory::gc::{GcRuntime, HasIndirectionPtr, WeakGcPtr};
 use once_cell::sync::OnceCell;
 use std::cell::{Ref, RefCell};
 use std::{
@@ -21,7 +21,7 @@ use std::{
 /// Represents a Mun struct pointer.
 #[repr(transparent)]
 #[derive(Clone)]
-pub struct RawStruct(GcPtr);
+pub struct RawStruct(pub(crate) GcPtr);
 
 impl RawStruct {
     /// Returns a pointer to the struct memory.
@@ -40,7 +40,7 @@ pub struct StructRef<'s> {
 
 impl<'s> StructRef<'s> {
     /// Creates a `StructRef` that wraps a raw Mun struct.
-    fn new<'r>(raw: RawStruct, runtime: &'r Runtime) -> Self
+    pub(crate) fn new<'r>(raw: RawStruct, runtime: &'r Runtime) -> Self
     where
         'r: 's,
     {
@@ -57,6 +57,14 @@ impl<'s> StructRef<'s> {
         RootedStruct::new(&self.runtime.gc, runtime, self.raw)
     }
 
+    /// Creates a weak reference to the struct, which does not keep it alive.
+    pub fn downgrade(&self, runtime: Rc<RefCell<Runtime>>) -> WeakStructRef {
+        WeakStructRef {
+            weak: self.runtime.gc.downgrade(self.raw.0),
+            runtime,
+        }
+    }
+
     /// Returns the type information of the struct.
     pub fn type_info(&self) -> &abi::TypeInfo {
         // Safety: The type returned from `ptr_type` is guaranteed to live at least as long as
@@ -80,6 +88,18 @@ impl<'s> StructRef<'s> {
         NonNull::new_unchecked(self.raw.get_ptr().add(offset as usize).cast::<T>() as *mut _)
     }
 
+    /// Returns the names and types of the struct's fields, in the order in which they are
+    /// declared. The index of a field in this order can be passed to [`StructRef::get_by_index`],
+    /// so generic code, e.g. an inspector or a serializer, can access every field without knowing
+    /// its name.
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &abi::TypeInfo)> {
+        // Safety: `as_struct` is guaranteed to return `Some` for `StructRef`s.
+        let struct_info = self.type_info().as_struct().unwrap();
+        struct_info
+            .field_names()
+            .zip(struct_info.field_types().iter().copied())
+    }
+
     /// Retrieves the value of the field corresponding to the specified `field_name`.
     pub fn get<T: ReturnTypeReflection + Marshal<'s>>(&self, field_name: &str) -> Result<T, String>
     where
@@ -91,8 +111,32 @@ impl<'s> StructRef<'s> {
         let struct_info = type_info.as_struct().unwrap();
         let field_idx =
             abi::StructInfo::find_field_index(type_info.name(), struct_info, field_name)?;
+        self.get_by_index(field_idx)
+    }
 
-        // Safety: If we found the `field_idx`, we are guaranteed to also have the `field_type` and
+    /// Retrieves the value of the field at index `field_idx`, in the order in which the fields are
+    /// declared.
+    pub fn get_by_index<T: ReturnTypeReflection + Marshal<'s>>(
+        &self,
+        field_idx: usize,
+    ) -> Result<T, String>
+    where
+        T: 's,
+    {
+        let type_info = self.type_info();
+
+        // Safety: `as_struct` is guaranteed to return `Some` for `StructRef`s.
+        let struct_info = type_info.as_struct().unwrap();
+        let field_name = struct_info.field_names().nth(field_idx).ok_or_else(|| {
+            format!(
+                "Struct `{}` does not contain a field at index {}. It has {} fields.",
+                type_info.name(),
+                field_idx,
+                struct_info.num_fields,
+            )
+        })?;
+
+        // Safety: If the field has a name, we are guaranteed to also have the `field_type` and
         // `field_offset`.
         let field_type = unsafe { struct_info.field_types().get_unchecked(field_idx) };
         equals_return_type::<T>(field_type).map_err(|(expected, found)| {
@@ -104,8 +148,15 @@ impl<'s> StructRef<'s> {
                 found,
             )
         })?;
+        T::validate_layout(field_type).map_err(|e| {
+            format!(
+                "Mismatched types for `{}::{}`. {}",
+                type_info.name(),
+                field_name,
+                e
+            )
+        })?;
 
-        // If we found the `field_idx`, we are guaranteed to also have the `field_offset`
         let field_ptr =
             unsafe { self.field_offset_unchecked::<T::MunType>(struct_info, field_idx) };
         Ok(Marshal::marshal_from_ptr(
@@ -144,11 +195,20 @@ impl<'s> StructRef<'s> {
                 found,
             )
         })?;
+        T::validate_layout(field_type).map_err(|e| {
+            format!(
+                "Mismatched types for `{}::{}`. {}",
+                type_info.name(),
+                field_name,
+                e
+            )
+        })?;
 
         let field_ptr =
             unsafe { self.field_offset_unchecked::<T::MunType>(struct_info, field_idx) };
         let old = Marshal::marshal_from_ptr(field_ptr, self.runtime, Some(field_type));
         Marshal::marshal_to_ptr(value, field_ptr, Some(field_type));
+        self.runtime.gc.write_barrier(self.raw.0);
         Ok(old)
     }
 
@@ -177,10 +237,19 @@ impl<'s> StructRef<'s> {
                 found,
             )
         })?;
+        T::validate_layout(field_type).map_err(|e| {
+            format!(
+                "Mismatched types for `{}::{}`. {}",
+                type_info.name(),
+                field_name,
+                e
+            )
+        })?;
 
         let field_ptr =
             unsafe { self.field_offset_unchecked::<T::MunType>(struct_info, field_idx) };
         Marshal::marshal_to_ptr(value, field_ptr, Some(field_type));
+        self.runtime.gc.write_barrier(self.raw.0);
         Ok(())
     }
 }
@@ -207,9 +276,7 @@ impl<'r> ReturnTypeReflection for StructRef<'r> {
     fn type_guid() -> abi::Guid {
         // TODO: Once `const_fn` lands, replace this with a const md5 hash
         static GUID: OnceCell<abi::Guid> = OnceCell::new();
-        *GUID.get_or_init(|| abi::Guid {
-            b: md5::compute(<Self as ReturnTypeReflection>::type_name()).0,
-        })
+        *GUID.get_or_init(|| abi::Guid::from_name(<Self as ReturnTypeReflection>::type_name()))
     }
 }
 
@@ -289,6 +356,37 @@ impl<'s> Marshal<'s> for StructRef<'s> {
     }
 }
 
+/// A Rust struct whose fields are copied from and to the fields with the same names of a Mun
+/// struct with the same name. This trait should be implemented with `#[derive(MunStruct)]`.
+///
+/// The type of every field is checked when it is copied, so a conversion fails if the Mun struct
+/// changed, e.g. after it was hot reloaded.
+pub trait MunStruct<'s>: Sized {
+    /// Retrieves the name of the Mun struct.
+    fn type_name() -> &'static str;
+
+    /// Copies the fields of the Mun struct `s` into a new value.
+    fn from_struct_ref(s: &StructRef<'s>) -> Result<Self, String>;
+
+    /// Copies the fields of `self` into the Mun struct `s`. If an error occurs, the fields that
+    /// precede the erroneous field have already been copied.
+    fn write_to_struct_ref(self, s: &mut StructRef<'s>) -> Result<(), String>;
+
+    /// Checks that `s` is an instance of the Mun struct.
+    fn check_type(s: &StructRef<'s>) -> Result<(), String> {
+        let type_name = s.type_info().name();
+        if type_name != Self::type_name() {
+            Err(format!(
+                "Mismatched struct types. Expected: `{}`. Found: `{}`.",
+                Self::type_name(),
+                type_name
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
 /// Type-agnostic wrapper for interoperability with a Mun struct, that has been rooted. To marshal,
 /// obtain a `StructRef` for the `RootedStruct`.
 pub struct RootedStruct {
@@ -331,12 +429,82 @@ impl RootedStruct {
         RootedStructRef::new(RawStruct(self.handle.handle()), self.borrow_runtime())
     }
 
+    /// Creates a weak reference to the struct, which does not keep it alive.
+    pub fn downgrade(&self) -> WeakStructRef {
+        let weak = self.borrow_runtime().gc.downgrade(self.handle.handle());
+        WeakStructRef {
+            weak,
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Pins the struct, which guarantees that the address of its memory remains stable until the
+    /// returned `PinnedStruct` is dropped. This enables the host to pass the struct's memory to
+    /// systems that hold on to raw pointers.
+    ///
+    /// Hot reloading is postponed while structs are pinned, as it can move their memory.
+    pub fn pin(&self) -> PinnedStruct {
+        let gc = self.borrow_runtime().gc.clone();
+        gc.pin(self.handle.handle());
+        PinnedStruct {
+            handle: self.handle.handle(),
+            gc,
+            runtime: self.runtime.clone(),
+        }
+    }
+
     /// Borrows the struct's runtime.
     pub fn borrow_runtime(&self) -> Ref<Runtime> {
         self.runtime.borrow()
     }
 }
 
+/// A Mun struct of which the memory is guaranteed to remain at the same address, for as long as the
+/// `PinnedStruct` is alive. See [`RootedStruct::pin`].
+pub struct PinnedStruct {
+    handle: GcPtr,
+    gc: Arc<GarbageCollector>,
+    runtime: Rc<RefCell<Runtime>>,
+}
+
+impl PinnedStruct {
+    /// Returns a pointer to the struct's memory, which is valid while the `PinnedStruct` is alive.
+    pub fn as_ptr(&self) -> NonNull<u8> {
+        // Safety: The memory pointer of an allocated object is never null
+        unsafe { NonNull::new_unchecked(self.handle.deref::<u8>() as *mut u8) }
+    }
+
+    /// Converts the `PinnedStruct` to a pinned `RootedStructRef` that can be used just like a
+    /// `StructRef`.
+    pub fn by_ref(&self) -> Pin<Box<RootedStructRef>> {
+        RootedStructRef::new(RawStruct(self.handle), self.runtime.borrow())
+    }
+}
+
+impl Drop for PinnedStruct {
+    fn drop(&mut self) {
+        self.gc.unpin(self.handle);
+    }
+}
+
+/// A weak reference to a Mun struct, which does not keep the struct alive. This allows the host to
+/// cache structs without preventing them from being garbage collected. To access the struct, it has
+/// to be upgraded to a `RootedStruct`.
+#[derive(Clone)]
+pub struct WeakStructRef {
+    weak: WeakGcPtr,
+    runtime: Rc<RefCell<Runtime>>,
+}
+
+impl WeakStructRef {
+    /// Roots the struct, if it has not been garbage collected yet.
+    pub fn upgrade(&self) -> Option<RootedStruct> {
+        let gc = self.runtime.borrow().gc.clone();
+        let raw = RawStruct(gc.upgrade(self.weak)?);
+        Some(RootedStruct::new(&gc, self.runtime.clone(), raw))
+    }
+}
+
 /// Type-agnostic wrapper for safely obtaining a `StructRef` from a `RootedStruct`.
 pub struct RootedStructRef<'s> {
     runtime: Ref<'s, Runtime>,
@@ -0,0 +1,130 @@
+use crate::garbage_collector::{GarbageCollector, GcPtr};
+use memory::{
+    gc::{Event, Observer},
+    TypeMemory,
+};
+use parking_lot::{Mutex, RwLock};
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// An allocation or deallocation of an object by the garbage collector of a `Runtime`. See
+/// [`Runtime::on_allocation`] and [`Runtime::on_deallocation`].
+///
+/// [`Runtime::on_allocation`]: struct.Runtime.html#method.on_allocation
+/// [`Runtime::on_deallocation`]: struct.Runtime.html#method.on_deallocation
+#[derive(Clone, Copy, Debug)]
+pub struct AllocationEvent<'a> {
+    /// The handle of the object, which identifies it until it is deallocated.
+    pub handle: GcPtr,
+    /// The type of the object.
+    pub type_info: &'a abi::TypeInfo,
+    /// The number of bytes that are allocated for the object.
+    pub size: usize,
+    /// The id of the stack that allocated the object, as returned by the function that was passed
+    /// to [`Runtime::set_allocation_stack_id`], if any.
+    ///
+    /// [`Runtime::set_allocation_stack_id`]: struct.Runtime.html#method.set_allocation_stack_id
+    pub stack_id: Option<u64>,
+}
+
+type AllocationHook = Box<dyn Fn(&AllocationEvent) + Send + Sync>;
+type StackIdFn = Box<dyn Fn() -> Option<u64> + Send + Sync>;
+
+/// Observes the garbage collector of a `Runtime` to invoke the allocation hooks of the host. The
+/// hooks are only invoked while tracking is enabled.
+#[derive(Default)]
+pub struct AllocationTracker {
+    is_enabled: AtomicBool,
+    on_allocation: RwLock<Vec<AllocationHook>>,
+    on_deallocation: RwLock<Vec<AllocationHook>>,
+    stack_id: RwLock<Option<StackIdFn>>,
+    /// The ids of the stacks that allocated the live objects, if they were known
+    stack_ids: Mutex<HashMap<GcPtr, u64>>,
+}
+
+impl AllocationTracker {
+    /// Constructs a tracker that invokes the hooks if `is_enabled` is `true`.
+    pub fn new(is_enabled: bool) -> Self {
+        Self {
+            is_enabled: AtomicBool::new(is_enabled),
+            ..Default::default()
+        }
+    }
+
+    /// Enables or disables the invocation of the hooks.
+    pub fn set_enabled(&self, is_enabled: bool) {
+        self.is_enabled.store(is_enabled, Ordering::Release);
+        if !is_enabled {
+            // The objects might be deallocated without being observed
+            self.stack_ids.lock().clear();
+        }
+    }
+
+    /// Adds a hook that is invoked for every allocation.
+    pub fn add_allocation_hook(&self, hook: AllocationHook) {
+        self.on_allocation.write().push(hook);
+    }
+
+    /// Adds a hook that is invoked for every deallocation.
+    pub fn add_deallocation_hook(&self, hook: AllocationHook) {
+        self.on_deallocation.write().push(hook);
+    }
+
+    /// Sets the function that returns the id of the stack that allocates an object.
+    pub fn set_stack_id(&self, stack_id: StackIdFn) {
+        *self.stack_id.write() = Some(stack_id);
+    }
+
+    /// Invokes the `hooks` for the object `handle`.
+    fn notify(&self, hooks: &RwLock<Vec<AllocationHook>>, handle: GcPtr, stack_id: Option<u64>) {
+        let hooks = hooks.read();
+        if hooks.is_empty() {
+            return;
+        }
+
+        // Safety: The tracker only observes a `GarbageCollector`, which emits the events of
+        // objects that are alive or being deallocated.
+        let ty = unsafe { GarbageCollector::ptr_type_unchecked(handle) };
+        let event = AllocationEvent {
+            handle,
+            // Safety: The type of an object outlives the object.
+            type_info: unsafe { ty.into_inner().as_ref() },
+            size: ty.layout().size(),
+            stack_id,
+        };
+        for hook in hooks.iter() {
+            hook(&event);
+        }
+    }
+}
+
+impl Observer for AllocationTracker {
+    type Event = Event;
+
+    fn event(&self, event: Event) {
+        if !self.is_enabled.load(Ordering::Acquire) {
+            return;
+        }
+
+        match event {
+            Event::Allocation(handle) => {
+                let stack_id = self
+                    .stack_id
+                    .read()
+                    .as_ref()
+                    .and_then(|stack_id| stack_id());
+                if let Some(stack_id) = stack_id {
+                    self.stack_ids.lock().insert(handle, stack_id);
+                }
+                self.notify(&self.on_allocation, handle, stack_id);
+            }
+            Event::Deallocation(handle) => {
+                let stack_id = self.stack_ids.lock().remove(&handle);
+                self.notify(&self.on_deallocation, handle, stack_id);
+            }
+            Event::Start | Event::End => (),
+        }
+    }
+}
@@ -3,31 +3,96 @@ use std::path::{Path, PathBuf};
 
 use crate::DispatchTable;
 use abi::AssemblyInfo;
+#[cfg(not(target_arch = "wasm32"))]
 use libloading::Symbol;
 
+mod compat;
+#[cfg(not(target_arch = "wasm32"))]
 mod temp_library;
 
+#[cfg(not(target_arch = "wasm32"))]
 use self::temp_library::TempLibrary;
-use crate::garbage_collector::{GarbageCollector, UnsafeTypeInfo};
-use memory::mapping::{Mapping, MemoryMapper};
-use std::{collections::HashSet, ptr::NonNull, sync::Arc};
+use crate::garbage_collector::GarbageCollector;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::garbage_collector::UnsafeTypeInfo;
+#[cfg(not(target_arch = "wasm32"))]
+use memory::mapping::{Mapping, MemoryMapper, Migrations};
+#[cfg(not(target_arch = "wasm32"))]
+use std::any::Any;
+#[cfg(not(target_arch = "wasm32"))]
+use std::ptr::NonNull;
+use std::{collections::HashSet, ffi, sync::Arc};
+
+/// The functions that a munlib exposes to the runtime.
+///
+/// On targets without dynamic libraries, like `wasm32`, the munlib is linked into the executable
+/// of the host and the functions are obtained with the [`static_assembly!`] macro.
+#[derive(Clone, Copy)]
+pub struct StaticAssembly {
+    /// Returns the information of the assembly
+    pub get_info: extern "C" fn() -> AssemblyInfo,
+    /// Returns the ABI version of the assembly
+    pub get_version: extern "C" fn() -> u32,
+    /// Sets the allocator that is used by the assembly
+    pub set_allocator_handle: extern "C" fn(*mut ffi::c_void),
+}
 
 /// An assembly is a hot reloadable compilation unit, consisting of one or more Mun modules.
 pub struct Assembly {
     library_path: PathBuf,
-    library: TempLibrary,
-    legacy_libs: Vec<TempLibrary>,
+    /// The loaded shared library, or `None` if the assembly is statically linked
+    #[cfg(not(target_arch = "wasm32"))]
+    library: Option<TempLibrary>,
+    /// The owner of the code of an assembly that was compiled into the memory of the process
+    #[cfg(not(target_arch = "wasm32"))]
+    in_process_code: Option<Box<dyn Any + Send + Sync>>,
+    /// The code of previous versions of the assembly, which is retained because objects of their
+    /// types are still alive
+    #[cfg(not(target_arch = "wasm32"))]
+    legacy_code: Vec<Box<dyn Any + Send + Sync>>,
     info: AssemblyInfo,
+    /// The ABI version that the assembly was built against
+    abi_version: u32,
+    /// The memory that backs the information of an assembly that was built against a previous
+    /// ABI version, which is converted to the current ABI
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    converted_info: Option<compat::ConvertedInfo>,
+    /// The indices of the entries in the dispatch table of the assembly that refer to functions
+    /// outside of the assembly, which are resolved by the runtime when the assembly is linked
+    imports: Vec<usize>,
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
     allocator: Arc<GarbageCollector>,
 }
 
 impl Assembly {
     /// Loads an assembly and its information for the shared library at `library_path`. The
     /// resulting `Assembly` is ensured to be linkable.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn load(
         library_path: &Path,
         gc: Arc<GarbageCollector>,
         runtime_dispatch_table: &DispatchTable,
+    ) -> Result<Self, anyhow::Error> {
+        let assembly = Assembly::load_unlinked(library_path, gc)?;
+
+        // Ensure that any loaded `Assembly` can be linked safely.
+        assembly.ensure_linkable(runtime_dispatch_table)?;
+        Ok(assembly)
+    }
+
+    /// Loads the assembly of the shared library at `library_path` to inspect its information, e.g.
+    /// to generate bindings for it. The assembly is not linked, so its functions cannot be invoked.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn inspect(library_path: &Path) -> Result<Self, anyhow::Error> {
+        Assembly::load_unlinked(library_path, Arc::new(GarbageCollector::default()))
+    }
+
+    /// Loads an assembly and its information for the shared library at `library_path`, without
+    /// verifying that it is linkable.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn load_unlinked(
+        library_path: &Path,
+        gc: Arc<GarbageCollector>,
     ) -> Result<Self, anyhow::Error> {
         let library = TempLibrary::new(library_path)?;
 
@@ -38,14 +103,47 @@ impl Assembly {
         let get_version: Symbol<'_, extern "C" fn() -> u32> =
             unsafe { library.library().get(abi::GET_VERSION_FN_NAME.as_bytes()) }?;
 
-        let set_allocator_handle: Symbol<'_, extern "C" fn(*mut std::ffi::c_void)> = unsafe {
+        let set_allocator_handle: Symbol<'_, extern "C" fn(*mut ffi::c_void)> = unsafe {
             library
                 .library()
                 .get(abi::SET_ALLOCATOR_HANDLE_FN_NAME.as_bytes())
         }?;
 
-        let version = get_version();
-        if abi::ABI_VERSION != version {
+        let entry_points = StaticAssembly {
+            get_info: *get_info,
+            get_version: *get_version,
+            set_allocator_handle: *set_allocator_handle,
+        };
+        let mut assembly = Assembly::from_static_unlinked(library_path, &entry_points, gc)?;
+        assembly.library = Some(library);
+        Ok(assembly)
+    }
+
+    /// Constructs an assembly from the functions of a munlib that is linked into the executable.
+    /// `library_path` identifies the assembly in the runtime. The resulting `Assembly` is ensured
+    /// to be linkable.
+    pub fn from_static(
+        library_path: &Path,
+        assembly: &StaticAssembly,
+        gc: Arc<GarbageCollector>,
+        runtime_dispatch_table: &DispatchTable,
+    ) -> Result<Self, anyhow::Error> {
+        let assembly = Assembly::from_static_unlinked(library_path, assembly, gc)?;
+
+        // Ensure that any loaded `Assembly` can be linked safely.
+        assembly.ensure_linkable(runtime_dispatch_table)?;
+        Ok(assembly)
+    }
+
+    /// Constructs an assembly from the functions of a munlib that is linked into the executable,
+    /// without verifying that it is linkable.
+    fn from_static_unlinked(
+        library_path: &Path,
+        assembly: &StaticAssembly,
+        gc: Arc<GarbageCollector>,
+    ) -> Result<Self, anyhow::Error> {
+        let version = (assembly.get_version)();
+        if !compat::is_supported(version) {
             return Err(anyhow::anyhow!(
                 "ABI version mismatch. munlib is `{}` but runtime is `{}`",
                 version,
@@ -53,25 +151,41 @@ impl Assembly {
             ));
         }
 
-        let allocator_ptr = Arc::into_raw(gc.clone()) as *mut std::ffi::c_void;
-        set_allocator_handle(allocator_ptr);
+        let allocator_ptr = Arc::into_raw(gc.clone()) as *mut ffi::c_void;
+        (assembly.set_allocator_handle)(allocator_ptr);
 
-        let info = get_info();
-        let assembly = Assembly {
+        let (info, converted_info) = compat::get_info(assembly, version);
+        // Only the functions that do *not* yet have a function pointer assigned by the compiler
+        // are imported.
+        let imports = info
+            .dispatch_table
+            .iter()
+            .enumerate()
+            .filter(|(_, (fn_ptr, _))| fn_ptr.is_null())
+            .map(|(idx, _)| idx)
+            .collect();
+        Ok(Assembly {
             library_path: library_path.to_path_buf(),
-            library,
-            legacy_libs: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            library: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            in_process_code: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            legacy_code: Vec::new(),
             info,
+            abi_version: version,
+            converted_info,
+            imports,
             allocator: gc,
-        };
-
-        // Ensure that any loaded `Assembly` can be linked safely.
-        assembly.ensure_linkable(runtime_dispatch_table)?;
-        Ok(assembly)
+        })
     }
 
-    /// Verifies that the `Assembly` resolves all dependencies in the `DispatchTable`.
-    fn ensure_linkable(&self, runtime_dispatch_table: &DispatchTable) -> Result<(), io::Error> {
+    /// Verifies that the `Assembly` resolves all dependencies in the `DispatchTable`, and that it
+    /// contains all functions that other assemblies depend on.
+    pub(crate) fn ensure_linkable(
+        &self,
+        runtime_dispatch_table: &DispatchTable,
+    ) -> Result<(), io::Error> {
         let fn_names: HashSet<&str> = self
             .info
             .symbols
@@ -80,13 +194,7 @@ impl Assembly {
             .map(|f| f.prototype.name())
             .collect();
 
-        for (fn_ptr, fn_prototype) in self.info.dispatch_table.iter() {
-            // Only take signatures into account that do *not* yet have a function pointer assigned
-            // by the compiler.
-            if !fn_ptr.is_null() {
-                continue;
-            }
-
+        for fn_prototype in self.imports() {
             // Ensure that the required function is in the runtime dispatch table and that its signature
             // is the same.
             match runtime_dispatch_table.get_fn(fn_prototype.name()) {
@@ -112,7 +220,7 @@ impl Assembly {
 
         if let Some(dependencies) = runtime_dispatch_table
             .fn_dependencies
-            .get(self.info.symbols.path())
+            .get(self.library_path.to_string_lossy().as_ref())
         {
             for fn_name in dependencies.keys() {
                 if !fn_names.contains(&fn_name.as_str()) {
@@ -124,9 +232,10 @@ impl Assembly {
             }
 
             for fn_definition in self.info.symbols.functions().iter() {
-                let (fn_prototype, _) = dependencies
-                    .get(fn_definition.prototype.name())
-                    .expect("The dependency must exist after the previous check.");
+                let fn_prototype = match dependencies.get(fn_definition.prototype.name()) {
+                    Some((fn_prototype, _)) => fn_prototype,
+                    None => continue,
+                };
 
                 if fn_prototype.signature != fn_definition.prototype.signature {
                     return Err(io::Error::new(
@@ -140,17 +249,25 @@ impl Assembly {
         Ok(())
     }
 
-    /// Links the assembly using the runtime's dispatch table.
+    /// Inserts the functions of the assembly into the runtime's dispatch table, which allows other
+    /// assemblies to link to them.
+    pub(crate) fn register(&self, runtime_dispatch_table: &mut DispatchTable) {
+        for function in self.info.symbols.functions() {
+            runtime_dispatch_table.insert_fn(function.prototype.name(), function.clone());
+        }
+    }
+
+    /// Links the assembly using the runtime's dispatch table. An assembly that was already linked
+    /// is relinked, e.g. to call the functions of another assembly that was hot reloaded.
     ///
     /// Requires that `ensure_linkable` has been called beforehand. This happens upon creation of
     /// an `Assembly` - in the `load` function - making this function safe.
     pub fn link(&mut self, runtime_dispatch_table: &mut DispatchTable) {
-        for function in self.info.symbols.functions() {
-            runtime_dispatch_table.insert_fn(function.prototype.name(), function.clone());
-        }
+        self.register(runtime_dispatch_table);
 
-        for (dispatch_ptr, fn_prototype) in self.info.dispatch_table.iter_mut() {
-            if dispatch_ptr.is_null() {
+        let imports = &self.imports;
+        for (idx, (dispatch_ptr, fn_prototype)) in self.info.dispatch_table.iter_mut().enumerate() {
+            if imports.contains(&idx) {
                 let fn_ptr = runtime_dispatch_table
                     .get_fn(fn_prototype.name())
                     .unwrap_or_else(|| panic!("Function '{}' is expected to exist.", fn_prototype))
@@ -160,15 +277,66 @@ impl Assembly {
         }
     }
 
+    /// Constructs an assembly from the functions of a munlib that was compiled into the memory of
+    /// the process, e.g. by a just-in-time compiler. `code` owns the memory of the compiled code,
+    /// which is kept alive for as long as the code can be executed. The resulting `Assembly` is
+    /// ensured to be linkable.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn in_process(
+        library_path: &Path,
+        assembly: &StaticAssembly,
+        code: Box<dyn Any + Send + Sync>,
+        gc: Arc<GarbageCollector>,
+        runtime_dispatch_table: &DispatchTable,
+    ) -> Result<Self, anyhow::Error> {
+        let mut assembly =
+            Assembly::from_static(library_path, assembly, gc, runtime_dispatch_table)?;
+        assembly.in_process_code = Some(code);
+        Ok(assembly)
+    }
+
     /// Swaps the assembly's shared library and its information for the library at `library_path`.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn swap(
         &mut self,
         library_path: &Path,
         runtime_dispatch_table: &mut DispatchTable,
     ) -> Result<(), anyhow::Error> {
-        let mut new_assembly =
+        let new_assembly =
             Assembly::load(library_path, self.allocator.clone(), runtime_dispatch_table)?;
+        self.replace(new_assembly, runtime_dispatch_table, &Migrations::new());
+        Ok(())
+    }
+
+    /// Swaps the assembly's code and its information for code that was compiled into the memory
+    /// of the process, see [`Assembly::in_process`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn swap_in_process(
+        &mut self,
+        assembly: &StaticAssembly,
+        code: Box<dyn Any + Send + Sync>,
+        runtime_dispatch_table: &mut DispatchTable,
+    ) -> Result<(), anyhow::Error> {
+        let new_assembly = Assembly::in_process(
+            &self.library_path,
+            assembly,
+            code,
+            self.allocator.clone(),
+            runtime_dispatch_table,
+        )?;
+        self.replace(new_assembly, runtime_dispatch_table, &Migrations::new());
+        Ok(())
+    }
 
+    /// Replaces the assembly with `new_assembly`, mapping all objects of the old types to the new
+    /// types. The `migrations` are used for the types of which the layout changed.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn replace(
+        &mut self,
+        mut new_assembly: Assembly,
+        runtime_dispatch_table: &mut DispatchTable,
+        migrations: &Migrations<UnsafeTypeInfo>,
+    ) {
         let old_types: Vec<UnsafeTypeInfo> = self
             .info
             .symbols
@@ -195,7 +363,7 @@ impl Assembly {
             })
             .collect();
 
-        let mapping = Mapping::new(&old_types, &new_types);
+        let mapping = Mapping::with_migrations(&old_types, &new_types, migrations);
         let deleted_objects = self.allocator.map_memory(mapping);
 
         // Remove the old assembly's functions
@@ -205,18 +373,31 @@ impl Assembly {
 
         new_assembly.link(runtime_dispatch_table);
 
-        // Retain all existing legacy libs
-        new_assembly.legacy_libs.append(&mut self.legacy_libs);
+        // Retain all existing legacy code
+        new_assembly.legacy_code.append(&mut self.legacy_code);
 
         std::mem::swap(self, &mut new_assembly);
         let old_assembly = new_assembly;
 
         if !deleted_objects.is_empty() {
-            // Retain the previous assembly
-            self.legacy_libs.push(old_assembly.into_library());
+            // Retain the code of the previous assembly
+            if let Some(code) = old_assembly.in_process_code {
+                self.legacy_code.push(code);
+            }
+            if let Some(library) = old_assembly.library {
+                self.legacy_code.push(Box::new(library));
+            }
+            if let Some(converted_info) = old_assembly.converted_info {
+                self.legacy_code.push(Box::new(converted_info));
+            }
         }
+    }
 
-        Ok(())
+    /// Takes the code of the previous versions of the assembly, which must be retained for as long
+    /// as objects of their types are alive.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn take_legacy_code(&mut self) -> Vec<Box<dyn Any + Send + Sync>> {
+        std::mem::take(&mut self.legacy_code)
     }
 
     /// Returns the assembly's information.
@@ -224,13 +405,57 @@ impl Assembly {
         &self.info
     }
 
+    /// Returns the ABI version that the assembly was built against. Assemblies that were built
+    /// against a previous version are converted to the current version upon loading.
+    pub fn abi_version(&self) -> u32 {
+        self.abi_version
+    }
+
+    /// Returns `true` if the code of the assembly notifies the garbage collector of the
+    /// references that it stores in objects, which is required for incremental collections.
+    /// Assemblies that were built against ABI version 0.2.0 do not.
+    pub fn emits_write_barriers(&self) -> bool {
+        compat::emits_write_barriers(self.abi_version)
+    }
+
+    /// Returns the public functions of the assembly.
+    pub fn functions(&self) -> &[abi::FunctionDefinition] {
+        self.info.symbols.functions()
+    }
+
+    /// Returns the test functions of the assembly, which are marked with a `#[test]` attribute.
+    /// Their names are prefixed with the path of the module that defines them.
+    pub fn tests(&self) -> &[abi::FunctionDefinition] {
+        self.info.tests()
+    }
+
+    /// Returns the benchmark functions of the assembly, which are marked with a `#[bench]`
+    /// attribute. Their names are prefixed with the path of the module that defines them.
+    pub fn benches(&self) -> &[abi::FunctionDefinition] {
+        self.info.benches()
+    }
+
+    /// Returns the prototypes of the functions that the assembly imports from the runtime, i.e.
+    /// from the user functions or from other assemblies.
+    pub fn imports(&self) -> impl Iterator<Item = &abi::FunctionPrototype> {
+        let prototypes = self.info.dispatch_table.prototypes();
+        self.imports.iter().map(move |idx| &prototypes[*idx])
+    }
+
+    /// Returns the types that are used by the public functions and structs of the assembly.
+    pub fn types(&self) -> &[&abi::TypeInfo] {
+        self.info.symbols.types()
+    }
+
     /// Returns the path corresponding to the assembly's library.
     pub fn library_path(&self) -> &Path {
         self.library_path.as_path()
     }
 
-    /// Converts the `Assembly` into a `TempLibrary`, consuming the input in the process.
-    pub fn into_library(self) -> TempLibrary {
+    /// Converts the `Assembly` into a `TempLibrary`, consuming the input in the process. Returns
+    /// `None` if the assembly is statically linked.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn into_library(self) -> Option<TempLibrary> {
         self.library
     }
 }
@@ -0,0 +1,326 @@
+//! Loading of munlibs that were built against the previous version of the ABI.
+//!
+//! The information of such a munlib is converted to the layout of the current ABI. The functions
+//! and the types of structs are copied, because their layout lacks fields that the runtime reads,
+//! and the GUIDs of structs are derived anew from their fields. All other information, like names
+//! and function pointers, still refers to the memory of the munlib.
+
+use crate::assembly::StaticAssembly;
+use abi::{AssemblyInfo, FunctionDefinition, FunctionPrototype, FunctionSignature, Guid, TypeInfo};
+use std::{collections::HashMap, ffi::CStr, os::raw::c_char, ptr};
+
+/// The previous ABI version, of which munlibs can still be loaded.
+#[allow(clippy::zero_prefixed_literal)]
+const V0_2_0: u32 = 00_02_00;
+
+/// The information of a munlib built against ABI version 0.2.0.
+mod v0_2 {
+    use std::os::raw::c_char;
+
+    /// The `FunctionDefinition` before the documentation was introduced.
+    #[repr(C)]
+    pub struct FunctionDefinition {
+        pub prototype: abi::FunctionPrototype,
+        pub fn_ptr: *const std::ffi::c_void,
+    }
+
+    /// The `StructInfo` before the renamed fields were introduced.
+    #[repr(C)]
+    pub struct StructInfo {
+        pub field_names: *const *const c_char,
+        pub field_types: *const *const abi::TypeInfo,
+        pub field_offsets: *const u16,
+        pub num_fields: u16,
+        pub memory_kind: abi::StructMemoryKind,
+    }
+
+    /// The `AssemblyInfo` before the build metadata and the test functions were introduced.
+    #[repr(C)]
+    pub struct AssemblyInfo {
+        pub symbols: abi::ModuleInfo,
+        pub dispatch_table: abi::DispatchTable,
+        pub dependencies: *const *const c_char,
+        pub num_dependencies: u32,
+    }
+}
+
+/// The metadata of a munlib that was built before build metadata was introduced.
+const UNKNOWN_METADATA: &[u8] = b"\0";
+
+/// Returns `true` if munlibs that were built against ABI `version` can be loaded.
+pub(crate) fn is_supported(version: u32) -> bool {
+    version == abi::ABI_VERSION || version == V0_2_0
+}
+
+/// Returns `true` if the code of munlibs that were built against ABI `version` notifies the garbage
+/// collector of the references that it stores in objects, which incremental collections require.
+pub(crate) fn emits_write_barriers(version: u32) -> bool {
+    version != V0_2_0
+}
+
+/// The memory that backs the converted information of a munlib, which must outlive all references
+/// to its types.
+#[derive(Default)]
+pub(crate) struct ConvertedInfo {
+    /// The copies are boxed, so their addresses do not change while they are added
+    #[allow(clippy::vec_box)]
+    struct_types: Vec<Box<StructTypeInfo>>,
+    field_types: Vec<Vec<*const TypeInfo>>,
+    arg_types: Vec<Vec<*const TypeInfo>>,
+    types: Vec<*const TypeInfo>,
+    functions: Vec<FunctionDefinition>,
+    prototypes: Vec<FunctionPrototype>,
+}
+
+// Safety: The converted information is never mutated after it was constructed.
+unsafe impl Send for ConvertedInfo {}
+unsafe impl Sync for ConvertedInfo {}
+
+/// The type of a struct in the layout of the current ABI, such that `TypeInfo::as_struct` finds
+/// its `StructInfo`.
+#[repr(C)]
+struct StructTypeInfo {
+    type_info: TypeInfo,
+    struct_info: abi::StructInfo,
+}
+
+/// Obtains the information of the munlib `assembly` that was built against ABI `version`, which
+/// must be supported. If the information has to be converted, the memory that backs it is
+/// returned as well.
+pub(crate) fn get_info(
+    assembly: &StaticAssembly,
+    version: u32,
+) -> (AssemblyInfo, Option<ConvertedInfo>) {
+    if version == abi::ABI_VERSION {
+        return ((assembly.get_info)(), None);
+    }
+
+    // Safety: A munlib of ABI version 0.2.0 returns the information in the layout of that version.
+    let get_info: extern "C" fn() -> v0_2::AssemblyInfo =
+        unsafe { std::mem::transmute(assembly.get_info) };
+    let info = get_info();
+    let unknown = UNKNOWN_METADATA.as_ptr().cast::<c_char>();
+    let mut info = AssemblyInfo {
+        symbols: info.symbols,
+        dispatch_table: info.dispatch_table,
+        dependencies: info.dependencies,
+        num_dependencies: info.num_dependencies,
+        compiler_version: unknown,
+        source_hash: unknown,
+        fingerprint: unknown,
+        tests: ptr::null(),
+        num_tests: 0,
+        benches: ptr::null(),
+        num_benches: 0,
+    };
+
+    // Safety: The information of the munlib lives as long as the munlib and has the layout of ABI
+    // version 0.2.0.
+    let converted = unsafe { ConvertedInfo::convert(&mut info) };
+    (info, Some(converted))
+}
+
+impl ConvertedInfo {
+    /// Replaces the functions and the types of the structs that are referred to by `info`, which
+    /// have the layout of ABI version 0.2.0, with copies in the current layout.
+    unsafe fn convert(info: &mut AssemblyInfo) -> Self {
+        let mut converted = ConvertedInfo::default();
+        let mut struct_types = HashMap::new();
+        let mut guids = HashMap::new();
+
+        // The functions of the munlib are not yet in the layout of the current ABI
+        let legacy_functions: &[v0_2::FunctionDefinition] = if info.symbols.num_functions == 0 {
+            &[]
+        } else {
+            std::slice::from_raw_parts(
+                info.symbols.functions.cast::<v0_2::FunctionDefinition>(),
+                info.symbols.num_functions as usize,
+            )
+        };
+
+        // Copy all struct types that are reachable from the types and signatures of the munlib
+        let signatures = legacy_functions
+            .iter()
+            .map(|function| &function.prototype.signature)
+            .chain(
+                info.dispatch_table
+                    .prototypes()
+                    .iter()
+                    .map(|prototype| &prototype.signature),
+            );
+        let mut queue: Vec<*const TypeInfo> = info
+            .symbols
+            .types()
+            .iter()
+            .map(|ty| *ty as *const TypeInfo)
+            .chain(signatures.flat_map(|signature| {
+                signature
+                    .arg_types()
+                    .iter()
+                    .map(|ty| *ty as *const TypeInfo)
+                    .chain(signature.return_type().map(|ty| ty as *const TypeInfo))
+            }))
+            .collect();
+        let mut legacy_struct_infos = Vec::new();
+        while let Some(ty) = queue.pop() {
+            if struct_types.contains_key(&ty) || !(*ty).group.is_struct() {
+                continue;
+            }
+
+            let legacy = legacy_struct_info(&*ty);
+            let copy = Box::new(StructTypeInfo {
+                type_info: TypeInfo {
+                    guid: derive_guid(ty, &mut guids),
+                    name: (*ty).name,
+                    size_in_bits: (*ty).size_in_bits,
+                    alignment: (*ty).alignment,
+                    group: (*ty).group,
+                },
+                struct_info: abi::StructInfo {
+                    field_names: legacy.field_names,
+                    field_types: ptr::null(),
+                    field_offsets: legacy.field_offsets,
+                    num_fields: legacy.num_fields,
+                    memory_kind: legacy.memory_kind.clone(),
+                    field_renamed_from: ptr::null(),
+                },
+            });
+            struct_types.insert(ty, &copy.type_info as *const TypeInfo);
+            converted.struct_types.push(copy);
+            legacy_struct_infos.push(legacy);
+            queue.extend_from_slice(legacy_field_types(legacy));
+        }
+
+        let convert = |ty: *const TypeInfo| *struct_types.get(&ty).unwrap_or(&ty);
+
+        // Redirect all references to the copies
+        for (copy, legacy) in converted.struct_types.iter_mut().zip(legacy_struct_infos) {
+            if legacy.num_fields == 0 {
+                continue;
+            }
+            let field_types: Vec<_> = legacy_field_types(legacy)
+                .iter()
+                .map(|ty| convert(*ty))
+                .collect();
+            copy.struct_info.field_types = field_types.as_ptr();
+            converted.field_types.push(field_types);
+        }
+
+        converted.types = info.symbols.types().iter().map(|ty| convert(*ty)).collect();
+        info.symbols.types = converted.types.as_ptr();
+
+        let mut arg_types = Vec::new();
+        let mut convert_prototype = |prototype: &FunctionPrototype| FunctionPrototype {
+            name: prototype.name,
+            signature: FunctionSignature {
+                arg_types: {
+                    let types: Vec<_> = prototype
+                        .signature
+                        .arg_types()
+                        .iter()
+                        .map(|ty| convert(*ty))
+                        .collect();
+                    let ptr = types.as_ptr();
+                    arg_types.push(types);
+                    ptr
+                },
+                return_type: prototype
+                    .signature
+                    .return_type()
+                    .map_or(ptr::null(), |ty| convert(ty as *const TypeInfo)),
+                num_arg_types: prototype.signature.num_arg_types,
+            },
+        };
+
+        converted.functions = legacy_functions
+            .iter()
+            .map(|function| FunctionDefinition {
+                prototype: convert_prototype(&function.prototype),
+                fn_ptr: function.fn_ptr,
+                docs: ptr::null(),
+            })
+            .collect();
+        info.symbols.functions = converted.functions.as_ptr();
+
+        // The function pointers of the dispatch table are used by the code of the munlib, so only
+        // the prototypes are replaced.
+        converted.prototypes = info
+            .dispatch_table
+            .prototypes()
+            .iter()
+            .map(&mut convert_prototype)
+            .collect();
+        info.dispatch_table.prototypes = converted.prototypes.as_ptr();
+
+        converted.arg_types = arg_types;
+        converted
+    }
+}
+
+/// Derives the GUID of the type `ty` of a munlib of ABI version 0.2.0, which derived the GUIDs of
+/// structs from the names of their field types instead of from their GUIDs.
+unsafe fn derive_guid(ty: *const TypeInfo, guids: &mut HashMap<*const TypeInfo, Guid>) -> Guid {
+    // The GUIDs of fundamental types are derived from their names in both versions
+    if !(*ty).group.is_struct() {
+        return (*ty).guid;
+    }
+    if let Some(guid) = guids.get(&ty) {
+        return *guid;
+    }
+
+    let legacy = legacy_struct_info(&*ty);
+    let field_names = legacy_field_names(legacy);
+    let mut fields = Vec::with_capacity(field_names.len());
+    for (name, field_ty) in field_names.iter().zip(legacy_field_types(legacy)) {
+        let name = CStr::from_ptr(*name)
+            .to_str()
+            .expect("field name must be valid UTF-8");
+        // A `gc` struct can refer to itself, so it is referred to by name
+        let guid = if legacy_struct_kind(*field_ty) == Some(&abi::StructMemoryKind::GC) {
+            Guid::from_gc_struct_reference((**field_ty).name())
+        } else {
+            derive_guid(*field_ty, guids)
+        };
+        fields.push((name, guid));
+    }
+
+    let guid = Guid::from_struct((*ty).name(), fields);
+    guids.insert(ty, guid);
+    guid
+}
+
+/// Returns the `StructInfo` of the struct type `ty` in the layout of ABI version 0.2.0.
+unsafe fn legacy_struct_info(ty: &TypeInfo) -> &v0_2::StructInfo {
+    // The `StructInfo` is located at the same offset in both layouts
+    let struct_info =
+        ty.as_struct()
+            .expect("a struct type must have struct information") as *const abi::StructInfo;
+    &*struct_info.cast::<v0_2::StructInfo>()
+}
+
+/// Returns the memory kind of `ty` if it is a struct type in the layout of ABI version 0.2.0.
+unsafe fn legacy_struct_kind<'a>(ty: *const TypeInfo) -> Option<&'a abi::StructMemoryKind> {
+    if (*ty).group.is_struct() {
+        Some(&legacy_struct_info(&*ty).memory_kind)
+    } else {
+        None
+    }
+}
+
+/// Returns the names of the fields of a struct in the layout of ABI version 0.2.0.
+unsafe fn legacy_field_names(struct_info: &v0_2::StructInfo) -> &[*const c_char] {
+    if struct_info.num_fields == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(struct_info.field_names, struct_info.num_fields as usize)
+    }
+}
+
+/// Returns the types of the fields of a struct in the layout of ABI version 0.2.0.
+unsafe fn legacy_field_types(struct_info: &v0_2::StructInfo) -> &[*const TypeInfo] {
+    if struct_info.num_fields == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(struct_info.field_types, struct_info.num_fields as usize)
+    }
+}
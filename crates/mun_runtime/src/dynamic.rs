@@ -0,0 +1,347 @@
+//! Invocation of Mun functions whose signature is only known at runtime, e.g. because they are
+//! chosen by the user of an editor or a console.
+//!
+//! Without a library like libffi, the only way to call a function with an arbitrary signature is
+//! to call it as a function with a fixed signature that occupies the same registers. On targets
+//! that assign integer and floating-point arguments to their own set of registers, a function is
+//! called as `extern "C" fn(u64, .., f64, ..)`: integer arguments are extended to 64 bits and moved
+//! to the integer registers, in order, and floating-point arguments are moved to the floating-point
+//! registers, in order. Arguments that do not fit in the registers are not supported.
+
+use crate::{
+    adt::RawStruct,
+    marshal::Marshal,
+    reflection::{equals_argument_type, ReturnTypeReflection},
+    InvokeError, Runtime, StructRef,
+};
+use std::fmt;
+
+/// A value of one of the types that can be passed to and returned from
+/// [`Runtime::invoke_dynamic`].
+///
+/// [`Runtime::invoke_dynamic`]: struct.Runtime.html#method.invoke_dynamic
+#[derive(Clone)]
+pub enum Value<'r> {
+    /// The value of a function without a return type.
+    Empty,
+    /// A `bool`.
+    Bool(bool),
+    /// An `i8`.
+    I8(i8),
+    /// An `i16`.
+    I16(i16),
+    /// An `i32`.
+    I32(i32),
+    /// An `i64`.
+    I64(i64),
+    /// A `u8`.
+    U8(u8),
+    /// A `u16`.
+    U16(u16),
+    /// A `u32`.
+    U32(u32),
+    /// A `u64`.
+    U64(u64),
+    /// An `f32`.
+    F32(f32),
+    /// An `f64`.
+    F64(f64),
+    /// A `Handle` to a value that is owned by the host.
+    Handle(abi::Handle),
+    /// A reference to a struct.
+    Struct(StructRef<'r>),
+}
+
+impl<'r> Value<'r> {
+    /// Returns the name of the type of the value.
+    pub fn type_name<'a>(&'a self, runtime: &'a Runtime) -> &'a str {
+        match self {
+            Value::Empty => <() as ReturnTypeReflection>::type_name(),
+            Value::Bool(_) => <bool as ReturnTypeReflection>::type_name(),
+            Value::I8(_) => <i8 as ReturnTypeReflection>::type_name(),
+            Value::I16(_) => <i16 as ReturnTypeReflection>::type_name(),
+            Value::I32(_) => <i32 as ReturnTypeReflection>::type_name(),
+            Value::I64(_) => <i64 as ReturnTypeReflection>::type_name(),
+            Value::U8(_) => <u8 as ReturnTypeReflection>::type_name(),
+            Value::U16(_) => <u16 as ReturnTypeReflection>::type_name(),
+            Value::U32(_) => <u32 as ReturnTypeReflection>::type_name(),
+            Value::U64(_) => <u64 as ReturnTypeReflection>::type_name(),
+            Value::F32(_) => <f32 as ReturnTypeReflection>::type_name(),
+            Value::F64(_) => <f64 as ReturnTypeReflection>::type_name(),
+            Value::Handle(_) => <abi::Handle as ReturnTypeReflection>::type_name(),
+            Value::Struct(s) => crate::ArgumentReflection::type_name(s, runtime),
+        }
+    }
+
+    /// Returns the `Guid` of the type of the value.
+    fn type_guid(&self, runtime: &Runtime) -> abi::Guid {
+        match self {
+            Value::Empty => <() as ReturnTypeReflection>::type_guid(),
+            Value::Bool(_) => <bool as ReturnTypeReflection>::type_guid(),
+            Value::I8(_) => <i8 as ReturnTypeReflection>::type_guid(),
+            Value::I16(_) => <i16 as ReturnTypeReflection>::type_guid(),
+            Value::I32(_) => <i32 as ReturnTypeReflection>::type_guid(),
+            Value::I64(_) => <i64 as ReturnTypeReflection>::type_guid(),
+            Value::U8(_) => <u8 as ReturnTypeReflection>::type_guid(),
+            Value::U16(_) => <u16 as ReturnTypeReflection>::type_guid(),
+            Value::U32(_) => <u32 as ReturnTypeReflection>::type_guid(),
+            Value::U64(_) => <u64 as ReturnTypeReflection>::type_guid(),
+            Value::F32(_) => <f32 as ReturnTypeReflection>::type_guid(),
+            Value::F64(_) => <f64 as ReturnTypeReflection>::type_guid(),
+            Value::Handle(_) => <abi::Handle as ReturnTypeReflection>::type_guid(),
+            Value::Struct(s) => crate::ArgumentReflection::type_guid(s, runtime),
+        }
+    }
+
+    /// Returns the register in which the value is passed as an argument.
+    fn to_register(&self) -> Register {
+        match self {
+            Value::Empty => Register::Int(0),
+            Value::Bool(v) => Register::Int(*v as u64),
+            Value::I8(v) => Register::Int(*v as i64 as u64),
+            Value::I16(v) => Register::Int(*v as i64 as u64),
+            Value::I32(v) => Register::Int(*v as i64 as u64),
+            Value::I64(v) => Register::Int(*v as u64),
+            Value::U8(v) => Register::Int(u64::from(*v)),
+            Value::U16(v) => Register::Int(u64::from(*v)),
+            Value::U32(v) => Register::Int(u64::from(*v)),
+            Value::U64(v) => Register::Int(*v),
+            Value::Handle(v) => Register::Int(v.0 as u64),
+            Value::Struct(s) => {
+                let ptr: memory::gc::RawGcPtr = s.clone().into_raw().0.into();
+                Register::Int(ptr as u64)
+            }
+            // The lower bits of a floating-point register hold a single-precision value
+            Value::F32(v) => Register::Float(f64::from_bits(u64::from(v.to_bits()))),
+            Value::F64(v) => Register::Float(*v),
+        }
+    }
+}
+
+impl<'r> fmt::Debug for Value<'r> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Empty => write!(f, "()"),
+            Value::Bool(v) => write!(f, "{:?}", v),
+            Value::I8(v) => write!(f, "{:?}", v),
+            Value::I16(v) => write!(f, "{:?}", v),
+            Value::I32(v) => write!(f, "{:?}", v),
+            Value::I64(v) => write!(f, "{:?}", v),
+            Value::U8(v) => write!(f, "{:?}", v),
+            Value::U16(v) => write!(f, "{:?}", v),
+            Value::U32(v) => write!(f, "{:?}", v),
+            Value::U64(v) => write!(f, "{:?}", v),
+            Value::F32(v) => write!(f, "{:?}", v),
+            Value::F64(v) => write!(f, "{:?}", v),
+            Value::Handle(v) => write!(f, "{:?}", v),
+            Value::Struct(s) => write!(f, "{} {{ .. }}", s.type_info().name()),
+        }
+    }
+}
+
+/// The class of register in which an argument is passed.
+enum Register {
+    Int(u64),
+    Float(f64),
+}
+
+/// The number of integer registers that are used to pass arguments. Both targets use eight
+/// floating-point registers.
+#[cfg(target_arch = "x86_64")]
+const NUM_INT_REGISTERS: usize = 6;
+#[cfg(target_arch = "aarch64")]
+const NUM_INT_REGISTERS: usize = 8;
+const NUM_FLOAT_REGISTERS: usize = 8;
+
+/// The signature with which every function is invoked. Mun functions unwind when they panic, so
+/// the signature uses the `C-unwind` ABI.
+type DynamicFn<R> = extern "C-unwind" fn(
+    u64,
+    u64,
+    u64,
+    u64,
+    u64,
+    u64,
+    u64,
+    u64,
+    f64,
+    f64,
+    f64,
+    f64,
+    f64,
+    f64,
+    f64,
+    f64,
+) -> R;
+
+/// Returns the value of the type `type_info` that is returned in the integer register `int` or
+/// the floating-point register `float`.
+fn value_from_registers<'r>(
+    runtime: &'r Runtime,
+    type_info: &abi::TypeInfo,
+    int: u64,
+    float: f64,
+) -> Result<Value<'r>, String> {
+    macro_rules! match_guid {
+        ($($ty:ty => $value:expr),+ $(,)?) => {
+            $(
+                if type_info.guid == <$ty as ReturnTypeReflection>::type_guid() {
+                    return Ok($value);
+                }
+            )+
+        };
+    }
+
+    if let abi::TypeGroup::StructTypes = type_info.group {
+        // Safety: a function that returns a struct returns a pointer to the heap-allocated struct
+        let ptr = int as memory::gc::RawGcPtr;
+        let raw = RawStruct(ptr.into());
+        return Ok(Value::Struct(StructRef::marshal_from(raw, runtime)));
+    }
+    match_guid!(
+        bool => Value::Bool(int as u8 != 0),
+        i8 => Value::I8(int as i8),
+        i16 => Value::I16(int as i16),
+        i32 => Value::I32(int as i32),
+        i64 => Value::I64(int as i64),
+        u8 => Value::U8(int as u8),
+        u16 => Value::U16(int as u16),
+        u32 => Value::U32(int as u32),
+        u64 => Value::U64(int),
+        abi::Handle => Value::Handle(abi::Handle(int as usize)),
+        f32 => Value::F32(f32::from_bits(float.to_bits() as u32)),
+        f64 => Value::F64(float),
+    );
+    Err(format!(
+        "The type `{}` is not supported by dynamic invocation.",
+        type_info.name()
+    ))
+}
+
+impl Runtime {
+    /// Invokes the function `function_name` with `args`, which are checked against the signature
+    /// of the function at runtime. Unlike [`invoke_fn!`], the types of the arguments and the
+    /// return value do not have to be known at compile time.
+    ///
+    /// Dynamic invocation is only supported on x86-64 and AArch64 targets, excluding Windows.
+    /// Functions whose arguments do not fit in the argument registers of the target, and 128-bit
+    /// integers, are not supported.
+    ///
+    /// [`invoke_fn!`]: macro.invoke_fn.html
+    pub fn invoke_dynamic<'r>(
+        &'r self,
+        function_name: &str,
+        args: &[Value<'r>],
+    ) -> Result<Value<'r>, InvokeError> {
+        let function_info = self.get_function_definition(function_name).ok_or_else(|| {
+            InvokeError::FunctionNotFound {
+                function_name: function_name.to_owned(),
+            }
+        })?;
+
+        // Validate function signature
+        let arg_types = function_info.prototype.signature.arg_types();
+        if arg_types.len() != args.len() {
+            return Err(InvokeError::SignatureMismatch {
+                function_name: function_name.to_owned(),
+                message: format!(
+                    "Invalid number of arguments. Expected: {}. Found: {}.",
+                    arg_types.len(),
+                    args.len(),
+                ),
+            });
+        }
+
+        // Eight integer arguments are passed, of which the ones that do not fit in the registers
+        // of the target are ignored by the function.
+        let mut int_registers = [0u64; 8];
+        let mut float_registers = [0f64; NUM_FLOAT_REGISTERS];
+        let (mut num_ints, mut num_floats) = (0, 0);
+        for (idx, (arg, arg_type)) in args.iter().zip(arg_types.iter()).enumerate() {
+            let is_valid = match arg {
+                Value::Struct(s) => equals_argument_type(self, arg_type, s).is_ok(),
+                Value::Empty => false,
+                _ => arg.type_guid(self) == arg_type.guid,
+            };
+            if !is_valid {
+                return Err(InvokeError::SignatureMismatch {
+                    function_name: function_name.to_owned(),
+                    message: format!(
+                        "Invalid argument type at index {}. Expected: {}. Found: {}.",
+                        idx,
+                        arg_type.name(),
+                        arg.type_name(self),
+                    ),
+                });
+            }
+
+            match arg.to_register() {
+                Register::Int(value) if num_ints < NUM_INT_REGISTERS => {
+                    int_registers[num_ints] = value;
+                    num_ints += 1;
+                }
+                Register::Float(value) if num_floats < float_registers.len() => {
+                    float_registers[num_floats] = value;
+                    num_floats += 1;
+                }
+                _ => {
+                    return Err(InvokeError::UnsupportedSignature {
+                        function_name: function_name.to_owned(),
+                        message: format!(
+                            "Too many arguments to invoke function '{}' dynamically.",
+                            function_name
+                        ),
+                    })
+                }
+            }
+        }
+
+        let return_type = function_info.prototype.signature.return_type();
+        let returns_float = return_type.map_or(false, |ty| {
+            ty.guid == <f32 as ReturnTypeReflection>::type_guid()
+                || ty.guid == <f64 as ReturnTypeReflection>::type_guid()
+        });
+
+        let i = int_registers;
+        let f = float_registers;
+        let fn_ptr = function_info.fn_ptr;
+
+        // A Mun function unwinds if it traps (e.g. on a division by zero)
+        let _invocation = self.invocation_lock.read_recursive();
+        let _stack_guard = crate::stack::StackGuard::enter(self.max_stack_size);
+        let _interrupt_guard = crate::interrupt::InterruptGuard::enter(&self.invoke_limits);
+        let result = std::panic::catch_unwind(|| unsafe {
+            // Safety: the arguments have been validated against the signature of the function
+            // and are passed in the registers in which the function expects them.
+            if returns_float {
+                let function: DynamicFn<f64> = std::mem::transmute(fn_ptr);
+                let float = function(
+                    i[0], i[1], i[2], i[3], i[4], i[5], i[6], i[7], f[0], f[1], f[2], f[3], f[4],
+                    f[5], f[6], f[7],
+                );
+                (0, float)
+            } else {
+                let function: DynamicFn<u64> = std::mem::transmute(fn_ptr);
+                let int = function(
+                    i[0], i[1], i[2], i[3], i[4], i[5], i[6], i[7], f[0], f[1], f[2], f[3], f[4],
+                    f[5], f[6], f[7],
+                );
+                (int, 0.0)
+            }
+        });
+
+        match result {
+            Ok((int, float)) => {
+                match return_type {
+                    Some(return_type) => value_from_registers(self, return_type, int, float)
+                        .map_err(|message| InvokeError::UnsupportedSignature {
+                            function_name: function_name.to_owned(),
+                            message,
+                        }),
+                    None => Ok(Value::Empty),
+                }
+            }
+            Err(payload) => Err(InvokeError::from_unwind(function_name, payload)),
+        }
+    }
+}
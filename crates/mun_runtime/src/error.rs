@@ -0,0 +1,103 @@
+use crate::panic::{panic_message, Panic};
+use std::{any::Any, fmt};
+
+/// Describes why the invocation of a Mun function failed, so the host can handle each case
+/// differently, e.g. by reporting the source location of a panic in an editor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InvokeError {
+    /// The function does not exist in the loaded assemblies.
+    FunctionNotFound {
+        /// The name of the function
+        function_name: String,
+    },
+    /// The number or types of the arguments, or the return type, do not match the signature of
+    /// the function.
+    SignatureMismatch {
+        /// The name of the function
+        function_name: String,
+        /// Describes the mismatch
+        message: String,
+    },
+    /// The signature of the function cannot be invoked, e.g. because dynamic invocation does not
+    /// support one of its types.
+    UnsupportedSignature {
+        /// The name of the function
+        function_name: String,
+        /// Describes why the signature is not supported
+        message: String,
+    },
+    /// The function panicked, e.g. because it called `core::panic()`, an assertion failed, or an
+    /// integer was divided by zero.
+    Panic {
+        /// The name of the function
+        function_name: String,
+        /// The message and source location of the panic
+        panic: Panic,
+    },
+    /// The invocation was aborted by the runtime, e.g. because it ran out of memory or was
+    /// interrupted by the host.
+    Aborted {
+        /// The name of the function
+        function_name: String,
+        /// Describes why the invocation was aborted
+        message: String,
+    },
+}
+
+impl InvokeError {
+    /// Constructs the error for a function that unwound with `payload`.
+    pub(crate) fn from_unwind(function_name: &str, payload: Box<dyn Any + Send>) -> Self {
+        let function_name = function_name.to_owned();
+        match payload.downcast::<Panic>() {
+            Ok(panic) => InvokeError::Panic {
+                function_name,
+                panic: *panic,
+            },
+            Err(payload) => InvokeError::Aborted {
+                function_name,
+                message: panic_message(payload.as_ref()),
+            },
+        }
+    }
+
+    /// Returns the name of the function of which the invocation failed.
+    pub fn function_name(&self) -> &str {
+        match self {
+            InvokeError::FunctionNotFound { function_name }
+            | InvokeError::SignatureMismatch { function_name, .. }
+            | InvokeError::UnsupportedSignature { function_name, .. }
+            | InvokeError::Panic { function_name, .. }
+            | InvokeError::Aborted { function_name, .. } => function_name,
+        }
+    }
+
+    /// Returns the `Panic` that aborted the invocation, if the function panicked.
+    pub fn panic(&self) -> Option<&Panic> {
+        match self {
+            InvokeError::Panic { panic, .. } => Some(panic),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for InvokeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvokeError::FunctionNotFound { function_name } => {
+                write!(f, "Failed to obtain function '{}'", function_name)
+            }
+            InvokeError::SignatureMismatch { message, .. }
+            | InvokeError::UnsupportedSignature { message, .. } => write!(f, "{}", message),
+            InvokeError::Panic {
+                function_name,
+                panic,
+            } => write!(f, "Function '{}' trapped: {}", function_name, panic),
+            InvokeError::Aborted {
+                function_name,
+                message,
+            } => write!(f, "Function '{}' trapped: {}", function_name, message),
+        }
+    }
+}
+
+impl std::error::Error for InvokeError {}
@@ -1,5 +1,6 @@
+use crate::allocation::AllocationTracker;
 use memory::gc::{self, HasIndirectionPtr};
-use std::{alloc::Layout, hash::Hash, ptr::NonNull};
+use std::{alloc::Layout, collections::BTreeMap, hash::Hash, ptr::NonNull, time::Duration};
 
 /// `UnsafeTypeInfo` is a type that wraps a `NonNull<TypeInfo>` and indicates unsafe interior
 /// operations on the wrapped `TypeInfo`. The unsafety originates from uncertainty about the
@@ -78,6 +79,13 @@ impl memory::TypeFields<UnsafeTypeInfo> for UnsafeTypeInfo {
             &[]
         }
     }
+    fn fields_renamed_from(&self) -> Vec<Option<&str>> {
+        if let Some(s) = unsafe { self.0.as_ref().as_struct() } {
+            s.field_renamed_from().collect()
+        } else {
+            Vec::new()
+        }
+    }
 }
 
 unsafe impl Send for UnsafeTypeInfo {}
@@ -143,7 +151,27 @@ impl gc::TypeTrace for UnsafeTypeInfo {
 }
 
 /// Defines the garbage collector used by the `Runtime`.
-pub type GarbageCollector = gc::MarkSweep<UnsafeTypeInfo, gc::NoopObserver<gc::Event>>;
+pub type GarbageCollector = gc::MarkSweep<UnsafeTypeInfo, AllocationTracker>;
+
+/// Statistics about the memory that is managed by the garbage collector of a `Runtime`.
+#[derive(Clone, Debug, Default)]
+pub struct GcStats {
+    /// The number of bytes that are allocated for objects.
+    pub allocated_memory: usize,
+    /// The number of bytes that were allocated since the last completed collection.
+    pub allocated_since_collection: usize,
+    /// The duration of the last collection, or of the last step of an incremental collection.
+    pub last_pause: Duration,
+    /// The number of live objects per type name.
+    pub objects_per_type: BTreeMap<String, usize>,
+}
+
+impl GcStats {
+    /// Returns the total number of live objects.
+    pub fn live_objects(&self) -> usize {
+        self.objects_per_type.values().sum()
+    }
+}
 
 pub use gc::GcPtr;
 pub type GcRootPtr = gc::GcRootPtr<UnsafeTypeInfo, GarbageCollector>;
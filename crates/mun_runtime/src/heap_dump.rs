@@ -0,0 +1,206 @@
+use crate::{garbage_collector::GcPtr, Runtime};
+use memory::gc::{HasIndirectionPtr, RawGcPtr};
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+/// The number of values per node in the `nodes` array of a heap snapshot.
+const NODE_FIELD_COUNT: usize = 6;
+
+/// The index of the `object` node type and the `synthetic` node type in the `node_types` of a heap
+/// snapshot.
+const NODE_TYPE_OBJECT: usize = 3;
+const NODE_TYPE_SYNTHETIC: usize = 9;
+
+/// The index of the `element` edge type and the `property` edge type in the `edge_types` of a heap
+/// snapshot.
+const EDGE_TYPE_ELEMENT: usize = 1;
+const EDGE_TYPE_PROPERTY: usize = 2;
+
+/// The metadata of a heap snapshot, which describes the layout of its nodes and edges.
+const META: &str = r#"{"node_fields":["type","name","id","self_size","edge_count","trace_node_id"],"node_types":[["hidden","array","string","object","code","closure","regexp","number","native","synthetic","concatenated string","sliced string"],"string","number","number","number","number"],"edge_fields":["type","name_or_index","to_node"],"edge_types":[["context","element","property","internal","hidden","shortcut","weak"],"string_or_number","node"]}"#;
+
+/// Writes a graph of all objects of the garbage collector of `runtime` to `writer`, in the JSON
+/// format of a heap snapshot of the Chrome DevTools.
+///
+/// Every object is a node of which the name is the name of its type and the size is the size of
+/// its type. The references of its fields to other objects are edges that are named after the
+/// fields, where the fields of value structs are separated by a dot. The first node is a synthetic
+/// node, called `(GC roots)`, which references all rooted objects.
+pub(crate) fn write<W: Write>(runtime: &Runtime, mut writer: W) -> io::Result<()> {
+    let objects = runtime.gc.objects();
+    let node_indices: HashMap<GcPtr, usize> = objects
+        .iter()
+        .enumerate()
+        .map(|(index, (handle, _, _))| (*handle, index + 1))
+        .collect();
+
+    let mut dump = Dump::default();
+    let roots_name = dump.string("(GC roots)");
+    let root_edges = objects
+        .iter()
+        .filter(|(_, _, roots)| *roots > 0)
+        .enumerate()
+        .map(|(index, (handle, _, _))| [EDGE_TYPE_ELEMENT, index, node_indices[handle]])
+        .collect::<Vec<_>>();
+    dump.node(NODE_TYPE_SYNTHETIC, roots_name, 0, root_edges.len());
+    dump.edges.extend(root_edges);
+
+    for (handle, ty, _) in objects.iter() {
+        // Safety: The type of an object lives at least as long as the `Runtime`.
+        let ty = unsafe { ty.into_inner().as_ref() };
+        let edge_count = dump.edges.len();
+        // Safety: No memory is collected while the heap is dumped, so the object is alive.
+        unsafe {
+            dump.references(ty, handle.deref::<u8>(), "", &node_indices);
+        }
+        let name = dump.string(ty.name());
+        dump.node(
+            NODE_TYPE_OBJECT,
+            name,
+            ty.size_in_bytes(),
+            dump.edges.len() - edge_count,
+        );
+    }
+
+    dump.write(&mut writer)
+}
+
+/// The nodes, edges and strings of a heap snapshot.
+#[derive(Default)]
+struct Dump {
+    nodes: Vec<[usize; NODE_FIELD_COUNT]>,
+    /// The type, name or index, and the index of the node that it refers to of every edge
+    edges: Vec<[usize; 3]>,
+    strings: Vec<String>,
+    string_indices: HashMap<String, usize>,
+}
+
+impl Dump {
+    /// Returns the index of `s` in the strings of the snapshot, adding it if it was not
+    /// encountered before.
+    fn string(&mut self, s: &str) -> usize {
+        if let Some(index) = self.string_indices.get(s) {
+            return *index;
+        }
+        self.strings.push(s.to_string());
+        self.string_indices
+            .insert(s.to_string(), self.strings.len() - 1);
+        self.strings.len() - 1
+    }
+
+    /// Adds a node, of which the `edge_count` edges are the last edges that were added.
+    fn node(&mut self, ty: usize, name: usize, size: usize, edge_count: usize) {
+        let id = self.nodes.len() + 1;
+        self.nodes.push([ty, name, id, size, edge_count, 0]);
+    }
+
+    /// Adds an edge for every reference of the value of type `ty` at `ptr` to another object,
+    /// which is named after the field of the reference prefixed with `path`.
+    unsafe fn references(
+        &mut self,
+        ty: &abi::TypeInfo,
+        ptr: *const u8,
+        path: &str,
+        node_indices: &HashMap<GcPtr, usize>,
+    ) {
+        let struct_info = match ty.as_struct() {
+            Some(struct_info) => struct_info,
+            None => return,
+        };
+
+        let fields = struct_info
+            .field_names()
+            .zip(struct_info.field_types().iter())
+            .zip(struct_info.field_offsets().iter());
+        for ((field_name, field_ty), offset) in fields {
+            let field_path = if path.is_empty() {
+                field_name.to_string()
+            } else {
+                format!("{}.{}", path, field_name)
+            };
+
+            let field_ptr = ptr.add(*offset as usize);
+            let is_gc_struct = field_ty
+                .as_struct()
+                .map_or(false, |s| s.memory_kind == abi::StructMemoryKind::GC);
+            if is_gc_struct {
+                let field = *field_ptr.cast::<GcPtr>();
+                let raw: RawGcPtr = field.into();
+                if raw.is_null() {
+                    continue;
+                }
+                if let Some(node_index) = node_indices.get(&field) {
+                    let name = self.string(&field_path);
+                    self.edges.push([EDGE_TYPE_PROPERTY, name, *node_index]);
+                }
+            } else {
+                self.references(field_ty, field_ptr, &field_path, node_indices);
+            }
+        }
+    }
+
+    /// Writes the snapshot as JSON.
+    fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(
+            writer,
+            r#"{{"snapshot":{{"meta":{},"node_count":{},"edge_count":{},"trace_function_count":0}},"#,
+            META,
+            self.nodes.len(),
+            self.edges.len()
+        )?;
+
+        write!(writer, r#""nodes":["#)?;
+        write_values(writer, self.nodes.iter().flat_map(|node| node.iter()))?;
+        write!(writer, r#"],"edges":["#)?;
+        // Edges refer to nodes by the index of their first value in `nodes`
+        let edges: Vec<_> = self
+            .edges
+            .iter()
+            .map(|[ty, name_or_index, node_index]| {
+                [*ty, *name_or_index, node_index * NODE_FIELD_COUNT]
+            })
+            .collect();
+        write_values(writer, edges.iter().flat_map(|edge| edge.iter()))?;
+        write!(
+            writer,
+            r#"],"trace_function_infos":[],"trace_tree":[],"samples":[],"locations":[],"strings":["#
+        )?;
+        for (index, s) in self.strings.iter().enumerate() {
+            if index > 0 {
+                write!(writer, ",")?;
+            }
+            write_string(writer, s)?;
+        }
+        writeln!(writer, "]}}")
+    }
+}
+
+/// Writes the comma-separated `values`.
+fn write_values<'a, W: Write, I: Iterator<Item = &'a usize>>(
+    writer: &mut W,
+    values: I,
+) -> io::Result<()> {
+    for (index, value) in values.enumerate() {
+        if index > 0 {
+            write!(writer, ",")?;
+        }
+        write!(writer, "{}", value)?;
+    }
+    Ok(())
+}
+
+/// Writes `s` as a JSON string.
+fn write_string<W: Write>(writer: &mut W, s: &str) -> io::Result<()> {
+    write!(writer, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(writer, "\\\"")?,
+            '\\' => write!(writer, "\\\\")?,
+            c if (c as u32) < 0x20 => write!(writer, "\\u{:04x}", c as u32)?,
+            c => write!(writer, "{}", c)?,
+        }
+    }
+    write!(writer, "\"")
+}
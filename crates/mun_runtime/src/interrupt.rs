@@ -0,0 +1,218 @@
+use std::{
+    cell::{Cell, RefCell},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// The number of loop iterations between two checks of whether an invocation exceeded its budget.
+const CHECK_INTERVAL: u64 = 1024;
+
+thread_local! {
+    /// The number of loop iterations that Mun code on this thread may execute before its budget is
+    /// checked.
+    static COUNTER: Cell<i64> = Cell::new(i64::MAX);
+
+    /// The budget of the outermost invocation of a Mun function on this thread, if any.
+    static BUDGET: RefCell<Option<Budget>> = RefCell::new(None);
+}
+
+/// A handle that interrupts the invocations of Mun functions of a `Runtime`, e.g. from a watchdog
+/// thread. See [`Runtime::interrupt_handle`].
+///
+/// [`Runtime::interrupt_handle`]: struct.Runtime.html#method.interrupt_handle
+#[derive(Clone, Debug, Default)]
+pub struct InterruptHandle {
+    /// The number of times that the host interrupted the invocations
+    interrupts: Arc<AtomicU64>,
+}
+
+impl InterruptHandle {
+    /// Aborts all running invocations of Mun functions at their next loop iteration. Invocations
+    /// that start afterwards are not affected.
+    pub fn interrupt(&self) {
+        self.interrupts.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+/// The limits of the execution of an invocation of a Mun function.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct InvokeLimits {
+    pub max_duration: Option<Duration>,
+    pub max_loop_iterations: Option<u64>,
+    pub interrupt_handle: InterruptHandle,
+}
+
+/// The remaining budget of an invocation.
+struct Budget {
+    deadline: Option<Instant>,
+    remaining_iterations: Option<u64>,
+    /// The number of iterations that were granted by the last refill of the counter
+    granted_iterations: u64,
+    interrupts: Arc<AtomicU64>,
+    /// The number of interrupts when the invocation started
+    start_interrupts: u64,
+}
+
+impl Budget {
+    /// Grants the next batch of iterations, returning the value of the counter.
+    fn grant(&mut self) -> i64 {
+        self.granted_iterations = self
+            .remaining_iterations
+            .map_or(CHECK_INTERVAL, |remaining| remaining.min(CHECK_INTERVAL));
+        self.granted_iterations as i64
+    }
+}
+
+/// The `interrupt_counter` intrinsic that is called by Mun code before entering a loop. Every
+/// iteration of the loop decrements the returned counter and calls `interrupt_check` when it
+/// becomes negative.
+pub(crate) extern "C" fn interrupt_counter() -> *mut i64 {
+    COUNTER.with(|counter| counter.as_ptr())
+}
+
+/// The `interrupt_check` intrinsic that is called by Mun code when its loop iteration counter runs
+/// out. It unwinds the stack up to the invocation of the Mun function if the budget of the
+/// invocation is exceeded, or if it was interrupted by the host.
+pub(crate) extern "C-unwind" fn interrupt_check() {
+    let error = BUDGET.with(|budget| {
+        let mut budget = budget.borrow_mut();
+        let budget = match budget.as_mut() {
+            Some(budget) => budget,
+            None => {
+                COUNTER.with(|counter| counter.set(i64::MAX));
+                return None;
+            }
+        };
+
+        if budget.interrupts.load(Ordering::Acquire) != budget.start_interrupts {
+            return Some("execution interrupted by the host");
+        }
+        if budget
+            .deadline
+            .map_or(false, |deadline| Instant::now() >= deadline)
+        {
+            return Some("execution interrupted: the invocation exceeded its time budget");
+        }
+        if let Some(remaining) = budget.remaining_iterations.as_mut() {
+            *remaining -= budget.granted_iterations;
+            if *remaining == 0 {
+                return Some("execution interrupted: the invocation exceeded its iteration budget");
+            }
+        }
+
+        // The iteration that called this function is part of the next batch
+        let value = budget.grant() - 1;
+        COUNTER.with(|counter| counter.set(value));
+        None
+    });
+
+    if let Some(message) = error {
+        std::panic::resume_unwind(Box::new(message.to_owned()));
+    }
+}
+
+/// Limits the execution of Mun code for as long as the guard is alive. Only the outermost
+/// invocation of a Mun function sets the budget, so invocations from within Mun code, e.g. by an
+/// extern function, share the budget of the outer invocation.
+pub(crate) struct InterruptGuard {
+    is_outermost: bool,
+}
+
+impl InterruptGuard {
+    /// Starts the budget of an invocation that is limited by `limits`.
+    pub fn enter(limits: &InvokeLimits) -> Self {
+        let is_outermost = BUDGET.with(|budget| {
+            let mut budget = budget.borrow_mut();
+            if budget.is_some() {
+                return false;
+            }
+
+            // Only invocations that are running while the host interrupts them are aborted
+            let interrupts = limits.interrupt_handle.interrupts.clone();
+            let start_interrupts = interrupts.load(Ordering::Acquire);
+
+            let mut new_budget = Budget {
+                deadline: limits
+                    .max_duration
+                    .map(|max_duration| Instant::now() + max_duration),
+                remaining_iterations: limits.max_loop_iterations,
+                granted_iterations: 0,
+                interrupts,
+                start_interrupts,
+            };
+            let value = new_budget.grant();
+            COUNTER.with(|counter| counter.set(value));
+            *budget = Some(new_budget);
+            true
+        });
+        InterruptGuard { is_outermost }
+    }
+}
+
+impl Drop for InterruptGuard {
+    fn drop(&mut self) {
+        if self.is_outermost {
+            BUDGET.with(|budget| *budget.borrow_mut() = None);
+            COUNTER.with(|counter| counter.set(i64::MAX));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{interrupt_check, InterruptGuard, InvokeLimits};
+    use crate::InvokeError;
+
+    /// Calls the `interrupt_check` intrinsic the way Mun code does, returning the error of the
+    /// invocation if it is interrupted.
+    fn check() -> Result<(), InvokeError> {
+        let check_fn: extern "C-unwind" fn() = interrupt_check;
+        std::panic::catch_unwind(|| check_fn())
+            .map_err(|payload| InvokeError::from_unwind("main", payload))
+    }
+
+    #[test]
+    fn interrupted_invocation_returns_error() {
+        let limits = InvokeLimits::default();
+        let _guard = InterruptGuard::enter(&limits);
+        assert_eq!(check(), Ok(()));
+
+        limits.interrupt_handle.interrupt();
+        assert_eq!(
+            check(),
+            Err(InvokeError::Aborted {
+                function_name: "main".to_owned(),
+                message: "execution interrupted by the host".to_owned(),
+            })
+        );
+    }
+
+    #[test]
+    fn exceeded_iteration_budget_returns_error() {
+        let limits = InvokeLimits {
+            max_loop_iterations: Some(2048),
+            ..InvokeLimits::default()
+        };
+        let _guard = InterruptGuard::enter(&limits);
+        assert_eq!(check(), Ok(()));
+        assert_eq!(
+            check(),
+            Err(InvokeError::Aborted {
+                function_name: "main".to_owned(),
+                message: "execution interrupted: the invocation exceeded its iteration budget"
+                    .to_owned(),
+            })
+        );
+    }
+
+    #[test]
+    fn interrupt_before_invocation_is_ignored() {
+        let limits = InvokeLimits::default();
+        limits.interrupt_handle.interrupt();
+        let _guard = InterruptGuard::enter(&limits);
+        assert_eq!(check(), Ok(()));
+    }
+}
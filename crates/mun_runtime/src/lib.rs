@@ -2,6 +2,10 @@
 //!
 //! The Mun Runtime provides functionality for automatically hot reloading Mun C ABI
 //! compliant shared libraries.
+//!
+//! On `wasm32` there are no shared libraries, so only a subset of the runtime is available: a
+//! munlib that is linked into the executable is loaded with [`RuntimeBuilder::from_static`] and
+//! cannot be hot reloaded.
 #![warn(missing_docs)]
 
 mod assembly;
@@ -10,46 +14,134 @@ mod macros;
 #[macro_use]
 mod garbage_collector;
 mod adt;
+mod allocation;
+mod heap_dump;
+mod interrupt;
+// Dynamic invocation relies on the calling convention of the target
+#[cfg(all(
+    any(target_arch = "x86_64", target_arch = "aarch64"),
+    not(target_os = "windows")
+))]
+mod dynamic;
+mod error;
 mod marshal;
+#[cfg(not(target_arch = "wasm32"))]
+mod migration;
+mod panic;
 mod reflection;
+#[cfg(not(target_arch = "wasm32"))]
+mod reload;
+mod snapshot;
+mod stack;
+mod value_struct;
 
+use allocation::AllocationTracker;
 use anyhow::Error;
-use garbage_collector::GarbageCollector;
-use memory::gc::{self, GcRuntime};
+use interrupt::InvokeLimits;
+use memory::gc::GcRuntime;
+#[cfg(not(target_arch = "wasm32"))]
+use memory::mapping::Migrations;
+use memory::TypeDesc;
+#[cfg(not(target_arch = "wasm32"))]
 use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+#[cfg(not(target_arch = "wasm32"))]
+use parking_lot::Mutex;
+use parking_lot::RwLock;
+#[cfg(not(target_arch = "wasm32"))]
+use reload::{PendingReload, ReloadHooks};
 use rustc_hash::FxHashMap;
 use std::{
     cell::RefCell,
-    collections::HashMap,
-    ffi, io, mem,
+    collections::{BTreeMap, HashMap, HashSet},
+    ffi, mem,
     path::{Path, PathBuf},
     ptr::NonNull,
     rc::Rc,
     string::ToString,
-    sync::{
-        mpsc::{channel, Receiver},
-        Arc,
-    },
+    sync::Arc,
     time::Duration,
 };
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    any::Any,
+    io,
+    sync::mpsc::{channel, Receiver, TryRecvError},
+};
 
+#[cfg(all(
+    any(target_arch = "x86_64", target_arch = "aarch64"),
+    not(target_os = "windows")
+))]
+pub use crate::dynamic::Value;
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::migration::StructMigration;
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::reload::ReloadChanges;
 pub use crate::{
-    adt::{RootedStruct, StructRef},
-    assembly::Assembly,
-    garbage_collector::UnsafeTypeInfo,
+    adt::{MunStruct, PinnedStruct, RawStruct, RootedStruct, StructRef, WeakStructRef},
+    allocation::AllocationEvent,
+    assembly::{Assembly, StaticAssembly},
+    error::InvokeError,
+    garbage_collector::{GarbageCollector, GcStats, UnsafeTypeInfo},
+    interrupt::InterruptHandle,
     marshal::Marshal,
+    panic::Panic,
     reflection::{ArgumentReflection, ReturnTypeReflection},
+    stack::DEFAULT_MAX_STACK_SIZE,
+    value_struct::ValueStruct,
 };
-pub use abi::IntoFunctionDefinition;
+pub use abi::{
+    AssemblyInfo, ClosureConflict, FieldInfo, FunctionDefinition, FunctionDefinitionStorage,
+    FunctionPrototype, Guid, Handle, IntoClosureDefinition, IntoFunctionDefinition, StructInfo,
+    TypeInfo,
+};
+pub use memory::gc::{Allocator, SystemAllocator};
+pub use mun_runtime_derive::MunStruct;
 
 /// Options for the construction of a [`Runtime`].
 pub struct RuntimeOptions {
     /// Path to the entry point library
     pub library_path: PathBuf,
+    /// Paths to libraries that are loaded alongside the entry point library. The assemblies can
+    /// call the public functions of each other, which are declared as `extern` functions, and are
+    /// hot reloaded independently.
+    pub additional_library_paths: Vec<PathBuf>,
+    /// Directories that are watched for new libraries with the `munlib` extension, which are
+    /// loaded by [`Runtime::update`] alongside the other assemblies.
+    pub watch_directories: Vec<PathBuf>,
+    /// The munlib that is linked into the executable. If specified, no library is loaded and
+    /// `library_path` only identifies the assembly.
+    pub static_assembly: Option<StaticAssembly>,
     /// Delay during which filesystem events are collected, deduplicated, and after which emitted.
     pub delay: Duration,
     /// Custom user injected functions
     pub user_functions: Vec<(abi::FunctionDefinition, abi::FunctionDefinitionStorage)>,
+    /// An optional `tracing` dispatcher that receives the spans and events emitted by the runtime.
+    /// If not specified, the globally set default dispatcher is used.
+    pub tracing_dispatch: Option<tracing::Dispatch>,
+    /// The number of bytes of stack that an invocation of a Mun function may use. A recursive
+    /// function that exceeds it panics with a stack overflow.
+    pub max_stack_size: usize,
+    /// The allocator through which the garbage collector allocates the memory of Mun objects.
+    pub allocator: Arc<dyn Allocator>,
+    /// The number of bytes that can be allocated after a collection, before garbage is collected
+    /// automatically by [`Runtime::update`]. If not specified, garbage is only collected manually.
+    pub gc_threshold: Option<usize>,
+    /// The maximum number of bytes that Mun code can allocate for objects. An allocation that
+    /// exceeds it aborts the invocation of the Mun function with an error. If not specified, the
+    /// memory is unlimited.
+    pub max_heap_size: Option<usize>,
+    /// The maximum duration of an invocation of a Mun function. An invocation that exceeds it is
+    /// aborted with an error at the next iteration of a loop. If not specified, the duration is
+    /// unlimited.
+    pub max_invoke_duration: Option<Duration>,
+    /// The maximum number of loop iterations that an invocation of a Mun function may execute. An
+    /// invocation that exceeds it is aborted with an error. If not specified, the number of
+    /// iterations is unlimited.
+    pub max_loop_iterations: Option<u64>,
+    /// Whether the allocation hooks are invoked for every allocation and deallocation of an
+    /// object, see [`Runtime::on_allocation`].
+    pub track_allocations: bool,
 }
 
 /// A builder for the [`Runtime`].
@@ -63,18 +155,122 @@ impl RuntimeBuilder {
         Self {
             options: RuntimeOptions {
                 library_path: library_path.into(),
+                additional_library_paths: Vec::new(),
+                watch_directories: Vec::new(),
+                static_assembly: None,
                 delay: Duration::from_millis(10),
                 user_functions: Default::default(),
+                tracing_dispatch: None,
+                max_stack_size: DEFAULT_MAX_STACK_SIZE,
+                allocator: Arc::new(SystemAllocator),
+                gc_threshold: None,
+                max_heap_size: None,
+                max_invoke_duration: None,
+                max_loop_iterations: None,
+                track_allocations: false,
             },
         }
     }
 
+    /// Constructs a new `RuntimeBuilder` for a munlib that is linked into the executable, see
+    /// [`static_assembly!`]. This is the only way to load Mun code on targets without shared
+    /// libraries, like `wasm32`. A statically linked munlib cannot be hot reloaded.
+    pub fn from_static(assembly: StaticAssembly) -> Self {
+        let mut builder = Self::new("main.munlib");
+        builder.options.static_assembly = Some(assembly);
+        builder
+    }
+
+    /// Adds the shared library at `library_path`, which is loaded alongside the entry point
+    /// library. The assemblies can call the public functions of each other.
+    pub fn add_library<P: Into<PathBuf>>(mut self, library_path: P) -> Self {
+        self.options
+            .additional_library_paths
+            .push(library_path.into());
+        self
+    }
+
+    /// Adds all shared libraries with the `munlib` extension in the directory at `dir`, see
+    /// [`RuntimeBuilder::add_library`].
+    pub fn add_directory<P: AsRef<Path>>(mut self, dir: P) -> Result<Self, std::io::Error> {
+        let mut library_paths = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(false, |ext| ext == "munlib") {
+                library_paths.push(path);
+            }
+        }
+        // Load the libraries in a deterministic order
+        library_paths.sort();
+        self.options.additional_library_paths.extend(library_paths);
+        Ok(self)
+    }
+
+    /// Watches the directory at `dir` for new libraries with the `munlib` extension, which are
+    /// loaded automatically by [`Runtime::update`]. Changed libraries in the directory are hot
+    /// reloaded like all other assemblies.
+    pub fn watch_directory<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.options.watch_directories.push(dir.into());
+        self
+    }
+
     /// Sets the `delay`.
     pub fn set_delay(mut self, delay: Duration) -> Self {
         self.options.delay = delay;
         self
     }
 
+    /// Sets the number of bytes of stack that an invocation of a Mun function may use.
+    pub fn set_max_stack_size(mut self, max_stack_size: usize) -> Self {
+        self.options.max_stack_size = max_stack_size;
+        self
+    }
+
+    /// Sets the allocator through which the garbage collector allocates the memory of Mun
+    /// objects. This enables routing the memory of scripts through a custom heap, e.g. to track
+    /// it or to enforce a memory budget.
+    pub fn set_allocator<A: Allocator + 'static>(mut self, allocator: A) -> Self {
+        self.options.allocator = Arc::new(allocator);
+        self
+    }
+
+    /// Sets the number of bytes that can be allocated after a collection, before garbage is
+    /// collected automatically by [`Runtime::update`].
+    pub fn set_gc_threshold(mut self, gc_threshold: usize) -> Self {
+        self.options.gc_threshold = Some(gc_threshold);
+        self
+    }
+
+    /// Sets the maximum number of bytes that Mun code can allocate for objects, so a buggy script
+    /// cannot exhaust the memory of the host. An allocation that exceeds it aborts the invocation
+    /// of the Mun function with an error.
+    pub fn set_max_heap_size(mut self, max_heap_size: usize) -> Self {
+        self.options.max_heap_size = Some(max_heap_size);
+        self
+    }
+
+    /// Sets the maximum duration of an invocation of a Mun function, so an accidental infinite loop
+    /// cannot hang the host. An invocation that exceeds it is aborted with an error.
+    pub fn set_max_invoke_duration(mut self, max_invoke_duration: Duration) -> Self {
+        self.options.max_invoke_duration = Some(max_invoke_duration);
+        self
+    }
+
+    /// Sets the maximum number of loop iterations that an invocation of a Mun function may execute.
+    /// An invocation that exceeds it is aborted with an error. As opposed to a maximum duration,
+    /// this is deterministic.
+    pub fn set_max_loop_iterations(mut self, max_loop_iterations: u64) -> Self {
+        self.options.max_loop_iterations = Some(max_loop_iterations);
+        self
+    }
+
+    /// Enables or disables the invocation of the allocation hooks, see
+    /// [`Runtime::on_allocation`]. Tracking is disabled by default, as it slows down allocations.
+    pub fn set_allocation_tracking(mut self, enabled: bool) -> Self {
+        self.options.track_allocations = enabled;
+        self
+    }
+
     /// Adds a custom user function to the dispatch table.
     pub fn insert_fn<S: AsRef<str>, F: abi::IntoFunctionDefinition>(
         mut self,
@@ -85,9 +281,43 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Adds a custom user function to the dispatch table that invokes a Rust closure, e.g. one that
+    /// captures state of the host application.
+    ///
+    /// Only one closure per closure type can be registered at a time, so registering the same
+    /// closure expression again - even for another runtime - fails while the previously registered
+    /// closure is alive.
+    pub fn insert_closure<S: AsRef<str>, M, F: IntoClosureDefinition<M>>(
+        mut self,
+        name: S,
+        closure: F,
+    ) -> Result<Self, abi::ClosureConflict> {
+        self.options.user_functions.push(closure.into(name)?);
+        Ok(self)
+    }
+
+    /// Sets a `tracing` subscriber that receives the spans and events emitted by the runtime, e.g.
+    /// when loading or reloading assemblies. This enables embedders to pipe timings and reload
+    /// events into their own profiling or telemetry systems.
+    pub fn with_tracing_subscriber<S>(mut self, subscriber: S) -> Self
+    where
+        S: tracing::Subscriber + Send + Sync + 'static,
+    {
+        self.options.tracing_dispatch = Some(tracing::Dispatch::new(subscriber));
+        self
+    }
+
+    /// Constructs a [`Runtime`] with the builder's options. The `Runtime` is `Send` and `Sync`, so
+    /// it can be shared across threads, e.g. in an `Arc<RwLock<Runtime>>`. Functions can be
+    /// invoked concurrently through shared references, whereas updating the runtime requires
+    /// exclusive access.
+    pub fn finish(self) -> Result<Runtime, Error> {
+        Runtime::new(self.options)
+    }
+
     /// Spawns a [`Runtime`] with the builder's options.
     pub fn spawn(self) -> Result<Rc<RefCell<Runtime>>, Error> {
-        Runtime::new(self.options).map(|runtime| Rc::new(RefCell::new(runtime)))
+        self.finish().map(|runtime| Rc::new(RefCell::new(runtime)))
     }
 }
 
@@ -96,7 +326,7 @@ type Dependency<T> = (T, DependencyCounter);
 type DependencyMap<T> = FxHashMap<String, Dependency<T>>;
 
 /// A runtime dispatch table that maps full paths to function and struct information.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct DispatchTable {
     functions: FxHashMap<String, abi::FunctionDefinition>,
     fn_dependencies: FxHashMap<String, DependencyMap<abi::FunctionPrototype>>,
@@ -164,10 +394,34 @@ impl DispatchTable {
 pub struct Runtime {
     assemblies: HashMap<PathBuf, Assembly>,
     dispatch_table: DispatchTable,
+    #[cfg(not(target_arch = "wasm32"))]
     watcher: RecommendedWatcher,
-    watcher_rx: Receiver<DebouncedEvent>,
+    #[cfg(not(target_arch = "wasm32"))]
+    watcher_rx: Mutex<Receiver<DebouncedEvent>>,
+    /// The directories in which new libraries are loaded automatically
+    #[cfg(not(target_arch = "wasm32"))]
+    watch_directories: Vec<PathBuf>,
     gc: Arc<GarbageCollector>,
     _user_functions: Vec<abi::FunctionDefinitionStorage>,
+    tracing_dispatch: Option<tracing::Dispatch>,
+    max_stack_size: usize,
+    invoke_limits: InvokeLimits,
+    /// Held for reading during every invocation of a Mun function, because the objects that are
+    /// allocated by a running function are not rooted and must not be collected
+    invocation_lock: RwLock<()>,
+    gc_threshold: Option<usize>,
+    automatic_gc: bool,
+    /// The code of previous versions of unloaded assemblies, which is retained because objects of
+    /// their types are still alive
+    #[cfg(not(target_arch = "wasm32"))]
+    legacy_code: Vec<Box<dyn Any + Send + Sync>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    reload_hooks: ReloadHooks,
+    /// The assemblies that are being loaded by `update_async`, in the order in which they changed
+    #[cfg(not(target_arch = "wasm32"))]
+    pending_reloads: Vec<PendingReload>,
+    #[cfg(not(target_arch = "wasm32"))]
+    migrations: Migrations<UnsafeTypeInfo>,
 }
 
 /// Retrieve the allocator using the provided handle.
@@ -180,7 +434,7 @@ unsafe fn get_allocator(alloc_handle: *mut ffi::c_void) -> Arc<GarbageCollector>
     Arc::from_raw(alloc_handle as *const GarbageCollector)
 }
 
-extern "C" fn new(
+extern "C-unwind" fn new(
     type_info: *const abi::TypeInfo,
     alloc_handle: *mut ffi::c_void,
 ) -> *const *mut ffi::c_void {
@@ -190,28 +444,93 @@ extern "C" fn new(
     let allocator = unsafe { get_allocator(alloc_handle) };
     // Safety: the Mun Compiler guarantees that `new` is never called with `ptr::null()`.
     let type_info = UnsafeTypeInfo::new(unsafe { NonNull::new_unchecked(type_info as *mut _) });
-    let handle = allocator.alloc(type_info);
+    let handle = allocator.try_alloc(type_info);
 
     // Prevent destruction of the allocator
     mem::forget(allocator);
 
-    handle.into()
+    match handle {
+        Some(handle) => handle.into(),
+        // Unwinds the stack up to the invocation of the Mun function, where the error is caught
+        None => std::panic::resume_unwind(Box::new(format!(
+            "out of memory: allocating a `{}` exceeds the maximum heap size",
+            type_info.name()
+        ))),
+    }
+}
+
+extern "C" fn drop_object(obj: *const *mut ffi::c_void, alloc_handle: *mut ffi::c_void) {
+    // Safety: `drop` is only called from within Mun assemblies' core logic, so we are guaranteed
+    // that the `Runtime` and its `GarbageCollector` still exist if this function is called, and
+    // will continue to do so for the duration of this function.
+    let allocator = unsafe { get_allocator(alloc_handle) };
+    // The Mun Compiler guarantees that `drop` is only called for objects that are no longer
+    // referenced.
+    allocator.dealloc(obj.into());
+
+    // Prevent destruction of the allocator
+    mem::forget(allocator);
+}
+
+extern "C" fn write_barrier(obj: *const *mut ffi::c_void, alloc_handle: *mut ffi::c_void) {
+    // Safety: `write_barrier` is only called from within Mun assemblies' core logic, so we are
+    // guaranteed that the `Runtime` and its `GarbageCollector` still exist if this function is
+    // called, and will continue to do so for the duration of this function.
+    let allocator = unsafe { get_allocator(alloc_handle) };
+    allocator.write_barrier(obj.into());
+
+    // Prevent destruction of the allocator
+    mem::forget(allocator);
 }
 
 impl Runtime {
     /// Constructs a new `Runtime` that loads the library at `library_path` and its
     /// dependencies. The `Runtime` contains a file watcher that is triggered with an interval
     /// of `dur`.
-    pub fn new(mut options: RuntimeOptions) -> Result<Runtime, Error> {
-        let (tx, rx) = channel();
+    pub fn new(options: RuntimeOptions) -> Result<Runtime, Error> {
+        match options.tracing_dispatch.clone() {
+            Some(dispatch) => {
+                tracing::dispatcher::with_default(&dispatch, || Runtime::new_impl(options))
+            }
+            None => Runtime::new_impl(options),
+        }
+    }
 
+    fn new_impl(mut options: RuntimeOptions) -> Result<Runtime, Error> {
         let mut dispatch_table = DispatchTable::default();
 
         // Add internal functions
         options.user_functions.push(IntoFunctionDefinition::into(
-            new as extern "C" fn(*const abi::TypeInfo, *mut ffi::c_void) -> *const *mut ffi::c_void,
+            new as extern "C-unwind" fn(
+                *const abi::TypeInfo,
+                *mut ffi::c_void,
+            ) -> *const *mut ffi::c_void,
             "new",
         ));
+        options.user_functions.push(IntoFunctionDefinition::into(
+            drop_object as extern "C" fn(*const *mut ffi::c_void, *mut ffi::c_void),
+            "drop",
+        ));
+        options.user_functions.push(IntoFunctionDefinition::into(
+            write_barrier as extern "C" fn(*const *mut ffi::c_void, *mut ffi::c_void),
+            "write_barrier",
+        ));
+        options.user_functions.push(IntoFunctionDefinition::into(
+            panic::panic as extern "C-unwind" fn(*const u8, u32, u32),
+            "panic",
+        ));
+        options.user_functions.push(IntoFunctionDefinition::into(
+            stack::stack_limit as extern "C" fn() -> *const u8,
+            "stack_limit",
+        ));
+        options.user_functions.push(IntoFunctionDefinition::into(
+            interrupt::interrupt_counter as extern "C" fn() -> *mut i64,
+            "interrupt_counter",
+        ));
+        options.user_functions.push(IntoFunctionDefinition::into(
+            interrupt::interrupt_check as extern "C-unwind" fn(),
+            "interrupt_check",
+        ));
 
         let mut storages = Vec::with_capacity(options.user_functions.len());
         for (info, storage) in options.user_functions.into_iter() {
@@ -219,41 +538,371 @@ impl Runtime {
             storages.push(storage)
         }
 
-        let watcher: RecommendedWatcher = Watcher::new(tx, options.delay)?;
+        #[cfg(not(target_arch = "wasm32"))]
+        let (watcher, watcher_rx) = {
+            let (tx, rx) = channel();
+            let watcher: RecommendedWatcher = Watcher::new(tx, options.delay)?;
+            (watcher, rx)
+        };
+        let gc = Arc::new(self::garbage_collector::GarbageCollector::with_allocator(
+            AllocationTracker::new(options.track_allocations),
+            options.allocator.clone(),
+        ));
+        gc.set_memory_limit(options.max_heap_size);
+
         let mut runtime = Runtime {
             assemblies: HashMap::new(),
             dispatch_table,
+            #[cfg(not(target_arch = "wasm32"))]
             watcher,
-            watcher_rx: rx,
-            gc: Arc::new(self::garbage_collector::GarbageCollector::default()),
+            #[cfg(not(target_arch = "wasm32"))]
+            watcher_rx: Mutex::new(watcher_rx),
+            #[cfg(not(target_arch = "wasm32"))]
+            watch_directories: Vec::new(),
+            gc,
             _user_functions: storages,
+            tracing_dispatch: options.tracing_dispatch,
+            max_stack_size: options.max_stack_size,
+            invoke_limits: InvokeLimits {
+                max_duration: options.max_invoke_duration,
+                max_loop_iterations: options.max_loop_iterations,
+                interrupt_handle: InterruptHandle::default(),
+            },
+            invocation_lock: RwLock::new(()),
+            gc_threshold: options.gc_threshold,
+            automatic_gc: true,
+            #[cfg(not(target_arch = "wasm32"))]
+            legacy_code: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            reload_hooks: ReloadHooks::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            pending_reloads: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            migrations: Migrations::new(),
         };
 
-        runtime.add_assembly(&options.library_path)?;
+        match &options.static_assembly {
+            Some(assembly) => {
+                if !options.additional_library_paths.is_empty()
+                    || !options.watch_directories.is_empty()
+                {
+                    return Err(anyhow::anyhow!(
+                        "a statically linked assembly cannot be loaded alongside other assemblies"
+                    ));
+                }
+                runtime.add_static_assembly(&options.library_path, assembly)?
+            }
+            None => {
+                let mut library_paths = vec![options.library_path];
+                library_paths.extend(options.additional_library_paths);
+                runtime.add_assemblies(&library_paths)?;
+                #[cfg(not(target_arch = "wasm32"))]
+                runtime.add_watch_directories(options.watch_directories)?;
+            }
+        }
         Ok(runtime)
     }
 
+    /// Adds an assembly for a munlib that is linked into the executable. The assembly is
+    /// identified by `library_path`.
+    fn add_static_assembly(
+        &mut self,
+        library_path: &Path,
+        assembly: &StaticAssembly,
+    ) -> Result<(), Error> {
+        let mut assembly = Assembly::from_static(
+            library_path,
+            assembly,
+            self.gc.clone(),
+            &self.dispatch_table,
+        )?;
+        if assembly.info().dependencies().next().is_some() {
+            return Err(anyhow::anyhow!(
+                "a statically linked assembly cannot have dependencies"
+            ));
+        }
+        assembly.link(&mut self.dispatch_table);
+
+        self.assemblies.insert(library_path.to_path_buf(), assembly);
+        Ok(())
+    }
+
+    /// Adds the assemblies corresponding to the libraries at `library_paths`.
+    #[cfg(target_arch = "wasm32")]
+    fn add_assemblies(&mut self, library_paths: &[PathBuf]) -> Result<(), Error> {
+        Err(anyhow::anyhow!(
+            "cannot load `{}`: shared libraries are not supported on WebAssembly, use a statically linked assembly instead",
+            library_paths[0].display()
+        ))
+    }
+
+    /// Adds the assemblies corresponding to the libraries at `library_paths`, together with the
+    /// assemblies that they depend on. The assemblies can call the functions of each other. The
+    /// batch is validated against a copy of the dispatch table, so if any of the assemblies cannot
+    /// be loaded or linked, none of them are added and the runtime is left untouched.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn add_assemblies(&mut self, library_paths: &[PathBuf]) -> Result<(), Error> {
+        let mut library_paths = library_paths
+            .iter()
+            .map(|library_path| library_path.canonicalize())
+            .collect::<Result<Vec<_>, _>>()?;
+        for (idx, library_path) in library_paths.iter().enumerate() {
+            if self.assemblies.contains_key(library_path)
+                || library_paths[..idx].contains(library_path)
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    "An assembly with the same name already exists.",
+                )
+                .into());
+            }
+        }
+
+        // Dependencies are appended to the batch while it is loaded
+        let mut assemblies: Vec<Assembly> = Vec::with_capacity(library_paths.len());
+        let mut idx = 0;
+        while idx < library_paths.len() {
+            let library_path = library_paths[idx].clone();
+            idx += 1;
+
+            let span = tracing::info_span!("load", path = %library_path.display());
+            let _enter = span.enter();
+
+            let assembly = Assembly::load_unlinked(&library_path, self.gc.clone())?;
+            for dependency in assembly.info().dependencies() {
+                // Multiple assemblies can depend on the same assembly, which is only loaded once
+                let dependency = Path::new(dependency).canonicalize()?;
+                if !self.assemblies.contains_key(&dependency)
+                    && !library_paths.contains(&dependency)
+                {
+                    library_paths.push(dependency);
+                }
+            }
+            assemblies.push(assembly);
+        }
+
+        // Register the functions of all assemblies before linking any of them, so they can call
+        // each other regardless of the order in which they were loaded.
+        let mut dispatch_table = self.dispatch_table.clone();
+        for assembly in assemblies.iter() {
+            for function in assembly.functions() {
+                if dispatch_table.get_fn(function.prototype.name()).is_some() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::AlreadyExists,
+                        format!(
+                            "Failed to link: function `{}` of `{}` is already defined.",
+                            function.prototype.name(),
+                            assembly.library_path().display()
+                        ),
+                    )
+                    .into());
+                }
+            }
+            assembly.register(&mut dispatch_table);
+        }
+        for assembly in assemblies.iter() {
+            assembly.ensure_linkable(&dispatch_table)?;
+        }
+
+        for assembly in assemblies.iter() {
+            self.watcher.watch(
+                assembly.library_path().parent().unwrap(),
+                RecursiveMode::NonRecursive,
+            )?;
+        }
+        for mut assembly in assemblies {
+            assembly.link(&mut dispatch_table);
+            self.assemblies
+                .insert(assembly.library_path().to_path_buf(), assembly);
+        }
+        self.dispatch_table = dispatch_table;
+        self.update_dependencies();
+        Ok(())
+    }
+
     /// Adds an assembly corresponding to the library at `library_path`.
+    #[cfg(not(target_arch = "wasm32"))]
     fn add_assembly(&mut self, library_path: &Path) -> Result<(), Error> {
-        let library_path = library_path.canonicalize()?;
-        if self.assemblies.contains_key(&library_path) {
-            return Err(io::Error::new(
-                io::ErrorKind::AlreadyExists,
-                "An assembly with the same name already exists.",
-            )
-            .into());
+        self.add_assemblies(&[library_path.to_path_buf()])
+    }
+
+    /// Starts watching the directories at `dirs` for new libraries.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn add_watch_directories(&mut self, dirs: Vec<PathBuf>) -> Result<(), Error> {
+        for dir in dirs {
+            // The paths of filesystem events are relative to the watched path
+            let dir = dir.canonicalize()?;
+            self.watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+            self.watch_directories.push(dir);
         }
+        Ok(())
+    }
 
-        let mut assembly = Assembly::load(&library_path, self.gc.clone(), &self.dispatch_table)?;
-        for dependency in assembly.info().dependencies() {
-            self.add_assembly(Path::new(dependency))?;
+    /// Returns `true` if `path` refers to a library in a watched directory that is not loaded.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn is_new_library(&self, path: &Path) -> bool {
+        path.extension().map_or(false, |ext| ext == "munlib")
+            && !self.assemblies.contains_key(path)
+            && path.parent().map_or(false, |parent| {
+                self.watch_directories.iter().any(|dir| dir == parent)
+            })
+    }
+
+    /// Loads the new library at `library_path`, reporting whether it succeeded. If it fails,
+    /// neither the library nor its dependencies are loaded. A library that failed to load, e.g.
+    /// because it is still being written, is loaded again when it changes.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_new_library(&mut self, library_path: &Path) -> bool {
+        if let Err(e) = self.add_assembly(library_path) {
+            tracing::error!("failed to load assembly: {:?}", e);
+            false
+        } else {
+            tracing::info!(path = %library_path.display(), "loaded assembly");
+            true
         }
-        assembly.link(&mut self.dispatch_table);
+    }
+
+    /// Replaces the loaded assembly at `library_path` with `new_assembly`, invoking the reload
+    /// hooks. If `new_assembly` failed to load or cannot be linked, the runtime keeps running the
+    /// previous version, the hooks of a failed reload are invoked, and the error is returned.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn reload(
+        &mut self,
+        library_path: &Path,
+        new_assembly: Result<Assembly, Error>,
+    ) -> Result<(), Error> {
+        let new_assembly = new_assembly.and_then(|new_assembly| {
+            self.ensure_reloadable(library_path, &new_assembly)?;
+            Ok(new_assembly)
+        });
+
+        let hooks = &mut self.reload_hooks;
+        let assembly = self
+            .assemblies
+            .get_mut(library_path)
+            .expect("the reloaded assembly must be loaded");
+        let new_assembly = match new_assembly {
+            Ok(new_assembly) => new_assembly,
+            Err(e) => {
+                for hook in hooks.reload_failed.iter_mut() {
+                    hook(assembly, &e);
+                }
+                return Err(e);
+            }
+        };
 
-        self.watcher
-            .watch(library_path.parent().unwrap(), RecursiveMode::NonRecursive)?;
+        let changes = ReloadChanges::new(assembly, &new_assembly);
+        for hook in hooks.before_reload.iter_mut() {
+            hook(assembly, &changes);
+        }
+        assembly.replace(new_assembly, &mut self.dispatch_table, &self.migrations);
+        for hook in hooks.after_reload.iter_mut() {
+            hook(assembly, &changes);
+        }
 
-        self.assemblies.insert(library_path, assembly);
+        self.relink();
+        Ok(())
+    }
+
+    /// Verifies that replacing the assembly at `library_path` with `new_assembly` leaves all
+    /// assemblies linkable. The replacement is applied to a copy of the dispatch table, so the
+    /// runtime keeps running the previous version if the reload would fail.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn ensure_reloadable(&self, library_path: &Path, new_assembly: &Assembly) -> Result<(), Error> {
+        let mut dispatch_table = self.dispatch_table.clone();
+        for function in self.assemblies[library_path].functions() {
+            dispatch_table.remove_fn(function.prototype.name());
+        }
+        new_assembly.register(&mut dispatch_table);
+        new_assembly.ensure_linkable(&dispatch_table)?;
+
+        for (path, assembly) in self.assemblies.iter() {
+            if path != library_path {
+                assembly.ensure_linkable(&dispatch_table).map_err(|e| {
+                    anyhow::anyhow!("{} It is required by `{}`.", e, path.display())
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Relinks all assemblies after an assembly was reloaded, so calls between assemblies invoke
+    /// the reloaded functions.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn relink(&mut self) {
+        for assembly in self.assemblies.values_mut() {
+            assembly.link(&mut self.dispatch_table);
+        }
+        self.update_dependencies();
+    }
+
+    /// Records which functions every assembly imports from the other assemblies, which ensures
+    /// that an assembly cannot be reloaded without the functions that others depend on.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_dependencies(&mut self) {
+        self.dispatch_table.fn_dependencies.clear();
+        for assembly in self.assemblies.values() {
+            for fn_prototype in assembly.imports() {
+                let provider = self.assemblies.values().find(|provider| {
+                    provider
+                        .functions()
+                        .iter()
+                        .any(|function| function.prototype.name() == fn_prototype.name())
+                });
+                if let Some(provider) = provider {
+                    self.dispatch_table.add_fn_dependency(
+                        provider.library_path().to_string_lossy(),
+                        fn_prototype.name(),
+                        fn_prototype.clone(),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Loads the assembly identified by `library_path` from code that was compiled into the memory
+    /// of this process, e.g. by the just-in-time compiler of `mun_codegen`. This skips writing a
+    /// shared library to disk and loading it, which reduces the latency of hot reloading. If an
+    /// assembly with the same path is already loaded, it is hot reloaded. `code` keeps the
+    /// compiled code alive, e.g. a `JitCode` handle, and is held for as long as the code can be
+    /// executed. As the runtime can be sent to other threads, so can `code`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_in_process(
+        &mut self,
+        library_path: &Path,
+        assembly: StaticAssembly,
+        code: Box<dyn Any + Send + Sync>,
+    ) -> Result<(), Error> {
+        let span = tracing::info_span!("load_in_process", path = %library_path.display());
+        let _enter = span.enter();
+
+        if self.assemblies.contains_key(library_path) {
+            let new_assembly = Assembly::in_process(
+                library_path,
+                &assembly,
+                code,
+                self.gc.clone(),
+                &self.dispatch_table,
+            );
+            return self.reload(library_path, new_assembly);
+        }
+
+        let mut assembly = Assembly::in_process(
+            library_path,
+            &assembly,
+            code,
+            self.gc.clone(),
+            &self.dispatch_table,
+        )?;
+        if assembly.info().dependencies().next().is_some() {
+            return Err(anyhow::anyhow!(
+                "an assembly that is compiled in-process cannot have dependencies"
+            ));
+        }
+        assembly.link(&mut self.dispatch_table);
+
+        self.assemblies.insert(library_path.to_path_buf(), assembly);
+        self.update_dependencies();
         Ok(())
     }
 
@@ -262,27 +911,279 @@ impl Runtime {
         self.dispatch_table.get_fn(function_name)
     }
 
-    /// Updates the state of the runtime. This includes checking for file changes, and reloading
-    /// compiled assemblies.
+    /// Returns the assemblies that are currently loaded. The [`AssemblyInfo`] of each assembly
+    /// contains the build metadata that was embedded by the compiler, e.g. to invalidate caches or
+    /// to include in bug reports.
+    pub fn assemblies(&self) -> impl Iterator<Item = &Assembly> {
+        self.assemblies.values()
+    }
+
+    /// Returns the public functions of the assemblies that are currently loaded, e.g. to bind the
+    /// functions that follow a naming convention.
+    pub fn functions(&self) -> impl Iterator<Item = &abi::FunctionDefinition> {
+        self.assemblies
+            .values()
+            .flat_map(|assembly| assembly.functions().iter())
+    }
+
+    /// Returns the test functions of the assemblies that are currently loaded, which are marked
+    /// with a `#[test]` attribute, see [`Runtime::run_test`].
+    pub fn tests(&self) -> impl Iterator<Item = &abi::FunctionDefinition> {
+        self.assemblies
+            .values()
+            .flat_map(|assembly| assembly.tests().iter())
+    }
+
+    /// Runs the test function named `test_name` of one of the loaded assemblies. Returns an
+    /// [`InvokeError::Panic`] with the message of the panic if the test fails.
+    pub fn run_test(&self, test_name: &str) -> Result<(), InvokeError> {
+        let test = self
+            .tests()
+            .find(|test| test.prototype.name() == test_name)
+            .ok_or_else(|| InvokeError::FunctionNotFound {
+                function_name: test_name.to_owned(),
+            })?;
+        self.invoke_repeatedly(test, 1).map(|_| ())
+    }
+
+    /// Returns the benchmark functions of the assemblies that are currently loaded, which are
+    /// marked with a `#[bench]` attribute, see [`Runtime::run_bench`].
+    pub fn benches(&self) -> impl Iterator<Item = &abi::FunctionDefinition> {
+        self.assemblies
+            .values()
+            .flat_map(|assembly| assembly.benches().iter())
+    }
+
+    /// Runs the benchmark function named `bench_name` of one of the loaded assemblies
+    /// `iterations` times in a row and returns the time that it took. Returns an
+    /// [`InvokeError::Panic`] with the message of the panic if the benchmark panics.
+    pub fn run_bench(&self, bench_name: &str, iterations: u64) -> Result<Duration, InvokeError> {
+        let bench = self
+            .benches()
+            .find(|bench| bench.prototype.name() == bench_name)
+            .ok_or_else(|| InvokeError::FunctionNotFound {
+                function_name: bench_name.to_owned(),
+            })?;
+        self.invoke_repeatedly(bench, iterations)
+    }
+
+    /// Invokes the test or benchmark function `function` `iterations` times and returns the time
+    /// that it took, or the error of the first invocation that unwound.
+    fn invoke_repeatedly(
+        &self,
+        function: &abi::FunctionDefinition,
+        iterations: u64,
+    ) -> Result<Duration, InvokeError> {
+        // Safety: test and benchmark functions take no arguments and return no value.
+        let fn_ptr: extern "C-unwind" fn() = unsafe { std::mem::transmute(function.fn_ptr) };
+
+        // A test fails by unwinding, e.g. if an assertion fails
+        let _invocation = self.invocation_lock.read_recursive();
+        let _stack_guard = crate::stack::StackGuard::enter(self.max_stack_size);
+        let _interrupt_guard = crate::interrupt::InterruptGuard::enter(&self.invoke_limits);
+        let start = std::time::Instant::now();
+        std::panic::catch_unwind(|| {
+            for _ in 0..iterations {
+                fn_ptr();
+            }
+        })
+        .map(|_| start.elapsed())
+        .map_err(|payload| InvokeError::from_unwind(function.prototype.name(), payload))
+    }
+
+    /// Returns the types of the assemblies that are currently loaded. A type that is used by
+    /// multiple assemblies is only returned once.
+    pub fn types(&self) -> impl Iterator<Item = &abi::TypeInfo> {
+        let mut guids = HashSet::new();
+        self.assemblies
+            .values()
+            .flat_map(|assembly| assembly.types().iter().copied())
+            .filter(move |type_info| guids.insert(type_info.guid))
+    }
+
+    /// Unloads the assembly corresponding to the library at `library_path`, e.g. when the scripts
+    /// of a level are no longer needed. The functions of the assembly are removed from the dispatch
+    /// table, memory is collected, and the shared library is unmapped.
+    ///
+    /// Fails if other assemblies call functions of the assembly, or if objects of its types are
+    /// still alive, e.g. because they are referenced by a [`StructRef`].
+    pub fn unload(&mut self, library_path: &Path) -> Result<(), Error> {
+        let library_path = library_path
+            .canonicalize()
+            .unwrap_or_else(|_| library_path.to_path_buf());
+        let span = tracing::info_span!("unload", path = %library_path.display());
+        let _enter = span.enter();
+
+        let assembly = self.assemblies.get(&library_path).ok_or_else(|| {
+            anyhow::anyhow!("no assembly is loaded from `{}`", library_path.display())
+        })?;
+
+        if let Some(dependencies) = self
+            .dispatch_table
+            .fn_dependencies
+            .get(library_path.to_string_lossy().as_ref())
+        {
+            if let Some(fn_name) = dependencies.keys().next() {
+                return Err(anyhow::anyhow!(
+                    "cannot unload `{}`: function `{}` is called by another assembly",
+                    library_path.display(),
+                    fn_name
+                ));
+            }
+        }
+
+        // Objects of the assembly's types refer to its type information, so they cannot outlive
+        // the shared library.
+        self.gc.collect();
+        let types: HashSet<*const abi::TypeInfo> = assembly
+            .types()
+            .iter()
+            .map(|ty| *ty as *const abi::TypeInfo)
+            .collect();
+        let alive_objects = self
+            .gc
+            .count_objects(|ty| types.contains(&(ty.into_inner().as_ptr() as *const _)));
+        if alive_objects > 0 {
+            return Err(anyhow::anyhow!(
+                "cannot unload `{}`: {} objects of its types are still alive",
+                library_path.display(),
+                alive_objects
+            ));
+        }
+
+        #[allow(unused_mut)]
+        let mut assembly = self
+            .assemblies
+            .remove(&library_path)
+            .expect("the assembly must exist after the previous check");
+        for function in assembly.functions() {
+            self.dispatch_table.remove_fn(function.prototype.name());
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.legacy_code.append(&mut assembly.take_legacy_code());
+            self.update_dependencies();
+        }
+        tracing::info!("unloaded assembly");
+        Ok(())
+    }
+
+    /// Registers a callback that is invoked before an assembly is hot reloaded, e.g. to pause the
+    /// simulation. The callback receives the assembly that is about to be replaced and the changes
+    /// of its functions and types.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn on_before_reload<F>(&mut self, callback: F)
+    where
+        F: FnMut(&Assembly, &ReloadChanges) + Send + Sync + 'static,
+    {
+        self.reload_hooks.before_reload.push(Box::new(callback));
+    }
+
+    /// Registers a callback that is invoked after an assembly was hot reloaded, e.g. to refresh
+    /// cached bindings. The callback receives the reloaded assembly and the changes of its
+    /// functions and types.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn on_after_reload<F>(&mut self, callback: F)
+    where
+        F: FnMut(&Assembly, &ReloadChanges) + Send + Sync + 'static,
+    {
+        self.reload_hooks.after_reload.push(Box::new(callback));
+    }
+
+    /// Registers a callback that is invoked when an assembly fails to hot reload. The callback
+    /// receives the assembly, which remains loaded, and the error.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn on_reload_failed<F>(&mut self, callback: F)
+    where
+        F: FnMut(&Assembly, &Error) + Send + Sync + 'static,
+    {
+        self.reload_hooks.reload_failed.push(Box::new(callback));
+    }
+
+    /// Registers a function that migrates the objects of the struct with `old_guid` to the struct
+    /// with `new_guid` when an assembly is hot reloaded, e.g. to split a field into two fields.
+    /// The migration is invoked for every object after its fields that could be mapped
+    /// automatically have been mapped. It is also invoked if the struct was renamed or changed so
+    /// much that its objects would otherwise be dropped, in which case all fields of the new
+    /// struct are zero-initialized.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn add_migration<F>(&mut self, old_guid: abi::Guid, new_guid: abi::Guid, migration: F)
+    where
+        F: Fn(&mut StructMigration) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.migrations.insert(
+            (old_guid, new_guid),
+            migration::into_migration_fn(migration),
+        );
+    }
+
+    /// Updates the state of the runtime. This includes checking for file changes, reloading
+    /// compiled assemblies, loading new libraries in watched directories, and collecting garbage if
+    /// more memory than the garbage collection threshold was allocated since the last collection.
+    /// Assemblies are not reloaded while structs are pinned, see [`RootedStruct::pin`].
     pub fn update(&mut self) -> bool {
-        while let Ok(event) = self.watcher_rx.try_recv() {
+        let reloaded = match self.tracing_dispatch.clone() {
+            Some(dispatch) => tracing::dispatcher::with_default(&dispatch, || self.update_impl()),
+            None => self.update_impl(),
+        };
+        self.collect_garbage_if_needed();
+        reloaded
+    }
+
+    /// Updates the state of the runtime like [`Runtime::update`], but loads changed assemblies on
+    /// a background thread, so a reload does not block the caller. Returns `true` if an assembly
+    /// that finished loading since the previous call was reloaded.
+    ///
+    /// The loaded assembly replaces the previous version, and the memory of its objects is mapped,
+    /// during a call to `update_async`, which is a safe point at which no Mun function is running.
+    pub fn update_async(&mut self) -> bool {
+        let reloaded = match self.tracing_dispatch.clone() {
+            Some(dispatch) => {
+                tracing::dispatcher::with_default(&dispatch, || self.update_async_impl())
+            }
+            None => self.update_async_impl(),
+        };
+        self.collect_garbage_if_needed();
+        reloaded
+    }
+
+    /// Collects garbage if automatic collection is enabled, and more memory than the garbage
+    /// collection threshold was allocated since the last collection.
+    fn collect_garbage_if_needed(&self) {
+        let exceeds_threshold = self.gc_threshold.map_or(false, |gc_threshold| {
+            self.gc.stats().allocated_since_collection >= gc_threshold
+        });
+        if self.automatic_gc && exceeds_threshold {
+            self.collect_garbage();
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn update_impl(&mut self) -> bool {
+        // Statically linked assemblies cannot be reloaded
+        false
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_impl(&mut self) -> bool {
+        // Reloading an assembly can move the memory of objects, so it is postponed until no more
+        // objects are pinned.
+        if self.gc.pinned_objects() > 0 {
+            return false;
+        }
+
+        while let Ok(event) = self.watcher_rx.get_mut().try_recv() {
             use notify::DebouncedEvent::*;
             match event {
                 Write(ref path) | Rename(_, ref path) | Create(ref path) => {
-                    if let Some(assembly) = self.assemblies.get_mut(path) {
-                        if let Err(e) = assembly.swap(path, &mut self.dispatch_table) {
-                            println!(
-                                "An error occured while reloading assembly '{}': {:?}",
-                                path.to_string_lossy(),
-                                e
-                            );
-                        } else {
-                            println!(
-                                "Succesfully reloaded assembly: '{}'",
-                                path.to_string_lossy()
-                            );
+                    if self.assemblies.contains_key(path) {
+                        let new_assembly =
+                            Assembly::load(path, self.gc.clone(), &self.dispatch_table);
+                        if self.reload_and_report(path, new_assembly) {
                             return true;
                         }
+                    } else if self.is_new_library(path) && self.load_new_library(path) {
+                        return true;
                     }
                 }
                 _ => {}
@@ -291,6 +1192,73 @@ impl Runtime {
         false
     }
 
+    #[cfg(target_arch = "wasm32")]
+    fn update_async_impl(&mut self) -> bool {
+        // Statically linked assemblies cannot be reloaded
+        false
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_async_impl(&mut self) -> bool {
+        // Reloading an assembly can move the memory of objects, so it is postponed until no more
+        // objects are pinned.
+        if self.gc.pinned_objects() > 0 {
+            return false;
+        }
+
+        // Start loading the assemblies that changed. New libraries are loaded right away, as they
+        // must be linked against the other assemblies.
+        let mut reloaded = false;
+        while let Ok(event) = self.watcher_rx.get_mut().try_recv() {
+            use notify::DebouncedEvent::*;
+            match event {
+                Write(path) | Rename(_, path) | Create(path) => {
+                    if self.assemblies.contains_key(&path) {
+                        self.pending_reloads
+                            .push(PendingReload::spawn(path, self.gc.clone()));
+                    } else if self.is_new_library(&path) {
+                        reloaded |= self.load_new_library(&path);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Reload the assemblies that finished loading, without overtaking a previous change
+        while let Some(pending) = self.pending_reloads.first() {
+            let new_assembly = match pending.receiver.try_recv() {
+                Ok(new_assembly) => new_assembly.map(|loaded| loaded.0),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => Err(anyhow::anyhow!(
+                    "the thread that loaded the assembly panicked"
+                )),
+            };
+            let pending = self.pending_reloads.remove(0);
+            reloaded |= self.reload_and_report(&pending.library_path, new_assembly);
+        }
+        reloaded
+    }
+
+    /// Replaces the assembly at `library_path` with `new_assembly`, reporting whether it succeeded.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn reload_and_report(&mut self, path: &Path, new_assembly: Result<Assembly, Error>) -> bool {
+        let span = tracing::info_span!("reload", path = %path.display());
+        let _enter = span.enter();
+        if let Err(e) = self.reload(path, new_assembly) {
+            tracing::error!("failed to reload assembly: {:?}", e);
+            false
+        } else {
+            tracing::info!("reloaded assembly");
+            true
+        }
+    }
+
+    /// Returns a handle that interrupts the running invocations of Mun functions, e.g. from a
+    /// watchdog thread. An invocation is aborted with an error at the next iteration of a loop.
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.invoke_limits.interrupt_handle.clone()
+    }
+
     /// Returns a shared reference to the runtime's garbage collector.
     ///
     /// We cannot return an `Arc` here, because the lifetime of data contained in `GarbageCollector`
@@ -300,14 +1268,156 @@ impl Runtime {
     }
 
     /// Collects all memory that is no longer referenced by rooted objects. Returns `true` if memory
-    /// was reclaimed, `false` otherwise. This behavior will likely change in the future.
+    /// was reclaimed, `false` otherwise.
+    #[deprecated(note = "use `Runtime::collect_garbage` instead")]
     pub fn gc_collect(&self) -> bool {
-        self.gc.collect()
+        self.collect_garbage()
+    }
+
+    /// Collects all memory that is no longer referenced by rooted objects, regardless of the
+    /// garbage collection threshold. Returns `true` if memory was reclaimed, `false` otherwise.
+    /// This enables the host to schedule collections, e.g. at frame boundaries.
+    ///
+    /// No memory is collected while a function is being invoked on any thread, because the objects
+    /// that it allocated are not rooted.
+    pub fn collect_garbage(&self) -> bool {
+        match self.invocation_lock.try_write() {
+            Some(_guard) => self.gc.collect(),
+            None => false,
+        }
+    }
+
+    /// Sets the number of bytes that can be allocated after a collection, before garbage is
+    /// collected automatically by [`Runtime::update`]. If `None`, garbage is only collected
+    /// manually.
+    pub fn set_gc_threshold(&mut self, gc_threshold: Option<usize>) {
+        self.gc_threshold = gc_threshold;
+    }
+
+    /// Enables or disables the automatic collection of garbage by [`Runtime::update`], e.g. to
+    /// prevent pauses during a critical section. Manual collections are not affected.
+    pub fn set_automatic_gc(&mut self, enabled: bool) {
+        self.automatic_gc = enabled;
+    }
+
+    /// Performs a step of an incremental garbage collection, during which at most `budget` objects
+    /// are traced or swept. Returns `true` if the collection was completed, `false` otherwise.
+    ///
+    /// As opposed to [`Runtime::collect_garbage`], this bounds the pause that is caused by the
+    /// garbage collector, so a real-time application can perform a step every frame. Memory that
+    /// is allocated during a collection is only reclaimed by the next collection.
+    ///
+    /// No work is performed while a function is being invoked on any thread. While an assembly is
+    /// loaded that does not emit write barriers, see [`Assembly::emits_write_barriers`], a full
+    /// collection is performed instead, which returns whether memory was reclaimed like
+    /// [`Runtime::collect_garbage`].
+    pub fn gc_collect_incremental(&self, budget: usize) -> bool {
+        match self.invocation_lock.try_write() {
+            Some(_guard) => {
+                if self
+                    .assemblies
+                    .values()
+                    .all(|assembly| assembly.emits_write_barriers())
+                {
+                    self.gc.collect_incremental(budget)
+                } else {
+                    self.gc.collect()
+                }
+            }
+            None => false,
+        }
+    }
+
+    /// Enables or disables the invocation of the allocation hooks, e.g. while a memory profiler is
+    /// attached.
+    pub fn set_allocation_tracking(&mut self, enabled: bool) {
+        self.gc.observer().set_enabled(enabled);
+    }
+
+    /// Registers a callback that is invoked for every object that is allocated by the garbage
+    /// collector, while allocation tracking is enabled. This enables memory profilers to attribute
+    /// the allocations of scripts to the systems of the host, e.g. with the stack id that is set
+    /// with [`Runtime::set_allocation_stack_id`].
+    ///
+    /// The callback is invoked on the thread that allocates the object, while the garbage
+    /// collector is locked, so it must not access the objects of the runtime.
+    pub fn on_allocation<F>(&mut self, callback: F)
+    where
+        F: Fn(&AllocationEvent) + Send + Sync + 'static,
+    {
+        self.gc.observer().add_allocation_hook(Box::new(callback));
     }
 
-    /// Returns statistics about the garbage collector.
-    pub fn gc_stats(&self) -> gc::Stats {
-        self.gc.stats()
+    /// Registers a callback that is invoked for every object that is deallocated by the garbage
+    /// collector, while allocation tracking is enabled. Like [`Runtime::on_allocation`], the
+    /// callback must not access the objects of the runtime.
+    pub fn on_deallocation<F>(&mut self, callback: F)
+    where
+        F: Fn(&AllocationEvent) + Send + Sync + 'static,
+    {
+        self.gc.observer().add_deallocation_hook(Box::new(callback));
+    }
+
+    /// Sets the function that returns the id of the current stack, e.g. the id of a call stack
+    /// that was captured by a profiler, or of the system of the host that is running. It is
+    /// invoked for every allocation while allocation tracking is enabled, and the id is passed to
+    /// the allocation hooks of the object.
+    pub fn set_allocation_stack_id<F>(&mut self, stack_id: F)
+    where
+        F: Fn() -> Option<u64> + Send + Sync + 'static,
+    {
+        self.gc.observer().set_stack_id(Box::new(stack_id));
+    }
+
+    /// Returns statistics about the garbage collector, e.g. to track the memory usage of scripts
+    /// in telemetry.
+    pub fn gc_stats(&self) -> GcStats {
+        let stats = self.gc.stats();
+
+        // Multiple versions of a type can be alive during a hot reload
+        let mut objects_per_type = BTreeMap::new();
+        for (ty, count) in self.gc.count_objects_per_type() {
+            *objects_per_type.entry(ty.name().to_string()).or_insert(0) += count;
+        }
+
+        GcStats {
+            allocated_memory: stats.allocated_memory,
+            allocated_since_collection: stats.allocated_since_collection,
+            last_pause: stats.last_pause,
+            objects_per_type,
+        }
+    }
+
+    /// Serializes the structs `roots` and all objects that are reachable from them into a binary
+    /// snapshot, e.g. to implement save games or to replay the state of scripts. The snapshot can
+    /// be restored with [`Runtime::restore_snapshot`] by a runtime that has loaded the same types.
+    pub fn save_snapshot(&self, roots: &[StructRef]) -> Vec<u8> {
+        let roots: Vec<_> = roots.iter().map(|root| root.clone().into_raw().0).collect();
+        snapshot::save(self, &roots)
+    }
+
+    /// Restores the objects of a snapshot that was created with [`Runtime::save_snapshot`].
+    /// Returns the structs that were passed as roots to `save_snapshot`, in the same order. The
+    /// returned structs are not rooted.
+    ///
+    /// Fails if the data is not a valid snapshot, or if the types of the snapshot are not loaded.
+    pub fn restore_snapshot(&self, snapshot: &[u8]) -> Result<Vec<StructRef>, Error> {
+        let roots = snapshot::restore(self, snapshot)?;
+        Ok(roots
+            .into_iter()
+            .map(|root| StructRef::new(RawStruct(root), self))
+            .collect())
+    }
+
+    /// Writes a graph of all objects of the garbage collector, their types, sizes and references
+    /// to `writer`, e.g. to diagnose memory leaks that are caused by objects that are retained by
+    /// scripts. The graph is written in the JSON format of a heap snapshot of the Chrome DevTools,
+    /// so it can be inspected offline with their memory tools.
+    ///
+    /// No memory is collected while the heap is dumped.
+    pub fn dump_heap<W: std::io::Write>(&self, writer: W) -> std::io::Result<()> {
+        let _invocation = self.invocation_lock.read_recursive();
+        heap_dump::write(self, writer)
     }
 }
 
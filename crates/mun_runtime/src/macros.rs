@@ -16,99 +16,123 @@ macro_rules! invoke_fn_impl {
             /// An invocation error that contains the function name, a mutable reference to the
             /// runtime, passed arguments, and the output type. This allows the caller to retry
             /// the function invocation using the `Retriable` trait.
+            ///
+            /// The invocation of a function that unwound, e.g. because it panicked, consumed the
+            /// arguments, so it cannot be retried.
             pub struct $ErrName<'i, 's, $($T: ArgumentReflection + Marshal<'i>,)*> {
-                msg: String,
+                error: crate::InvokeError,
                 function_name: &'s str,
-                $($Arg: $T,)*
+                arguments: Option<($($T,)*)>,
                 input: core::marker::PhantomData<&'i ()>,
             }
 
             impl<'i, 's, $($T: ArgumentReflection + Marshal<'i>,)*> core::fmt::Debug for $ErrName<'i, 's, $($T,)*> {
                 fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-                    write!(f, "{}", &self.msg)
+                    write!(f, "{}", &self.error)
                 }
             }
 
             impl<'i, 's, $($T: ArgumentReflection + Marshal<'i>,)*> core::fmt::Display for $ErrName<'i, 's, $($T,)*> {
                 fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-                    write!(f, "{}", &self.msg)
+                    write!(f, "{}", &self.error)
                 }
             }
 
             impl<'i, 's, $($T: ArgumentReflection + Marshal<'i>,)*> std::error::Error for $ErrName<'i, 's, $($T,)*> {
                 fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-                    None
+                    Some(&self.error)
                 }
             }
 
             impl<'i, 's, $($T: ArgumentReflection + Marshal<'i>,)*> $ErrName<'i, 's, $($T,)*> {
                 /// Constructs a new invocation error.
                 #[allow(clippy::too_many_arguments)]
-                pub fn new(err_msg: String, function_name: &'s str, $($Arg: $T),*) -> Self {
+                pub fn new(error: crate::InvokeError, function_name: &'s str, $($Arg: $T),*) -> Self {
                     Self {
-                        msg: err_msg,
+                        error,
                         function_name,
-                        $($Arg,)*
+                        arguments: Some(($($Arg,)*)),
                         input: core::marker::PhantomData,
                     }
                 }
 
+                /// Constructs the error of an invocation that consumed its arguments.
+                fn without_arguments(error: crate::InvokeError, function_name: &'s str) -> Self {
+                    Self {
+                        error,
+                        function_name,
+                        arguments: None,
+                        input: core::marker::PhantomData,
+                    }
+                }
+
+                /// Returns the reason why the invocation failed.
+                pub fn error(&self) -> &crate::InvokeError {
+                    &self.error
+                }
+
+                /// Returns the `Panic` that aborted the invocation, if the function panicked.
+                pub fn panic(&self) -> Option<&crate::Panic> {
+                    self.error.panic()
+                }
+
+                /// Returns whether the invocation can be retried, which is not the case if the
+                /// function unwound.
+                pub fn is_retriable(&self) -> bool {
+                    self.arguments.is_some()
+                }
+
                 /// Retries a function invocation once, resulting in a potentially successful
-                /// invocation.
-                // FIXME: `unwrap_or_else` does not compile for `StructRef`, due to
-                // https://doc.rust-lang.org/nomicon/lifetime-mismatch.html#improperly-reduced-borrows
-                pub fn retry<'r, 'o, Output>(self, runtime: &'r mut Runtime) -> Result<Output, Self>
+                /// invocation. If the invocation cannot be retried, the error is returned
+                /// immediately.
+                pub fn retry<'r, 'o, Output>(mut self, runtime: &'r mut Runtime) -> Result<Output, Self>
                 where
                     Output: 'o + ReturnTypeReflection + Marshal<'o>,
                     'r: 'o,
                 {
-                    // Safety: The output of `retry_impl` is guaranteed to only contain a shared
-                    // reference.
-                    unsafe { self.retry_impl(runtime) }
+                    let ($($Arg,)*) = match self.arguments.take() {
+                        Some(arguments) => arguments,
+                        None => return Err(self),
+                    };
+
+                    eprintln!("{}", self.error);
+                    while !runtime.update() {
+                        // Wait until there has been an update that might fix the error
+                    }
+
+                    // The output only contains a shared borrow of the runtime
+                    let runtime: &'r Runtime = runtime;
+                    $crate::Runtime::$FnName(runtime, self.function_name, $($Arg,)*)
                 }
 
                 /// Retries the function invocation until it succeeds, resulting in an output.
-                // FIXME: `unwrap_or_else` does not compile for `StructRef`, due to
-                // https://doc.rust-lang.org/nomicon/lifetime-mismatch.html#improperly-reduced-borrows
+                ///
+                /// # Panics
+                ///
+                /// Panics if the invocation cannot be retried.
                 pub fn wait<'r, 'o, Output>(mut self, runtime: &'r mut Runtime) -> Output
                 where
                     Output: 'o + ReturnTypeReflection + Marshal<'o>,
                     'r: 'o,
                 {
-                    // Safety: The output of `retry_impl` is guaranteed to only contain a shared
-                    // reference.
-                    let runtime = &*runtime;
+                    if !self.is_retriable() {
+                        panic!("{}", self.error);
+                    }
 
+                    // The borrow checker cannot tell that a failed invocation releases its borrow
+                    // of the runtime, see:
+                    // https://doc.rust-lang.org/nomicon/lifetime-mismatch.html#improperly-reduced-borrows
+                    let runtime: *mut Runtime = runtime;
                     loop {
-                        self = match unsafe { self.retry_impl(runtime) } {
+                        // Safety: `runtime` originates from a mutable borrow that lives for `'r`.
+                        // The error of a failed invocation does not borrow the runtime, so the
+                        // borrow that is created here is the only borrow of the runtime.
+                        self = match self.retry(unsafe { &mut *runtime }) {
                             Ok(output) => return output,
                             Err(e) => e,
                         };
                     }
                 }
-
-                /// Inner implementation that retries a function invocation once, resulting in a
-                /// potentially successful invocation. This is a workaround for:
-                /// https://doc.rust-lang.org/nomicon/lifetime-mismatch.html
-                ///
-                /// # Safety
-                ///
-                /// When calling this function, you have to guarantee that `runtime` is mutably
-                /// borrowed. The `Output` value can only contain a shared borrow of `runtime`.
-                unsafe fn retry_impl<'r, 'o, Output>(self, runtime: &'r Runtime) -> Result<Output, Self>
-                where
-                    Output: 'o + ReturnTypeReflection + Marshal<'o>,
-                    'r: 'o,
-                {
-                    #[allow(clippy::cast_ref_to_mut)]
-                    let runtime = &mut *(runtime as *const Runtime as *mut Runtime);
-
-                    eprintln!("{}", self.msg);
-                    while !runtime.update() {
-                        // Wait until there has been an update that might fix the error
-                    }
-                    $crate::Runtime::$FnName(runtime, self.function_name, $(self.$Arg,)*)
-                }
             }
 
             impl Runtime {
@@ -126,20 +150,26 @@ macro_rules! invoke_fn_impl {
                 where
                     'r: 'o,
                 {
+                    let mismatch = |message: String| crate::InvokeError::SignatureMismatch {
+                        function_name: function_name.to_owned(),
+                        message,
+                    };
                     match runtime
                         .get_function_definition(function_name)
-                        .ok_or_else(|| format!("Failed to obtain function '{}'", function_name))
+                        .ok_or_else(|| crate::InvokeError::FunctionNotFound {
+                            function_name: function_name.to_owned(),
+                        })
                         .and_then(|function_info| {
                             // Validate function signature
                             let num_args = $crate::count_args!($($T),*);
 
                             let arg_types = function_info.prototype.signature.arg_types();
                             if arg_types.len() != num_args {
-                                return Err(format!(
+                                return Err(mismatch(format!(
                                     "Invalid number of arguments. Expected: {}. Found: {}.",
                                     arg_types.len(),
                                     num_args,
-                                ));
+                                )));
                             }
 
                             #[allow(unused_mut, unused_variables)]
@@ -147,13 +177,16 @@ macro_rules! invoke_fn_impl {
                             $(
                                 crate::reflection::equals_argument_type(runtime, &arg_types[idx], &$Arg)
                                     .map_err(|(expected, found)| {
-                                        format!(
+                                        mismatch(format!(
                                             "Invalid argument type at index {}. Expected: {}. Found: {}.",
                                             idx,
                                             expected,
                                             found,
-                                        )
+                                        ))
                                     })?;
+                                $T::validate_layout(&arg_types[idx]).map_err(|e| {
+                                    mismatch(format!("Invalid argument type at index {}. {}", idx, e))
+                                })?;
                                 idx += 1;
                             )*
 
@@ -164,23 +197,47 @@ macro_rules! invoke_fn_impl {
                             } else {
                                 Ok(())
                             }.map_err(|(expected, found)| {
-                                format!(
+                                mismatch(format!(
                                     "Invalid return type. Expected: {}. Found: {}",
                                     expected,
                                     found,
-                                )
+                                ))
                             })?;
+                            if let Some(return_type) = function_info.prototype.signature.return_type() {
+                                Output::validate_layout(return_type)
+                                    .map_err(|e| mismatch(format!("Invalid return type. {}", e)))?;
+                            }
 
                             Ok(function_info)
                         }) {
                         Ok(function_info) => {
-                            let function: fn($($T::MunType),*) -> Output::MunType = unsafe {
+                            let function: extern "C-unwind" fn($($T::MunType),*) -> Output::MunType = unsafe {
                                 core::mem::transmute(function_info.fn_ptr)
                             };
-                            let result = function($($Arg.marshal_into()),*);
+                            #[allow(unused_variables)]
+                            let arg_types = function_info.prototype.signature.arg_types();
+                            // A Mun function unwinds if it traps (e.g. on a division by zero)
+                            let _invocation = runtime.invocation_lock.read_recursive();
+                            let _stack_guard = crate::stack::StackGuard::enter(runtime.max_stack_size);
+                            let _interrupt_guard = crate::interrupt::InterruptGuard::enter(&runtime.invoke_limits);
+                            let result = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+                                #[allow(unused_mut, unused_variables)]
+                                let mut idx = 0;
+                                function($({
+                                    let arg = $Arg.marshal_into_arg(runtime, arg_types[idx]);
+                                    idx += 1;
+                                    arg
+                                }),*)
+                            }));
 
-                            // Marshall the result
-                            return Ok(Marshal::marshal_from(result, runtime))
+                            match result {
+                                // Marshall the result
+                                Ok(result) => Ok(Marshal::marshal_from(result, runtime)),
+                                Err(payload) => Err($ErrName::without_arguments(
+                                    crate::InvokeError::from_unwind(function_name, payload),
+                                    function_name,
+                                )),
+                            }
                         }
                         Err(e) => Err($ErrName::new(e, function_name, $($Arg),*))
                     }
@@ -259,3 +316,38 @@ macro_rules! invoke_fn {
         )
     };
 }
+
+/// Returns the [`StaticAssembly`] of the munlib that is linked into the executable, which can be
+/// loaded with [`RuntimeBuilder::from_static`]. Only a single munlib can be linked into an
+/// executable, as the functions of every munlib have the same names.
+///
+/// [`StaticAssembly`]: struct.StaticAssembly.html
+/// [`RuntimeBuilder::from_static`]: struct.RuntimeBuilder.html#method.from_static
+#[macro_export]
+macro_rules! static_assembly {
+    () => {{
+        extern "C" {
+            fn get_info() -> $crate::AssemblyInfo;
+            fn get_version() -> u32;
+            fn set_allocator_handle(handle: *mut ::std::ffi::c_void);
+        }
+
+        extern "C" fn get_info_wrapper() -> $crate::AssemblyInfo {
+            unsafe { get_info() }
+        }
+
+        extern "C" fn get_version_wrapper() -> u32 {
+            unsafe { get_version() }
+        }
+
+        extern "C" fn set_allocator_handle_wrapper(handle: *mut ::std::ffi::c_void) {
+            unsafe { set_allocator_handle(handle) }
+        }
+
+        $crate::StaticAssembly {
+            get_info: get_info_wrapper,
+            get_version: get_version_wrapper,
+            set_allocator_handle: set_allocator_handle_wrapper,
+        }
+    }};
+}
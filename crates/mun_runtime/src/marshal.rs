@@ -18,6 +18,12 @@ pub trait Marshal<'t>: Sized {
     /// Marshals itself into a `Marshalled` value (i.e. Rust -> Mun).
     fn marshal_into(self) -> Self::MunType;
 
+    /// Marshals itself into a `Marshalled` value that is passed as an argument of type `type_info`
+    /// to a Mun function (i.e. Rust -> Mun).
+    fn marshal_into_arg(self, _runtime: &Runtime, _type_info: &abi::TypeInfo) -> Self::MunType {
+        self.marshal_into()
+    }
+
     /// Marshals the value at memory location `ptr` into a `Marshalled` value (i.e. Mun -> Rust).
     fn marshal_from_ptr<'r>(
         ptr: NonNull<Self::MunType>,
@@ -30,4 +36,10 @@ pub trait Marshal<'t>: Sized {
 
     /// Marshals `value` to memory location `ptr` (i.e. Rust -> Mun).
     fn marshal_to_ptr(value: Self, ptr: NonNull<Self::MunType>, type_info: Option<&abi::TypeInfo>);
+
+    /// Verifies that the memory layout of the type matches `type_info`, after its `Guid` has been
+    /// matched.
+    fn validate_layout(_type_info: &abi::TypeInfo) -> Result<(), String> {
+        Ok(())
+    }
 }
@@ -0,0 +1,99 @@
+use crate::{garbage_collector::UnsafeTypeInfo, reflection::ReturnTypeReflection};
+use memory::{mapping::MigrationFn, TypeDesc};
+use std::{ptr::NonNull, sync::Arc};
+
+/// The old and the new memory of an object that is migrated to a new layout of its struct during a
+/// hot reload, see [`Runtime::add_migration`].
+///
+/// When the migration is invoked, the fields of the new struct that could be mapped automatically
+/// have already been mapped and all other fields are zero-initialized.
+///
+/// [`Runtime::add_migration`]: struct.Runtime.html#method.add_migration
+pub struct StructMigration<'a> {
+    old_ty: &'a abi::TypeInfo,
+    old: NonNull<u8>,
+    new_ty: &'a abi::TypeInfo,
+    new: NonNull<u8>,
+}
+
+impl<'a> StructMigration<'a> {
+    /// Returns the type information of the old struct.
+    pub fn old_type_info(&self) -> &abi::TypeInfo {
+        self.old_ty
+    }
+
+    /// Returns the type information of the new struct.
+    pub fn new_type_info(&self) -> &abi::TypeInfo {
+        self.new_ty
+    }
+
+    /// Retrieves the value of the field corresponding to `field_name` of the old struct.
+    pub fn get<T: ReturnTypeReflection + Copy>(&self, field_name: &str) -> Result<T, String> {
+        let field_ptr = field_ptr::<T>(self.old_ty, self.old, field_name)?;
+        Ok(unsafe { *field_ptr.as_ptr() })
+    }
+
+    /// Replaces the value of the field corresponding to `field_name` of the new struct.
+    pub fn set<T: ReturnTypeReflection + Copy>(
+        &mut self,
+        field_name: &str,
+        value: T,
+    ) -> Result<(), String> {
+        let field_ptr = field_ptr::<T>(self.new_ty, self.new, field_name)?;
+        unsafe { *field_ptr.as_ptr() = value };
+        Ok(())
+    }
+}
+
+/// Returns a pointer to the field corresponding to `field_name` of the struct of type `type_info`
+/// at `ptr`, if the field is of type `T`. Fields that refer to a gc struct cannot be accessed, as
+/// they contain a handle instead of the struct's memory.
+fn field_ptr<T: ReturnTypeReflection>(
+    type_info: &abi::TypeInfo,
+    ptr: NonNull<u8>,
+    field_name: &str,
+) -> Result<NonNull<T>, String> {
+    // Safety: migrations are only registered for struct types.
+    let struct_info = type_info.as_struct().unwrap();
+    let field_idx = abi::StructInfo::find_field_index(type_info.name(), struct_info, field_name)?;
+
+    // Safety: If we found the `field_idx`, we are guaranteed to also have the `field_type` and
+    // `field_offset`.
+    let field_type = unsafe { struct_info.field_types().get_unchecked(field_idx) };
+    let is_gc_struct = field_type
+        .as_struct()
+        .map_or(false, |s| s.memory_kind == abi::StructMemoryKind::GC);
+    if field_type.guid != T::type_guid() || is_gc_struct {
+        return Err(format!(
+            "Mismatched types for `{}::{}`. Expected: `{}`. Found: `{}`.",
+            type_info.name(),
+            field_name,
+            field_type.name(),
+            T::type_name(),
+        ));
+    }
+
+    let offset = unsafe { *struct_info.field_offsets().get_unchecked(field_idx) };
+    Ok(unsafe { NonNull::new_unchecked(ptr.as_ptr().add(offset as usize).cast::<T>()) })
+}
+
+/// Wraps a user-defined `migration` into a function that can be invoked by the garbage collector
+/// while mapping memory. Errors are reported as warnings, as the hot reload cannot be aborted at
+/// that point.
+pub(crate) fn into_migration_fn<F>(migration: F) -> MigrationFn<UnsafeTypeInfo>
+where
+    F: Fn(&mut StructMigration) -> Result<(), String> + Send + Sync + 'static,
+{
+    Arc::new(move |old_ty, old, new_ty, new| {
+        let mut struct_migration = StructMigration {
+            // Safety: the types of both versions of the assembly are alive during the mapping.
+            old_ty: unsafe { old_ty.into_inner().as_ref() },
+            old,
+            new_ty: unsafe { new_ty.into_inner().as_ref() },
+            new,
+        };
+        if let Err(e) = migration(&mut struct_migration) {
+            tracing::warn!("failed to migrate `{}`: {}", new_ty.name(), e);
+        }
+    })
+}
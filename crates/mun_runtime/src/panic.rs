@@ -0,0 +1,90 @@
+use std::{any::Any, ffi, fmt};
+
+/// Describes why the invocation of a Mun function was aborted, e.g. because it called
+/// `core::panic()`, an assertion failed, or an integer was divided by zero.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Panic {
+    message: String,
+    line: u32,
+    column: u32,
+}
+
+impl Panic {
+    /// Returns the message of the panic.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the one-based line in the source code at which the panic occurred.
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// Returns the one-based column in the source code at which the panic occurred.
+    pub fn column(&self) -> u32 {
+        self.column
+    }
+}
+
+impl fmt::Display for Panic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}:{}", self.message, self.line, self.column)
+    }
+}
+
+/// Returns the message of a panic that was caught while invoking a function.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(panic) = payload.downcast_ref::<Panic>() {
+        panic.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else {
+        "unknown error".to_owned()
+    }
+}
+
+/// The `panic` intrinsic that is called by Mun code. It unwinds the stack up to the invocation of
+/// the Mun function, where the `Panic` is caught and returned as an error. Unwinding out of an
+/// `extern "C"` function aborts the process, so the intrinsics that unwind use the `C-unwind` ABI.
+pub(crate) extern "C-unwind" fn panic(message: *const u8, line: u32, column: u32) {
+    // Safety: the Mun Compiler guarantees that `panic` is always called with a null-terminated
+    // string.
+    let message = unsafe { ffi::CStr::from_ptr(message as *const _) };
+    std::panic::resume_unwind(Box::new(Panic {
+        message: message.to_string_lossy().into_owned(),
+        line,
+        column,
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{panic, Panic};
+    use crate::{IntoFunctionDefinition, InvokeError};
+
+    #[test]
+    fn panic_unwinds_to_invocation() {
+        // Mun code calls the intrinsic through the function pointer in its dispatch table
+        let (fn_def, _storage) = IntoFunctionDefinition::into(
+            panic as extern "C-unwind" fn(*const u8, u32, u32),
+            "panic",
+        );
+        let panic_fn: extern "C-unwind" fn(*const u8, u32, u32) =
+            unsafe { std::mem::transmute(fn_def.fn_ptr) };
+
+        let payload = std::panic::catch_unwind(|| panic_fn(b"not implemented\0".as_ptr(), 13, 9))
+            .expect_err("the intrinsic must unwind");
+        let error = InvokeError::from_unwind("unimplemented", payload);
+        assert_eq!(
+            error.panic(),
+            Some(&Panic {
+                message: "not implemented".to_owned(),
+                line: 13,
+                column: 9,
+            })
+        );
+        assert_eq!(error.function_name(), "unimplemented");
+    }
+}
@@ -26,7 +26,11 @@ pub fn equals_return_type<T: ReturnTypeReflection>(
             }
         }
         abi::TypeGroup::StructTypes => {
-            if <StructRef as ReturnTypeReflection>::type_guid() != T::type_guid() {
+            // A struct is either returned as a `StructRef` or copied into a value struct with
+            // the same `Guid`
+            if type_info.guid != T::type_guid()
+                && <StructRef as ReturnTypeReflection>::type_guid() != T::type_guid()
+            {
                 return Err(("struct", T::type_name()));
             }
         }
@@ -117,8 +121,9 @@ macro_rules! impl_primitive_type {
 }
 
 impl_primitive_type!(
-    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, bool
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, bool, char
 );
+impl_primitive_type!(abi::Handle);
 
 impl ReturnTypeReflection for () {
     fn type_name() -> &'static str {
@@ -128,9 +133,7 @@ impl ReturnTypeReflection for () {
     fn type_guid() -> abi::Guid {
         // TODO: Once `const_fn` lands, replace this with a const md5 hash
         static GUID: OnceCell<abi::Guid> = OnceCell::new();
-        *GUID.get_or_init(|| abi::Guid {
-            b: md5::compute(Self::type_name()).0,
-        })
+        *GUID.get_or_init(|| abi::Guid::from_name(Self::type_name()))
     }
 }
 impl<'t> Marshal<'t> for () {
@@ -0,0 +1,124 @@
+use crate::{garbage_collector::GarbageCollector, Assembly};
+use anyhow::Error;
+use std::{
+    collections::BTreeSet,
+    path::PathBuf,
+    sync::{
+        mpsc::{channel, Receiver},
+        Arc,
+    },
+    thread,
+};
+
+/// Describes how the public functions and types of an assembly change when it is hot reloaded.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReloadChanges {
+    functions: Vec<String>,
+    types: Vec<String>,
+}
+
+impl ReloadChanges {
+    /// Determines the changes between the `old` and the `new` version of an assembly.
+    pub(crate) fn new(old: &Assembly, new: &Assembly) -> Self {
+        let mut functions = BTreeSet::new();
+        for old_fn in old.functions() {
+            let new_fn = new
+                .functions()
+                .iter()
+                .find(|new_fn| new_fn.prototype.name() == old_fn.prototype.name());
+            match new_fn {
+                Some(new_fn) if new_fn.prototype.signature == old_fn.prototype.signature => (),
+                _ => {
+                    functions.insert(old_fn.prototype.name().to_string());
+                }
+            }
+        }
+        for new_fn in new.functions() {
+            if old
+                .functions()
+                .iter()
+                .all(|old_fn| old_fn.prototype.name() != new_fn.prototype.name())
+            {
+                functions.insert(new_fn.prototype.name().to_string());
+            }
+        }
+
+        // The `Guid` of a type is derived from its name and fields, so any change of its layout
+        // results in a different `Guid`.
+        let mut types = BTreeSet::new();
+        for (assembly, other_assembly) in [(old, new), (new, old)].iter() {
+            for ty in assembly.types() {
+                if other_assembly
+                    .types()
+                    .iter()
+                    .all(|other| other.guid != ty.guid)
+                {
+                    types.insert(ty.name().to_string());
+                }
+            }
+        }
+
+        Self {
+            functions: functions.into_iter().collect(),
+            types: types.into_iter().collect(),
+        }
+    }
+
+    /// Returns the names of the public functions that were added, removed, or of which the
+    /// signature changed. Functions of which only the body changed are not included.
+    pub fn functions(&self) -> &[String] {
+        &self.functions
+    }
+
+    /// Returns the names of the types that were added, removed, or of which the fields changed.
+    pub fn types(&self) -> &[String] {
+        &self.types
+    }
+
+    /// Returns `true` if neither the signature of a public function nor a type changed.
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty() && self.types.is_empty()
+    }
+}
+
+type Hook<T> = Box<dyn FnMut(&Assembly, &T) + Send + Sync>;
+
+/// The callbacks that are invoked when an assembly is hot reloaded.
+#[derive(Default)]
+pub(crate) struct ReloadHooks {
+    pub before_reload: Vec<Hook<ReloadChanges>>,
+    pub after_reload: Vec<Hook<ReloadChanges>>,
+    pub reload_failed: Vec<Hook<Error>>,
+}
+
+/// An `Assembly` that is sent from the thread that loaded it.
+pub(crate) struct LoadedAssembly(pub Assembly);
+
+// Safety: The information of an assembly only refers to the data of its library, which remains
+// loaded for as long as the `Assembly` is alive, regardless of the thread that owns it.
+unsafe impl Send for LoadedAssembly {}
+
+/// An assembly that is being loaded on a background thread, see [`Runtime::update_async`].
+///
+/// [`Runtime::update_async`]: struct.Runtime.html#method.update_async
+pub(crate) struct PendingReload {
+    pub library_path: PathBuf,
+    pub receiver: Receiver<Result<LoadedAssembly, Error>>,
+}
+
+impl PendingReload {
+    /// Starts loading the assembly at `library_path` on a background thread.
+    pub fn spawn(library_path: PathBuf, gc: Arc<GarbageCollector>) -> Self {
+        let (sender, receiver) = channel();
+        let path = library_path.clone();
+        thread::spawn(move || {
+            let new_assembly = Assembly::load_unlinked(&path, gc).map(LoadedAssembly);
+            // The runtime might have been dropped in the meantime
+            let _ = sender.send(new_assembly);
+        });
+        PendingReload {
+            library_path,
+            receiver,
+        }
+    }
+}
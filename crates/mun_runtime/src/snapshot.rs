@@ -0,0 +1,292 @@
+use crate::{
+    garbage_collector::{GarbageCollector, GcPtr, UnsafeTypeInfo},
+    Runtime,
+};
+use anyhow::{anyhow, Error};
+use memory::gc::{GcRuntime, HasIndirectionPtr, RawGcPtr};
+use std::{collections::HashMap, convert::TryInto, ptr::NonNull};
+
+/// Identifies the binary format of a heap snapshot.
+const MAGIC: &[u8; 8] = b"MUNHEAP\0";
+
+/// The version of the snapshot format, which is incremented for every incompatible change.
+const VERSION: u32 = 1;
+
+/// Serializes the objects `roots` and all objects that are reachable from them.
+///
+/// A snapshot consists of a header, a table of the types of all objects, the fields of all objects
+/// and the indices of the roots. Fundamental values are stored in little-endian byte order and
+/// references to objects as an index into the serialized objects, so the snapshot does not depend
+/// on the memory layout of the platform.
+pub(crate) fn save(runtime: &Runtime, roots: &[GcPtr]) -> Vec<u8> {
+    let mut writer = Writer {
+        gc: &runtime.gc,
+        objects: Vec::new(),
+        object_indices: HashMap::new(),
+        types: Vec::new(),
+        type_indices: HashMap::new(),
+        buf: Vec::new(),
+    };
+
+    let roots: Vec<u32> = roots
+        .iter()
+        .map(|root| writer.object_index(*root))
+        .collect();
+
+    // Objects that are discovered while serializing are appended to the list of objects
+    let mut index = 0;
+    while index < writer.objects.len() {
+        writer.write_object(writer.objects[index]);
+        index += 1;
+    }
+
+    let mut snapshot = Vec::new();
+    snapshot.extend_from_slice(MAGIC);
+    write_u32(&mut snapshot, VERSION);
+    write_u32(&mut snapshot, writer.types.len() as u32);
+    for ty in writer.types.iter() {
+        snapshot.extend_from_slice(&ty.guid.b);
+        write_u32(&mut snapshot, ty.name().len() as u32);
+        snapshot.extend_from_slice(ty.name().as_bytes());
+    }
+    write_u32(&mut snapshot, writer.objects.len() as u32);
+    snapshot.append(&mut writer.buf);
+    write_u32(&mut snapshot, roots.len() as u32);
+    for root in roots {
+        write_u32(&mut snapshot, root);
+    }
+    snapshot
+}
+
+/// Deserializes the objects of a snapshot that was created with `save`, allocating them in the
+/// garbage collector of `runtime`. Returns the roots of the snapshot, which are not rooted.
+pub(crate) fn restore(runtime: &Runtime, snapshot: &[u8]) -> Result<Vec<GcPtr>, Error> {
+    let mut reader = Reader {
+        data: snapshot,
+        pos: 0,
+    };
+
+    if reader.read_bytes(MAGIC.len())? != MAGIC {
+        return Err(anyhow!("the data is not a heap snapshot"));
+    }
+    let version = reader.read_u32()?;
+    if version != VERSION {
+        return Err(anyhow!(
+            "unsupported snapshot version: {}. Expected: {}.",
+            version,
+            VERSION
+        ));
+    }
+
+    let type_count = reader.read_u32()?;
+    let mut types = Vec::with_capacity(type_count as usize);
+    for _ in 0..type_count {
+        let mut guid = abi::Guid { b: [0; 16] };
+        guid.b.copy_from_slice(reader.read_bytes(16)?);
+        let name_len = reader.read_u32()? as usize;
+        let name = String::from_utf8_lossy(reader.read_bytes(name_len)?).into_owned();
+
+        // The `Guid` of a type is derived from its name and fields, so a type with the same `Guid`
+        // has the same fields.
+        let ty = runtime
+            .types()
+            .find(|ty| ty.guid == guid)
+            .ok_or_else(|| anyhow!("the type `{}` of the snapshot is not loaded", name))?;
+        types.push(ty);
+    }
+
+    // Objects of an invalid snapshot might contain invalid references, so they must not be traced
+    let mut objects = Vec::new();
+    let roots = read_objects(runtime, &mut reader, &types, &mut objects);
+    if roots.is_err() {
+        for obj in objects {
+            runtime.gc.dealloc(obj);
+        }
+    }
+    roots
+}
+
+/// Deserializes and allocates the objects of a snapshot, which are stored in `objects`. Returns
+/// the roots of the snapshot.
+fn read_objects(
+    runtime: &Runtime,
+    reader: &mut Reader,
+    types: &[&abi::TypeInfo],
+    objects: &mut Vec<GcPtr>,
+) -> Result<Vec<GcPtr>, Error> {
+    let object_count = reader.read_u32()?;
+    objects.reserve(object_count as usize);
+    let mut references = Vec::new();
+    for _ in 0..object_count {
+        let type_index = reader.read_u32()? as usize;
+        let ty = *types
+            .get(type_index)
+            .ok_or_else(|| anyhow!("invalid type index: {}", type_index))?;
+
+        let mut obj = runtime.gc.alloc(UnsafeTypeInfo::new(NonNull::from(ty)));
+        objects.push(obj);
+        reader.read_fields(ty, unsafe { obj.deref_mut::<u8>() }, &mut references)?;
+    }
+
+    // References can only be stored once all objects have been allocated
+    for (field, index) in references {
+        let obj = if index == 0 {
+            (std::ptr::null() as RawGcPtr).into()
+        } else {
+            *objects
+                .get(index as usize - 1)
+                .ok_or_else(|| anyhow!("invalid object index: {}", index - 1))?
+        };
+        unsafe { *field = obj };
+    }
+    for obj in objects.iter() {
+        runtime.gc.write_barrier(*obj);
+    }
+
+    let root_count = reader.read_u32()?;
+    let mut roots = Vec::with_capacity(root_count as usize);
+    for _ in 0..root_count {
+        let index = reader.read_u32()? as usize;
+        let root = objects
+            .get(index)
+            .ok_or_else(|| anyhow!("invalid object index: {}", index))?;
+        roots.push(*root);
+    }
+
+    if reader.pos != reader.data.len() {
+        return Err(anyhow!("unexpected data at the end of the snapshot"));
+    }
+    Ok(roots)
+}
+
+/// Serializes objects, and the objects that they reference.
+struct Writer<'a> {
+    gc: &'a GarbageCollector,
+    objects: Vec<GcPtr>,
+    object_indices: HashMap<GcPtr, u32>,
+    types: Vec<&'a abi::TypeInfo>,
+    type_indices: HashMap<abi::Guid, u32>,
+    buf: Vec<u8>,
+}
+
+impl<'a> Writer<'a> {
+    /// Returns the index of the specified `obj`, adding it to the objects that are serialized if
+    /// it was not encountered before.
+    fn object_index(&mut self, obj: GcPtr) -> u32 {
+        let objects = &mut self.objects;
+        *self.object_indices.entry(obj).or_insert_with(|| {
+            objects.push(obj);
+            (objects.len() - 1) as u32
+        })
+    }
+
+    /// Serializes the type and fields of the specified `obj`.
+    fn write_object(&mut self, obj: GcPtr) {
+        // Safety: The type of an object lives at least as long as the `Runtime`.
+        let ty = unsafe { &*self.gc.ptr_type(obj).into_inner().as_ptr() };
+        let types = &mut self.types;
+        let type_index = *self.type_indices.entry(ty.guid).or_insert_with(|| {
+            types.push(ty);
+            (types.len() - 1) as u32
+        });
+
+        write_u32(&mut self.buf, type_index);
+        unsafe { self.write_fields(ty, obj.deref::<u8>()) };
+    }
+
+    /// Serializes the value of type `ty` at `ptr`.
+    unsafe fn write_fields(&mut self, ty: &abi::TypeInfo, ptr: *const u8) {
+        if let Some(struct_info) = ty.as_struct() {
+            let fields = struct_info
+                .field_types()
+                .iter()
+                .zip(struct_info.field_offsets().iter());
+            for (field_ty, offset) in fields {
+                let field_ptr = ptr.add(*offset as usize);
+                let is_gc_struct = field_ty
+                    .as_struct()
+                    .map_or(false, |s| s.memory_kind == abi::StructMemoryKind::GC);
+                if is_gc_struct {
+                    // References are stored as the index of the object plus one, so zero
+                    // represents a null reference.
+                    let field = *field_ptr.cast::<GcPtr>();
+                    let raw: RawGcPtr = field.into();
+                    let index = if raw.is_null() {
+                        0
+                    } else {
+                        self.object_index(field) + 1
+                    };
+                    write_u32(&mut self.buf, index);
+                } else {
+                    self.write_fields(field_ty, field_ptr);
+                }
+            }
+        } else {
+            let mut bytes = std::slice::from_raw_parts(ptr, ty.size_in_bytes()).to_vec();
+            if cfg!(target_endian = "big") {
+                bytes.reverse();
+            }
+            self.buf.append(&mut bytes);
+        }
+    }
+}
+
+/// Deserializes the data of a snapshot.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| anyhow!("unexpected end of the snapshot"))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Deserializes a value of type `ty` into the memory at `ptr`. The references to other objects
+    /// are stored in `references`, together with the index of the object that they refer to.
+    fn read_fields(
+        &mut self,
+        ty: &abi::TypeInfo,
+        ptr: *mut u8,
+        references: &mut Vec<(*mut GcPtr, u32)>,
+    ) -> Result<(), Error> {
+        if let Some(struct_info) = ty.as_struct() {
+            let fields = struct_info
+                .field_types()
+                .iter()
+                .zip(struct_info.field_offsets().iter());
+            for (field_ty, offset) in fields {
+                let field_ptr = unsafe { ptr.add(*offset as usize) };
+                let is_gc_struct = field_ty
+                    .as_struct()
+                    .map_or(false, |s| s.memory_kind == abi::StructMemoryKind::GC);
+                if is_gc_struct {
+                    references.push((field_ptr.cast::<GcPtr>(), self.read_u32()?));
+                } else {
+                    self.read_fields(field_ty, field_ptr, references)?;
+                }
+            }
+        } else {
+            let mut bytes = self.read_bytes(ty.size_in_bytes())?.to_vec();
+            if cfg!(target_endian = "big") {
+                bytes.reverse();
+            }
+            unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len()) };
+        }
+        Ok(())
+    }
+}
+
+fn write_u32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
@@ -0,0 +1,49 @@
+use std::cell::Cell;
+
+/// The number of bytes of stack that an invocation of a Mun function may use, if not specified
+/// otherwise.
+pub const DEFAULT_MAX_STACK_SIZE: usize = 1024 * 1024;
+
+thread_local! {
+    /// The lowest address of the stack that Mun code on this thread may use, or zero if no Mun
+    /// function is being invoked.
+    static STACK_LIMIT: Cell<usize> = Cell::new(0);
+}
+
+/// The `stack_limit` intrinsic that is called by recursive Mun functions. A function panics if its
+/// frame lies below the returned address.
+pub(crate) extern "C" fn stack_limit() -> *const u8 {
+    STACK_LIMIT.with(|limit| limit.get() as *const u8)
+}
+
+/// Limits the stack that is available to Mun code for as long as the guard is alive. Only the
+/// outermost invocation of a Mun function sets the limit, so invocations from within Mun code,
+/// e.g. by an extern function, share the stack of the outer invocation.
+pub(crate) struct StackGuard {
+    is_outermost: bool,
+}
+
+impl StackGuard {
+    /// Limits the stack of Mun code to `max_stack_size` bytes below the current frame.
+    pub fn enter(max_stack_size: usize) -> Self {
+        let frame = 0u8;
+        let frame_address = &frame as *const u8 as usize;
+        let is_outermost = STACK_LIMIT.with(|limit| {
+            if limit.get() == 0 {
+                limit.set(frame_address.saturating_sub(max_stack_size).max(1));
+                true
+            } else {
+                false
+            }
+        });
+        StackGuard { is_outermost }
+    }
+}
+
+impl Drop for StackGuard {
+    fn drop(&mut self) {
+        if self.is_outermost {
+            STACK_LIMIT.with(|limit| limit.set(0));
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{adt::RawStruct, garbage_collector::UnsafeTypeInfo, Runtime};
+use memory::gc::HasIndirectionPtr;
+use std::ptr::{self, NonNull};
+
+/// A `#[repr(C)]` Rust struct that has the same memory layout as a Mun value struct. Values of the
+/// struct are copied to and from Mun, instead of being accessed through a [`StructRef`].
+///
+/// This trait should not be implemented manually; use the [`value_struct!`] macro instead.
+///
+/// # Safety
+///
+/// The struct must be `#[repr(C)]` and its fields must have the same names and types, in the same
+/// order, as the fields of the Mun struct.
+///
+/// [`StructRef`]: struct.StructRef.html
+/// [`value_struct!`]: macro.value_struct.html
+pub unsafe trait ValueStruct: Copy {
+    /// Retrieves the name of the Mun struct.
+    fn type_name() -> &'static str;
+
+    /// Retrieves the `Guid` of the Mun struct, which is derived from the names and the types of
+    /// its fields.
+    fn type_guid() -> abi::Guid;
+
+    /// Verifies that the memory layout of the struct matches the layout of `type_info`, which must
+    /// describe a value struct with the same `Guid`.
+    #[doc(hidden)]
+    fn validate_layout(type_info: &abi::TypeInfo) -> Result<(), String> {
+        let struct_info = match type_info.as_struct() {
+            Some(struct_info) => struct_info,
+            None => return Err(format!("`{}` is not a struct", type_info.name())),
+        };
+        if struct_info.memory_kind != abi::StructMemoryKind::Value {
+            return Err(format!(
+                "`{}` is a gc struct and cannot be marshalled by value",
+                type_info.name()
+            ));
+        }
+        if type_info.size_in_bytes() != std::mem::size_of::<Self>()
+            || type_info.alignment() != std::mem::align_of::<Self>()
+        {
+            return Err(format!(
+                "The layout of `{}` does not match. Expected: {} bytes, aligned to {} bytes. \
+                 Found: {} bytes, aligned to {} bytes.",
+                type_info.name(),
+                type_info.size_in_bytes(),
+                type_info.alignment(),
+                std::mem::size_of::<Self>(),
+                std::mem::align_of::<Self>(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Copies the struct from the heap-allocated Mun struct `raw`.
+    #[doc(hidden)]
+    fn from_raw(raw: RawStruct) -> Self {
+        unsafe { *(raw.get_ptr() as *const Self) }
+    }
+
+    /// Copies the struct into a new heap-allocated Mun struct of type `type_info`, which is how
+    /// value structs are passed as arguments to Mun functions.
+    #[doc(hidden)]
+    fn into_raw(self, runtime: &Runtime, type_info: &abi::TypeInfo) -> RawStruct {
+        let mut gc_handle = runtime.gc().alloc(
+            // Safety: `type_info` is a shared reference, so is guaranteed to not be `ptr::null()`.
+            UnsafeTypeInfo::new(unsafe {
+                NonNull::new_unchecked(type_info as *const abi::TypeInfo as *mut _)
+            }),
+        );
+        unsafe { ptr::write(gc_handle.deref_mut::<Self>(), self) };
+        RawStruct(gc_handle)
+    }
+
+    /// Copies the struct from the memory location `ptr`, which points to a struct value.
+    #[doc(hidden)]
+    fn from_ptr(ptr: NonNull<u8>) -> Self {
+        unsafe { *ptr.cast::<Self>().as_ptr() }
+    }
+
+    /// Copies the struct to the memory location `ptr`, which points to a struct value.
+    #[doc(hidden)]
+    fn to_ptr(self, ptr: NonNull<u8>) {
+        unsafe { *ptr.cast::<Self>().as_ptr() = self };
+    }
+}
+
+/// Defines a `#[repr(C)]` Rust struct that mirrors a Mun value struct with the same name and
+/// fields, and implements the traits that are required to pass it to and return it from Mun
+/// functions with [`invoke_fn!`]. Fields that are themselves value structs have to be defined
+/// with this macro as well.
+///
+/// When a function is invoked, the layout of the struct is validated against the type information
+/// of the Mun struct. An invocation with a struct that does not match fails instead of misreading
+/// memory.
+///
+/// ```ignore
+/// mun_runtime::value_struct! {
+///     pub struct Vec2 { pub x: f32, pub y: f32 }
+/// }
+/// ```
+///
+/// [`invoke_fn!`]: macro.invoke_fn.html
+#[macro_export]
+macro_rules! value_struct {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $Name:ident { $($field_vis:vis $field:ident: $Ty:ty),* $(,)? }
+    ) => {
+        $(#[$attr])*
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        $vis struct $Name {
+            $($field_vis $field: $Ty,)*
+        }
+
+        unsafe impl $crate::ValueStruct for $Name {
+            fn type_name() -> &'static str {
+                stringify!($Name)
+            }
+
+            fn type_guid() -> $crate::Guid {
+                $crate::Guid::from_struct(
+                    stringify!($Name),
+                    vec![$((
+                        stringify!($field),
+                        <$Ty as $crate::ReturnTypeReflection>::type_guid(),
+                    )),*],
+                )
+            }
+        }
+
+        impl $crate::ArgumentReflection for $Name {
+            fn type_guid(&self, _runtime: &$crate::Runtime) -> $crate::Guid {
+                <Self as $crate::ValueStruct>::type_guid()
+            }
+
+            fn type_name(&self, _runtime: &$crate::Runtime) -> &str {
+                <Self as $crate::ValueStruct>::type_name()
+            }
+        }
+
+        impl $crate::ReturnTypeReflection for $Name {
+            fn type_guid() -> $crate::Guid {
+                <Self as $crate::ValueStruct>::type_guid()
+            }
+
+            fn type_name() -> &'static str {
+                <Self as $crate::ValueStruct>::type_name()
+            }
+        }
+
+        impl<'t> $crate::Marshal<'t> for $Name {
+            type MunType = $crate::RawStruct;
+
+            fn marshal_from<'r>(value: Self::MunType, _runtime: &'r $crate::Runtime) -> Self
+            where
+                Self: 't,
+                'r: 't,
+            {
+                <Self as $crate::ValueStruct>::from_raw(value)
+            }
+
+            fn marshal_into(self) -> Self::MunType {
+                panic!(
+                    "the value struct `{}` can only be marshalled with its type information",
+                    stringify!($Name)
+                )
+            }
+
+            fn marshal_into_arg(
+                self,
+                runtime: &$crate::Runtime,
+                type_info: &$crate::TypeInfo,
+            ) -> Self::MunType {
+                <Self as $crate::ValueStruct>::into_raw(self, runtime, type_info)
+            }
+
+            fn marshal_from_ptr<'r>(
+                ptr: ::std::ptr::NonNull<Self::MunType>,
+                _runtime: &'r $crate::Runtime,
+                _type_info: Option<&$crate::TypeInfo>,
+            ) -> Self
+            where
+                Self: 't,
+                'r: 't,
+            {
+                <Self as $crate::ValueStruct>::from_ptr(ptr.cast())
+            }
+
+            fn marshal_to_ptr(
+                value: Self,
+                ptr: ::std::ptr::NonNull<Self::MunType>,
+                _type_info: Option<&$crate::TypeInfo>,
+            ) {
+                <Self as $crate::ValueStruct>::to_ptr(value, ptr.cast())
+            }
+
+            fn validate_layout(type_info: &$crate::TypeInfo) -> Result<(), String> {
+                <Self as $crate::ValueStruct>::validate_layout(type_info)
+            }
+        }
+    };
+}
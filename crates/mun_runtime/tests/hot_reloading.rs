@@ -1,11 +1,8 @@
-#[macro_use]
-mod util;
-
-use util::*;
+use mun_test::{assert_invoke_eq, CompileAndRunTestDriver};
 
 #[test]
 fn hotreloadable() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r"
     pub fn main() -> i32 { 5 }
     ",
@@ -22,9 +19,55 @@ fn hotreloadable() {
     assert_invoke_eq!(i32, 10, driver, "main");
 }
 
+#[test]
+fn hotreload_async() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn main() -> i32 { 5 }
+    ",
+    );
+    assert_invoke_eq!(i32, 5, driver, "main");
+
+    driver.update_assembly(
+        r"
+    pub fn main() -> i32 { 10 }
+    ",
+    );
+    let runtime = driver.runtime();
+    let start_time = std::time::Instant::now();
+    while !runtime.borrow_mut().update_async() {
+        // The previous version can still be invoked while the new version is loading
+        assert_invoke_eq!(i32, 5, driver, "main");
+        if start_time.elapsed() > std::time::Duration::from_secs(10) {
+            panic!("runtime did not update after recompilation within 10secs");
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    assert_invoke_eq!(i32, 10, driver, "main");
+}
+
+#[test]
+fn hotreload_in_process() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn main() -> i32 { 5 }
+    ",
+    );
+    assert_invoke_eq!(i32, 5, driver, "main");
+
+    let runtime = driver.runtime();
+    driver.update_in_process(
+        runtime.borrow(),
+        r"
+    pub fn main() -> i32 { 10 }
+    ",
+    );
+    assert_invoke_eq!(i32, 10, driver, "main");
+}
+
 #[test]
 fn hotreload_struct_decl() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     struct(gc) Args {
         n: i32,
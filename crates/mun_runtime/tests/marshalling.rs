@@ -1,13 +1,14 @@
-use mun_runtime::{invoke_fn, ArgumentReflection, Marshal, ReturnTypeReflection, StructRef};
-
-#[macro_use]
-mod util;
-
-use util::*;
+use mun_compiler::Config;
+use mun_runtime::{
+    invoke_fn, ArgumentReflection, Handle, InvokeError, Marshal, MunStruct, ReturnTypeReflection,
+    StructRef,
+};
+use mun_test::{assert_invoke_eq, CompileAndRunTestDriver};
+use std::convert::TryFrom;
 
 #[test]
 fn compile_and_run() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r"
         pub fn main() {}
     ",
@@ -17,7 +18,7 @@ fn compile_and_run() {
 
 #[test]
 fn return_value() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r"
         pub fn main()->i32 { 3 }
     ",
@@ -27,7 +28,7 @@ fn return_value() {
 
 #[test]
 fn arguments() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r"
         pub fn main(a:i32, b:i32)->i32 { a+b }
     ",
@@ -39,7 +40,7 @@ fn arguments() {
 
 #[test]
 fn dispatch_table() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r"
         pub fn add(a:i32, b:i32)->i32 { a+b }
         pub fn main(a:i32, b:i32)->i32 { add(a,b) }
@@ -57,7 +58,7 @@ fn dispatch_table() {
 
 #[test]
 fn booleans() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
         pub fn equal(a:i64, b:i64)->bool                 { a==b }
         pub fn equalf(a:f64, b:f64)->bool            { a==b }
@@ -99,9 +100,26 @@ fn booleans() {
     assert_invoke_eq!(bool, true, driver, "greater_equalf", 64f64, 64f64);
 }
 
+#[test]
+fn chars() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+        pub fn smiley() -> char { '😀' }
+        pub fn newline() -> char { '\n' }
+        pub fn is_a(c: char) -> bool { c == 'a' }
+        pub fn identity(c: char) -> char { c }
+    "#,
+    );
+    assert_invoke_eq!(char, '😀', driver, "smiley");
+    assert_invoke_eq!(char, '\n', driver, "newline");
+    assert_invoke_eq!(bool, true, driver, "is_a", 'a');
+    assert_invoke_eq!(bool, false, driver, "is_a", 'b');
+    assert_invoke_eq!(char, 'ß', driver, "identity", 'ß');
+}
+
 #[test]
 fn fibonacci() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     pub fn fibonacci(n:i64)->i64 {
         if n <= 1 {
@@ -120,7 +138,7 @@ fn fibonacci() {
 
 #[test]
 fn fibonacci_loop() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     pub fn fibonacci(n:i64)->i64 {
         let a = 0;
@@ -147,7 +165,7 @@ fn fibonacci_loop() {
 
 #[test]
 fn fibonacci_loop_break() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     pub fn fibonacci(n:i64)->i64 {
         let a = 0;
@@ -172,9 +190,344 @@ fn fibonacci_loop_break() {
     assert_invoke_eq!(i64, 46368, driver, "fibonacci", 24i64);
 }
 
+#[test]
+fn labeled_loops() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    // Returns the number of pairs (i, j) with i < j < n where j is not a multiple of 3, stopping
+    // at the first pair for which i * j exceeds max.
+    pub fn count_pairs(n: i32, max: i32) -> i32 {
+        let count = 0;
+        let i = 0;
+        'outer: loop {
+            if i >= n {
+                break count;
+            }
+            let j = i;
+            'inner: while j < n {
+                j += 1;
+                if j >= n {
+                    break 'inner;
+                }
+                if j % 3 == 0 {
+                    continue 'inner;
+                }
+                if i * j > max {
+                    break 'outer count;
+                }
+                count += 1;
+            }
+            i += 1;
+            continue 'outer;
+        }
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(i32, 0, driver, "count_pairs", 0i32, 100i32);
+    assert_invoke_eq!(i32, 3, driver, "count_pairs", 3i32, 100i32);
+    assert_invoke_eq!(i32, 2, driver, "count_pairs", 4i32, 1i32);
+}
+
+#[test]
+fn source_location() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub fn line() -> u32 {
+        core::source_location().line
+    }
+
+    pub fn column() -> u32 {
+        let loc = core::source_location();
+        loc.column
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(u32, 3, driver, "line");
+    assert_invoke_eq!(u32, 19, driver, "column");
+}
+
+#[test]
+fn source_location_reassign() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub fn line() -> u32 {
+        let loc = core::source_location();
+        loc = core::source_location();
+        loc.line
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(u32, 4, driver, "line");
+}
+
+#[test]
+fn source_location_file() {
+    extern "C" fn file_len(file: Handle) -> u32 {
+        let file = unsafe { std::ffi::CStr::from_ptr(file.0 as *const std::os::raw::c_char) };
+        assert_eq!(file.to_str(), Ok("main.mun"));
+        file.to_bytes().len() as u32
+    }
+
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    extern fn file_len(file: Handle) -> u32;
+
+    pub fn main() -> u32 {
+        file_len(core::source_location().file)
+    }
+    "#,
+    )
+    .insert_fn("file_len", file_len as extern "C" fn(Handle) -> u32);
+
+    assert_invoke_eq!(u32, 8, driver, "main");
+}
+
+#[test]
+fn float_vectors() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub fn length_squared(x: f32, y: f32, z: f32, w: f32) -> f32 {
+        let v = float4(x, y, z, w);
+        let v = v * v;
+        v.x + v.y + v.z + v.w
+    }
+
+    pub fn subtract(a: f32, b: f32) -> f32 {
+        let v = float2(a, 1.0) - float2(b, 1.0);
+        v.y = -v.x;
+        v.y
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(f32, 30.0, driver, "length_squared", 1f32, 2f32, 3f32, 4f32);
+    assert_invoke_eq!(f32, -2.5, driver, "subtract", 3.5f32, 1f32);
+}
+
+#[test]
+fn math_intrinsics() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub fn hypot(x: f64, y: f64) -> f64 {
+        core::sqrt(core::pow(x, 2.0) + core::pow(y, 2.0))
+    }
+
+    pub fn wave(x: f32) -> f32 {
+        core::sin(x) * core::sin(x) + core::cos(x) * core::cos(x)
+    }
+
+    pub fn clamp(value: i32, low: i32, high: i32) -> i32 {
+        core::min(core::max(value, low), high)
+    }
+
+    pub fn distance(a: i64, b: i64) -> i64 {
+        core::abs(a - b)
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(f64, 5.0, driver, "hypot", 3f64, 4f64);
+    assert_invoke_eq!(f32, 1.0, driver, "wave", 0f32);
+    assert_invoke_eq!(i32, 10, driver, "clamp", 12i32, 0i32, 10i32);
+    assert_invoke_eq!(i32, 0, driver, "clamp", -3i32, 0i32, 10i32);
+    assert_invoke_eq!(i64, 7, driver, "distance", 3i64, 10i64);
+}
+
+#[test]
+fn tail_recursion() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub fn sum(n: i64, acc: i64) -> i64 {
+        if n == 0 {
+            acc
+        } else {
+            sum(n - 1, acc + n)
+        }
+    }
+
+    pub fn count_down(n: i64) -> i64 {
+        if n > 0 {
+            return count_down(n - 1);
+        }
+        n
+    }
+    "#,
+    );
+
+    // Without tail calls these would overflow the stack
+    assert_invoke_eq!(i64, 500_000_500_000, driver, "sum", 1_000_000i64, 0i64);
+    assert_invoke_eq!(i64, 0, driver, "count_down", 1_000_000i64);
+}
+
+#[test]
+fn value_struct_returns() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    struct(value) Vec2 { x: f32, y: f32 }
+    struct(value) Vec3 { x: f64, y: f64, z: f64 }
+
+    fn scale2(v: Vec2, s: f32) -> Vec2 {
+        Vec2 { x: v.x * s, y: v.y * s }
+    }
+
+    fn cross(a: Vec3, b: Vec3) -> Vec3 {
+        Vec3 {
+            x: a.y * b.z - a.z * b.y,
+            y: a.z * b.x - a.x * b.z,
+            z: a.x * b.y - a.y * b.x,
+        }
+    }
+
+    pub fn scaled_length_squared(x: f32, y: f32, s: f32) -> f32 {
+        let v = scale2(Vec2 { x, y }, s);
+        v.x * v.x + v.y * v.y
+    }
+
+    pub fn cross_z(x: f64, y: f64) -> f64 {
+        cross(Vec3 { x, y: 0.0, z: 0.0 }, Vec3 { x: 0.0, y, z: 0.0 }).z
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(f32, 20.0, driver, "scaled_length_squared", 1f32, 2f32, 2f32);
+    assert_invoke_eq!(f64, 6.0, driver, "cross_z", 2f64, 3f64);
+}
+
+mun_runtime::value_struct! {
+    #[derive(Debug, PartialEq)]
+    struct Vec2 { x: f32, y: f32 }
+}
+
+mun_runtime::value_struct! {
+    #[derive(Debug, PartialEq)]
+    struct Rect { min: Vec2, max: Vec2 }
+}
+
+#[test]
+fn nested_value_struct_by_value() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct(value) Vec2 { x: f32, y: f32 }
+    pub struct(value) Rect { min: Vec2, max: Vec2 }
+
+    pub fn grow(r: Rect, d: f32) -> Rect {
+        Rect {
+            min: Vec2 { x: r.min.x - d, y: r.min.y - d },
+            max: Vec2 { x: r.max.x + d, y: r.max.y + d },
+        }
+    }
+
+    pub fn area(r: Rect) -> f32 {
+        (r.max.x - r.min.x) * (r.max.y - r.min.y)
+    }
+    "#,
+    );
+
+    let rect = Rect {
+        min: Vec2 { x: 1.0, y: 2.0 },
+        max: Vec2 { x: 3.0, y: 5.0 },
+    };
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+    let grown: Rect = invoke_fn!(runtime_ref, "grow", rect, 1.0f32).unwrap();
+    assert_eq!(
+        grown,
+        Rect {
+            min: Vec2 { x: 0.0, y: 1.0 },
+            max: Vec2 { x: 4.0, y: 6.0 },
+        }
+    );
+    let area: f32 = invoke_fn!(runtime_ref, "area", grown).unwrap();
+    assert_eq!(area, 20.0);
+}
+
+mun_runtime::value_struct! {
+    struct Counter { count: i32 }
+}
+
+#[test]
+fn value_struct_layout_mismatch() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct Counter { count: i32 }
+
+    pub fn new_counter() -> Counter {
+        Counter { count: 0 }
+    }
+    "#,
+    );
+
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+    let result: Result<Counter, _> = invoke_fn!(runtime_ref, "new_counter");
+    let message = result.err().unwrap().to_string();
+    assert!(
+        message.contains("cannot be marshalled by value"),
+        "{}",
+        message
+    );
+}
+
+#[derive(MunStruct, Debug, PartialEq)]
+struct Particle {
+    mass: f32,
+    position: Vec2,
+    alive: bool,
+}
+
+#[derive(MunStruct, Debug)]
+struct Bullet {
+    mass: f64,
+}
+
+#[test]
+fn derived_mun_struct() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct(value) Vec2 { x: f32, y: f32 }
+    pub struct Particle { mass: f32, position: Vec2, alive: bool }
+    pub struct Bullet { mass: f32 }
+
+    pub fn new_particle(mass: f32) -> Particle {
+        Particle { mass, position: Vec2 { x: 1.0, y: 2.0 }, alive: true }
+    }
+
+    pub fn new_bullet() -> Bullet {
+        Bullet { mass: 0.1 }
+    }
+    "#,
+    );
+
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+    let mut particle_ref: StructRef = invoke_fn!(runtime_ref, "new_particle", 3f32).unwrap();
+    let mut particle = Particle::try_from(particle_ref.clone()).unwrap();
+    assert_eq!(
+        particle,
+        Particle {
+            mass: 3.0,
+            position: Vec2 { x: 1.0, y: 2.0 },
+            alive: true,
+        }
+    );
+
+    particle.alive = false;
+    particle.position.y = 4.0;
+    particle.write_to_struct_ref(&mut particle_ref).unwrap();
+    assert_eq!(particle_ref.get::<bool>("alive"), Ok(false));
+    assert_eq!(particle_ref.get::<Vec2>("position").unwrap().y, 4.0);
+
+    let bullet_ref: StructRef = invoke_fn!(runtime_ref, "new_bullet").unwrap();
+    assert!(Bullet::try_from(bullet_ref.clone()).is_err());
+    assert!(Particle::try_from(bullet_ref).is_err());
+}
+
 #[test]
 fn fibonacci_while() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     pub fn fibonacci(n:i64)->i64 {
         let a = 0;
@@ -199,7 +552,7 @@ fn fibonacci_while() {
 
 #[test]
 fn true_is_true() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     pub fn test_true()->bool {
         true
@@ -219,7 +572,7 @@ fn compiler_valid_utf8() {
     use std::ffi::CStr;
     use std::slice;
 
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     struct Foo {
         a: i32,
@@ -267,7 +620,7 @@ fn compiler_valid_utf8() {
 
 #[test]
 fn fields() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
         struct(gc) Foo { a:i32, b:i32 };
         pub fn main(foo:i32)->bool {
@@ -284,7 +637,7 @@ fn fields() {
 
 #[test]
 fn field_crash() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     struct(gc) Foo { a: i32 };
 
@@ -299,7 +652,7 @@ fn field_crash() {
 
 #[test]
 fn marshal_struct() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     struct(value) Foo { a: i32, b: bool };
     struct Bar(i32, bool);
@@ -489,6 +842,48 @@ fn marshal_struct() {
     assert!(bar_err.is_err());
 }
 
+#[test]
+fn struct_fields() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct Foo { a: i32, b: bool, c: Bar }
+    pub struct Bar(f64);
+
+    pub fn foo_new(a: i32, b: bool, c: f64) -> Foo {
+        Foo { a, b, c: Bar(c) }
+    }
+    "#,
+    );
+
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+    let foo: StructRef = invoke_fn!(runtime_ref, "foo_new", 3i32, true, 1.5f64).unwrap();
+
+    let fields: Vec<_> = foo
+        .fields()
+        .map(|(name, type_info)| (name.to_string(), type_info.name().to_string()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            ("a".to_string(), "core::i32".to_string()),
+            ("b".to_string(), "core::bool".to_string()),
+            ("c".to_string(), "Bar".to_string()),
+        ]
+    );
+
+    assert_eq!(foo.get_by_index::<i32>(0), Ok(3));
+    assert_eq!(foo.get_by_index::<bool>(1), Ok(true));
+    let bar = foo.get_by_index::<StructRef>(2).unwrap();
+    assert_eq!(bar.get_by_index::<f64>(0), Ok(1.5));
+
+    // Specify invalid return type
+    assert!(foo.get_by_index::<f64>(0).is_err());
+
+    // Specify invalid index
+    assert!(foo.get_by_index::<i32>(3).is_err());
+}
+
 #[test]
 fn extern_fn() {
     extern "C" fn add_int(a: i32, b: i32) -> i32 {
@@ -496,7 +891,7 @@ fn extern_fn() {
         a + b + 9
     }
 
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     extern fn add(a: i32, b: i32) -> i32;
     pub fn main() -> i32 {
@@ -508,10 +903,102 @@ fn extern_fn() {
     assert_invoke_eq!(i32, 16, driver, "main");
 }
 
+#[test]
+fn extern_fn_with_handles() {
+    extern "C" fn spawn(id: u32) -> Handle {
+        Handle(0x1000 + id as usize)
+    }
+
+    extern "C" fn entity_id(entity: Handle) -> u32 {
+        (entity.0 - 0x1000) as u32
+    }
+
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    extern fn spawn(id: u32) -> Handle;
+    extern fn entity_id(entity: Handle) -> u32;
+
+    struct Player {
+        entity: Handle,
+    }
+
+    pub fn main(id: u32) -> u32 {
+        let player = Player { entity: spawn(id) };
+        entity_id(player.entity)
+    }
+
+    pub fn same(a: Handle, b: Handle) -> bool {
+        a == b
+    }
+    "#,
+    )
+    .insert_fn("spawn", spawn as extern "C" fn(u32) -> Handle)
+    .insert_fn("entity_id", entity_id as extern "C" fn(Handle) -> u32);
+    assert_invoke_eq!(u32, 7, driver, "main", 7u32);
+    assert_invoke_eq!(bool, true, driver, "same", Handle(3), Handle(3));
+    assert_invoke_eq!(bool, false, driver, "same", Handle(3), Handle(4));
+}
+
+#[test]
+fn extern_fn_without_return_value() {
+    use std::sync::atomic::{AtomicI64, Ordering};
+
+    static LOGGED: AtomicI64 = AtomicI64::new(0);
+
+    extern "C" fn log(level: i64) {
+        LOGGED.fetch_add(level, Ordering::SeqCst);
+    }
+
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    extern fn log(level: i64);
+    pub fn main(level: i64) {
+        log(level);
+        log(level * 2);
+    }
+    "#,
+    )
+    .insert_fn("log", log as extern "C" fn(i64));
+    assert_invoke_eq!((), (), driver, "main", 3i64);
+    assert_eq!(LOGGED.load(Ordering::SeqCst), 9);
+}
+
+#[test]
+fn extern_fn_closure() {
+    use std::sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc,
+    };
+
+    let logged = Arc::new(AtomicI64::new(0));
+    let offset = 10;
+
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    extern fn log(level: i64);
+    extern fn add_offset(value: i64) -> i64;
+    pub fn main(level: i64) -> i64 {
+        log(level);
+        log(level * 2);
+        add_offset(level)
+    }
+    "#,
+    )
+    .insert_closure("log", {
+        let logged = logged.clone();
+        move |level: i64| {
+            logged.fetch_add(level, Ordering::SeqCst);
+        }
+    })
+    .insert_closure("add_offset", move |value: i64| value + offset);
+    assert_invoke_eq!(i64, 13, driver, "main", 3i64);
+    assert_eq!(logged.load(Ordering::SeqCst), 9);
+}
+
 #[test]
 #[should_panic]
 fn extern_fn_missing() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     extern fn add(a: i32, b: i32) -> i32;
     pub fn main() -> i32 { add(3,4) }
@@ -526,7 +1013,7 @@ fn extern_fn_invalid_signature() {
         0
     }
 
-    let result = TestDriver::new(
+    let result = CompileAndRunTestDriver::new(
         r#"
     extern fn add(a: i32, b: i32) -> i32;
     pub fn main() -> i32 { add(3,4) }
@@ -545,7 +1032,7 @@ fn extern_fn_invalid_sig() {
         3
     }
 
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     extern fn add(a: i32, b: i32) -> i32;
     pub fn main() -> i32 { add(3,4) }
@@ -557,7 +1044,7 @@ fn extern_fn_invalid_sig() {
 
 #[test]
 fn test_primitive_types() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     struct Primitives {
         a:u8,
@@ -643,7 +1130,7 @@ fn can_add_external_without_return() {
         println!("{}", a);
     }
 
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     extern fn foo(a: i32,);
     pub fn main(){ foo(3); }
@@ -659,7 +1146,7 @@ fn can_add_external_without_return() {
 
 #[test]
 fn signed_and_unsigned_rem() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     pub fn signed() -> i32 {
         (0 - 2) % 5
@@ -674,3 +1161,606 @@ fn signed_and_unsigned_rem() {
     assert_invoke_eq!(i32, -2, driver, "signed");
     assert_invoke_eq!(i32, 2, driver, "unsigned");
 }
+
+#[test]
+fn atomics() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct(gc) Counter { hits: Atomic<i32>, total: Atomic<u64> }
+
+    pub fn new_counter() -> Counter {
+        Counter { hits: 0, total: 10 }
+    }
+
+    pub fn increment(counter: Counter) -> i32 {
+        counter.total.fetch_add(2);
+        counter.hits.fetch_add(1)
+    }
+
+    pub fn reset(counter: Counter) {
+        counter.hits.store(0);
+    }
+
+    pub fn hits(counter: Counter) -> i32 {
+        counter.hits.load()
+    }
+
+    pub fn total(counter: Counter) -> u64 {
+        counter.total.load()
+    }
+    "#,
+    );
+
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+
+    let counter: StructRef = invoke_fn!(runtime_ref, "new_counter").unwrap();
+    let previous: i32 = invoke_fn!(runtime_ref, "increment", counter.clone()).unwrap();
+    assert_eq!(previous, 0);
+    let previous: i32 = invoke_fn!(runtime_ref, "increment", counter.clone()).unwrap();
+    assert_eq!(previous, 1);
+
+    let hits: i32 = invoke_fn!(runtime_ref, "hits", counter.clone()).unwrap();
+    assert_eq!(hits, 2);
+    let total: u64 = invoke_fn!(runtime_ref, "total", counter.clone()).unwrap();
+    assert_eq!(total, 14);
+
+    let _: () = invoke_fn!(runtime_ref, "reset", counter.clone()).unwrap();
+    let hits: i32 = invoke_fn!(runtime_ref, "hits", counter).unwrap();
+    assert_eq!(hits, 0);
+}
+
+#[test]
+fn any_downcast() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    fn wrap_float(value: f32) -> any { value as any }
+    fn wrap_int(value: i32) -> any { value as any }
+
+    pub fn float_or(value: f32, default: f32) -> f32 {
+        (wrap_float(value) as f32).unwrap_or(default)
+    }
+
+    pub fn int_as_float_or(value: i32, default: f32) -> f32 {
+        (wrap_int(value) as f32).unwrap_or(default)
+    }
+
+    pub fn is_int(value: i32) -> bool {
+        (wrap_int(value) as i32).is_some()
+    }
+
+    pub fn is_bool(value: i32) -> bool {
+        (wrap_int(value) as bool).is_some()
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(f32, 1.5, driver, "float_or", 1.5f32, 3.0f32);
+    assert_invoke_eq!(f32, 3.0, driver, "int_as_float_or", 1i32, 3.0f32);
+    assert_invoke_eq!(bool, true, driver, "is_int", 5i32);
+    assert_invoke_eq!(bool, false, driver, "is_bool", 5i32);
+}
+
+#[test]
+fn any_reassign() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub fn reassign_any(first: i32, second: f32) -> f32 {
+        let value: any = first as any;
+        value = second as any;
+        (value as f32).unwrap_or(0.0)
+    }
+
+    pub fn reassign_optional(value: i32) -> i32 {
+        let downcast = (value as any) as i32;
+        downcast = (true as any) as i32;
+        downcast.unwrap_or(-1)
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(f32, 2.5, driver, "reassign_any", 1i32, 2.5f32);
+    assert_invoke_eq!(i32, -1, driver, "reassign_optional", 5i32);
+}
+
+#[test]
+fn struct_update_syntax() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    struct(value) Vec2 { x: f32, y: f32 }
+    struct(gc) Entity { position: Vec2, health: i32, armor: i32 }
+
+    pub fn with_y(x: f32, y: f32) -> f32 {
+        let a = Vec2 { x, y: 0.0 };
+        let b = Vec2 { y, ..a };
+        b.x + b.y
+    }
+
+    pub fn damaged(health: i32, armor: i32) -> i32 {
+        let entity = Entity { position: Vec2 { x: 0.0, y: 0.0 }, health, armor };
+        let damaged = Entity { health: entity.health - 10, ..entity };
+        damaged.health + damaged.armor
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(f32, 3.0, driver, "with_y", 1.0f32, 2.0f32);
+    assert_invoke_eq!(i32, 95, driver, "damaged", 100i32, 5i32);
+}
+
+#[test]
+fn operator_overloading() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    struct(value) Vec2 { x: f32, y: f32 }
+    struct(gc) Money { cents: i64 }
+
+    impl Add for Vec2 {
+        fn add(lhs: Vec2, rhs: Vec2) -> Vec2 {
+            Vec2 { x: lhs.x + rhs.x, y: lhs.y + rhs.y }
+        }
+    }
+
+    impl Mul for Vec2 {
+        fn mul(lhs: Vec2, rhs: f32) -> Vec2 {
+            Vec2 { x: lhs.x * rhs, y: lhs.y * rhs }
+        }
+    }
+
+    impl Sub for Money {
+        fn sub(lhs: Money, rhs: Money) -> Money {
+            Money { cents: lhs.cents - rhs.cents }
+        }
+    }
+
+    pub fn sum(x: f32, y: f32) -> f32 {
+        let a = Vec2 { x, y };
+        let b = (a + a) * 2.0;
+        b.x + b.y
+    }
+
+    pub fn change(paid: i64, price: i64) -> i64 {
+        let change = Money { cents: paid } - Money { cents: price };
+        change.cents
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(f32, 12.0, driver, "sum", 1.0f32, 2.0f32);
+    assert_invoke_eq!(i64, 25, driver, "change", 100i64, 75i64);
+}
+
+#[test]
+fn derived_comparison() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    #[derive(Eq, Ord)]
+    struct(value) Version { major: u32, minor: u32 }
+
+    #[derive(Eq)]
+    struct(gc) Package { version: Version, stable: bool }
+
+    pub fn is_newer(major_a: u32, minor_a: u32, major_b: u32, minor_b: u32) -> bool {
+        Version { major: major_a, minor: minor_a } > Version { major: major_b, minor: minor_b }
+    }
+
+    pub fn is_same(major_a: u32, minor_a: u32, major_b: u32, minor_b: u32) -> bool {
+        let a = Package { version: Version { major: major_a, minor: minor_a }, stable: true };
+        let b = Package { version: Version { major: major_b, minor: minor_b }, stable: true };
+        a == b
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(bool, true, driver, "is_newer", 1u32, 3u32, 1u32, 2u32);
+    assert_invoke_eq!(bool, true, driver, "is_newer", 2u32, 0u32, 1u32, 9u32);
+    assert_invoke_eq!(bool, false, driver, "is_newer", 1u32, 2u32, 1u32, 2u32);
+    assert_invoke_eq!(bool, true, driver, "is_same", 1u32, 2u32, 1u32, 2u32);
+    assert_invoke_eq!(bool, false, driver, "is_same", 1u32, 2u32, 1u32, 3u32);
+}
+
+#[test]
+fn inline_functions() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    #[inline]
+    fn square(x: f32) -> f32 { x * x }
+
+    #[inline(always)]
+    pub fn length_squared(x: f32, y: f32) -> f32 { square(x) + square(y) }
+
+    #[inline(never)]
+    fn half(x: f32) -> f32 { x / 2.0 }
+
+    pub fn half_length_squared(x: f32, y: f32) -> f32 {
+        half(length_squared(x, y))
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(f32, 25.0, driver, "length_squared", 3.0f32, 4.0f32);
+    assert_invoke_eq!(f32, 12.5, driver, "half_length_squared", 3.0f32, 4.0f32);
+}
+
+#[test]
+fn export_name() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    #[export(name = "on_update")]
+    pub fn update(delta: f32) -> f32 { tick(delta) * 2.0 }
+
+    #[export(name = "on_tick")]
+    pub fn tick(delta: f32) -> f32 { delta + 1.0 }
+    "#,
+    );
+
+    assert_invoke_eq!(f32, 4.0, driver, "on_update", 1.0f32);
+    assert_invoke_eq!(f32, 2.0, driver, "on_tick", 1.0f32);
+}
+
+#[test]
+fn function_docs() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    /// Returns the answer
+    ///
+    /// The answer is always the same.
+    pub fn answer() -> i32 { 42 }
+
+    pub fn question() -> i32 { 0 }
+    "#,
+    );
+
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+    assert_eq!(
+        runtime_ref
+            .get_function_definition("answer")
+            .unwrap()
+            .docs(),
+        Some("Returns the answer\n\nThe answer is always the same.")
+    );
+    assert_eq!(
+        runtime_ref
+            .get_function_definition("question")
+            .unwrap()
+            .docs(),
+        None
+    );
+}
+
+#[test]
+fn diverging_expressions() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    struct(value) Pair { a: i32, b: i32 }
+
+    fn sum(a: i32, b: i32) -> i32 { a + b }
+
+    pub fn early(n: i32) -> i32 {
+        let value: i32 = if n > 0 { n } else { return -1 };
+        let pair = Pair { a: value, b: if n > 10 { return 10 } else { 1 } };
+        sum(pair.a, if n > 5 { return 5 } else { pair.b }) + 100
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(i32, -1, driver, "early", -3i32);
+    assert_invoke_eq!(i32, 103, driver, "early", 2i32);
+    assert_invoke_eq!(i32, 5, driver, "early", 7i32);
+    assert_invoke_eq!(i32, 10, driver, "early", 20i32);
+}
+
+#[test]
+fn tuple_and_unit_structs() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    struct Marker;
+    struct Wrapper(i64);
+    struct(value) Pair(i64, f64);
+
+    fn unwrap(wrapper: Wrapper) -> i64 {
+        wrapper.0
+    }
+
+    pub fn wrapped(a: i64) -> i64 {
+        let _marker = Marker;
+        let wrapper = Wrapper(a);
+        wrapper.0 += 1;
+        unwrap(wrapper)
+    }
+
+    pub fn scaled_second(a: i64, b: f64) -> f64 {
+        let pair = Pair(a, b);
+        pair.1 * 2.0
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(i64, 8, driver, "wrapped", 7i64);
+    assert_invoke_eq!(f64, 3.0, driver, "scaled_second", 1i64, 1.5f64);
+}
+
+#[test]
+fn recursive_struct_type_info() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct(gc) Node { value: i64, next: Node }
+
+    pub fn next_value(node: Node) -> i64 {
+        node.next.value
+    }
+    "#,
+    );
+
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+    let func = runtime_ref.get_function_definition("next_value").unwrap();
+    let node_type = func.prototype.signature.arg_types()[0];
+    let node_struct = node_type.as_struct().unwrap();
+
+    // The `next` field refers to the `Node` type itself
+    assert!(std::ptr::eq(node_struct.field_types()[1], node_type));
+}
+
+#[test]
+fn wrapping_overflow() {
+    let mut driver = CompileAndRunTestDriver::with_config(
+        r#"
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+    pub fn mul(a: u8, b: u8) -> u8 { a * b }
+    "#,
+        Config {
+            overflow_checks: Some(false),
+            ..Config::default()
+        },
+    );
+    assert_invoke_eq!(i32, std::i32::MIN, driver, "add", std::i32::MAX, 1i32);
+    assert_invoke_eq!(u8, 4, driver, "mul", 130u8, 2u8);
+}
+
+#[test]
+fn checked_overflow() {
+    let mut driver = CompileAndRunTestDriver::with_config(
+        r#"
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+    pub fn sub(a: u64, b: u64) -> u64 {
+        let result = a;
+        result -= b;
+        result
+    }
+    "#,
+        Config {
+            overflow_checks: Some(true),
+            ..Config::default()
+        },
+    );
+    assert_invoke_eq!(i32, 5, driver, "add", 2i32, 3i32);
+    assert_invoke_eq!(u64, 1, driver, "sub", 3u64, 2u64);
+}
+
+#[test]
+fn checked_overflow_traps() {
+    let mut driver = CompileAndRunTestDriver::with_config(
+        r#"
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+    "#,
+        Config {
+            overflow_checks: Some(true),
+            ..Config::default()
+        },
+    );
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+    let result: Result<i32, _> = invoke_fn!(runtime_ref, "add", std::i32::MAX, 1i32);
+    let error = result.unwrap_err();
+    let panic = error.panic().expect("expected a panic");
+    assert_eq!(panic.message(), "attempt to add with overflow");
+    assert_eq!(panic.line(), 2);
+
+    // The runtime is still usable after a trap
+    let result: i32 = invoke_fn!(runtime_ref, "add", 2i32, 3i32).unwrap();
+    assert_eq!(result, 5);
+}
+
+#[test]
+fn division_by_zero_traps() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub fn divide(a: i32, b: i32) -> i32 { a / b }
+    pub fn remainder(a: u8, b: u8) -> u8 { a % b }
+    "#,
+    );
+    assert_invoke_eq!(i32, 3, driver, "divide", 7i32, 2i32);
+    assert_invoke_eq!(u8, 1, driver, "remainder", 7u8, 2u8);
+
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+    let result: Result<i32, _> = invoke_fn!(runtime_ref, "divide", 1i32, 0i32);
+    let error = result.unwrap_err();
+    assert!(error.to_string().contains("attempt to divide by zero"));
+    // The trapped invocation consumed its arguments
+    assert!(!error.is_retriable());
+    let result: Result<i32, _> = invoke_fn!(runtime_ref, "divide", std::i32::MIN, -1i32);
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("attempt to divide with overflow"));
+    let result: Result<u8, _> = invoke_fn!(runtime_ref, "remainder", 1u8, 0u8);
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("attempt to calculate the remainder with a divisor of zero"));
+
+    // The runtime is still usable after a trap
+    let result: i32 = invoke_fn!(runtime_ref, "divide", 8i32, 4i32).unwrap();
+    assert_eq!(result, 2);
+}
+
+#[test]
+fn stack_overflow_traps() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub fn depth(n: i64) -> i64 {
+        if n == 0 { 0 } else { 1 + depth(n - 1) }
+    }
+    "#,
+    );
+    assert_invoke_eq!(i64, 100, driver, "depth", 100i64);
+
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+    let result: Result<i64, _> = invoke_fn!(runtime_ref, "depth", 100_000_000i64);
+    assert!(result.unwrap_err().to_string().contains("stack overflow"));
+
+    // The runtime is still usable after a trap
+    let result: i64 = invoke_fn!(runtime_ref, "depth", 10i64).unwrap();
+    assert_eq!(result, 10);
+}
+
+#[test]
+fn loop_iteration_budget_traps() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub fn count(n: i64) -> i64 {
+        let i = 0;
+        while i < n {
+            i += 1;
+        }
+        i
+    }
+    "#,
+    )
+    .set_max_loop_iterations(100);
+    assert_invoke_eq!(i64, 50, driver, "count", 50i64);
+
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+    let result: Result<i64, _> = invoke_fn!(runtime_ref, "count", 1000i64);
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("exceeded its iteration budget"));
+
+    // Every invocation has its own budget
+    let result: i64 = invoke_fn!(runtime_ref, "count", 50i64).unwrap();
+    assert_eq!(result, 50);
+}
+
+#[test]
+fn interrupt_handle_traps() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub fn forever() {
+        loop {}
+    }
+    "#,
+    );
+
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+    let interrupt_handle = runtime_ref.interrupt_handle();
+    let watchdog = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        interrupt_handle.interrupt();
+    });
+
+    let result: Result<(), _> = invoke_fn!(runtime_ref, "forever");
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("interrupted by the host"));
+    watchdog.join().unwrap();
+}
+
+#[test]
+fn invoke_errors() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub fn div(a: i32, b: i32) -> i32 {
+        a / b
+    }
+    "#,
+    );
+
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+
+    let result: Result<i32, _> = invoke_fn!(runtime_ref, "unknown");
+    assert_eq!(
+        result.unwrap_err().error(),
+        &InvokeError::FunctionNotFound {
+            function_name: "unknown".to_string()
+        }
+    );
+
+    let result: Result<i32, _> = invoke_fn!(runtime_ref, "div", 1i32);
+    match result.unwrap_err().error() {
+        InvokeError::SignatureMismatch { function_name, .. } => assert_eq!(function_name, "div"),
+        error => panic!("unexpected error: {:?}", error),
+    }
+
+    let result: Result<f64, _> = invoke_fn!(runtime_ref, "div", 1i32, 1i32);
+    match result.unwrap_err().error() {
+        InvokeError::SignatureMismatch { message, .. } => {
+            assert!(message.starts_with("Invalid return type"))
+        }
+        error => panic!("unexpected error: {:?}", error),
+    }
+
+    let result: Result<i32, _> = invoke_fn!(runtime_ref, "div", 1i32, 0i32);
+    match result.unwrap_err().error() {
+        InvokeError::Panic {
+            function_name,
+            panic,
+        } => {
+            assert_eq!(function_name, "div");
+            assert_eq!(panic.line(), 3);
+        }
+        error => panic!("unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn panic_and_assert_trap() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub fn checked_sub(a: u32, b: u32) -> u32 {
+        core::assert(a >= b, "subtraction underflows");
+        a - b
+    }
+
+    pub fn positive(a: i32) -> i32 {
+        core::assert(a > 0);
+        a
+    }
+
+    pub fn unimplemented() -> i32 {
+        core::panic("not implemented")
+    }
+    "#,
+    );
+    assert_invoke_eq!(u32, 2, driver, "checked_sub", 5u32, 3u32);
+    assert_invoke_eq!(i32, 4, driver, "positive", 4i32);
+
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+    let result: Result<u32, _> = invoke_fn!(runtime_ref, "checked_sub", 3u32, 5u32);
+    let error = result.unwrap_err();
+    let panic = error.panic().expect("expected a panic");
+    assert_eq!(panic.message(), "subtraction underflows");
+    assert_eq!((panic.line(), panic.column()), (3, 9));
+    assert!(error.to_string().contains("subtraction underflows at 3:9"));
+
+    let result: Result<i32, _> = invoke_fn!(runtime_ref, "positive", -1i32);
+    let error = result.unwrap_err();
+    let panic = error.panic().expect("expected a panic");
+    assert_eq!(panic.message(), "assertion failed: a > 0");
+    assert_eq!((panic.line(), panic.column()), (8, 9));
+
+    let result: Result<i32, _> = invoke_fn!(runtime_ref, "unimplemented");
+    let error = result.unwrap_err();
+    let panic = error.panic().expect("expected a panic");
+    assert_eq!(panic.message(), "not implemented");
+    assert_eq!((panic.line(), panic.column()), (13, 9));
+}
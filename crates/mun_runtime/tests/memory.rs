@@ -1,13 +1,13 @@
-use mun_runtime::{invoke_fn, StructRef};
-
-#[macro_use]
-mod util;
-
-use util::*;
+use mun_runtime::{invoke_fn, Guid, ReturnTypeReflection, StructRef};
+use mun_test::CompileAndRunTestDriver;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
 
 #[test]
 fn gc_trace() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     pub struct Foo {
         quz: f64,
@@ -35,18 +35,421 @@ fn gc_trace() {
     let value: StructRef = invoke_fn!(runtime_ref, "new_foo").unwrap();
     let value = value.root(driver.runtime());
 
-    assert_eq!(runtime_ref.gc_collect(), false);
+    assert_eq!(runtime_ref.collect_garbage(), false);
     assert!(runtime_ref.gc_stats().allocated_memory > 0);
 
+    let stats = runtime_ref.gc_stats();
+    assert_eq!(stats.live_objects(), 2);
+    assert_eq!(stats.objects_per_type["Foo"], 1);
+    assert_eq!(stats.objects_per_type["Bar"], 1);
+    assert_eq!(stats.allocated_since_collection, 0);
+
     drop(value);
 
-    assert_eq!(runtime_ref.gc_collect(), true);
+    assert_eq!(runtime_ref.collect_garbage(), true);
     assert_eq!(runtime_ref.gc_stats().allocated_memory, 0);
 }
 
+#[test]
+fn gc_threshold() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct Foo {
+        a: i64,
+    }
+
+    pub fn new_foo() -> Foo {
+        Foo { a: 3 }
+    }
+    "#,
+    );
+
+    let runtime = driver.runtime();
+    runtime.borrow_mut().set_gc_threshold(Some(1));
+
+    {
+        let runtime_ref = runtime.borrow();
+        let _foo: StructRef = invoke_fn!(runtime_ref, "new_foo").unwrap();
+        assert!(runtime_ref.gc_stats().allocated_memory > 0);
+    }
+
+    // No garbage is collected while automatic collection is disabled
+    runtime.borrow_mut().set_automatic_gc(false);
+    runtime.borrow_mut().update();
+    assert!(runtime.borrow().gc_stats().allocated_memory > 0);
+
+    // The threshold was exceeded, so garbage is collected
+    runtime.borrow_mut().set_automatic_gc(true);
+    runtime.borrow_mut().update();
+    assert_eq!(runtime.borrow().gc_stats().allocated_memory, 0);
+}
+
+#[test]
+fn weak_struct_ref() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct Foo {
+        a: i64,
+    }
+
+    pub fn new_foo() -> Foo {
+        Foo { a: 3 }
+    }
+    "#,
+    );
+
+    let runtime = driver.runtime();
+    let weak = {
+        let runtime_ref = runtime.borrow();
+        let foo: StructRef = invoke_fn!(runtime_ref, "new_foo").unwrap();
+        foo.downgrade(driver.runtime())
+    };
+
+    // The struct is still alive, so it can be upgraded
+    let rooted = weak.upgrade().expect("struct should still be alive");
+    assert_eq!(rooted.by_ref().get::<i64>("a"), Ok(3));
+
+    // A weak reference does not keep the struct alive
+    assert_eq!(runtime.borrow().collect_garbage(), false);
+    drop(rooted);
+    assert_eq!(runtime.borrow().collect_garbage(), true);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn pinned_struct() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct Foo {
+        a: i64,
+    }
+
+    pub fn new_foo() -> Foo {
+        Foo { a: 3 }
+    }
+    "#,
+    );
+
+    let runtime = driver.runtime();
+    let rooted = {
+        let runtime_ref = runtime.borrow();
+        let foo: StructRef = invoke_fn!(runtime_ref, "new_foo").unwrap();
+        foo.root(driver.runtime())
+    };
+
+    let pinned = rooted.pin();
+    let ptr = pinned.as_ptr();
+    assert_eq!(unsafe { *ptr.cast::<i64>().as_ptr() }, 3);
+
+    // A pinned struct is kept alive at the same address
+    drop(rooted);
+    assert_eq!(runtime.borrow().collect_garbage(), false);
+    assert_eq!(pinned.as_ptr(), ptr);
+    assert_eq!(pinned.by_ref().get::<i64>("a"), Ok(3));
+
+    drop(pinned);
+    assert_eq!(runtime.borrow().collect_garbage(), true);
+}
+
+#[test]
+fn snapshot() {
+    let code = r#"
+    pub struct Foo {
+        a: Bar,
+        b: Bar,
+        c: Baz,
+    }
+
+    pub struct Bar {
+        d: i64
+    }
+
+    pub struct(value) Baz {
+        e: f32,
+        f: bool,
+    }
+
+    pub fn new_foo() -> Foo {
+        let bar = Bar { d: 3 };
+        Foo { a: bar, b: bar, c: Baz { e: 1.5, f: true } }
+    }
+    "#;
+    let mut driver = CompileAndRunTestDriver::new(code);
+    let snapshot = {
+        let runtime = driver.runtime();
+        let runtime_ref = runtime.borrow();
+        let foo: StructRef = invoke_fn!(runtime_ref, "new_foo").unwrap();
+        runtime_ref.save_snapshot(&[foo])
+    };
+
+    let mut restored_driver = CompileAndRunTestDriver::new(code);
+    let runtime = restored_driver.runtime();
+    let runtime_ref = runtime.borrow();
+    let mut roots = runtime_ref.restore_snapshot(&snapshot).unwrap();
+    assert_eq!(roots.len(), 1);
+
+    let foo = roots.pop().unwrap();
+    assert_eq!(foo.type_info().name(), "Foo");
+    let a = foo.get::<StructRef>("a").unwrap();
+    assert_eq!(a.get::<i64>("d"), Ok(3));
+    let c = foo.get::<StructRef>("c").unwrap();
+    assert_eq!(c.get::<f32>("e"), Ok(1.5));
+    assert_eq!(c.get::<bool>("f"), Ok(true));
+
+    // References to the same object are restored as such
+    assert_eq!(runtime_ref.gc_stats().objects_per_type["Bar"], 1);
+
+    // Invalid data is rejected
+    assert!(runtime_ref
+        .restore_snapshot(&snapshot[..snapshot.len() - 1])
+        .is_err());
+}
+
+#[test]
+fn max_heap_size() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct Foo {
+        a: i64,
+    }
+
+    pub fn new_foo() -> Foo {
+        Foo { a: 3 }
+    }
+
+    pub fn new_foos() -> i64 {
+        let a = Foo { a: 1 };
+        let b = Foo { a: 2 };
+        a.a + b.a
+    }
+    "#,
+    )
+    .set_max_heap_size(std::mem::size_of::<i64>());
+
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+    let foo: StructRef = invoke_fn!(runtime_ref, "new_foo").unwrap();
+    let foo = foo.root(driver.runtime());
+
+    // The heap is full, so allocating another object fails
+    let result: Result<i64, _> = invoke_fn!(runtime_ref, "new_foos");
+    assert!(result.unwrap_err().to_string().contains("out of memory"));
+
+    // The runtime is still usable after the allocation failed
+    drop(foo);
+    assert_eq!(runtime_ref.collect_garbage(), true);
+    let foo: Result<StructRef, _> = invoke_fn!(runtime_ref, "new_foo");
+    assert!(foo.is_ok());
+}
+
+#[test]
+fn dump_heap() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct Foo {
+        bar: Bar,
+        baz: Baz,
+    }
+
+    pub struct Bar {
+        a: i64
+    }
+
+    pub struct(value) Baz {
+        bar: Bar,
+    }
+
+    pub fn new_foo() -> Foo {
+        Foo { bar: Bar { a: 3 }, baz: Baz { bar: Bar { a: 4 } } }
+    }
+    "#,
+    );
+
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+    let foo: StructRef = invoke_fn!(runtime_ref, "new_foo").unwrap();
+    let _foo = foo.root(driver.runtime());
+
+    let mut dump = Vec::new();
+    runtime_ref.dump_heap(&mut dump).unwrap();
+    let dump = String::from_utf8(dump).unwrap();
+
+    // The roots, `Foo` and both `Bar`s are nodes, which are referenced by three edges
+    assert!(dump.contains(r#""node_count":4,"edge_count":3"#));
+    assert!(dump.contains(r#""(GC roots)""#));
+    assert!(dump.contains(r#""Foo""#));
+    assert!(dump.contains(r#""Bar""#));
+    assert!(dump.contains(r#""bar""#));
+    assert!(dump.contains(r#""baz.bar""#));
+}
+
+#[test]
+fn allocation_hooks() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct Foo {
+        bar: Bar,
+    }
+
+    pub struct Bar {
+        baz: i64
+    }
+
+    pub fn new_foo() -> Foo {
+        Foo { bar: Bar { baz: 3 } }
+    }
+    "#,
+    );
+
+    let allocations = Arc::new(Mutex::new(Vec::new()));
+    let deallocations = Arc::new(AtomicUsize::new(0));
+
+    let runtime = driver.runtime();
+    {
+        let mut runtime_mut = runtime.borrow_mut();
+        let allocations = allocations.clone();
+        runtime_mut.on_allocation(move |event| {
+            allocations
+                .lock()
+                .unwrap()
+                .push((event.type_info.name().to_string(), event.stack_id))
+        });
+        let deallocations = deallocations.clone();
+        runtime_mut.on_deallocation(move |event| {
+            assert_eq!(event.stack_id, Some(7));
+            deallocations.fetch_add(1, Ordering::SeqCst);
+        });
+        runtime_mut.set_allocation_stack_id(|| Some(7));
+    }
+
+    // Tracking is disabled by default
+    {
+        let runtime_ref = runtime.borrow();
+        let _foo: StructRef = invoke_fn!(runtime_ref, "new_foo").unwrap();
+        assert_eq!(runtime_ref.collect_garbage(), true);
+    }
+    assert!(allocations.lock().unwrap().is_empty());
+    assert_eq!(deallocations.load(Ordering::SeqCst), 0);
+
+    runtime.borrow_mut().set_allocation_tracking(true);
+    {
+        let runtime_ref = runtime.borrow();
+        let _foo: StructRef = invoke_fn!(runtime_ref, "new_foo").unwrap();
+        assert_eq!(runtime_ref.collect_garbage(), true);
+    }
+
+    let mut allocations = allocations.lock().unwrap().clone();
+    allocations.sort();
+    assert_eq!(
+        allocations,
+        vec![("Bar".to_string(), Some(7)), ("Foo".to_string(), Some(7))]
+    );
+    assert_eq!(deallocations.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn gc_collect_incremental() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct Foo {
+        bar: Bar,
+    }
+
+    pub struct Bar {
+        baz: i64
+    }
+
+    pub fn new_foo() -> Foo {
+        Foo {
+            bar: Bar {
+                baz: 3
+            }
+        }
+    }
+
+    pub fn new_bar(baz: i64) -> Bar {
+        Bar { baz: baz }
+    }
+
+    pub fn set_bar(foo: Foo, bar: Bar) {
+        foo.bar = bar;
+    }
+    "#,
+    );
+
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+
+    let foo: StructRef = invoke_fn!(runtime_ref, "new_foo").unwrap();
+    let rooted_foo = foo.clone().root(driver.runtime());
+    let bar: StructRef = invoke_fn!(runtime_ref, "new_bar", 5i64).unwrap();
+
+    // Start a collection, which traces `foo`
+    assert!(!runtime_ref.gc_collect_incremental(1));
+
+    // Store `bar` in `foo` after it was traced
+    let _: () = invoke_fn!(runtime_ref, "set_bar", foo, bar).unwrap();
+
+    // Finish the collection, `bar` should not be collected
+    while !runtime_ref.gc_collect_incremental(1) {}
+    {
+        let foo = rooted_foo.by_ref();
+        let bar = foo.get::<StructRef>("bar").unwrap();
+        assert_eq!(bar.get::<i64>("baz").unwrap(), 5);
+    }
+
+    drop(rooted_foo);
+    assert_eq!(runtime_ref.collect_garbage(), true);
+    assert_eq!(runtime_ref.gc_stats().allocated_memory, 0);
+}
+
+#[test]
+fn drop_non_escaping_objects() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct Foo {
+        a: f32,
+        b: i64,
+    }
+
+    pub fn temporary() -> f32 {
+        Foo { a: 1.0, b: 2 }.a
+    }
+
+    pub fn binding() -> i64 {
+        let foo = Foo { a: 1.0, b: 2 };
+        foo.b += 1;
+        foo.b
+    }
+
+    pub fn escaping() -> Foo {
+        let foo = Foo { a: 1.0, b: 2 };
+        foo
+    }
+    "#,
+    );
+
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+
+    // Objects that are not referenced after the function returns are released immediately
+    let a: f32 = invoke_fn!(runtime_ref, "temporary").unwrap();
+    assert_eq!(a, 1.0);
+    assert_eq!(runtime_ref.gc_stats().allocated_memory, 0);
+
+    let b: i64 = invoke_fn!(runtime_ref, "binding").unwrap();
+    assert_eq!(b, 3);
+    assert_eq!(runtime_ref.gc_stats().allocated_memory, 0);
+
+    // An object that is returned must be kept alive
+    let foo: StructRef = invoke_fn!(runtime_ref, "escaping").unwrap();
+    assert!(runtime_ref.gc_stats().allocated_memory > 0);
+    assert_eq!(foo.get::<i64>("b").unwrap(), 2);
+}
+
 #[test]
 fn map_struct_insert_field1() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
         struct Foo {
             b: i64,
@@ -84,7 +487,7 @@ fn map_struct_insert_field1() {
 
 #[test]
 fn map_struct_insert_field2() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
         struct Foo {
             a: i64,
@@ -122,7 +525,7 @@ fn map_struct_insert_field2() {
 
 #[test]
 fn map_struct_insert_field3() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
         struct Foo {
             a: i64,
@@ -160,7 +563,7 @@ fn map_struct_insert_field3() {
 
 #[test]
 fn map_struct_remove_field1() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
         struct Foo {
             a: f64,
@@ -196,7 +599,7 @@ fn map_struct_remove_field1() {
 
 #[test]
 fn map_struct_remove_field2() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
         struct Foo {
             a: f64,
@@ -232,7 +635,7 @@ fn map_struct_remove_field2() {
 
 #[test]
 fn map_struct_remove_field3() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
         struct Foo {
             a: i64,
@@ -268,7 +671,7 @@ fn map_struct_remove_field3() {
 
 #[test]
 fn map_struct_cast_fields1() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
         struct Foo(
             u8,
@@ -316,7 +719,7 @@ fn map_struct_cast_fields1() {
 
 #[test]
 fn map_struct_cast_fields2() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
         struct Foo(
             i16,
@@ -349,7 +752,7 @@ fn map_struct_cast_fields2() {
 
 #[test]
 fn map_struct_swap_fields1() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
         struct Foo {
             a: f64,
@@ -389,7 +792,7 @@ fn map_struct_swap_fields1() {
 
 #[test]
 fn map_struct_swap_fields2() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
         struct Foo {
             a: f64,
@@ -433,7 +836,7 @@ fn map_struct_swap_fields2() {
 
 #[test]
 fn map_struct_rename_field1() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
         struct Foo {
             a: i64,
@@ -473,7 +876,7 @@ fn map_struct_rename_field1() {
 
 #[test]
 fn map_struct_rename_field2() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
         struct Foo {
             a: i64,
@@ -511,9 +914,50 @@ fn map_struct_rename_field2() {
     assert_eq!(foo.by_ref().get::<f64>("f").unwrap(), c);
 }
 
+#[test]
+fn map_struct_rename_field_with_attribute() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+        struct Foo {
+            a: i64,
+            b: f64,
+            c: f64,
+        }
+
+        pub fn foo_new(a: i64, b: f64, c: f64) -> Foo {
+            Foo { a, b, c }
+        }
+    "#,
+    );
+
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+
+    let a = 5i64;
+    let b = 1.0f64;
+    let c = 3.0f64;
+    let foo: StructRef = invoke_fn!(runtime_ref, "foo_new", a, b, c).unwrap();
+    let foo = foo.root(driver.runtime());
+
+    driver.update(
+        runtime_ref,
+        r#"
+        struct Foo {
+            a: i64,
+            #[rename_from(c)]
+            d: f64,
+            e: f64,
+        }
+    "#,
+    );
+    assert_eq!(foo.by_ref().get::<i64>("a").unwrap(), a);
+    assert_eq!(foo.by_ref().get::<f64>("d").unwrap(), c);
+    assert_eq!(foo.by_ref().get::<f64>("e").unwrap(), b);
+}
+
 #[test]
 fn map_struct_all() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
         struct Foo {
             a: i32,
@@ -556,9 +1000,112 @@ fn map_struct_all() {
     assert_eq!(foo.by_ref().get::<i32>("f").unwrap(), 0);
 }
 
+#[test]
+fn map_struct_with_migration() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+        struct Foo {
+            a: i64,
+            b: f64,
+        }
+
+        pub fn foo_new(a: i64, b: f64) -> Foo {
+            Foo { a, b }
+        }
+    "#,
+    );
+
+    let runtime = driver.runtime();
+    let a = 0x1_0000_0002i64;
+    let b = 1.0f64;
+    let (foo, old_guid) = {
+        let runtime_ref = runtime.borrow();
+        let foo: StructRef = invoke_fn!(runtime_ref, "foo_new", a, b).unwrap();
+        let old_guid = foo.type_info().guid;
+        (foo.root(driver.runtime()), old_guid)
+    };
+
+    let i32_guid = <i32 as ReturnTypeReflection>::type_guid();
+    let new_guid = Guid::from_struct(
+        "Foo",
+        vec![
+            ("b", <f64 as ReturnTypeReflection>::type_guid()),
+            ("a_lo", i32_guid),
+            ("a_hi", i32_guid),
+        ],
+    );
+    runtime
+        .borrow_mut()
+        .add_migration(old_guid, new_guid, |migration| {
+            let a: i64 = migration.get("a")?;
+            migration.set("a_lo", a as i32)?;
+            migration.set("a_hi", (a >> 32) as i32)
+        });
+
+    driver.update(
+        runtime.borrow(),
+        r#"
+        struct Foo {
+            b: f64,
+            a_lo: i32,
+            a_hi: i32,
+        }
+    "#,
+    );
+    assert_eq!(foo.by_ref().get::<f64>("b").unwrap(), b);
+    assert_eq!(foo.by_ref().get::<i32>("a_lo").unwrap(), 2);
+    assert_eq!(foo.by_ref().get::<i32>("a_hi").unwrap(), 1);
+}
+
+#[test]
+fn map_renamed_struct_with_migration() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+        struct Foo {
+            a: i64,
+        }
+
+        pub fn foo_new(a: i64) -> Foo {
+            Foo { a }
+        }
+    "#,
+    );
+
+    let runtime = driver.runtime();
+    let a = 5i64;
+    let (foo, old_guid) = {
+        let runtime_ref = runtime.borrow();
+        let foo: StructRef = invoke_fn!(runtime_ref, "foo_new", a).unwrap();
+        let old_guid = foo.type_info().guid;
+        (foo.root(driver.runtime()), old_guid)
+    };
+
+    let new_guid = Guid::from_struct(
+        "Bar",
+        vec![("b", <i64 as ReturnTypeReflection>::type_guid())],
+    );
+    runtime
+        .borrow_mut()
+        .add_migration(old_guid, new_guid, |migration| {
+            let a: i64 = migration.get("a")?;
+            migration.set("b", a)
+        });
+
+    driver.update(
+        runtime.borrow(),
+        r#"
+        struct Bar {
+            b: i64,
+        }
+    "#,
+    );
+    assert_eq!(foo.by_ref().type_info().name(), "Bar");
+    assert_eq!(foo.by_ref().get::<i64>("b").unwrap(), a);
+}
+
 #[test]
 fn delete_used_struct() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
         struct Foo {
             a: i64,
@@ -609,7 +1156,7 @@ fn delete_used_struct() {
 
 #[test]
 fn nested_structs() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
     struct(gc) GcStruct(f32, f32);
     struct(value) ValueStruct(f32, f32);
@@ -833,7 +1380,7 @@ fn nested_structs() {
 
 #[test]
 fn insert_struct() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r#"
         struct Foo {
             a: i64,
@@ -1,11 +1,9 @@
-mod util;
-
+use mun_test::CompileAndRunTestDriver;
 use std::io;
-use util::*;
 
 #[test]
 fn error_assembly_not_linkable() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r"
     extern fn dependency() -> i32;
     
@@ -27,7 +25,7 @@ fn error_assembly_not_linkable() {
 
 #[test]
 fn arg_missing_bug() {
-    let mut driver = TestDriver::new(
+    let mut driver = CompileAndRunTestDriver::new(
         r"
     pub fn fibonacci_n() -> i64 {
         let n = arg();
@@ -49,3 +47,796 @@ fn arg_missing_bug() {
 
     driver.spawn().unwrap()
 }
+
+#[test]
+fn assembly_build_metadata() {
+    let source = r"
+    pub fn main() -> i32 { 5 }
+    ";
+
+    let fingerprint = |source: &str| {
+        let mut driver = CompileAndRunTestDriver::new(source);
+        let runtime = driver.runtime();
+        let runtime_ref = runtime.borrow();
+        let assembly = runtime_ref.assemblies().next().unwrap();
+        let info = assembly.info();
+        assert!(!info.compiler_version().is_empty());
+        assert_eq!(info.source_hash().len(), 32);
+        info.fingerprint().to_owned()
+    };
+
+    // Building the same source twice results in the same fingerprint
+    assert_eq!(fingerprint(source), fingerprint(source));
+    assert_ne!(
+        fingerprint(source),
+        fingerprint("pub fn main() -> i32 { 6 }")
+    );
+}
+
+#[test]
+fn invoke_dynamic() {
+    use mun_runtime::{InvokeError, Value};
+
+    let mut driver = CompileAndRunTestDriver::new(
+        r"
+    pub struct(value) Vec2 { x: f32, y: f32 }
+
+    pub fn mix(a: i8, b: f32, c: i64, d: f64, e: bool) -> f64 {
+        let sum = (a as f64) * (b as f64) + (c as f64) + d;
+        if e { sum } else { -sum }
+    }
+
+    pub fn vec2(x: f32, y: f32) -> Vec2 {
+        Vec2 { x, y }
+    }
+
+    pub fn length_squared(v: Vec2) -> f32 {
+        v.x * v.x + v.y * v.y
+    }
+
+    pub fn nothing() {}
+    ",
+    );
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+
+    let args = [
+        Value::I8(-3),
+        Value::F32(2.5),
+        Value::I64(10),
+        Value::F64(0.5),
+        Value::Bool(false),
+    ];
+    match runtime_ref.invoke_dynamic("mix", &args) {
+        Ok(Value::F64(result)) => assert_eq!(result, -3.0),
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    let v = runtime_ref
+        .invoke_dynamic("vec2", &[Value::F32(3.0), Value::F32(4.0)])
+        .unwrap();
+    match runtime_ref.invoke_dynamic("length_squared", &[v]) {
+        Ok(Value::F32(result)) => assert_eq!(result, 25.0),
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    match runtime_ref.invoke_dynamic("nothing", &[]) {
+        Ok(Value::Empty) => (),
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    // The arguments are checked against the signature of the function
+    assert!(runtime_ref
+        .invoke_dynamic("vec2", &[Value::F32(3.0)])
+        .is_err());
+    assert!(runtime_ref
+        .invoke_dynamic("vec2", &[Value::F32(3.0), Value::F64(4.0)])
+        .is_err());
+    match runtime_ref.invoke_dynamic("unknown", &[]) {
+        Err(InvokeError::FunctionNotFound { function_name }) => {
+            assert_eq!(function_name, "unknown")
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn reflect_functions_and_types() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r"
+    pub struct Player { health: f32, alive: bool }
+
+    pub fn on_start() {}
+    pub fn on_hit(player: Player, damage: f32) {}
+    pub fn heal(player: Player) {}
+    fn on_private() {}
+    ",
+    );
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+
+    let mut callbacks: Vec<_> = runtime_ref
+        .functions()
+        .map(|f| f.prototype.name())
+        .filter(|name| name.starts_with("on_"))
+        .collect();
+    callbacks.sort();
+    assert_eq!(callbacks, vec!["on_hit", "on_start"]);
+
+    let on_hit = runtime_ref
+        .functions()
+        .find(|f| f.prototype.name() == "on_hit")
+        .unwrap();
+    assert_eq!(
+        on_hit.prototype.signature.to_string(),
+        "fn(Player, core::f32)"
+    );
+
+    let player = runtime_ref
+        .types()
+        .find(|ty| ty.name() == "Player")
+        .unwrap();
+    let fields: Vec<_> = player
+        .as_struct()
+        .unwrap()
+        .fields()
+        .map(|field| (field.name, field.type_info.name(), field.offset))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![("health", "core::f32", 0), ("alive", "core::bool", 4)]
+    );
+}
+
+#[test]
+fn concurrent_invocations() {
+    use mun_runtime::{invoke_fn, Runtime, RuntimeBuilder};
+    use std::sync::{Arc, RwLock};
+
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Runtime>();
+
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn fibonacci(n: i64) -> i64 {
+        if n <= 1 {
+            n
+        } else {
+            fibonacci(n - 1) + fibonacci(n - 2)
+        }
+    }
+    ",
+    );
+    let runtime = Arc::new(RwLock::new(
+        RuntimeBuilder::new(driver.assembly_path())
+            .finish()
+            .unwrap(),
+    ));
+
+    let threads: Vec<_> = (0..4)
+        .map(|i| {
+            let runtime = runtime.clone();
+            std::thread::spawn(move || {
+                let runtime_ref = runtime.read().unwrap();
+                let result: i64 = invoke_fn!(runtime_ref, "fibonacci", 20i64 + i).unwrap();
+                result
+            })
+        })
+        .collect();
+    let results: Vec<i64> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+    assert_eq!(results, vec![6765, 10946, 17711, 28657]);
+
+    // Memory can be collected once no function is being invoked
+    runtime.write().unwrap().collect_garbage();
+}
+
+#[test]
+fn cross_assembly_calls() {
+    use mun_runtime::{invoke_fn, RuntimeBuilder};
+
+    let mut weapons = CompileAndRunTestDriver::new(
+        r"
+    extern fn multiplier() -> i32;
+
+    pub fn damage() -> i32 { 5 * multiplier() }
+    ",
+    );
+    let game = CompileAndRunTestDriver::new(
+        r"
+    extern fn damage() -> i32;
+
+    pub fn multiplier() -> i32 { 2 }
+    pub fn attack() -> i32 { damage() * multiplier() }
+    ",
+    );
+
+    // The assemblies call each other, so neither can be linked on its own
+    let mut runtime = RuntimeBuilder::new(game.assembly_path())
+        .add_library(weapons.assembly_path())
+        .finish()
+        .unwrap();
+    assert_eq!(runtime.assemblies().count(), 2);
+    let result: i32 = invoke_fn!(runtime, "attack").unwrap();
+    assert_eq!(result, 20);
+
+    // Reloading one assembly relinks the calls from the other
+    weapons.update_assembly(
+        r"
+    extern fn multiplier() -> i32;
+
+    pub fn damage() -> i32 { 7 * multiplier() }
+    ",
+    );
+    let start_time = std::time::Instant::now();
+    while !runtime.update() {
+        if start_time.elapsed() > std::time::Duration::from_secs(10) {
+            panic!("runtime did not update after recompilation within 10secs");
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    let result: i32 = invoke_fn!(runtime, "attack").unwrap();
+    assert_eq!(result, 28);
+}
+
+#[test]
+fn watch_directory() {
+    use mun_runtime::{invoke_fn, RuntimeBuilder};
+
+    let game = CompileAndRunTestDriver::new(
+        r"
+    pub fn main() -> i32 { 1 }
+    ",
+    );
+    let plugin = CompileAndRunTestDriver::new(
+        r"
+    pub fn bonus() -> i32 { 2 }
+    ",
+    );
+
+    let plugins_dir = tempfile::TempDir::new().unwrap();
+    let mut runtime = RuntimeBuilder::new(game.assembly_path())
+        .watch_directory(plugins_dir.path())
+        .finish()
+        .unwrap();
+    assert_eq!(runtime.assemblies().count(), 1);
+
+    // A library that is added to the directory is loaded without restarting the runtime
+    std::fs::copy(
+        plugin.assembly_path(),
+        plugins_dir.path().join("plugin.munlib"),
+    )
+    .unwrap();
+    let start_time = std::time::Instant::now();
+    while !runtime.update() {
+        if start_time.elapsed() > std::time::Duration::from_secs(10) {
+            panic!("runtime did not load the new library within 10secs");
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    assert_eq!(runtime.assemblies().count(), 2);
+    let result: i32 = invoke_fn!(runtime, "bonus").unwrap();
+    assert_eq!(result, 2);
+}
+
+#[test]
+fn watch_directory_unlinkable_library() {
+    use mun_runtime::{invoke_fn, RuntimeBuilder};
+
+    let game = CompileAndRunTestDriver::new(
+        r"
+    pub fn main() -> i32 { 1 }
+    ",
+    );
+    let mut plugin = CompileAndRunTestDriver::new(
+        r"
+    extern fn missing() -> i32;
+
+    pub fn bonus() -> i32 { missing() }
+    ",
+    );
+    let marker = CompileAndRunTestDriver::new(
+        r"
+    pub fn marker() -> i32 { 3 }
+    ",
+    );
+
+    let plugins_dir = tempfile::TempDir::new().unwrap();
+    let mut runtime = RuntimeBuilder::new(game.assembly_path())
+        .watch_directory(plugins_dir.path())
+        .finish()
+        .unwrap();
+    let wait_for_function = |runtime: &mut mun_runtime::Runtime, name: &str| {
+        let start_time = std::time::Instant::now();
+        while runtime.get_function_definition(name).is_none() {
+            if start_time.elapsed() > std::time::Duration::from_secs(10) {
+                panic!("runtime did not load the new library within 10secs");
+            }
+            runtime.update();
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+    };
+
+    // A library that cannot be linked is not loaded, which becomes apparent once a library that
+    // is added afterwards was loaded
+    std::fs::copy(
+        plugin.assembly_path(),
+        plugins_dir.path().join("plugin.munlib"),
+    )
+    .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    std::fs::copy(
+        marker.assembly_path(),
+        plugins_dir.path().join("marker.munlib"),
+    )
+    .unwrap();
+    wait_for_function(&mut runtime, "marker");
+    assert_eq!(runtime.assemblies().count(), 2);
+    assert!(runtime.get_function_definition("bonus").is_none());
+    let result: i32 = invoke_fn!(runtime, "main").unwrap();
+    assert_eq!(result, 1);
+
+    // The library is loaded once it is fixed
+    plugin.update_assembly(
+        r"
+    pub fn bonus() -> i32 { 2 }
+    ",
+    );
+    std::fs::copy(
+        plugin.assembly_path(),
+        plugins_dir.path().join("plugin.munlib"),
+    )
+    .unwrap();
+    wait_for_function(&mut runtime, "bonus");
+    assert_eq!(runtime.assemblies().count(), 3);
+    let result: i32 = invoke_fn!(runtime, "bonus").unwrap();
+    assert_eq!(result, 2);
+}
+
+#[test]
+fn failed_load_leaves_runtime_untouched() {
+    use mun_compiler::Config;
+    use mun_runtime::{invoke_fn, RuntimeBuilder};
+
+    let game = CompileAndRunTestDriver::new(
+        r"
+    pub fn main() -> i32 { 1 }
+    ",
+    );
+    let physics = CompileAndRunTestDriver::new(
+        r"
+    pub fn gravity() -> i32 { 10 }
+    ",
+    );
+    // The dependency is loaded along with the plugin, which imports a function that is missing
+    let unlinkable = CompileAndRunTestDriver::with_dependencies(
+        r"
+    extern fn missing() -> i32;
+
+    pub fn bonus() -> i32 { physics::gravity() + missing() }
+    ",
+        Config::default(),
+        &[("physics", physics.assembly_path())],
+    );
+    // Defines a function with the same name as a function of the game
+    let duplicate = CompileAndRunTestDriver::new(
+        r"
+    pub fn main() -> i32 { 2 }
+    ",
+    );
+    let valid = CompileAndRunTestDriver::new(
+        r"
+    pub fn extra() -> i32 { 3 }
+    ",
+    );
+
+    let plugins_dir = tempfile::TempDir::new().unwrap();
+    let mut runtime = RuntimeBuilder::new(game.assembly_path())
+        .watch_directory(plugins_dir.path())
+        .finish()
+        .unwrap();
+
+    // The libraries that fail to load are handled before the valid library is loaded
+    for (name, driver) in [("unlinkable", &unlinkable), ("duplicate", &duplicate)].iter() {
+        std::fs::copy(
+            driver.assembly_path(),
+            plugins_dir.path().join(format!("{}.munlib", name)),
+        )
+        .unwrap();
+    }
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    std::fs::copy(
+        valid.assembly_path(),
+        plugins_dir.path().join("valid.munlib"),
+    )
+    .unwrap();
+    let start_time = std::time::Instant::now();
+    while runtime.get_function_definition("extra").is_none() {
+        if start_time.elapsed() > std::time::Duration::from_secs(10) {
+            panic!("runtime did not load the new library within 10secs");
+        }
+        runtime.update();
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+
+    assert_eq!(runtime.assemblies().count(), 2);
+    assert!(runtime.get_function_definition("gravity").is_none());
+    assert!(runtime.get_function_definition("bonus").is_none());
+    let result: i32 = invoke_fn!(runtime, "main").unwrap();
+    assert_eq!(result, 1);
+    let result: i32 = invoke_fn!(runtime, "extra").unwrap();
+    assert_eq!(result, 3);
+}
+
+#[test]
+fn unload_assembly() {
+    use mun_runtime::{invoke_fn, RuntimeBuilder, StructRef};
+
+    let level = CompileAndRunTestDriver::new(
+        r"
+    pub struct Enemy { health: f32 }
+
+    pub fn spawn_enemy() -> Enemy { Enemy { health: 100.0 } }
+    pub fn enemy_count() -> i32 { 3 }
+    ",
+    );
+    let hud = CompileAndRunTestDriver::new(
+        r"
+    extern fn enemy_count() -> i32;
+
+    pub fn enemies_left() -> i32 { enemy_count() }
+    ",
+    );
+    let runtime = RuntimeBuilder::new(level.assembly_path())
+        .add_library(hud.assembly_path())
+        .spawn()
+        .unwrap();
+
+    // The level cannot be unloaded while the HUD calls its functions
+    assert!(runtime.borrow_mut().unload(level.assembly_path()).is_err());
+    runtime.borrow_mut().unload(hud.assembly_path()).unwrap();
+    assert!(runtime
+        .borrow()
+        .get_function_definition("enemies_left")
+        .is_none());
+    assert!(runtime.borrow_mut().unload(hud.assembly_path()).is_err());
+
+    // The level cannot be unloaded while objects of its types are alive
+    let enemy = {
+        let runtime_ref = runtime.borrow();
+        let enemy: StructRef = invoke_fn!(runtime_ref, "spawn_enemy").unwrap();
+        enemy.root(runtime.clone())
+    };
+    assert!(runtime.borrow_mut().unload(level.assembly_path()).is_err());
+    drop(enemy);
+
+    let mut runtime_ref = runtime.borrow_mut();
+    runtime_ref.unload(level.assembly_path()).unwrap();
+    assert_eq!(runtime_ref.assemblies().count(), 0);
+    let result: Result<i32, _> = invoke_fn!(runtime_ref, "enemy_count");
+    assert!(result.is_err());
+}
+
+#[test]
+fn reload_hooks() {
+    use std::sync::{Arc, Mutex};
+
+    let mut driver = CompileAndRunTestDriver::new(
+        r"
+    pub struct Player { health: f32 }
+
+    pub fn damage() -> f32 { 1.0 }
+    pub fn unchanged() -> i32 { 0 }
+    ",
+    );
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let runtime = driver.runtime();
+    {
+        let mut runtime_ref = runtime.borrow_mut();
+        let before = events.clone();
+        runtime_ref.on_before_reload(move |_, changes| {
+            before.lock().unwrap().push(format!(
+                "before {:?} {:?}",
+                changes.functions(),
+                changes.types()
+            ))
+        });
+        let after = events.clone();
+        runtime_ref.on_after_reload(move |assembly, _| {
+            after
+                .lock()
+                .unwrap()
+                .push(format!("after {}", assembly.functions().len()))
+        });
+        let failed = events.clone();
+        runtime_ref.on_reload_failed(move |_, _| failed.lock().unwrap().push("failed".to_owned()));
+    }
+
+    driver.update(
+        runtime.borrow(),
+        r"
+    pub struct Player { health: f32, armor: f32 }
+
+    pub fn damage(armor: f32) -> f32 { 1.0 - armor }
+    pub fn unchanged() -> i32 { 1 }
+    pub fn added() {}
+    ",
+    );
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![r#"before ["added", "damage"] ["Player"]"#, "after 3"]
+    );
+
+    // An assembly that cannot be linked fails to reload
+    events.lock().unwrap().clear();
+    driver.update_assembly(
+        r"
+    extern fn missing() -> i32;
+
+    pub fn unchanged() -> i32 { missing() }
+    ",
+    );
+    let start_time = std::time::Instant::now();
+    while !events.lock().unwrap().iter().any(|event| event == "failed") {
+        if start_time.elapsed() > std::time::Duration::from_secs(10) {
+            panic!("runtime did not try to reload the assembly within 10secs");
+        }
+        runtime.borrow_mut().update();
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    // The previous version of the assembly remains loaded
+    assert!(runtime.borrow().get_function_definition("added").is_some());
+}
+
+#[test]
+fn failed_reload_keeps_previous_version() {
+    use mun_runtime::invoke_fn;
+
+    let mut driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn helper() -> i32 { 1 }
+    pub fn main() -> i32 { helper() }
+    ",
+    );
+    let runtime = driver.runtime();
+
+    // The new version imports a function that only the previous version defines, which can only
+    // be detected once the functions of the previous version are removed
+    driver.update_assembly(
+        r"
+    extern fn helper() -> i32;
+
+    pub fn main() -> i32 { helper() + 1 }
+    ",
+    );
+    let start_time = std::time::Instant::now();
+    while start_time.elapsed() < std::time::Duration::from_millis(500) {
+        assert!(!runtime.borrow_mut().update());
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+
+    let runtime_ref = runtime.borrow();
+    let result: i32 = invoke_fn!(runtime_ref, "main").unwrap();
+    assert_eq!(result, 1);
+    assert!(runtime_ref.get_function_definition("helper").is_some());
+}
+
+#[test]
+fn run_tests() {
+    use mun_runtime::InvokeError;
+
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+
+    #[test]
+    fn adds() { core::assert(add(1, 2) == 3); }
+
+    #[test]
+    fn fails() { core::assert(add(1, 2) == 4, "wrong sum"); }
+    "#,
+    );
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+    let mut tests: Vec<_> = runtime_ref
+        .tests()
+        .map(|test| test.prototype.name().to_owned())
+        .collect();
+    tests.sort();
+    assert_eq!(tests, vec!["adds", "fails"]);
+    assert!(runtime_ref.get_function_definition("adds").is_none());
+
+    assert_eq!(runtime_ref.run_test("adds"), Ok(()));
+    match runtime_ref.run_test("fails").unwrap_err() {
+        InvokeError::Panic { panic, .. } => assert_eq!(panic.message(), "wrong sum"),
+        error => panic!("unexpected error: {:?}", error),
+    }
+    assert!(matches!(
+        runtime_ref.run_test("missing"),
+        Err(InvokeError::FunctionNotFound { .. })
+    ));
+}
+
+#[test]
+fn run_benches() {
+    use mun_runtime::InvokeError;
+
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+
+    #[bench]
+    fn adds() { add(1, 2); }
+
+    #[bench]
+    fn fails() { core::assert(add(1, 2) == 4, "wrong sum"); }
+    "#,
+    );
+    let runtime = driver.runtime();
+    let runtime_ref = runtime.borrow();
+    let mut benches: Vec<_> = runtime_ref
+        .benches()
+        .map(|bench| bench.prototype.name().to_owned())
+        .collect();
+    benches.sort();
+    assert_eq!(benches, vec!["adds", "fails"]);
+    assert!(runtime_ref.tests().next().is_none());
+    assert!(runtime_ref.get_function_definition("adds").is_none());
+
+    assert!(runtime_ref.run_bench("adds", 100).is_ok());
+    match runtime_ref.run_bench("fails", 100).unwrap_err() {
+        InvokeError::Panic { panic, .. } => assert_eq!(panic.message(), "wrong sum"),
+        error => panic!("unexpected error: {:?}", error),
+    }
+    assert!(matches!(
+        runtime_ref.run_bench("missing", 1),
+        Err(InvokeError::FunctionNotFound { .. })
+    ));
+}
+
+#[test]
+fn load_previous_abi_version() {
+    use abi::{HasStaticTypeInfo, IntoFunctionDefinition};
+    use mun_runtime::{invoke_fn, RuntimeBuilder, StaticAssembly};
+    use std::{ffi::c_void, os::raw::c_char, ptr};
+
+    /// The layout of a struct type in ABI version 0.2.0, which lacks the renamed fields
+    #[repr(C)]
+    struct LegacyStructTypeInfo {
+        type_info: abi::TypeInfo,
+        field_names: *const *const c_char,
+        field_types: *const *const abi::TypeInfo,
+        field_offsets: *const u16,
+        num_fields: u16,
+        memory_kind: abi::StructMemoryKind,
+    }
+
+    /// The layout of a function in ABI version 0.2.0, which lacks the documentation
+    #[repr(C)]
+    struct LegacyFunctionDefinition {
+        prototype: abi::FunctionPrototype,
+        fn_ptr: *const c_void,
+    }
+
+    /// The layout of the assembly information in ABI version 0.2.0, which lacks the build
+    /// metadata and the tests
+    #[repr(C)]
+    struct LegacyAssemblyInfo {
+        symbols: abi::ModuleInfo,
+        dispatch_table: abi::DispatchTable,
+        dependencies: *const *const c_char,
+        num_dependencies: u32,
+    }
+
+    static FIELD_OFFSETS: [u16; 2] = [0, 4];
+
+    extern "C" fn main() -> i32 {
+        42
+    }
+
+    extern "C" fn get_info() -> LegacyAssemblyInfo {
+        let field_names: &'static [*const c_char] = Box::leak(Box::new([
+            b"x\0".as_ptr().cast::<c_char>(),
+            b"y\0".as_ptr().cast::<c_char>(),
+        ]));
+        let field_types: &'static [*const abi::TypeInfo] = Box::leak(Box::new([
+            f32::type_info() as *const abi::TypeInfo,
+            f32::type_info() as *const abi::TypeInfo,
+        ]));
+        let vec2 = Box::leak(Box::new(LegacyStructTypeInfo {
+            type_info: abi::TypeInfo {
+                guid: abi::Guid { b: [1; 16] },
+                name: b"Vec2\0".as_ptr().cast::<c_char>(),
+                size_in_bits: 64,
+                alignment: 4,
+                group: abi::TypeGroup::StructTypes,
+            },
+            field_names: field_names.as_ptr(),
+            field_types: field_types.as_ptr(),
+            field_offsets: FIELD_OFFSETS.as_ptr(),
+            num_fields: 2,
+            memory_kind: abi::StructMemoryKind::Value,
+        }));
+        let types: &'static [*const abi::TypeInfo] =
+            Box::leak(Box::new([&vec2.type_info as *const abi::TypeInfo]));
+
+        let (main, storage) = IntoFunctionDefinition::into(main as extern "C" fn() -> i32, "main");
+        std::mem::forget(storage);
+        let functions: &'static [LegacyFunctionDefinition] =
+            Box::leak(Box::new([LegacyFunctionDefinition {
+                prototype: main.prototype,
+                fn_ptr: main.fn_ptr,
+            }]));
+
+        LegacyAssemblyInfo {
+            symbols: abi::ModuleInfo {
+                path: b"\0".as_ptr().cast::<c_char>(),
+                functions: functions.as_ptr().cast::<abi::FunctionDefinition>(),
+                num_functions: 1,
+                types: types.as_ptr(),
+                num_types: 1,
+            },
+            dispatch_table: abi::DispatchTable {
+                prototypes: ptr::null(),
+                fn_ptrs: ptr::null_mut(),
+                num_entries: 0,
+            },
+            dependencies: ptr::null(),
+            num_dependencies: 0,
+        }
+    }
+
+    extern "C" fn get_version() -> u32 {
+        200
+    }
+
+    extern "C" fn get_unsupported_version() -> u32 {
+        100
+    }
+
+    extern "C" fn set_allocator_handle(_handle: *mut c_void) {}
+
+    let assembly = StaticAssembly {
+        // Safety: the runtime converts the information of legacy assemblies from their layout
+        get_info: unsafe {
+            std::mem::transmute::<extern "C" fn() -> LegacyAssemblyInfo, _>(get_info)
+        },
+        get_version,
+        set_allocator_handle,
+    };
+    let runtime = RuntimeBuilder::from_static(assembly).spawn().unwrap();
+    let runtime_ref = runtime.borrow();
+    assert_eq!(runtime_ref.assemblies().next().unwrap().abi_version(), 200);
+    assert!(!runtime_ref
+        .assemblies()
+        .next()
+        .unwrap()
+        .emits_write_barriers());
+
+    let result: i32 = invoke_fn!(runtime_ref, "main").unwrap();
+    assert_eq!(result, 42);
+    assert!(runtime_ref
+        .get_function_definition("main")
+        .unwrap()
+        .docs()
+        .is_none());
+
+    let vec2 = runtime_ref.types().find(|ty| ty.name() == "Vec2").unwrap();
+    let f32_guid = f32::type_info().guid;
+    assert_eq!(
+        vec2.guid,
+        abi::Guid::from_struct("Vec2", vec![("x", f32_guid), ("y", f32_guid)])
+    );
+    let struct_info = vec2.as_struct().unwrap();
+    assert_eq!(
+        struct_info.field_names().collect::<Vec<_>>(),
+        vec!["x", "y"]
+    );
+    assert_eq!(struct_info.field_offsets(), &[0, 4]);
+    assert!(struct_info.field_renamed_from().all(|name| name.is_none()));
+
+    let assembly = StaticAssembly {
+        get_version: get_unsupported_version,
+        ..assembly
+    };
+    assert!(RuntimeBuilder::from_static(assembly).spawn().is_err());
+}
@@ -0,0 +1,158 @@
+//! Exposes the fields of Mun structs.
+
+use crate::{ErrorHandle, RuntimeHandle, HUB};
+use anyhow::anyhow;
+use memory::gc::{GcPtr, HasIndirectionPtr};
+use runtime::Runtime;
+use std::{
+    ffi::{c_void, CStr},
+    os::raw::c_char,
+};
+
+/// Retrieves the type and the address of the field `field_name` of the struct `obj`, if the field
+/// is of the type corresponding to `field_type`.
+unsafe fn field_ptr(
+    runtime: &Runtime,
+    mut obj: GcPtr,
+    field_name: *const c_char,
+    field_type: abi::Guid,
+) -> Result<(&abi::TypeInfo, *mut u8), anyhow::Error> {
+    if field_name.is_null() {
+        return Err(anyhow!("Invalid argument: 'field_name' is null pointer."));
+    }
+
+    let field_name = match CStr::from_ptr(field_name).to_str() {
+        Ok(name) => name,
+        Err(_) => {
+            return Err(anyhow!(
+                "Invalid argument: 'field_name' is not UTF-8 encoded."
+            ))
+        }
+    };
+
+    // Safety: The type of an object lives at least as long as the `Runtime`.
+    let type_info = &*runtime.gc().ptr_type(obj).into_inner().as_ptr();
+    let struct_info = type_info
+        .as_struct()
+        .ok_or_else(|| anyhow!("Invalid argument: 'obj' is not a struct."))?;
+    let field_idx = abi::StructInfo::find_field_index(type_info.name(), struct_info, field_name)
+        .map_err(|e| anyhow!(e))?;
+
+    // Safety: If we found the `field_idx`, we are guaranteed to also have the `field_type` and
+    // `field_offset`.
+    let field_type_info = *struct_info.field_types().get_unchecked(field_idx);
+    if field_type_info.guid != field_type {
+        return Err(anyhow!(
+            "Mismatched types for `{}::{}`. Found: `{}`.",
+            type_info.name(),
+            field_name,
+            field_type_info.name(),
+        ));
+    }
+
+    let offset = *struct_info.field_offsets().get_unchecked(field_idx);
+    Ok((field_type_info, obj.deref_mut::<u8>().add(offset as usize)))
+}
+
+/// Returns the number of bytes of the value of a field of type `type_info`. A field that refers to
+/// a gc struct contains a `GcPtr`.
+fn field_size(type_info: &abi::TypeInfo) -> usize {
+    let is_gc_struct = type_info
+        .as_struct()
+        .map_or(false, |s| s.memory_kind == abi::StructMemoryKind::GC);
+    if is_gc_struct {
+        std::mem::size_of::<GcPtr>()
+    } else {
+        type_info.size_in_bytes()
+    }
+}
+
+/// Copies the value of the field `field_name` of the struct `obj` to `value`, if the field is of
+/// the type corresponding to `field_type`. A field that refers to a gc struct is copied as a
+/// `GcPtr`. If successful, `value` is set, otherwise a non-zero error handle is returned.
+///
+/// If a non-zero error handle is returned, it must be manually destructed using
+/// [`mun_error_destroy`].
+///
+/// # Safety
+///
+/// This function receives raw pointers as parameters. If any of the arguments is a null pointer,
+/// an error will be returned. Passing pointers to invalid data, will lead to undefined behavior.
+/// `value` must point to memory that can hold a value of the field's type.
+#[no_mangle]
+pub unsafe extern "C" fn mun_struct_get_field(
+    handle: RuntimeHandle,
+    obj: GcPtr,
+    field_name: *const c_char,
+    field_type: abi::Guid,
+    value: *mut c_void,
+) -> ErrorHandle {
+    let runtime = match (handle.0 as *mut Runtime).as_ref() {
+        Some(runtime) => runtime,
+        None => {
+            return HUB
+                .errors
+                .register(anyhow!("Invalid argument: 'runtime' is null pointer."))
+        }
+    };
+
+    if value.is_null() {
+        return HUB
+            .errors
+            .register(anyhow!("Invalid argument: 'value' is null pointer."));
+    }
+
+    let (field_type_info, field_ptr) = match field_ptr(runtime, obj, field_name, field_type) {
+        Ok(field) => field,
+        Err(e) => return HUB.errors.register(e),
+    };
+
+    std::ptr::copy_nonoverlapping(field_ptr, value.cast::<u8>(), field_size(field_type_info));
+    ErrorHandle::default()
+}
+
+/// Replaces the value of the field `field_name` of the struct `obj` with `value`, if the field is
+/// of the type corresponding to `field_type`. A field that refers to a gc struct is replaced by a
+/// `GcPtr`. If successful, the field is set, otherwise a non-zero error handle is returned.
+///
+/// If a non-zero error handle is returned, it must be manually destructed using
+/// [`mun_error_destroy`].
+///
+/// # Safety
+///
+/// This function receives raw pointers as parameters. If any of the arguments is a null pointer,
+/// an error will be returned. Passing pointers to invalid data, will lead to undefined behavior.
+/// `value` must point to a value of the field's type.
+#[no_mangle]
+pub unsafe extern "C" fn mun_struct_set_field(
+    handle: RuntimeHandle,
+    obj: GcPtr,
+    field_name: *const c_char,
+    field_type: abi::Guid,
+    value: *const c_void,
+) -> ErrorHandle {
+    let runtime = match (handle.0 as *mut Runtime).as_ref() {
+        Some(runtime) => runtime,
+        None => {
+            return HUB
+                .errors
+                .register(anyhow!("Invalid argument: 'runtime' is null pointer."))
+        }
+    };
+
+    if value.is_null() {
+        return HUB
+            .errors
+            .register(anyhow!("Invalid argument: 'value' is null pointer."));
+    }
+
+    let (field_type_info, field_ptr) = match field_ptr(runtime, obj, field_name, field_type) {
+        Ok(field) => field,
+        Err(e) => return HUB.errors.register(e),
+    };
+
+    std::ptr::copy_nonoverlapping(value.cast::<u8>(), field_ptr, field_size(field_type_info));
+    // The field might contain references to other objects
+    runtime.gc().write_barrier(obj);
+    ErrorHandle::default()
+}
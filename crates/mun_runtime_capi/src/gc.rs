@@ -172,6 +172,6 @@ pub unsafe extern "C" fn mun_gc_collect(
         }
     };
 
-    *reclaimed = runtime.gc_collect();
+    *reclaimed = runtime.collect_garbage();
     ErrorHandle::default()
 }
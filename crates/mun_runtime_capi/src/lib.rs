@@ -4,6 +4,7 @@
 //! integrate the Mun Runtime into other languages that allow interoperability with C.
 #![warn(missing_docs)]
 
+pub mod adt;
 pub mod error;
 pub mod gc;
 pub mod hub;
@@ -12,7 +13,7 @@ pub mod hub;
 mod tests;
 
 use std::ffi::{c_void, CStr, CString};
-use std::{os::raw::c_char, time::Duration};
+use std::{os::raw::c_char, sync::Arc, time::Duration};
 
 use crate::error::ErrorHandle;
 use crate::hub::HUB;
@@ -139,8 +140,19 @@ pub unsafe extern "C" fn mun_runtime_create(
 
     let runtime_options = runtime::RuntimeOptions {
         library_path: library_path.into(),
+        additional_library_paths: Vec::new(),
+        watch_directories: Vec::new(),
+        static_assembly: None,
         delay: Duration::from_millis(delay_ms.into()),
         user_functions,
+        tracing_dispatch: None,
+        max_stack_size: runtime::DEFAULT_MAX_STACK_SIZE,
+        allocator: Arc::new(runtime::SystemAllocator),
+        gc_threshold: None,
+        max_heap_size: None,
+        max_invoke_duration: None,
+        max_loop_iterations: None,
+        track_allocations: false,
     };
 
     let runtime = match Runtime::new(runtime_options) {
@@ -231,6 +243,77 @@ pub unsafe extern "C" fn mun_runtime_get_function_definition(
     ErrorHandle::default()
 }
 
+/// Retrieves the type information of the type `type_name` from the runtime corresponding to
+/// `handle`. If successful, `has_type_info` and `type_info` are set, otherwise a non-zero error
+/// handle is returned.
+///
+/// If a non-zero error handle is returned, it must be manually destructed using
+/// [`mun_error_destroy`].
+///
+/// # Safety
+///
+/// This function receives raw pointers as parameters. If any of the arguments is a null pointer,
+/// an error will be returned. Passing pointers to invalid data, will lead to undefined behavior.
+#[no_mangle]
+pub unsafe extern "C" fn mun_runtime_find_type_info(
+    handle: RuntimeHandle,
+    type_name: *const c_char,
+    has_type_info: *mut bool,
+    type_info: *mut gc::UnsafeTypeInfo,
+) -> ErrorHandle {
+    let runtime = match (handle.0 as *mut Runtime).as_ref() {
+        Some(runtime) => runtime,
+        None => {
+            return HUB
+                .errors
+                .register(anyhow!("Invalid argument: 'runtime' is null pointer."))
+        }
+    };
+
+    if type_name.is_null() {
+        return HUB
+            .errors
+            .register(anyhow!("Invalid argument: 'type_name' is null pointer."));
+    }
+
+    let type_name = match CStr::from_ptr(type_name).to_str() {
+        Ok(name) => name,
+        Err(_) => {
+            return HUB.errors.register(anyhow!(
+                "Invalid argument: 'type_name' is not UTF-8 encoded."
+            ))
+        }
+    };
+
+    let has_type_info = match has_type_info.as_mut() {
+        Some(has_info) => has_info,
+        None => {
+            return HUB.errors.register(anyhow!(
+                "Invalid argument: 'has_type_info' is null pointer."
+            ))
+        }
+    };
+
+    let type_info = match type_info.as_mut() {
+        Some(info) => info,
+        None => {
+            return HUB
+                .errors
+                .register(anyhow!("Invalid argument: 'type_info' is null pointer."))
+        }
+    };
+
+    match runtime.types().find(|ty| ty.name() == type_name) {
+        Some(info) => {
+            *has_type_info = true;
+            *type_info = gc::UnsafeTypeInfo::new(std::ptr::NonNull::from(info));
+        }
+        None => *has_type_info = false,
+    }
+
+    ErrorHandle::default()
+}
+
 /// Updates the runtime corresponding to `handle`. If successful, `updated` is set, otherwise a
 /// non-zero error handle is returned.
 ///
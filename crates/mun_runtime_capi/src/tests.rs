@@ -1,4 +1,4 @@
-use crate::{error::*, gc::*, *};
+use crate::{adt::*, error::*, gc::*, *};
 use compiler::{Config, Driver, PathOrInline, RelativePathBuf};
 use memory::gc::{GcPtr, HasIndirectionPtr, RawGcPtr};
 use runtime::UnsafeTypeInfo;
@@ -92,12 +92,25 @@ macro_rules! test_invalid_runtime {
 
 test_invalid_runtime!(
     runtime_get_function_definition(ptr::null(), ptr::null_mut(), ptr::null_mut()),
+    runtime_find_type_info(ptr::null(), ptr::null_mut(), ptr::null_mut()),
     runtime_update(ptr::null_mut()),
     gc_alloc(UnsafeTypeInfo::new(NonNull::dangling()), ptr::null_mut()),
     gc_ptr_type(mem::zeroed::<GcPtr>(), ptr::null_mut()),
     gc_root(mem::zeroed::<GcPtr>()),
     gc_unroot(mem::zeroed::<GcPtr>()),
-    gc_collect(ptr::null_mut())
+    gc_collect(ptr::null_mut()),
+    struct_get_field(
+        mem::zeroed::<GcPtr>(),
+        ptr::null(),
+        abi::Guid { b: [0; 16] },
+        ptr::null_mut()
+    ),
+    struct_set_field(
+        mem::zeroed::<GcPtr>(),
+        ptr::null(),
+        abi::Guid { b: [0; 16] },
+        ptr::null()
+    )
 );
 
 #[test]
@@ -544,3 +557,164 @@ fn test_gc_ptr_collect_invalid_reclaimed() {
 
     unsafe { mun_destroy_string(message.as_ptr()) };
 }
+
+#[test]
+fn test_runtime_find_type_info_invalid_type_name() {
+    let driver = TestDriver::new(
+        r#"
+        struct Foo;
+
+        pub fn main() -> Foo { Foo }
+    "#,
+    );
+
+    let mut has_type_info = false;
+    let mut type_info = MaybeUninit::uninit();
+    let handle = unsafe {
+        mun_runtime_find_type_info(
+            driver.runtime,
+            ptr::null(),
+            &mut has_type_info as *mut _,
+            type_info.as_mut_ptr(),
+        )
+    };
+
+    let message = unsafe { CStr::from_ptr(mun_error_message(handle)) };
+    assert_eq!(
+        message.to_str().unwrap(),
+        "Invalid argument: 'type_name' is null pointer."
+    );
+
+    unsafe { mun_destroy_string(message.as_ptr()) };
+}
+
+#[test]
+fn test_runtime_find_type_info() {
+    let driver = TestDriver::new(
+        r#"
+        struct Foo;
+
+        pub fn main() -> Foo { Foo }
+    "#,
+    );
+
+    let type_name = CString::new("Bar").expect("Invalid type name");
+    let mut has_type_info = true;
+    let mut type_info = MaybeUninit::uninit();
+    let handle = unsafe {
+        mun_runtime_find_type_info(
+            driver.runtime,
+            type_name.as_ptr(),
+            &mut has_type_info as *mut _,
+            type_info.as_mut_ptr(),
+        )
+    };
+    assert_eq!(handle.token(), 0);
+    assert!(!has_type_info);
+
+    let type_name = CString::new("Foo").expect("Invalid type name");
+    let handle = unsafe {
+        mun_runtime_find_type_info(
+            driver.runtime,
+            type_name.as_ptr(),
+            &mut has_type_info as *mut _,
+            type_info.as_mut_ptr(),
+        )
+    };
+    assert_eq!(handle.token(), 0);
+    assert!(has_type_info);
+
+    let type_info = unsafe { type_info.assume_init() };
+    assert_eq!(unsafe { type_info.into_inner().as_ref() }.name(), "Foo");
+}
+
+#[test]
+fn test_struct_fields() {
+    let driver = TestDriver::new(
+        r#"
+        struct Foo { a: i32, b: bool }
+
+        pub fn get_a(foo: Foo) -> i32 { foo.a }
+    "#,
+    );
+
+    let type_name = CString::new("Foo").expect("Invalid type name");
+    let mut has_type_info = false;
+    let mut type_info = MaybeUninit::uninit();
+    let handle = unsafe {
+        mun_runtime_find_type_info(
+            driver.runtime,
+            type_name.as_ptr(),
+            &mut has_type_info as *mut _,
+            type_info.as_mut_ptr(),
+        )
+    };
+    assert_eq!(handle.token(), 0);
+
+    let mut obj = MaybeUninit::uninit();
+    let handle = unsafe { mun_gc_alloc(driver.runtime, type_info.assume_init(), obj.as_mut_ptr()) };
+    assert_eq!(handle.token(), 0);
+    let obj = unsafe { obj.assume_init() };
+
+    let field_name = CString::new("a").expect("Invalid field name");
+    let i32_guid = <i32 as abi::HasStaticTypeInfo>::type_info().guid;
+    let value: i32 = 42;
+    let handle = unsafe {
+        mun_struct_set_field(
+            driver.runtime,
+            obj,
+            field_name.as_ptr(),
+            i32_guid,
+            &value as *const i32 as *const _,
+        )
+    };
+    assert_eq!(handle.token(), 0);
+
+    let mut result: i32 = 0;
+    let handle = unsafe {
+        mun_struct_get_field(
+            driver.runtime,
+            obj,
+            field_name.as_ptr(),
+            i32_guid,
+            &mut result as *mut i32 as *mut _,
+        )
+    };
+    assert_eq!(handle.token(), 0);
+    assert_eq!(result, 42);
+
+    // Fields can only be accessed with their own type
+    let field_name = CString::new("b").expect("Invalid field name");
+    let handle = unsafe {
+        mun_struct_get_field(
+            driver.runtime,
+            obj,
+            field_name.as_ptr(),
+            i32_guid,
+            &mut result as *mut i32 as *mut _,
+        )
+    };
+    let message = unsafe { CStr::from_ptr(mun_error_message(handle)) };
+    assert_eq!(
+        message.to_str().unwrap(),
+        "Mismatched types for `Foo::b`. Found: `core::bool`."
+    );
+    unsafe { mun_destroy_string(message.as_ptr()) };
+
+    let field_name = CString::new("c").expect("Invalid field name");
+    let handle = unsafe {
+        mun_struct_get_field(
+            driver.runtime,
+            obj,
+            field_name.as_ptr(),
+            i32_guid,
+            &mut result as *mut i32 as *mut _,
+        )
+    };
+    let message = unsafe { CStr::from_ptr(mun_error_message(handle)) };
+    assert_eq!(
+        message.to_str().unwrap(),
+        "Struct `Foo` does not contain field `c`."
+    );
+    unsafe { mun_destroy_string(message.as_ptr()) };
+}
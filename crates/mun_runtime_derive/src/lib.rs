@@ -0,0 +1,83 @@
+#![cfg(not(tarpaulin_include))]
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
+
+/// This procedural macro implements the `MunStruct` trait, as well as `TryFrom<StructRef>`, for a
+/// struct with named fields. Every field is mapped to the field of the Mun struct with the same
+/// name, which is checked to have the same type when the struct is converted.
+#[proc_macro_derive(MunStruct)]
+pub fn mun_struct_derive(input: TokenStream) -> TokenStream {
+    // Parse Phase
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    let struct_data = match derive_input.data {
+        Data::Struct(data) => data,
+        Data::Union(_) => {
+            panic!("#[derive(MunStruct)] is only defined for structs, not for unions!")
+        }
+        Data::Enum(_) => panic!("#[derive(MunStruct)] is only defined for structs, not for enums!"),
+    };
+    let fields = match struct_data.fields {
+        Fields::Named(fields) => fields.named,
+        _ => panic!("#[derive(MunStruct)] is only defined for structs with named fields!"),
+    };
+    if !derive_input.generics.params.is_empty() {
+        panic!("#[derive(MunStruct)] is not defined for generic structs!");
+    }
+
+    // Get the typename of the struct we're working with
+    let ident = &derive_input.ident;
+    let type_name = ident.to_string();
+
+    let field_idents: Vec<_> = fields.iter().map(|f| f.ident.as_ref().unwrap()).collect();
+    let field_names: Vec<_> = field_idents.iter().map(|i| i.to_string()).collect();
+
+    // Generate a list of where clauses that ensure that we can marshal each field
+    let field_bounds = fields.iter().map(|f| {
+        let ty = &f.ty;
+        quote! {
+            #ty: ::mun_runtime::ArgumentReflection
+                + ::mun_runtime::ReturnTypeReflection
+                + ::mun_runtime::Marshal<'s>
+                + 's
+        }
+    });
+
+    // Generate Phase
+    (quote! {
+        impl<'s> ::mun_runtime::MunStruct<'s> for #ident
+        where
+            #(#field_bounds),*
+        {
+            fn type_name() -> &'static str {
+                #type_name
+            }
+
+            fn from_struct_ref(s: &::mun_runtime::StructRef<'s>) -> Result<Self, String> {
+                <Self as ::mun_runtime::MunStruct<'s>>::check_type(s)?;
+                Ok(Self {
+                    #(#field_idents: s.get(#field_names)?),*
+                })
+            }
+
+            fn write_to_struct_ref(self, s: &mut ::mun_runtime::StructRef<'s>) -> Result<(), String> {
+                <Self as ::mun_runtime::MunStruct<'s>>::check_type(s)?;
+                #(s.set(#field_names, self.#field_idents)?;)*
+                Ok(())
+            }
+        }
+
+        impl<'s> ::std::convert::TryFrom<::mun_runtime::StructRef<'s>> for #ident
+        where
+            Self: ::mun_runtime::MunStruct<'s>,
+        {
+            type Error = String;
+
+            fn try_from(s: ::mun_runtime::StructRef<'s>) -> Result<Self, String> {
+                ::mun_runtime::MunStruct::from_struct_ref(&s)
+            }
+        }
+    })
+    .into()
+}
@@ -0,0 +1,38 @@
+//! The Mun Runtime Python bindings
+//!
+//! The `mun` Python package exposes the Mun Runtime to Python, e.g. to drive munlibs from tools
+//! pipelines and test harnesses. Values are converted to and from Mun types dynamically, based on
+//! the signatures of functions and the types of struct fields:
+//!
+//! ```python
+//! import mun
+//!
+//! runtime = mun.RuntimeBuilder("main.munlib").set_delay(0.01).finish()
+//! foo = runtime.invoke("new_foo", 1.0)
+//! foo.set("b", foo.get("a") + 2.0)
+//! print(foo.type_name, runtime.invoke("sum", foo))
+//! ```
+//!
+//! Like [`Runtime::invoke_dynamic`], the bindings are only supported on x86-64 and AArch64
+//! targets, excluding Windows.
+//!
+//! [`Runtime::invoke_dynamic`]: ../mun_runtime/struct.Runtime.html#method.invoke_dynamic
+#![warn(missing_docs)]
+
+mod runtime;
+mod struct_ref;
+mod value;
+
+use pyo3::prelude::*;
+
+pub use crate::runtime::{Runtime, RuntimeBuilder};
+pub use crate::struct_ref::StructRef;
+
+/// Initializes the `mun` Python module.
+#[pymodule]
+fn mun(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<RuntimeBuilder>()?;
+    m.add_class::<Runtime>()?;
+    m.add_class::<StructRef>()?;
+    Ok(())
+}
@@ -0,0 +1,178 @@
+use crate::value::{to_object, to_value};
+use pyo3::{
+    exceptions::{PyRuntimeError, PyTypeError, PyValueError},
+    prelude::*,
+    types::PyTuple,
+};
+use std::{cell::RefCell, path::PathBuf, rc::Rc, time::Duration};
+
+/// A builder for the construction of a `Runtime`. Every setter returns the builder, so calls can be
+/// chained.
+#[pyclass(unsendable)]
+pub struct RuntimeBuilder {
+    /// The builder, which is taken when the runtime is constructed
+    builder: Option<mun_runtime::RuntimeBuilder>,
+}
+
+impl RuntimeBuilder {
+    /// Replaces the builder with the result of `f`, unless the runtime was already constructed.
+    fn map<F>(&mut self, f: F) -> PyResult<()>
+    where
+        F: FnOnce(mun_runtime::RuntimeBuilder) -> mun_runtime::RuntimeBuilder,
+    {
+        let builder = self.builder.take().ok_or_else(already_finished)?;
+        self.builder = Some(f(builder));
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl RuntimeBuilder {
+    /// Constructs a new builder for a runtime that loads the library at `library_path`.
+    #[new]
+    fn new(library_path: PathBuf) -> Self {
+        Self {
+            builder: Some(mun_runtime::RuntimeBuilder::new(library_path)),
+        }
+    }
+
+    /// Adds the library at `library_path` to the libraries that are loaded by the runtime.
+    fn add_library(mut slf: PyRefMut<Self>, library_path: PathBuf) -> PyResult<PyRefMut<Self>> {
+        slf.map(|builder| builder.add_library(library_path))?;
+        Ok(slf)
+    }
+
+    /// Adds the directory `dir`, from which new munlibs are loaded when the runtime is updated.
+    fn watch_directory(mut slf: PyRefMut<Self>, dir: PathBuf) -> PyResult<PyRefMut<Self>> {
+        slf.map(|builder| builder.watch_directory(dir))?;
+        Ok(slf)
+    }
+
+    /// Sets the delay, in seconds, after which a change of a library is reloaded.
+    fn set_delay(mut slf: PyRefMut<Self>, delay: f64) -> PyResult<PyRefMut<Self>> {
+        let delay = duration(delay)?;
+        slf.map(|builder| builder.set_delay(delay))?;
+        Ok(slf)
+    }
+
+    /// Sets the maximum number of bytes that invocations of Mun functions may use of the stack.
+    fn set_max_stack_size(
+        mut slf: PyRefMut<Self>,
+        max_stack_size: usize,
+    ) -> PyResult<PyRefMut<Self>> {
+        slf.map(|builder| builder.set_max_stack_size(max_stack_size))?;
+        Ok(slf)
+    }
+
+    /// Sets the number of allocated bytes after which the garbage collector collects.
+    fn set_gc_threshold(mut slf: PyRefMut<Self>, gc_threshold: usize) -> PyResult<PyRefMut<Self>> {
+        slf.map(|builder| builder.set_gc_threshold(gc_threshold))?;
+        Ok(slf)
+    }
+
+    /// Sets the maximum number of bytes that Mun code may allocate on the heap.
+    fn set_max_heap_size(
+        mut slf: PyRefMut<Self>,
+        max_heap_size: usize,
+    ) -> PyResult<PyRefMut<Self>> {
+        slf.map(|builder| builder.set_max_heap_size(max_heap_size))?;
+        Ok(slf)
+    }
+
+    /// Sets the maximum duration, in seconds, of an invocation of a Mun function.
+    fn set_max_invoke_duration(
+        mut slf: PyRefMut<Self>,
+        max_invoke_duration: f64,
+    ) -> PyResult<PyRefMut<Self>> {
+        let max_invoke_duration = duration(max_invoke_duration)?;
+        slf.map(|builder| builder.set_max_invoke_duration(max_invoke_duration))?;
+        Ok(slf)
+    }
+
+    /// Sets the maximum number of loop iterations of an invocation of a Mun function.
+    fn set_max_loop_iterations(
+        mut slf: PyRefMut<Self>,
+        max_loop_iterations: u64,
+    ) -> PyResult<PyRefMut<Self>> {
+        slf.map(|builder| builder.set_max_loop_iterations(max_loop_iterations))?;
+        Ok(slf)
+    }
+
+    /// Constructs the runtime. The builder cannot be used afterwards.
+    fn finish(&mut self) -> PyResult<Runtime> {
+        let builder = self.builder.take().ok_or_else(already_finished)?;
+        let runtime = builder
+            .spawn()
+            .map_err(|e| PyRuntimeError::new_err(format!("{:?}", e)))?;
+        Ok(Runtime { runtime })
+    }
+}
+
+/// Returns the error for the use of a builder of which the runtime was already constructed.
+fn already_finished() -> PyErr {
+    PyRuntimeError::new_err("The runtime was already constructed.")
+}
+
+/// Converts the number of `seconds` to a `Duration`.
+fn duration(seconds: f64) -> PyResult<Duration> {
+    if seconds.is_finite() && seconds >= 0.0 {
+        Ok(Duration::from_secs_f64(seconds))
+    } else {
+        Err(PyValueError::new_err(format!(
+            "Invalid duration: {}. Expected a non-negative number of seconds.",
+            seconds
+        )))
+    }
+}
+
+/// A runtime that loads and hot reloads munlibs, and invokes their functions.
+#[pyclass(unsendable)]
+pub struct Runtime {
+    runtime: Rc<RefCell<mun_runtime::Runtime>>,
+}
+
+#[pymethods]
+impl Runtime {
+    /// Invokes the function `function_name` with `args`, which are converted to the types of the
+    /// function's parameters. Returns the function's return value, or `None` if it has no return
+    /// type.
+    #[args(args = "*")]
+    fn invoke(&self, py: Python, function_name: &str, args: &PyTuple) -> PyResult<PyObject> {
+        let runtime_ref = self.runtime.borrow();
+        let function_info = runtime_ref
+            .get_function_definition(function_name)
+            .ok_or_else(|| {
+                PyValueError::new_err(format!("Failed to obtain function '{}'", function_name))
+            })?;
+
+        let arg_types = function_info.prototype.signature.arg_types();
+        if arg_types.len() != args.len() {
+            return Err(PyTypeError::new_err(format!(
+                "Invalid number of arguments. Expected: {}. Found: {}.",
+                arg_types.len(),
+                args.len(),
+            )));
+        }
+        let args = args
+            .iter()
+            .zip(arg_types.iter())
+            .map(|(arg, arg_type)| to_value(&self.runtime, &runtime_ref, arg, arg_type))
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let result = runtime_ref
+            .invoke_dynamic(function_name, &args)
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        to_object(py, &self.runtime, result)
+    }
+
+    /// Reloads the libraries that changed. Returns `True` if a library was reloaded.
+    fn update(&self) -> bool {
+        self.runtime.borrow_mut().update()
+    }
+
+    /// Collects all memory that is no longer referenced by rooted objects. Returns `True` if memory
+    /// was reclaimed.
+    fn collect_garbage(&self) -> bool {
+        self.runtime.borrow().collect_garbage()
+    }
+}
@@ -0,0 +1,145 @@
+use crate::value::{to_object, to_value, unsupported_type};
+use mun_runtime::{ReturnTypeReflection, RootedStruct, Value};
+use pyo3::{
+    exceptions::{PyAttributeError, PyTypeError, PyValueError},
+    prelude::*,
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// A reference to a Mun struct, which is rooted for as long as the Python object exists. Its fields
+/// are accessed by name and converted to and from Python objects based on their types.
+#[pyclass(unsendable)]
+pub struct StructRef {
+    runtime: Rc<RefCell<mun_runtime::Runtime>>,
+    inner: RootedStruct,
+}
+
+impl StructRef {
+    /// Wraps the struct `inner`, which was allocated by `runtime`.
+    pub(crate) fn new(runtime: Rc<RefCell<mun_runtime::Runtime>>, inner: RootedStruct) -> Self {
+        Self { runtime, inner }
+    }
+
+    /// Returns a `StructRef` of the runtime for the struct, using the borrow `runtime_ref` of
+    /// `runtime`. Returns an error if the struct was not allocated by `runtime`.
+    pub(crate) fn as_ref<'r>(
+        &self,
+        runtime: &Rc<RefCell<mun_runtime::Runtime>>,
+        runtime_ref: &'r mun_runtime::Runtime,
+    ) -> PyResult<mun_runtime::StructRef<'r>> {
+        if !Rc::ptr_eq(&self.runtime, runtime) {
+            return Err(PyValueError::new_err(
+                "The struct was allocated by a different runtime.",
+            ));
+        }
+
+        // Safety: The struct was allocated by the runtime
+        Ok(unsafe { self.inner.as_ref(runtime_ref) })
+    }
+}
+
+#[pymethods]
+impl StructRef {
+    /// Returns the name of the struct's type.
+    #[getter]
+    fn type_name(&self) -> String {
+        self.inner.by_ref().type_info().name().to_string()
+    }
+
+    /// Returns the value of the field `field_name`.
+    fn get(&self, py: Python, field_name: &str) -> PyResult<PyObject> {
+        let runtime_ref = self.runtime.borrow();
+        let s = self.as_ref(&self.runtime, &runtime_ref)?;
+        let value = get_field(&s, field_name)?;
+        to_object(py, &self.runtime, value)
+    }
+
+    /// Sets the value of the field `field_name` to `value`, which is converted to the type of the
+    /// field.
+    fn set(&self, field_name: &str, value: &PyAny) -> PyResult<()> {
+        let runtime_ref = self.runtime.borrow();
+        let mut s = self.as_ref(&self.runtime, &runtime_ref)?;
+        let value = to_value(
+            &self.runtime,
+            &runtime_ref,
+            value,
+            field_type(&s, field_name)?,
+        )?;
+        set_field(&mut s, field_name, value).map_err(PyTypeError::new_err)
+    }
+}
+
+/// Returns the type of the field `field_name` of `s`.
+fn field_type<'a>(s: &'a mun_runtime::StructRef, field_name: &str) -> PyResult<&'a abi::TypeInfo> {
+    let type_info = s.type_info();
+
+    // Safety: `as_struct` is guaranteed to return `Some` for `StructRef`s.
+    let struct_info = type_info.as_struct().unwrap();
+    let field_idx = abi::StructInfo::find_field_index(type_info.name(), struct_info, field_name)
+        .map_err(PyAttributeError::new_err)?;
+    Ok(struct_info.field_types()[field_idx])
+}
+
+/// Returns the value of the field `field_name` of `s`.
+fn get_field<'r>(s: &mun_runtime::StructRef<'r>, field_name: &str) -> PyResult<Value<'r>> {
+    let type_info = field_type(s, field_name)?;
+
+    macro_rules! match_guid {
+        ($($ty:ty => $variant:ident),+ $(,)?) => {
+            $(
+                if type_info.guid == <$ty as ReturnTypeReflection>::type_guid() {
+                    return s
+                        .get::<$ty>(field_name)
+                        .map(Value::$variant)
+                        .map_err(PyTypeError::new_err);
+                }
+            )+
+        };
+    }
+
+    if type_info.group.is_struct() {
+        return s
+            .get(field_name)
+            .map(Value::Struct)
+            .map_err(PyTypeError::new_err);
+    }
+    match_guid!(
+        bool => Bool,
+        i8 => I8,
+        i16 => I16,
+        i32 => I32,
+        i64 => I64,
+        u8 => U8,
+        u16 => U16,
+        u32 => U32,
+        u64 => U64,
+        f32 => F32,
+        f64 => F64,
+        abi::Handle => Handle,
+    );
+    Err(PyTypeError::new_err(unsupported_type(type_info)))
+}
+
+/// Sets the value of the field `field_name` of `s` to `value`.
+fn set_field<'r>(
+    s: &mut mun_runtime::StructRef<'r>,
+    field_name: &str,
+    value: Value<'r>,
+) -> Result<(), String> {
+    match value {
+        Value::Empty => Err(format!("Cannot assign an empty value to `{}`.", field_name)),
+        Value::Bool(v) => s.set(field_name, v),
+        Value::I8(v) => s.set(field_name, v),
+        Value::I16(v) => s.set(field_name, v),
+        Value::I32(v) => s.set(field_name, v),
+        Value::I64(v) => s.set(field_name, v),
+        Value::U8(v) => s.set(field_name, v),
+        Value::U16(v) => s.set(field_name, v),
+        Value::U32(v) => s.set(field_name, v),
+        Value::U64(v) => s.set(field_name, v),
+        Value::F32(v) => s.set(field_name, v),
+        Value::F64(v) => s.set(field_name, v),
+        Value::Handle(v) => s.set(field_name, v),
+        Value::Struct(v) => s.set(field_name, v),
+    }
+}
@@ -0,0 +1,82 @@
+use crate::struct_ref::StructRef;
+use mun_runtime::{ReturnTypeReflection, Value};
+use pyo3::{exceptions::PyTypeError, prelude::*};
+use std::{cell::RefCell, rc::Rc};
+
+/// Converts the Python object `obj` to a value of the Mun type `type_info`. Structs must have been
+/// allocated by `runtime`, of which `runtime_ref` is a borrow.
+pub(crate) fn to_value<'r>(
+    runtime: &Rc<RefCell<mun_runtime::Runtime>>,
+    runtime_ref: &'r mun_runtime::Runtime,
+    obj: &PyAny,
+    type_info: &abi::TypeInfo,
+) -> PyResult<Value<'r>> {
+    macro_rules! match_guid {
+        ($($ty:ty => $variant:ident),+ $(,)?) => {
+            $(
+                if type_info.guid == <$ty as ReturnTypeReflection>::type_guid() {
+                    return Ok(Value::$variant(obj.extract()?));
+                }
+            )+
+        };
+    }
+
+    if type_info.group.is_struct() {
+        let s: PyRef<StructRef> = obj.extract()?;
+        return s.as_ref(runtime, runtime_ref).map(Value::Struct);
+    }
+    if type_info.guid == <abi::Handle as ReturnTypeReflection>::type_guid() {
+        return Ok(Value::Handle(abi::Handle(obj.extract()?)));
+    }
+    match_guid!(
+        bool => Bool,
+        i8 => I8,
+        i16 => I16,
+        i32 => I32,
+        i64 => I64,
+        u8 => U8,
+        u16 => U16,
+        u32 => U32,
+        u64 => U64,
+        f32 => F32,
+        f64 => F64,
+    );
+    Err(PyTypeError::new_err(unsupported_type(type_info)))
+}
+
+/// Converts `value` to a Python object. Structs are rooted, so they remain alive for as long as the
+/// Python object exists.
+pub(crate) fn to_object(
+    py: Python,
+    runtime: &Rc<RefCell<mun_runtime::Runtime>>,
+    value: Value,
+) -> PyResult<PyObject> {
+    let obj = match value {
+        Value::Empty => py.None(),
+        Value::Bool(v) => v.into_py(py),
+        Value::I8(v) => v.into_py(py),
+        Value::I16(v) => v.into_py(py),
+        Value::I32(v) => v.into_py(py),
+        Value::I64(v) => v.into_py(py),
+        Value::U8(v) => v.into_py(py),
+        Value::U16(v) => v.into_py(py),
+        Value::U32(v) => v.into_py(py),
+        Value::U64(v) => v.into_py(py),
+        Value::F32(v) => v.into_py(py),
+        Value::F64(v) => v.into_py(py),
+        Value::Handle(v) => v.0.into_py(py),
+        Value::Struct(s) => {
+            let rooted = s.root(runtime.clone());
+            Py::new(py, StructRef::new(runtime.clone(), rooted))?.into_py(py)
+        }
+    };
+    Ok(obj)
+}
+
+/// Returns the error message for a type that cannot be converted to and from Python.
+pub(crate) fn unsupported_type(type_info: &abi::TypeInfo) -> String {
+    format!(
+        "The type `{}` is not supported by the Python bindings.",
+        type_info.name()
+    )
+}
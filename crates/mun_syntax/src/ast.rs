@@ -1,13 +1,16 @@
+pub mod edit;
 mod expr_extensions;
 #[macro_use]
 mod extensions;
 mod generated;
+pub mod make;
 mod tokens;
 mod traits;
 
 use crate::{syntax_node::SyntaxNodeChildren, SmolStr, SyntaxKind, SyntaxNode, SyntaxToken};
 
 pub use self::{
+    edit::AstNodeEdit,
     expr_extensions::*,
     extensions::{PathSegmentKind, StructKind},
     generated::*,
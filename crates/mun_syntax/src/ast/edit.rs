@@ -0,0 +1,274 @@
+//! Functions that return an edited copy of a syntax node.
+//!
+//! Syntax trees are immutable, so every edit returns a node of a new tree that shares all
+//! unmodified parts with the original tree. The whitespace and comments around the edited parts are
+//! preserved, so the text of the new tree only differs from the original text within the edited
+//! node. Use `text_edit` to turn this difference into a `TextEdit`.
+
+use crate::{
+    ast::{self, make},
+    syntax_node::{GreenNode, MunLanguage, NodeOrToken},
+    AstNode, SyntaxElement, SyntaxKind, SyntaxNode, TextEdit,
+};
+use rowan::Language;
+use std::ops::Range;
+
+/// Returns an edit that changes the text of `old` into the text of `new`, which is an edited copy
+/// of `old`.
+pub fn text_edit<N: AstNode>(old: &N, new: &N) -> TextEdit {
+    TextEdit::replace(old.syntax().text_range(), new.syntax().text().to_string())
+}
+
+pub trait AstNodeEdit: AstNode + Sized {
+    /// Returns a copy of this node in which the descendant `old` is replaced by `new`, e.g. to
+    /// rename a `Name` or `NameRef`.
+    fn replace_descendant<D: AstNode>(&self, old: &D, new: D) -> Self {
+        assert!(
+            old.syntax().ancestors().any(|it| it == *self.syntax()),
+            "`old` must be a descendant of the edited node"
+        );
+        if old.syntax() == self.syntax() {
+            return Self::cast(new.syntax().clone()).expect("replacement must be of the same kind");
+        }
+        // The replacement is spliced into the children of the parent, because it may be of a
+        // different kind than `old`, e.g. a path that replaces a binary expression.
+        let parent = old.syntax().parent().unwrap();
+        let index = index_in_parent(&old.syntax().clone().into());
+        let new_parent =
+            replace_children(&parent, index..index + 1, vec![new.syntax().clone().into()]);
+        let new_root = new_parent.ancestors().last().unwrap();
+        Self::cast(node_at_same_position(self.syntax(), new_root)).unwrap()
+    }
+}
+
+impl<N: AstNode> AstNodeEdit for N {}
+
+impl ast::RecordFieldList {
+    /// Returns a copy of this list with `field` added after the existing fields.
+    pub fn append_field(&self, field: ast::RecordField) -> ast::RecordFieldList {
+        self.append_fields(std::iter::once(field))
+    }
+
+    /// Returns a copy of this list with `fields` added after the existing fields and before the
+    /// spread expression, if any.
+    pub fn append_fields(
+        &self,
+        fields: impl IntoIterator<Item = ast::RecordField>,
+    ) -> ast::RecordFieldList {
+        let fields: Vec<_> = fields.into_iter().map(|it| it.syntax().clone()).collect();
+        let spread_start = self
+            .syntax()
+            .children_with_tokens()
+            .find(|it| it.kind() == T![..]);
+        let last_field = self.fields().last().map(|it| it.syntax().clone());
+        ast::RecordFieldList::cast(append_list_items(
+            self.syntax(),
+            last_field,
+            spread_start,
+            fields,
+        ))
+        .unwrap()
+    }
+}
+
+impl ast::RecordFieldDefList {
+    /// Returns a copy of this list with `field` added after the existing fields.
+    pub fn append_field(&self, field: ast::RecordFieldDef) -> ast::RecordFieldDefList {
+        self.append_fields(std::iter::once(field))
+    }
+
+    /// Returns a copy of this list with `fields` added after the existing fields.
+    pub fn append_fields(
+        &self,
+        fields: impl IntoIterator<Item = ast::RecordFieldDef>,
+    ) -> ast::RecordFieldDefList {
+        let fields: Vec<_> = fields.into_iter().map(|it| it.syntax().clone()).collect();
+        let last_field = self.fields().last().map(|it| it.syntax().clone());
+        ast::RecordFieldDefList::cast(append_list_items(self.syntax(), last_field, None, fields))
+            .unwrap()
+    }
+}
+
+impl ast::BlockExpr {
+    /// Returns a copy of this block with `statement` inserted before `anchor`, which is one of the
+    /// statements or the tail expression of the block. The new statement is separated from `anchor`
+    /// in the same way as `anchor` is separated from the element before it; if `anchor` starts on a
+    /// new line, so does the statement after the new one.
+    pub fn insert_statement(&self, anchor: &SyntaxNode, statement: ast::Stmt) -> ast::BlockExpr {
+        assert_eq!(
+            anchor.parent().as_ref(),
+            Some(self.syntax()),
+            "`anchor` must be a child of the block"
+        );
+        let separator = match anchor.prev_sibling_or_token() {
+            Some(NodeOrToken::Token(token))
+                if token.kind() == SyntaxKind::WHITESPACE && token.text().contains('\n') =>
+            {
+                token.text().to_string()
+            }
+            _ => " ".to_string(),
+        };
+        let index = index_in_parent(&anchor.clone().into());
+        ast::BlockExpr::cast(replace_children(
+            self.syntax(),
+            index..index,
+            vec![
+                statement.syntax().clone().into(),
+                make::tokens::whitespace(&separator).into(),
+            ],
+        ))
+        .unwrap()
+    }
+}
+
+/// Adds `items` to a comma separated list that is delimited by curly braces. The new items are
+/// inserted after `last_item` or, if the list is empty, before `end` (e.g. the `..` of a spread
+/// expression) or the closing brace. The items are separated in the same way as the existing items;
+/// if the last item starts on a new line, so do the new items.
+fn append_list_items(
+    list: &SyntaxNode,
+    last_item: Option<SyntaxNode>,
+    end: Option<SyntaxElement>,
+    items: Vec<SyntaxNode>,
+) -> SyntaxNode {
+    if items.is_empty() {
+        return list.clone();
+    }
+
+    let mut to_insert: Vec<SyntaxElement> = Vec::new();
+    let position = match last_item {
+        Some(last_item) => {
+            let separator = match last_item.prev_sibling_or_token() {
+                Some(NodeOrToken::Token(token))
+                    if token.kind() == SyntaxKind::WHITESPACE && token.text().contains('\n') =>
+                {
+                    token.text().to_string()
+                }
+                _ => " ".to_string(),
+            };
+            let trailing_comma = last_item
+                .siblings_with_tokens(rowan::Direction::Next)
+                .skip(1)
+                .find(|it| !it.kind().is_trivia())
+                .filter(|it| it.kind() == T![,]);
+            match trailing_comma {
+                // Keep the trailing comma by adding one after every new item
+                Some(comma) => {
+                    for item in items {
+                        to_insert.push(make::tokens::whitespace(&separator).into());
+                        to_insert.push(item.into());
+                        to_insert.push(make::tokens::comma().into());
+                    }
+                    // Keep a comment at the end of the line of the last item on that line
+                    let mut index = index_in_parent(&comma) + 1;
+                    if separator.contains('\n') {
+                        index += list
+                            .children_with_tokens()
+                            .skip(index)
+                            .take_while(|it| {
+                                it.kind() == SyntaxKind::COMMENT
+                                    || (it.kind() == SyntaxKind::WHITESPACE
+                                        && !it.to_string().contains('\n'))
+                            })
+                            .count();
+                    }
+                    index..index
+                }
+                None => {
+                    for item in items {
+                        to_insert.push(make::tokens::comma().into());
+                        to_insert.push(make::tokens::whitespace(&separator).into());
+                        to_insert.push(item.into());
+                    }
+                    let index = index_in_parent(&last_item.into()) + 1;
+                    index..index
+                }
+            }
+        }
+        None => match end {
+            Some(end) => {
+                for item in items {
+                    to_insert.push(item.into());
+                    to_insert.push(make::tokens::comma().into());
+                    to_insert.push(make::tokens::single_space().into());
+                }
+                let index = index_in_parent(&end);
+                index..index
+            }
+            None => {
+                // Replace everything between the braces, e.g. `{}` becomes `{ a: 1, b: 2 }`
+                for (idx, item) in items.into_iter().enumerate() {
+                    if idx > 0 {
+                        to_insert.push(make::tokens::comma().into());
+                    }
+                    to_insert.push(make::tokens::single_space().into());
+                    to_insert.push(item.into());
+                }
+                to_insert.push(make::tokens::single_space().into());
+                let children: Vec<_> = list.children_with_tokens().collect();
+                let start = children
+                    .iter()
+                    .position(|it| it.kind() == T!['{'])
+                    .map_or(0, |it| it + 1);
+                let end = children
+                    .iter()
+                    .rposition(|it| it.kind() == T!['}'])
+                    .unwrap_or(children.len());
+                start..end.max(start)
+            }
+        },
+    };
+    replace_children(list, position, to_insert)
+}
+
+/// Returns a copy of `parent` in which the children with indices in `range` are replaced by
+/// `replacement`.
+fn replace_children(
+    parent: &SyntaxNode,
+    range: Range<usize>,
+    replacement: Vec<SyntaxElement>,
+) -> SyntaxNode {
+    let mut children = parent.green().children().to_vec();
+    children.splice(
+        range,
+        replacement.into_iter().map(|element| match element {
+            NodeOrToken::Node(node) => node.green().clone().into(),
+            NodeOrToken::Token(token) => token.green().clone().into(),
+        }),
+    );
+    let new_parent = GreenNode::new(
+        MunLanguage::kind_to_raw(parent.kind()),
+        children.into_boxed_slice(),
+    );
+    node_at_same_position(
+        parent,
+        SyntaxNode::new_root(parent.replace_with(new_parent)),
+    )
+}
+
+/// Returns the node of `new_root` that is at the same position as `node` is in its own tree. The
+/// structure of the trees between the roots and the node must be the same.
+fn node_at_same_position(node: &SyntaxNode, new_root: SyntaxNode) -> SyntaxNode {
+    let mut indices: Vec<_> = node
+        .ancestors()
+        .take_while(|it| it.parent().is_some())
+        .map(|it| index_in_parent(&it.into()))
+        .collect();
+    indices.reverse();
+    indices.into_iter().fold(new_root, |parent, index| {
+        parent
+            .children_with_tokens()
+            .nth(index)
+            .and_then(|it| it.into_node())
+            .expect("the structure of the tree must be unchanged")
+    })
+}
+
+/// Returns the index of `element` among the children and tokens of its parent.
+fn index_in_parent(element: &SyntaxElement) -> usize {
+    let parent = element.parent().expect("element must have a parent");
+    parent
+        .children_with_tokens()
+        .position(|it| it == *element)
+        .unwrap()
+}
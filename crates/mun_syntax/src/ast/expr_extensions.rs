@@ -204,6 +204,7 @@ impl ast::FieldExpr {
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum LiteralKind {
     String,
+    Char,
     IntNumber,
     FloatNumber,
     Bool,
@@ -221,6 +222,7 @@ impl Literal {
     pub fn kind(&self) -> LiteralKind {
         match self.token().kind() {
             STRING => LiteralKind::String,
+            CHAR => LiteralKind::Char,
             FLOAT_NUMBER => LiteralKind::FloatNumber,
             INT_NUMBER => LiteralKind::IntNumber,
             T![true] | T![false] => LiteralKind::Bool,
@@ -235,6 +237,7 @@ impl Literal {
         let text = token.text();
         match self.kind() {
             LiteralKind::String => (text.clone(), None),
+            LiteralKind::Char => (text.clone(), None),
             LiteralKind::IntNumber => {
                 let (str, suffix) = split_int_text_and_suffix(text);
                 (SmolStr::new(str), suffix.map(SmolStr::new))
@@ -352,6 +355,35 @@ impl ast::IfExpr {
     }
 }
 
+/// Returns the `LABEL_IDENT` token (e.g. `'outer`) that is a direct child of the specified node.
+fn label_ident_token(node: &impl AstNode) -> Option<SyntaxToken> {
+    node.syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find(|it| it.kind() == LABEL_IDENT)
+}
+
+impl ast::Label {
+    /// Returns the token of the label, e.g. `'outer` in `'outer: loop {}`
+    pub fn label_ident_token(&self) -> Option<SyntaxToken> {
+        label_ident_token(self)
+    }
+}
+
+impl ast::BreakExpr {
+    /// Returns the token of the label that is targeted, e.g. `'outer` in `break 'outer`
+    pub fn label_ident_token(&self) -> Option<SyntaxToken> {
+        label_ident_token(self)
+    }
+}
+
+impl ast::ContinueExpr {
+    /// Returns the token of the label that is targeted, e.g. `'outer` in `continue 'outer`
+    pub fn label_ident_token(&self) -> Option<SyntaxToken> {
+        label_ident_token(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{split_float_text_and_suffix, split_int_text_and_suffix};
@@ -2,7 +2,7 @@ use crate::{
     ast::{self, child_opt, AstNode, NameOwner},
     SyntaxKind, T,
 };
-use crate::{SmolStr, SyntaxNode};
+use crate::{SmolStr, SyntaxNode, SyntaxToken};
 use abi::StructMemoryKind;
 use text_unit::TextRange;
 
@@ -168,6 +168,49 @@ impl ast::StructDef {
     }
 }
 
+impl ast::Attr {
+    /// Returns the name of the attribute if its path consists of a single identifier, e.g.
+    /// `derive` in `#[derive(Eq)]`.
+    pub fn simple_name(&self) -> Option<SmolStr> {
+        let path = self.path()?;
+        if path.qualifier().is_some() {
+            return None;
+        }
+        path.segment()?
+            .name_ref()
+            .map(|name_ref| name_ref.text().clone())
+    }
+}
+
+impl ast::TokenTree {
+    /// Returns the identifiers in the token tree, e.g. `Eq` and `Ord` in `#[derive(Eq, Ord)]`.
+    pub fn idents(&self) -> impl Iterator<Item = SyntaxToken> {
+        self.syntax()
+            .children_with_tokens()
+            .filter_map(|it| it.into_token())
+            .filter(|it| it.kind() == SyntaxKind::IDENT)
+    }
+}
+
+impl ast::ImplDef {
+    /// Returns the trait that is implemented, e.g. `Add` in `impl Add for Vec2 {}`.
+    pub fn target_trait(&self) -> Option<ast::TypeRef> {
+        self.syntax()
+            .children()
+            .filter_map(ast::TypeRef::cast)
+            .next()
+    }
+
+    /// Returns the type for which the trait is implemented, e.g. `Vec2` in
+    /// `impl Add for Vec2 {}`.
+    pub fn target_type(&self) -> Option<ast::TypeRef> {
+        self.syntax()
+            .children()
+            .filter_map(ast::TypeRef::cast)
+            .nth(1)
+    }
+}
+
 pub enum VisibilityKind {
     PubPackage,
     PubSuper,
@@ -46,6 +46,41 @@ impl ArgList {
     }
 }
 
+// Attr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Attr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for Attr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            ATTR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Attr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl Attr {
+    pub fn path(&self) -> Option<Path> {
+        super::child_opt(self)
+    }
+
+    pub fn token_tree(&self) -> Option<TokenTree> {
+        super::child_opt(self)
+    }
+}
+
 // BinExpr
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -203,6 +238,41 @@ impl CallExpr {
     }
 }
 
+// CastExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CastExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for CastExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            CAST_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(CastExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl CastExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+
+    pub fn type_ref(&self) -> Option<TypeRef> {
+        super::child_opt(self)
+    }
+}
+
 // Condition
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -238,6 +308,33 @@ impl Condition {
     }
 }
 
+// ContinueExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContinueExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for ContinueExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            CONTINUE_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(ContinueExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ContinueExpr {}
+
 // Expr
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -248,9 +345,9 @@ pub struct Expr {
 impl AstNode for Expr {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            LITERAL | PREFIX_EXPR | PATH_EXPR | BIN_EXPR | PAREN_EXPR | CALL_EXPR | FIELD_EXPR
-            | IF_EXPR | LOOP_EXPR | WHILE_EXPR | RETURN_EXPR | BREAK_EXPR | BLOCK_EXPR
-            | RECORD_LIT => true,
+            LITERAL | PREFIX_EXPR | PATH_EXPR | BIN_EXPR | PAREN_EXPR | CALL_EXPR
+            | METHOD_CALL_EXPR | FIELD_EXPR | CAST_EXPR | IF_EXPR | LOOP_EXPR | WHILE_EXPR
+            | RETURN_EXPR | BREAK_EXPR | CONTINUE_EXPR | BLOCK_EXPR | RECORD_LIT => true,
             _ => false,
         }
     }
@@ -273,12 +370,15 @@ pub enum ExprKind {
     BinExpr(BinExpr),
     ParenExpr(ParenExpr),
     CallExpr(CallExpr),
+    MethodCallExpr(MethodCallExpr),
     FieldExpr(FieldExpr),
+    CastExpr(CastExpr),
     IfExpr(IfExpr),
     LoopExpr(LoopExpr),
     WhileExpr(WhileExpr),
     ReturnExpr(ReturnExpr),
     BreakExpr(BreakExpr),
+    ContinueExpr(ContinueExpr),
     BlockExpr(BlockExpr),
     RecordLit(RecordLit),
 }
@@ -312,11 +412,21 @@ impl From<CallExpr> for Expr {
         Expr { syntax: n.syntax }
     }
 }
+impl From<MethodCallExpr> for Expr {
+    fn from(n: MethodCallExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
 impl From<FieldExpr> for Expr {
     fn from(n: FieldExpr) -> Expr {
         Expr { syntax: n.syntax }
     }
 }
+impl From<CastExpr> for Expr {
+    fn from(n: CastExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
 impl From<IfExpr> for Expr {
     fn from(n: IfExpr) -> Expr {
         Expr { syntax: n.syntax }
@@ -342,6 +452,11 @@ impl From<BreakExpr> for Expr {
         Expr { syntax: n.syntax }
     }
 }
+impl From<ContinueExpr> for Expr {
+    fn from(n: ContinueExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
 impl From<BlockExpr> for Expr {
     fn from(n: BlockExpr) -> Expr {
         Expr { syntax: n.syntax }
@@ -362,12 +477,19 @@ impl Expr {
             BIN_EXPR => ExprKind::BinExpr(BinExpr::cast(self.syntax.clone()).unwrap()),
             PAREN_EXPR => ExprKind::ParenExpr(ParenExpr::cast(self.syntax.clone()).unwrap()),
             CALL_EXPR => ExprKind::CallExpr(CallExpr::cast(self.syntax.clone()).unwrap()),
+            METHOD_CALL_EXPR => {
+                ExprKind::MethodCallExpr(MethodCallExpr::cast(self.syntax.clone()).unwrap())
+            }
             FIELD_EXPR => ExprKind::FieldExpr(FieldExpr::cast(self.syntax.clone()).unwrap()),
+            CAST_EXPR => ExprKind::CastExpr(CastExpr::cast(self.syntax.clone()).unwrap()),
             IF_EXPR => ExprKind::IfExpr(IfExpr::cast(self.syntax.clone()).unwrap()),
             LOOP_EXPR => ExprKind::LoopExpr(LoopExpr::cast(self.syntax.clone()).unwrap()),
             WHILE_EXPR => ExprKind::WhileExpr(WhileExpr::cast(self.syntax.clone()).unwrap()),
             RETURN_EXPR => ExprKind::ReturnExpr(ReturnExpr::cast(self.syntax.clone()).unwrap()),
             BREAK_EXPR => ExprKind::BreakExpr(BreakExpr::cast(self.syntax.clone()).unwrap()),
+            CONTINUE_EXPR => {
+                ExprKind::ContinueExpr(ContinueExpr::cast(self.syntax.clone()).unwrap())
+            }
             BLOCK_EXPR => ExprKind::BlockExpr(BlockExpr::cast(self.syntax.clone()).unwrap()),
             RECORD_LIT => ExprKind::RecordLit(RecordLit::cast(self.syntax.clone()).unwrap()),
             _ => unreachable!(),
@@ -472,6 +594,7 @@ impl ast::NameOwner for FunctionDef {}
 impl ast::VisibilityOwner for FunctionDef {}
 impl ast::DocCommentsOwner for FunctionDef {}
 impl ast::ExternOwner for FunctionDef {}
+impl ast::AttrsOwner for FunctionDef {}
 impl FunctionDef {
     pub fn param_list(&self) -> Option<ParamList> {
         super::child_opt(self)
@@ -517,6 +640,94 @@ impl IfExpr {
     }
 }
 
+// ImplDef
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImplDef {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for ImplDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            IMPL_DEF => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(ImplDef { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ast::DocCommentsOwner for ImplDef {}
+impl ast::AttrsOwner for ImplDef {}
+impl ImplDef {
+    pub fn item_list(&self) -> Option<ItemList> {
+        super::child_opt(self)
+    }
+}
+
+// ItemList
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ItemList {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for ItemList {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            ITEM_LIST => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(ItemList { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ast::FunctionDefOwner for ItemList {}
+impl ItemList {}
+
+// Label
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Label {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for Label {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            LABEL => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Label { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl Label {}
+
 // LetStmt
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -606,7 +817,11 @@ impl AstNode for LoopExpr {
     }
 }
 impl ast::LoopBodyOwner for LoopExpr {}
-impl LoopExpr {}
+impl LoopExpr {
+    pub fn label(&self) -> Option<Label> {
+        super::child_opt(self)
+    }
+}
 
 // MemoryTypeSpecifier
 
@@ -635,6 +850,42 @@ impl AstNode for MemoryTypeSpecifier {
 }
 impl MemoryTypeSpecifier {}
 
+// MethodCallExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MethodCallExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for MethodCallExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            METHOD_CALL_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(MethodCallExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ast::ArgListOwner for MethodCallExpr {}
+impl MethodCallExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+
+    pub fn name_ref(&self) -> Option<NameRef> {
+        super::child_opt(self)
+    }
+}
+
 // ModuleItem
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -645,7 +896,7 @@ pub struct ModuleItem {
 impl AstNode for ModuleItem {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            FUNCTION_DEF | STRUCT_DEF => true,
+            FUNCTION_DEF | STRUCT_DEF | IMPL_DEF => true,
             _ => false,
         }
     }
@@ -664,6 +915,7 @@ impl AstNode for ModuleItem {
 pub enum ModuleItemKind {
     FunctionDef(FunctionDef),
     StructDef(StructDef),
+    ImplDef(ImplDef),
 }
 impl From<FunctionDef> for ModuleItem {
     fn from(n: FunctionDef) -> ModuleItem {
@@ -675,6 +927,11 @@ impl From<StructDef> for ModuleItem {
         ModuleItem { syntax: n.syntax }
     }
 }
+impl From<ImplDef> for ModuleItem {
+    fn from(n: ImplDef) -> ModuleItem {
+        ModuleItem { syntax: n.syntax }
+    }
+}
 
 impl ModuleItem {
     pub fn kind(&self) -> ModuleItemKind {
@@ -683,6 +940,7 @@ impl ModuleItem {
                 ModuleItemKind::FunctionDef(FunctionDef::cast(self.syntax.clone()).unwrap())
             }
             STRUCT_DEF => ModuleItemKind::StructDef(StructDef::cast(self.syntax.clone()).unwrap()),
+            IMPL_DEF => ModuleItemKind::ImplDef(ImplDef::cast(self.syntax.clone()).unwrap()),
             _ => unreachable!(),
         }
     }
@@ -1015,6 +1273,10 @@ impl PathSegment {
     pub fn name_ref(&self) -> Option<NameRef> {
         super::child_opt(self)
     }
+
+    pub fn type_arg_list(&self) -> Option<TypeArgList> {
+        super::child_opt(self)
+    }
 }
 
 // PathType
@@ -1169,6 +1431,7 @@ impl AstNode for RecordFieldDef {
 impl ast::NameOwner for RecordFieldDef {}
 impl ast::VisibilityOwner for RecordFieldDef {}
 impl ast::DocCommentsOwner for RecordFieldDef {}
+impl ast::AttrsOwner for RecordFieldDef {}
 impl ast::TypeAscriptionOwner for RecordFieldDef {}
 impl RecordFieldDef {}
 
@@ -1445,12 +1708,40 @@ impl AstNode for StructDef {
 impl ast::NameOwner for StructDef {}
 impl ast::VisibilityOwner for StructDef {}
 impl ast::DocCommentsOwner for StructDef {}
+impl ast::AttrsOwner for StructDef {}
 impl StructDef {
     pub fn memory_type_specifier(&self) -> Option<MemoryTypeSpecifier> {
         super::child_opt(self)
     }
 }
 
+// TokenTree
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TokenTree {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for TokenTree {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            TOKEN_TREE => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(TokenTree { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl TokenTree {}
+
 // TupleFieldDef
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1514,6 +1805,68 @@ impl TupleFieldDefList {
     }
 }
 
+// TypeArg
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TypeArg {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for TypeArg {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            TYPE_ARG => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(TypeArg { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl TypeArg {
+    pub fn type_ref(&self) -> Option<TypeRef> {
+        super::child_opt(self)
+    }
+}
+
+// TypeArgList
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TypeArgList {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for TypeArgList {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            TYPE_ARG_LIST => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(TypeArgList { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl TypeArgList {
+    pub fn type_args(&self) -> impl Iterator<Item = TypeArg> {
+        super::children(self)
+    }
+}
+
 // TypeRef
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1624,4 +1977,8 @@ impl WhileExpr {
     pub fn condition(&self) -> Option<Condition> {
         super::child_opt(self)
     }
+
+    pub fn label(&self) -> Option<Label> {
+        super::child_opt(self)
+    }
 }
@@ -0,0 +1,102 @@
+//! Constructors for syntax nodes and tokens.
+//!
+//! Every node is created by parsing a small snippet of source text which contains it, so the result
+//! is always well-formed. The returned nodes are the roots of their own trees; use the functions in
+//! `ast::edit` to insert them into an existing tree.
+
+use crate::{ast, AstNode, SourceFile, SyntaxKind, SyntaxNode, SyntaxToken};
+
+pub fn name(text: &str) -> ast::Name {
+    ast_from_text(&format!("fn {}() {{}}", text))
+}
+
+pub fn name_ref(text: &str) -> ast::NameRef {
+    ast_from_text(&format!("fn f() {{ {} }}", text))
+}
+
+pub fn type_ref(text: &str) -> ast::TypeRef {
+    ast_from_text(&format!("fn f(a: {}) {{}}", text))
+}
+
+pub fn expr_from_text(text: &str) -> ast::Expr {
+    let let_stmt: ast::LetStmt = ast_from_text(&format!("fn f() {{ let a = {}; }}", text));
+    detach(
+        let_stmt
+            .initializer()
+            .unwrap_or_else(|| panic!("failed to parse expression `{}`", text)),
+    )
+}
+
+/// Constructs a `let` statement that binds `initializer` to `name`, e.g. `let a = 1;`.
+pub fn let_stmt(name: ast::Name, initializer: ast::Expr) -> ast::LetStmt {
+    ast_from_text(&format!(
+        "fn f() {{ let {} = {}; }}",
+        name.syntax(),
+        initializer.syntax()
+    ))
+}
+
+/// Constructs a field of a record literal, e.g. `a: 1` or the shorthand `a` if `expr` is `None`.
+pub fn record_field(name: ast::NameRef, expr: Option<ast::Expr>) -> ast::RecordField {
+    let text = match expr {
+        Some(expr) => format!("{}: {}", name.syntax(), expr.syntax()),
+        None => name.syntax().to_string(),
+    };
+    ast_from_text(&format!("fn f() {{ S {{ {} }} }}", text))
+}
+
+/// Constructs the field of a record struct definition, e.g. `a: i32`.
+pub fn record_field_def(name: ast::Name, ty: ast::TypeRef) -> ast::RecordFieldDef {
+    ast_from_text(&format!(
+        "struct S {{ {}: {} }}",
+        name.syntax(),
+        ty.syntax()
+    ))
+}
+
+/// Returns the first node of type `N` in the tree that is parsed from `text` as the root of its own
+/// tree.
+fn ast_from_text<N: AstNode>(text: &str) -> N {
+    let parse = SourceFile::parse(text);
+    let node = parse
+        .tree()
+        .syntax()
+        .descendants()
+        .find_map(N::cast)
+        .unwrap_or_else(|| panic!("failed to make node from `{}`", text));
+    detach(node)
+}
+
+/// Returns a copy of `node` which is the root of its own tree.
+fn detach<N: AstNode>(node: N) -> N {
+    N::cast(SyntaxNode::new_root(node.syntax().green().clone())).unwrap()
+}
+
+pub mod tokens {
+    use super::*;
+
+    pub fn comma() -> SyntaxToken {
+        token_from_text("fn f(a, b) {}", T![,])
+    }
+
+    pub fn single_space() -> SyntaxToken {
+        whitespace(" ")
+    }
+
+    /// Constructs a whitespace token, `text` must only contain whitespace characters.
+    pub fn whitespace(text: &str) -> SyntaxToken {
+        assert!(text.trim().is_empty(), "`{}` is not whitespace", text);
+        token_from_text(text, SyntaxKind::WHITESPACE)
+    }
+
+    /// Returns the first token of the specified kind in the tree that is parsed from `text`.
+    fn token_from_text(text: &str, kind: SyntaxKind) -> SyntaxToken {
+        SourceFile::parse(text)
+            .tree()
+            .syntax()
+            .descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .find(|it| it.kind() == kind)
+            .unwrap_or_else(|| panic!("failed to make token from `{}`", text))
+    }
+}
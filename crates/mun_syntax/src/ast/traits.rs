@@ -44,12 +44,52 @@ pub trait ArgListOwner: AstNode {
     }
 }
 
+pub trait AttrsOwner: AstNode {
+    fn attrs(&self) -> AstChildren<ast::Attr> {
+        children(self)
+    }
+}
+
 pub trait DocCommentsOwner: AstNode {
     fn doc_comments(&self) -> CommentIter {
         CommentIter {
             iter: self.syntax().children_with_tokens(),
         }
     }
+
+    /// Returns the text of all outer doc comments (e.g. `/// ...`) of this item, without the
+    /// comment prefixes, or `None` if the item is not documented.
+    fn doc_comment_text(&self) -> Option<String> {
+        let lines: Vec<String> = self
+            .doc_comments()
+            .filter(|comment| comment.kind().doc == Some(ast::CommentPlacement::Outer))
+            .map(|comment| {
+                let text = comment.text().as_str();
+                let prefix_len = comment.prefix().len();
+
+                // Strip the prefix and a single space that follows it
+                let start = match text[prefix_len..].chars().next() {
+                    Some(c) if c.is_whitespace() => prefix_len + c.len_utf8(),
+                    _ => prefix_len,
+                };
+                let end = if comment.kind().shape.is_block() && text.ends_with("*/") {
+                    text.len() - 2
+                } else {
+                    text.len()
+                };
+                text.get(start..end)
+                    .unwrap_or_default()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+
+        if lines.is_empty() {
+            None
+        } else {
+            Some(lines.join("\n"))
+        }
+    }
 }
 
 pub struct CommentIter {
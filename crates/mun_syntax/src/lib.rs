@@ -17,6 +17,7 @@ mod parsing;
 mod ptr;
 mod syntax_error;
 mod syntax_node;
+mod text_edit;
 
 #[cfg(test)]
 mod tests;
@@ -30,6 +31,7 @@ pub use crate::{
     syntax_error::{Location, SyntaxError, SyntaxErrorKind},
     syntax_kind::SyntaxKind,
     syntax_node::{Direction, SyntaxElement, SyntaxNode, SyntaxToken, SyntaxTreeBuilder},
+    text_edit::TextEdit,
 };
 pub use rowan::{SmolStr, TextRange, TextUnit};
 
@@ -110,6 +112,23 @@ impl Parse<SyntaxNode> {
 }
 
 impl Parse<SourceFile> {
+    /// Returns the syntax tree of the text after applying `edit`. If possible, only the token or
+    /// the `{}` block that contains the edit is parsed again and the rest of the tree is reused.
+    pub fn reparse(&self, edit: &TextEdit) -> Parse<SourceFile> {
+        self.incremental_reparse(edit)
+            .unwrap_or_else(|| self.full_reparse(edit))
+    }
+
+    fn incremental_reparse(&self, edit: &TextEdit) -> Option<Parse<SourceFile>> {
+        parsing::incremental_reparse(self.tree().syntax(), edit, self.errors.to_vec())
+            .map(|(green_node, errors, _reparsed_range)| Parse::new(green_node, errors))
+    }
+
+    fn full_reparse(&self, edit: &TextEdit) -> Parse<SourceFile> {
+        let text = edit.apply(&self.tree().syntax().text().to_string());
+        SourceFile::parse(&text)
+    }
+
     pub fn debug_dump(&self) -> String {
         let mut buf = format!("{:#?}", self.tree().syntax());
         for err in self.errors.iter() {
@@ -162,7 +181,7 @@ fn api_walkthrough() {
     for item in file.items() {
         match item.kind() {
             ast::ModuleItemKind::FunctionDef(f) => func = Some(f),
-            ast::ModuleItemKind::StructDef(_) => (),
+            ast::ModuleItemKind::StructDef(_) | ast::ModuleItemKind::ImplDef(_) => (),
         }
     }
 
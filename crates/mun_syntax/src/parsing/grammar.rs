@@ -1,4 +1,5 @@
 mod adt;
+mod attributes;
 mod declarations;
 mod expressions;
 mod params;
@@ -30,6 +31,19 @@ pub(crate) fn root(p: &mut Parser) {
     m.complete(p, SOURCE_FILE);
 }
 
+/// Returns the function that parses a node of the specified kind on its own, if the node can be
+/// reparsed without its surrounding context.
+pub(crate) fn reparser(kind: SyntaxKind) -> Option<fn(&mut Parser)> {
+    let reparser: fn(&mut Parser) = match kind {
+        BLOCK_EXPR => expressions::block,
+        RECORD_FIELD_LIST => expressions::record_field_list,
+        RECORD_FIELD_DEF_LIST => adt::record_field_def_list,
+        ITEM_LIST => declarations::item_list,
+        _ => return None,
+    };
+    Some(reparser)
+}
+
 //pub(crate) fn pattern(p: &mut Parser) {
 //    patterns::pattern(p)
 //}
@@ -0,0 +1,47 @@
+use super::*;
+
+pub(super) fn outer_attributes(p: &mut Parser) {
+    while p.at(T![#]) {
+        attribute(p);
+    }
+}
+
+fn attribute(p: &mut Parser) {
+    assert!(p.at(T![#]));
+    let attr = p.start();
+    p.bump(T![#]);
+    if p.at(T!['[']) {
+        p.bump(T!['[']);
+        paths::expr_path(p);
+        if p.at(T!['(']) {
+            token_tree(p);
+        }
+        p.expect(T![']']);
+    } else {
+        p.error("expected `[`");
+    }
+    attr.complete(p, ATTR);
+}
+
+/// Parses a balanced sequence of tokens that is enclosed in delimiters, e.g. the arguments of
+/// `#[derive(Eq, Ord)]`.
+fn token_tree(p: &mut Parser) {
+    let closing = match p.current() {
+        T!['('] => T![')'],
+        T!['['] => T![']'],
+        T!['{'] => T!['}'],
+        _ => unreachable!(),
+    };
+    let m = p.start();
+    p.bump_any();
+    while !p.at(EOF) && !p.at(closing) {
+        match p.current() {
+            T!['('] | T!['['] | T!['{'] => token_tree(p),
+            // A mismatched closing delimiter is reported when the closing delimiter is expected
+            T![')'] | T![']'] | T!['}'] => break,
+            _ => p.bump_any(),
+        }
+    }
+    p.expect(closing);
+    m.complete(p, TOKEN_TREE);
+}
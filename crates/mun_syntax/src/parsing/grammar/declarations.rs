@@ -1,7 +1,8 @@
 use super::*;
 use crate::T;
 
-pub(super) const DECLARATION_RECOVERY_SET: TokenSet = token_set![FN_KW, PUB_KW, STRUCT_KW];
+pub(super) const DECLARATION_RECOVERY_SET: TokenSet =
+    token_set![FN_KW, PUB_KW, STRUCT_KW, IMPL_KW, HASH];
 
 pub(super) fn mod_contents(p: &mut Parser) {
     while !p.at(EOF) {
@@ -32,6 +33,7 @@ pub(super) fn declaration(p: &mut Parser) {
 }
 
 pub(super) fn maybe_declaration(p: &mut Parser, m: Marker) -> Result<(), Marker> {
+    attributes::outer_attributes(p);
     opt_visibility(p);
 
     let m = match declarations_without_modifiers(p, m) {
@@ -65,11 +67,53 @@ fn declarations_without_modifiers(p: &mut Parser, m: Marker) -> Result<(), Marke
         T![struct] => {
             adt::struct_def(p, m);
         }
+        T![impl] => {
+            impl_def(p, m);
+        }
         _ => return Err(m),
     };
     Ok(())
 }
 
+fn impl_def(p: &mut Parser, m: Marker) {
+    assert!(p.at(T![impl]));
+    p.bump(T![impl]);
+    types::type_(p);
+    p.expect(T![for]);
+    types::type_(p);
+    if p.at(T!['{']) {
+        item_list(p);
+    } else {
+        p.error("expected `{`");
+    }
+    m.complete(p, IMPL_DEF);
+}
+
+pub(super) fn item_list(p: &mut Parser) {
+    assert!(p.at(T!['{']));
+    let m = p.start();
+    p.bump(T!['{']);
+    while !p.at(EOF) && !p.at(T!['}']) {
+        if p.at(T![fn]) || p.at(T![#]) {
+            let m = p.start();
+            attributes::outer_attributes(p);
+            if p.at(T![fn]) {
+                fn_def(p);
+                m.complete(p, FUNCTION_DEF);
+            } else {
+                m.abandon(p);
+                p.error("expected a function");
+            }
+        } else if p.at(T!['{']) {
+            error_block(p, "expected a function");
+        } else {
+            p.error_and_bump("expected a function");
+        }
+    }
+    p.expect(T!['}']);
+    m.complete(p, ITEM_LIST);
+}
+
 pub(super) fn fn_def(p: &mut Parser) {
     assert!(p.at(T![fn]));
     p.bump(T![fn]);
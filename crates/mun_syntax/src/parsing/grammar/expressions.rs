@@ -2,7 +2,7 @@ use super::*;
 use crate::parsing::grammar::paths::PATH_FIRST;
 
 pub(crate) const LITERAL_FIRST: TokenSet =
-    token_set![TRUE_KW, FALSE_KW, INT_NUMBER, FLOAT_NUMBER, STRING];
+    token_set![TRUE_KW, FALSE_KW, INT_NUMBER, FLOAT_NUMBER, STRING, CHAR];
 
 const EXPR_RECOVERY_SET: TokenSet = token_set![LET_KW];
 
@@ -14,7 +14,9 @@ const ATOM_EXPR_FIRST: TokenSet = LITERAL_FIRST.union(PATH_FIRST).union(token_se
     T![loop],
     T![return],
     T![break],
+    T![continue],
     T![while],
+    LABEL_IDENT,
 ]);
 
 const LHS_FIRST: TokenSet = ATOM_EXPR_FIRST.union(token_set![EXCLAMATION, MINUS]);
@@ -136,6 +138,16 @@ fn expr_bp(p: &mut Parser, r: Restrictions, bp: u8) -> (Option<CompletedMarker>,
     };
 
     loop {
+        // A cast binds stronger than all binary operators, e.g. `a + b as f32` is parsed as
+        // `a + (b as f32)`.
+        if p.at(T![as]) {
+            if CAST_BP < bp {
+                break;
+            }
+            lhs = cast_expr(p, lhs);
+            continue;
+        }
+
         let (op_bp, op) = current_op(p);
         if op_bp < bp {
             break;
@@ -151,6 +163,17 @@ fn expr_bp(p: &mut Parser, r: Restrictions, bp: u8) -> (Option<CompletedMarker>,
     (Some(lhs), BlockLike::NotBlock)
 }
 
+/// The binding power of the `as` operator
+const CAST_BP: u8 = 12;
+
+fn cast_expr(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
+    assert!(p.at(T![as]));
+    let m = lhs.precede(p);
+    p.bump(T![as]);
+    types::type_(p);
+    m.complete(p, CAST_EXPR)
+}
+
 fn current_op(p: &Parser) -> (u8, SyntaxKind) {
     match p.current() {
         T![+] if p.at(T![+=]) => (1, T![+=]),
@@ -257,12 +280,21 @@ fn postfix_dot_expr(
 ) -> Result<CompletedMarker, CompletedMarker> {
     assert!(p.at(T![.]));
     if p.nth(1) == IDENT && p.nth(2) == T!['('] {
-        unimplemented!("Method calls are not supported yet.");
+        return Ok(method_call_expr(p, lhs));
     }
 
     Ok(field_expr(p, lhs))
 }
 
+fn method_call_expr(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
+    assert!(p.at(T![.]) && p.nth(1) == IDENT && p.nth(2) == T!['(']);
+    let m = lhs.precede(p);
+    p.bump(T![.]);
+    name_ref(p);
+    arg_list(p);
+    m.complete(p, METHOD_CALL_EXPR)
+}
+
 fn field_expr(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
     assert!(p.at(T![.]) || p.at(INDEX));
     let m = lhs.precede(p);
@@ -290,10 +322,24 @@ fn atom_expr(p: &mut Parser, r: Restrictions) -> Option<(CompletedMarker, BlockL
         T!['('] => paren_expr(p),
         T!['{'] => block_expr(p),
         T![if] => if_expr(p),
-        T![loop] => loop_expr(p),
+        T![loop] => loop_expr(p, None),
         T![return] => ret_expr(p),
-        T![while] => while_expr(p),
+        T![while] => while_expr(p, None),
         T![break] => break_expr(p, r),
+        T![continue] => continue_expr(p),
+        LABEL_IDENT if p.nth_at(1, T![:]) => {
+            let m = p.start();
+            label(p);
+            match p.current() {
+                T![loop] => loop_expr(p, Some(m)),
+                T![while] => while_expr(p, Some(m)),
+                _ => {
+                    p.error("expected a loop");
+                    m.complete(p, ERROR);
+                    return None;
+                }
+            }
+        }
         _ => {
             p.error_recover("expected expression", EXPR_RECOVERY_SET);
             return None;
@@ -355,9 +401,17 @@ fn if_expr(p: &mut Parser) -> CompletedMarker {
     m.complete(p, IF_EXPR)
 }
 
-fn loop_expr(p: &mut Parser) -> CompletedMarker {
-    assert!(p.at(T![loop]));
+fn label(p: &mut Parser) {
+    assert!(p.at(LABEL_IDENT) && p.nth_at(1, T![:]));
     let m = p.start();
+    p.bump(LABEL_IDENT);
+    p.bump(T![:]);
+    m.complete(p, LABEL);
+}
+
+fn loop_expr(p: &mut Parser, m: Option<Marker>) -> CompletedMarker {
+    assert!(p.at(T![loop]));
+    let m = m.unwrap_or_else(|| p.start());
     p.bump(T![loop]);
     block(p);
     m.complete(p, LOOP_EXPR)
@@ -383,22 +437,31 @@ fn break_expr(p: &mut Parser, r: Restrictions) -> CompletedMarker {
     assert!(p.at(T![break]));
     let m = p.start();
     p.bump(T![break]);
+    p.eat(LABEL_IDENT);
     if p.at_ts(EXPR_FIRST) && !(r.forbid_structs && p.at(T!['{'])) {
         expr(p);
     }
     m.complete(p, BREAK_EXPR)
 }
 
-fn while_expr(p: &mut Parser) -> CompletedMarker {
-    assert!(p.at(T![while]));
+fn continue_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(T![continue]));
     let m = p.start();
+    p.bump(T![continue]);
+    p.eat(LABEL_IDENT);
+    m.complete(p, CONTINUE_EXPR)
+}
+
+fn while_expr(p: &mut Parser, m: Option<Marker>) -> CompletedMarker {
+    assert!(p.at(T![while]));
+    let m = m.unwrap_or_else(|| p.start());
     p.bump(T![while]);
     cond(p);
     block(p);
     m.complete(p, WHILE_EXPR)
 }
 
-fn record_field_list(p: &mut Parser) {
+pub(super) fn record_field_list(p: &mut Parser) {
     assert!(p.at(T!['{']));
     let m = p.start();
     p.bump(T!['{']);
@@ -412,6 +475,10 @@ fn record_field_list(p: &mut Parser) {
                 }
                 m.complete(p, RECORD_FIELD);
             }
+            T![.] if p.at(T![..]) => {
+                p.bump(T![..]);
+                expr(p);
+            }
             T!['{'] => error_block(p, "expected a field"),
             _ => p.error_and_bump("expected an identifier"),
         }
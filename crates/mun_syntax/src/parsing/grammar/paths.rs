@@ -4,8 +4,9 @@ pub(super) const PATH_FIRST: TokenSet = token_set![IDENT, SELF_KW, SUPER_KW, COL
 
 pub(super) fn is_path_start(p: &Parser) -> bool {
     match p.current() {
-        IDENT | T![::] => true,
-        _ => false,
+        IDENT => true,
+        T![self] | T![super] => p.nth_at(1, T![::]),
+        _ => p.at(T![::]),
     }
 }
 
@@ -43,7 +44,7 @@ fn path(p: &mut Parser, mode: Mode) {
     }
 }
 
-fn path_segment(p: &mut Parser, _mode: Mode, first: bool) {
+fn path_segment(p: &mut Parser, mode: Mode, first: bool) {
     let m = p.start();
     if first {
         p.eat(T![::]);
@@ -51,6 +52,9 @@ fn path_segment(p: &mut Parser, _mode: Mode, first: bool) {
     match p.current() {
         IDENT => {
             name_ref(p);
+            if mode == Mode::Type && p.at(T![<]) {
+                types::type_arg_list(p);
+            }
         }
         T![self] | T![super] => p.bump_any(),
         _ => p.error_recover(
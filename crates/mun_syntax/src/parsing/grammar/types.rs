@@ -25,6 +25,28 @@ pub(super) fn path_type(p: &mut Parser) {
     m.complete(p, PATH_TYPE);
 }
 
+/// Parses a list of type arguments, e.g. the `<i32>` in `Atomic<i32>`.
+pub(super) fn type_arg_list(p: &mut Parser) {
+    assert!(p.at(T![<]));
+    let m = p.start();
+    p.bump(T![<]);
+    while !p.at(T![>]) && !p.at(EOF) {
+        if !p.at_ts(TYPE_FIRST) {
+            p.error("expected type");
+            break;
+        }
+
+        let arg = p.start();
+        type_(p);
+        arg.complete(p, TYPE_ARG);
+        if !p.at(T![>]) && !p.eat(T![,]) {
+            break;
+        }
+    }
+    p.expect(T![>]);
+    m.complete(p, TYPE_ARG_LIST);
+}
+
 fn never_type(p: &mut Parser) {
     assert!(p.at(T![never]));
     let m = p.start();
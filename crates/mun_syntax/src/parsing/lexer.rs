@@ -5,7 +5,11 @@ mod numbers;
 mod strings;
 
 use self::{
-    classes::*, comments::scan_comment, cursor::Cursor, numbers::scan_number, strings::scan_string,
+    classes::*,
+    comments::scan_comment,
+    cursor::Cursor,
+    numbers::scan_number,
+    strings::{is_raw_string_start, scan_raw_string, scan_string},
 };
 use crate::{
     SyntaxKind::{self, *},
@@ -57,6 +61,11 @@ fn next_token_inner(c: char, cursor: &mut Cursor) -> SyntaxKind {
         }
     }
 
+    if c == 'r' && is_raw_string_start(cursor) {
+        scan_raw_string(cursor);
+        return STRING;
+    }
+
     let ident_start = is_ident_start(c);
     if ident_start {
         return scan_identifier_or_keyword(c, cursor);
@@ -79,10 +88,18 @@ fn next_token_inner(c: char, cursor: &mut Cursor) -> SyntaxKind {
             cursor.bump();
             return NEQ;
         }
-        '"' | '\'' => {
+        '"' => {
             scan_string(c, cursor);
             return STRING;
         }
+        '\'' => {
+            if is_label_start(cursor) {
+                cursor.bump_while(is_ident_continue);
+                return LABEL_IDENT;
+            }
+            scan_string(c, cursor);
+            return CHAR;
+        }
         _ => (),
     }
     ERROR
@@ -101,6 +118,19 @@ fn scan_identifier_or_keyword(c: char, cursor: &mut Cursor) -> SyntaxKind {
     IDENT
 }
 
+/// Returns true if the cursor, positioned right after a `'`, is at the start of a loop label (e.g.
+/// `'outer`). An identifier that is followed by a closing `'` is a character literal instead.
+fn is_label_start(cursor: &Cursor) -> bool {
+    if !cursor.matches_nth_if(0, is_ident_start) {
+        return false;
+    }
+    let mut n = 1;
+    while cursor.matches_nth_if(n, is_ident_continue) {
+        n += 1;
+    }
+    !cursor.matches_nth_if(n, |c| c == '\'')
+}
+
 fn scan_index(c: char, cursor: &mut Cursor) -> Option<SyntaxKind> {
     if c == '.' {
         let mut is_first = true;
@@ -20,3 +20,42 @@ pub(crate) fn scan_string(c: char, cursor: &mut Cursor) {
         }
     }
 }
+
+/// Returns true if the cursor, positioned right after an `r`, is at the start of a raw string
+/// (e.g. `r"..."` or `r#"..."#`).
+pub(crate) fn is_raw_string_start(cursor: &Cursor) -> bool {
+    let mut n = 0;
+    while cursor.matches_nth_if(n, |c| c == '#') {
+        n += 1;
+    }
+    cursor.matches_nth_if(n, |c| c == '"')
+}
+
+/// Scans a raw string, the leading `r` must already have been consumed. A raw string does not
+/// process any escape sequences and is terminated by a `"` followed by the same number of `#`
+/// characters that preceded the opening `"`.
+pub(crate) fn scan_raw_string(cursor: &mut Cursor) {
+    let mut hashes = 0;
+    while cursor.matches('#') {
+        cursor.bump();
+        hashes += 1;
+    }
+
+    if !cursor.matches('"') {
+        return;
+    }
+    cursor.bump();
+
+    while let Some(c) = cursor.bump() {
+        if c == '"' {
+            let mut closing_hashes = 0;
+            while closing_hashes < hashes && cursor.matches('#') {
+                cursor.bump();
+                closing_hashes += 1;
+            }
+            if closing_hashes == hashes {
+                return;
+            }
+        }
+    }
+}
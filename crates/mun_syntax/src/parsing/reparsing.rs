@@ -0,0 +1,202 @@
+//! Implementation of incremental re-parsing.
+//!
+//! We use two simple strategies for this:
+//!   - if the edit modifies only a single token (like changing an identifier's letter), we replace
+//!     only this token.
+//!   - otherwise, we search for the nearest `{}` block which contains the edit and try to parse
+//!     only this block.
+
+use crate::{
+    parsing::{
+        grammar,
+        lexer::{tokenize, Token},
+        text_token_source::TextTokenSource,
+        text_tree_sink::TextTreeSink,
+    },
+    syntax_node::{GreenNode, MunLanguage, NodeOrToken, SyntaxElement, SyntaxNode},
+    SyntaxError,
+    SyntaxKind::{self, *},
+    TextEdit, TextRange, TextUnit,
+};
+use rowan::{GreenToken, Language};
+
+/// A function that parses a node on its own
+type Reparser = fn(&mut super::parser::Parser);
+
+/// Reparses the part of the tree `node` that is affected by `edit`. Returns the new tree, the
+/// syntax errors of the new tree and the range of the old tree that was reparsed, or `None` if the
+/// edit cannot be applied without parsing the whole text again.
+pub(crate) fn incremental_reparse(
+    node: &SyntaxNode,
+    edit: &TextEdit,
+    errors: Vec<SyntaxError>,
+) -> Option<(GreenNode, Vec<SyntaxError>, TextRange)> {
+    if let Some((green, old_range)) = reparse_token(node, edit) {
+        return Some((
+            green,
+            merge_errors(errors, Vec::new(), old_range, edit),
+            old_range,
+        ));
+    }
+
+    if let Some((green, new_errors, old_range)) = reparse_block(node, edit) {
+        return Some((
+            green,
+            merge_errors(errors, new_errors, old_range, edit),
+            old_range,
+        ));
+    }
+    None
+}
+
+/// Replaces the single token that contains `edit`, if the edited text is still a single token of
+/// the same kind.
+fn reparse_token(root: &SyntaxNode, edit: &TextEdit) -> Option<(GreenNode, TextRange)> {
+    let token = root.covering_element(edit.range).into_token()?;
+    match token.kind() {
+        WHITESPACE | COMMENT | IDENT | STRING => {
+            let text = text_after_edit(token.clone().into(), edit);
+            if token.kind() == WHITESPACE || token.kind() == COMMENT {
+                // Newlines and doc comments determine to which item the trivia is attached
+                if token.text().contains('\n')
+                    || text.contains('\n')
+                    || is_doc_comment(token.text()) != is_doc_comment(&text)
+                {
+                    return None;
+                }
+            }
+
+            let new_token_kind = single_token_kind(&text)?;
+            if new_token_kind != token.kind()
+                || (new_token_kind == IDENT && is_contextual_kw(&text))
+                || (token.kind() == IDENT && is_contextual_kw(token.text()))
+            {
+                return None;
+            }
+
+            // Check that the edited token is not a part of the bigger token, e.g. an identifier
+            // that is extended with the token after it.
+            if let Some(next_char) = root.text().char_at(token.text_range().end()) {
+                let tokens_with_next_char = tokenize(&format!("{}{}", text, next_char));
+                if tokens_with_next_char.len() == 1 {
+                    return None;
+                }
+            }
+
+            let new_token = GreenToken::new(MunLanguage::kind_to_raw(token.kind()), text.into());
+            Some((token.replace_with(new_token), token.text_range()))
+        }
+        _ => None,
+    }
+}
+
+/// Reparses the innermost block that contains `edit`.
+fn reparse_block(
+    root: &SyntaxNode,
+    edit: &TextEdit,
+) -> Option<(GreenNode, Vec<SyntaxError>, TextRange)> {
+    let (node, reparser) = find_reparsable_node(root, edit.range)?;
+    let text = text_after_edit(node.clone().into(), edit);
+    let tokens = tokenize(&text);
+    if !is_balanced(&tokens) {
+        return None;
+    }
+
+    let mut token_source = TextTokenSource::new(&text, &tokens);
+    let mut tree_sink = TextTreeSink::new(&text, &tokens);
+    super::parse_from_tokens(&mut token_source, &mut tree_sink, reparser);
+    let (green, new_errors) = tree_sink.finish();
+    Some((node.replace_with(green), new_errors, node.text_range()))
+}
+
+/// Returns the text of `element` after applying `edit`, which must be contained in `element`.
+fn text_after_edit(element: SyntaxElement, edit: &TextEdit) -> String {
+    let edit = TextEdit::replace(
+        edit.range - element.text_range().start(),
+        edit.replace_with.clone(),
+    );
+    let text = match element {
+        NodeOrToken::Token(token) => token.text().to_string(),
+        NodeOrToken::Node(node) => node.text().to_string(),
+    };
+    edit.apply(&text)
+}
+
+/// Returns the kind of the token if `text` consists of exactly one token.
+fn single_token_kind(text: &str) -> Option<SyntaxKind> {
+    let tokens = tokenize(text);
+    match tokens.as_slice() {
+        [token] if token.len == TextUnit::of_str(text) => Some(token.kind),
+        _ => None,
+    }
+}
+
+fn is_contextual_kw(text: &str) -> bool {
+    text == "gc" || text == "value"
+}
+
+fn is_doc_comment(text: &str) -> bool {
+    text.starts_with("///") || text.starts_with("/**")
+}
+
+/// Returns the innermost node that contains `range` and which can be parsed on its own.
+fn find_reparsable_node(node: &SyntaxNode, range: TextRange) -> Option<(SyntaxNode, Reparser)> {
+    let node = match node.covering_element(range) {
+        NodeOrToken::Token(token) => token.parent(),
+        NodeOrToken::Node(node) => node,
+    };
+    node.ancestors()
+        .find_map(|node| grammar::reparser(node.kind()).map(|reparser| (node, reparser)))
+}
+
+/// Returns true if the tokens start with `{`, end with the matching `}` and contain only balanced
+/// pairs of braces in between.
+fn is_balanced(tokens: &[Token]) -> bool {
+    if tokens.len() < 2
+        || tokens.first().unwrap().kind != T!['{']
+        || tokens.last().unwrap().kind != T!['}']
+    {
+        return false;
+    }
+    let mut balance = 0usize;
+    for token in &tokens[1..tokens.len() - 1] {
+        match token.kind {
+            T!['{'] => balance += 1,
+            T!['}'] => {
+                balance = match balance.checked_sub(1) {
+                    Some(b) => b,
+                    None => return false,
+                }
+            }
+            _ => {}
+        }
+    }
+    balance == 0
+}
+
+/// Combines the errors of the parts of the tree that were not reparsed with the errors of the
+/// reparsed range.
+fn merge_errors(
+    old_errors: Vec<SyntaxError>,
+    new_errors: Vec<SyntaxError>,
+    old_range: TextRange,
+    edit: &TextEdit,
+) -> Vec<SyntaxError> {
+    let mut result = Vec::new();
+    let mut errors_after = Vec::new();
+    for error in old_errors {
+        if error.location().offset() <= old_range.start() {
+            result.push(error)
+        } else if error.location().offset() >= old_range.end() {
+            errors_after
+                .push(error.add_offset(TextUnit::of_str(&edit.replace_with), edit.range.len()));
+        }
+    }
+    result.extend(
+        new_errors
+            .into_iter()
+            .map(|error| error.add_offset(old_range.start(), 0.into())),
+    );
+    result.extend(errors_after);
+    result
+}
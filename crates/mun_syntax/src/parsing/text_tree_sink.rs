@@ -149,6 +149,19 @@ fn n_attached_trivias<'a>(
                 _ => unreachable!(),
             })
             .count(),
+        // Only attach the doc comments that directly precede the item, so they become part of the
+        // item they document
+        STRUCT_DEF | IMPL_DEF | RECORD_FIELD_DEF => {
+            let mut count = 0;
+            for (i, (kind, text)) in trivias.enumerate() {
+                match kind {
+                    WHITESPACE if text.contains("\n\n") => break,
+                    COMMENT if text.starts_with("///") || text.starts_with("/**") => count = i + 1,
+                    _ => (),
+                }
+            }
+            count
+        }
         _ => 0,
     }
 }
@@ -66,6 +66,7 @@ pub enum SyntaxKind {
     SHL,
     SHR,
     BREAK_KW,
+    CONTINUE_KW,
     DO_KW,
     ELSE_KW,
     FALSE_KW,
@@ -78,12 +79,14 @@ pub enum SyntaxKind {
     TRUE_KW,
     WHILE_KW,
     LOOP_KW,
+    AS_KW,
     LET_KW,
     MUT_KW,
     CLASS_KW,
     STRUCT_KW,
     NEVER_KW,
     PUB_KW,
+    IMPL_KW,
     PACKAGE_KW,
     SUPER_KW,
     SELF_KW,
@@ -91,8 +94,10 @@ pub enum SyntaxKind {
     INT_NUMBER,
     FLOAT_NUMBER,
     STRING,
+    CHAR,
     ERROR,
     IDENT,
+    LABEL_IDENT,
     INDEX,
     WHITESPACE,
     COMMENT,
@@ -111,8 +116,14 @@ pub enum SyntaxKind {
     RECORD_FIELD_DEF,
     TUPLE_FIELD_DEF_LIST,
     TUPLE_FIELD_DEF,
+    IMPL_DEF,
+    ITEM_LIST,
+    ATTR,
+    TOKEN_TREE,
     PATH_TYPE,
     NEVER_TYPE,
+    TYPE_ARG_LIST,
+    TYPE_ARG,
     LET_STMT,
     EXPR_STMT,
     PATH_EXPR,
@@ -121,13 +132,17 @@ pub enum SyntaxKind {
     BIN_EXPR,
     PAREN_EXPR,
     CALL_EXPR,
+    METHOD_CALL_EXPR,
     FIELD_EXPR,
+    CAST_EXPR,
     IF_EXPR,
     BLOCK_EXPR,
     RETURN_EXPR,
     WHILE_EXPR,
     LOOP_EXPR,
     BREAK_EXPR,
+    CONTINUE_EXPR,
+    LABEL,
     CONDITION,
     BIND_PAT,
     PLACEHOLDER_PAT,
@@ -291,6 +306,9 @@ macro_rules! T {
     (break) => {
         $crate::SyntaxKind::BREAK_KW
     };
+    (continue) => {
+        $crate::SyntaxKind::CONTINUE_KW
+    };
     (do) => {
         $crate::SyntaxKind::DO_KW
     };
@@ -327,6 +345,9 @@ macro_rules! T {
     (loop) => {
         $crate::SyntaxKind::LOOP_KW
     };
+    (as) => {
+        $crate::SyntaxKind::AS_KW
+    };
     (let) => {
         $crate::SyntaxKind::LET_KW
     };
@@ -345,6 +366,9 @@ macro_rules! T {
     (pub) => {
         $crate::SyntaxKind::PUB_KW
     };
+    (impl) => {
+        $crate::SyntaxKind::IMPL_KW
+    };
     (package) => {
         $crate::SyntaxKind::PACKAGE_KW
     };
@@ -377,6 +401,7 @@ impl SyntaxKind {
     pub fn is_keyword(self) -> bool {
         match self {
             | BREAK_KW
+            | CONTINUE_KW
             | DO_KW
             | ELSE_KW
             | FALSE_KW
@@ -389,12 +414,14 @@ impl SyntaxKind {
             | TRUE_KW
             | WHILE_KW
             | LOOP_KW
+            | AS_KW
             | LET_KW
             | MUT_KW
             | CLASS_KW
             | STRUCT_KW
             | NEVER_KW
             | PUB_KW
+            | IMPL_KW
             | PACKAGE_KW
             | SUPER_KW
             | SELF_KW
@@ -465,6 +492,7 @@ impl SyntaxKind {
                 | INT_NUMBER
                 | FLOAT_NUMBER
                 | STRING
+                | CHAR
                     => true,
                 _ => false
             }
@@ -521,6 +549,7 @@ impl SyntaxKind {
             SHL => &SyntaxInfo { name: "SHL" },
             SHR => &SyntaxInfo { name: "SHR" },
             BREAK_KW => &SyntaxInfo { name: "BREAK_KW" },
+            CONTINUE_KW => &SyntaxInfo { name: "CONTINUE_KW" },
             DO_KW => &SyntaxInfo { name: "DO_KW" },
             ELSE_KW => &SyntaxInfo { name: "ELSE_KW" },
             FALSE_KW => &SyntaxInfo { name: "FALSE_KW" },
@@ -533,12 +562,14 @@ impl SyntaxKind {
             TRUE_KW => &SyntaxInfo { name: "TRUE_KW" },
             WHILE_KW => &SyntaxInfo { name: "WHILE_KW" },
             LOOP_KW => &SyntaxInfo { name: "LOOP_KW" },
+            AS_KW => &SyntaxInfo { name: "AS_KW" },
             LET_KW => &SyntaxInfo { name: "LET_KW" },
             MUT_KW => &SyntaxInfo { name: "MUT_KW" },
             CLASS_KW => &SyntaxInfo { name: "CLASS_KW" },
             STRUCT_KW => &SyntaxInfo { name: "STRUCT_KW" },
             NEVER_KW => &SyntaxInfo { name: "NEVER_KW" },
             PUB_KW => &SyntaxInfo { name: "PUB_KW" },
+            IMPL_KW => &SyntaxInfo { name: "IMPL_KW" },
             PACKAGE_KW => &SyntaxInfo { name: "PACKAGE_KW" },
             SUPER_KW => &SyntaxInfo { name: "SUPER_KW" },
             SELF_KW => &SyntaxInfo { name: "SELF_KW" },
@@ -546,8 +577,10 @@ impl SyntaxKind {
             INT_NUMBER => &SyntaxInfo { name: "INT_NUMBER" },
             FLOAT_NUMBER => &SyntaxInfo { name: "FLOAT_NUMBER" },
             STRING => &SyntaxInfo { name: "STRING" },
+            CHAR => &SyntaxInfo { name: "CHAR" },
             ERROR => &SyntaxInfo { name: "ERROR" },
             IDENT => &SyntaxInfo { name: "IDENT" },
+            LABEL_IDENT => &SyntaxInfo { name: "LABEL_IDENT" },
             INDEX => &SyntaxInfo { name: "INDEX" },
             WHITESPACE => &SyntaxInfo { name: "WHITESPACE" },
             COMMENT => &SyntaxInfo { name: "COMMENT" },
@@ -566,8 +599,14 @@ impl SyntaxKind {
             RECORD_FIELD_DEF => &SyntaxInfo { name: "RECORD_FIELD_DEF" },
             TUPLE_FIELD_DEF_LIST => &SyntaxInfo { name: "TUPLE_FIELD_DEF_LIST" },
             TUPLE_FIELD_DEF => &SyntaxInfo { name: "TUPLE_FIELD_DEF" },
+            IMPL_DEF => &SyntaxInfo { name: "IMPL_DEF" },
+            ITEM_LIST => &SyntaxInfo { name: "ITEM_LIST" },
+            ATTR => &SyntaxInfo { name: "ATTR" },
+            TOKEN_TREE => &SyntaxInfo { name: "TOKEN_TREE" },
             PATH_TYPE => &SyntaxInfo { name: "PATH_TYPE" },
             NEVER_TYPE => &SyntaxInfo { name: "NEVER_TYPE" },
+            TYPE_ARG_LIST => &SyntaxInfo { name: "TYPE_ARG_LIST" },
+            TYPE_ARG => &SyntaxInfo { name: "TYPE_ARG" },
             LET_STMT => &SyntaxInfo { name: "LET_STMT" },
             EXPR_STMT => &SyntaxInfo { name: "EXPR_STMT" },
             PATH_EXPR => &SyntaxInfo { name: "PATH_EXPR" },
@@ -576,13 +615,17 @@ impl SyntaxKind {
             BIN_EXPR => &SyntaxInfo { name: "BIN_EXPR" },
             PAREN_EXPR => &SyntaxInfo { name: "PAREN_EXPR" },
             CALL_EXPR => &SyntaxInfo { name: "CALL_EXPR" },
+            METHOD_CALL_EXPR => &SyntaxInfo { name: "METHOD_CALL_EXPR" },
             FIELD_EXPR => &SyntaxInfo { name: "FIELD_EXPR" },
+            CAST_EXPR => &SyntaxInfo { name: "CAST_EXPR" },
             IF_EXPR => &SyntaxInfo { name: "IF_EXPR" },
             BLOCK_EXPR => &SyntaxInfo { name: "BLOCK_EXPR" },
             RETURN_EXPR => &SyntaxInfo { name: "RETURN_EXPR" },
             WHILE_EXPR => &SyntaxInfo { name: "WHILE_EXPR" },
             LOOP_EXPR => &SyntaxInfo { name: "LOOP_EXPR" },
             BREAK_EXPR => &SyntaxInfo { name: "BREAK_EXPR" },
+            CONTINUE_EXPR => &SyntaxInfo { name: "CONTINUE_EXPR" },
+            LABEL => &SyntaxInfo { name: "LABEL" },
             CONDITION => &SyntaxInfo { name: "CONDITION" },
             BIND_PAT => &SyntaxInfo { name: "BIND_PAT" },
             PLACEHOLDER_PAT => &SyntaxInfo { name: "PLACEHOLDER_PAT" },
@@ -603,6 +646,7 @@ impl SyntaxKind {
     pub fn from_keyword(ident: &str) -> Option<SyntaxKind> {
         let kw = match ident {
             "break" => BREAK_KW,
+            "continue" => CONTINUE_KW,
             "do" => DO_KW,
             "else" => ELSE_KW,
             "false" => FALSE_KW,
@@ -615,12 +659,14 @@ impl SyntaxKind {
             "true" => TRUE_KW,
             "while" => WHILE_KW,
             "loop" => LOOP_KW,
+            "as" => AS_KW,
             "let" => LET_KW,
             "mut" => MUT_KW,
             "class" => CLASS_KW,
             "struct" => STRUCT_KW,
             "never" => NEVER_KW,
             "pub" => PUB_KW,
+            "impl" => IMPL_KW,
             "package" => PACKAGE_KW,
             "super" => SUPER_KW,
             "self" => SELF_KW,
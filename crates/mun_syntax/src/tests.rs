@@ -1,2 +1,4 @@
+mod edit;
 mod lexer;
 mod parser;
+mod reparsing;
@@ -0,0 +1,110 @@
+use crate::{
+    ast::{self, edit, make, AstNodeEdit},
+    AstNode, SourceFile,
+};
+
+/// Parses `text`, applies `f` to the first node of type `N` and checks that applying the resulting
+/// text edit to `text` results in `expected`.
+fn check_edit<N: AstNode>(text: &str, expected: &str, f: impl FnOnce(&N) -> N) {
+    let file = SourceFile::parse(text).tree();
+    let node = file.syntax().descendants().find_map(N::cast).unwrap();
+    let new_node = f(&node);
+    assert_eq!(edit::text_edit(&node, &new_node).apply(text), expected);
+}
+
+fn field(name: &str, expr: &str) -> ast::RecordField {
+    make::record_field(make::name_ref(name), Some(make::expr_from_text(expr)))
+}
+
+#[test]
+fn append_record_fields() {
+    check_edit(
+        "fn f() { Foo { a: 1 } }",
+        "fn f() { Foo { a: 1, b: 2, c: true } }",
+        |list: &ast::RecordFieldList| list.append_fields(vec![field("b", "2"), field("c", "true")]),
+    );
+    check_edit(
+        "fn f() { Foo {} }",
+        "fn f() { Foo { b: 2 } }",
+        |list: &ast::RecordFieldList| list.append_field(field("b", "2")),
+    );
+    check_edit(
+        "fn f() { Foo { a: 1, ..foo } }",
+        "fn f() { Foo { a: 1, b: 2, ..foo } }",
+        |list: &ast::RecordFieldList| list.append_field(field("b", "2")),
+    );
+    check_edit(
+        "fn f() { Foo { ..foo } }",
+        "fn f() { Foo { b: 2, ..foo } }",
+        |list: &ast::RecordFieldList| list.append_field(field("b", "2")),
+    );
+}
+
+#[test]
+fn append_record_fields_preserves_layout() {
+    check_edit(
+        "fn f() {\n    Foo {\n        a: 1, // first\n    }\n}",
+        "fn f() {\n    Foo {\n        a: 1, // first\n        b: 2,\n    }\n}",
+        |list: &ast::RecordFieldList| list.append_field(field("b", "2")),
+    );
+    check_edit(
+        "struct Foo {\n    a: i32\n}",
+        "struct Foo {\n    a: i32,\n    b: f32\n}",
+        |list: &ast::RecordFieldDefList| {
+            list.append_field(make::record_field_def(
+                make::name("b"),
+                make::type_ref("f32"),
+            ))
+        },
+    );
+}
+
+#[test]
+fn replace_descendant() {
+    check_edit(
+        "fn f() { foo(a) + a }",
+        "fn f() { bar(a) + a }",
+        |expr: &ast::BinExpr| {
+            let name_ref = expr
+                .syntax()
+                .descendants()
+                .find_map(ast::NameRef::cast)
+                .unwrap();
+            expr.replace_descendant(&name_ref, make::name_ref("bar"))
+        },
+    );
+    // The replacement may be of a different kind
+    check_edit(
+        "fn f() { foo(a * 2) }",
+        "fn f() { foo(b) }",
+        |call: &ast::CallExpr| {
+            let arg = call
+                .syntax()
+                .descendants()
+                .find_map(ast::BinExpr::cast)
+                .unwrap();
+            call.replace_descendant(&ast::Expr::from(arg), make::expr_from_text("b"))
+        },
+    );
+}
+
+#[test]
+fn insert_statement() {
+    let let_stmt = || make::let_stmt(make::name("b"), make::expr_from_text("a + 1")).into();
+    check_edit(
+        "fn f() {\n    let a = 1;\n    a * 2\n}",
+        "fn f() {\n    let a = 1;\n    let b = a + 1;\n    a * 2\n}",
+        |block: &ast::BlockExpr| {
+            let tail = block.expr().unwrap();
+            block.insert_statement(tail.syntax(), let_stmt())
+        },
+    );
+    check_edit(
+        "fn f() { foo(a); }",
+        "fn f() { let b = a + 1; foo(a); }",
+        |block: &ast::BlockExpr| {
+            let stmt = block.statements().next().unwrap();
+            block.insert_statement(stmt.syntax(), let_stmt())
+        },
+    );
+}
@@ -112,6 +112,44 @@ fn strings() {
     )
 }
 
+#[test]
+fn raw_strings() {
+    lex_snapshot(
+        r###"
+    r"C:\path\to\file"
+    r#"a "quoted" string"#
+    r##"ends with "#"##
+    r"multi
+    line"
+    r"unterminated
+    "###,
+    )
+}
+
+#[test]
+fn chars() {
+    lex_snapshot(
+        r#"
+    'a'
+    '\n'
+    '\''
+    '\\'
+    '€'
+    "#,
+    )
+}
+
+#[test]
+fn labels() {
+    lex_snapshot(
+        r#"
+    'outer
+    'a 'b'
+    '_a
+    "#,
+    )
+}
+
 #[test]
 fn keywords() {
     lex_snapshot(
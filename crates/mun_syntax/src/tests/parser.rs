@@ -48,11 +48,26 @@ fn literals() {
         let c = 1;
         let d = 1.12;
         let e = "Hello, world!"
+        let f = 'a'
     }
     "#,
     );
 }
 
+#[test]
+fn impl_def() {
+    snapshot_test(
+        r#"
+    impl Add for Vec2 {
+        fn add(lhs: Vec2, rhs: Vec2) -> Vec2 { lhs }
+    }
+    impl Sub for Vec2 {}
+    impl Mul Vec2 {}    // error: expected FOR_KW
+    impl Div for Vec2 { struct Foo; }   // error: expected a function
+    "#,
+    )
+}
+
 #[test]
 fn struct_def() {
     snapshot_test(
@@ -286,6 +301,39 @@ fn while_expr() {
     )
 }
 
+#[test]
+fn labeled_loops() {
+    snapshot_test(
+        r#"
+    fn foo() {
+        'outer: loop {
+            'inner: while true {
+                continue 'outer;
+                break 'inner;
+            }
+            break 'outer 3;
+        }
+        continue;
+        'label: 3;
+    }
+    "#,
+    )
+}
+
+#[test]
+fn cast_expr() {
+    snapshot_test(
+        r#"
+    fn foo() {
+        let a = 3 as any;
+        let b = a as f32;
+        let c = -a + b as i32 * 2;
+        let d = a as;
+    }
+    "#,
+    )
+}
+
 #[test]
 fn struct_lit() {
     snapshot_test(
@@ -303,6 +351,19 @@ fn struct_lit() {
     )
 }
 
+#[test]
+fn struct_lit_spread() {
+    snapshot_test(
+        r#"
+    fn foo() {
+        S { ..a };
+        S { x: 1, ..a };
+        S { x: 1, ..S { x: 2, y: 3 } };
+    }
+    "#,
+    )
+}
+
 #[test]
 fn struct_field_index() {
     snapshot_test(
@@ -355,3 +416,97 @@ fn extern_fn() {
     "#,
     )
 }
+
+#[test]
+fn method_call() {
+    snapshot_test(
+        r#"
+    fn foo() {
+        a.load();
+        a.b.fetch_add(1);
+        a.store(b.load(), 3)
+    }
+    "#,
+    )
+}
+
+#[test]
+fn type_args() {
+    snapshot_test(
+        r#"
+    struct(gc) Foo {
+        a: Atomic<i32>,
+        b: Atomic<u64,>,
+        c: Atomic<foo::Bar, i32>,
+    }
+    fn foo(a: Atomic<>) {}
+    fn bar(a: Atomic<i32) {}  // error: expected `>`
+    "#,
+    )
+}
+
+#[test]
+fn attributes() {
+    snapshot_test(
+        r#"
+    #[derive(Eq, Ord)]
+    struct Foo;
+
+    struct Point {
+        #[rename_from(x)]
+        left: f32,
+    }
+
+    #[inline]
+    #[export(name = "bar")]
+    pub fn bar() {}
+
+    impl Add for Foo {
+        #[inline(always)]
+        fn add(lhs: Foo, rhs: Foo) -> Foo { lhs }
+    }
+
+    #[derive(Eq]  // error: expected `)`
+    struct Bar;
+    # struct Baz;  // error: expected `[`
+    "#,
+    )
+}
+
+#[test]
+fn doc_comments() {
+    snapshot_test(
+        r#"
+    /// Adds two numbers
+    fn add(a: i32, b: i32) -> i32 { a + b }
+
+    /// A point
+    #[derive(Eq)]
+    struct Point {
+        /// The horizontal coordinate
+        x: f32,
+        y: f32,
+    }
+
+    /** Operators for points */
+    impl Add for Point {}
+
+    // Not attached, because of the empty line
+
+    fn foo() {}
+    "#,
+    )
+}
+
+#[test]
+fn module_paths() {
+    snapshot_test(
+        r#"
+    fn main() {
+        let a = ai::enemy::attack();
+        let b: self::Foo = super::Bar::new;
+        ::root();
+    }
+    "#,
+    )
+}
@@ -0,0 +1,126 @@
+use crate::{
+    parsing::incremental_reparse, AstNode, Parse, SourceFile, TextEdit, TextRange, TextUnit,
+};
+
+/// Replaces the first occurrence of `pattern` in `before` and checks that incrementally reparsing
+/// the tree results in the same tree as parsing the edited text. `reparsed_len` is the length of
+/// the part of the old tree that is expected to be parsed again.
+fn check_incremental_reparse(before: &str, pattern: &str, replace_with: &str, reparsed_len: u32) {
+    let before = before.trim().replace("\n    ", "\n");
+    let start = before.find(pattern).expect("pattern not found");
+    let edit = TextEdit::replace(
+        TextRange::offset_len(TextUnit::from_usize(start), TextUnit::of_str(pattern)),
+        replace_with.to_owned(),
+    );
+    let after = edit.apply(&before);
+
+    let fully_reparsed = SourceFile::parse(&after);
+    let incrementally_reparsed: Parse<SourceFile> = {
+        let before = SourceFile::parse(&before);
+        let (green, errors, range) =
+            incremental_reparse(before.tree().syntax(), &edit, before.errors.to_vec())
+                .expect("cannot incrementally reparse");
+        assert_eq!(range.len(), reparsed_len.into());
+        Parse::new(green, errors)
+    };
+
+    assert_eq!(
+        fully_reparsed.debug_dump(),
+        incrementally_reparsed.debug_dump()
+    );
+}
+
+#[test]
+fn reparse_token() {
+    check_incremental_reparse("fn foo() {}", "foo", "foobar", 3);
+    check_incremental_reparse("fn foo() -> Bar {}", "Bar", "Baz", 3);
+    check_incremental_reparse("fn foo() { let a =  1; }", "  ", " ", 2);
+    check_incremental_reparse(
+        r#"
+    fn foo() {
+        // a comment
+        let a = "a string";
+    }
+    "#,
+        "a string",
+        "another string",
+        10,
+    );
+    check_incremental_reparse("fn foo() {} // comment", "comment", "other comment", 10);
+}
+
+#[test]
+fn reparse_block() {
+    check_incremental_reparse(
+        r#"
+    fn foo() {
+        let a = 1 + 1;
+    }
+    "#,
+        "1 + 1",
+        "2 * (3 + 4)",
+        22,
+    );
+    check_incremental_reparse(
+        r#"
+    fn foo(a: bool) -> i32 {
+        if a {
+            1
+        } else {
+            2
+        }
+    }
+    "#,
+        "2\n",
+        "let b = 3; b\n",
+        17,
+    );
+    check_incremental_reparse("struct Foo { a: i32 }", "i32", "f64, b: bool", 10);
+    check_incremental_reparse("fn foo() -> Foo { Foo { a: 1 } }", "a: 1", "a: 1, b: 2", 8);
+    check_incremental_reparse(
+        r#"
+    impl Add for Foo {
+        fn add(a: Foo, b: Foo) -> Foo { a }
+    }
+    "#,
+        "fn add",
+        "fn sub(a: Foo, b: Foo) -> Foo { a }\n    fn add",
+        43,
+    );
+}
+
+#[test]
+fn reparse_keeps_errors() {
+    check_incremental_reparse(
+        r#"
+    fn foo() { let }
+    fn bar() {
+        1 + 1
+    }
+    fn baz() { let }
+    "#,
+        "1 + 1",
+        "let a = ; 2",
+        13,
+    );
+}
+
+#[test]
+fn reparse_falls_back_to_full_parse() {
+    let before = SourceFile::parse("fn foo() {\n    1\n}\nfn bar() {}");
+    for &(range, replace_with) in &[
+        // Unbalanced braces
+        (TextRange::from_to(15.into(), 17.into()), ""),
+        // Identifier that becomes a keyword
+        (TextRange::from_to(3.into(), 6.into()), "loop"),
+        // Inserting an empty line affects which comments are attached to an item
+        (TextRange::from_to(17.into(), 18.into()), "\n\n"),
+    ] {
+        let edit = TextEdit::replace(range, replace_with.to_owned());
+        let text = edit.apply(&before.tree().syntax().text().to_string());
+        assert_eq!(
+            before.reparse(&edit).debug_dump(),
+            SourceFile::parse(&text).debug_dump()
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{TextRange, TextUnit};
+
+/// The replacement of a range of text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: TextRange,
+    pub replace_with: String,
+}
+
+impl TextEdit {
+    pub fn insert(offset: TextUnit, text: String) -> TextEdit {
+        TextEdit {
+            range: TextRange::offset_len(offset, 0.into()),
+            replace_with: text,
+        }
+    }
+
+    pub fn replace(range: TextRange, text: String) -> TextEdit {
+        TextEdit {
+            range,
+            replace_with: text,
+        }
+    }
+
+    pub fn delete(range: TextRange) -> TextEdit {
+        TextEdit {
+            range,
+            replace_with: String::new(),
+        }
+    }
+
+    /// Returns `text` with this edit applied to it.
+    pub fn apply(&self, text: &str) -> String {
+        let start = self.range.start().to_usize();
+        let end = self.range.end().to_usize();
+        let mut result =
+            String::with_capacity(text.len() - (end - start) + self.replace_with.len());
+        result.push_str(&text[..start]);
+        result.push_str(&self.replace_with);
+        result.push_str(&text[end..]);
+        result
+    }
+}
@@ -1,5 +1,7 @@
+mod android_base;
 mod apple_base;
 mod linux_base;
+mod wasm_base;
 mod windows_msvc_base;
 use crate::host_triple;
 use thiserror::Error;
@@ -9,6 +11,7 @@ pub enum LinkerFlavor {
     Ld,
     Ld64,
     Msvc,
+    Wasm,
 }
 
 /// Everything Mun knows about a target.
@@ -67,6 +70,10 @@ pub struct TargetOptions {
 
     /// Whether the target toolchain is like Windows
     pub is_like_windows: bool,
+
+    /// Whether the target is WebAssembly. WebAssembly modules cannot be loaded as dynamic
+    /// libraries, so they have to be linked into the module of the host.
+    pub is_like_wasm: bool,
 }
 
 impl Default for TargetOptions {
@@ -77,12 +84,13 @@ impl Default for TargetOptions {
             features: "".to_string(),
             dll_prefix: "lib".to_string(),
             is_like_windows: false,
+            is_like_wasm: false,
         }
     }
 }
 #[derive(Error, Debug)]
 pub enum LoadTargetError {
-    #[error("target not found: {0}")]
+    #[error("unknown target `{0}`, the supported targets are: {}", TARGETS.join(", "))]
     BuiltinTargetNotFound(String),
 
     #[error("{0}")]
@@ -110,8 +118,7 @@ macro_rules! supported_targets {
                         Ok(t)
                     },
                 )+
-                    _ => Err(LoadTargetError::BuiltinTargetNotFound(
-                        format!("Unable to find target: {}", target)))
+                    _ => Err(LoadTargetError::BuiltinTargetNotFound(target.to_owned()))
             }
         }
 
@@ -126,9 +133,12 @@ macro_rules! supported_targets {
 }
 
 supported_targets!(
+    ("aarch64-apple-darwin", aarch64_apple_darwin),
+    ("aarch64-linux-android", aarch64_linux_android),
     ("x86_64-apple-darwin", x86_64_apple_darwin),
     ("x86_64-pc-windows-msvc", x86_64_pc_windows_msvc),
     ("x86_64-unknown-linux-gnu", x86_64_unknown_linux_gnu),
+    ("wasm32-unknown-unknown", wasm32_unknown_unknown),
 );
 
 impl Target {
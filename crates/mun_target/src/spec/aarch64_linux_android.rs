@@ -0,0 +1,20 @@
+use crate::spec::{LinkerFlavor, Target, TargetResult};
+
+pub fn target() -> TargetResult {
+    let mut base = super::android_base::opts();
+    base.features = "+neon,+fp-armv8".to_string();
+
+    Ok(Target {
+        llvm_target: "aarch64-linux-android".to_string(),
+        target_endian: "little".to_string(),
+        target_pointer_width: "64".to_string(),
+        target_c_int_width: "32".to_string(),
+        target_os: "android".to_string(),
+        target_env: String::new(),
+        target_vendor: "unknown".to_string(),
+        arch: "aarch64".to_string(),
+        data_layout: "e-m:e-i8:8:32-i16:16:32-i64:64-i128:128-n32:64-S128".to_string(),
+        linker_flavor: LinkerFlavor::Ld,
+        options: base,
+    })
+}
@@ -8,7 +8,7 @@ pub fn opts() -> TargetOptions {
     }
 }
 
-fn macos_deployment_target() -> (u32, u32) {
+fn macos_deployment_target(arch: &str) -> (u32, u32) {
     let deployment_target = env::var("MACOSX_DEPLOYMENT_TARGET").ok();
     let version = deployment_target
         .as_ref()
@@ -22,10 +22,26 @@ fn macos_deployment_target() -> (u32, u32) {
                 .ok()
         });
 
-    version.unwrap_or((10, 7))
+    // Apple Silicon is only supported from macOS 11 onwards
+    let default = if arch == "aarch64" { (11, 0) } else { (10, 7) };
+    version.unwrap_or(default)
+}
+
+/// Returns the name of the architecture as used by Apple tools like `ld64`, e.g. `arm64` for
+/// `aarch64`.
+pub fn macos_arch_name(arch: &str) -> &str {
+    match arch {
+        "aarch64" => "arm64",
+        arch => arch,
+    }
 }
 
 pub fn macos_llvm_target(arch: &str) -> String {
-    let (major, minor) = macos_deployment_target();
-    format!("{}-apple-macosx{}.{}.0", arch, major, minor)
+    let (major, minor) = macos_deployment_target(arch);
+    format!(
+        "{}-apple-macosx{}.{}.0",
+        macos_arch_name(arch),
+        major,
+        minor
+    )
 }
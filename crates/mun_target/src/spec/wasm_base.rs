@@ -0,0 +1,9 @@
+use crate::spec::TargetOptions;
+
+pub fn opts() -> TargetOptions {
+    TargetOptions {
+        dll_prefix: "".to_string(),
+        is_like_wasm: true,
+        ..Default::default()
+    }
+}
@@ -23,3 +23,37 @@ fn data_layout_linux() {
 
     insta::assert_debug_snapshot!(layout);
 }
+
+#[test]
+fn data_layout_wasm32() {
+    let layout =
+        TargetDataLayout::parse(&Target::search("wasm32-unknown-unknown").unwrap()).unwrap();
+
+    insta::assert_debug_snapshot!(layout);
+}
+
+#[test]
+fn data_layout_darwin_aarch64() {
+    let layout = TargetDataLayout::parse(&Target::search("aarch64-apple-darwin").unwrap()).unwrap();
+
+    insta::assert_debug_snapshot!(layout);
+}
+
+#[test]
+fn data_layout_android_aarch64() {
+    let layout =
+        TargetDataLayout::parse(&Target::search("aarch64-linux-android").unwrap()).unwrap();
+
+    insta::assert_debug_snapshot!(layout);
+}
+
+#[test]
+fn unknown_target() {
+    let error = Target::search("x86_64-unknown-switch").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "unknown target `x86_64-unknown-switch`, the supported targets are: \
+         aarch64-apple-darwin, aarch64-linux-android, x86_64-apple-darwin, x86_64-pc-windows-msvc, \
+         x86_64-unknown-linux-gnu, wasm32-unknown-unknown"
+    );
+}
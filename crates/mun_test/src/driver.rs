@@ -0,0 +1,259 @@
+use mun_compiler::{Config, DisplayColor, Driver, FileId, PathOrInline, RelativePathBuf};
+use mun_runtime::{
+    IntoClosureDefinition, IntoFunctionDefinition, Runtime, RuntimeBuilder, StaticAssembly,
+};
+use std::{
+    cell::{Ref, RefCell},
+    io::Cursor,
+    path::{Path, PathBuf},
+    rc::Rc,
+    thread::sleep,
+    time::Duration,
+};
+
+/// Implements a compiler and runtime in one that can invoke functions. Use of the
+/// `CompileAndRunTestDriver` enables quick testing of Mun constructs in the runtime with
+/// hot-reloading support. All files are written to a temporary directory that is removed when the
+/// driver is dropped.
+///
+/// ```
+/// use mun_test::{assert_invoke_eq, CompileAndRunTestDriver};
+///
+/// let mut driver = CompileAndRunTestDriver::new("pub fn main() -> i32 { 5 }");
+/// assert_invoke_eq!(i32, 5, driver, "main");
+///
+/// // Recompiles the source and waits for the runtime to reload the assembly
+/// let runtime = driver.runtime();
+/// driver.update(runtime.borrow(), "pub fn main() -> i32 { 7 }");
+/// assert_invoke_eq!(i32, 7, driver, "main");
+/// ```
+pub struct CompileAndRunTestDriver {
+    _temp_dir: tempfile::TempDir,
+    out_path: PathBuf,
+    file_id: FileId,
+    driver: Driver,
+    runtime: RuntimeOrBuilder,
+}
+
+enum RuntimeOrBuilder {
+    Runtime(Rc<RefCell<Runtime>>),
+    Builder(RuntimeBuilder),
+    Pending,
+}
+
+impl RuntimeOrBuilder {
+    fn spawn(&mut self) -> Result<(), anyhow::Error> {
+        let previous = std::mem::replace(self, RuntimeOrBuilder::Pending);
+        let runtime = match previous {
+            RuntimeOrBuilder::Runtime(runtime) => runtime,
+            RuntimeOrBuilder::Builder(builder) => builder.spawn()?,
+            _ => unreachable!(),
+        };
+        *self = RuntimeOrBuilder::Runtime(runtime);
+        Ok(())
+    }
+}
+
+impl CompileAndRunTestDriver {
+    /// Constructs a new `CompileAndRunTestDriver` from a single Mun source. Panics if the source
+    /// contains compiler errors.
+    pub fn new(text: &str) -> Self {
+        Self::with_config(text, Config::default())
+    }
+
+    /// Constructs a new `CompileAndRunTestDriver` from a single Mun source, which is compiled with
+    /// the specified `config`. Panics if the source contains compiler errors.
+    pub fn with_config(text: &str, config: Config) -> Self {
+        Self::with_dependencies(text, config, &[])
+    }
+
+    /// Constructs a new `CompileAndRunTestDriver` from a single Mun source, which is compiled with
+    /// the specified `config`. The source can call the public functions of the compiled assemblies
+    /// in `dependencies` through modules of the given names, and the runtime loads these
+    /// assemblies alongside it. Panics if the source contains compiler errors.
+    pub fn with_dependencies(text: &str, config: Config, dependencies: &[(&str, &Path)]) -> Self {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            out_dir: Some(temp_dir.path().to_path_buf()),
+            display_color: DisplayColor::Disable,
+            ..config
+        };
+        let input = PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("main.mun"),
+            contents: text.to_owned(),
+        };
+        let (mut driver, file_id) = Driver::with_file(config, input).unwrap();
+        for (name, library_path) in dependencies {
+            driver.add_assembly_dependency(name, library_path).unwrap();
+        }
+        let mut compiler_errors: Vec<u8> = Vec::new();
+        if driver
+            .emit_diagnostics(&mut Cursor::new(&mut compiler_errors))
+            .unwrap()
+        {
+            panic!(
+                "compiler errors:\n{}",
+                String::from_utf8(compiler_errors)
+                    .expect("compiler errors are not UTF-8 formatted")
+            )
+        }
+        let out_path = driver.assembly_output_path(file_id);
+        driver.write_assembly(file_id, true).unwrap();
+        let builder = RuntimeBuilder::new(&out_path);
+        CompileAndRunTestDriver {
+            _temp_dir: temp_dir,
+            driver,
+            out_path,
+            file_id,
+            runtime: RuntimeOrBuilder::Builder(builder),
+        }
+    }
+
+    /// Spawns a `Runtime` from the `RuntimeBuilder`, if it hadn't already been spawned.
+    pub fn spawn(&mut self) -> Result<(), anyhow::Error> {
+        self.runtime.spawn().map(|_| ())
+    }
+
+    /// Updates the text of the Mun source and ensures that the generated assembly has been
+    /// reloaded. Panics if the new source contains compiler errors or if the runtime did not
+    /// reload the assembly within 10 seconds.
+    ///
+    /// A reference to the borrowed `runtime` is used as an argument to ensure that the runtime was
+    /// spawned prior to calling update AND to allow moving of the existing borrow inside the update
+    /// function. This obviates the necessity for `update` to use the `Runtime`.
+    pub fn update(&mut self, runtime: Ref<'_, Runtime>, text: &str) {
+        self.update_assembly(text);
+        let start_time = std::time::Instant::now();
+        drop(runtime);
+        while !self.runtime().borrow_mut().update() {
+            let now = std::time::Instant::now();
+            if now - start_time > std::time::Duration::from_secs(10) {
+                panic!("runtime did not update after recompilation within 10secs");
+            } else {
+                sleep(Duration::from_millis(1));
+            }
+        }
+    }
+
+    /// Updates the text of the Mun source and writes the recompiled assembly, without waiting for
+    /// a runtime to reload it. Panics if the new source contains compiler errors.
+    pub fn update_assembly(&mut self, text: &str) {
+        self.driver.set_file_text(self.file_id, text);
+        let mut compiler_errors: Vec<u8> = Vec::new();
+        if self
+            .driver
+            .emit_diagnostics(&mut Cursor::new(&mut compiler_errors))
+            .unwrap()
+        {
+            panic!(
+                "compiler errors:\n{}",
+                String::from_utf8(compiler_errors)
+                    .expect("compiler errors are not UTF-8 formatted")
+            )
+        }
+        let out_path = self.driver.assembly_output_path(self.file_id);
+        self.driver.write_assembly(self.file_id, true).unwrap();
+        assert_eq!(
+            &out_path, &self.out_path,
+            "recompiling did not result in the same assembly"
+        );
+    }
+
+    /// Updates the text of the Mun source and reloads the assembly from code that is compiled into
+    /// the memory of the process, without writing a shared library to disk. Panics if the new
+    /// source contains compiler errors or if the assembly could not be reloaded.
+    pub fn update_in_process(&mut self, runtime: Ref<'_, Runtime>, text: &str) {
+        self.driver.set_file_text(self.file_id, text);
+        let mut compiler_errors: Vec<u8> = Vec::new();
+        if self
+            .driver
+            .emit_diagnostics(&mut Cursor::new(&mut compiler_errors))
+            .unwrap()
+        {
+            panic!(
+                "compiler errors:\n{}",
+                String::from_utf8(compiler_errors)
+                    .expect("compiler errors are not UTF-8 formatted")
+            )
+        }
+        let jit = self.driver.jit_assembly(self.file_id).unwrap();
+        let assembly = StaticAssembly {
+            get_info: jit.get_info,
+            get_version: jit.get_version,
+            set_allocator_handle: jit.set_allocator_handle,
+        };
+        drop(runtime);
+        self.runtime()
+            .borrow_mut()
+            .load_in_process(
+                &self.out_path.canonicalize().unwrap(),
+                assembly,
+                Box::new(jit.code),
+            )
+            .unwrap();
+    }
+
+    /// Returns the path of the compiled assembly, which remains valid for as long as the driver is
+    /// alive.
+    pub fn assembly_path(&self) -> &Path {
+        &self.out_path
+    }
+
+    /// Adds a custom user function to the dispatch table.
+    pub fn insert_fn<S: AsRef<str>, F: IntoFunctionDefinition>(mut self, name: S, func: F) -> Self {
+        self.runtime = match self.runtime {
+            RuntimeOrBuilder::Builder(builder) => {
+                RuntimeOrBuilder::Builder(builder.insert_fn(name, func))
+            }
+            _ => unreachable!(),
+        };
+        self
+    }
+
+    /// Adds a custom user function to the dispatch table that invokes a Rust closure. Panics if a
+    /// closure of the same type is already bound to a function.
+    pub fn insert_closure<S: AsRef<str>, M, F: IntoClosureDefinition<M>>(
+        mut self,
+        name: S,
+        closure: F,
+    ) -> Self {
+        self.runtime = match self.runtime {
+            RuntimeOrBuilder::Builder(builder) => {
+                RuntimeOrBuilder::Builder(builder.insert_closure(name, closure).unwrap())
+            }
+            _ => unreachable!(),
+        };
+        self
+    }
+
+    /// Sets the maximum number of bytes that Mun code can allocate for objects.
+    pub fn set_max_heap_size(mut self, max_heap_size: usize) -> Self {
+        self.runtime = match self.runtime {
+            RuntimeOrBuilder::Builder(builder) => {
+                RuntimeOrBuilder::Builder(builder.set_max_heap_size(max_heap_size))
+            }
+            _ => unreachable!(),
+        };
+        self
+    }
+
+    /// Sets the maximum number of loop iterations that an invocation of a Mun function may execute.
+    pub fn set_max_loop_iterations(mut self, max_loop_iterations: u64) -> Self {
+        self.runtime = match self.runtime {
+            RuntimeOrBuilder::Builder(builder) => {
+                RuntimeOrBuilder::Builder(builder.set_max_loop_iterations(max_loop_iterations))
+            }
+            _ => unreachable!(),
+        };
+        self
+    }
+
+    /// Returns the `Runtime` used by this instance
+    pub fn runtime(&mut self) -> Rc<RefCell<Runtime>> {
+        self.runtime.spawn().unwrap();
+        match &mut self.runtime {
+            RuntimeOrBuilder::Runtime(r) => r.clone(),
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,15 @@
+//! Utilities for testing Mun code.
+//!
+//! The [`CompileAndRunTestDriver`] compiles a snippet of Mun source into an assembly in a
+//! temporary directory and loads it into a [`Runtime`](mun_runtime::Runtime), which allows host
+//! projects to write integration tests against their Mun scripts.
+#![warn(missing_docs)]
+
+#[macro_use]
+mod macros;
+mod driver;
+
+pub use driver::CompileAndRunTestDriver;
+
+#[doc(hidden)]
+pub use mun_runtime::invoke_fn;
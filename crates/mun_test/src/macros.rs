@@ -0,0 +1,25 @@
+/// Invokes a function through the runtime of a `CompileAndRunTestDriver` and asserts that its
+/// result is equal to the expected value.
+///
+/// ```
+/// use mun_test::{assert_invoke_eq, CompileAndRunTestDriver};
+///
+/// let mut driver = CompileAndRunTestDriver::new("pub fn main() -> i32 { 5 }");
+/// assert_invoke_eq!(i32, 5, driver, "main");
+/// ```
+#[macro_export]
+macro_rules! assert_invoke_eq {
+    ($ExpectedType:ty, $ExpectedResult:expr, $Driver:expr, $FnName:expr $(, $Arg:expr)*) => {
+        {
+            let runtime = $Driver.runtime();
+            let runtime_ref = runtime.borrow();
+            let result: $ExpectedType = $crate::invoke_fn!(runtime_ref, $FnName $(, $Arg)*).unwrap();
+            assert_eq!(
+                result, $ExpectedResult, "{}({}) == {:?}",
+                $FnName,
+                stringify!($($Arg),*),
+                $ExpectedResult
+            );
+        }
+    }
+}